@@ -12,9 +12,12 @@ import { ErrorAlerts } from './payment/ErrorAlerts';
 import { formatTime, formatCnyAmount } from './payment/utils';
 import type { Trade, PaymentInstructionsProps, TradeStatus } from './payment/types';
 import { useTranslations } from 'next-intl';
+import { useAccount, useSignMessage } from 'wagmi';
 
 export function PaymentInstructions({ trades, onAllSettled }: PaymentInstructionsProps) {
   const t = useTranslations('buy.paymentInstructions');
+  const { address } = useAccount();
+  const { signMessageAsync } = useSignMessage();
   const [tradeStatuses, setTradeStatuses] = useState<Map<string, TradeStatus>>(
     new Map(
       trades.map((t) => [
@@ -111,8 +114,13 @@ export function PaymentInstructions({ trades, onAllSettled }: PaymentInstruction
     });
 
     try {
-      // Step 1: Upload PDF to backend
-      const uploadResponse = await api.uploadPdf(tradeId, file);
+      // Step 1: Upload PDF to backend, signed by the buyer's wallet
+      if (!address) {
+        throw new Error('Connect the buyer wallet to upload the payment PDF');
+      }
+      const signedAt = Math.floor(Date.now() / 1000);
+      const signature = await signMessageAsync({ message: api.pdfUploadMessage(address, signedAt) });
+      const uploadResponse = await api.uploadPdf(tradeId, file, { signedAt, signature });
       console.log('PDF uploaded successfully:', uploadResponse);
         
       setTradeStatuses((prev) => {
@@ -137,12 +137,22 @@ export const api = {
     return response.data;
   },
 
-  // Upload PDF for a trade
-  async uploadPdf(tradeId: string, pdfFile: File): Promise<{ trade_id: string; filename: string; size: number; uploaded_at: string }> {
+  // Message the buyer's wallet signs to upload PDFs (orderbook pdf_upload_message)
+  pdfUploadMessage(buyer: string, signedAt: number): string {
+    return `zkAlipay PDF upload\nBuyer: ${buyer.toLowerCase()}\nSigned at: ${signedAt}`;
+  },
+
+  // Upload PDF for a trade (signed by the trade's buyer)
+  async uploadPdf(
+    tradeId: string,
+    pdfFile: File,
+    auth: { signedAt: number; signature: string }
+  ): Promise<{ trade_id: string; filename: string; size: number; uploaded_at: string }> {
     const formData = new FormData();
     formData.append('pdf', pdfFile);
     
     const response = await axios.post(`${API_BASE}/api/trades/${tradeId}/pdf`, formData, {
+      params: { signed_at: auth.signedAt, signature: auth.signature },
       headers: {
         'Content-Type': 'multipart/form-data',
       },
@@ -0,0 +1,63 @@
+// Matching engine benchmarks
+// Baseline for match_buy_intent over a deep book: run with `cargo bench --bench matching`
+
+use chrono::Utc;
+use criterion::{black_box, criterion_group, criterion_main, BatchSize, BenchmarkId, Criterion};
+use rust_decimal::Decimal;
+use zkalipay_orderbook::db::models::DbOrder;
+use zkalipay_orderbook::match_buy_intent;
+
+const BOOK_SIZE: usize = 10_000;
+
+/// 1,000 USDC (6 decimals) per order
+const ORDER_AMOUNT: u64 = 1_000_000_000;
+
+/// Book sorted by rate the way the orders query returns it (700.00-799.99 CNY per token)
+fn order_book(size: usize) -> Vec<DbOrder> {
+    (0..size)
+        .map(|i| DbOrder {
+            order_id: format!("0x{:064x}", i),
+            seller: format!("0x{:040x}", i % 500).parse().unwrap(),
+            token: "0x036cbd53842c5426634e7929541ec2318f3dcf7e".parse().unwrap(),
+            total_amount: ORDER_AMOUNT.to_string(),
+            remaining_amount: ORDER_AMOUNT.to_string(),
+            exchange_rate: (70_000 + (i * 10_000 / size)).to_string(),
+            alipay_id: "13800138000".to_string(),
+            alipay_name: "张三".to_string(),
+            created_at: 1_700_000_000 + i as i64,
+            synced_at: Utc::now(),
+            alipay_id_format: "phone".to_string(),
+            min_fill: None,
+            lot_size: None,
+        })
+        .collect()
+}
+
+fn bench_match_buy_intent(c: &mut Criterion) {
+    let book = order_book(BOOK_SIZE);
+    let mut group = c.benchmark_group("match_buy_intent");
+
+    // (label, amount, max_rate): a single fill, a sweep of the whole book, and a
+    // rate cap that stops halfway
+    let cases = [
+        ("single_fill", Decimal::from(ORDER_AMOUNT / 2), None),
+        ("sweep_book", Decimal::from(ORDER_AMOUNT) * Decimal::from(BOOK_SIZE as u64), None),
+        ("max_rate_cutoff", Decimal::from(ORDER_AMOUNT) * Decimal::from(BOOK_SIZE as u64), Some(Decimal::from(75_000))),
+    ];
+
+    for (label, amount, max_rate) in cases {
+        group.bench_with_input(BenchmarkId::new(label, BOOK_SIZE), &amount, |b, &amount| {
+            // The book is consumed by value; clone outside the measured routine
+            b.iter_batched(
+                || book.clone(),
+                |orders| match_buy_intent(black_box(orders), black_box(amount), max_rate),
+                BatchSize::LargeInput,
+            )
+        });
+    }
+
+    group.finish();
+}
+
+criterion_group!(benches, bench_match_buy_intent);
+criterion_main!(benches);
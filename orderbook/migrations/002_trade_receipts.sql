@@ -0,0 +1,18 @@
+-- ============================================================================
+-- TRADE RECEIPTS TABLE - Signed settlement attestations
+-- ============================================================================
+-- One receipt per settled trade. receipt_json is stored exactly as signed so
+-- that the EIP-191 signature can be re-verified byte-for-byte by third parties.
+
+CREATE TABLE IF NOT EXISTS trade_receipts (
+    "tradeId" VARCHAR(66) PRIMARY KEY,                    -- bytes32 (references trades.tradeId)
+    "receipt_json" TEXT NOT NULL,                         -- Canonical receipt JSON (signed payload)
+    "signature" VARCHAR(132) NOT NULL,                    -- 65-byte EIP-191 signature (0x-prefixed hex)
+    "signer" VARCHAR(42) NOT NULL,                        -- Address of the attestation key
+    "issuedAt" TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW(),
+
+    FOREIGN KEY ("tradeId") REFERENCES trades("tradeId") ON DELETE CASCADE
+);
+
+COMMENT ON TABLE trade_receipts IS 'Signed settlement attestations, downloadable at /api/trades/:id/receipt';
+COMMENT ON COLUMN trade_receipts."receipt_json" IS 'Exact bytes signed with personal_sign (EIP-191)';
@@ -0,0 +1,21 @@
+-- ============================================================================
+-- CONTRACT CONFIG EVENTS TABLE - Admin change history
+-- ============================================================================
+-- Append-only log of contract configuration changes observed by the event
+-- listener (trade limits, verifier, zkPDF config, pause state).
+
+CREATE TABLE IF NOT EXISTS contract_config_events (
+    "id" BIGSERIAL PRIMARY KEY,
+    "eventName" VARCHAR(32) NOT NULL,                     -- ConfigUpdated, ZkVerifierUpdated, ZkPDFConfigUpdated, Paused, Unpaused
+    "details" TEXT NOT NULL,                              -- Decoded event fields as JSON
+    "blockNumber" BIGINT NOT NULL,
+    "txHash" VARCHAR(66) NOT NULL,                        -- 0x-prefixed transaction hash
+    "logIndex" BIGINT NOT NULL,
+    "syncedAt" TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW(),
+
+    UNIQUE ("txHash", "logIndex")                         -- Replays after restart are no-ops
+);
+
+CREATE INDEX IF NOT EXISTS idx_contract_config_events_block ON contract_config_events("blockNumber" DESC);
+
+COMMENT ON TABLE contract_config_events IS 'History of on-chain admin config changes, exposed at /api/admin/config-history';
@@ -0,0 +1,15 @@
+-- ============================================================================
+-- PAYMENT NONCE CHECKS
+-- ============================================================================
+-- Uniqueness is enforced by the UNIQUE constraint on trades."paymentNonce"
+-- (trades_paymentNonce_key, backed by a unique index), which makes the plain
+-- index from 001 redundant. Nonces are generated on-chain as exactly 8 digits
+-- (_generate8DigitNonce), so anything else indicates a decoding bug.
+
+DROP INDEX IF EXISTS "idx_trades_paymentNonce";
+
+ALTER TABLE trades
+    ADD CONSTRAINT "trades_paymentNonce_format"
+    CHECK ("paymentNonce" ~ '^[0-9]{8}$') NOT VALID;
+
+COMMENT ON CONSTRAINT "trades_paymentNonce_format" ON trades IS 'Payment nonce must be the 8-digit string generated by the contract';
@@ -0,0 +1,12 @@
+-- ============================================================================
+-- ALIPAY ID FORMAT - Per-order account ID format
+-- ============================================================================
+-- Determines how the account line is masked when building proof inputs and the
+-- expected hash: 'phone' (139******41) or 'email' (ali***@163.com).
+
+ALTER TABLE orders
+    ADD COLUMN IF NOT EXISTS "alipayIdFormat" VARCHAR(16) NOT NULL DEFAULT 'phone';
+
+UPDATE orders SET "alipayIdFormat" = 'email' WHERE "alipayId" LIKE '%@%';
+
+COMMENT ON COLUMN orders."alipayIdFormat" IS 'Alipay account ID format: phone or email (NOT on-chain)';
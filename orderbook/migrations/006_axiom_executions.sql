@@ -0,0 +1,20 @@
+-- ============================================================================
+-- AXIOM EXECUTIONS TABLE - Cached execute-mode results
+-- ============================================================================
+-- Execute mode is deterministic in its inputs, so a validation retry with the
+-- same input streams against the same program returns the stored output
+-- instead of re-running the guest on Axiom.
+
+CREATE TABLE IF NOT EXISTS axiom_executions (
+    "inputHash" VARCHAR(66) NOT NULL,                     -- SHA-256 of the input streams (0x-prefixed)
+    "programId" VARCHAR(64) NOT NULL,                     -- Axiom program the streams were executed by
+    "tradeId" VARCHAR(66) NOT NULL,                       -- Trade that first triggered the execution
+    "actualHash" VARCHAR(66) NOT NULL,                    -- Guest output (public values, 0x-prefixed)
+    "executedAt" TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW(),
+
+    PRIMARY KEY ("inputHash", "programId")
+);
+
+CREATE INDEX IF NOT EXISTS idx_axiom_executions_trade ON axiom_executions("tradeId");
+
+COMMENT ON TABLE axiom_executions IS 'Axiom execute-mode outputs keyed by input stream hash, reused by /api/validate-pdf-axiom';
@@ -0,0 +1,22 @@
+-- ============================================================================
+-- VALIDATION ATTEMPTS TABLE - Per-trade PDF validation history
+-- ============================================================================
+-- One row per validation attempt, successful or not, so support can see
+-- whether a buyer's PDF ever validated and what changed between attempts.
+
+CREATE TABLE IF NOT EXISTS validation_attempts (
+    "id" BIGSERIAL PRIMARY KEY,
+    "tradeId" VARCHAR(66) NOT NULL,                       -- bytes32 as 0x-prefixed hex string
+    "source" VARCHAR(16) NOT NULL,                        -- axiom, axiom_cached, local
+    "programId" VARCHAR(64),                              -- Axiom program (NULL for local OpenVM runs)
+    "inputHash" VARCHAR(66),                              -- SHA-256 of the input streams (0x-prefixed)
+    "expectedHash" VARCHAR(66),                           -- Locally computed expected output
+    "actualHash" VARCHAR(66),                             -- Guest output (NULL if execution failed)
+    "isValid" BOOLEAN,                                    -- Verdict (NULL if execution failed)
+    "error" TEXT,                                         -- Failure reason when no verdict was reached
+    "attemptedAt" TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX IF NOT EXISTS idx_validation_attempts_trade ON validation_attempts("tradeId", "attemptedAt" DESC);
+
+COMMENT ON TABLE validation_attempts IS 'History of PDF validation attempts, exposed at /api/trades/:trade_id/validations';
@@ -0,0 +1,49 @@
+-- ============================================================================
+-- FINANCIAL INVARIANTS
+-- ============================================================================
+-- The orderbook mirrors on-chain state, so these can only be violated by a bug
+-- in event handling. Rejecting the write surfaces the bug as a typed error
+-- (DbError::ConstraintViolation) instead of silently corrupting balances.
+--
+-- Already enforced by 001: remainingAmount <= totalAmount, status IN (0, 1, 2).
+-- New checks are NOT VALID (as in 004): enforced for every new write without
+-- failing the migration on legacy rows.
+
+ALTER TABLE orders
+    ADD CONSTRAINT "orders_remainingAmount_nonnegative"
+    CHECK ("remainingAmount" >= 0) NOT VALID;
+
+ALTER TABLE trades
+    ADD CONSTRAINT "trades_expiresAt_after_createdAt"
+    CHECK ("expiresAt" > "createdAt") NOT VALID;
+
+ALTER TABLE trades
+    ADD CONSTRAINT "trades_paymentNonce_nonempty"
+    CHECK (LENGTH("paymentNonce") > 0) NOT VALID;
+
+-- ----------------------------------------------------------------------------
+-- Trade status transitions: SETTLED and EXPIRED are terminal on-chain, so a
+-- trade may only leave PENDING (0). Raised as check_violation so it maps to the
+-- same typed error as the CHECK constraints above.
+-- ----------------------------------------------------------------------------
+
+CREATE OR REPLACE FUNCTION trades_check_status_transition() RETURNS TRIGGER AS $$
+BEGIN
+    IF OLD."status" <> 0 AND NEW."status" <> OLD."status" THEN
+        RAISE EXCEPTION 'trade % cannot change status from % to %', OLD."tradeId", OLD."status", NEW."status"
+            USING ERRCODE = 'check_violation', CONSTRAINT = 'trades_status_transition';
+    END IF;
+    RETURN NEW;
+END;
+$$ LANGUAGE plpgsql;
+
+DROP TRIGGER IF EXISTS "trades_status_transition" ON trades;
+CREATE TRIGGER "trades_status_transition"
+    BEFORE UPDATE OF "status" ON trades
+    FOR EACH ROW
+    EXECUTE FUNCTION trades_check_status_transition();
+
+COMMENT ON CONSTRAINT "orders_remainingAmount_nonnegative" ON orders IS 'Remaining amount can never go below zero';
+COMMENT ON CONSTRAINT "trades_expiresAt_after_createdAt" ON trades IS 'Payment window must be positive';
+COMMENT ON CONSTRAINT "trades_paymentNonce_nonempty" ON trades IS 'Every trade needs a payment nonce';
+COMMENT ON TRIGGER "trades_status_transition" ON trades IS 'Only PENDING trades may change status';
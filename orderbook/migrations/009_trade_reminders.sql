@@ -0,0 +1,20 @@
+-- ============================================================================
+-- TRADE REMINDERS TABLE - Notifications already sent per trade
+-- ============================================================================
+-- The reminder scheduler scans upcoming expirations every minute; a row here
+-- means the reminder was delivered, so restarts and overlapping scans never
+-- notify the same trade twice.
+
+CREATE TABLE IF NOT EXISTS trade_reminders (
+    "tradeId" VARCHAR(66) NOT NULL REFERENCES trades("tradeId") ON DELETE CASCADE,
+    "kind" VARCHAR(32) NOT NULL,                          -- Reminder type (expiry)
+    "sentAt" TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW(),
+
+    PRIMARY KEY ("tradeId", "kind")
+);
+
+-- Scheduler scan: PENDING trades without a PDF, ordered by deadline
+CREATE INDEX IF NOT EXISTS idx_trades_pending_no_pdf_expiresAt
+    ON trades("expiresAt") WHERE "status" = 0 AND "pdf_file" IS NULL;
+
+COMMENT ON TABLE trade_reminders IS 'Delivered trade reminder notifications (one per trade and kind)';
@@ -0,0 +1,28 @@
+-- ============================================================================
+-- RELAYER SPEND TABLE - Gas paid by the relayer, attributed to trades/orders
+-- ============================================================================
+-- One row per mined relayer transaction (reverted ones included, they still
+-- cost gas), so the operator can see what each trade costs to relay and price
+-- fees accordingly. No foreign keys: fills are recorded before the event
+-- listener has synced the trade.
+
+CREATE TABLE IF NOT EXISTS relayer_spend (
+    "id" BIGSERIAL PRIMARY KEY,
+    "txHash" VARCHAR(66) NOT NULL UNIQUE,
+    "action" VARCHAR(32) NOT NULL,                        -- fillOrder, submitPaymentProof, cancelExpiredTrade
+    "tradeId" VARCHAR(66),                                -- NULL if the tx never produced a trade
+    "orderId" VARCHAR(66),
+    "relayer" VARCHAR(42) NOT NULL,                       -- Signing relayer key
+    "gasUsed" NUMERIC(78, 0) NOT NULL,
+    "effectiveGasPrice" NUMERIC(78, 0) NOT NULL,          -- wei
+    "costWei" NUMERIC(78, 0) NOT NULL,                    -- gasUsed * effectiveGasPrice
+    "reverted" BOOLEAN NOT NULL DEFAULT FALSE,
+    "blockNumber" BIGINT,
+    "recordedAt" TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX IF NOT EXISTS idx_relayer_spend_trade ON relayer_spend("tradeId");
+CREATE INDEX IF NOT EXISTS idx_relayer_spend_order ON relayer_spend("orderId");
+CREATE INDEX IF NOT EXISTS idx_relayer_spend_recordedAt ON relayer_spend("recordedAt" DESC);
+
+COMMENT ON TABLE relayer_spend IS 'Relayer gas spend per transaction, exposed at /api/analytics/relayer-costs';
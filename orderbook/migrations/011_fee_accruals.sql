@@ -0,0 +1,24 @@
+-- ============================================================================
+-- FEE ACCRUALS TABLE - Operator fee owed per settled trade
+-- ============================================================================
+-- The escrow contract does not collect fees yet. Until it does, the backend
+-- computes the fee for each settled trade (see src/fees) and records it here
+-- against the paying side, so per-address statements can be issued and later
+-- reconciled against on-chain collection.
+
+CREATE TABLE IF NOT EXISTS fee_accruals (
+    "tradeId" VARCHAR(66) PRIMARY KEY REFERENCES trades("tradeId") ON DELETE CASCADE,
+    "orderId" VARCHAR(66) NOT NULL,
+    "payerRole" VARCHAR(8) NOT NULL CHECK ("payerRole" IN ('seller', 'buyer')),
+    "payer" VARCHAR(42) NOT NULL,                         -- Lowercase address
+    "token" VARCHAR(42) NOT NULL,                         -- Lowercase token address
+    "tokenFee" NUMERIC(78, 0) NOT NULL,                   -- Token base units
+    "cnyFee" NUMERIC(78, 0) NOT NULL,                     -- CNY cents
+    "schedule" VARCHAR(32) NOT NULL,                      -- Schedule applied, e.g. bps:25 or flat_cny:200
+    "accruedAt" TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX IF NOT EXISTS idx_fee_accruals_payer ON fee_accruals("payer", "accruedAt" DESC);
+CREATE INDEX IF NOT EXISTS idx_fee_accruals_accruedAt ON fee_accruals("accruedAt" DESC);
+
+COMMENT ON TABLE fee_accruals IS 'Operator fee accruals per settled trade, exposed at /api/fees';
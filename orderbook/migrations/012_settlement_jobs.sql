@@ -0,0 +1,29 @@
+-- ============================================================================
+-- SETTLEMENT JOBS TABLE - validate -> prove -> submit orchestration
+-- ============================================================================
+-- POST /api/trades/:trade_id/settle runs the three settlement steps as one job.
+-- The current step is persisted so a failed job can be resumed from where it
+-- stopped (re-POST) and jobs interrupted by a restart are picked up on startup.
+-- At most one job per trade runs at a time.
+
+CREATE TABLE IF NOT EXISTS settlement_jobs (
+    "jobId" UUID PRIMARY KEY,
+    "tradeId" VARCHAR(66) NOT NULL REFERENCES trades("tradeId") ON DELETE CASCADE,
+    "status" VARCHAR(16) NOT NULL DEFAULT 'running'
+        CHECK ("status" IN ('running', 'completed', 'failed')),
+    "step" VARCHAR(16) NOT NULL DEFAULT 'validate'
+        CHECK ("step" IN ('validate', 'prove', 'submit', 'done')),
+    "attempts" INTEGER NOT NULL DEFAULT 1,                 -- Incremented on every resume
+    "proofId" VARCHAR(255),                                -- Axiom proof used for submission
+    "txHash" VARCHAR(66),                                  -- submitPaymentProof transaction
+    "error" TEXT,                                          -- Why the last attempt failed
+    "createdAt" TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW(),
+    "updatedAt" TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW(),
+    "completedAt" TIMESTAMP WITH TIME ZONE
+);
+
+CREATE UNIQUE INDEX IF NOT EXISTS idx_settlement_jobs_running
+    ON settlement_jobs("tradeId") WHERE "status" = 'running';
+CREATE INDEX IF NOT EXISTS idx_settlement_jobs_trade ON settlement_jobs("tradeId", "createdAt" DESC);
+
+COMMENT ON TABLE settlement_jobs IS 'Settlement pipeline jobs (validate, prove, submit), see /api/settlement-jobs';
@@ -0,0 +1,10 @@
+-- ============================================================================
+-- ORDER BOOK INDEXES - Active orders in price order
+-- ============================================================================
+-- /api/orders/active and matching read the cheapest active orders first. Without
+-- an index on the sort key every request sorted the whole book (seq scan + top-N
+-- sort, ~5ms at 10k orders); this partial index serves the LIMIT directly from
+-- an index scan (~0.2ms). Baseline numbers: tests/load_test.rs, benches/matching.rs.
+
+CREATE INDEX IF NOT EXISTS "idx_orders_active_rate"
+    ON orders("exchangeRate", "createdAt") WHERE "remainingAmount" > 0;
@@ -0,0 +1,42 @@
+-- ============================================================================
+-- NORMALIZED ADDRESSES - Lowercase 0x form, sargable lookups
+-- ============================================================================
+-- Address columns are stored as '0x' + 40 lowercase hex chars (the repositories
+-- normalize on write and on lookup), so buyer/seller/token filters are plain
+-- equality and can use an index instead of LOWER(REPLACE(...)) over every row.
+
+UPDATE orders SET seller = '0x' || LOWER(REGEXP_REPLACE(seller, '^0[xX]', ''))
+    WHERE seller !~ '^0x[0-9a-f]*$';
+UPDATE orders SET token = '0x' || LOWER(REGEXP_REPLACE(token, '^0[xX]', ''))
+    WHERE token !~ '^0x[0-9a-f]*$';
+UPDATE trades SET buyer = '0x' || LOWER(REGEXP_REPLACE(buyer, '^0[xX]', ''))
+    WHERE buyer !~ '^0x[0-9a-f]*$';
+UPDATE fee_accruals SET payer = '0x' || LOWER(REGEXP_REPLACE(payer, '^0[xX]', ''))
+    WHERE payer !~ '^0x[0-9a-f]*$';
+UPDATE fee_accruals SET token = '0x' || LOWER(REGEXP_REPLACE(token, '^0[xX]', ''))
+    WHERE token !~ '^0x[0-9a-f]*$';
+
+-- NOT VALID: enforced for new rows without failing on legacy malformed ones
+ALTER TABLE orders ADD CONSTRAINT "orders_seller_normalized"
+    CHECK (seller ~ '^0x[0-9a-f]{40}$') NOT VALID;
+ALTER TABLE orders ADD CONSTRAINT "orders_token_normalized"
+    CHECK (token ~ '^0x[0-9a-f]{40}$') NOT VALID;
+ALTER TABLE trades ADD CONSTRAINT "trades_buyer_normalized"
+    CHECK (buyer ~ '^0x[0-9a-f]{40}$') NOT VALID;
+
+-- Buyer history and seller order lists: equality + newest-first in one index
+DROP INDEX IF EXISTS "idx_trades_buyer";
+CREATE INDEX IF NOT EXISTS "idx_trades_buyer_createdAt" ON trades("buyer", "createdAt" DESC);
+
+DROP INDEX IF EXISTS "idx_orders_seller";
+CREATE INDEX IF NOT EXISTS "idx_orders_seller_createdAt" ON orders("seller", "createdAt" DESC);
+
+-- Active orders for one token in price order (match-intent); replaces the
+-- (token, remainingAmount) index, which could filter but not serve the sort
+DROP INDEX IF EXISTS "idx_orders_token_remainingAmount";
+CREATE INDEX IF NOT EXISTS "idx_orders_token_active_rate"
+    ON orders("token", "exchangeRate", "createdAt") WHERE "remainingAmount" > 0;
+
+COMMENT ON COLUMN trades."buyer" IS 'Buyer address, 0x + 40 lowercase hex';
+COMMENT ON COLUMN orders."seller" IS 'Seller address, 0x + 40 lowercase hex';
+COMMENT ON COLUMN orders."token" IS 'Token address, 0x + 40 lowercase hex';
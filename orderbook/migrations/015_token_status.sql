@@ -0,0 +1,17 @@
+-- ============================================================================
+-- TOKEN STATUS TABLE - Admin-managed token allowlist / denylist
+-- ============================================================================
+-- Delisting a token soft-pauses it in the orderbook: matching and execute-fill
+-- refuse it and its orders drop out of the active listing (sellers still see
+-- them, flagged as suspended). Nothing changes on-chain. With any token on the
+-- allowlist, every token not on it is suspended as well.
+
+CREATE TABLE IF NOT EXISTS token_status (
+    "token" VARCHAR(42) PRIMARY KEY CHECK ("token" ~ '^0x[0-9a-f]{40}$'),
+    "status" VARCHAR(8) NOT NULL CHECK ("status" IN ('allowed', 'denied')),
+    "reason" TEXT,                                         -- Shown at /api/tokens/status
+    "updatedBy" VARCHAR(64),                               -- Admin key name, if keys are configured
+    "updatedAt" TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW()
+);
+
+COMMENT ON TABLE token_status IS 'Token allowlist/denylist for matching, see /api/tokens/status';
@@ -0,0 +1,18 @@
+-- ============================================================================
+-- ORDER FILL LIMITS - Seller-set minimum fill and lot size (off-chain)
+-- ============================================================================
+-- The escrow contract has no per-order minimum fill, so this is a seller
+-- preference the orderbook enforces when matching and executing fills: a fill
+-- must be at least "minFill" and a multiple of "lotSize" (both in token base
+-- units), except a fill taking the order's whole remaining amount. Set with a
+-- seller signature via POST /api/orders/:order_id/fill-limits; "fillLimitsSignedAt"
+-- is the signed timestamp, so an older signature can't be replayed over a newer one.
+
+ALTER TABLE orders ADD COLUMN IF NOT EXISTS "minFill" NUMERIC(78,0)
+    CONSTRAINT "orders_minFill_positive" CHECK ("minFill" > 0);
+ALTER TABLE orders ADD COLUMN IF NOT EXISTS "lotSize" NUMERIC(78,0)
+    CONSTRAINT "orders_lotSize_positive" CHECK ("lotSize" > 0);
+ALTER TABLE orders ADD COLUMN IF NOT EXISTS "fillLimitsSignedAt" BIGINT;
+
+COMMENT ON COLUMN orders."minFill" IS 'Seller preference (not on-chain): smallest fill in token base units';
+COMMENT ON COLUMN orders."lotSize" IS 'Seller preference (not on-chain): fills must be a multiple of this';
@@ -0,0 +1,28 @@
+-- ============================================================================
+-- API KEYS - Programmatic access for market makers and bots
+-- ============================================================================
+-- Admin-issued keys bound to a wallet and scoped to read and/or trade, sent in
+-- the x-api-key header. Only the SHA-256 of the secret is stored; the secret is
+-- shown once at creation. Requests without a key keep working as before (the
+-- web app); a key adds identity, a wallet binding and its own rate limit.
+
+CREATE TABLE IF NOT EXISTS api_keys (
+    "keyId" UUID PRIMARY KEY,
+    "keyHash" CHAR(64) NOT NULL UNIQUE,                    -- hex SHA-256 of the secret
+    "keyPrefix" VARCHAR(16) NOT NULL,                      -- First chars of the secret, to recognize it in listings
+    "name" VARCHAR(64) NOT NULL CHECK ("name" ~ '^[A-Za-z0-9_.-]+$'),
+    "wallet" VARCHAR(42) NOT NULL CHECK ("wallet" ~ '^0x[0-9a-f]{40}$'),
+    "scopes" TEXT[] NOT NULL CHECK (cardinality("scopes") > 0 AND "scopes" <@ ARRAY['read', 'trade']::TEXT[]),
+    "rateLimitPerMinute" INTEGER NOT NULL CHECK ("rateLimitPerMinute" > 0),
+    "createdBy" VARCHAR(64),                               -- Admin key name
+    "createdAt" TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW(),
+    "lastUsedAt" TIMESTAMP WITH TIME ZONE,                 -- Updated at most once a minute per key
+    "revokedAt" TIMESTAMP WITH TIME ZONE
+);
+
+CREATE INDEX IF NOT EXISTS "idx_api_keys_wallet" ON api_keys("wallet");
+
+-- Names label the per-key usage metrics, so they're unique among live keys
+CREATE UNIQUE INDEX IF NOT EXISTS "idx_api_keys_active_name" ON api_keys("name") WHERE "revokedAt" IS NULL;
+
+COMMENT ON TABLE api_keys IS 'Market-maker API keys (x-api-key), see api::api_keys';
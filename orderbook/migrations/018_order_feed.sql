@@ -0,0 +1,55 @@
+-- ============================================================================
+-- ORDER FEED - Sequence-numbered log of order changes
+-- ============================================================================
+-- Backs GET /api/feed/orders.ndjson: market makers take a snapshot at sequence N
+-- and apply every change after N, resuming from the last sequence they applied.
+-- The log is written by a trigger, so every write path (event listener, fill
+-- limits) is covered. Sequence numbers come from a single counter row rather
+-- than a SEQUENCE: the row lock orders concurrent writers and a rolled-back
+-- write rolls its number back too, so the log is gapless and committed in
+-- sequence order - a reader never sees N+1 before N, and a gap means data loss.
+
+CREATE TABLE IF NOT EXISTS order_feed_sequence (
+    "id" BOOLEAN PRIMARY KEY DEFAULT TRUE CHECK ("id"),  -- single row
+    "seq" BIGINT NOT NULL
+);
+INSERT INTO order_feed_sequence ("id", "seq") VALUES (TRUE, 0) ON CONFLICT DO NOTHING;
+
+CREATE TABLE IF NOT EXISTS order_feed (
+    "seq" BIGINT PRIMARY KEY,
+    "orderId" VARCHAR(66) NOT NULL,
+    "kind" VARCHAR(8) NOT NULL CHECK ("kind" IN ('created', 'updated')),
+    -- Mutable order fields as of this change (the rest of an order never changes)
+    "remainingAmount" NUMERIC(78,0) NOT NULL,
+    "minFill" NUMERIC(78,0),
+    "lotSize" NUMERIC(78,0),
+    "loggedAt" TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW()
+);
+
+CREATE OR REPLACE FUNCTION orders_log_feed() RETURNS TRIGGER AS $$
+DECLARE
+    next_seq BIGINT;
+BEGIN
+    IF TG_OP = 'UPDATE'
+        AND NEW."remainingAmount" IS NOT DISTINCT FROM OLD."remainingAmount"
+        AND NEW."minFill" IS NOT DISTINCT FROM OLD."minFill"
+        AND NEW."lotSize" IS NOT DISTINCT FROM OLD."lotSize" THEN
+        RETURN NULL;
+    END IF;
+
+    UPDATE order_feed_sequence SET "seq" = "seq" + 1 RETURNING "seq" INTO next_seq;
+    INSERT INTO order_feed ("seq", "orderId", "kind", "remainingAmount", "minFill", "lotSize")
+    VALUES (next_seq, NEW."orderId", CASE TG_OP WHEN 'INSERT' THEN 'created' ELSE 'updated' END,
+            NEW."remainingAmount", NEW."minFill", NEW."lotSize");
+    RETURN NULL;
+END;
+$$ LANGUAGE plpgsql;
+
+DROP TRIGGER IF EXISTS "orders_feed" ON orders;
+CREATE TRIGGER "orders_feed"
+    AFTER INSERT OR UPDATE ON orders
+    FOR EACH ROW
+    EXECUTE FUNCTION orders_log_feed();
+
+COMMENT ON TABLE order_feed IS 'Gapless order change log for /api/feed/orders.ndjson';
+COMMENT ON TRIGGER "orders_feed" ON orders IS 'Appends order changes to order_feed';
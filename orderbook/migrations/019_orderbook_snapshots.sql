@@ -0,0 +1,24 @@
+-- ============================================================================
+-- ORDERBOOK SNAPSHOTS - Time-bucketed aggregate book state per token
+-- ============================================================================
+-- Written by the api-server's snapshot scheduler once per bucket and read by
+-- GET /api/analytics/orderbook-history for charting liquidity and spreads over
+-- time. Rows are keyed by bucket start, so every instance can run the
+-- scheduler: a bucket written twice is simply overwritten with the later state.
+-- Tokens with no active orders in a bucket have no row.
+
+CREATE TABLE IF NOT EXISTS orderbook_snapshots (
+    "bucket" TIMESTAMP WITH TIME ZONE NOT NULL,            -- Bucket start (aligned to the snapshot interval)
+    "token" VARCHAR(42) NOT NULL,
+    "orderCount" INTEGER NOT NULL,                         -- Orders with remainingAmount > 0
+    "totalLiquidity" NUMERIC(78,0) NOT NULL,               -- Sum of remainingAmount
+    "bestRate" NUMERIC(78,0) NOT NULL,                     -- Lowest exchangeRate
+    "worstRate" NUMERIC(78,0) NOT NULL,                    -- Highest exchangeRate
+    "weightedRate" NUMERIC(78,0) NOT NULL,                 -- exchangeRate weighted by remainingAmount
+    "recordedAt" TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW(),
+    PRIMARY KEY ("bucket", "token")
+);
+
+CREATE INDEX IF NOT EXISTS "idx_orderbook_snapshots_token_bucket" ON orderbook_snapshots("token", "bucket");
+
+COMMENT ON TABLE orderbook_snapshots IS 'Aggregate book state per token and time bucket, see /api/analytics/orderbook-history';
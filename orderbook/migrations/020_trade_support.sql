@@ -0,0 +1,31 @@
+-- ============================================================================
+-- TRADE SUPPORT - Operator metadata and notes on trades
+-- ============================================================================
+-- Lets operators track support cases ("buyer says bank transfer delayed")
+-- through the admin API instead of editing rows by hand. Kept out of the
+-- trades table, which mirrors the on-chain Trade struct. Buyer contact
+-- handles are personal data: both tables are only exposed to named admin keys.
+
+CREATE TABLE IF NOT EXISTS trade_support (
+    "tradeId" VARCHAR(66) PRIMARY KEY REFERENCES trades("tradeId") ON DELETE CASCADE,
+    "buyerContact" TEXT,                                   -- e.g. Telegram handle or email
+    "tags" TEXT[] NOT NULL DEFAULT '{}',                   -- Lowercase labels, e.g. {payment-delayed}
+    "updatedBy" VARCHAR(64),                               -- Admin key name
+    "updatedAt" TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX IF NOT EXISTS "idx_trade_support_tags" ON trade_support USING GIN ("tags");
+
+-- Append-only: notes are never edited, a correction is a new note
+CREATE TABLE IF NOT EXISTS trade_notes (
+    "id" BIGSERIAL PRIMARY KEY,
+    "tradeId" VARCHAR(66) NOT NULL REFERENCES trades("tradeId") ON DELETE CASCADE,
+    "note" TEXT NOT NULL CHECK (length("note") > 0),
+    "author" VARCHAR(64),                                  -- Admin key name
+    "createdAt" TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX IF NOT EXISTS "idx_trade_notes_trade" ON trade_notes("tradeId", "createdAt");
+
+COMMENT ON TABLE trade_support IS 'Operator-maintained support metadata per trade, see /api/admin/trades/:trade_id/support';
+COMMENT ON TABLE trade_notes IS 'Operator support notes per trade (append-only)';
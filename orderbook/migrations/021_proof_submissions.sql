@@ -0,0 +1,20 @@
+-- ============================================================================
+-- PROOF SUBMISSIONS - One submitPaymentProof in flight per trade
+-- ============================================================================
+-- Two concurrent submissions of the same trade would both send a transaction
+-- and one would revert, wasting relayer gas. A submission first claims the
+-- trade's row ('submitting'); other attempts get 409 with the pending tx hash
+-- until it is confirmed ('submitted') or fails ('failed', claimable again).
+-- A claim left behind by a crashed or timed-out submission can be taken over
+-- once it is older than the lease (see PROOF_SUBMISSION_LEASE_SECS).
+
+CREATE TABLE IF NOT EXISTS proof_submissions (
+    "tradeId" VARCHAR(66) PRIMARY KEY REFERENCES trades("tradeId") ON DELETE CASCADE,
+    "status" VARCHAR(16) NOT NULL CHECK ("status" IN ('submitting', 'submitted', 'failed')),
+    "txHash" VARCHAR(66),                                  -- Set once the transaction is sent
+    "attempts" INTEGER NOT NULL DEFAULT 1,
+    "startedAt" TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW(),  -- Start of the current attempt
+    "updatedAt" TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW()
+);
+
+COMMENT ON TABLE proof_submissions IS 'Per-trade submitPaymentProof claim, prevents concurrent submissions';
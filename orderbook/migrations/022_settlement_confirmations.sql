@@ -0,0 +1,21 @@
+-- ============================================================================
+-- SETTLEMENT CONFIRMATIONS - Confirmation depth of settlement transactions
+-- ============================================================================
+-- A trade turns SETTLED as soon as its TradeSettled event is seen, at one
+-- confirmation. The confirmation tracker follows the settlement tx until it is
+-- buried under the configured depth (SETTLEMENT_CONFIRMATIONS), re-reading its
+-- receipt so a reorg that moves or drops the tx resets the count, and then sets
+-- finalizedAt - the point at which the trade_settled webhook fires.
+
+ALTER TABLE trades ADD COLUMN IF NOT EXISTS "settlementBlock" BIGINT;              -- Block the settlement tx was mined in
+ALTER TABLE trades ADD COLUMN IF NOT EXISTS "confirmations" INTEGER NOT NULL DEFAULT 0;
+ALTER TABLE trades ADD COLUMN IF NOT EXISTS "finalizedAt" TIMESTAMP WITH TIME ZONE; -- Depth reached
+
+-- Trades settled before tracking existed are long final
+UPDATE trades SET "finalizedAt" = "syncedAt" WHERE "status" = 1 AND "finalizedAt" IS NULL;
+
+-- Tracker scan: settled trades not final yet
+CREATE INDEX IF NOT EXISTS idx_trades_settled_unfinalized
+    ON trades("tradeId") WHERE "status" = 1 AND "finalizedAt" IS NULL;
+
+COMMENT ON COLUMN trades."confirmations" IS 'Confirmations of the settlement tx, tracked until finalizedAt';
@@ -0,0 +1,32 @@
+-- ============================================================================
+-- ORDER INTENTS - Seller-signed (EIP-712) orders posted before funding
+-- ============================================================================
+-- "Post then fund": a seller signs an OrderIntent with the terms of the escrow
+-- order they are about to create, the orderbook verifies and lists it, and the
+-- seller funds the escrow afterwards. When an OrderCreatedAndLocked event with
+-- the same seller, token, amount, rate and payee arrives, the intent is marked
+-- funded and linked to the order. The payee (Alipay id/name) is only committed
+-- to by "alipayHash" until funding. "intentHash" is the EIP-712 struct hash.
+
+CREATE TABLE IF NOT EXISTS order_intents (
+    "intentHash" VARCHAR(66) PRIMARY KEY,
+    "seller" VARCHAR(42) NOT NULL,
+    "token" VARCHAR(42) NOT NULL,
+    "amount" NUMERIC(78,0) NOT NULL CHECK ("amount" > 0),      -- Token base units
+    "exchangeRate" NUMERIC(78,0) NOT NULL CHECK ("exchangeRate" > 0), -- CNY cents per token
+    "alipayHash" VARCHAR(66) NOT NULL,                       -- keccak256(abi.encode(alipayId, alipayName))
+    "nonce" NUMERIC(78,0) NOT NULL,
+    "deadline" BIGINT NOT NULL,                              -- Unix timestamp, void afterwards
+    "signature" VARCHAR(132) NOT NULL,
+    "status" VARCHAR(16) NOT NULL DEFAULT 'open'
+        CHECK ("status" IN ('open', 'cancelled', 'funded')),
+    "orderId" VARCHAR(66) REFERENCES orders("orderId"),      -- Set when funded
+    "createdAt" TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW(),
+    "updatedAt" TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW(),
+    UNIQUE ("seller", "nonce")
+);
+
+CREATE INDEX IF NOT EXISTS "idx_order_intents_open" ON order_intents("token", "deadline")
+    WHERE "status" = 'open';
+
+COMMENT ON TABLE order_intents IS 'Seller-signed EIP-712 order intents awaiting escrow funding, see /api/order-intents';
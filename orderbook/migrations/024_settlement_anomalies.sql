@@ -0,0 +1,40 @@
+-- ============================================================================
+-- SETTLEMENT ANOMALIES - Validation/settlement outcomes per seller and template
+-- ============================================================================
+-- Every PDF validation and proof submission records its outcome together with
+-- the order's seller and the receipt's template (a fingerprint of the PDF's
+-- label layout, see crate::anomalies). When the mismatch rate of one template
+-- spikes - most likely Alipay changed the receipt layout - the anomaly monitor
+-- flags the template, alerts admins and /api/status shows a banner until an
+-- admin clears the flag.
+
+CREATE TABLE IF NOT EXISTS settlement_outcomes (
+    "id" BIGSERIAL PRIMARY KEY,
+    "tradeId" VARCHAR(66) NOT NULL,
+    "seller" VARCHAR(42) NOT NULL,
+    "template" VARCHAR(32) NOT NULL,
+    "stage" VARCHAR(16) NOT NULL CHECK ("stage" IN ('validation', 'submission')),
+    "outcome" VARCHAR(16) NOT NULL CHECK ("outcome" IN ('ok', 'mismatch', 'proof_failed')),
+    "recordedAt" TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX IF NOT EXISTS idx_settlement_outcomes_template ON settlement_outcomes("template", "recordedAt");
+CREATE INDEX IF NOT EXISTS idx_settlement_outcomes_seller ON settlement_outcomes("seller", "recordedAt");
+CREATE INDEX IF NOT EXISTS idx_settlement_outcomes_trade ON settlement_outcomes("tradeId", "recordedAt" DESC);
+
+-- One row per flag; a template is flagged while it has a row with "clearedAt" NULL
+CREATE TABLE IF NOT EXISTS flagged_templates (
+    "id" BIGSERIAL PRIMARY KEY,
+    "template" VARCHAR(32) NOT NULL,
+    "mismatchRate" DOUBLE PRECISION NOT NULL,
+    "samples" BIGINT NOT NULL,
+    "flaggedAt" TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW(),
+    "clearedAt" TIMESTAMP WITH TIME ZONE,
+    "clearedBy" VARCHAR(64)                                -- Admin key name
+);
+
+CREATE UNIQUE INDEX IF NOT EXISTS idx_flagged_templates_active ON flagged_templates("template")
+    WHERE "clearedAt" IS NULL;
+
+COMMENT ON TABLE settlement_outcomes IS 'Validation and proof submission outcomes per seller/template, input of the anomaly monitor';
+COMMENT ON TABLE flagged_templates IS 'PDF templates flagged for a mismatch spike, surfaced by /api/status';
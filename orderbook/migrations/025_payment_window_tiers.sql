@@ -0,0 +1,23 @@
+-- ============================================================================
+-- PAYMENT WINDOW TIERS - Admin-managed payment window policy per token / trade size
+-- ============================================================================
+-- Large trades need longer to pay (Alipay transfer limits, bank confirmations).
+-- fillOrder has no per-trade window - every trade expires paymentWindow after the
+-- fill - so a tier is the window a trade of its size *should* get. execute-fill
+-- returns it with the trade and warns the buyer when the contract's window is
+-- shorter. A tier applies to trades worth at least minValueCny; token tiers take
+-- precedence over the any-token (NULL) tiers.
+
+CREATE TABLE IF NOT EXISTS payment_window_tiers (
+    "id" BIGSERIAL PRIMARY KEY,
+    "token" VARCHAR(42) CHECK ("token" ~ '^0x[0-9a-f]{40}$'),   -- NULL: any token
+    "minValueCny" BIGINT NOT NULL CHECK ("minValueCny" >= 0),   -- CNY cents
+    "windowSecs" BIGINT NOT NULL CHECK ("windowSecs" > 0),
+    "updatedBy" VARCHAR(64),                                     -- Admin key name, if keys are configured
+    "updatedAt" TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW()
+);
+
+CREATE UNIQUE INDEX IF NOT EXISTS idx_payment_window_tiers_tier
+    ON payment_window_tiers (COALESCE("token", ''), "minValueCny");
+
+COMMENT ON TABLE payment_window_tiers IS 'Recommended payment window per token and trade value tier, see /api/admin/payment-windows';
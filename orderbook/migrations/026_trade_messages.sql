@@ -0,0 +1,25 @@
+-- ============================================================================
+-- TRADE MESSAGES - Buyer/seller coordination channel per trade
+-- ============================================================================
+-- Lets the two parties of a trade sort out a mistyped Alipay note or a partial
+-- payment without leaving the platform. Only the trade's buyer and the order's
+-- seller can post or read (wallet signature or a wallet-bound API key).
+-- Append-only; attachments (screenshots, receipts) are size-limited and stored
+-- inline.
+
+CREATE TABLE IF NOT EXISTS trade_messages (
+    "id" BIGSERIAL PRIMARY KEY,
+    "tradeId" VARCHAR(66) NOT NULL REFERENCES trades("tradeId") ON DELETE CASCADE,
+    "sender" VARCHAR(42) NOT NULL,
+    "role" VARCHAR(8) NOT NULL CHECK ("role" IN ('buyer', 'seller')),
+    "body" TEXT NOT NULL DEFAULT '' CHECK (length("body") <= 2000),
+    "attachment" BYTEA,
+    "attachmentName" VARCHAR(128),
+    "attachmentType" VARCHAR(32),                          -- Detected from the content, not the client
+    "createdAt" TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW(),
+    CHECK (length("body") > 0 OR "attachment" IS NOT NULL)
+);
+
+CREATE INDEX IF NOT EXISTS "idx_trade_messages_trade" ON trade_messages("tradeId", "id");
+
+COMMENT ON TABLE trade_messages IS 'Buyer/seller messages per trade, see /api/trades/:trade_id/messages';
@@ -0,0 +1,29 @@
+-- ============================================================================
+-- DEAD LETTER EVENTS - Contract events the listener failed to apply
+-- ============================================================================
+-- The listener moves past a block range even if handling one of its events
+-- failed (one bad event mustn't stall the book), so the failed logs are kept
+-- here with the error instead of being dropped. Admins inspect them at
+-- /api/admin/dead-letters and queue a reprocess once the cause is fixed; the
+-- listener (on whichever replica leads) replays queued entries on its next poll.
+
+CREATE TABLE IF NOT EXISTS dead_letter_events (
+    "id" BIGSERIAL PRIMARY KEY,
+    "eventType" VARCHAR(32) NOT NULL,                      -- Event name, or OrderBatch for a bulk order tx
+    "blockNumber" BIGINT NOT NULL,
+    "txHash" VARCHAR(66) NOT NULL,
+    "logIndex" BIGINT NOT NULL,                            -- First log of the entry
+    "logs" TEXT NOT NULL,                                  -- Raw logs as JSON (eth_getLogs form), in order
+    "error" TEXT NOT NULL,                                 -- Latest failure
+    "attempts" INTEGER NOT NULL DEFAULT 1,
+    "status" VARCHAR(12) NOT NULL DEFAULT 'pending'
+        CHECK ("status" IN ('pending', 'queued', 'reprocessed', 'discarded')),
+    "resolvedBy" VARCHAR(64),                              -- Admin key name that queued or discarded it
+    "createdAt" TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW(),
+    "updatedAt" TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW(),
+    UNIQUE ("txHash", "logIndex")
+);
+
+CREATE INDEX IF NOT EXISTS "idx_dead_letter_events_status" ON dead_letter_events("status", "id");
+
+COMMENT ON TABLE dead_letter_events IS 'Contract events whose handling failed, see /api/admin/dead-letters';
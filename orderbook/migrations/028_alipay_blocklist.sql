@@ -0,0 +1,29 @@
+-- ============================================================================
+-- ALIPAY BLOCKLIST - Operator-managed list of flagged seller Alipay accounts
+-- ============================================================================
+-- Accounts tied to fraud or chargebacks. Orders paying out to a listed account
+-- drop out of matching and the active listing, and execute-fill refuses fills
+-- against them. Nothing changes on-chain: the seller can still withdraw.
+-- IDs are stored trimmed and lowercased and compared the same way against
+-- orders."alipayId". Every change is kept in alipay_blocklist_audit.
+
+CREATE TABLE IF NOT EXISTS alipay_blocklist (
+    "alipayId" VARCHAR(128) PRIMARY KEY CHECK ("alipayId" = lower(btrim("alipayId")) AND "alipayId" <> ''),
+    "reason" TEXT,
+    "addedBy" VARCHAR(64),                                 -- Admin key name, if keys are configured
+    "addedAt" TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW()
+);
+
+CREATE TABLE IF NOT EXISTS alipay_blocklist_audit (
+    "id" BIGSERIAL PRIMARY KEY,
+    "alipayId" VARCHAR(128) NOT NULL,
+    "action" VARCHAR(8) NOT NULL CHECK ("action" IN ('block', 'unblock')),
+    "reason" TEXT,
+    "actor" VARCHAR(64),                                   -- Admin key name, if keys are configured
+    "createdAt" TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX IF NOT EXISTS "idx_alipay_blocklist_audit_account" ON alipay_blocklist_audit("alipayId", "id");
+
+COMMENT ON TABLE alipay_blocklist IS 'Seller Alipay accounts excluded from matching, see /api/admin/alipay-blocklist';
+COMMENT ON TABLE alipay_blocklist_audit IS 'Blocklist changes, newest last';
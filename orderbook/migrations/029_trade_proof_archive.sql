@@ -0,0 +1,25 @@
+-- ============================================================================
+-- TRADE PROOF ARCHIVE - Proofs replaced by a PDF re-upload
+-- ============================================================================
+-- A proof proves one PDF. When the buyer uploads another PDF the trade's proof
+-- fields are moved here and cleared, so a proof of the old receipt can't be
+-- submitted; the archive keeps it for support. Proof generation only stores its
+-- result if the PDF it read is still the trade's (pdf_uploaded_at unchanged),
+-- and submission refuses a proof generated before the latest upload.
+
+CREATE TABLE IF NOT EXISTS trade_proof_archive (
+    "id" BIGSERIAL PRIMARY KEY,
+    "tradeId" VARCHAR(66) NOT NULL REFERENCES trades("tradeId") ON DELETE CASCADE,
+    "userPublicValues" BYTEA,
+    "accumulator" BYTEA,
+    "proofData" BYTEA,
+    "axiomProofId" VARCHAR(100),
+    "proofJson" TEXT,
+    "generatedAt" TIMESTAMP WITH TIME ZONE,
+    "pdfUploadedAt" TIMESTAMP WITH TIME ZONE,              -- Upload the archived proof was made from
+    "archivedAt" TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX IF NOT EXISTS "idx_trade_proof_archive_trade" ON trade_proof_archive("tradeId", "id");
+
+COMMENT ON TABLE trade_proof_archive IS 'Proof artifacts cleared by a PDF re-upload, newest last';
@@ -0,0 +1,28 @@
+-- ============================================================================
+-- BUYER SPENDING LIMITS - Rolling CNY caps per buyer tier
+-- ============================================================================
+-- A tier caps the CNY value of the trades a buyer opened in the last 24 hours
+-- and the last 30 days (NULL: no cap). Buyers are in tier 0 unless a row in
+-- buyer_tiers puts them higher - set by an admin or by the KYC provider once
+-- it verified them. A buyer gets the limits of the highest configured tier at
+-- or below their own, so without any tiers nobody is limited.
+
+CREATE TABLE IF NOT EXISTS spending_limit_tiers (
+    "tier" INTEGER PRIMARY KEY CHECK ("tier" >= 0),
+    "dailyLimitCny" BIGINT CHECK ("dailyLimitCny" >= 0),       -- CNY cents over 24h
+    "monthlyLimitCny" BIGINT CHECK ("monthlyLimitCny" >= 0),   -- CNY cents over 30 days
+    "updatedBy" VARCHAR(64),                                    -- Admin key name, if keys are configured
+    "updatedAt" TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW()
+);
+
+CREATE TABLE IF NOT EXISTS buyer_tiers (
+    "buyer" VARCHAR(42) PRIMARY KEY CHECK ("buyer" ~ '^0x[0-9a-f]{40}$'),
+    "tier" INTEGER NOT NULL CHECK ("tier" >= 0),
+    "source" VARCHAR(16) NOT NULL CHECK ("source" IN ('admin', 'kyc')),
+    "reference" VARCHAR(128),                                   -- KYC provider's verification id
+    "updatedBy" VARCHAR(64),                                    -- Admin key name or KYC provider
+    "updatedAt" TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW()
+);
+
+COMMENT ON TABLE spending_limit_tiers IS 'Rolling 24h / 30d CNY caps per buyer tier, see /api/admin/spending-limits';
+COMMENT ON TABLE buyer_tiers IS 'Buyers above tier 0, assigned by an admin or the KYC provider';
@@ -0,0 +1,34 @@
+-- ============================================================================
+-- ADDRESS SCREENING - Sanctions / abuse verdicts and refusals
+-- ============================================================================
+-- Buyers are screened in execute-fill, sellers when their orders are synced
+-- (see screening.rs). address_screenings caches the provider's latest verdict
+-- per address; a flagged seller's orders stay out of matching until a later
+-- screening clears them. Every refusal of service is logged with the verdict's
+-- reason in screening_refusals.
+
+CREATE TABLE IF NOT EXISTS address_screenings (
+    "address" VARCHAR(42) PRIMARY KEY CHECK ("address" ~ '^0x[0-9a-f]{40}$'),
+    "flagged" BOOLEAN NOT NULL,
+    "reason" TEXT,                                              -- Provider's reason for a flag
+    "provider" VARCHAR(64) NOT NULL,
+    "screenedAt" TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX IF NOT EXISTS idx_address_screenings_flagged ON address_screenings("address") WHERE "flagged";
+
+CREATE TABLE IF NOT EXISTS screening_refusals (
+    "id" BIGSERIAL PRIMARY KEY,
+    "address" VARCHAR(42) NOT NULL,
+    "role" VARCHAR(8) NOT NULL CHECK ("role" IN ('buyer', 'seller')),
+    "context" VARCHAR(16) NOT NULL CHECK ("context" IN ('execute_fill', 'order_sync')),
+    "reference" VARCHAR(66),                                    -- Order the refusal concerns, if any
+    "reason" TEXT,
+    "provider" VARCHAR(64) NOT NULL,
+    "createdAt" TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX IF NOT EXISTS idx_screening_refusals_address ON screening_refusals("address", "createdAt" DESC);
+
+COMMENT ON TABLE address_screenings IS 'Latest screening verdict per address (cache and matching exclusion)';
+COMMENT ON TABLE screening_refusals IS 'Audit log of service refused to flagged addresses, see /api/admin/screening';
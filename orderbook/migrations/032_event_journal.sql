@@ -0,0 +1,108 @@
+-- ============================================================================
+-- EVENT JOURNAL - Normalized lifecycle events for downstream consumers
+-- ============================================================================
+-- Analytics warehouses and risk systems consume order and trade lifecycle events
+-- from a queue instead of reading this schema. Events are written here by
+-- triggers, in the same transaction as the change they describe (so no write
+-- path can skip them), and the journal publisher (see journal) forwards them to
+-- the configured sink and stamps "publishedAt". A sink outage only delays
+-- delivery; delivery is at-least-once and consumers dedupe on "id".
+--
+-- Payloads are flat JSON with amounts as decimal strings (uint256 doesn't fit a
+-- JSON number) and addresses lowercase, so consumers never need this schema.
+
+CREATE TABLE IF NOT EXISTS event_journal (
+    "id" BIGSERIAL PRIMARY KEY,
+    "eventType" VARCHAR(32) NOT NULL CHECK ("eventType" IN (
+        'order_created', 'order_updated', 'trade_created', 'trade_settled', 'trade_expired'
+    )),
+    "aggregateId" VARCHAR(66) NOT NULL,                  -- orderId or tradeId
+    "payload" JSONB NOT NULL,
+    "occurredAt" TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW(),
+    "publishedAt" TIMESTAMP WITH TIME ZONE               -- NULL until the sink accepted it
+);
+
+CREATE INDEX IF NOT EXISTS "idx_event_journal_unpublished"
+    ON event_journal("id") WHERE "publishedAt" IS NULL;
+CREATE INDEX IF NOT EXISTS "idx_event_journal_publishedAt"
+    ON event_journal("publishedAt") WHERE "publishedAt" IS NOT NULL;
+
+CREATE OR REPLACE FUNCTION orders_journal_event() RETURNS TRIGGER AS $$
+BEGIN
+    IF TG_OP = 'UPDATE'
+        AND NEW."remainingAmount" IS NOT DISTINCT FROM OLD."remainingAmount"
+        AND NEW."minFill" IS NOT DISTINCT FROM OLD."minFill"
+        AND NEW."lotSize" IS NOT DISTINCT FROM OLD."lotSize" THEN
+        RETURN NULL;
+    END IF;
+
+    INSERT INTO event_journal ("eventType", "aggregateId", "payload")
+    VALUES (
+        CASE TG_OP WHEN 'INSERT' THEN 'order_created' ELSE 'order_updated' END,
+        NEW."orderId",
+        jsonb_build_object(
+            'order_id', NEW."orderId",
+            'seller', NEW."seller",
+            'token', NEW."token",
+            'total_amount', NEW."totalAmount"::TEXT,
+            'remaining_amount', NEW."remainingAmount"::TEXT,
+            'exchange_rate', NEW."exchangeRate"::TEXT,
+            'min_fill', NEW."minFill"::TEXT,
+            'lot_size', NEW."lotSize"::TEXT,
+            'created_at', NEW."createdAt"
+        )
+    );
+    RETURN NULL;
+END;
+$$ LANGUAGE plpgsql;
+
+DROP TRIGGER IF EXISTS "orders_journal" ON orders;
+CREATE TRIGGER "orders_journal"
+    AFTER INSERT OR UPDATE ON orders
+    FOR EACH ROW
+    EXECUTE FUNCTION orders_journal_event();
+
+CREATE OR REPLACE FUNCTION trades_journal_event() RETURNS TRIGGER AS $$
+DECLARE
+    event_type VARCHAR(32);
+BEGIN
+    IF TG_OP = 'INSERT' THEN
+        event_type := 'trade_created';
+    ELSIF NEW."status" IS DISTINCT FROM OLD."status" AND NEW."status" = 1 THEN
+        event_type := 'trade_settled';
+    ELSIF NEW."status" IS DISTINCT FROM OLD."status" AND NEW."status" = 2 THEN
+        event_type := 'trade_expired';
+    ELSE
+        RETURN NULL;
+    END IF;
+
+    INSERT INTO event_journal ("eventType", "aggregateId", "payload")
+    VALUES (
+        event_type,
+        NEW."tradeId",
+        jsonb_build_object(
+            'trade_id', NEW."tradeId",
+            'order_id', NEW."orderId",
+            'buyer', NEW."buyer",
+            'token_amount', NEW."tokenAmount"::TEXT,
+            'cny_amount', NEW."cnyAmount"::TEXT,
+            'status', NEW."status",
+            'created_at', NEW."createdAt",
+            'expires_at', NEW."expiresAt",
+            'escrow_tx_hash', NEW."escrowTxHash",
+            'settlement_tx_hash', NEW."settlementTxHash"
+        )
+    );
+    RETURN NULL;
+END;
+$$ LANGUAGE plpgsql;
+
+DROP TRIGGER IF EXISTS "trades_journal" ON trades;
+CREATE TRIGGER "trades_journal"
+    AFTER INSERT OR UPDATE OF "status" ON trades
+    FOR EACH ROW
+    EXECUTE FUNCTION trades_journal_event();
+
+COMMENT ON TABLE event_journal IS 'Outbox of normalized lifecycle events, forwarded by the journal publisher';
+COMMENT ON TRIGGER "orders_journal" ON orders IS 'Journals order_created / order_updated';
+COMMENT ON TRIGGER "trades_journal" ON trades IS 'Journals trade_created / trade_settled / trade_expired';
@@ -0,0 +1,73 @@
+-- ============================================================================
+-- ORDERBOOK NOTIFY - Invalidation feed for the in-memory order book
+-- ============================================================================
+-- API replicas keep each token's matchable orders in memory (cache::orderbook) and
+-- LISTEN on "orderbook_changed" to drop them when they change. The payload is the
+-- token whose orders changed, or '*' when a change can affect every token: a
+-- blocklisted Alipay account, a seller flagged or cleared by screening, or a trade
+-- outcome that moves seller reputation. Notifications are delivered on commit and
+-- Postgres folds duplicates within a transaction, so a batch sync sends one per token.
+
+CREATE OR REPLACE FUNCTION orderbook_notify_token() RETURNS TRIGGER AS $$
+BEGIN
+    IF TG_OP = 'DELETE' THEN
+        PERFORM pg_notify('orderbook_changed', OLD."token");
+    ELSE
+        PERFORM pg_notify('orderbook_changed', NEW."token");
+    END IF;
+    RETURN NULL;
+END;
+$$ LANGUAGE plpgsql;
+
+CREATE OR REPLACE FUNCTION orderbook_notify_all() RETURNS TRIGGER AS $$
+BEGIN
+    PERFORM pg_notify('orderbook_changed', '*');
+    RETURN NULL;
+END;
+$$ LANGUAGE plpgsql;
+
+CREATE OR REPLACE FUNCTION orderbook_notify_screening() RETURNS TRIGGER AS $$
+DECLARE
+    was_flagged BOOLEAN := FALSE;
+    is_flagged BOOLEAN := FALSE;
+BEGIN
+    IF TG_OP <> 'INSERT' THEN
+        was_flagged := OLD."flagged";
+    END IF;
+    IF TG_OP <> 'DELETE' THEN
+        is_flagged := NEW."flagged";
+    END IF;
+
+    -- Only flagged sellers are left out of the book; clean verdicts don't change it
+    IF was_flagged OR is_flagged THEN
+        PERFORM pg_notify('orderbook_changed', '*');
+    END IF;
+    RETURN NULL;
+END;
+$$ LANGUAGE plpgsql;
+
+DROP TRIGGER IF EXISTS "orders_orderbook_notify" ON orders;
+CREATE TRIGGER "orders_orderbook_notify"
+    AFTER INSERT OR UPDATE OR DELETE ON orders
+    FOR EACH ROW
+    EXECUTE FUNCTION orderbook_notify_token();
+
+DROP TRIGGER IF EXISTS "alipay_blocklist_orderbook_notify" ON alipay_blocklist;
+CREATE TRIGGER "alipay_blocklist_orderbook_notify"
+    AFTER INSERT OR UPDATE OR DELETE ON alipay_blocklist
+    FOR EACH STATEMENT
+    EXECUTE FUNCTION orderbook_notify_all();
+
+DROP TRIGGER IF EXISTS "address_screenings_orderbook_notify" ON address_screenings;
+CREATE TRIGGER "address_screenings_orderbook_notify"
+    AFTER INSERT OR UPDATE OR DELETE ON address_screenings
+    FOR EACH ROW
+    EXECUTE FUNCTION orderbook_notify_screening();
+
+DROP TRIGGER IF EXISTS "trades_orderbook_notify" ON trades;
+CREATE TRIGGER "trades_orderbook_notify"
+    AFTER UPDATE OF "status" ON trades
+    FOR EACH STATEMENT
+    EXECUTE FUNCTION orderbook_notify_all();
+
+COMMENT ON TRIGGER "orders_orderbook_notify" ON orders IS 'Invalidates the token''s in-memory order book';
@@ -0,0 +1,19 @@
+-- ============================================================================
+-- PROOF FORMAT - Verifier format each stored proof was generated in
+-- ============================================================================
+-- Proof component sizes depend on the zk verifier the escrow points at (see
+-- proof_format). Each proof records the format it was checked against when it
+-- was generated, so after a verifier upgrade a pending trade's proof is
+-- validated against its own format and the buyer is told to generate it again,
+-- rather than failing a size check against the new verifier's format.
+-- Proofs stored before this migration are all halo2-v1.
+
+ALTER TABLE trades ADD COLUMN IF NOT EXISTS "proofFormat" VARCHAR(32);
+ALTER TABLE trade_proof_archive ADD COLUMN IF NOT EXISTS "proofFormat" VARCHAR(32);
+
+UPDATE trades SET "proofFormat" = 'halo2-v1'
+WHERE "proofFormat" IS NULL AND (proof_data IS NOT NULL OR proof_json IS NOT NULL);
+UPDATE trade_proof_archive SET "proofFormat" = 'halo2-v1'
+WHERE "proofFormat" IS NULL AND ("proofData" IS NOT NULL OR "proofJson" IS NOT NULL);
+
+COMMENT ON COLUMN trades."proofFormat" IS 'ProofFormat version of the stored proof (NULL when there is none)';
@@ -0,0 +1,13 @@
+-- ============================================================================
+-- RELAYER SPEND BATCHES - One spend row per trade of a batched transaction
+-- ============================================================================
+-- With settlement batching (blockchain::settlement_batch) one Multicall3 transaction
+-- submits several trades' proofs. Each trade records its share of the gas under the
+-- same tx hash, so rows are unique per (tx hash, trade) instead of per tx hash.
+
+ALTER TABLE relayer_spend DROP CONSTRAINT IF EXISTS "relayer_spend_txHash_key";
+
+CREATE UNIQUE INDEX IF NOT EXISTS idx_relayer_spend_tx_trade
+    ON relayer_spend("txHash", COALESCE("tradeId", ''));
+
+COMMENT ON INDEX idx_relayer_spend_tx_trade IS 'A batched transaction has one row per trade, each with its share of the gas';
@@ -0,0 +1,27 @@
+-- ============================================================================
+-- PROVER TRANSCRIPTS - Axiom request/response history per proof job
+-- ============================================================================
+-- The Axiom client only logged response bodies to stdout, which is gone once logs
+-- rotate. Each execution (validation) and proof job now records what was sent to
+-- Axiom (endpoint and request metadata - never the input streams or the API key)
+-- and the terminal responses it got back, so a failed proof can be debugged later
+-- from /api/admin/trades/:trade_id/prover-transcripts. Response bodies are
+-- truncated and have the API key redacted.
+
+CREATE TABLE IF NOT EXISTS prover_transcripts (
+    "id" BIGSERIAL PRIMARY KEY,
+    "tradeId" VARCHAR(66) NOT NULL,
+    "kind" VARCHAR(16) NOT NULL CHECK ("kind" IN ('execution', 'proof')),
+    "programId" VARCHAR(128) NOT NULL,
+    "axiomId" VARCHAR(128),                              -- Execution or proof id (NULL if never submitted)
+    "status" VARCHAR(16) NOT NULL CHECK ("status" IN ('succeeded', 'failed')),
+    "error" TEXT,
+    "entries" JSONB NOT NULL,                            -- [{at, method, path, request, status, response, polls}]
+    "startedAt" TIMESTAMP WITH TIME ZONE NOT NULL,
+    "finishedAt" TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX IF NOT EXISTS "idx_prover_transcripts_trade"
+    ON prover_transcripts("tradeId", "startedAt" DESC);
+
+COMMENT ON TABLE prover_transcripts IS 'Redacted Axiom request/response transcripts per execution and proof job';
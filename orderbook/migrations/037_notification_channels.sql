@@ -0,0 +1,22 @@
+-- ============================================================================
+-- NOTIFICATION CHANNELS - Per-user delivery preferences
+-- ============================================================================
+-- Notifications used to go only to the operator's webhook. A wallet can now register
+-- where it wants to be told about its trades directly: an email address, a phone
+-- number (SMS gateway) or a Telegram chat, each limited to a set of events or
+-- receiving all of them. Preferences are set with a personal_sign signature of the
+-- address (see /api/notifications/:address/channels).
+
+CREATE TABLE IF NOT EXISTS notification_channels (
+    "address" VARCHAR(42) NOT NULL CHECK ("address" ~ '^0x[0-9a-f]{40}$'),
+    "channel" VARCHAR(16) NOT NULL CHECK ("channel" IN ('email', 'sms', 'telegram')),
+    "destination" TEXT NOT NULL,                         -- Email address, E.164 phone number or Telegram chat id
+    "events" TEXT[],                                     -- Notification events to deliver (NULL = all)
+    "signedAt" BIGINT NOT NULL,                          -- signed_at of the signature that set it
+    "createdAt" TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW(),
+    "updatedAt" TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW(),
+
+    PRIMARY KEY ("address", "channel")
+);
+
+COMMENT ON TABLE notification_channels IS 'Email/SMS/Telegram destinations a wallet receives its notifications on';
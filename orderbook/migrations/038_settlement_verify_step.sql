@@ -0,0 +1,13 @@
+-- ============================================================================
+-- SETTLEMENT VERIFY STEP - Fork dry run between prove and submit
+-- ============================================================================
+-- Settlement jobs gained a verify step that mines the submission on an anvil fork
+-- of the target chain before the real one (SETTLEMENT_FORK_RPC_URL, see
+-- blockchain/fork_check.rs), so a proof the verifier rejects fails the job without
+-- spending gas. Without a fork configured the step passes straight through.
+
+ALTER TABLE settlement_jobs DROP CONSTRAINT IF EXISTS settlement_jobs_step_check;
+ALTER TABLE settlement_jobs ADD CONSTRAINT settlement_jobs_step_check
+    CHECK ("step" IN ('validate', 'prove', 'verify', 'submit', 'done'));
+
+COMMENT ON TABLE settlement_jobs IS 'Settlement pipeline jobs (validate, prove, verify, submit), see /api/settlement-jobs';
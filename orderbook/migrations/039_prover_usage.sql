@@ -0,0 +1,25 @@
+-- ============================================================================
+-- PROVER USAGE - Axiom credits spent per execution and proof job
+-- ============================================================================
+-- Axiom bills every execution and proof against the account's credits, and a job
+-- submitted after they ran out only fails once it's already in flight. Every job
+-- Axiom accepted is recorded here with the credits it costs (per the configured
+-- AXIOM_*_CREDITS) and, when Axiom reported one in a response header, the balance
+-- it reported. The prover budget (src/axiom_prover/budget.rs) sums the current
+-- month's rows to refuse new jobs before they start, and /api/admin/prover-budget
+-- shows the balance.
+
+CREATE TABLE IF NOT EXISTS prover_usage (
+    "id" BIGSERIAL PRIMARY KEY,
+    "tradeId" VARCHAR(66) NOT NULL,
+    "kind" VARCHAR(16) NOT NULL CHECK ("kind" IN ('execution', 'proof')),
+    "axiomId" VARCHAR(128) NOT NULL,                     -- Execution or proof id
+    "credits" BIGINT NOT NULL CHECK ("credits" >= 0),
+    "reportedRemaining" BIGINT,                          -- Balance from Axiom's response header (NULL if none)
+    "createdAt" TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX IF NOT EXISTS "idx_prover_usage_created"
+    ON prover_usage("createdAt" DESC);
+
+COMMENT ON TABLE prover_usage IS 'Axiom credits spent per accepted execution and proof job';
@@ -0,0 +1,24 @@
+-- ============================================================================
+-- TRADE PAYMENTS - Alipay transfers buyers declare per trade
+-- ============================================================================
+-- A buy filled by several orders is one trade per fill, each paid with its own
+-- Alipay transfer of its own amount, and buyers mix up which transfer belongs to
+-- which trade. Before uploading the receipt, the buyer declares each transfer they
+-- made for a trade (time and amount); the declared amount is checked against the
+-- trade's cnyAmount, and a mismatch that equals another of the buyer's pending
+-- trades is pointed out. Support sees the declarations alongside the trade's notes.
+
+CREATE TABLE IF NOT EXISTS trade_payments (
+    "id" BIGSERIAL PRIMARY KEY,
+    "tradeId" VARCHAR(66) NOT NULL REFERENCES trades("tradeId") ON DELETE CASCADE,
+    "buyer" VARCHAR(42) NOT NULL,
+    "amount" BIGINT NOT NULL CHECK ("amount" > 0),       -- CNY cents the buyer says they sent
+    "paidAt" TIMESTAMP WITH TIME ZONE NOT NULL,           -- When the buyer says they sent it
+    "amountStatus" VARCHAR(8) NOT NULL CHECK ("amountStatus" IN ('exact', 'short', 'over')),
+    "matchesTradeId" VARCHAR(66),                         -- Buyer's other pending trade of exactly this amount
+    "createdAt" TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX IF NOT EXISTS "idx_trade_payments_trade" ON trade_payments("tradeId", "id");
+
+COMMENT ON TABLE trade_payments IS 'Buyer-declared Alipay transfers per trade, see /api/trades/:trade_id/payments';
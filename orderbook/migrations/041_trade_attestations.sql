@@ -0,0 +1,28 @@
+-- ============================================================================
+-- TRADE ATTESTATIONS - EAS record of each settled trade
+-- ============================================================================
+-- With EAS attestations enabled (see src/blockchain/eas.rs), the event listener
+-- queues every settled trade here and the attester has the relayer write an
+-- Ethereum Attestation Service attestation summarizing it once the settlement is
+-- final. A replica claims a row ('attesting') before sending the transaction so
+-- two replicas never attest the same trade; a claim left by a crashed attempt is
+-- taken over after the lease. Failed attempts go back to 'pending' until the
+-- configured attempts are used up ('failed').
+
+CREATE TABLE IF NOT EXISTS trade_attestations (
+    "tradeId" VARCHAR(66) PRIMARY KEY REFERENCES trades("tradeId") ON DELETE CASCADE,
+    "status" VARCHAR(16) NOT NULL DEFAULT 'pending'
+        CHECK ("status" IN ('pending', 'attesting', 'attested', 'failed')),
+    "attempts" INTEGER NOT NULL DEFAULT 0,                 -- Incremented on every claim
+    "uid" VARCHAR(66),                                     -- EAS attestation UID
+    "txHash" VARCHAR(66),                                  -- attest transaction
+    "error" TEXT,                                          -- Why the last attempt failed
+    "createdAt" TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW(),
+    "updatedAt" TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW(),
+    "attestedAt" TIMESTAMP WITH TIME ZONE
+);
+
+CREATE INDEX IF NOT EXISTS idx_trade_attestations_open
+    ON trade_attestations("createdAt") WHERE "status" IN ('pending', 'attesting');
+
+COMMENT ON TABLE trade_attestations IS 'EAS attestations of settled trades, see /api/trades/:trade_id/attestation';
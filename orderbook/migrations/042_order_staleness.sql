@@ -0,0 +1,33 @@
+-- ============================================================================
+-- ORDER STALENESS - Liquidity its seller stopped looking after
+-- ============================================================================
+-- Orders never expire on-chain, so an order its seller abandoned keeps being
+-- matched and buyers lock trades nobody watches for. An order's last activity is
+-- the latest of its creation, any change logged in order_feed (fills, partial
+-- withdrawals, fill limits) and the seller's signed refresh
+-- (POST /api/orders/:order_id/refresh). An active order idle for "staleAfterDays"
+-- is stale: match requests can leave stale orders out (exclude_stale), and the
+-- seller is nudged to withdraw or refresh it, at most once every
+-- "notifyEveryDays". Admins set both thresholds (/api/admin/order-staleness).
+
+CREATE TABLE IF NOT EXISTS order_staleness_settings (
+    "id" BOOLEAN PRIMARY KEY DEFAULT TRUE CHECK ("id"),  -- single row
+    "staleAfterDays" INTEGER NOT NULL DEFAULT 14 CHECK ("staleAfterDays" > 0),
+    "notifyEveryDays" INTEGER NOT NULL DEFAULT 7 CHECK ("notifyEveryDays" > 0),
+    "updatedBy" VARCHAR(64),                             -- Admin key name, if keys are configured
+    "updatedAt" TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW()
+);
+INSERT INTO order_staleness_settings ("id") VALUES (TRUE) ON CONFLICT DO NOTHING;
+
+CREATE TABLE IF NOT EXISTS order_activity (
+    "orderId" VARCHAR(66) PRIMARY KEY REFERENCES orders("orderId") ON DELETE CASCADE,
+    "refreshedAt" TIMESTAMP WITH TIME ZONE,              -- Seller's latest refresh
+    "refreshSignedAt" BIGINT,                            -- Its signed timestamp (older signatures can't be replayed)
+    "staleNotifiedAt" TIMESTAMP WITH TIME ZONE           -- Last stale-order nudge to the seller
+);
+
+-- Latest change per order, for the last-activity lookup
+CREATE INDEX IF NOT EXISTS idx_order_feed_order ON order_feed("orderId", "loggedAt" DESC);
+
+COMMENT ON TABLE order_staleness_settings IS 'Admin thresholds for stale orders, see /api/admin/order-staleness';
+COMMENT ON TABLE order_activity IS 'Seller refreshes and stale-order nudges per order';
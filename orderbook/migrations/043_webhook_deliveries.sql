@@ -0,0 +1,12 @@
+-- ============================================================================
+-- WEBHOOK DELIVERIES - Monotonic delivery IDs for signed webhook payloads
+-- ============================================================================
+-- Every webhook POST carries a delivery ID and a timestamp, covered by the
+-- subscriber's HMAC-SHA256 signature (see src/notifications/webhooks.rs). IDs come
+-- from this sequence so they keep increasing across restarts and replicas: a
+-- consumer that remembers the last ID it accepted rejects replayed or reordered
+-- callbacks. A retried event is a new delivery with a new ID.
+
+CREATE SEQUENCE IF NOT EXISTS webhook_delivery_ids;
+
+COMMENT ON SEQUENCE webhook_delivery_ids IS 'Delivery IDs of webhook payloads, see notifications::webhooks';
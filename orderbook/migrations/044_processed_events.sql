@@ -0,0 +1,20 @@
+-- ============================================================================
+-- PROCESSED EVENTS - Contract logs whose side effects have been applied
+-- ============================================================================
+-- The listener sees a log again when it resyncs a range (after a restart before
+-- the synced block was saved, a reorg rewind or a dead-letter replay). Its mirror
+-- writes are idempotent, but order amount adjustments, notifications, fee
+-- accruals and event counts are not: each log claims its (txHash, logIndex) here
+-- first and only the first claim applies them (see src/db/processed_events.rs).
+-- Amount adjustments are claimed in the same transaction that applies them.
+
+CREATE TABLE IF NOT EXISTS processed_events (
+    "txHash" VARCHAR(66) NOT NULL,
+    "logIndex" BIGINT NOT NULL,                            -- First log of a bulk order tx
+    "eventType" VARCHAR(32) NOT NULL,
+    "blockNumber" BIGINT NOT NULL,
+    "processedAt" TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW(),
+    PRIMARY KEY ("txHash", "logIndex")
+);
+
+COMMENT ON TABLE processed_events IS 'Contract logs the event listener has applied once, see blockchain::events';
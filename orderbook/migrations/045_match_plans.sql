@@ -0,0 +1,27 @@
+-- ============================================================================
+-- MATCH PLANS - Every plan /api/match-intent handed out, for audit and replay
+-- ============================================================================
+-- A plan is kept with the request that produced it, the order priority and a hash
+-- of the book it was matched against (see api::matching::book_hash). execute-fill
+-- takes the plan's ID, refuses plans past "expiresAt" and fills that differ from
+-- the stored ones, and appends the trades it creates - so after an incident the
+-- fills of a trade can be traced back to why they were chosen.
+
+CREATE TABLE IF NOT EXISTS match_plans (
+    "planId" UUID PRIMARY KEY,
+    "token" VARCHAR(42) NOT NULL,
+    "buyer" VARCHAR(42),                                   -- When the request named one
+    "request" TEXT NOT NULL,                               -- Request parameters as JSON
+    "plan" TEXT NOT NULL,                                  -- MatchPlan as returned, as JSON
+    "bookHash" VARCHAR(66) NOT NULL,                       -- sha256 of the orders matched against
+    "orderPriority" VARCHAR(16) NOT NULL,
+    "createdAt" TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW(),
+    "expiresAt" TIMESTAMP WITH TIME ZONE NOT NULL,
+    "executedBy" VARCHAR(42),                              -- Buyer of the last execute-fill
+    "tradeIds" TEXT[] NOT NULL DEFAULT '{}',               -- Trades created from the plan
+    "executedAt" TIMESTAMP WITH TIME ZONE
+);
+
+CREATE INDEX IF NOT EXISTS idx_match_plans_created ON match_plans("createdAt");
+
+COMMENT ON TABLE match_plans IS 'Match plans returned by /api/match-intent and the trades executed from them';
@@ -0,0 +1,8 @@
+-- ============================================================================
+-- PROCESSED EVENTS PRUNING - Claims are deleted after a retention window
+-- ============================================================================
+-- The live listener only resyncs the last few blocks, so a claim is not needed
+-- long after its log was applied. The listener deletes claims older than its
+-- retention hourly (see src/blockchain/events.rs); this index keeps that cheap.
+
+CREATE INDEX IF NOT EXISTS idx_processed_events_processed_at ON processed_events("processedAt");
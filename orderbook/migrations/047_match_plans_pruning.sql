@@ -0,0 +1,9 @@
+-- ============================================================================
+-- MATCH PLANS PRUNING - Plans that expire unexecuted are deleted
+-- ============================================================================
+-- /api/match-intent stores a plan per quote, and most quotes are never executed.
+-- The auto-cancel service deletes plans past "expiresAt" without trades (see
+-- src/db/match_plans.rs); executed plans are kept for audit.
+
+CREATE INDEX IF NOT EXISTS idx_match_plans_unexecuted_expiry ON match_plans("expiresAt")
+    WHERE "executedAt" IS NULL;
@@ -0,0 +1,22 @@
+-- ============================================================================
+-- SETTLEMENT JOB LEASES - One process runs a settlement job at a time
+-- ============================================================================
+-- Every api-server used to restart all running jobs on startup, so overlapping
+-- replicas (a rolling deploy, or several replicas) proved and submitted the same job
+-- twice. The process running a job now holds a lease on it, renewed while the job
+-- runs; only jobs whose lease lapsed are claimed by another process (see
+-- src/db/settlement_jobs.rs).
+
+ALTER TABLE settlement_jobs ADD COLUMN IF NOT EXISTS "leaseOwner" UUID;
+ALTER TABLE settlement_jobs ADD COLUMN IF NOT EXISTS "leaseExpiresAt" TIMESTAMP WITH TIME ZONE;
+
+-- Jobs running during the upgrade belong to processes that don't renew leases yet:
+-- give them time to finish before they become claimable
+UPDATE settlement_jobs SET "leaseExpiresAt" = NOW() + INTERVAL '10 minutes'
+WHERE "status" = 'running' AND "leaseExpiresAt" IS NULL;
+
+CREATE INDEX IF NOT EXISTS idx_settlement_jobs_lease ON settlement_jobs("leaseExpiresAt")
+    WHERE "status" = 'running';
+
+COMMENT ON COLUMN settlement_jobs."leaseOwner" IS 'Process running the job (random per api-server start)';
+COMMENT ON COLUMN settlement_jobs."leaseExpiresAt" IS 'Claimable by another process after this, unless renewed';
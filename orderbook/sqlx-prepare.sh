@@ -0,0 +1,34 @@
+#!/bin/bash
+# Regenerate (or verify) the committed SQLx offline query metadata in .sqlx/
+#
+# All queries use the checked query!/query_as!/query_scalar! macros. Local builds
+# verify them against DATABASE_URL (.cargo/config.toml sets SQLX_OFFLINE=false);
+# the Docker build uses SQLX_OFFLINE=true and relies on .sqlx/ alone, so any query
+# or migration change must be followed by running this script and committing .sqlx/.
+#
+# Usage:
+#   ./sqlx-prepare.sh          # apply migrations and regenerate .sqlx/
+#   ./sqlx-prepare.sh --check  # fail if .sqlx/ is out of date (for CI)
+#
+# Requires: sqlx-cli (cargo install sqlx-cli --no-default-features --features postgres)
+
+set -euo pipefail
+
+cd "$(dirname "$0")"
+
+if [ -f .env ]; then
+    set -a
+    source .env
+    set +a
+fi
+
+: "${DATABASE_URL:?DATABASE_URL must point at a scratch Postgres database}"
+
+# Schema must match migrations/ before queries are checked against it
+sqlx migrate run
+
+if [ "${1:-}" = "--check" ]; then
+    cargo sqlx prepare --check -- --all-targets
+else
+    cargo sqlx prepare -- --all-targets
+fi
@@ -0,0 +1,119 @@
+// Alipay account ID formats as rendered in Alipay transfer receipts
+// The masked account line (账号：...) is part of the expected hash, so masking must
+// match the receipt byte-for-byte for each supported format.
+
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::str::FromStr;
+use thiserror::Error;
+
+use crate::text_utils::{grapheme_len, is_ascii_digits, mask_middle, take_graphemes};
+
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+pub enum AlipayIdError {
+    #[error("Invalid phone-format Alipay ID: expected 11 digits, got {0:?}")]
+    InvalidPhone(String),
+    #[error("Invalid email-format Alipay ID: {0:?}")]
+    InvalidEmail(String),
+    #[error("Unknown Alipay ID format: {0}")]
+    UnknownFormat(String),
+}
+
+/// Account ID format of an order's Alipay account (stored per order)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum AlipayIdFormat {
+    /// 11-digit mainland phone number, masked as 139******41
+    #[default]
+    Phone,
+    /// Email address, local part masked as abc***@example.com
+    Email,
+}
+
+impl AlipayIdFormat {
+    /// Infer the format from the account ID itself
+    pub fn detect(alipay_id: &str) -> Self {
+        if alipay_id.contains('@') {
+            AlipayIdFormat::Email
+        } else {
+            AlipayIdFormat::Phone
+        }
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            AlipayIdFormat::Phone => "phone",
+            AlipayIdFormat::Email => "email",
+        }
+    }
+}
+
+impl fmt::Display for AlipayIdFormat {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for AlipayIdFormat {
+    type Err = AlipayIdError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "phone" => Ok(AlipayIdFormat::Phone),
+            "email" => Ok(AlipayIdFormat::Email),
+            other => Err(AlipayIdError::UnknownFormat(other.to_string())),
+        }
+    }
+}
+
+/// Mask an Alipay ID the way the receipt PDF shows it
+///
+/// - Phone: first 3 and last 2 digits, middle 6 masked ("13945908941" → "139******41")
+/// - Email: first 3 characters of the local part (1 if the local part is 3 or
+///   shorter), then "***", domain kept ("alice.w@163.com" → "ali***@163.com")
+///
+/// Note: the on-chain maskAlipayId only implements the phone format, so email-format
+/// orders need a matching contract upgrade before their proofs can settle.
+pub fn mask_alipay_id(alipay_id: &str, format: AlipayIdFormat) -> Result<String, AlipayIdError> {
+    match format {
+        AlipayIdFormat::Phone => {
+            if !is_ascii_digits(alipay_id, 11) {
+                return Err(AlipayIdError::InvalidPhone(alipay_id.to_string()));
+            }
+            mask_middle(alipay_id, 3, 2, '*', 6)
+                .ok_or_else(|| AlipayIdError::InvalidPhone(alipay_id.to_string()))
+        }
+        AlipayIdFormat::Email => {
+            let (local, domain) = alipay_id
+                .split_once('@')
+                .filter(|(local, domain)| !local.is_empty() && !domain.is_empty() && !domain.contains('@'))
+                .ok_or_else(|| AlipayIdError::InvalidEmail(alipay_id.to_string()))?;
+
+            let keep = if grapheme_len(local) > 3 { 3 } else { 1 };
+            Ok(format!("{}***@{}", take_graphemes(local, keep), domain))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_mask_phone_and_email() {
+        assert_eq!(mask_alipay_id("13945908941", AlipayIdFormat::Phone).unwrap(), "139******41");
+        assert!(mask_alipay_id("1394590894", AlipayIdFormat::Phone).is_err());
+        assert!(mask_alipay_id("alice@163.com", AlipayIdFormat::Phone).is_err());
+
+        assert_eq!(mask_alipay_id("alice.w@163.com", AlipayIdFormat::Email).unwrap(), "ali***@163.com");
+        assert_eq!(mask_alipay_id("bob@qq.com", AlipayIdFormat::Email).unwrap(), "b***@qq.com");
+        assert!(mask_alipay_id("@qq.com", AlipayIdFormat::Email).is_err());
+        assert!(mask_alipay_id("13945908941", AlipayIdFormat::Email).is_err());
+        assert_eq!(mask_alipay_id("张小明@qq.com", AlipayIdFormat::Email).unwrap(), "张***@qq.com");
+        assert!(mask_alipay_id("139４5908941", AlipayIdFormat::Phone).is_err());
+
+        assert_eq!(AlipayIdFormat::detect("alice@163.com"), AlipayIdFormat::Email);
+        assert_eq!(AlipayIdFormat::detect("13945908941"), AlipayIdFormat::Phone);
+        assert_eq!("email".parse::<AlipayIdFormat>().unwrap(), AlipayIdFormat::Email);
+    }
+}
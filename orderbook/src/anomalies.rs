@@ -0,0 +1,192 @@
+// Settlement failure anomaly detection
+// Validations (hash mismatch) and proof submissions (PaymentDetailsMismatch,
+// ProofVerificationFailed reverts) record their outcome per seller and per receipt
+// template. A template is the fingerprint of a receipt's label layout, so receipts
+// from the same Alipay layout share it whatever the payment details. Individual
+// mismatches are usually a buyer's wrong PDF; many mismatches on one template at once
+// usually mean Alipay changed the layout and every proof on it will fail. The monitor
+// flags such a template, alerts admins (template_flagged webhook) and /api/status
+// shows a banner until an admin clears the flag.
+//
+// Configuration (env):
+// - ANOMALY_WINDOW_SECS: window the mismatch rate is computed over (default 3600)
+// - ANOMALY_MIN_SAMPLES: outcomes a template needs in the window before it can be flagged (default 10)
+// - ANOMALY_MISMATCH_RATE: mismatch rate that flags a template (default 0.5)
+
+use chrono::{Duration as ChronoDuration, Utc};
+use sha2::{Digest, Sha256};
+use std::sync::Arc;
+use tokio::task::JoinHandle;
+use tokio::time::{interval, Duration};
+
+use crate::blockchain::address::EthAddress;
+use crate::db::anomalies::{AnomalyRepository, PostgresAnomalyRepository};
+use crate::db::{Database, DbResult};
+use crate::notifications::{NotificationEvent, Notifier};
+
+/// Template of receipts whose text layer couldn't be read or has no labels
+pub const UNKNOWN_TEMPLATE: &str = "unknown";
+
+/// How often the monitor checks the rates
+const SCAN_INTERVAL: Duration = Duration::from_secs(300);
+
+/// Fingerprint of a receipt's layout: the ordered labels ("付款方式：" etc.) of its text
+/// layer, without the values. Receipts of the same layout share it.
+pub fn template_fingerprint(text: &str) -> String {
+    let labels: Vec<&str> = text
+        .lines()
+        .map(str::trim)
+        .filter_map(|line| line.split_once(['：', ':']).map(|(label, _)| label.trim()))
+        .filter(|label| !label.is_empty() && label.chars().count() <= 16 && !label.chars().any(|c| c.is_ascii_digit()))
+        .collect();
+    if labels.is_empty() {
+        return UNKNOWN_TEMPLATE.to_string();
+    }
+
+    let digest = Sha256::digest(labels.join("\n").as_bytes());
+    format!("tpl_{}", &hex::encode(digest)[..12])
+}
+
+/// Template of a receipt PDF (CPU-bound: call from a blocking task)
+pub fn pdf_template(pdf_bytes: &[u8]) -> String {
+    crate::pdf_text::extract_text(pdf_bytes)
+        .map(|text| template_fingerprint(&text))
+        .unwrap_or_else(|_| UNKNOWN_TEMPLATE.to_string())
+}
+
+/// Record an outcome; failures are logged, not surfaced (monitoring is best-effort)
+pub async fn record_outcome(db: &Database, trade_id: &str, seller: &EthAddress, template: &str, stage: &str, outcome: &str) {
+    if let Err(e) = db.record_settlement_outcome(trade_id, seller, template, stage, outcome).await {
+        tracing::warn!("Failed to record {} outcome for trade {}: {}", stage, trade_id, e);
+    }
+}
+
+/// Flagging thresholds
+#[derive(Debug, Clone)]
+pub struct AnomalyConfig {
+    pub window_secs: i64,
+    pub min_samples: i64,
+    pub mismatch_rate: f64,
+}
+
+impl Default for AnomalyConfig {
+    fn default() -> Self {
+        Self { window_secs: 3600, min_samples: 10, mismatch_rate: 0.5 }
+    }
+}
+
+impl AnomalyConfig {
+    pub fn from_env() -> Self {
+        let defaults = Self::default();
+        Self {
+            window_secs: std::env::var("ANOMALY_WINDOW_SECS").ok().and_then(|v| v.parse().ok()).unwrap_or(defaults.window_secs),
+            min_samples: std::env::var("ANOMALY_MIN_SAMPLES").ok().and_then(|v| v.parse().ok()).unwrap_or(defaults.min_samples),
+            mismatch_rate: std::env::var("ANOMALY_MISMATCH_RATE").ok().and_then(|v| v.parse().ok()).unwrap_or(defaults.mismatch_rate),
+        }
+    }
+
+    /// Whether `mismatches` out of `samples` is a spike
+    pub fn is_spike(&self, samples: i64, mismatches: i64) -> bool {
+        samples >= self.min_samples.max(1) && mismatches as f64 / samples as f64 >= self.mismatch_rate
+    }
+}
+
+pub struct AnomalyMonitor {
+    repo: PostgresAnomalyRepository,
+    notifier: Option<Arc<dyn Notifier>>,
+    config: AnomalyConfig,
+}
+
+impl AnomalyMonitor {
+    pub fn new(db_pool: sqlx::PgPool, config: AnomalyConfig) -> Self {
+        Self {
+            repo: PostgresAnomalyRepository::new(db_pool),
+            notifier: None,
+            config,
+        }
+    }
+
+    /// Send a template_flagged event when a template is flagged
+    pub fn with_notifier(mut self, notifier: Arc<dyn Notifier>) -> Self {
+        self.notifier = Some(notifier);
+        self
+    }
+
+    /// Spawn the scan loop as a background task
+    pub fn spawn(self) -> JoinHandle<()> {
+        tokio::spawn(async move { self.run().await })
+    }
+
+    async fn run(self) {
+        let mut ticker = interval(SCAN_INTERVAL);
+        loop {
+            ticker.tick().await;
+            if let Err(e) = self.scan().await {
+                tracing::warn!("🚨 Anomaly scan failed: {}", e);
+            }
+        }
+    }
+
+    /// Flag every template whose mismatch rate over the window is a spike
+    /// Returns the number of templates newly flagged.
+    pub async fn scan(&self) -> DbResult<usize> {
+        let since = Utc::now() - ChronoDuration::seconds(self.config.window_secs);
+        let stats = self.repo.template_stats(since).await?;
+
+        let mut flagged = 0;
+        for stat in stats.iter().filter(|s| s.key != UNKNOWN_TEMPLATE) {
+            if !self.config.is_spike(stat.samples, stat.mismatches) {
+                continue;
+            }
+            let rate = stat.mismatches as f64 / stat.samples as f64;
+            let Some(flag) = self.repo.flag(&stat.key, rate, stat.samples, since).await? else {
+                continue;
+            };
+            flagged += 1;
+            tracing::warn!(
+                "🚨 Template {} flagged: {}/{} mismatches in the last {}s (Alipay layout change?)",
+                flag.template, stat.mismatches, stat.samples, self.config.window_secs
+            );
+
+            if let Some(notifier) = &self.notifier {
+                let event = NotificationEvent::TemplateFlagged {
+                    template: flag.template.clone(),
+                    mismatch_rate: rate,
+                    samples: stat.samples,
+                    window_secs: self.config.window_secs,
+                };
+                if let Err(e) = notifier.notify(&event).await {
+                    tracing::warn!("🚨 Failed to send template_flagged for {}: {}", flag.template, e);
+                }
+            }
+        }
+
+        Ok(flagged)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_template_fingerprint() {
+        let receipt = "转账成功\n付款方式：余额\n收款方：张三\n账号：139******41\n金额：735.00\n12345678\n";
+        let other_payment = "转账成功\n付款方式：花呗\n收款方：李四\n账号：alice***@163.com\n金额：10.00\n87654321\n";
+        assert_eq!(template_fingerprint(receipt), template_fingerprint(other_payment));
+        assert!(template_fingerprint(receipt).starts_with("tpl_"));
+
+        // A relabelled or reordered layout is a different template
+        let new_layout = "转账成功\n收款方：张三\n付款方式：余额\n账号：139******41\n金额：735.00\n";
+        assert_ne!(template_fingerprint(receipt), template_fingerprint(new_layout));
+        assert_eq!(template_fingerprint("no labels here"), UNKNOWN_TEMPLATE);
+    }
+
+    #[test]
+    fn test_is_spike() {
+        let config = AnomalyConfig::default();
+        assert!(!config.is_spike(9, 9));
+        assert!(config.is_spike(10, 5));
+        assert!(!config.is_spike(10, 4));
+    }
+}
@@ -0,0 +1,223 @@
+// Market-maker API keys (x-api-key header)
+// Keys are issued by admins (POST /api/admin/api-keys), bound to a wallet and scoped
+// to read and/or trade. Requests without the header are served as before; with it,
+// the key must be live, carry the scope the route needs and stay under its
+// per-minute limit. Lookups are cached for KEY_CACHE_TTL and limits are counted per
+// process, so behind several replicas a key's limit applies per replica and a
+// revocation can take up to KEY_CACHE_TTL to reach the other replicas.
+
+use axum::{
+    extract::{Request, State},
+    http::Method,
+    middleware::Next,
+    response::Response,
+};
+use sha2::{Digest, Sha256};
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+use uuid::Uuid;
+
+use crate::api::{error::ApiError, state::AppState};
+use crate::blockchain::address::EthAddress;
+use crate::db::api_keys::{SCOPE_READ, SCOPE_TRADE};
+use crate::db::models::DbApiKey;
+
+/// Header carrying the caller's API key
+pub const API_KEY_HEADER: &str = "x-api-key";
+
+/// Issued secrets start with this, so leaked keys are easy to grep for
+const SECRET_PREFIX: &str = "zkak_";
+
+/// Chars of the secret kept in the clear to recognize a key in listings
+const DISPLAY_PREFIX_LEN: usize = 12;
+
+/// How long a key lookup (hit or miss) is reused
+const KEY_CACHE_TTL: Duration = Duration::from_secs(30);
+
+/// Rate limit window
+const RATE_WINDOW: Duration = Duration::from_secs(60);
+
+pub const DEFAULT_RATE_LIMIT_PER_MINUTE: i32 = 120;
+
+/// Authenticated key, added to the request extensions for handlers
+#[derive(Debug, Clone)]
+pub struct ApiKeyIdentity {
+    pub key_id: Uuid,
+    pub name: String,
+    pub wallet: EthAddress,
+    pub scopes: Vec<String>,
+}
+
+impl ApiKeyIdentity {
+    pub fn has_scope(&self, scope: &str) -> bool {
+        self.scopes.iter().any(|s| s == scope)
+    }
+}
+
+/// New random secret (shown to the admin once, only its hash is stored)
+pub fn generate_secret() -> String {
+    format!("{}{}", SECRET_PREFIX, hex::encode(ethers::core::rand::random::<[u8; 32]>()))
+}
+
+pub fn hash_secret(secret: &str) -> String {
+    hex::encode(Sha256::digest(secret.as_bytes()))
+}
+
+pub fn display_prefix(secret: &str) -> String {
+    secret.chars().take(DISPLAY_PREFIX_LEN).collect()
+}
+
+/// Scope a request needs: reads (and the side-effect-free match-intent) need read,
+/// everything else trade
+pub fn required_scope(method: &Method, path: &str) -> &'static str {
+    if method == Method::GET || method == Method::HEAD || path == "/api/match-intent" {
+        SCOPE_READ
+    } else {
+        SCOPE_TRADE
+    }
+}
+
+struct CachedKey {
+    key: Option<(ApiKeyIdentity, u32)>, // identity, requests per minute
+    fetched: Instant,
+}
+
+struct Window {
+    started: Instant,
+    count: u32,
+}
+
+/// Per-process key cache and rate limit counters
+#[derive(Default)]
+pub struct ApiKeyRegistry {
+    cache: Mutex<HashMap<String, CachedKey>>, // secret hash -> lookup
+    windows: Mutex<HashMap<Uuid, Window>>,
+}
+
+impl ApiKeyRegistry {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Live key for a secret, None if unknown or revoked
+    async fn lookup(&self, state: &AppState, secret: &str) -> Result<Option<(ApiKeyIdentity, u32)>, ApiError> {
+        let hash = hash_secret(secret);
+        if let Some(cached) = self.cache.lock().unwrap().get(&hash) {
+            if cached.fetched.elapsed() < KEY_CACHE_TTL {
+                return Ok(cached.key.clone());
+            }
+        }
+
+        let key = state.db.find_api_key(&hash).await?.map(|key: DbApiKey| {
+            let limit = key.rate_limit_per_minute.max(1) as u32;
+            (ApiKeyIdentity { key_id: key.key_id, name: key.name, wallet: key.wallet, scopes: key.scopes }, limit)
+        });
+
+        let mut cache = self.cache.lock().unwrap();
+        cache.retain(|_, c| c.fetched.elapsed() < KEY_CACHE_TTL);
+        cache.insert(hash, CachedKey { key: key.clone(), fetched: Instant::now() });
+        Ok(key)
+    }
+
+    /// Forget a revoked key on this replica right away
+    pub fn invalidate(&self, key_id: Uuid) {
+        self.cache
+            .lock()
+            .unwrap()
+            .retain(|_, c| !matches!(&c.key, Some((identity, _)) if identity.key_id == key_id));
+        self.windows.lock().unwrap().remove(&key_id);
+    }
+
+    /// Count a request against the key's limit
+    /// Ok(true) for the first request of a window, Err(seconds left) when over the limit
+    fn admit(&self, key_id: Uuid, limit: u32) -> Result<bool, u64> {
+        let mut windows = self.windows.lock().unwrap();
+        let now = Instant::now();
+        let window = windows.entry(key_id).or_insert(Window { started: now, count: 0 });
+        if now.duration_since(window.started) >= RATE_WINDOW {
+            *window = Window { started: now, count: 0 };
+        }
+        if window.count >= limit {
+            let left = RATE_WINDOW.saturating_sub(now.duration_since(window.started));
+            return Err(left.as_secs().max(1));
+        }
+        window.count += 1;
+        Ok(window.count == 1)
+    }
+}
+
+/// Middleware: authenticate x-api-key when present (see module docs)
+pub async fn authenticate_api_key(
+    State(state): State<AppState>,
+    mut request: Request,
+    next: Next,
+) -> Result<Response, ApiError> {
+    let Some(secret) = request.headers().get(API_KEY_HEADER) else {
+        return Ok(next.run(request).await);
+    };
+    let secret = secret
+        .to_str()
+        .map_err(|_| ApiError::Unauthorized("Invalid API key".to_string()))?
+        .to_string();
+
+    let metrics = &state.metrics.api_keys;
+    let Some((identity, limit)) = state.api_keys.lookup(&state, &secret).await? else {
+        metrics.record_auth_failure();
+        return Err(ApiError::Unauthorized("Invalid API key".to_string()));
+    };
+
+    let scope = required_scope(request.method(), request.uri().path());
+    if !identity.has_scope(scope) {
+        metrics.record_forbidden(&identity.name);
+        return Err(ApiError::Forbidden(format!("API key {} lacks the {} scope", identity.name, scope)));
+    }
+
+    match state.api_keys.admit(identity.key_id, limit) {
+        Ok(first_in_window) => {
+            metrics.record_request(&identity.name);
+            if first_in_window {
+                if let Err(e) = state.db.touch_api_key(identity.key_id).await {
+                    tracing::warn!("Failed to record API key use for {}: {}", identity.name, e);
+                }
+            }
+        }
+        Err(retry_after) => {
+            metrics.record_rate_limited(&identity.name);
+            return Err(ApiError::RateLimited(retry_after));
+        }
+    }
+
+    request.extensions_mut().insert(identity);
+    Ok(next.run(request).await)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_rate_limit_window() {
+        let registry = ApiKeyRegistry::new();
+        let key_id = Uuid::new_v4();
+        assert_eq!(registry.admit(key_id, 2), Ok(true));
+        assert_eq!(registry.admit(key_id, 2), Ok(false));
+        assert!(matches!(registry.admit(key_id, 2), Err(secs) if secs <= 60));
+
+        // Other keys have their own window
+        assert_eq!(registry.admit(Uuid::new_v4(), 2), Ok(true));
+    }
+
+    #[test]
+    fn test_secret_and_scopes() {
+        let secret = generate_secret();
+        assert!(secret.starts_with(SECRET_PREFIX));
+        assert_ne!(secret, generate_secret());
+        assert_eq!(hash_secret(&secret).len(), 64);
+        assert_eq!(display_prefix(&secret).len(), DISPLAY_PREFIX_LEN);
+
+        assert_eq!(required_scope(&Method::GET, "/api/orders/active"), SCOPE_READ);
+        assert_eq!(required_scope(&Method::POST, "/api/match-intent"), SCOPE_READ);
+        assert_eq!(required_scope(&Method::POST, "/api/execute-fill"), SCOPE_TRADE);
+    }
+}
@@ -0,0 +1,194 @@
+// Server-side clock for trade deadlines
+// Trade expiry is enforced on-chain against block.timestamp, and buyers' clocks drift,
+// so countdowns are computed here - from the latest block timestamp when blockchain
+// integration is enabled, otherwise from system time.
+
+use chrono::{TimeZone, Utc};
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+use tokio::sync::RwLock;
+use tokio::time::Instant;
+
+use crate::api::display::{Locale, TradeDisplay};
+use crate::api::state::AppState;
+use crate::blockchain::client::EthereumClient;
+use crate::db::models::DbTrade;
+
+/// How long a fetched block timestamp is extrapolated before refetching
+pub const CHAIN_TIME_REFRESH_SECS: u64 = 30;
+
+/// Default threshold for the expiring_soon flag
+pub const DEFAULT_EXPIRY_WARNING_SECS: u64 = 5 * 60;
+
+/// TradeStatus::PENDING
+const TRADE_PENDING: i32 = 0;
+
+/// A point in time and where it came from
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum TimeSource {
+    /// Latest block timestamp, advanced by local elapsed time since it was fetched
+    Chain,
+    /// Server system clock (chain unavailable)
+    System,
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct ServerTime {
+    pub unix: i64,
+    pub source: TimeSource,
+}
+
+/// Latest block timestamp, refreshed at most every CHAIN_TIME_REFRESH_SECS
+#[derive(Default)]
+pub struct ChainClock {
+    anchor: RwLock<Option<(u64, Instant)>>,
+}
+
+impl ChainClock {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Current time, preferring chain time when a client is available
+    pub async fn now(&self, client: Option<&EthereumClient>) -> ServerTime {
+        let system = ServerTime {
+            unix: Utc::now().timestamp(),
+            source: TimeSource::System,
+        };
+        let Some(client) = client else {
+            return system;
+        };
+
+        if let Some((block_ts, fetched)) = *self.anchor.read().await {
+            if fetched.elapsed() < Duration::from_secs(CHAIN_TIME_REFRESH_SECS) {
+                return ServerTime {
+                    unix: (block_ts + fetched.elapsed().as_secs()) as i64,
+                    source: TimeSource::Chain,
+                };
+            }
+        }
+
+        match client.get_latest_block_timestamp().await {
+            Ok(block_ts) => {
+                *self.anchor.write().await = Some((block_ts, Instant::now()));
+                ServerTime {
+                    unix: block_ts as i64,
+                    source: TimeSource::Chain,
+                }
+            }
+            Err(e) => {
+                tracing::warn!("Failed to read latest block timestamp, using system time: {}", e);
+                system
+            }
+        }
+    }
+}
+
+/// Payment countdown attached to trade responses
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct TradeTiming {
+    /// Seconds until expiresAt (None unless the trade is PENDING)
+    pub seconds_remaining: Option<i64>,
+
+    /// PENDING and within the warning threshold of expiry
+    pub expiring_soon: bool,
+}
+
+impl TradeTiming {
+    pub fn new(trade: &DbTrade, now: i64, warning_secs: u64) -> Self {
+        if trade.status != TRADE_PENDING {
+            return Self::default();
+        }
+
+        let remaining = (trade.expires_at - now).max(0);
+        Self {
+            seconds_remaining: Some(remaining),
+            expiring_soon: remaining <= warning_secs as i64,
+        }
+    }
+}
+
+/// A trade with its payment countdown
+#[derive(Debug, Serialize)]
+pub struct TradeView {
+    #[serde(flatten)]
+    pub trade: DbTrade,
+
+    #[serde(flatten)]
+    pub timing: TradeTiming,
+
+    #[serde(flatten)]
+    pub display: TradeDisplay,
+}
+
+/// Response for GET /api/time
+#[derive(Debug, Serialize)]
+pub struct TimeResponse {
+    /// Current time (RFC 3339)
+    pub server_time: String,
+    pub unix_timestamp: i64,
+    pub source: TimeSource,
+}
+
+impl From<ServerTime> for TimeResponse {
+    fn from(time: ServerTime) -> Self {
+        Self {
+            server_time: Utc
+                .timestamp_opt(time.unix, 0)
+                .single()
+                .unwrap_or_else(Utc::now)
+                .to_rfc3339(),
+            unix_timestamp: time.unix,
+            source: time.source,
+        }
+    }
+}
+
+/// Current server time (chain time where possible)
+pub async fn server_time(state: &AppState) -> ServerTime {
+    state.chain_clock.now(state.blockchain_client.as_deref()).await
+}
+
+/// Attach countdowns (using a single clock reading) and display amounts to trades
+pub async fn with_timing(state: &AppState, trades: Vec<DbTrade>, locale: Locale) -> Vec<TradeView> {
+    let now = server_time(state).await.unix;
+    trades
+        .into_iter()
+        .map(|trade| TradeView {
+            timing: TradeTiming::new(&trade, now, state.expiry_warning_secs),
+            display: TradeDisplay::new(&trade, &state.token_display, locale),
+            trade,
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_timing_only_for_pending_trades() {
+        // Optional fields are left out (deserialize as None)
+        let mut trade: DbTrade = serde_json::from_value(serde_json::json!({
+            "trade_id": "0x01", "order_id": "0x02", "buyer": "0x3333333333333333333333333333333333333333",
+            "token_amount": "1", "cny_amount": "1", "payment_nonce": "12345678",
+            "created_at": 1_000, "expires_at": 1_900, "status": 0,
+            "synced_at": "2025-01-01T00:00:00Z"
+        }))
+        .unwrap();
+
+        let timing = TradeTiming::new(&trade, 1_000, 300);
+        assert_eq!(timing.seconds_remaining, Some(900));
+        assert!(!timing.expiring_soon);
+
+        let timing = TradeTiming::new(&trade, 1_700, 300);
+        assert_eq!(timing.seconds_remaining, Some(200));
+        assert!(timing.expiring_soon);
+
+        assert_eq!(TradeTiming::new(&trade, 2_000, 300).seconds_remaining, Some(0));
+
+        trade.status = 1;
+        assert_eq!(TradeTiming::new(&trade, 1_000, 300).seconds_remaining, None);
+    }
+}
@@ -0,0 +1,217 @@
+// Dual confirmation for destructive admin actions (force-expire, force-release)
+// The first call runs the safety checks and returns a short-lived confirmation token;
+// the action only executes when a second call presents the token for the same trade
+// and parameters. With ADMIN_API_KEYS configured, both calls must carry an admin key
+// (x-admin-key header) and the confirming key must differ from the requesting one.
+
+use axum::http::HeaderMap;
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+use uuid::Uuid;
+
+use crate::api::error::ApiError;
+
+/// Header carrying the caller's admin key
+pub const ADMIN_KEY_HEADER: &str = "x-admin-key";
+
+/// How long a confirmation token stays valid
+pub const DEFAULT_CONFIRMATION_TTL: Duration = Duration::from_secs(300);
+
+/// Named admin keys, loaded from ADMIN_API_KEYS ("name:key,name:key")
+#[derive(Debug, Clone, Default)]
+pub struct AdminKeys {
+    keys: HashMap<String, String>, // key -> name
+}
+
+impl AdminKeys {
+    pub fn from_env() -> Result<Self, String> {
+        match std::env::var("ADMIN_API_KEYS") {
+            Ok(value) => Self::parse(&value),
+            Err(_) => Ok(Self::default()),
+        }
+    }
+
+    pub fn parse(value: &str) -> Result<Self, String> {
+        let mut keys = HashMap::new();
+        for entry in value.split(',').map(str::trim).filter(|e| !e.is_empty()) {
+            let (name, key) = entry
+                .split_once(':')
+                .filter(|(name, key)| !name.is_empty() && !key.is_empty())
+                .ok_or_else(|| format!("ADMIN_API_KEYS entries must be name:key, got {:?}", entry))?;
+            if keys.insert(key.to_string(), name.to_string()).is_some() {
+                return Err(format!("ADMIN_API_KEYS has a duplicate key (admin {})", name));
+            }
+        }
+        Ok(Self { keys })
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.keys.is_empty()
+    }
+
+    /// Name of the admin calling, from the x-admin-key header
+    /// None when no keys are configured (the confirmation token alone guards the action)
+    pub fn authenticate(&self, headers: &HeaderMap) -> Result<Option<String>, ApiError> {
+        if self.is_empty() {
+            return Ok(None);
+        }
+
+        let key = headers
+            .get(ADMIN_KEY_HEADER)
+            .and_then(|v| v.to_str().ok())
+            .ok_or_else(|| ApiError::Unauthorized(format!("Missing {} header", ADMIN_KEY_HEADER)))?;
+
+        self.keys
+            .get(key)
+            .cloned()
+            .map(Some)
+            .ok_or_else(|| ApiError::Unauthorized("Invalid admin key".to_string()))
+    }
+
+    /// Like `authenticate`, but refuses outright when no keys are configured
+    /// For endpoints that must never be open (personal data, third-party credentials)
+    pub fn require(&self, headers: &HeaderMap) -> Result<Option<String>, ApiError> {
+        if self.is_empty() {
+            return Err(ApiError::ServiceUnavailable("This endpoint requires ADMIN_API_KEYS".to_string()));
+        }
+        self.authenticate(headers)
+    }
+}
+
+/// An action waiting for its second confirmation
+#[derive(Debug, Clone)]
+pub struct PendingAction {
+    pub action: &'static str,
+    pub trade_id: String,
+    /// Canonical form of the action's parameters (e.g. gas overrides)
+    pub params: String,
+    pub requested_by: Option<String>,
+    expires: Instant,
+}
+
+/// Outstanding confirmation tokens (in-memory: a restart simply requires re-requesting)
+#[derive(Debug)]
+pub struct AdminConfirmations {
+    ttl: Duration,
+    pending: Mutex<HashMap<Uuid, PendingAction>>,
+}
+
+impl Default for AdminConfirmations {
+    fn default() -> Self {
+        Self::new(DEFAULT_CONFIRMATION_TTL)
+    }
+}
+
+impl AdminConfirmations {
+    pub fn new(ttl: Duration) -> Self {
+        Self { ttl, pending: Mutex::new(HashMap::new()) }
+    }
+
+    pub fn ttl(&self) -> Duration {
+        self.ttl
+    }
+
+    /// Register an action and return the token that confirms it
+    pub fn request(&self, action: &'static str, trade_id: &str, params: &str, requested_by: Option<String>) -> Uuid {
+        let mut pending = self.pending.lock().unwrap();
+        let now = Instant::now();
+        pending.retain(|_, p| p.expires > now);
+
+        let token = Uuid::new_v4();
+        pending.insert(token, PendingAction {
+            action,
+            trade_id: trade_id.to_string(),
+            params: params.to_string(),
+            requested_by,
+            expires: now + self.ttl,
+        });
+        token
+    }
+
+    /// Redeem a token for exactly the action it was issued for
+    /// The token is consumed only on success, so a rejected confirmation can be retried
+    /// by the right admin.
+    pub fn confirm(
+        &self,
+        token: Uuid,
+        action: &str,
+        trade_id: &str,
+        params: &str,
+        confirmed_by: Option<&str>,
+    ) -> Result<PendingAction, ApiError> {
+        let mut pending = self.pending.lock().unwrap();
+
+        let entry = match pending.get(&token) {
+            Some(entry) if entry.expires > Instant::now() => entry,
+            Some(_) => {
+                pending.remove(&token);
+                return Err(ApiError::BadRequest("Confirmation token has expired - request the action again".to_string()));
+            }
+            None => return Err(ApiError::BadRequest("Unknown confirmation token".to_string())),
+        };
+
+        if entry.action != action || entry.trade_id != trade_id || entry.params != params {
+            return Err(ApiError::BadRequest(format!(
+                "Confirmation token was issued for {} on trade {} ({}) - parameters must match",
+                entry.action, entry.trade_id, entry.params
+            )));
+        }
+        if let (Some(requested_by), Some(confirmed_by)) = (&entry.requested_by, confirmed_by) {
+            if requested_by == confirmed_by {
+                return Err(ApiError::Forbidden(format!(
+                    "Action was requested by {} - a different admin key must confirm it",
+                    requested_by
+                )));
+            }
+        }
+
+        Ok(pending.remove(&token).expect("entry checked above"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_confirmation_requires_matching_action_and_second_admin() {
+        let confirmations = AdminConfirmations::default();
+        let token = confirmations.request("force_expire", "0xabc", "", Some("alice".to_string()));
+
+        assert!(confirmations.confirm(token, "force_release", "0xabc", "", Some("bob")).is_err());
+        assert!(confirmations.confirm(token, "force_expire", "0xdef", "", Some("bob")).is_err());
+        assert!(matches!(
+            confirmations.confirm(token, "force_expire", "0xabc", "", Some("alice")),
+            Err(ApiError::Forbidden(_))
+        ));
+
+        let action = confirmations.confirm(token, "force_expire", "0xabc", "", Some("bob")).unwrap();
+        assert_eq!(action.requested_by.as_deref(), Some("alice"));
+
+        // Single use
+        assert!(confirmations.confirm(token, "force_expire", "0xabc", "", Some("bob")).is_err());
+
+        let expired = AdminConfirmations::new(Duration::ZERO);
+        let token = expired.request("force_expire", "0xabc", "", None);
+        assert!(expired.confirm(token, "force_expire", "0xabc", "", None).is_err());
+    }
+
+    #[test]
+    fn test_parse_admin_keys() {
+        let keys = AdminKeys::parse("alice:k1, bob:k2").unwrap();
+        let mut headers = HeaderMap::new();
+        headers.insert(ADMIN_KEY_HEADER, "k2".parse().unwrap());
+        assert_eq!(keys.authenticate(&headers).unwrap().as_deref(), Some("bob"));
+        assert!(keys.authenticate(&HeaderMap::new()).is_err());
+
+        assert!(AdminKeys::parse("").unwrap().authenticate(&HeaderMap::new()).unwrap().is_none());
+        assert_eq!(keys.require(&headers).unwrap().as_deref(), Some("bob"));
+        assert!(matches!(
+            AdminKeys::parse("").unwrap().require(&headers),
+            Err(ApiError::ServiceUnavailable(_))
+        ));
+        assert!(AdminKeys::parse("alice").is_err());
+        assert!(AdminKeys::parse("alice:k1,bob:k1").is_err());
+    }
+}
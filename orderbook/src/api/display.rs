@@ -0,0 +1,265 @@
+// Display formatting of amounts
+// Each frontend used to turn raw base units and CNY cents into "1,060.00 CNY" on its
+// own, and they drifted - while the CNY amount is what the buyer types into Alipay and
+// the receipt (and so the proof) has to show exactly. Order, trade and match responses
+// now carry ready-made display strings next to the raw values. CNY comes from cents;
+// token amounts use the symbol and decimals registered for the token (fields are left
+// out for unregistered tokens). Amounts are never rounded: a token amount shows every
+// significant decimal, with at least two. The locale only picks the separators.
+//
+// Configuration (env):
+// - TOKEN_DISPLAY: comma-separated "0x<token>=SYMBOL:decimals" entries (decimals must
+//   match the token contract's, they also price fills in CNY)
+
+use ethers::types::U256;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::str::FromStr;
+use thiserror::Error;
+
+use crate::api::matching::MatchPlan;
+use crate::blockchain::address::EthAddress;
+use crate::blockchain::types::fill_value_cny;
+use crate::db::models::DbTrade;
+
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+pub enum DisplayError {
+    #[error("Invalid token display entry {0:?}: expected 0x<token>=SYMBOL:decimals")]
+    InvalidEntry(String),
+}
+
+/// Separator conventions for display amounts (request parameter "locale", default en)
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(try_from = "String")]
+pub enum Locale {
+    /// 1,060.00 (also zh)
+    #[default]
+    En,
+    /// 1.060,00
+    De,
+    /// 1 060,00
+    Fr,
+}
+
+impl Locale {
+    /// (thousands separator, decimal point)
+    fn separators(self) -> (char, char) {
+        match self {
+            Self::En => (',', '.'),
+            Self::De => ('.', ','),
+            Self::Fr => ('\u{a0}', ','),
+        }
+    }
+}
+
+impl FromStr for Locale {
+    type Err = String;
+
+    /// Language tags by their primary subtag ("zh-CN" -> en separators)
+    fn from_str(tag: &str) -> Result<Self, Self::Err> {
+        let language = tag.split(['-', '_']).next().unwrap_or_default().to_ascii_lowercase();
+        match language.as_str() {
+            "en" | "zh" => Ok(Self::En),
+            "de" => Ok(Self::De),
+            "fr" => Ok(Self::Fr),
+            _ => Err(format!("Unsupported locale {:?}: expected en, zh, de or fr", tag)),
+        }
+    }
+}
+
+impl TryFrom<String> for Locale {
+    type Error = String;
+
+    fn try_from(tag: String) -> Result<Self, Self::Error> {
+        tag.parse()
+    }
+}
+
+/// Query parameters of responses with display amounts and nothing else to filter
+#[derive(Debug, Default, Deserialize)]
+pub struct DisplayQuery {
+    /// Separators of display amounts (en, zh, de or fr; default en)
+    #[serde(default)]
+    pub locale: Locale,
+}
+
+/// Format an integer amount of `decimals`-decimal units (e.g. 106000 cents -> "1,060.00")
+/// None if `raw` isn't a non-negative integer.
+pub fn format_units(raw: &str, decimals: u8, locale: Locale) -> Option<String> {
+    if raw.is_empty() || !raw.bytes().all(|b| b.is_ascii_digit()) {
+        return None;
+    }
+    let decimals = decimals as usize;
+    let padded = format!("{:0>width$}", raw.trim_start_matches('0'), width = decimals + 1);
+    let (whole, fraction) = padded.split_at(padded.len() - decimals);
+    let shown = fraction.trim_end_matches('0').len().max(decimals.min(2));
+
+    let (thousands, point) = locale.separators();
+    let mut formatted = String::with_capacity(padded.len() + padded.len() / 3 + 1);
+    for (i, digit) in whole.chars().enumerate() {
+        if i > 0 && (whole.len() - i) % 3 == 0 {
+            formatted.push(thousands);
+        }
+        formatted.push(digit);
+    }
+    if shown > 0 {
+        formatted.push(point);
+        formatted.push_str(&fraction[..shown]);
+    }
+    Some(formatted)
+}
+
+/// CNY cents as "1,060.00 CNY"
+pub fn format_cny(cents: &str, locale: Locale) -> Option<String> {
+    format_units(cents, 2, locale).map(|amount| format!("{} CNY", amount))
+}
+
+/// Symbol and decimals of a token
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TokenDisplay {
+    pub symbol: String,
+    pub decimals: u8,
+}
+
+impl TokenDisplay {
+    /// Base units as "100.00 USDC"
+    pub fn format(&self, raw: &str, locale: Locale) -> Option<String> {
+        format_units(raw, self.decimals, locale).map(|amount| format!("{} {}", amount, self.symbol))
+    }
+
+    /// CNY the buyer pays for `fill_amount` base units at `exchange_rate`, priced as the
+    /// contract does
+    pub fn format_fill_cny(&self, fill_amount: &str, exchange_rate: &str, locale: Locale) -> Option<String> {
+        let fill_amount = U256::from_dec_str(fill_amount).ok()?;
+        let exchange_rate = U256::from_dec_str(exchange_rate).ok()?;
+        format_cny(&fill_value_cny(fill_amount, exchange_rate, self.decimals).to_string(), locale)
+    }
+}
+
+/// Tokens with display metadata, from TOKEN_DISPLAY
+#[derive(Debug, Clone, Default)]
+pub struct TokenDisplayRegistry {
+    tokens: HashMap<EthAddress, TokenDisplay>,
+}
+
+impl TokenDisplayRegistry {
+    /// Build the registry from TOKEN_DISPLAY (empty when unset)
+    pub fn from_env() -> Result<Self, DisplayError> {
+        match std::env::var("TOKEN_DISPLAY") {
+            Ok(entries) => Self::parse(&entries),
+            Err(_) => Ok(Self::default()),
+        }
+    }
+
+    /// Parse comma-separated "0x<token>=SYMBOL:decimals" entries
+    pub fn parse(entries: &str) -> Result<Self, DisplayError> {
+        let tokens = entries
+            .split(',')
+            .filter(|entry| !entry.trim().is_empty())
+            .map(|entry| {
+                let invalid = || DisplayError::InvalidEntry(entry.to_string());
+                let (token, rest) = entry.trim().split_once('=').ok_or_else(invalid)?;
+                let (symbol, decimals) = rest.split_once(':').ok_or_else(invalid)?;
+                if symbol.is_empty() {
+                    return Err(invalid());
+                }
+                let token = token.parse::<EthAddress>().map_err(|_| invalid())?;
+                let decimals = decimals.parse().map_err(|_| invalid())?;
+                Ok((token, TokenDisplay { symbol: symbol.to_string(), decimals }))
+            })
+            .collect::<Result<_, _>>()?;
+        Ok(Self { tokens })
+    }
+
+    pub fn get(&self, token: &EthAddress) -> Option<&TokenDisplay> {
+        self.tokens.get(token)
+    }
+
+    pub fn len(&self) -> usize {
+        self.tokens.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.tokens.is_empty()
+    }
+}
+
+/// Display amounts attached to trade responses
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct TradeDisplay {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub token_amount_display: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub cny_amount_display: Option<String>,
+}
+
+impl TradeDisplay {
+    /// The token amount needs the trade's token (only set on joined queries) to be registered
+    pub fn new(trade: &DbTrade, tokens: &TokenDisplayRegistry, locale: Locale) -> Self {
+        Self {
+            token_amount_display: trade
+                .token
+                .as_ref()
+                .and_then(|token| tokens.get(token))
+                .and_then(|token| token.format(&trade.token_amount, locale)),
+            cny_amount_display: format_cny(&trade.cny_amount, locale),
+        }
+    }
+}
+
+/// Fill in a match plan's display amounts (left unset for an unregistered token)
+pub fn display_plan(plan: &mut MatchPlan, tokens: &TokenDisplayRegistry, locale: Locale) {
+    for fill in &mut plan.fills {
+        if let Some(token) = tokens.get(&fill.token) {
+            fill.fill_amount_display = token.format(&fill.fill_amount, locale);
+            fill.cny_amount_display = token.format_fill_cny(&fill.fill_amount, &fill.exchange_rate, locale);
+        }
+    }
+    plan.total_filled_display = plan
+        .fills
+        .first()
+        .and_then(|fill| tokens.get(&fill.token))
+        .and_then(|token| token.format(&plan.total_filled, locale));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const USDC: &str = "0x2222222222222222222222222222222222222222";
+
+    #[test]
+    fn test_format_units_is_exact_per_locale() {
+        assert_eq!(format_cny("106000", Locale::En).unwrap(), "1,060.00 CNY");
+        assert_eq!(format_cny("106000", Locale::De).unwrap(), "1.060,00 CNY");
+        assert_eq!(format_cny("106000", Locale::Fr).unwrap(), "1\u{a0}060,00 CNY");
+        assert_eq!(format_cny("5", Locale::En).unwrap(), "0.05 CNY");
+        assert_eq!(format_cny("0", Locale::En).unwrap(), "0.00 CNY");
+
+        // Significant decimals are kept, at least two shown
+        assert_eq!(format_units("100000000", 6, Locale::En).unwrap(), "100.00");
+        assert_eq!(format_units("1234567890123", 6, Locale::En).unwrap(), "1,234,567.890123");
+        assert_eq!(format_units("100500000", 6, Locale::En).unwrap(), "100.50");
+        assert_eq!(format_units("1000", 0, Locale::En).unwrap(), "1,000");
+        assert_eq!(format_units("1.5", 6, Locale::En), None);
+        assert_eq!(format_units("", 6, Locale::En), None);
+
+        assert_eq!("zh-CN".parse::<Locale>().unwrap(), Locale::En);
+        assert_eq!("de_DE".parse::<Locale>().unwrap(), Locale::De);
+        assert!("xx".parse::<Locale>().is_err());
+    }
+
+    #[test]
+    fn test_registry_formats_registered_tokens() {
+        let registry = TokenDisplayRegistry::parse(&format!("{}=USDC:6, ", USDC)).unwrap();
+        let usdc = registry.get(&USDC.parse().unwrap()).unwrap();
+        assert_eq!(usdc.format("100000000", Locale::En).unwrap(), "100.00 USDC");
+        // 100 USDC at 7.35 CNY
+        assert_eq!(usdc.format_fill_cny("100000000", "735", Locale::En).unwrap(), "735.00 CNY");
+        assert!(registry.get(&"0x3333333333333333333333333333333333333333".parse().unwrap()).is_none());
+
+        assert!(TokenDisplayRegistry::parse("0x22=USDC:6").is_err());
+        assert!(TokenDisplayRegistry::parse(&format!("{}=USDC", USDC)).is_err());
+        assert!(TokenDisplayRegistry::parse(&format!("{}=:6", USDC)).is_err());
+    }
+}
@@ -1,10 +1,15 @@
 use axum::{
-    http::StatusCode,
+    http::{header, HeaderValue, StatusCode},
     response::{IntoResponse, Response},
     Json,
 };
 use serde_json::json;
+use crate::axiom_prover::budget::BudgetError;
+use crate::blockchain::address::AddressError;
+use crate::blockchain::ids::IdError;
 use crate::db::DbError;
+use crate::proof_inputs::PublicValuesMismatch;
+use crate::spending_limits::LimitExceeded;
 
 /// API error type that can be converted to HTTP responses
 #[derive(Debug)]
@@ -18,12 +23,49 @@ pub enum ApiError {
     /// Invalid request (validation errors)
     BadRequest(String),
     
+    /// Missing or invalid admin credentials
+    Unauthorized(String),
+    
+    /// Authenticated, but not allowed (e.g. confirming one's own admin action)
+    Forbidden(String),
+    
     /// Resource not found
     NotFound(String),
     
     /// Service unavailable (e.g., blockchain integration disabled)
     ServiceUnavailable(String),
     
+    /// Contract is paused - state-changing calls would revert
+    MarketPaused,
+    
+    /// API key over its per-minute request limit (seconds until the window resets)
+    RateLimited(u64),
+    
+    /// Token is delisted (denylisted, or off a non-empty allowlist) - matching is suspended
+    TokenSuspended(String),
+    
+    /// The order pays out to a blocklisted Alipay account (fraud, chargebacks) - fills are refused
+    SellerBlocked(String),
+    
+    /// Maintenance mode - mutating endpoints are refused (seconds until a scheduled window ends)
+    Maintenance { reason: String, retry_after: Option<u64> },
+    
+    /// Another proof submission for the trade is in flight, or it already went through
+    ProofSubmissionConflict { in_flight: bool, tx_hash: Option<String> },
+    
+    /// The stored proof doesn't prove the trade's payment details - submitPaymentProof would
+    /// revert with PaymentDetailsMismatch
+    PublicValuesMismatch(Box<PublicValuesMismatch>),
+    
+    /// Screening flagged an address the request involves (refusal id in screening_refusals)
+    AddressFlagged { role: String, refusal_id: i64 },
+    
+    /// The fill would take the buyer over a rolling spending cap of their tier
+    SpendingLimitExceeded(Box<LimitExceeded>),
+    
+    /// Axiom credits left don't cover the job (seconds until the monthly budget resets)
+    ProverBudgetExhausted { needed: i64, remaining: i64, retry_after: u64 },
+    
     /// Internal server error
     Internal(String),
 }
@@ -38,8 +80,37 @@ impl From<DbError> for ApiError {
     }
 }
 
-impl IntoResponse for ApiError {
-    fn into_response(self) -> Response {
+impl From<AddressError> for ApiError {
+    fn from(err: AddressError) -> Self {
+        ApiError::BadRequest(err.to_string())
+    }
+}
+
+impl From<IdError> for ApiError {
+    fn from(err: IdError) -> Self {
+        ApiError::BadRequest(err.to_string())
+    }
+}
+
+impl From<BudgetError> for ApiError {
+    fn from(err: BudgetError) -> Self {
+        match err {
+            BudgetError::Exhausted { needed, remaining, resets_at } => ApiError::ProverBudgetExhausted {
+                needed,
+                remaining,
+                retry_after: (resets_at - chrono::Utc::now()).num_seconds().max(0) as u64,
+            },
+            BudgetError::Database(e) => e.into(),
+            BudgetError::Config(e) => ApiError::Internal(e),
+        }
+    }
+}
+
+impl ApiError {
+    /// HTTP status, client-facing message and optional machine-readable code
+    /// (database and internal details are logged, not exposed)
+    fn public_parts(self) -> (StatusCode, String, Option<&'static str>) {
+        let mut code = None;
         let (status, error_message) = match self {
             ApiError::Database(err) => {
                 // Log the actual database error for debugging
@@ -53,24 +124,141 @@ impl IntoResponse for ApiError {
             ApiError::BadRequest(msg) => {
                 (StatusCode::BAD_REQUEST, msg)
             }
+            ApiError::Unauthorized(msg) => {
+                (StatusCode::UNAUTHORIZED, msg)
+            }
+            ApiError::Forbidden(msg) => {
+                (StatusCode::FORBIDDEN, msg)
+            }
             ApiError::NotFound(msg) => {
                 (StatusCode::NOT_FOUND, msg)
             }
             ApiError::ServiceUnavailable(msg) => {
                 (StatusCode::SERVICE_UNAVAILABLE, msg)
             }
+            ApiError::MarketPaused => {
+                code = Some("market_paused");
+                (StatusCode::SERVICE_UNAVAILABLE, "Market is paused - trading is temporarily disabled".to_string())
+            }
+            ApiError::RateLimited(retry_after) => {
+                code = Some("rate_limited");
+                (StatusCode::TOO_MANY_REQUESTS, format!("API key rate limit exceeded - retry in {}s", retry_after))
+            }
+            ApiError::TokenSuspended(token) => {
+                code = Some("token_suspended");
+                (StatusCode::UNPROCESSABLE_ENTITY, format!("Trading in token {} is suspended", token))
+            }
+            ApiError::SellerBlocked(order_id) => {
+                code = Some("seller_blocked");
+                (StatusCode::UNPROCESSABLE_ENTITY, format!("Order {} is not available for trading", order_id))
+            }
+            ApiError::Maintenance { reason, .. } => {
+                code = Some("maintenance");
+                (StatusCode::SERVICE_UNAVAILABLE, reason)
+            }
+            ApiError::ProofSubmissionConflict { in_flight: true, .. } => {
+                code = Some("submission_in_flight");
+                (StatusCode::CONFLICT, "A proof submission for this trade is already in flight".to_string())
+            }
+            ApiError::ProofSubmissionConflict { in_flight: false, .. } => {
+                code = Some("proof_already_submitted");
+                (StatusCode::CONFLICT, "The proof for this trade was already submitted".to_string())
+            }
+            ApiError::PublicValuesMismatch(report) => {
+                code = Some("public_values_mismatch");
+                (
+                    StatusCode::UNPROCESSABLE_ENTITY,
+                    format!("The proof was generated for other payment details than this trade's ({}) - regenerate it", report),
+                )
+            }
+            ApiError::AddressFlagged { role, refusal_id } => {
+                code = Some("address_flagged");
+                (
+                    StatusCode::FORBIDDEN,
+                    format!("Service refused: the {} address did not pass screening (reference {})", role, refusal_id),
+                )
+            }
+            ApiError::SpendingLimitExceeded(exceeded) => {
+                code = Some("spending_limit_exceeded");
+                (StatusCode::UNPROCESSABLE_ENTITY, format!("Spending limit exceeded: {}", exceeded))
+            }
+            ApiError::ProverBudgetExhausted { needed, remaining, .. } => {
+                code = Some("prover_budget_exhausted");
+                (
+                    StatusCode::SERVICE_UNAVAILABLE,
+                    format!(
+                        "Proof generation is paused: the prover budget has {} credits left and this needs {} - retry once it resets",
+                        remaining, needed
+                    ),
+                )
+            }
             ApiError::Internal(msg) => {
                 tracing::error!("Internal error: {}", msg);
                 (StatusCode::INTERNAL_SERVER_ERROR, "Internal server error".to_string())
             }
         };
 
-        let body = Json(json!({
+        (status, error_message, code)
+    }
+
+    /// Client-facing message, for per-item errors in batch responses
+    pub fn into_message(self) -> String {
+        self.public_parts().1
+    }
+}
+
+impl IntoResponse for ApiError {
+    fn into_response(self) -> Response {
+        let retry_after = match &self {
+            ApiError::RateLimited(secs) => Some(*secs),
+            ApiError::Maintenance { retry_after, .. } => *retry_after,
+            ApiError::ProverBudgetExhausted { retry_after, .. } => Some(*retry_after),
+            _ => None,
+        };
+        let refusal_id = match &self {
+            ApiError::AddressFlagged { refusal_id, .. } => Some(*refusal_id),
+            _ => None,
+        };
+        let tx_hash = match &self {
+            ApiError::ProofSubmissionConflict { tx_hash, .. } => tx_hash.clone(),
+            _ => None,
+        };
+        let mismatch = match &self {
+            ApiError::PublicValuesMismatch(report) => serde_json::to_value(report).ok(),
+            _ => None,
+        };
+        let limit = match &self {
+            ApiError::SpendingLimitExceeded(exceeded) => serde_json::to_value(exceeded).ok(),
+            _ => None,
+        };
+        let (status, error_message, code) = self.public_parts();
+
+        let mut body = json!({
             "error": error_message,
             "status": status.as_u16(),
-        }));
+        });
+        if let Some(code) = code {
+            body["code"] = json!(code);
+        }
+        if let Some(refusal_id) = refusal_id {
+            body["refusal_id"] = json!(refusal_id);
+        }
+        if let Some(tx_hash) = tx_hash {
+            body["tx_hash"] = json!(tx_hash);
+        }
+        if let Some(mismatch) = mismatch {
+            body["mismatch"] = mismatch;
+        }
+        if let Some(limit) = limit {
+            body["limit"] = limit;
+        }
+        let body = Json(body);
 
-        (status, body).into_response()
+        let mut response = (status, body).into_response();
+        if let Some(secs) = retry_after {
+            response.headers_mut().insert(header::RETRY_AFTER, HeaderValue::from(secs));
+        }
+        response
     }
 }
 
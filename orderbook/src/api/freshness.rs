@@ -0,0 +1,98 @@
+// Orderbook freshness: how far the DB mirror trails the chain
+// Orders are only as current as the event listener, so list/match responses carry
+// sync metadata and matching can be refused when the mirror is too stale.
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::api::{error::{ApiError, ApiResult}, state::AppState};
+
+/// Approximate block time used to convert block lag into seconds (Base: 2s blocks)
+pub const ESTIMATED_BLOCK_TIME_SECS: u64 = 2;
+
+/// Sync metadata attached to order list and match responses
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct SyncStatus {
+    /// Last block processed by the event listener (None if it never ran)
+    pub last_synced_block: Option<u64>,
+
+    /// When the event listener last advanced (RFC 3339)
+    pub synced_at: Option<String>,
+
+    /// Estimated staleness of order data in seconds
+    pub staleness_secs: Option<u64>,
+}
+
+impl SyncStatus {
+    /// Build from the persisted sync state and the listener's observed block lag
+    ///
+    /// Staleness is the time since the last sync plus the remaining block lag
+    /// converted to seconds, so a listener that is busy catching up still counts as stale.
+    pub fn new(last_synced_block: u64, synced_at: DateTime<Utc>, lag_blocks: u64, now: DateTime<Utc>) -> Self {
+        let since_sync = (now - synced_at).num_seconds().max(0) as u64;
+
+        Self {
+            last_synced_block: Some(last_synced_block),
+            synced_at: Some(synced_at.to_rfc3339()),
+            staleness_secs: Some(since_sync + lag_blocks * ESTIMATED_BLOCK_TIME_SECS),
+        }
+    }
+
+    /// Whether order data is older than the given threshold (unknown counts as stale)
+    pub fn is_stale(&self, max_staleness_secs: u64) -> bool {
+        self.staleness_secs.is_none_or(|s| s > max_staleness_secs)
+    }
+}
+
+/// Current sync status of the orderbook mirror
+pub async fn sync_status(state: &AppState) -> ApiResult<SyncStatus> {
+    let status = match state.db.get_sync_state().await? {
+        // The listener stores the next block to process, so the last synced block is one below
+        Some(row) => SyncStatus::new(
+            (row.last_synced_block.max(1) - 1) as u64,
+            row.last_synced_at,
+            state.metrics.listener.lag_blocks(),
+            Utc::now(),
+        ),
+        None => SyncStatus::default(),
+    };
+
+    Ok(status)
+}
+
+/// Refuse with 503 when a staleness limit is configured and exceeded
+pub fn ensure_fresh(state: &AppState, status: &SyncStatus) -> ApiResult<()> {
+    let Some(max) = state.max_sync_staleness_secs else {
+        return Ok(());
+    };
+
+    if status.is_stale(max) {
+        return Err(ApiError::ServiceUnavailable(match status.staleness_secs {
+            Some(secs) => format!(
+                "Orderbook is {}s behind the chain (limit {}s) - try again shortly",
+                secs, max
+            ),
+            None => "Orderbook sync status unknown - matching disabled".to_string(),
+        }));
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Duration;
+
+    #[test]
+    fn test_staleness_includes_block_lag() {
+        let now = Utc::now();
+        let status = SyncStatus::new(1_000, now - Duration::seconds(10), 30, now);
+
+        assert_eq!(status.last_synced_block, Some(1_000));
+        assert_eq!(status.staleness_secs, Some(10 + 30 * ESTIMATED_BLOCK_TIME_SECS));
+        assert!(status.is_stale(60));
+        assert!(!status.is_stale(120));
+        assert!(SyncStatus::default().is_stale(120));
+    }
+}
@@ -1,8 +1,35 @@
-use axum::{extract::State, Json};
-use ethers::types::Address;
+use axum::{extract::{Path, Query, State}, http::HeaderMap, Json};
+use ethers::types::{H256, U256};
+use ethers::utils::parse_units;
 use serde::{Deserialize, Serialize};
+use uuid::Uuid;
 
-use crate::api::{error::ApiError, state::AppState};
+use crate::alipay::{mask_alipay_id, AlipayIdFormat};
+use crate::anomalies::AnomalyConfig;
+use crate::api::api_keys::{display_prefix, generate_secret, hash_secret, DEFAULT_RATE_LIMIT_PER_MINUTE};
+use crate::api::{clock::server_time, error::ApiError, state::AppState};
+use crate::api::maintenance::{MaintenanceStatus, MaintenanceWindow};
+use crate::api::handlers::buyer::{record_spend, submit_trade_proof_with_gas};
+use crate::axiom_prover::budget::BudgetBalance;
+use crate::axiom_prover::poller::{PollBudget, PollConfig};
+use crate::blockchain::address::EthAddress;
+use crate::blockchain::client::{EthereumClientError, GasOverride, RelayerKeyStatus};
+use crate::blockchain::config_cache::ContractConfig;
+use crate::blockchain::ids::TradeId;
+use crate::blockchain::pending_txs::PendingTx;
+use crate::blockchain::types::{decode_settlement_revert, format_cny_cents};
+use crate::db::alipay_blocklist::normalize_alipay_id;
+use crate::db::api_keys::{NewApiKey, SCOPE_READ, SCOPE_TRADE};
+use crate::db::dead_letters::{DEAD_LETTER_DISCARDED, DEAD_LETTER_PENDING, DEAD_LETTER_QUEUED, DEAD_LETTER_REPROCESSED};
+use crate::db::models::{DbAlipayBlocklistChange, DbApiKey, DbBlockedAlipayId, DbBuyerTier, DbDeadLetter, DbFailureStats, DbFlaggedTemplate, DbPaymentWindowTier, DbProverTranscript, DbScreeningRefusal, DbScreeningVerdict, DbSpendingLimitTier, DbStaleOrder, DbStalenessSettings};
+use crate::db::relayer_spend::ACTION_CANCEL_EXPIRED;
+use crate::db::spending_limits::SOURCE_ADMIN;
+use crate::db::token_status::{TOKEN_ALLOWED, TOKEN_DENIED};
+use crate::db::DbError;
+use crate::hexutil::{decode_fixed, encode_0x};
+use crate::metrics::ApiKeyUsage;
+use crate::proof_inputs::ProofInputs;
+use crate::spending_limits::{buyer_limits, BuyerLimits};
 
 #[derive(Debug, Deserialize)]
 pub struct UpdateConfigRequest {
@@ -53,10 +80,114 @@ pub struct ContractConfigResponse {
     pub max_trade_value_cny: String,
     pub payment_window: String,
     pub paused: bool,
-    pub zk_verifier: String,
+    pub zk_verifier: EthAddress,
     pub public_key_der_hash: String,
     pub app_exe_commit: String,
     pub app_vm_commit: String,
+    pub fetched_at: String,
+}
+
+impl From<ContractConfig> for ContractConfigResponse {
+    fn from(config: ContractConfig) -> Self {
+        Self {
+            min_trade_value_cny: config.min_trade_value_cny.to_string(),
+            max_trade_value_cny: config.max_trade_value_cny.to_string(),
+            payment_window: config.payment_window.to_string(),
+            paused: config.paused,
+            zk_verifier: config.zk_verifier.into(),
+            public_key_der_hash: encode_0x(config.public_key_der_hash),
+            app_exe_commit: encode_0x(config.app_exe_commit),
+            app_vm_commit: encode_0x(config.app_vm_commit),
+            fetched_at: config.fetched_at.to_rfc3339(),
+        }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ConfigHistoryQuery {
+    pub limit: Option<i64>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ConfigEventDto {
+    pub event_name: String,
+    pub details: serde_json::Value,
+    pub block_number: i64,
+    pub tx_hash: String,
+    pub log_index: i64,
+    pub synced_at: String,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ConfigHistoryResponse {
+    pub events: Vec<ConfigEventDto>,
+    pub total: usize,
+}
+
+#[derive(Debug, Serialize)]
+pub struct RelayerKeyDto {
+    pub address: EthAddress,
+    pub active: bool,
+    pub pending_nonce: String,
+    pub confirmed_nonce: String,
+    /// Sent but not yet mined
+    pub in_flight: String,
+    pub balance_wei: String,
+}
+
+impl From<RelayerKeyStatus> for RelayerKeyDto {
+    fn from(status: RelayerKeyStatus) -> Self {
+        Self {
+            address: status.address.into(),
+            active: status.active,
+            pending_nonce: status.pending_nonce.to_string(),
+            confirmed_nonce: status.confirmed_nonce.to_string(),
+            in_flight: status.in_flight().to_string(),
+            balance_wei: status.balance_wei.to_string(),
+        }
+    }
+}
+
+#[derive(Debug, Serialize)]
+pub struct RelayerKeysResponse {
+    pub active: EthAddress,
+    pub keys: Vec<RelayerKeyDto>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct RotateRelayerRequest {
+    /// Address of a configured standby key
+    pub address: String,
+}
+
+#[derive(Debug, Serialize)]
+pub struct RotateRelayerResponse {
+    pub previous: EthAddress,
+    pub active: EthAddress,
+    pub message: String,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct SimulateSettlementRequest {
+    pub trade_id: String,
+}
+
+/// One preflight check of a settlement simulation
+#[derive(Debug, Serialize)]
+pub struct SettlementCheck {
+    pub name: &'static str,
+    pub passed: bool,
+    pub detail: String,
+}
+
+#[derive(Debug, Serialize)]
+pub struct SimulateSettlementResponse {
+    pub trade_id: String,
+    /// Every check passed, including the eth_call simulation
+    pub ready: bool,
+    pub checks: Vec<SettlementCheck>,
+    /// Contract error the simulation reverted with, if any
+    pub revert_error: Option<String>,
 }
 
 /// Update contract configuration (minTradeValue, maxTradeValue, paymentWindow)
@@ -85,6 +216,9 @@ pub async fn update_config_handler(
         .await
         .map_err(|e| ApiError::BlockchainError(e.to_string()))?;
 
+    // Config changed on-chain - drop the cached snapshot
+    state.contract_config.invalidate().await;
+
     Ok(Json(UpdateConfigResponse {
         tx_hash: format!("{:#x}", tx_hash),
         message: "Contract configuration updated successfully".to_string(),
@@ -101,10 +235,7 @@ pub async fn update_verifier_handler(
         .as_ref()
         .ok_or_else(|| ApiError::Internal("Blockchain client not available".to_string()))?;
 
-    let new_verifier: Address = req
-        .new_verifier_address
-        .parse()
-        .map_err(|_| ApiError::BadRequest("Invalid verifier address".to_string()))?;
+    let new_verifier = req.new_verifier_address.parse::<EthAddress>()?.to_address();
 
     tracing::info!("Updating zkPDF verifier to: {:?}", new_verifier);
 
@@ -113,6 +244,9 @@ pub async fn update_verifier_handler(
         .await
         .map_err(|e| ApiError::BlockchainError(e.to_string()))?;
 
+    // Config changed on-chain - drop the cached snapshot
+    state.contract_config.invalidate().await;
+
     Ok(Json(UpdateVerifierResponse {
         tx_hash: format!("{:#x}", tx_hash),
         message: "Verifier contract updated successfully".to_string(),
@@ -135,6 +269,9 @@ pub async fn pause_contract_handler(
         .await
         .map_err(|e| ApiError::BlockchainError(e.to_string()))?;
 
+    // Config changed on-chain - drop the cached snapshot
+    state.contract_config.invalidate().await;
+
     Ok(Json(PauseResponse {
         tx_hash: format!("{:#x}", tx_hash),
         message: "Contract paused successfully".to_string(),
@@ -157,6 +294,9 @@ pub async fn unpause_contract_handler(
         .await
         .map_err(|e| ApiError::BlockchainError(e.to_string()))?;
 
+    // Config changed on-chain - drop the cached snapshot
+    state.contract_config.invalidate().await;
+
     Ok(Json(PauseResponse {
         tx_hash: format!("{:#x}", tx_hash),
         message: "Contract unpaused successfully".to_string(),
@@ -174,11 +314,11 @@ pub async fn update_zkpdf_config_handler(
         .ok_or_else(|| ApiError::Internal("Blockchain client not available".to_string()))?;
 
     // Parse hex strings to bytes32
-    let public_key_der_hash = hex_to_bytes32(&req.public_key_der_hash)
+    let public_key_der_hash = decode_fixed::<32>(&req.public_key_der_hash)
         .map_err(|e| ApiError::BadRequest(format!("Invalid public_key_der_hash: {}", e)))?;
-    let app_exe_commit = hex_to_bytes32(&req.app_exe_commit)
+    let app_exe_commit = decode_fixed::<32>(&req.app_exe_commit)
         .map_err(|e| ApiError::BadRequest(format!("Invalid app_exe_commit: {}", e)))?;
-    let app_vm_commit = hex_to_bytes32(&req.app_vm_commit)
+    let app_vm_commit = decode_fixed::<32>(&req.app_vm_commit)
         .map_err(|e| ApiError::BadRequest(format!("Invalid app_vm_commit: {}", e)))?;
 
     tracing::info!(
@@ -193,47 +333,1628 @@ pub async fn update_zkpdf_config_handler(
         .await
         .map_err(|e| ApiError::BlockchainError(e.to_string()))?;
 
+    // Config changed on-chain - drop the cached snapshot
+    state.contract_config.invalidate().await;
+
     Ok(Json(UpdateZkPDFConfigResponse {
         tx_hash: format!("{:#x}", tx_hash),
         message: "zkPDF configuration updated successfully".to_string(),
     }))
 }
 
-/// Helper function to convert hex string to bytes32
-fn hex_to_bytes32(hex_str: &str) -> Result<[u8; 32], String> {
-    let hex_str = hex_str.strip_prefix("0x").unwrap_or(hex_str);
-    let bytes = hex::decode(hex_str).map_err(|e| format!("Hex decode error: {}", e))?;
-    if bytes.len() != 32 {
-        return Err(format!("Expected 32 bytes, got {}", bytes.len()));
-    }
-    let mut result = [0u8; 32];
-    result.copy_from_slice(&bytes);
-    Ok(result)
+/// Get current contract configuration (served from the cached snapshot)
+pub async fn get_config_handler(
+    State(state): State<AppState>,
+) -> Result<Json<ContractConfigResponse>, ApiError> {
+    let blockchain_client = state
+        .blockchain_client
+        .as_ref()
+        .ok_or_else(|| ApiError::Internal("Blockchain client not available".to_string()))?;
+
+    let config = state
+        .contract_config
+        .get(blockchain_client)
+        .await
+        .map_err(|e| ApiError::BlockchainError(e.to_string()))?;
+
+    Ok(Json(config.into()))
 }
 
-/// Get current contract configuration
-pub async fn get_config_handler(
+/// Force a refresh of the cached contract configuration
+pub async fn refresh_config_handler(
     State(state): State<AppState>,
+    headers: HeaderMap,
 ) -> Result<Json<ContractConfigResponse>, ApiError> {
+    state.admin_keys.authenticate(&headers)?;
+
+    let blockchain_client = state
+        .blockchain_client
+        .as_ref()
+        .ok_or_else(|| ApiError::Internal("Blockchain client not available".to_string()))?;
+
+    let config = state
+        .contract_config
+        .refresh(blockchain_client)
+        .await
+        .map_err(|e| ApiError::BlockchainError(e.to_string()))?;
+
+    tracing::info!("Contract config snapshot force-refreshed");
+
+    Ok(Json(config.into()))
+}
+
+/// Get history of on-chain config changes (recorded by the event listener)
+pub async fn get_config_history_handler(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Query(params): Query<ConfigHistoryQuery>,
+) -> Result<Json<ConfigHistoryResponse>, ApiError> {
+    state.admin_keys.authenticate(&headers)?;
+
+    let limit = params.limit.unwrap_or(50).clamp(1, 500);
+
+    let events: Vec<ConfigEventDto> = state
+        .db
+        .get_config_events(limit)
+        .await?
+        .into_iter()
+        .map(|e| ConfigEventDto {
+            details: serde_json::from_str(&e.details).unwrap_or(serde_json::Value::String(e.details)),
+            event_name: e.event_name,
+            block_number: e.block_number,
+            tx_hash: e.tx_hash,
+            log_index: e.log_index,
+            synced_at: e.synced_at.to_rfc3339(),
+        })
+        .collect();
+
+    let total = events.len();
+
+    Ok(Json(ConfigHistoryResponse { events, total }))
+}
+
+/// Dry-run settlement of a trade for support
+/// Runs every preflight submitPaymentProof depends on, then simulates the call with
+/// eth_call. Nothing is sent on-chain; failing checks are reported, not returned as errors.
+pub async fn simulate_settlement_handler(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Json(req): Json<SimulateSettlementRequest>,
+) -> Result<Json<SimulateSettlementResponse>, ApiError> {
+    state.admin_keys.authenticate(&headers)?;
+
+    simulate_settlement(&state, &req.trade_id.parse()?).await.map(Json)
+}
+
+async fn simulate_settlement(state: &AppState, trade_id: &TradeId) -> Result<SimulateSettlementResponse, ApiError> {
     let blockchain_client = state
         .blockchain_client
         .as_ref()
         .ok_or_else(|| ApiError::Internal("Blockchain client not available".to_string()))?;
 
-    let config = blockchain_client
-        .get_contract_config()
+    let trade = state.db.get_trade(&trade_id.to_string()).await?;
+    let order = state.db.get_order(&trade.order_id).await?;
+    let config = state
+        .contract_config
+        .get(blockchain_client)
         .await
         .map_err(|e| ApiError::BlockchainError(e.to_string()))?;
 
-    Ok(Json(ContractConfigResponse {
-        min_trade_value_cny: config.0.to_string(),
-        max_trade_value_cny: config.1.to_string(),
-        payment_window: config.2.to_string(),
-        paused: config.3,
-        zk_verifier: format!("{:#x}", config.4),
-        public_key_der_hash: format!("0x{}", hex::encode(config.5)),
-        app_exe_commit: format!("0x{}", hex::encode(config.6)),
-        app_vm_commit: format!("0x{}", hex::encode(config.7)),
+    let mut checks = Vec::new();
+    let mut check = |name: &'static str, passed: bool, detail: String| {
+        checks.push(SettlementCheck { name, passed, detail });
+    };
+
+    check("trade_pending", trade.status == 0, format!("status={} (0=PENDING, 1=SETTLED, 2=EXPIRED)", trade.status));
+
+    let now = server_time(state).await;
+    check(
+        "not_expired",
+        now.unix <= trade.expires_at,
+        format!("expiresAt={}, now={} ({:?} time)", trade.expires_at, now.unix, now.source),
+    );
+
+    check("contract_not_paused", !config.paused, format!("paused={}", config.paused));
+
+    let proof = match (&trade.proof_user_public_values, &trade.proof_accumulator, &trade.proof_data) {
+        (Some(upv), Some(acc), Some(data)) => Some((upv, acc, data)),
+        _ => None,
+    };
+    check(
+        "proof_present",
+        proof.is_some(),
+        match &trade.axiom_proof_id {
+            Some(id) if proof.is_some() => format!("axiom_proof_id={}", id),
+            _ => "No proof generated for this trade".to_string(),
+        },
+    );
+
+    let mut sized_proof = None;
+    if let Some((upv, acc, data)) = proof {
+        // Sizes of the format the proof was generated in, which the verifier must still take
+        let verifier_format = state.proof_formats.for_verifier(&config.zk_verifier);
+        let stored_format = state.proof_formats.of_stored(trade.proof_format.as_deref());
+        check(
+            "proof_format",
+            stored_format.as_ref().is_ok_and(|f| f.version == verifier_format.version),
+            match &stored_format {
+                Ok(f) => format!("proof={}, verifier {:?} expects {}", f.version, config.zk_verifier, verifier_format.version),
+                Err(e) => e.to_string(),
+            },
+        );
+
+        let format = stored_format.unwrap_or(verifier_format);
+        let sizes_ok = format.validate(upv, acc, data).is_ok();
+        check(
+            "proof_sizes",
+            sizes_ok,
+            format!(
+                "user_public_values={}/{}, accumulator={}/{}, proof={}/{} bytes ({})",
+                upv.len(), format.user_public_values_len,
+                acc.len(), format.accumulator_len,
+                data.len(), format.proof_data_len,
+                format.version
+            ),
+        );
+        if sizes_ok {
+            let mut upv_array = [0u8; 32];
+            upv_array.copy_from_slice(upv);
+            sized_proof = Some((upv_array, acc.clone(), data.clone()));
+        }
+    }
+
+    // Same expected hash the contract computes in submitPaymentProof
+    if let Some((upv, _, _)) = &sized_proof {
+        let expected = ProofInputs::for_trade(&order, &trade, config.public_key_der_hash)
+            .and_then(|inputs| inputs.expected_hash())
+            .map_err(|e| e.to_string());
+        match expected {
+            Ok(expected) => check(
+                "public_values_match",
+                *upv == expected,
+                format!("proof={}, expected={}", encode_0x(upv), encode_0x(expected)),
+            ),
+            Err(e) => check("public_values_match", false, format!("Cannot compute expected hash: {}", e)),
+        }
+    }
+
+    // Commitments the proof was generated for (from the stored Axiom proof JSON)
+    if sized_proof.is_some() {
+        let commits = trade
+            .proof_json
+            .as_deref()
+            .and_then(|json| serde_json::from_str::<serde_json::Value>(json).ok())
+            .and_then(|json| {
+                let exe = json.get("app_exe_commit")?.as_str()?.to_string();
+                let vm = json.get("app_vm_commit")?.as_str()?.to_string();
+                Some((exe, vm))
+            });
+        match commits {
+            Some((exe, vm)) => {
+                let matches = |proof_commit: &str, onchain: &[u8; 32]| {
+                    decode_fixed::<32>(proof_commit).map(|c| &c == onchain).unwrap_or(false)
+                };
+                check(
+                    "commitments_match",
+                    matches(&exe, &config.app_exe_commit) && matches(&vm, &config.app_vm_commit),
+                    format!(
+                        "proof appExeCommit={}, appVmCommit={}; contract appExeCommit={}, appVmCommit={}",
+                        exe, vm, encode_0x(config.app_exe_commit), encode_0x(config.app_vm_commit)
+                    ),
+                );
+            }
+            None => check("commitments_match", false, "Proof JSON has no app_exe_commit/app_vm_commit".to_string()),
+        }
+    }
+
+    let mut revert_error = None;
+    match sized_proof {
+        Some((upv, acc, data)) => {
+            match blockchain_client.simulate_payment_proof(trade_id.0, upv, acc, data).await {
+                Ok(()) => check("simulation", true, "eth_call succeeded".to_string()),
+                Err(e) => {
+                    let error_msg = e.to_string();
+                    let detail = match decode_settlement_revert(&error_msg) {
+                        Some((name, message)) => {
+                            revert_error = Some(name.to_string());
+                            format!("{}: {}", name, message)
+                        }
+                        None => error_msg,
+                    };
+                    check("simulation", false, detail);
+                }
+            }
+        }
+        None => check("simulation", false, "Skipped: no usable proof".to_string()),
+    }
+
+    let ready = checks.iter().all(|c| c.passed);
+    tracing::info!("Settlement simulation for trade {}: ready={}", trade.trade_id, ready);
+
+    Ok(SimulateSettlementResponse {
+        trade_id: trade.trade_id,
+        ready,
+        checks,
+        revert_error,
+    })
+}
+
+/// Nonce state and balance of every configured relayer key
+pub async fn get_relayer_keys_handler(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+) -> Result<Json<RelayerKeysResponse>, ApiError> {
+    state.admin_keys.authenticate(&headers)?;
+
+    let blockchain_client = state
+        .blockchain_client
+        .as_ref()
+        .ok_or_else(|| ApiError::Internal("Blockchain client not available".to_string()))?;
+
+    let keys = blockchain_client
+        .relayer_key_statuses()
+        .await
+        .map_err(|e| ApiError::BlockchainError(e.to_string()))?
+        .into_iter()
+        .map(RelayerKeyDto::from)
+        .collect();
+
+    Ok(Json(RelayerKeysResponse {
+        active: blockchain_client.relayer_address().into(),
+        keys,
+    }))
+}
+
+/// Switch the active relayer key to a configured standby key
+/// Takes effect for the next transaction; rotation is not persisted across restarts
+/// (RELAYER_PRIVATE_KEY is active again on startup).
+pub async fn rotate_relayer_handler(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Json(req): Json<RotateRelayerRequest>,
+) -> Result<Json<RotateRelayerResponse>, ApiError> {
+    let admin = state.admin_keys.authenticate(&headers)?;
+
+    let blockchain_client = state
+        .blockchain_client
+        .as_ref()
+        .ok_or_else(|| ApiError::Internal("Blockchain client not available".to_string()))?;
+
+    let address = req.address.parse::<EthAddress>()?.to_address();
+
+    // Refuse to rotate onto a key that cannot pay for gas
+    let target = blockchain_client
+        .relayer_key_statuses()
+        .await
+        .map_err(|e| ApiError::BlockchainError(e.to_string()))?
+        .into_iter()
+        .find(|k| k.address == address)
+        .ok_or_else(|| ApiError::BadRequest(format!("Relayer key {:#x} is not configured", address)))?;
+    if target.balance_wei.is_zero() {
+        return Err(ApiError::BadRequest(format!("Relayer key {:#x} has no balance", address)));
+    }
+
+    let previous = blockchain_client
+        .rotate_to(address)
+        .map_err(|e| ApiError::BadRequest(e.to_string()))?;
+    tracing::info!("Relayer rotation to {:#x} requested by {}", address, admin.as_deref().unwrap_or("unauthenticated admin"));
+
+    Ok(Json(RotateRelayerResponse {
+        previous: previous.into(),
+        active: address.into(),
+        message: "Relayer key rotated. Pending transactions from the previous key will still confirm.".to_string(),
+    }))
+}
+
+#[derive(Debug, Serialize)]
+pub struct PendingTxDto {
+    pub tx_hash: String,
+    /// Contract method (fillOrder, submitPaymentProof, ...) or "cancel"
+    pub kind: String,
+    pub trade_id: Option<String>,
+    pub order_id: Option<String>,
+    pub relayer: EthAddress,
+    pub nonce: String,
+    pub gas_limit: String,
+    /// Gas price in wei (max fee per gas for EIP-1559 transactions)
+    pub gas_price_wei: String,
+    pub priority_fee_wei: Option<String>,
+    pub sent_at: i64,
+    pub age_secs: i64,
+    /// Transaction this one replaced (bump or cancel)
+    pub replaces: Option<String>,
+}
+
+impl From<PendingTx> for PendingTxDto {
+    fn from(tx: PendingTx) -> Self {
+        Self {
+            tx_hash: format!("{:#x}", tx.tx_hash),
+            age_secs: tx.age_secs(),
+            kind: tx.kind,
+            trade_id: tx.trade_id,
+            order_id: tx.order_id,
+            relayer: tx.relayer.into(),
+            nonce: tx.nonce.to_string(),
+            gas_limit: tx.gas_limit.to_string(),
+            gas_price_wei: tx.gas_price.to_string(),
+            priority_fee_wei: tx.priority_fee.map(|fee| fee.to_string()),
+            sent_at: tx.sent_at.timestamp(),
+            replaces: tx.replaces.map(|hash| format!("{:#x}", hash)),
+        }
+    }
+}
+
+#[derive(Debug, Serialize)]
+pub struct PendingTxsResponse {
+    pub transactions: Vec<PendingTxDto>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ReplacePendingTxRequest {
+    /// Gas price (max fee) for the replacement, decimal gwei; it is always at least
+    /// 12.5% above the original's
+    pub gas_price_gwei: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ReplacePendingTxResponse {
+    pub replaced: String,
+    pub replacement: PendingTxDto,
+    pub message: String,
+}
+
+/// GET /api/admin/pending-txs
+/// Relayer transactions sent and not yet mined (type, trade/order, nonce, gas price, age)
+pub async fn get_pending_txs_handler(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+) -> Result<Json<PendingTxsResponse>, ApiError> {
+    state.admin_keys.authenticate(&headers)?;
+
+    let blockchain_client = state
+        .blockchain_client
+        .as_ref()
+        .ok_or_else(|| ApiError::Internal("Blockchain client not available".to_string()))?;
+
+    let transactions = blockchain_client
+        .pending_transactions()
+        .await
+        .map_err(|e| ApiError::BlockchainError(e.to_string()))?
+        .into_iter()
+        .map(PendingTxDto::from)
+        .collect();
+
+    Ok(Json(PendingTxsResponse { transactions }))
+}
+
+/// POST /api/admin/pending-txs/:tx_hash/bump
+/// Re-send a stuck transaction at its nonce with a higher gas price
+pub async fn bump_pending_tx_handler(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Path(tx_hash): Path<String>,
+    Json(req): Json<ReplacePendingTxRequest>,
+) -> Result<Json<ReplacePendingTxResponse>, ApiError> {
+    replace_pending_tx(&state, &headers, &tx_hash, false, req).await
+}
+
+/// POST /api/admin/pending-txs/:tx_hash/cancel
+/// Replace a stuck transaction with an empty self-transfer at its nonce
+pub async fn cancel_pending_tx_handler(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Path(tx_hash): Path<String>,
+    Json(req): Json<ReplacePendingTxRequest>,
+) -> Result<Json<ReplacePendingTxResponse>, ApiError> {
+    replace_pending_tx(&state, &headers, &tx_hash, true, req).await
+}
+
+async fn replace_pending_tx(
+    state: &AppState,
+    headers: &HeaderMap,
+    tx_hash: &str,
+    cancel: bool,
+    req: ReplacePendingTxRequest,
+) -> Result<Json<ReplacePendingTxResponse>, ApiError> {
+    let admin = state.admin_keys.authenticate(headers)?;
+    let blockchain_client = state
+        .blockchain_client
+        .as_ref()
+        .ok_or_else(|| ApiError::Internal("Blockchain client not available".to_string()))?;
+
+    let tx_hash: H256 = tx_hash
+        .parse()
+        .map_err(|_| ApiError::BadRequest(format!("Invalid transaction hash: {}", tx_hash)))?;
+    let gas_price = parse_gas_override(None, req.gas_price_gwei.as_deref())?.gas_price;
+
+    // Only what is still pending can be replaced (mined transactions are pruned here)
+    let pending = blockchain_client
+        .pending_transactions()
+        .await
+        .map_err(|e| ApiError::BlockchainError(e.to_string()))?;
+    if !pending.iter().any(|tx| tx.tx_hash == tx_hash) {
+        return Err(ApiError::NotFound(format!("No pending relayer transaction {:#x}", tx_hash)));
+    }
+
+    let replacement = blockchain_client
+        .replace_pending(tx_hash, cancel, gas_price)
+        .await
+        .map_err(|e| ApiError::BlockchainError(e.to_string()))?;
+    tracing::warn!(
+        "⛽ {} {} pending tx {:#x}",
+        admin.as_deref().unwrap_or("admin"),
+        if cancel { "cancelled" } else { "bumped" },
+        tx_hash
+    );
+
+    let message = if cancel {
+        "Cancellation sent. Whichever of the two is mined first takes the nonce; the original's caller sees it fail."
+    } else {
+        "Replacement sent with a higher gas price. Whichever of the two is mined first takes the nonce."
+    };
+    Ok(Json(ReplacePendingTxResponse {
+        replaced: format!("{:#x}", tx_hash),
+        replacement: replacement.into(),
+        message: message.to_string(),
+    }))
+}
+
+// ============ Incident response: dual-confirmed force actions ============
+
+pub const ACTION_FORCE_EXPIRE: &str = "force_expire";
+pub const ACTION_FORCE_RELEASE: &str = "force_release";
+
+/// Upper bounds on gas overrides - anything above is almost certainly a typo
+const MAX_GAS_LIMIT_OVERRIDE: u64 = 30_000_000;
+const MAX_GAS_PRICE_GWEI_OVERRIDE: u64 = 1_000;
+
+#[derive(Debug, Deserialize)]
+pub struct ForceExpireRequest {
+    pub trade_id: String,
+    pub confirmation_token: Option<Uuid>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ForceReleaseRequest {
+    pub trade_id: String,
+    pub gas_limit: Option<u64>,
+    pub gas_price_gwei: Option<String>, // decimal, e.g. "0.05"
+    pub confirmation_token: Option<Uuid>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ForceActionResponse {
+    pub action: &'static str,
+    pub trade_id: String,
+    /// Every safety check passed
+    pub ready: bool,
+    pub checks: Vec<SettlementCheck>,
+    /// Issued on the first call: repeat the request with it to execute
+    pub confirmation_token: Option<Uuid>,
+    pub confirmation_expires_in: Option<u64>,
+    pub requested_by: Option<String>,
+    pub confirmed_by: Option<String>,
+    /// Set once the action has been sent
+    pub tx_hash: Option<String>,
+    pub message: String,
+}
+
+/// Outcome of the confirmation step of a force action
+enum ForceGate {
+    /// Nothing to execute yet (checks failed, or a token was just issued)
+    Respond(ForceActionResponse),
+    /// Confirmed by a second call: run the action
+    Execute(ForceActionResponse),
+}
+
+/// Shared two-step flow: refuse on failing checks, issue a token on the first call,
+/// redeem it (same action, trade and parameters; different admin key) on the second
+fn force_gate(
+    state: &AppState,
+    admin: Option<String>,
+    action: &'static str,
+    trade_id: &str,
+    params: &str,
+    token: Option<Uuid>,
+    checks: Vec<SettlementCheck>,
+) -> Result<ForceGate, ApiError> {
+    let ready = checks.iter().all(|c| c.passed);
+    let mut response = ForceActionResponse {
+        action,
+        trade_id: trade_id.to_string(),
+        ready,
+        checks,
+        confirmation_token: None,
+        confirmation_expires_in: None,
+        requested_by: None,
+        confirmed_by: None,
+        tx_hash: None,
+        message: String::new(),
+    };
+
+    if !ready {
+        response.message = "Safety checks failed - nothing was sent".to_string();
+        return Ok(ForceGate::Respond(response));
+    }
+
+    match token {
+        None => {
+            let ttl = state.admin_confirmations.ttl().as_secs();
+            response.confirmation_token = Some(state.admin_confirmations.request(action, trade_id, params, admin.clone()));
+            response.confirmation_expires_in = Some(ttl);
+            response.message = match &admin {
+                Some(_) => format!("Checks passed. A different admin must confirm within {}s with confirmation_token.", ttl),
+                None => format!("Checks passed. Repeat the request with confirmation_token within {}s to execute.", ttl),
+            };
+            response.requested_by = admin;
+            tracing::warn!("🚨 Admin {} requested for trade {} by {}", action, trade_id, response.requested_by.as_deref().unwrap_or("-"));
+            Ok(ForceGate::Respond(response))
+        }
+        Some(token) => {
+            let pending = state.admin_confirmations.confirm(token, action, trade_id, params, admin.as_deref())?;
+            response.requested_by = pending.requested_by;
+            response.confirmed_by = admin;
+            tracing::warn!(
+                "🚨 Admin {} for trade {} confirmed (requested by {}, confirmed by {})",
+                action, trade_id,
+                response.requested_by.as_deref().unwrap_or("-"),
+                response.confirmed_by.as_deref().unwrap_or("-")
+            );
+            Ok(ForceGate::Execute(response))
+        }
+    }
+}
+
+fn parse_gas_override(gas_limit: Option<u64>, gas_price_gwei: Option<&str>) -> Result<GasOverride, ApiError> {
+    if let Some(limit) = gas_limit {
+        if limit == 0 || limit > MAX_GAS_LIMIT_OVERRIDE {
+            return Err(ApiError::BadRequest(format!("gas_limit must be between 1 and {}", MAX_GAS_LIMIT_OVERRIDE)));
+        }
+    }
+
+    let gas_price = match gas_price_gwei {
+        Some(gwei) => {
+            let wei: U256 = parse_units(gwei.trim(), "gwei")
+                .map_err(|_| ApiError::BadRequest(format!("Invalid gas_price_gwei: {:?}", gwei)))?
+                .into();
+            if wei.is_zero() || wei > U256::from(MAX_GAS_PRICE_GWEI_OVERRIDE) * U256::exp10(9) {
+                return Err(ApiError::BadRequest(format!(
+                    "gas_price_gwei must be above 0 and at most {}",
+                    MAX_GAS_PRICE_GWEI_OVERRIDE
+                )));
+            }
+            Some(wei)
+        }
+        None => None,
+    };
+
+    Ok(GasOverride { gas_limit: gas_limit.map(U256::from), gas_price })
+}
+
+/// POST /api/admin/force-expire
+/// Cancel a stuck expired trade now (returning its tokens to the order) instead of
+/// waiting for the auto-cancel service. The contract still enforces expiry; this
+/// checks the on-chain trade first and requires a second, confirming call.
+pub async fn force_expire_handler(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Json(req): Json<ForceExpireRequest>,
+) -> Result<Json<ForceActionResponse>, ApiError> {
+    let admin = state.admin_keys.authenticate(&headers)?;
+    let blockchain_client = state
+        .blockchain_client
+        .as_ref()
+        .ok_or_else(|| ApiError::Internal("Blockchain client not available".to_string()))?;
+
+    let trade_id: TradeId = req.trade_id.parse()?;
+    let onchain = blockchain_client
+        .get_onchain_trade(trade_id.0)
+        .await
+        .map_err(|e| ApiError::BlockchainError(e.to_string()))?;
+    let chain_now = blockchain_client
+        .get_latest_block_timestamp()
+        .await
+        .map_err(|e| ApiError::BlockchainError(e.to_string()))?;
+
+    let checks = vec![
+        SettlementCheck { name: "trade_exists_onchain", passed: onchain.exists, detail: format!("exists={}", onchain.exists) },
+        SettlementCheck {
+            name: "trade_pending_onchain",
+            passed: onchain.status == 0,
+            detail: format!("status={} (0=PENDING, 1=SETTLED, 2=EXPIRED)", onchain.status),
+        },
+        SettlementCheck {
+            name: "expired_onchain",
+            passed: chain_now > onchain.expires_at,
+            detail: format!("expiresAt={}, latest block timestamp={}", onchain.expires_at, chain_now),
+        },
+    ];
+
+    let mut response = match force_gate(&state, admin, ACTION_FORCE_EXPIRE, &trade_id.to_string(), "", req.confirmation_token, checks)? {
+        ForceGate::Respond(response) => return Ok(Json(response)),
+        ForceGate::Execute(response) => response,
+    };
+
+    match blockchain_client.cancel_expired_trade(trade_id.0).await {
+        Ok(relayer_tx) => {
+            record_spend(&state, relayer_tx.spend(ACTION_CANCEL_EXPIRED, Some(trade_id.to_string()), None, false)).await;
+            response.tx_hash = Some(format!("{:?}", relayer_tx.tx_hash));
+            response.message = "Trade cancelled on-chain; tokens returned to the order".to_string();
+            Ok(Json(response))
+        }
+        Err(e) => {
+            if let EthereumClientError::TransactionReverted(tx) = &e {
+                record_spend(&state, tx.spend(ACTION_CANCEL_EXPIRED, Some(trade_id.to_string()), None, true)).await;
+            }
+            Err(ApiError::BlockchainError(e.to_string()))
+        }
+    }
+}
+
+/// POST /api/admin/force-release
+/// Re-submit a trade's stored proof with overridden gas (e.g. a stuck or underpriced
+/// settlement). Runs the full settlement simulation plus an on-chain status check and
+/// requires a second, confirming call with the same gas settings.
+pub async fn force_release_handler(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Json(req): Json<ForceReleaseRequest>,
+) -> Result<Json<ForceActionResponse>, ApiError> {
+    let admin = state.admin_keys.authenticate(&headers)?;
+    let blockchain_client = state
+        .blockchain_client
+        .as_ref()
+        .ok_or_else(|| ApiError::Internal("Blockchain client not available".to_string()))?;
+
+    let gas = parse_gas_override(req.gas_limit, req.gas_price_gwei.as_deref())?;
+    let params = format!(
+        "gas_limit={},gas_price_wei={}",
+        gas.gas_limit.map(|g| g.to_string()).unwrap_or_else(|| "auto".to_string()),
+        gas.gas_price.map(|p| p.to_string()).unwrap_or_else(|| "auto".to_string())
+    );
+
+    let trade_id: TradeId = req.trade_id.parse()?;
+    let onchain = blockchain_client
+        .get_onchain_trade(trade_id.0)
+        .await
+        .map_err(|e| ApiError::BlockchainError(e.to_string()))?;
+
+    // The DB mirror may lag: also require the contract itself to still hold the trade as pending
+    let mut checks = vec![SettlementCheck {
+        name: "trade_pending_onchain",
+        passed: onchain.exists && onchain.status == 0,
+        detail: format!("exists={}, status={} (0=PENDING, 1=SETTLED, 2=EXPIRED)", onchain.exists, onchain.status),
+    }];
+    checks.extend(simulate_settlement(&state, &trade_id).await?.checks);
+
+    let mut response = match force_gate(&state, admin, ACTION_FORCE_RELEASE, &trade_id.to_string(), &params, req.confirmation_token, checks)? {
+        ForceGate::Respond(response) => return Ok(Json(response)),
+        ForceGate::Execute(response) => response,
+    };
+
+    let submitted = submit_trade_proof_with_gas(&state, &trade_id, gas).await?;
+    response.tx_hash = Some(submitted.tx_hash);
+    response.message = format!("Proof re-submitted ({})", params);
+    Ok(Json(response))
+}
+
+#[derive(Debug, Deserialize)]
+pub struct SetTokenStatusRequest {
+    pub token: String,
+    pub status: String,          // allowed | denied | none (take off both lists)
+    pub reason: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct SetTokenStatusResponse {
+    pub token: EthAddress,
+    pub status: String,
+    pub message: String,
+}
+
+/// POST /api/admin/token-status
+/// Allowlist or denylist a token (soft pause, orderbook only - nothing changes
+/// on-chain). Suspended tokens are refused by match-intent and execute-fill and
+/// their orders drop out of the active listing.
+pub async fn set_token_status_handler(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Json(req): Json<SetTokenStatusRequest>,
+) -> Result<Json<SetTokenStatusResponse>, ApiError> {
+    let admin = state.admin_keys.authenticate(&headers)?;
+    let token: EthAddress = req.token.parse()?;
+
+    let message = match req.status.as_str() {
+        TOKEN_ALLOWED | TOKEN_DENIED => {
+            state
+                .db
+                .set_token_status(&token, &req.status, req.reason.as_deref(), admin.as_deref())
+                .await?;
+            format!("Token {} is now {}", token.to_checksum(), req.status)
+        }
+        "none" => {
+            if !state.db.remove_token_status(&token).await? {
+                return Err(ApiError::NotFound(format!("Token {} is not listed", token.to_checksum())));
+            }
+            format!("Token {} removed from the token lists", token.to_checksum())
+        }
+        other => {
+            return Err(ApiError::BadRequest(format!(
+                "Invalid status '{}': expected allowed, denied or none",
+                other
+            )))
+        }
+    };
+
+    tracing::info!("Token status change by {}: {}", admin.as_deref().unwrap_or("unauthenticated admin"), message);
+
+    Ok(Json(SetTokenStatusResponse { token, status: req.status, message }))
+}
+
+#[derive(Debug, Deserialize)]
+pub struct AlipayBlocklistQuery {
+    /// Audit entries to return (default 100)
+    pub audit_limit: Option<i64>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct AlipayBlocklistResponse {
+    pub entries: Vec<DbBlockedAlipayId>,
+    /// Latest changes, newest first
+    pub audit: Vec<DbAlipayBlocklistChange>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct SetAlipayBlocklistRequest {
+    pub alipay_id: String,
+    /// true to list the account, false to take it off
+    pub blocked: bool,
+    /// Kept in the audit log (fraud report, chargeback reference...)
+    pub reason: Option<String>,
+}
+
+/// GET /api/admin/alipay-blocklist?audit_limit=
+/// Blocklisted seller Alipay accounts and the latest changes to the list
+pub async fn get_alipay_blocklist_handler(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Query(params): Query<AlipayBlocklistQuery>,
+) -> Result<Json<AlipayBlocklistResponse>, ApiError> {
+    state.admin_keys.authenticate(&headers)?;
+
+    let audit_limit = params.audit_limit.unwrap_or(100).clamp(1, 1000);
+    Ok(Json(AlipayBlocklistResponse {
+        entries: state.db.list_blocked_alipay_ids().await?,
+        audit: state.db.get_alipay_blocklist_audit(audit_limit).await?,
+    }))
+}
+
+/// POST /api/admin/alipay-blocklist
+/// Blocklist a seller Alipay account (fraud, chargebacks) or take it off the list.
+/// Orders paying out to a listed account drop out of matching and the active listing,
+/// and execute-fill refuses them. Orderbook only - nothing changes on-chain.
+pub async fn set_alipay_blocklist_handler(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Json(req): Json<SetAlipayBlocklistRequest>,
+) -> Result<Json<AlipayBlocklistResponse>, ApiError> {
+    let admin = state.admin_keys.authenticate(&headers)?;
+    let alipay_id = normalize_alipay_id(&req.alipay_id);
+    if alipay_id.is_empty() || alipay_id.chars().count() > 128 {
+        return Err(ApiError::BadRequest("alipay_id must be 1 to 128 characters".to_string()));
+    }
+    let masked = mask_alipay_id(&alipay_id, AlipayIdFormat::detect(&alipay_id)).unwrap_or_else(|_| "(unrecognized format)".to_string());
+
+    if req.blocked {
+        state.db.block_alipay_id(&alipay_id, req.reason.as_deref(), admin.as_deref()).await?;
+    } else if !state.db.unblock_alipay_id(&alipay_id, req.reason.as_deref(), admin.as_deref()).await? {
+        return Err(ApiError::NotFound(format!("Alipay account {} is not blocklisted", masked)));
+    }
+    tracing::warn!(
+        "Alipay account {} {} by {}",
+        masked,
+        if req.blocked { "blocklisted" } else { "taken off the blocklist" },
+        admin.as_deref().unwrap_or("unauthenticated admin")
+    );
+
+    get_alipay_blocklist_handler(State(state), headers, Query(AlipayBlocklistQuery { audit_limit: None })).await
+}
+
+/// Longest payment window a tier may recommend
+const MAX_TIER_WINDOW_SECS: i64 = 24 * 3600;
+
+#[derive(Debug, Deserialize)]
+pub struct SetPaymentWindowTierRequest {
+    /// Token the tier applies to (omit for any token)
+    pub token: Option<String>,
+    /// Smallest trade value in the tier, CNY cents
+    pub min_value_cny: i64,
+    /// Recommended window in seconds (omit or null to remove the tier)
+    pub window_secs: Option<i64>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct PaymentWindowTiersResponse {
+    /// The contract's payment window, granted to every trade (omitted without blockchain integration)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub contract_window_secs: Option<u64>,
+    pub tiers: Vec<DbPaymentWindowTier>,
+}
+
+/// GET /api/admin/payment-windows
+/// Payment window tiers next to the contract's window
+pub async fn get_payment_windows_handler(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+) -> Result<Json<PaymentWindowTiersResponse>, ApiError> {
+    state.admin_keys.authenticate(&headers)?;
+
+    let contract_window_secs = match &state.blockchain_client {
+        Some(client) => Some(
+            state
+                .contract_config
+                .get(client)
+                .await
+                .map_err(|e| ApiError::BlockchainError(format!("Failed to get contract config: {}", e)))?
+                .payment_window
+                .as_u64(),
+        ),
+        None => None,
+    };
+
+    Ok(Json(PaymentWindowTiersResponse {
+        contract_window_secs,
+        tiers: state.db.list_payment_window_tiers().await?,
+    }))
+}
+
+/// POST /api/admin/payment-windows
+/// Set or remove the recommended payment window of a token / trade value tier.
+/// The contract window is unchanged; execute-fill warns buyers whose trade gets less.
+pub async fn set_payment_window_tier_handler(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Json(req): Json<SetPaymentWindowTierRequest>,
+) -> Result<Json<PaymentWindowTiersResponse>, ApiError> {
+    let admin = state.admin_keys.authenticate(&headers)?;
+    let token = req.token.as_deref().map(str::parse::<EthAddress>).transpose()?;
+    if req.min_value_cny < 0 {
+        return Err(ApiError::BadRequest("min_value_cny must not be negative".to_string()));
+    }
+    let tier = format!(
+        "{} from {} CNY",
+        token.as_ref().map(EthAddress::to_checksum).unwrap_or_else(|| "any token".to_string()),
+        format_cny_cents(U256::from(req.min_value_cny as u64))
+    );
+
+    match req.window_secs {
+        Some(window_secs) => {
+            if window_secs <= 0 || window_secs > MAX_TIER_WINDOW_SECS {
+                return Err(ApiError::BadRequest(format!(
+                    "window_secs must be between 1 and {}",
+                    MAX_TIER_WINDOW_SECS
+                )));
+            }
+            state
+                .db
+                .set_payment_window_tier(token.as_ref(), req.min_value_cny, window_secs, admin.as_deref())
+                .await?;
+            tracing::info!(
+                "Payment window tier {} set to {}s by {}",
+                tier, window_secs, admin.as_deref().unwrap_or("unauthenticated admin")
+            );
+        }
+        None => {
+            if !state.db.remove_payment_window_tier(token.as_ref(), req.min_value_cny).await? {
+                return Err(ApiError::NotFound(format!("No payment window tier {}", tier)));
+            }
+            tracing::info!(
+                "Payment window tier {} removed by {}",
+                tier, admin.as_deref().unwrap_or("unauthenticated admin")
+            );
+        }
+    }
+
+    get_payment_windows_handler(State(state)).await
+}
+
+/// Stale orders listed by the admin endpoint
+const STALE_ORDERS_LIMIT: i64 = 100;
+
+#[derive(Debug, Deserialize)]
+pub struct SetOrderStalenessRequest {
+    /// Days without fills or seller activity before an order is stale
+    pub stale_after_days: i32,
+    /// Days between nudges to the seller of a stale order
+    pub notify_every_days: i32,
+}
+
+#[derive(Debug, Serialize)]
+pub struct OrderStalenessResponse {
+    pub settings: DbStalenessSettings,
+    /// Currently stale orders, longest idle first (at most 100)
+    pub stale_orders: Vec<DbStaleOrder>,
+}
+
+/// GET /api/admin/order-staleness
+/// Staleness thresholds and the orders currently past them
+pub async fn get_order_staleness_handler(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+) -> Result<Json<OrderStalenessResponse>, ApiError> {
+    state.admin_keys.authenticate(&headers)?;
+
+    let settings = state.db.get_order_staleness_settings().await?;
+    let stale_before = chrono::Utc::now() - chrono::Duration::days(settings.stale_after_days as i64);
+    let stale_orders = state.db.get_stale_orders(None, stale_before, Some(STALE_ORDERS_LIMIT)).await?;
+    Ok(Json(OrderStalenessResponse { settings, stale_orders }))
+}
+
+/// POST /api/admin/order-staleness
+/// Set when orders count as stale and how often their sellers are nudged
+pub async fn set_order_staleness_handler(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Json(req): Json<SetOrderStalenessRequest>,
+) -> Result<Json<OrderStalenessResponse>, ApiError> {
+    let admin = state.admin_keys.authenticate(&headers)?;
+    if req.stale_after_days <= 0 || req.notify_every_days <= 0 {
+        return Err(ApiError::BadRequest("stale_after_days and notify_every_days must be positive".to_string()));
+    }
+
+    state
+        .db
+        .set_order_staleness_settings(req.stale_after_days, req.notify_every_days, admin.as_deref())
+        .await?;
+    tracing::info!(
+        "Orders stale after {} days, sellers nudged every {} days (set by {})",
+        req.stale_after_days, req.notify_every_days, admin.as_deref().unwrap_or("unauthenticated admin")
+    );
+
+    get_order_staleness_handler(State(state)).await
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ScreeningQuery {
+    /// Max refusals listed (default 100)
+    pub limit: Option<i64>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ScreeningResponse {
+    pub enabled: bool,
+    pub provider: String,
+    /// Addresses whose latest verdict is a flag (their orders are out of matching)
+    pub flagged: Vec<DbScreeningVerdict>,
+    /// Latest refusals, newest first
+    pub refusals: Vec<DbScreeningRefusal>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ClearScreeningRequest {
+    pub address: String,
+}
+
+/// GET /api/admin/screening?limit=
+/// Flagged addresses and the latest refusals of service
+pub async fn get_screening_handler(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Query(params): Query<ScreeningQuery>,
+) -> Result<Json<ScreeningResponse>, ApiError> {
+    state.admin_keys.authenticate(&headers)?;
+
+    let limit = params.limit.unwrap_or(100).clamp(1, 1000);
+    Ok(Json(ScreeningResponse {
+        enabled: state.screening.is_enabled(),
+        provider: state.screening.provider().to_string(),
+        flagged: state.db.list_flagged_addresses().await?,
+        refusals: state.db.get_screening_refusals(limit).await?,
     }))
 }
 
+/// POST /api/admin/screening/clear
+/// Drop an address's cached verdict (e.g. after the provider corrected a false positive);
+/// it is screened again on its next fill or order. Past refusals stay in the log.
+pub async fn clear_screening_handler(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Json(req): Json<ClearScreeningRequest>,
+) -> Result<Json<ScreeningResponse>, ApiError> {
+    let admin = state.admin_keys.authenticate(&headers)?;
+    let address: EthAddress = req.address.parse()?;
+
+    if !state.db.clear_screening_verdict(&address).await? {
+        return Err(ApiError::NotFound(format!("No screening verdict for {}", address)));
+    }
+    tracing::info!(
+        "Screening verdict for {} cleared by {}",
+        address, admin.as_deref().unwrap_or("unauthenticated admin")
+    );
+
+    get_screening_handler(State(state), headers, Query(ScreeningQuery { limit: None })).await
+}
+
+#[derive(Debug, Deserialize)]
+pub struct SpendingLimitsQuery {
+    /// Also report this buyer's tier, caps and usage
+    pub buyer: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct SetSpendingLimitTierRequest {
+    pub tier: i32,
+    /// CNY cents a buyer of the tier may trade per 24h (omit for no cap)
+    pub daily_limit_cny: Option<i64>,
+    /// CNY cents a buyer of the tier may trade per 30 days (omit for no cap)
+    pub monthly_limit_cny: Option<i64>,
+    /// Remove the tier instead
+    #[serde(default)]
+    pub remove: bool,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct SetBuyerTierRequest {
+    pub buyer: String,
+    /// 0 puts the buyer back in the default tier
+    pub tier: i32,
+    /// KYC case or ticket the decision is based on
+    pub reference: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct SpendingLimitsResponse {
+    pub tiers: Vec<DbSpendingLimitTier>,
+    /// Buyers above tier 0
+    pub buyers: Vec<DbBuyerTier>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub buyer_limits: Option<BuyerLimits>,
+}
+
+/// GET /api/admin/spending-limits?buyer=
+/// Spending limit tiers and the buyers placed above tier 0
+pub async fn get_spending_limits_handler(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Query(params): Query<SpendingLimitsQuery>,
+) -> Result<Json<SpendingLimitsResponse>, ApiError> {
+    state.admin_keys.authenticate(&headers)?;
+
+    let buyer_limits = match params.buyer.as_deref().map(str::parse::<EthAddress>).transpose()? {
+        Some(buyer) => buyer_limits(&state.db, &buyer).await?,
+        None => None,
+    };
+    Ok(Json(SpendingLimitsResponse {
+        tiers: state.db.list_spending_limit_tiers().await?,
+        buyers: state.db.list_buyer_tiers().await?,
+        buyer_limits,
+    }))
+}
+
+/// POST /api/admin/spending-limits
+/// Set or remove the rolling 24h / 30d caps of a buyer tier. A buyer gets the caps of the
+/// highest configured tier at or below their own; without tiers nobody is limited.
+pub async fn set_spending_limit_tier_handler(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Json(req): Json<SetSpendingLimitTierRequest>,
+) -> Result<Json<SpendingLimitsResponse>, ApiError> {
+    let admin = state.admin_keys.authenticate(&headers)?;
+    if req.tier < 0 {
+        return Err(ApiError::BadRequest("tier must not be negative".to_string()));
+    }
+
+    if req.remove {
+        if !state.db.remove_spending_limit_tier(req.tier).await? {
+            return Err(ApiError::NotFound(format!("No spending limit tier {}", req.tier)));
+        }
+        tracing::info!(
+            "Spending limit tier {} removed by {}",
+            req.tier, admin.as_deref().unwrap_or("unauthenticated admin")
+        );
+    } else {
+        if req.daily_limit_cny.is_some_and(|limit| limit < 0) || req.monthly_limit_cny.is_some_and(|limit| limit < 0) {
+            return Err(ApiError::BadRequest("Limits must not be negative".to_string()));
+        }
+        state
+            .db
+            .set_spending_limit_tier(req.tier, req.daily_limit_cny, req.monthly_limit_cny, admin.as_deref())
+            .await?;
+        let cap = |limit: Option<i64>| limit.map(|l| format!("{} CNY", format_cny_cents(U256::from(l as u64)))).unwrap_or_else(|| "no cap".to_string());
+        tracing::info!(
+            "Spending limit tier {} set to {} per 24h, {} per 30d by {}",
+            req.tier, cap(req.daily_limit_cny), cap(req.monthly_limit_cny), admin.as_deref().unwrap_or("unauthenticated admin")
+        );
+    }
+
+    get_spending_limits_handler(State(state), headers, Query(SpendingLimitsQuery { buyer: None })).await
+}
+
+/// POST /api/admin/buyer-tiers
+/// Put a buyer in a spending limit tier by hand (e.g. after a manual KYC review)
+pub async fn set_buyer_tier_handler(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Json(req): Json<SetBuyerTierRequest>,
+) -> Result<Json<SpendingLimitsResponse>, ApiError> {
+    let admin = state.admin_keys.authenticate(&headers)?;
+    let buyer: EthAddress = req.buyer.parse()?;
+    if req.tier < 0 {
+        return Err(ApiError::BadRequest("tier must not be negative".to_string()));
+    }
+
+    state
+        .db
+        .set_buyer_tier(&buyer, req.tier, SOURCE_ADMIN, req.reference.as_deref(), admin.as_deref())
+        .await?;
+    tracing::info!(
+        "Buyer {} put in spending limit tier {} by {}",
+        buyer, req.tier, admin.as_deref().unwrap_or("unauthenticated admin")
+    );
+
+    get_spending_limits_handler(State(state), headers, Query(SpendingLimitsQuery { buyer: Some(buyer.to_string()) })).await
+}
+
+#[derive(Debug, Deserialize)]
+pub struct SetMaintenanceRequest {
+    /// Turn the switch on or off (omit to leave it)
+    pub enabled: Option<bool>,
+    /// Message returned to refused requests while the switch is on
+    pub reason: Option<String>,
+    /// Replaces the scheduled windows (omit to leave them, [] to clear)
+    pub windows: Option<Vec<MaintenanceWindow>>,
+}
+
+/// GET /api/admin/maintenance
+/// Maintenance switch and scheduled windows on this replica
+pub async fn get_maintenance_handler(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+) -> Result<Json<MaintenanceStatus>, ApiError> {
+    state.admin_keys.authenticate(&headers)?;
+
+    Ok(Json(state.maintenance.status(chrono::Utc::now())))
+}
+
+/// POST /api/admin/maintenance
+/// Toggle maintenance mode and/or schedule windows. While active, mutating endpoints
+/// (other than admin ones) return 503. Applies to this replica only.
+pub async fn set_maintenance_handler(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Json(req): Json<SetMaintenanceRequest>,
+) -> Result<Json<MaintenanceStatus>, ApiError> {
+    let admin = state.admin_keys.authenticate(&headers)?;
+
+    if let Some(windows) = &req.windows {
+        for window in windows {
+            window.validate().map_err(ApiError::BadRequest)?;
+        }
+    }
+
+    if let Some(enabled) = req.enabled {
+        let reason = req.reason.filter(|r| !r.trim().is_empty());
+        state.maintenance.set(enabled, reason, admin.clone());
+        tracing::warn!(
+            "🚧 Maintenance mode {} by {}",
+            if enabled { "enabled" } else { "disabled" },
+            admin.as_deref().unwrap_or("unauthenticated admin")
+        );
+    }
+    if let Some(windows) = req.windows {
+        tracing::info!(
+            "🚧 {} maintenance windows scheduled by {}",
+            windows.len(),
+            admin.as_deref().unwrap_or("unauthenticated admin")
+        );
+        state.maintenance.set_windows(windows);
+    }
+
+    Ok(Json(state.maintenance.status(chrono::Utc::now())))
+}
+
+#[derive(Debug, Deserialize)]
+pub struct CreateApiKeyRequest {
+    pub name: String,                       // Letters, digits, '_', '.', '-' (labels usage metrics)
+    pub wallet: String,                     // Wallet the key trades as
+    pub scopes: Vec<String>,                // read and/or trade
+    pub rate_limit_per_minute: Option<i32>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ApiKeyDto {
+    pub key_id: Uuid,
+    pub key_prefix: String,
+    pub name: String,
+    pub wallet: EthAddress,
+    pub scopes: Vec<String>,
+    pub rate_limit_per_minute: i32,
+    pub created_by: Option<String>,
+    pub created_at: chrono::DateTime<chrono::Utc>,
+    pub last_used_at: Option<chrono::DateTime<chrono::Utc>>,
+    pub revoked_at: Option<chrono::DateTime<chrono::Utc>>,
+    /// Usage seen by this replica since it started
+    pub usage: ApiKeyUsage,
+}
+
+impl ApiKeyDto {
+    fn new(key: DbApiKey, usage: ApiKeyUsage) -> Self {
+        Self {
+            key_id: key.key_id,
+            key_prefix: key.key_prefix,
+            name: key.name,
+            wallet: key.wallet,
+            scopes: key.scopes,
+            rate_limit_per_minute: key.rate_limit_per_minute,
+            created_by: key.created_by,
+            created_at: key.created_at,
+            last_used_at: key.last_used_at,
+            revoked_at: key.revoked_at,
+            usage,
+        }
+    }
+}
+
+#[derive(Debug, Serialize)]
+pub struct CreateApiKeyResponse {
+    #[serde(flatten)]
+    pub key: ApiKeyDto,
+    /// The secret, shown only here - send it as the x-api-key header
+    pub secret: String,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ApiKeyListResponse {
+    pub keys: Vec<ApiKeyDto>,
+}
+
+/// POST /api/admin/api-keys
+/// Issue a market-maker API key bound to a wallet
+pub async fn create_api_key_handler(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Json(req): Json<CreateApiKeyRequest>,
+) -> Result<Json<CreateApiKeyResponse>, ApiError> {
+    let admin = state.admin_keys.require(&headers)?;
+
+    if req.name.is_empty()
+        || req.name.len() > 64
+        || !req.name.chars().all(|c| c.is_ascii_alphanumeric() || matches!(c, '_' | '.' | '-'))
+    {
+        return Err(ApiError::BadRequest("name must be 1-64 letters, digits, '_', '.' or '-'".to_string()));
+    }
+    let mut scopes = req.scopes.clone();
+    scopes.sort();
+    scopes.dedup();
+    if scopes.is_empty() || scopes.iter().any(|s| s != SCOPE_READ && s != SCOPE_TRADE) {
+        return Err(ApiError::BadRequest(format!("scopes must be a non-empty subset of [{}, {}]", SCOPE_READ, SCOPE_TRADE)));
+    }
+    let rate_limit_per_minute = req.rate_limit_per_minute.unwrap_or(DEFAULT_RATE_LIMIT_PER_MINUTE);
+    if rate_limit_per_minute <= 0 {
+        return Err(ApiError::BadRequest("rate_limit_per_minute must be positive".to_string()));
+    }
+
+    let secret = generate_secret();
+    let key = state
+        .db
+        .create_api_key(&NewApiKey {
+            key_hash: hash_secret(&secret),
+            key_prefix: display_prefix(&secret),
+            name: req.name,
+            wallet: req.wallet.parse()?,
+            scopes,
+            rate_limit_per_minute,
+            created_by: admin.clone(),
+        })
+        .await
+        .map_err(|e| match e {
+            DbError::SqlxError(sqlx::Error::Database(db)) if db.is_unique_violation() => {
+                ApiError::BadRequest("An active API key with this name already exists".to_string())
+            }
+            e => e.into(),
+        })?;
+
+    tracing::info!("API key {} ({}) issued for {} by {:?}", key.name, key.key_prefix, key.wallet, admin);
+
+    Ok(Json(CreateApiKeyResponse { key: ApiKeyDto::new(key, ApiKeyUsage::default()), secret }))
+}
+
+/// GET /api/admin/api-keys
+/// All keys (revoked included) with this replica's usage counts
+pub async fn list_api_keys_handler(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+) -> Result<Json<ApiKeyListResponse>, ApiError> {
+    state.admin_keys.require(&headers)?;
+
+    let keys = state
+        .db
+        .list_api_keys()
+        .await?
+        .into_iter()
+        .map(|key| {
+            let usage = state.metrics.api_keys.usage(&key.name);
+            ApiKeyDto::new(key, usage)
+        })
+        .collect();
+
+    Ok(Json(ApiKeyListResponse { keys }))
+}
+
+/// POST /api/admin/api-keys/:key_id/revoke
+/// Revoke a key (immediately on this replica, within the key cache TTL elsewhere)
+pub async fn revoke_api_key_handler(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Path(key_id): Path<Uuid>,
+) -> Result<Json<serde_json::Value>, ApiError> {
+    let admin = state.admin_keys.require(&headers)?;
+
+    if !state.db.revoke_api_key(key_id).await? {
+        return Err(ApiError::NotFound(format!("No active API key {}", key_id)));
+    }
+    state.api_keys.invalidate(key_id);
+    tracing::info!("API key {} revoked by {:?}", key_id, admin);
+
+    Ok(Json(serde_json::json!({ "key_id": key_id, "revoked": true })))
+}
+
+#[derive(Debug, Deserialize)]
+pub struct AnomaliesQuery {
+    /// Window in seconds (default ANOMALY_WINDOW_SECS)
+    pub window_secs: Option<i64>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct AnomaliesResponse {
+    pub window_secs: i64,
+    pub mismatch_rate_threshold: f64,
+    pub min_samples: i64,
+    /// Validation and submission outcomes per receipt template, most used first
+    pub templates: Vec<DbFailureStats>,
+    /// The same per seller
+    pub sellers: Vec<DbFailureStats>,
+    pub flagged_templates: Vec<DbFlaggedTemplate>,
+}
+
+/// GET /api/admin/anomalies?window_secs=
+/// PaymentDetailsMismatch and proof failure counts per template and seller, with the flagged templates
+pub async fn get_anomalies_handler(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Query(params): Query<AnomaliesQuery>,
+) -> Result<Json<AnomaliesResponse>, ApiError> {
+    state.admin_keys.authenticate(&headers)?;
+
+    let config = AnomalyConfig::from_env();
+    let window_secs = params.window_secs.unwrap_or(config.window_secs).clamp(60, 30 * 86400);
+    let since = chrono::Utc::now() - chrono::Duration::seconds(window_secs);
+    let (templates, sellers) = state.db.get_failure_stats(since).await?;
+
+    Ok(Json(AnomaliesResponse {
+        window_secs,
+        mismatch_rate_threshold: config.mismatch_rate,
+        min_samples: config.min_samples,
+        templates,
+        sellers,
+        flagged_templates: state.db.get_flagged_templates().await?,
+    }))
+}
+
+/// POST /api/admin/anomalies/templates/:template/clear
+/// Acknowledge a flagged template (removes the /api/status banner). It isn't flagged
+/// again for the failures already in the window.
+pub async fn clear_template_flag_handler(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Path(template): Path<String>,
+) -> Result<Json<serde_json::Value>, ApiError> {
+    let admin = state.admin_keys.authenticate(&headers)?;
+
+    if !state.db.clear_template_flag(&template, admin.as_deref()).await? {
+        return Err(ApiError::NotFound(format!("Template {} is not flagged", template)));
+    }
+    tracing::info!("🚨 Template {} flag cleared by {}", template, admin.as_deref().unwrap_or("unauthenticated admin"));
+
+    Ok(Json(serde_json::json!({ "template": template, "cleared": true })))
+}
+
+#[derive(Debug, Deserialize)]
+pub struct DeadLettersQuery {
+    /// pending | queued | reprocessed | discarded (default: all)
+    pub status: Option<String>,
+    pub limit: Option<i64>,
+}
+
+/// GET /api/admin/dead-letters?status=&limit=
+/// Contract events the listener failed to apply, oldest first
+pub async fn list_dead_letters_handler(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Query(params): Query<DeadLettersQuery>,
+) -> Result<Json<Vec<DbDeadLetter>>, ApiError> {
+    state.admin_keys.authenticate(&headers)?;
+    if let Some(status) = params.status.as_deref() {
+        if ![DEAD_LETTER_PENDING, DEAD_LETTER_QUEUED, DEAD_LETTER_REPROCESSED, DEAD_LETTER_DISCARDED].contains(&status) {
+            return Err(ApiError::BadRequest(format!(
+                "Invalid status '{}': expected pending, queued, reprocessed or discarded",
+                status
+            )));
+        }
+    }
+
+    let limit = params.limit.unwrap_or(100).clamp(1, 500);
+    Ok(Json(state.db.list_dead_letters(params.status.as_deref(), limit).await?))
+}
+
+/// POST /api/admin/dead-letters/:id/reprocess
+/// Queue a pending dead letter; the event listener replays it on its next poll and
+/// marks it reprocessed, or pending again with the new error
+pub async fn reprocess_dead_letter_handler(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Path(id): Path<i64>,
+) -> Result<Json<DbDeadLetter>, ApiError> {
+    let admin = state.admin_keys.authenticate(&headers)?;
+    update_dead_letter(&state, id, admin, true).await
+}
+
+/// POST /api/admin/dead-letters/:id/discard
+/// Give up on a pending dead letter (e.g. after fixing the row by hand)
+pub async fn discard_dead_letter_handler(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Path(id): Path<i64>,
+) -> Result<Json<DbDeadLetter>, ApiError> {
+    let admin = state.admin_keys.authenticate(&headers)?;
+    update_dead_letter(&state, id, admin, false).await
+}
+
+async fn update_dead_letter(state: &AppState, id: i64, admin: Option<String>, reprocess: bool) -> Result<Json<DbDeadLetter>, ApiError> {
+    let dead_letter = state
+        .db
+        .get_dead_letter(id)
+        .await?
+        .ok_or_else(|| ApiError::NotFound(format!("Dead letter {} not found", id)))?;
+
+    let updated = if reprocess {
+        state.db.queue_dead_letter(id, admin.as_deref()).await?
+    } else {
+        state.db.discard_dead_letter(id, admin.as_deref()).await?
+    };
+    if !updated {
+        return Err(ApiError::BadRequest(format!("Dead letter {} is {}, not pending", id, dead_letter.status)));
+    }
+    tracing::info!(
+        "📮 Dead letter #{} ({} in tx {}) {} by {}",
+        id,
+        dead_letter.event_type,
+        dead_letter.tx_hash,
+        if reprocess { "queued for reprocessing" } else { "discarded" },
+        admin.as_deref().unwrap_or("unauthenticated admin")
+    );
+
+    state
+        .db
+        .get_dead_letter(id)
+        .await?
+        .map(Json)
+        .ok_or_else(|| ApiError::NotFound(format!("Dead letter {} not found", id)))
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ProverTranscriptsQuery {
+    pub limit: Option<i64>,
+}
+
+/// A prover transcript with its entries as JSON
+#[derive(Debug, Serialize)]
+pub struct ProverTranscriptResponse {
+    pub id: i64,
+    pub kind: String,
+    pub program_id: String,
+    pub axiom_id: Option<String>,
+    pub status: String,
+    pub error: Option<String>,
+    pub entries: serde_json::Value,
+    pub started_at: chrono::DateTime<chrono::Utc>,
+    pub finished_at: chrono::DateTime<chrono::Utc>,
+}
+
+impl From<DbProverTranscript> for ProverTranscriptResponse {
+    fn from(transcript: DbProverTranscript) -> Self {
+        Self {
+            id: transcript.id,
+            kind: transcript.kind,
+            program_id: transcript.program_id,
+            axiom_id: transcript.axiom_id,
+            status: transcript.status,
+            error: transcript.error,
+            entries: serde_json::from_str(&transcript.entries).unwrap_or(serde_json::Value::Null),
+            started_at: transcript.started_at,
+            finished_at: transcript.finished_at,
+        }
+    }
+}
+
+/// GET /api/admin/trades/:trade_id/prover-transcripts?limit=
+/// Axiom requests and terminal responses of the trade's execution and proof jobs,
+/// newest first (API key redacted, input streams summarized)
+pub async fn get_prover_transcripts_handler(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Path(trade_id): Path<String>,
+    Query(params): Query<ProverTranscriptsQuery>,
+) -> Result<Json<Vec<ProverTranscriptResponse>>, ApiError> {
+    state.admin_keys.authenticate(&headers)?;
+    let trade_id = trade_id.parse::<TradeId>()?.to_string();
+
+    let limit = params.limit.unwrap_or(20).clamp(1, 100);
+    let transcripts = state.db.get_prover_transcripts(&trade_id, limit).await?;
+    Ok(Json(transcripts.into_iter().map(ProverTranscriptResponse::from).collect()))
+}
+
+/// GET /api/admin/prover-budget
+/// Axiom credits spent this month, the configured budget and what is left of it
+/// (jobs the balance can't cover are refused with 503 prover_budget_exhausted)
+pub async fn get_prover_budget_handler(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+) -> Result<Json<BudgetBalance>, ApiError> {
+    state.admin_keys.authenticate(&headers)?;
+
+    let budget = state.prover_budget.as_ref()
+        .ok_or_else(|| ApiError::ServiceUnavailable("Prover budget tracking not enabled".to_string()))?;
+    Ok(Json(budget.balance().await?))
+}
+
+#[derive(Debug, Serialize)]
+pub struct RuntimeConfigResponse {
+    /// Axiom status poll pacing and the poll budgets per operation
+    pub axiom_polls: PollConfig,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct SetRuntimeConfigRequest {
+    /// Budget for execution polls (unchanged when omitted)
+    pub execute: Option<PollBudget>,
+    /// Budget for EVM proof polls (unchanged when omitted)
+    pub evm_proof: Option<PollBudget>,
+}
+
+/// GET /api/admin/runtime-config
+/// Settings that can be tuned without a redeploy
+pub async fn get_runtime_config_handler(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+) -> Result<Json<RuntimeConfigResponse>, ApiError> {
+    state.admin_keys.authenticate(&headers)?;
+    Ok(Json(RuntimeConfigResponse { axiom_polls: state.poll_scheduler.config() }))
+}
+
+/// POST /api/admin/runtime-config
+/// Replace the Axiom poll budgets of new jobs (back to the env values on restart)
+pub async fn set_runtime_config_handler(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Json(req): Json<SetRuntimeConfigRequest>,
+) -> Result<Json<RuntimeConfigResponse>, ApiError> {
+    let admin = state.admin_keys.authenticate(&headers)?;
+
+    let mut budgets = state.poll_scheduler.budgets();
+    for (name, budget, current) in [
+        ("execute", req.execute, &mut budgets.execute),
+        ("evm_proof", req.evm_proof, &mut budgets.evm_proof),
+    ] {
+        let Some(budget) = budget else { continue };
+        budget.validate().map_err(|e| ApiError::BadRequest(format!("{}: {}", name, e)))?;
+        *current = budget;
+    }
+    state.poll_scheduler.set_budgets(budgets);
+    tracing::info!(
+        "Axiom poll budgets set to execute {:?}, evm_proof {:?} (set by {})",
+        budgets.execute, budgets.evm_proof, admin.as_deref().unwrap_or("unauthenticated admin")
+    );
+
+    get_runtime_config_handler(State(state), headers).await
+}
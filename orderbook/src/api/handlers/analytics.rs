@@ -0,0 +1,223 @@
+use axum::{
+    extract::{Query, State},
+    Json,
+};
+use chrono::{DateTime, Duration, Utc};
+use ethers::types::U256;
+use serde::{Deserialize, Serialize};
+
+use crate::api::{error::{ApiError, ApiResult}, state::AppState};
+use crate::blockchain::address::EthAddress;
+use crate::db::models::{DbActionRelayerCost, DbLifecycleFunnel, DbOrderbookSnapshot, DbTradeRelayerCost};
+
+#[derive(Debug, Deserialize)]
+pub struct RelayerCostsQuery {
+    /// Look-back window in days (default 30)
+    pub days: Option<i64>,
+    /// Max trades listed (default 50)
+    pub limit: Option<i64>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct RelayerCostsResponse {
+    pub since: DateTime<Utc>,
+    pub tx_count: i64,
+    pub gas_used: String,
+    pub cost_wei: String,
+    /// All spend in the window (reverted txs included) divided by the trades it was attributed to (wei)
+    pub avg_cost_per_trade_wei: String,
+    pub by_action: Vec<DbActionRelayerCost>,
+    pub by_trade: Vec<DbTradeRelayerCost>,
+}
+
+fn sum_decimal<'a>(values: impl Iterator<Item = &'a str>) -> U256 {
+    values
+        .filter_map(|v| U256::from_dec_str(v).ok())
+        .fold(U256::zero(), |acc, v| acc.saturating_add(v))
+}
+
+/// GET /api/analytics/relayer-costs
+/// Gas the relayer paid, per action and per trade, for pricing operator fees
+pub async fn relayer_costs_handler(
+    State(state): State<AppState>,
+    Query(params): Query<RelayerCostsQuery>,
+) -> ApiResult<Json<RelayerCostsResponse>> {
+    let days = params.days.unwrap_or(30).clamp(1, 365);
+    let limit = params.limit.unwrap_or(50).clamp(1, 500);
+    let since = Utc::now() - Duration::days(days);
+
+    let by_action = state.db.get_relayer_costs_by_action(since).await?;
+    let by_trade = state.db.get_relayer_costs_by_trade(since, limit).await?;
+
+    let tx_count = by_action.iter().map(|a| a.tx_count).sum();
+    let gas_used = sum_decimal(by_action.iter().map(|a| a.gas_used.as_str()));
+    let cost_wei = sum_decimal(by_action.iter().map(|a| a.cost_wei.as_str()));
+
+    let trade_count = state.db.count_relayer_cost_trades(since).await?;
+    let avg_cost_per_trade_wei = if trade_count > 0 {
+        cost_wei / U256::from(trade_count as u64)
+    } else {
+        U256::zero()
+    };
+
+    Ok(Json(RelayerCostsResponse {
+        since,
+        tx_count,
+        gas_used: gas_used.to_string(),
+        cost_wei: cost_wei.to_string(),
+        avg_cost_per_trade_wei: avg_cost_per_trade_wei.to_string(),
+        by_action,
+        by_trade,
+    }))
+}
+
+#[derive(Debug, Deserialize)]
+pub struct OrderbookHistoryQuery {
+    /// Only this token (default all tokens)
+    pub token: Option<String>,
+    /// Look-back window in hours (default 24)
+    pub hours: Option<i64>,
+    /// Max snapshots returned (default 2000)
+    pub limit: Option<i64>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct OrderbookHistoryResponse {
+    pub since: DateTime<Utc>,
+    pub until: DateTime<Utc>,
+    /// One entry per token per bucket, oldest first
+    pub snapshots: Vec<DbOrderbookSnapshot>,
+}
+
+/// GET /api/analytics/orderbook-history
+/// Liquidity, order count and best/weighted rates per token over time, for charting
+pub async fn orderbook_history_handler(
+    State(state): State<AppState>,
+    Query(params): Query<OrderbookHistoryQuery>,
+) -> ApiResult<Json<OrderbookHistoryResponse>> {
+    let token = params.token.as_deref().map(str::parse::<EthAddress>).transpose()?;
+    let hours = params.hours.unwrap_or(24).clamp(1, 24 * 90);
+    let limit = params.limit.unwrap_or(2000).clamp(1, 10_000);
+    let until = Utc::now();
+    let since = until - Duration::hours(hours);
+
+    let snapshots = state.db.get_orderbook_history(token.as_ref(), since, until, limit).await?;
+
+    Ok(Json(OrderbookHistoryResponse { since, until, snapshots }))
+}
+
+#[derive(Debug, Deserialize)]
+pub struct FunnelQuery {
+    /// Only orders of this token (default all tokens)
+    pub token: Option<String>,
+    /// Orders created from (RFC 3339, default 30 days before `to`)
+    pub from: Option<DateTime<Utc>>,
+    /// Orders created before (RFC 3339, default now)
+    pub to: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug, Serialize, PartialEq)]
+pub struct FunnelStage {
+    pub stage: &'static str,
+    /// What is counted: orders or trades
+    pub unit: &'static str,
+    pub count: i64,
+    /// Share of the previous stage that reached this one (none when the unit changes or
+    /// the previous stage is empty)
+    pub conversion_rate: Option<f64>,
+    /// How many of the previous stage stopped short of this one
+    pub dropped: Option<i64>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct FunnelResponse {
+    pub from: DateTime<Utc>,
+    pub to: DateTime<Utc>,
+    pub token: Option<EthAddress>,
+    /// created -> matched -> filled -> pdf_uploaded -> validated -> proven -> settled
+    pub stages: Vec<FunnelStage>,
+    /// Filled trades that expired instead of settling
+    pub expired: i64,
+}
+
+/// The funnel's stages in lifecycle order, each compared to the one before it
+fn funnel_stages(funnel: &DbLifecycleFunnel) -> Vec<FunnelStage> {
+    let counts = [
+        ("created", "orders", funnel.orders_created),
+        ("matched", "orders", funnel.orders_matched),
+        ("filled", "trades", funnel.trades_filled),
+        ("pdf_uploaded", "trades", funnel.pdf_uploaded),
+        ("validated", "trades", funnel.validated),
+        ("proven", "trades", funnel.proven),
+        ("settled", "trades", funnel.settled),
+    ];
+
+    let mut stages: Vec<FunnelStage> = Vec::with_capacity(counts.len());
+    for (stage, unit, count) in counts {
+        let previous = stages.last().filter(|p| p.unit == unit).map(|p| p.count);
+        stages.push(FunnelStage {
+            stage,
+            unit,
+            count,
+            conversion_rate: previous.filter(|&p| p > 0).map(|p| count as f64 / p as f64),
+            dropped: previous.map(|p| p - count),
+        });
+    }
+    stages
+}
+
+/// GET /api/analytics/funnel
+/// How far orders created in a period got through the trade lifecycle, to see where users drop off
+pub async fn funnel_handler(
+    State(state): State<AppState>,
+    Query(params): Query<FunnelQuery>,
+) -> ApiResult<Json<FunnelResponse>> {
+    let token = params.token.as_deref().map(str::parse::<EthAddress>).transpose()?;
+    let to = params.to.unwrap_or_else(Utc::now);
+    let from = params.from.unwrap_or(to - Duration::days(30));
+    if from >= to {
+        return Err(ApiError::BadRequest("from must be before to".to_string()));
+    }
+
+    let funnel = state.db.get_lifecycle_funnel(token.as_ref(), from, to).await?;
+
+    Ok(Json(FunnelResponse {
+        from,
+        to,
+        token,
+        stages: funnel_stages(&funnel),
+        expired: funnel.expired,
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_funnel_stages() {
+        let funnel = DbLifecycleFunnel {
+            orders_created: 10,
+            orders_matched: 4,
+            trades_filled: 6,
+            pdf_uploaded: 5,
+            validated: 4,
+            proven: 0,
+            settled: 0,
+            expired: 1,
+        };
+        let stages = funnel_stages(&funnel);
+
+        assert_eq!(stages.iter().map(|s| s.stage).collect::<Vec<_>>(), vec![
+            "created", "matched", "filled", "pdf_uploaded", "validated", "proven", "settled"
+        ]);
+        assert_eq!((stages[0].conversion_rate, stages[0].dropped), (None, None));
+        assert_eq!((stages[1].conversion_rate, stages[1].dropped), (Some(0.4), Some(6)));
+        // Orders to trades isn't a conversion
+        assert_eq!((stages[2].conversion_rate, stages[2].dropped), (None, None));
+        assert_eq!((stages[3].conversion_rate, stages[3].dropped), (Some(5.0 / 6.0), Some(1)));
+        assert_eq!((stages[5].conversion_rate, stages[5].dropped), (Some(0.0), Some(4)));
+        // Nothing to convert from
+        assert_eq!((stages[6].conversion_rate, stages[6].dropped), (None, Some(0)));
+    }
+}
@@ -1,17 +1,41 @@
 use axum::{
-    extract::{Path, State},
-    Json,
+    extract::{Path, Query, State},
+    Extension, Json,
 };
+use rust_decimal::Decimal;
 use serde::{Deserialize, Serialize};
-use ethers::types::U256;
+use std::str::FromStr;
+use std::sync::{Arc, Mutex};
+use ethers::types::{H256, U256};
+use uuid::Uuid;
 
 use crate::api::{
+    api_keys::ApiKeyIdentity,
+    clock::{server_time, with_timing, TradeTiming, TradeView},
+    display::{DisplayQuery, TradeDisplay},
     error::{ApiError, ApiResult},
     state::AppState,
-    matching::{MatchPlan, Fill},
+    matching::{FillLimits, MatchPlan, Fill},
 };
-use crate::blockchain::types::{order_id_to_bytes32, trade_id_to_bytes32};
+use crate::blockchain::types::{
+    decode_settlement_revert, fill_value_cny, format_cny_cents, validate_payment_nonce,
+};
+use crate::anomalies::{record_outcome, UNKNOWN_TEMPLATE};
+use crate::blockchain::address::EthAddress;
+use crate::blockchain::client::{EthereumClient, EthereumClientError, GasOverride, RelayerTx};
+use crate::blockchain::ids::{OrderId, TradeId};
+use crate::blockchain::settlement_batch::{OnSent, ProofSubmission};
+use crate::db::DbError;
+use crate::db::anomalies::{OUTCOME_MISMATCH, OUTCOME_OK, OUTCOME_PROOF_FAILED, STAGE_SUBMISSION};
+use crate::db::models::{DbOrder, DbTrade};
+use crate::db::proof_submissions::{PROOF_SUBMISSION_LEASE_SECS, SUBMISSION_FAILED, SUBMISSION_SUBMITTED, SUBMISSION_SUBMITTING};
+use crate::db::relayer_spend::{NewRelayerSpend, ACTION_FILL_ORDER, ACTION_SUBMIT_PROOF};
+use crate::db::screening::{CONTEXT_EXECUTE_FILL, ROLE_BUYER, ROLE_SELLER};
 use crate::db::trades::TradeRepository;
+use crate::latency::{LatencyBreakdown, PhaseTimer, PHASE_SEND, PHASE_VALIDATION};
+use crate::proof_inputs::{ProofInputs, MISMATCH_STALE_DETAILS, MISMATCH_UNKNOWN_DETAILS};
+use crate::sandbox::SandboxError;
+use crate::spending_limits;
 
 /// Request to execute fill order via relayer
 #[derive(Debug, Deserialize)]
@@ -20,6 +44,9 @@ pub struct ExecuteFillRequest {
     pub match_plan: MatchPlan,
     /// Buyer address
     pub buyer_address: String,
+    /// plan_id of the /match-intent response; the trades are recorded against the stored plan
+    #[serde(default)]
+    pub plan_id: Option<Uuid>,
 }
 
 /// Single trade result from fill
@@ -32,85 +59,406 @@ pub struct TradeResult {
     pub alipay_name: String,
     pub payment_nonce: String,
     pub expires_at: i64,
+    /// Payment window the trade got (the contract's, the same for every trade)
+    pub payment_window_secs: u64,
+    /// Window the payment window policy recommends for a trade of this token and value
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub recommended_window_secs: Option<u64>,
+    /// Set when the trade got less time than recommended
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub window_warning: Option<String>,
 }
 
 /// Response after executing fills
 #[derive(Debug, Serialize)]
 pub struct ExecuteFillResponse {
     pub trades: Vec<TradeResult>,
+    /// Where the request's time went (with ?debug=1)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub debug: Option<LatencyBreakdown>,
+}
+
+/// Query parameters of POST /api/execute-fill
+#[derive(Debug, Default, Deserialize)]
+pub struct ExecuteFillQuery {
+    /// 1 (or true) adds the latency breakdown to the response
+    #[serde(default)]
+    pub debug: Option<String>,
+}
+
+impl ExecuteFillQuery {
+    fn debug(&self) -> bool {
+        matches!(self.debug.as_deref(), Some("1" | "true"))
+    }
 }
 
 /// POST /api/execute-fill
 /// Relayer executes fillOrder() for each fill in the match plan
 pub async fn execute_fill_handler(
     State(state): State<AppState>,
+    Query(query): Query<ExecuteFillQuery>,
+    api_key: Option<Extension<ApiKeyIdentity>>,
     Json(req): Json<ExecuteFillRequest>,
 ) -> ApiResult<Json<ExecuteFillResponse>> {
-    // Check if blockchain client is available
-    let blockchain_client = state.blockchain_client
-        .as_ref()
-        .ok_or_else(|| ApiError::ServiceUnavailable(
-            "Blockchain integration not enabled".to_string()
-        ))?;
-
-    // Parse buyer address
-    let buyer_address: ethers::types::Address = req.buyer_address
-        .parse()
-        .map_err(|_| ApiError::BadRequest("Invalid buyer address".to_string()))?;
-
-    // Fetch payment window from contract
-    let payment_window = blockchain_client
-        .get_payment_window()
+    let timer = PhaseTimer::new();
+    let result = execute_fill(&state, api_key, req, &timer).await;
+
+    // Failed requests are timed too - a slow refusal still costs the buyer the wait
+    let breakdown = timer.breakdown();
+    state.metrics.execute_fill.observe(&breakdown);
+    tracing::debug!("execute-fill took {} ms: {:?}", breakdown.total_ms, breakdown.phases);
+
+    let mut response = result?;
+    if query.debug() {
+        response.debug = Some(breakdown);
+    }
+    Ok(Json(response))
+}
+
+async fn execute_fill(
+    state: &AppState,
+    api_key: Option<Extension<ApiKeyIdentity>>,
+    req: ExecuteFillRequest,
+    timer: &PhaseTimer,
+) -> ApiResult<ExecuteFillResponse> {
+    // Check if blockchain client is available (sandbox mode fills without one)
+    let blockchain_client = match &state.sandbox {
+        Some(_) => None,
+        None => Some(state.blockchain_client
+            .as_ref()
+            .ok_or_else(|| ApiError::ServiceUnavailable(
+                "Blockchain integration not enabled".to_string()
+            ))?),
+    };
+
+    // Parse buyer address (mixed case must be a valid checksum)
+    let buyer = req.buyer_address.parse::<EthAddress>()?;
+
+    // An API key only trades as the wallet it was issued for
+    if let Some(Extension(key)) = &api_key {
+        if key.wallet != buyer {
+            return Err(ApiError::Forbidden(format!(
+                "API key {} is bound to {}, not {}",
+                key.name,
+                key.wallet.to_checksum(),
+                buyer.to_checksum()
+            )));
+        }
+    }
+    let buyer_address = buyer.to_address();
+
+    if let Some(plan_id) = req.plan_id {
+        check_match_plan(state, plan_id, &buyer, &req.match_plan).await?;
+    }
+
+    let parsed_fills = timer
+        .time(PHASE_VALIDATION, validate_plan(state, blockchain_client, &buyer, &req.match_plan.fills))
+        .await?;
+    let fill_count = parsed_fills.len();
+
+    let mut trades = Vec::new();
+
+    // Execute each fill; a failure stops at that fill, after the earlier ones made trades
+    let executed: ApiResult<()> = async {
+        for (idx, (fill, order_id, fill_amount, value_cny, window)) in parsed_fills.into_iter().enumerate() {
+            tracing::info!(
+                "Executing fill {}/{}: {} USDC from order {}",
+                idx + 1,
+                fill_count,
+                fill.fill_amount,
+                fill.order_id
+            );
+
+            // One fill of an order at a time, checked against the liquidity left once it's our turn
+            // (an overlapping plan may have taken it) rather than reverting after paying for gas
+            let _fill_guard = state.fill_locks.lock(&order_id).await;
+            let remaining = timer
+                .time(PHASE_VALIDATION, async {
+                    match blockchain_client {
+                        Some(client) => client
+                            .get_order_remaining(order_id.0)
+                            .await
+                            .map_err(|e| ApiError::BlockchainError(e.to_string())),
+                        None => U256::from_dec_str(&state.db.get_order(&order_id.to_string()).await?.remaining_amount)
+                            .map_err(|e| ApiError::Internal(format!("Invalid remaining amount on order {}: {}", order_id, e))),
+                    }
+                })
+                .await?;
+            if fill_amount > remaining {
+                tracing::info!("Refusing fill of {} from order {}: only {} remaining", fill_amount, order_id, remaining);
+                return Err(ApiError::BadRequest(format!(
+                    "Fill {}/{} (order {}, amount {}) exceeds the {} remaining on the order - another buyer filled it first, match again",
+                    idx + 1,
+                    fill_count,
+                    fill.order_id,
+                    fill.fill_amount,
+                    remaining
+                )));
+            }
+
+            // Call fillOrder on blockchain
+            let Some(blockchain_client) = blockchain_client else {
+                let sandbox = state.sandbox.as_ref().expect("no blockchain client outside sandbox mode");
+                let (tx_hash, trade) = timer
+                    .time(PHASE_SEND, sandbox.fill_order(&state.db, &order_id, fill_amount, &buyer, value_cny))
+                    .await
+                    .map_err(|e| match e {
+                        SandboxError::Database(e) => ApiError::from(e),
+                        e => ApiError::BadRequest(e.to_string()),
+                    })?;
+                trades.push(TradeResult {
+                    trade_id: trade.trade_id,
+                    order_id: trade.order_id,
+                    tx_hash: format!("{:?}", tx_hash),
+                    alipay_id: fill.alipay_id.clone(),
+                    alipay_name: fill.alipay_name.clone(),
+                    payment_nonce: trade.payment_nonce,
+                    expires_at: trade.expires_at,
+                    payment_window_secs: window.granted_secs,
+                    recommended_window_secs: window.recommended_secs,
+                    window_warning: window.warning,
+                });
+                continue;
+            };
+            let (relayer_tx, trade_id, payment_nonce) = match blockchain_client
+                .fill_order(order_id.0, fill_amount, buyer_address, timer)
+                .await
+            {
+                Ok((relayer_tx, trade_id, payment_nonce)) => (relayer_tx, TradeId::from(trade_id), payment_nonce),
+                Err(e) => {
+                    if let EthereumClientError::TransactionReverted(tx) = &e {
+                        record_spend(state, tx.spend(ACTION_FILL_ORDER, None, Some(order_id.to_string()), true)).await;
+                    }
+                    return Err(ApiError::BlockchainError(e.to_string()));
+                }
+            };
+            let tx_hash = relayer_tx.tx_hash;
+            record_spend(
+                state,
+                relayer_tx.spend(ACTION_FILL_ORDER, Some(trade_id.to_string()), Some(order_id.to_string()), false),
+            )
+            .await;
+
+            tracing::info!(
+                "Fill executed: trade_id={}, tx_hash={:?}",
+                trade_id,
+                tx_hash
+            );
+
+            // Create trade result
+            trades.push(TradeResult {
+                trade_id: trade_id.to_string(),
+                order_id: order_id.to_string(),
+                tx_hash: format!("{:?}", tx_hash),
+                alipay_id: fill.alipay_id.clone(),
+                alipay_name: fill.alipay_name.clone(),
+                payment_nonce,
+                expires_at: (chrono::Utc::now().timestamp() + window.granted_secs as i64),
+                payment_window_secs: window.granted_secs,
+                recommended_window_secs: window.recommended_secs,
+                window_warning: window.warning,
+            });
+        }
+        Ok(())
+    }
+    .await;
+
+    // Audit trail only: the fills are on chain whether or not this is written, and the
+    // trades of a plan that failed part way are the ones an incident needs
+    if let Some(plan_id) = req.plan_id.filter(|_| !trades.is_empty()) {
+        let trade_ids: Vec<String> = trades.iter().map(|t| t.trade_id.clone()).collect();
+        if let Err(e) = state.db.record_match_plan_execution(plan_id, &buyer, &trade_ids).await {
+            tracing::error!("Failed to record the trades of match plan {}: {}", plan_id, e);
+        }
+    }
+    executed?;
+
+    Ok(ExecuteFillResponse { trades, debug: None })
+}
+
+/// The stored plan `plan_id` must be unexpired, matched for this buyer (or for anyone)
+/// and have the fills of the submitted plan
+async fn check_match_plan(state: &AppState, plan_id: Uuid, buyer: &EthAddress, plan: &MatchPlan) -> ApiResult<()> {
+    let stored = state
+        .db
+        .get_match_plan(plan_id)
+        .await?
+        .ok_or_else(|| ApiError::NotFound(format!("Match plan {} not found", plan_id)))?;
+    if stored.expires_at <= chrono::Utc::now() {
+        return Err(ApiError::BadRequest(format!("Match plan {} has expired - request a new one", plan_id)));
+    }
+    if stored.buyer.as_ref().is_some_and(|b| b != buyer) {
+        return Err(ApiError::BadRequest(format!("Match plan {} was matched for another buyer", plan_id)));
+    }
+    let stored_plan: MatchPlan = serde_json::from_str(&stored.plan)
+        .map_err(|e| ApiError::Internal(format!("Invalid stored match plan {}: {}", plan_id, e)))?;
+    let fills = |p: &MatchPlan| p.fills.iter().map(|f| (f.order_id.to_lowercase(), f.fill_amount.clone())).collect::<Vec<_>>();
+    if fills(&stored_plan) != fills(plan) {
+        return Err(ApiError::BadRequest(format!("Fills differ from those of match plan {}", plan_id)));
+    }
+    Ok(())
+}
+
+/// A checked fill of the plan: order, amount, CNY value and payment window
+type ParsedFill<'a> = (&'a Fill, OrderId, U256, U256, FillWindow);
+
+/// Check the buyer and every fill of the plan before any transaction is sent
+async fn validate_plan<'a>(
+    state: &AppState,
+    blockchain_client: Option<&Arc<EthereumClient>>,
+    buyer: &EthAddress,
+    fills: &'a [Fill],
+) -> ApiResult<Vec<ParsedFill<'a>>> {
+    // Sanctions / abuse screening of the buyer
+    state
+        .screening
+        .check(state.db.pool(), buyer, ROLE_BUYER, CONTEXT_EXECUTE_FILL, None)
+        .await?
+        .map_err(|refusal| ApiError::AddressFlagged { role: refusal.role, refusal_id: refusal.id })?;
+
+    // Payment window and trade limits from the cached contract config
+    let config = state
+        .chain_config()
         .await
-        .map_err(|e| ApiError::BlockchainError(format!("Failed to get payment window: {}", e)))?;
+        .ok_or_else(|| ApiError::ServiceUnavailable("Blockchain integration not enabled".to_string()))?
+        .map_err(|e| ApiError::BlockchainError(format!("Failed to get contract config: {}", e)))?;
+    if config.paused {
+        return Err(ApiError::MarketPaused);
+    }
+    let payment_window = config.payment_window;
     
     tracing::info!("Payment window from contract: {} seconds", payment_window);
 
-    let mut trades = Vec::new();
-
-    // Execute each fill
-    for (idx, fill) in req.match_plan.fills.iter().enumerate() {
-        tracing::info!(
-            "Executing fill {}/{}: {} USDC from order {}",
+    // Validate every fill against the contract's trade value limits before sending any
+    // transaction, so an out-of-range fill is rejected up front instead of reverting
+    // (possibly after earlier fills in the plan were already executed)
+    let limits = config.trade_limits();
+
+    let policy = state.db.get_token_policy().await?;
+    let window_policy = state.db.get_payment_window_policy().await?;
+
+    let fill_count = fills.len();
+    let mut parsed_fills = Vec::with_capacity(fill_count);
+    for (idx, fill) in fills.iter().enumerate() {
+        let order_id: OrderId = fill.order_id.parse()?;
+
+        // The plan comes from the client - check the synced order's token, the seller's
+        // Alipay account and fill limits, not the plan's. An order the listener hasn't
+        // synced can't be checked, so it can't be filled yet either.
+        let order = match state.db.get_order(&order_id.to_string()).await {
+            Ok(order) => order,
+            Err(DbError::OrderNotFound(_)) => {
+                return Err(ApiError::NotFound(format!(
+                    "Fill {}/{}: order {} is not in the order book (not synced yet?) - match again",
+                    idx + 1,
+                    fill_count,
+                    fill.order_id
+                )))
+            }
+            Err(e) => return Err(e.into()),
+        };
+        if policy.is_suspended(&order.token) {
+            return Err(ApiError::TokenSuspended(order.token.to_checksum()));
+        }
+        if state.db.is_alipay_id_blocked(&order.alipay_id).await? {
+            tracing::warn!("Refusing fill against order {}: seller Alipay account is blocklisted", order.order_id);
+            return Err(ApiError::SellerBlocked(order.order_id));
+        }
+        state
+            .screening
+            .check(state.db.pool(), &order.seller, ROLE_SELLER, CONTEXT_EXECUTE_FILL, Some(&order.order_id))
+            .await?
+            .map_err(|refusal| ApiError::AddressFlagged { role: refusal.role, refusal_id: refusal.id })?;
+        check_fill_limits(&order, &fill.fill_amount).map_err(|reason| ApiError::BadRequest(format!(
+            "Fill {}/{} (order {}, amount {}) is {}",
             idx + 1,
-            req.match_plan.fills.len(),
+            fill_count,
+            fill.order_id,
             fill.fill_amount,
-            fill.order_id
-        );
-
-        // Convert order ID to bytes32
-        let order_id_bytes = order_id_to_bytes32(&fill.order_id)
-            .map_err(|e| ApiError::BadRequest(format!("Invalid order ID: {}", e)))?;
+            reason
+        )))?;
 
         // Parse fill amount (must use from_dec_str to parse as decimal, not hex!)
         let fill_amount = U256::from_dec_str(&fill.fill_amount)
             .map_err(|e| ApiError::BadRequest(format!("Invalid fill amount: {}", e)))?;
 
-        // Call fillOrder on blockchain
-        let (tx_hash, trade_id, payment_nonce) = blockchain_client
-            .fill_order(order_id_bytes, fill_amount, buyer_address)
-            .await
-            .map_err(|e| ApiError::BlockchainError(e.to_string()))?;
-
-        tracing::info!(
-            "Fill executed: trade_id={}, tx_hash={:?}",
-            hex::encode(trade_id),
-            tx_hash
-        );
-
-        // Create trade result
-        trades.push(TradeResult {
-            trade_id: format!("0x{}", hex::encode(trade_id)),
-            order_id: fill.order_id.clone(),
-            tx_hash: format!("{:?}", tx_hash),
-            alipay_id: fill.alipay_id.clone(),
-            alipay_name: fill.alipay_name.clone(),
-            payment_nonce,
-            expires_at: (chrono::Utc::now().timestamp() + payment_window.as_u64() as i64),
+        // Use on-chain rate and decimals - these are what fillOrder checks against
+        let (exchange_rate, token_decimals) = match blockchain_client {
+            Some(client) => client
+                .get_order_pricing(order_id.0)
+                .await
+                .map_err(|e| ApiError::BlockchainError(e.to_string()))?,
+            None => sandbox_order_pricing(state, &order_id).await?,
+        };
+
+        let value_cny = fill_value_cny(fill_amount, exchange_rate, token_decimals);
+        limits.check(value_cny).map_err(|reason| ApiError::BadRequest(format!(
+            "Fill {}/{} (order {}, amount {}) is worth {} CNY, {}",
+            idx + 1,
+            fill_count,
+            fill.order_id,
+            fill.fill_amount,
+            format_cny_cents(value_cny),
+            reason
+        )))?;
+
+        let window = FillWindow::new(payment_window.as_u64(), window_policy.window_for(Some(&order.token), value_cny), value_cny);
+        parsed_fills.push((fill, order_id, fill_amount, value_cny, window));
+    }
+
+    // The buyer's rolling spending caps cover the whole plan
+    let plan_value_cny = parsed_fills
+        .iter()
+        .fold(U256::zero(), |acc, (_, _, _, value_cny, _)| acc.saturating_add(*value_cny));
+    let requested_cny = if plan_value_cny > U256::from(i64::MAX as u64) { i64::MAX } else { plan_value_cny.as_u64() as i64 };
+    spending_limits::check_buyer(&state.db, state.kyc_provider.as_deref(), buyer, requested_cny)
+        .await?
+        .map_err(|exceeded| {
+            tracing::info!("Refusing fills for {}: {}", buyer, exceeded);
+            ApiError::SpendingLimitExceeded(Box::new(exceeded))
+        })?;
+
+    Ok(parsed_fills)
+}
+
+/// Rate and decimals of a sandbox order (its synced rate, the sandbox's token decimals)
+async fn sandbox_order_pricing(state: &AppState, order_id: &OrderId) -> ApiResult<(U256, u8)> {
+    let sandbox = state.sandbox.as_ref().expect("sandbox pricing outside sandbox mode");
+    let order = state.db.get_order(&order_id.to_string()).await?;
+    let exchange_rate = U256::from_dec_str(&order.exchange_rate)
+        .map_err(|e| ApiError::Internal(format!("Invalid exchange rate on order {}: {}", order_id, e)))?;
+    Ok((exchange_rate, sandbox.token_decimals))
+}
+
+/// Payment window of a fill: what the contract grants against what the policy recommends.
+/// fillOrder takes no per-trade window, so a shortfall can only be pointed out to the buyer.
+struct FillWindow {
+    granted_secs: u64,
+    recommended_secs: Option<u64>,
+    warning: Option<String>,
+}
+
+impl FillWindow {
+    fn new(granted_secs: u64, recommended_secs: Option<u64>, value_cny: U256) -> Self {
+        let warning = recommended_secs.filter(|&recommended| recommended > granted_secs).map(|recommended| {
+            format!(
+                "This trade is worth {} CNY; trades this size usually need {} minutes to pay, but the payment window is {} minutes. Pay right away or split the purchase into smaller trades.",
+                format_cny_cents(value_cny),
+                recommended.div_ceil(60),
+                granted_secs / 60
+            )
         });
+        Self { granted_secs, recommended_secs, warning }
     }
+}
 
-    Ok(Json(ExecuteFillResponse { trades }))
+/// Check a fill amount against the order's seller fill limits
+fn check_fill_limits(order: &DbOrder, fill_amount: &str) -> Result<(), String> {
+    let invalid = |e: &dyn std::fmt::Display| format!("not a valid amount ({})", e);
+    let limits = FillLimits::of(order).map_err(|e| invalid(&e))?;
+    let amount = Decimal::from_str(fill_amount).map_err(|e| invalid(&e))?;
+    let remaining = Decimal::from_str(&order.remaining_amount).map_err(|e| invalid(&e))?;
+    limits.check(amount, remaining)
 }
 
 /// Request to submit payment proof
@@ -135,112 +483,219 @@ pub async fn submit_blockchain_proof_handler(
     State(state): State<AppState>,
     Json(req): Json<SubmitBlockchainProofRequest>,
 ) -> ApiResult<Json<SubmitBlockchainProofResponse>> {
-    let trade_id = &req.trade_id;
-    
+    submit_trade_proof(&state, &req.trade_id.parse()?).await.map(Json)
+}
+
+/// Submit a trade's stored proof on-chain (shared with settlement jobs)
+pub(crate) async fn submit_trade_proof(
+    state: &AppState,
+    trade_id: &TradeId,
+) -> ApiResult<SubmitBlockchainProofResponse> {
+    submit_trade_proof_with_gas(state, trade_id, GasOverride::default()).await
+}
+
+/// submit_trade_proof with manual gas settings (admin force-release)
+pub(crate) async fn submit_trade_proof_with_gas(
+    state: &AppState,
+    trade_id: &TradeId,
+    gas: GasOverride,
+) -> ApiResult<SubmitBlockchainProofResponse> {
     tracing::info!("🔐 Starting blockchain proof submission for trade {}", trade_id);
 
-    // Check if blockchain client is available
-    let blockchain_client = state.blockchain_client
-        .as_ref()
-        .ok_or_else(|| ApiError::ServiceUnavailable(
-            "Blockchain integration not enabled".to_string()
-        ))?;
+    // Check if blockchain client is available (sandbox mode settles without one)
+    let blockchain_client = match &state.sandbox {
+        Some(_) => None,
+        None => Some(state.blockchain_client
+            .as_ref()
+            .ok_or_else(|| ApiError::ServiceUnavailable(
+                "Blockchain integration not enabled".to_string()
+            ))?),
+    };
+
+    // submitPaymentProof is whenNotPaused - fail fast instead of reverting
+    if state.market_paused().await == Some(true) {
+        return Err(ApiError::MarketPaused);
+    }
 
     // Fetch trade from database
-    let trade = state.db.get_trade(trade_id).await
+    let mut trade = state.db.get_trade(&trade_id.to_string()).await
         .map_err(|e| ApiError::Database(format!("Failed to fetch trade: {}", e)))?;
 
     // Verify that proof has been generated - NO MOCK DATA!
-    let user_public_values = trade.proof_user_public_values
+    let user_public_values = trade.proof_user_public_values.take()
         .ok_or_else(|| ApiError::BadRequest("Proof not yet generated for this trade. Please generate the proof first.".to_string()))?;
     
-    let accumulator = trade.proof_accumulator
+    let accumulator = trade.proof_accumulator.take()
         .ok_or_else(|| ApiError::BadRequest("Proof accumulator not found".to_string()))?;
     
-    let proof_data = trade.proof_data
+    let proof_data = trade.proof_data.take()
         .ok_or_else(|| ApiError::BadRequest("Proof data not found".to_string()))?;
 
-    // Validate proof component sizes
-    if user_public_values.len() != 32 {
-        return Err(ApiError::Internal(format!(
-            "Invalid user_public_values size: expected 32, got {}",
-            user_public_values.len()
-        )));
-    }
-    if accumulator.len() != 384 {
-        return Err(ApiError::Internal(format!(
-            "Invalid accumulator size: expected 384, got {}",
-            accumulator.len()
-        )));
+    // A proof proves one PDF - refuse one generated before the latest upload
+    if let (Some(generated_at), Some(uploaded_at)) = (trade.proof_generated_at, trade.pdf_uploaded_at) {
+        if generated_at < uploaded_at {
+            tracing::warn!(
+                "⛔ Proof for trade {} generated at {} predates the PDF uploaded at {}",
+                trade_id, generated_at, uploaded_at
+            );
+            return Err(ApiError::BadRequest(format!(
+                "The proof was generated at {} from an earlier PDF (latest upload {}) - generate the proof again",
+                generated_at.to_rfc3339(),
+                uploaded_at.to_rfc3339()
+            )));
+        }
     }
-    if proof_data.len() != 1376 {
-        return Err(ApiError::Internal(format!(
-            "Invalid proof_data size: expected 1376, got {}",
-            proof_data.len()
-        )));
+
+    // Validate proof component sizes against the format the proof was generated in
+    let proof_format = state.proof_formats.of_stored(trade.proof_format.as_deref())
+        .map_err(|e| ApiError::Internal(e.to_string()))?;
+    proof_format.validate(&user_public_values, &accumulator, &proof_data)
+        .map_err(|e| ApiError::Internal(e.to_string()))?;
+
+    // A verifier upgrade can change the format - an older proof would only revert
+    if let Some(Ok(config)) = state.chain_config().await {
+        let verifier_format = state.proof_formats.for_verifier(&config.zk_verifier);
+        if verifier_format.version != proof_format.version {
+            tracing::warn!(
+                "⛔ Proof for trade {} is in format {}, verifier {:?} expects {}",
+                trade_id, proof_format.version, config.zk_verifier, verifier_format.version
+            );
+            return Err(ApiError::BadRequest(format!(
+                "The proof was generated for proof format {} but the verifier now expects {} - generate the proof again",
+                proof_format.version, verifier_format.version
+            )));
+        }
     }
 
     tracing::info!(
-        "📦 Proof components validated: user_public_values={} bytes, accumulator={} bytes, proof={} bytes",
+        "📦 Proof components validated ({}): user_public_values={} bytes, accumulator={} bytes, proof={} bytes",
+        proof_format.version,
         user_public_values.len(),
         accumulator.len(),
         proof_data.len()
     );
 
-    // Convert trade ID to bytes32
-    let trade_id_bytes = trade_id_to_bytes32(trade_id)
-        .map_err(|e| ApiError::BadRequest(format!("Invalid trade ID: {}", e)))?;
-
     // Convert user_public_values to [u8; 32]
     let mut user_public_values_array = [0u8; 32];
     user_public_values_array.copy_from_slice(&user_public_values);
 
+    // The contract hashes the on-chain trade and order - a proof made against other details
+    // (stale validation inputs, a test nonce) would only revert with PaymentDetailsMismatch
+    check_public_values(state, &trade, &user_public_values_array).await?;
+
+    let Some(blockchain_client) = blockchain_client else {
+        let sandbox = state.sandbox.as_ref().expect("no blockchain client outside sandbox mode");
+        let tx_hash = sandbox.settle(&state.db, &trade.trade_id).await.map_err(|e| match e {
+            SandboxError::Database(e) => ApiError::from(e),
+            e => ApiError::BadRequest(e.to_string()),
+        })?;
+        return Ok(SubmitBlockchainProofResponse {
+            success: true,
+            tx_hash: format!("{:?}", tx_hash),
+            message: "Sandbox trade settled (no transaction was sent).".to_string(),
+        });
+    };
+
+    // One submission per trade in flight - a concurrent one would only revert and burn gas
+    if let Err(holder) = state.db.claim_proof_submission(&trade.trade_id, PROOF_SUBMISSION_LEASE_SECS).await? {
+        tracing::warn!("⛔ Proof submission for trade {} refused: {} (tx {:?})", trade_id, holder.status, holder.tx_hash);
+        return Err(ApiError::ProofSubmissionConflict {
+            in_flight: holder.status == SUBMISSION_SUBMITTING,
+            tx_hash: holder.tx_hash,
+        });
+    }
+
     // Submit proof to blockchain
     tracing::info!("📤 Submitting proof to blockchain for trade {}", trade_id);
     
-    let tx_hash = match blockchain_client
-        .submit_payment_proof(
-            trade_id_bytes,
-            user_public_values_array,
-            accumulator,
-            proof_data,
-        )
-        .await
-    {
-        Ok(hash) => {
+    let sent = Arc::new(Mutex::new(None));
+    let on_sent: OnSent = {
+        let (db, sent, trade_id) = (state.db.clone(), sent.clone(), trade.trade_id.clone());
+        Arc::new(move |tx_hash: H256| {
+            *sent.lock().unwrap() = Some(tx_hash);
+            let (db, trade_id) = (db.clone(), trade_id.clone());
+            tokio::spawn(async move {
+                if let Err(e) = db.set_proof_submission_tx(&trade_id, &format!("{:?}", tx_hash)).await {
+                    tracing::warn!("Failed to record proof submission tx for trade {}: {}", trade_id, e);
+                }
+            });
+        })
+    };
+
+    // Manual gas settings apply to a single transaction, so those skip batching
+    let batcher = state.settlement_batcher.as_ref().filter(|_| gas == GasOverride::default());
+    let result = match batcher {
+        Some(batcher) => {
+            let proof = ProofSubmission {
+                trade_id: trade_id.0,
+                user_public_values: user_public_values_array,
+                accumulator,
+                proof: proof_data,
+            };
+            let submission = batcher.submit(proof, on_sent).await;
+            // Its call in a batch didn't settle the trade but still cost gas
+            if let Some(share) = submission.unsettled_batch {
+                record_spend(
+                    state,
+                    share.spend(ACTION_SUBMIT_PROOF, Some(trade.trade_id.clone()), Some(trade.order_id.clone()), true),
+                )
+                .await;
+            }
+            submission.result
+        }
+        None => {
+            blockchain_client
+                .submit_payment_proof_with_gas(
+                    trade_id.0,
+                    user_public_values_array,
+                    accumulator,
+                    proof_data,
+                    gas,
+                    move |tx_hash| on_sent(tx_hash),
+                )
+                .await
+        }
+    };
+    let sent = *sent.lock().unwrap();
+    finish_proof_submission(state, &trade.trade_id, &result, sent).await;
+
+    let tx_hash = match result {
+        Ok(relayer_tx) => {
             tracing::info!(
                 "✅ Proof submitted successfully for trade {}: tx_hash={:?}",
                 trade_id,
-                hash
+                relayer_tx.tx_hash
             );
-            hash
+            record_spend(
+                state,
+                relayer_tx.spend(ACTION_SUBMIT_PROOF, Some(trade.trade_id.clone()), Some(trade.order_id.clone()), false),
+            )
+            .await;
+            record_submission_outcome(state, &trade.trade_id, &trade.order_id, OUTCOME_OK).await;
+            relayer_tx.tx_hash
         }
         Err(e) => {
+            if let EthereumClientError::TransactionReverted(tx) = &e {
+                record_spend(
+                    state,
+                    tx.spend(ACTION_SUBMIT_PROOF, Some(trade.trade_id.clone()), Some(trade.order_id.clone()), true),
+                )
+                .await;
+            }
             let error_msg = e.to_string();
             tracing::error!("❌ Blockchain proof submission failed for trade {}: {}", trade_id, error_msg);
             
             // Check for specific contract errors
-            if error_msg.contains("0x826d29e4") || error_msg.contains("PaymentDetailsMismatch") {
-                return Err(ApiError::BadRequest(
-                    "Proof verification failed: Payment details do not match the trade. \
-                     The proof was rejected by the smart contract.".to_string()
-                ));
-            } else if error_msg.contains("0x5f3f6cfc") || error_msg.contains("TradeNotPending") {
-                return Err(ApiError::BadRequest(
-                    "This trade is no longer pending. It may have already been settled or expired.".to_string()
-                ));
-            } else if error_msg.contains("0xfd72c0a0") || error_msg.contains("TradeAlreadySettled") {
-                return Err(ApiError::BadRequest(
-                    "This trade has already been settled.".to_string()
-                ));
-            } else if error_msg.contains("0x78ef33c1") || error_msg.contains("TradeExpired") {
-                return Err(ApiError::BadRequest(
-                    "This trade has expired and cannot be settled.".to_string()
-                ));
-            } else if error_msg.contains("0xea8e4eb5") || error_msg.contains("NotAuthorized") {
-                return Err(ApiError::BadRequest(
-                    "You are not authorized to submit proof for this trade.".to_string()
-                ));
+            if let Some((name, message)) = decode_settlement_revert(&error_msg) {
+                let outcome = match name {
+                    "PaymentDetailsMismatch" => Some(OUTCOME_MISMATCH),
+                    "ProofVerificationFailed" => Some(OUTCOME_PROOF_FAILED),
+                    _ => None,
+                };
+                if let Some(outcome) = outcome {
+                    record_submission_outcome(state, &trade.trade_id, &trade.order_id, outcome).await;
+                }
+                return Err(ApiError::BadRequest(message.to_string()));
             } else if error_msg.contains("Gas estimation failed") {
                 return Err(ApiError::BadRequest(
                     format!("Transaction would revert: {}. The proof was rejected before sending to the blockchain.", error_msg)
@@ -253,11 +708,86 @@ pub async fn submit_blockchain_proof_handler(
 
     let tx_hash_str = format!("{:?}", tx_hash);
     
-    Ok(Json(SubmitBlockchainProofResponse {
+    Ok(SubmitBlockchainProofResponse {
         success: true,
         tx_hash: tx_hash_str,
         message: "Proof submitted to blockchain successfully. The trade will be settled once the transaction is confirmed.".to_string(),
-    }))
+    })
+}
+
+/// Release the trade's submission claim. A transaction that was sent but whose outcome
+/// is unknown (e.g. the receipt wait failed) keeps the claim until its lease runs out,
+/// since it may still be mined.
+async fn finish_proof_submission(
+    state: &AppState,
+    trade_id: &str,
+    result: &Result<RelayerTx, EthereumClientError>,
+    sent: Option<H256>,
+) {
+    let (status, tx_hash) = match result {
+        Ok(relayer_tx) => (SUBMISSION_SUBMITTED, Some(relayer_tx.tx_hash)),
+        Err(EthereumClientError::TransactionReverted(tx)) => (SUBMISSION_FAILED, Some(tx.tx_hash)),
+        Err(_) if sent.is_some() => {
+            tracing::warn!("Proof submission for trade {} has an unknown outcome, keeping the claim", trade_id);
+            return;
+        }
+        Err(_) => (SUBMISSION_FAILED, None),
+    };
+
+    let tx_hash = tx_hash.map(|hash| format!("{:?}", hash));
+    if let Err(e) = state.db.finish_proof_submission(trade_id, status, tx_hash.as_deref()).await {
+        tracing::warn!("Failed to record proof submission outcome for trade {}: {}", trade_id, e);
+    }
+}
+
+/// Refuse a proof whose public values don't hash the trade's current payment details,
+/// reporting what was expected and (from the validation history) the likely cause
+pub(crate) async fn check_public_values(state: &AppState, trade: &DbTrade, public_values: &[u8; 32]) -> ApiResult<()> {
+    let order = state.db.get_order(&trade.order_id).await?;
+    let config = state
+        .chain_config()
+        .await
+        .ok_or_else(|| ApiError::ServiceUnavailable("Blockchain integration not enabled".to_string()))?
+        .map_err(|e| ApiError::BlockchainError(format!("Failed to get contract config: {}", e)))?;
+
+    let mismatch = ProofInputs::for_trade(&order, trade, config.public_key_der_hash)
+        .and_then(|inputs| inputs.public_values_mismatch(public_values))
+        .map_err(|e| ApiError::Internal(format!("Failed to compute expected public values: {}", e)))?;
+    let Some(mut report) = mismatch else {
+        return Ok(());
+    };
+
+    // A validation that expected these values means the trade's details changed since
+    let validated = state
+        .db
+        .get_validation_attempts(&trade.trade_id)
+        .await?
+        .iter()
+        .any(|attempt| attempt.expected_hash.as_deref() == Some(report.proof_public_values.as_str()));
+    report.cause = Some(if validated { MISMATCH_STALE_DETAILS } else { MISMATCH_UNKNOWN_DETAILS });
+
+    tracing::warn!("⛔ Proof submission for trade {} refused: {}", trade.trade_id, report);
+    Err(ApiError::PublicValuesMismatch(Box::new(report)))
+}
+
+/// Record a proof submission outcome against the seller and the receipt's template
+/// (the one its validation recorded)
+async fn record_submission_outcome(state: &AppState, trade_id: &str, order_id: &str, outcome: &str) {
+    let seller = match state.db.get_order(order_id).await {
+        Ok(order) => order.seller,
+        Err(e) => {
+            tracing::warn!("Failed to load order {} to record submission outcome: {}", order_id, e);
+            return;
+        }
+    };
+    let template = state
+        .db
+        .get_trade_template(trade_id)
+        .await
+        .ok()
+        .flatten()
+        .unwrap_or_else(|| UNKNOWN_TEMPLATE.to_string());
+    record_outcome(&state.db, trade_id, &seller, &template, STAGE_SUBMISSION, outcome).await;
 }
 
 /// Request to submit proof (DEPRECATED - legacy endpoint)
@@ -303,148 +833,66 @@ pub async fn submit_proof_handler(
 /// Get trade details by ID
 pub async fn get_trade_handler(
     Path(trade_id): Path<String>,
+    Query(params): Query<DisplayQuery>,
     State(state): State<AppState>,
-) -> ApiResult<Json<crate::db::models::DbTrade>> {
-    // Query trade from database
-    let trade = sqlx::query!(
-        r#"
-        SELECT 
-            "tradeId", "orderId", "buyer", "tokenAmount"::text, "cnyAmount"::text,
-            "paymentNonce", "createdAt", "expiresAt", "status",
-            "escrowTxHash", "settlementTxHash", "syncedAt",
-            pdf_file, pdf_filename, pdf_uploaded_at,
-            proof_user_public_values, proof_accumulator, proof_data,
-            axiom_proof_id, proof_generated_at, proof_json
-        FROM trades
-        WHERE "tradeId" = $1
-        "#,
-        trade_id
-    )
-    .fetch_optional(state.db.pool())
-    .await
-    .map_err(|e| ApiError::Database(e.to_string()))?
-    .ok_or_else(|| ApiError::NotFound(format!("Trade not found: {}", trade_id)))?;
-
-    // Manually map to DbTrade
-    let db_trade = crate::db::models::DbTrade {
-        trade_id: trade.tradeId,
-        order_id: trade.orderId,
-        buyer: trade.buyer,
-        token_amount: trade.tokenAmount.unwrap_or_default(),
-        cny_amount: trade.cnyAmount.unwrap_or_default(),
-        payment_nonce: trade.paymentNonce,
-        created_at: trade.createdAt,
-        expires_at: trade.expiresAt,
-        status: trade.status,
-        escrow_tx_hash: trade.escrowTxHash,
-        settlement_tx_hash: trade.settlementTxHash,
-        synced_at: trade.syncedAt,
-        token: None, // Not available in single trade query (would need JOIN)
-        pdf_file: trade.pdf_file,
-        pdf_filename: trade.pdf_filename,
-        pdf_uploaded_at: trade.pdf_uploaded_at,
-        proof_user_public_values: trade.proof_user_public_values,
-            proof_accumulator: trade.proof_accumulator,
-            proof_data: trade.proof_data,
-            axiom_proof_id: trade.axiom_proof_id,
-            proof_generated_at: trade.proof_generated_at,
-            proof_json: trade.proof_json,
-        };
+) -> ApiResult<Json<TradeView>> {
+    let trade_id = trade_id.parse::<TradeId>()?.to_string();
 
-    Ok(Json(db_trade))
+    let db_trade = state.db.get_trade(&trade_id).await?;
+
+    let now = server_time(&state).await.unix;
+    Ok(Json(TradeView {
+        timing: TradeTiming::new(&db_trade, now, state.expiry_warning_secs),
+        display: TradeDisplay::new(&db_trade, &state.token_display, params.locale),
+        trade: db_trade,
+    }))
+}
+
+/// GET /api/trades/by-nonce/:nonce
+/// Look up a trade by its payment nonce (support workflows: the nonce is what
+/// appears on the buyer's Alipay payment)
+pub async fn get_trade_by_nonce_handler(
+    Path(nonce): Path<String>,
+    Query(params): Query<DisplayQuery>,
+    State(state): State<AppState>,
+) -> ApiResult<Json<TradeView>> {
+    validate_payment_nonce(&nonce).map_err(|e| ApiError::BadRequest(e.to_string()))?;
+
+    let trade = state
+        .db
+        .get_trade_by_nonce(&nonce)
+        .await?
+        .ok_or_else(|| ApiError::NotFound(format!("No trade with payment nonce {}", nonce)))?;
+
+    let now = server_time(&state).await.unix;
+    Ok(Json(TradeView {
+        timing: TradeTiming::new(&trade, now, state.expiry_warning_secs),
+        display: TradeDisplay::new(&trade, &state.token_display, params.locale),
+        trade,
+    }))
 }
 
 /// GET /api/trades/buyer/:buyer_address
 /// Get all trades for a specific buyer
 #[derive(Debug, Serialize)]
 pub struct TradesResponse {
-    pub trades: Vec<crate::db::models::DbTrade>,
+    pub trades: Vec<TradeView>,
 }
 
 pub async fn get_trades_by_buyer_handler(
     Path(buyer_address): Path<String>,
+    Query(params): Query<DisplayQuery>,
     State(state): State<AppState>,
 ) -> ApiResult<Json<TradesResponse>> {
-    // Normalize buyer address (lowercase, strip 0x if present)
-    let buyer_addr = buyer_address
-        .to_lowercase()
-        .trim_start_matches("0x")
-        .to_string();
-    
-    tracing::info!("Fetching trades for buyer: {}", buyer_addr);
-    
-    // Query trades table with JOIN to get token from orders
-    let trades = sqlx::query(
-        r#"
-        SELECT 
-            t."tradeId",
-            t."orderId",
-            t.buyer,
-            t."tokenAmount"::text,
-            t."cnyAmount"::text,
-            t."paymentNonce",
-            t."createdAt",
-            t."expiresAt",
-            t.status,
-            t."escrowTxHash",
-            t."settlementTxHash",
-            t."syncedAt",
-            t.pdf_file,
-            t.pdf_filename,
-            t.pdf_uploaded_at,
-            t.proof_user_public_values,
-            t.proof_accumulator,
-            t.proof_data,
-            t.axiom_proof_id,
-            t.proof_generated_at,
-            t.proof_json,
-            o.token
-        FROM trades t
-        INNER JOIN orders o ON t."orderId" = o."orderId"
-        WHERE LOWER(REPLACE(t.buyer, '0x', '')) = $1
-        ORDER BY t."createdAt" DESC
-        "#
-    )
-    .bind(&buyer_addr)
-    .fetch_all(state.db.pool())
-    .await
-    .map_err(|e| ApiError::Database(e.to_string()))?;
+    let buyer_address: EthAddress = buyer_address.parse()?;
+    tracing::info!("Fetching trades for buyer: {}", buyer_address);
     
-    // Map to DbTrade structs
-    let db_trades: Vec<crate::db::models::DbTrade> = trades
-        .into_iter()
-        .map(|row| {
-            use sqlx::Row;
-            crate::db::models::DbTrade {
-                trade_id: row.get("tradeId"),
-                order_id: row.get("orderId"),
-                buyer: row.get("buyer"),
-                token_amount: row.get("tokenAmount"),
-                cny_amount: row.get("cnyAmount"),
-                payment_nonce: row.get("paymentNonce"),
-                created_at: row.get("createdAt"),
-                expires_at: row.get("expiresAt"),
-                status: row.get("status"),
-                escrow_tx_hash: row.get("escrowTxHash"),
-                settlement_tx_hash: row.get("settlementTxHash"),
-                synced_at: row.get("syncedAt"),
-                pdf_file: row.get("pdf_file"),
-                pdf_filename: row.get("pdf_filename"),
-                pdf_uploaded_at: row.get("pdf_uploaded_at"),
-                proof_user_public_values: row.get("proof_user_public_values"),
-                proof_accumulator: row.get("proof_accumulator"),
-                proof_data: row.get("proof_data"),
-                axiom_proof_id: row.get("axiom_proof_id"),
-                proof_generated_at: row.get("proof_generated_at"),
-                proof_json: row.get("proof_json"),
-                token: Some(row.get("token")),
-            }
-        })
-        .collect();
+    // Trades joined with their order's token
+    let db_trades = state.db.get_trades_by_buyer(&buyer_address).await?;
     
-    tracing::info!("Found {} trades for buyer {}", db_trades.len(), buyer_addr);
+    tracing::info!("Found {} trades for buyer {}", db_trades.len(), buyer_address);
     
-    Ok(Json(TradesResponse { trades: db_trades }))
+    Ok(Json(TradesResponse { trades: with_timing(&state, db_trades, params.locale).await }))
 }
 
 /// Helper function to ABI-encode PaymentDetails struct for mock verifier
@@ -472,3 +920,9 @@ fn encode_payment_details(
     encode(&[Token::Tuple(tokens)])
 }
 
+/// Record relayer gas spend. Failures are only logged - the transaction is already mined.
+pub(crate) async fn record_spend(state: &AppState, spend: NewRelayerSpend) {
+    if let Err(e) = state.db.record_relayer_spend(&spend).await {
+        tracing::warn!("⚠️ Failed to record relayer spend for tx {}: {}", spend.tx_hash, e);
+    }
+}
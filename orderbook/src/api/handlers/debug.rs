@@ -2,6 +2,7 @@ use axum::{extract::State, Json};
 use serde::Serialize;
 
 use crate::api::{error::ApiResult, state::AppState};
+use crate::blockchain::address::EthAddress;
 use crate::db::models::{DbOrder, DbTrade};
 
 /// Debug response with full database dump
@@ -13,6 +14,7 @@ pub struct DatabaseDump {
 
 /// GET /api/debug/database
 /// Returns complete database state for debugging
+/// Reads every row and blob: inspect production through /api/admin/orders and /api/admin/trades
 pub async fn get_database_dump(
     State(state): State<AppState>,
 ) -> ApiResult<Json<DatabaseDump>> {
@@ -20,9 +22,9 @@ pub async fn get_database_dump(
     let orders = sqlx::query!(
         r#"
         SELECT 
-            "orderId", "seller", "token", "totalAmount"::text, "remainingAmount"::text,
+            "orderId", "seller" as "seller: EthAddress", "token" as "token: EthAddress", "totalAmount"::text, "remainingAmount"::text,
             "exchangeRate", "alipayId", "alipayName", 
-            "createdAt", "syncedAt"
+            "createdAt", "syncedAt", "alipayIdFormat", "minFill"::text, "lotSize"::text
         FROM orders
         ORDER BY "createdAt" DESC
         "#
@@ -44,6 +46,9 @@ pub async fn get_database_dump(
             alipay_name: row.alipayName,
             created_at: row.createdAt,
             synced_at: row.syncedAt,
+            alipay_id_format: row.alipayIdFormat,
+            min_fill: row.minFill,
+            lot_size: row.lotSize,
         })
         .collect();
 
@@ -51,12 +56,12 @@ pub async fn get_database_dump(
     let trades = sqlx::query!(
         r#"
         SELECT 
-            "tradeId", "orderId", "buyer", "tokenAmount"::text, "cnyAmount"::text,
+            "tradeId", "orderId", "buyer" as "buyer: EthAddress", "tokenAmount"::text, "cnyAmount"::text,
             "paymentNonce", "createdAt", "expiresAt", "status",
-            "escrowTxHash", "settlementTxHash", "syncedAt",
+            "escrowTxHash", "settlementTxHash", "settlementBlock", "confirmations", "finalizedAt", "syncedAt",
             pdf_file, pdf_filename, pdf_uploaded_at,
             proof_user_public_values, proof_accumulator, proof_data,
-            axiom_proof_id, proof_generated_at, proof_json
+            axiom_proof_id, proof_generated_at, proof_json, "proofFormat"
         FROM trades
         ORDER BY "createdAt" DESC
         "#
@@ -79,6 +84,9 @@ pub async fn get_database_dump(
             status: row.status,
             escrow_tx_hash: row.escrowTxHash,
             settlement_tx_hash: row.settlementTxHash,
+            settlement_block: row.settlementBlock,
+            confirmations: row.confirmations,
+            finalized_at: row.finalizedAt,
             synced_at: row.syncedAt,
             token: None, // Not available in debug dump (would need JOIN)
             pdf_file: row.pdf_file,
@@ -90,6 +98,7 @@ pub async fn get_database_dump(
             axiom_proof_id: row.axiom_proof_id,
             proof_generated_at: row.proof_generated_at,
             proof_json: row.proof_json,
+            proof_format: row.proofFormat,
         })
         .collect();
 
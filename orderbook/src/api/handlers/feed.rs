@@ -0,0 +1,204 @@
+// Order feed for market makers (GET /api/feed/orders.ndjson)
+// Newline-delimited JSON: a snapshot of the active book at sequence N, then every
+// order change after N with consecutive sequence numbers (migrations/018). A client
+// that sees a gap, or loses the connection, resumes with ?since=<last applied seq>.
+
+use axum::{
+    body::Body,
+    extract::{Query, State},
+    http::header,
+    response::IntoResponse,
+};
+use futures::stream;
+use serde::{Deserialize, Serialize};
+use std::collections::VecDeque;
+use std::convert::Infallible;
+use std::time::Duration;
+
+use crate::api::{
+    error::{ApiError, ApiResult},
+    handlers::orders::OrderDto,
+    state::AppState,
+};
+use crate::db::models::DbOrderFeedEntry;
+use crate::db::order_feed::FEED_CREATED;
+use crate::db::token_status::TokenPolicy;
+
+/// How often the log is polled for new changes
+const FEED_POLL_INTERVAL: Duration = Duration::from_secs(1);
+
+/// Heartbeat after this long without changes, so clients can tell a quiet book from a dead connection
+const FEED_HEARTBEAT_INTERVAL: Duration = Duration::from_secs(15);
+
+/// Changes read per poll
+const FEED_BATCH: i64 = 500;
+
+#[derive(Debug, Deserialize)]
+pub struct FeedQueryParams {
+    /// Resume after this sequence number instead of starting with a snapshot
+    pub since: Option<i64>,
+}
+
+/// One line of the feed
+#[derive(Debug, Serialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum FeedMessage {
+    /// Snapshot follows: `count` orders making up the active book as of `seq`
+    SnapshotStart { seq: i64, count: usize },
+    /// Active order in the snapshot
+    Order { seq: i64, order: OrderDto },
+    /// Snapshot complete; changes from `seq + 1` follow
+    SnapshotEnd { seq: i64 },
+    /// New order
+    Created { seq: i64, order: OrderDto },
+    /// Remaining amount or fill limits changed (remaining 0 = drop the order)
+    Updated {
+        seq: i64,
+        order_id: String,
+        remaining_amount: String,
+        min_fill: Option<String>,
+        lot_size: Option<String>,
+    },
+    /// No changes for a while; the stream is current as of `seq`
+    Heartbeat { seq: i64 },
+}
+
+impl FeedMessage {
+    fn from_entry(entry: DbOrderFeedEntry, policy: &TokenPolicy) -> Self {
+        if entry.kind == FEED_CREATED {
+            return FeedMessage::Created { seq: entry.seq, order: OrderDto::new(entry.order, policy) };
+        }
+        FeedMessage::Updated {
+            seq: entry.seq,
+            order_id: entry.order.order_id,
+            remaining_amount: entry.order.remaining_amount,
+            min_fill: entry.order.min_fill,
+            lot_size: entry.order.lot_size,
+        }
+    }
+
+    fn line(&self) -> String {
+        let mut line = serde_json::to_string(self).unwrap_or_default();
+        line.push('\n');
+        line
+    }
+}
+
+/// Stream position and lines waiting to be sent
+struct Feed {
+    state: AppState,
+    seq: i64,
+    queue: VecDeque<String>,
+    idle: Duration,
+}
+
+impl Feed {
+    /// Queue the next batch of changes; false when the log can't be read (ends the stream)
+    async fn poll(&mut self) -> bool {
+        let entries = match self.state.db.get_order_feed_since(self.seq, FEED_BATCH).await {
+            Ok(entries) => entries,
+            Err(e) => {
+                tracing::warn!("Order feed poll after seq {} failed: {}", self.seq, e);
+                return false;
+            }
+        };
+        if entries.is_empty() {
+            return true;
+        }
+
+        // Suspension is evaluated as of sending (token status changes aren't feed events)
+        let policy = match self.state.db.get_token_policy().await {
+            Ok(policy) => policy,
+            Err(e) => {
+                tracing::warn!("Order feed token policy read failed: {}", e);
+                return false;
+            }
+        };
+        self.idle = Duration::ZERO;
+        for entry in entries {
+            self.seq = entry.seq;
+            self.queue.push_back(FeedMessage::from_entry(entry, &policy).line());
+        }
+        true
+    }
+}
+
+/// GET /api/feed/orders.ndjson
+/// Snapshot + incremental order updates as newline-delimited JSON (see module docs)
+pub async fn order_feed_handler(
+    State(state): State<AppState>,
+    Query(params): Query<FeedQueryParams>,
+) -> ApiResult<impl IntoResponse> {
+    let feed = match params.since {
+        Some(since) => {
+            let head = state.db.get_order_feed_head().await?;
+            if !(0..=head).contains(&since) {
+                return Err(ApiError::BadRequest(format!("since must be between 0 and the current sequence {}", head)));
+            }
+            let mut feed = Feed { state, seq: since, queue: VecDeque::new(), idle: Duration::ZERO };
+            if !feed.poll().await {
+                return Err(ApiError::Internal("Order feed unavailable".to_string()));
+            }
+            feed
+        }
+        None => {
+            let (seq, orders) = state.db.get_order_feed_snapshot().await?;
+            let policy = state.db.get_token_policy().await?;
+            let mut queue = VecDeque::with_capacity(orders.len() + 2);
+            queue.push_back(FeedMessage::SnapshotStart { seq, count: orders.len() }.line());
+            for order in orders {
+                queue.push_back(FeedMessage::Order { seq, order: OrderDto::new(order, &policy) }.line());
+            }
+            queue.push_back(FeedMessage::SnapshotEnd { seq }.line());
+            Feed { state, seq, queue, idle: Duration::ZERO }
+        }
+    };
+
+    let lines = stream::unfold(Some(feed), |feed| async move {
+        let mut feed = feed?;
+        loop {
+            if let Some(line) = feed.queue.pop_front() {
+                return Some((Ok::<_, Infallible>(line), Some(feed)));
+            }
+
+            tokio::time::sleep(FEED_POLL_INTERVAL).await;
+            if !feed.poll().await {
+                // Ends the response; the client resumes from its last sequence
+                return None;
+            }
+            if feed.queue.is_empty() {
+                feed.idle += FEED_POLL_INTERVAL;
+                if feed.idle >= FEED_HEARTBEAT_INTERVAL {
+                    feed.idle = Duration::ZERO;
+                    feed.queue.push_back(FeedMessage::Heartbeat { seq: feed.seq }.line());
+                }
+            }
+        }
+    });
+
+    Ok(([(header::CONTENT_TYPE, "application/x-ndjson")], Body::from_stream(lines)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_feed_lines() {
+        let line = FeedMessage::Updated {
+            seq: 7,
+            order_id: "0x01".to_string(),
+            remaining_amount: "0".to_string(),
+            min_fill: None,
+            lot_size: None,
+        }
+        .line();
+        assert!(line.ends_with('\n') && !line.trim_end().contains('\n'));
+
+        let value: serde_json::Value = serde_json::from_str(&line).unwrap();
+        assert_eq!(value["type"], "updated");
+        assert_eq!(value["seq"], 7);
+        assert_eq!(value["remaining_amount"], "0");
+        assert_eq!(serde_json::from_str::<serde_json::Value>(&FeedMessage::Heartbeat { seq: 7 }.line()).unwrap()["type"], "heartbeat");
+    }
+}
@@ -0,0 +1,131 @@
+use axum::{
+    extract::{Path, Query, State},
+    Json,
+};
+use chrono::{DateTime, Duration, Utc};
+use ethers::types::U256;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+use crate::api::{error::{ApiError, ApiResult}, state::AppState};
+use crate::blockchain::address::EthAddress;
+use crate::db::models::{DbFeeAccrual, DbFeeTotal};
+
+#[derive(Debug, Deserialize)]
+pub struct FeesQuery {
+    /// Period start (RFC 3339, default 30 days before `to`)
+    pub from: Option<DateTime<Utc>>,
+    /// Period end, exclusive (RFC 3339, default now)
+    pub to: Option<DateTime<Utc>>,
+    /// Max payers listed (default 100)
+    pub limit: Option<i64>,
+}
+
+impl FeesQuery {
+    fn period(&self) -> ApiResult<(DateTime<Utc>, DateTime<Utc>)> {
+        let to = self.to.unwrap_or_else(Utc::now);
+        let from = self.from.unwrap_or(to - Duration::days(30));
+        if from >= to {
+            return Err(ApiError::BadRequest("from must be before to".to_string()));
+        }
+        Ok((from, to))
+    }
+}
+
+#[derive(Debug, Serialize)]
+pub struct FeesResponse {
+    pub enabled: bool,
+    /// Current schedule, e.g. bps:25 or flat_cny:200 (accruals keep the schedule they were computed with)
+    pub schedule: Option<String>,
+    pub payer: Option<&'static str>,
+    pub from: DateTime<Utc>,
+    pub to: DateTime<Utc>,
+    pub trade_count: i64,
+    pub cny_fee: String,
+    pub by_payer: Vec<DbFeeTotal>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct TokenFeeTotal {
+    pub token: EthAddress,
+    pub trade_count: usize,
+    pub token_fee: String,
+}
+
+#[derive(Debug, Serialize)]
+pub struct FeeStatementResponse {
+    pub address: EthAddress,
+    pub from: DateTime<Utc>,
+    pub to: DateTime<Utc>,
+    pub trade_count: usize,
+    pub cny_fee: String,
+    pub by_token: Vec<TokenFeeTotal>,
+    pub accruals: Vec<DbFeeAccrual>,
+}
+
+fn sum_decimal<'a>(values: impl Iterator<Item = &'a str>) -> U256 {
+    values
+        .filter_map(|v| U256::from_dec_str(v).ok())
+        .fold(U256::zero(), |acc, v| acc.saturating_add(v))
+}
+
+/// GET /api/fees
+/// Fee schedule and accrued operator fees per payer for a period
+pub async fn get_fees_handler(
+    State(state): State<AppState>,
+    Query(params): Query<FeesQuery>,
+) -> ApiResult<Json<FeesResponse>> {
+    let (from, to) = params.period()?;
+    let limit = params.limit.unwrap_or(100).clamp(1, 1000);
+
+    let by_payer = state.db.get_fee_totals(from, to, limit).await?;
+
+    Ok(Json(FeesResponse {
+        enabled: state.fee_engine.is_some(),
+        schedule: state.fee_engine.as_ref().map(|engine| engine.schedule.to_string()),
+        payer: state.fee_engine.as_ref().map(|engine| engine.payer.as_str()),
+        from,
+        to,
+        trade_count: by_payer.iter().map(|t| t.trade_count).sum(),
+        cny_fee: sum_decimal(by_payer.iter().map(|t| t.cny_fee.as_str())).to_string(),
+        by_payer,
+    }))
+}
+
+/// GET /api/fees/:address
+/// Fee statement for one seller or buyer: accruals in the period with per-token totals
+pub async fn get_fee_statement_handler(
+    Path(address): Path<String>,
+    State(state): State<AppState>,
+    Query(params): Query<FeesQuery>,
+) -> ApiResult<Json<FeeStatementResponse>> {
+    let address: EthAddress = address.parse()?;
+    let (from, to) = params.period()?;
+
+    let accruals = state.db.get_fee_accruals(&address, from, to).await?;
+
+    let mut by_token: BTreeMap<&EthAddress, (usize, U256)> = BTreeMap::new();
+    for accrual in &accruals {
+        let entry = by_token.entry(&accrual.token).or_default();
+        entry.0 += 1;
+        entry.1 = entry.1.saturating_add(U256::from_dec_str(&accrual.token_fee).unwrap_or_default());
+    }
+    let by_token = by_token
+        .into_iter()
+        .map(|(token, (trade_count, token_fee))| TokenFeeTotal {
+            token: token.clone(),
+            trade_count,
+            token_fee: token_fee.to_string(),
+        })
+        .collect();
+
+    Ok(Json(FeeStatementResponse {
+        address,
+        from,
+        to,
+        trade_count: accruals.len(),
+        cny_fee: sum_decimal(accruals.iter().map(|a| a.cny_fee.as_str())).to_string(),
+        by_token,
+        accruals,
+    }))
+}
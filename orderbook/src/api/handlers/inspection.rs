@@ -0,0 +1,227 @@
+use axum::{
+    extract::{Path, Query, State},
+    http::HeaderMap,
+    Json,
+};
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use uuid::Uuid;
+
+use crate::api::{error::ApiError, state::AppState};
+use crate::db::inspection::{
+    trade_status, OrderPage, OrderState, PageCursor, TradePage, ORDER_COLUMNS, TRADE_COLUMNS, TRADE_HEAVY_COLUMNS,
+};
+
+/// Rows per page unless `limit` says otherwise
+const DEFAULT_PAGE_SIZE: i64 = 50;
+const MAX_PAGE_SIZE: i64 = 500;
+
+#[derive(Debug, Default, Deserialize)]
+pub struct InspectionQuery {
+    /// Orders: active | filled; trades: pending | settled | expired (default: all)
+    pub status: Option<String>,
+    /// Comma-separated columns (default: all but proof_json)
+    pub columns: Option<String>,
+    /// Orders: created_at | remaining_amount; trades: created_at | expires_at | cny_amount
+    pub sort: Option<String>,
+    /// asc | desc (default desc)
+    pub order: Option<String>,
+    /// next_cursor of the previous page (same sort and order)
+    pub cursor: Option<String>,
+    /// Rows per page (default 50, at most 500)
+    pub limit: Option<i64>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct InspectionPage {
+    /// Selected columns of each row
+    pub rows: Vec<Map<String, Value>>,
+    /// Pass as `cursor` for the next page; None on the last page
+    pub next_cursor: Option<String>,
+}
+
+/// Requested columns, checked against `available`; all but `heavy` if none are requested
+fn select_columns(requested: Option<&str>, available: &[&'static str], heavy: &[&str]) -> Result<Vec<&'static str>, ApiError> {
+    let Some(requested) = requested.map(str::trim).filter(|r| !r.is_empty()) else {
+        return Ok(available.iter().copied().filter(|c| !heavy.contains(c)).collect());
+    };
+    let mut columns = Vec::new();
+    for name in requested.split(',').map(str::trim) {
+        let column = available.iter().copied().find(|c| *c == name).ok_or_else(|| {
+            ApiError::BadRequest(format!("Unknown column '{}': expected any of {}", name, available.join(", ")))
+        })?;
+        if !columns.contains(&column) {
+            columns.push(column);
+        }
+    }
+    Ok(columns)
+}
+
+/// `row`'s JSON with only `columns`
+fn project<T: Serialize>(row: &T, columns: &[&str]) -> Result<Map<String, Value>, ApiError> {
+    let Value::Object(mut fields) = serde_json::to_value(row).map_err(|e| ApiError::Internal(e.to_string()))? else {
+        return Err(ApiError::Internal("Row did not serialize to an object".to_string()));
+    };
+    fields.retain(|name, _| columns.contains(&name.as_str()));
+    Ok(fields)
+}
+
+/// Sort direction, descending unless `order` is asc
+fn descending(order: Option<&str>) -> Result<bool, ApiError> {
+    match order {
+        None | Some("desc") => Ok(true),
+        Some("asc") => Ok(false),
+        Some(other) => Err(ApiError::BadRequest(format!("Invalid order '{}': expected asc or desc", other))),
+    }
+}
+
+/// Shared paging parameters: direction, cursor and limit
+fn paging(params: &InspectionQuery) -> Result<(bool, Option<PageCursor>, i64), ApiError> {
+    let cursor = params.cursor.as_deref().map(str::parse).transpose().map_err(ApiError::BadRequest)?;
+    let limit = params.limit.unwrap_or(DEFAULT_PAGE_SIZE).clamp(1, MAX_PAGE_SIZE);
+    Ok((descending(params.order.as_deref())?, cursor, limit))
+}
+
+/// GET /api/admin/orders?status=&columns=&sort=&order=&cursor=&limit=
+/// Orders a page at a time, newest first by default
+pub async fn list_orders_handler(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Query(params): Query<InspectionQuery>,
+) -> Result<Json<InspectionPage>, ApiError> {
+    state.admin_keys.require(&headers)?;
+    let columns = select_columns(params.columns.as_deref(), ORDER_COLUMNS, &[])?;
+    let (descending, after, limit) = paging(&params)?;
+    let page = OrderPage {
+        status: params.status.as_deref().map(str::parse::<OrderState>).transpose().map_err(ApiError::BadRequest)?,
+        sort: params.sort.as_deref().map(str::parse).transpose().map_err(ApiError::BadRequest)?.unwrap_or_default(),
+        descending,
+        after,
+        // One more row than the page tells whether there is a next one
+        limit: limit + 1,
+    };
+
+    let mut orders = state.db.page_orders(&page).await?;
+    let next_cursor = (orders.len() as i64 > limit).then(|| {
+        orders.truncate(limit as usize);
+        orders.last().map(|o| PageCursor::new(&page.sort.value(o), &o.order_id).to_string())
+    });
+    let rows = orders.iter().map(|o| project(o, &columns)).collect::<Result<_, _>>()?;
+
+    Ok(Json(InspectionPage { rows, next_cursor: next_cursor.flatten() }))
+}
+
+/// GET /api/admin/trades?status=&columns=&sort=&order=&cursor=&limit=
+/// Trades a page at a time, newest first by default; PDFs and proof bytes are never
+/// returned and proof_json only when it is in `columns`
+pub async fn list_trades_handler(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Query(params): Query<InspectionQuery>,
+) -> Result<Json<InspectionPage>, ApiError> {
+    state.admin_keys.require(&headers)?;
+    let columns = select_columns(params.columns.as_deref(), TRADE_COLUMNS, TRADE_HEAVY_COLUMNS)?;
+    let (descending, after, limit) = paging(&params)?;
+    let status = params
+        .status
+        .as_deref()
+        .map(|name| {
+            trade_status(name).ok_or_else(|| {
+                ApiError::BadRequest(format!("Invalid status '{}': expected pending, settled or expired", name))
+            })
+        })
+        .transpose()?;
+    let page = TradePage {
+        status,
+        sort: params.sort.as_deref().map(str::parse).transpose().map_err(ApiError::BadRequest)?.unwrap_or_default(),
+        descending,
+        after,
+        limit: limit + 1,
+        with_proof_json: columns.contains(&"proof_json"),
+    };
+
+    let mut trades = state.db.page_trades(&page).await?;
+    let next_cursor = (trades.len() as i64 > limit).then(|| {
+        trades.truncate(limit as usize);
+        trades.last().map(|t| PageCursor::new(&page.sort.value(t), &t.trade_id).to_string())
+    });
+    let rows = trades.iter().map(|t| project(t, &columns)).collect::<Result<_, _>>()?;
+
+    Ok(Json(InspectionPage { rows, next_cursor: next_cursor.flatten() }))
+}
+
+/// GET /api/admin/match-plans/:plan_id
+/// A stored match plan with the request it answered, the hash of the book it was matched
+/// against and the trades executed from it
+pub async fn get_match_plan_handler(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Path(plan_id): Path<Uuid>,
+) -> Result<Json<Value>, ApiError> {
+    state.admin_keys.require(&headers)?;
+    let stored = state
+        .db
+        .get_match_plan(plan_id)
+        .await?
+        .ok_or_else(|| ApiError::NotFound(format!("Match plan {} not found", plan_id)))?;
+
+    // request and plan are stored as JSON text
+    let parse = |json: &str| serde_json::from_str::<Value>(json).map_err(|e| ApiError::Internal(e.to_string()));
+    let Value::Object(mut fields) = serde_json::to_value(&stored).map_err(|e| ApiError::Internal(e.to_string()))? else {
+        return Err(ApiError::Internal("Match plan did not serialize to an object".to_string()));
+    };
+    fields.insert("request".to_string(), parse(&stored.request)?);
+    fields.insert("plan".to_string(), parse(&stored.plan)?);
+    Ok(Json(Value::Object(fields)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::models::{DbOrder, DbTrade};
+
+    #[test]
+    fn test_select_columns() {
+        let default = select_columns(None, TRADE_COLUMNS, TRADE_HEAVY_COLUMNS).unwrap();
+        assert!(default.contains(&"trade_id") && !default.contains(&"proof_json"));
+        assert_eq!(select_columns(Some(" "), ORDER_COLUMNS, &[]).unwrap(), ORDER_COLUMNS);
+
+        assert_eq!(
+            select_columns(Some("status, trade_id,status,proof_json"), TRADE_COLUMNS, TRADE_HEAVY_COLUMNS).unwrap(),
+            vec!["status", "trade_id", "proof_json"]
+        );
+        assert!(select_columns(Some("trade_id,pdf_file"), TRADE_COLUMNS, TRADE_HEAVY_COLUMNS).is_err());
+    }
+
+    #[test]
+    fn test_columns_are_the_models_json_fields() {
+        // Every column must exist in the model's JSON, or selecting it would silently return nothing
+        let fields = |value: Value| value.as_object().unwrap().keys().cloned().collect::<Vec<_>>();
+        let order: DbOrder = serde_json::from_value(serde_json::json!({
+            "order_id": "0x01", "seller": "0x1111111111111111111111111111111111111111",
+            "token": "0x036cbd53842c5426634e7929541ec2318f3dcf7e", "total_amount": "1", "remaining_amount": "1",
+            "exchange_rate": "735", "alipay_id": "a", "alipay_name": "n", "created_at": 1,
+            "synced_at": "2025-01-01T00:00:00Z", "alipay_id_format": "phone", "min_fill": null, "lot_size": null
+        }))
+        .unwrap();
+        let mut expected = fields(serde_json::to_value(&order).unwrap());
+        let mut columns: Vec<String> = ORDER_COLUMNS.iter().map(|c| c.to_string()).collect();
+        expected.sort();
+        columns.sort();
+        assert_eq!(columns, expected);
+
+        let trade: DbTrade = serde_json::from_value(serde_json::json!({
+            "trade_id": "0x02", "order_id": "0x01", "buyer": "0x2222222222222222222222222222222222222222",
+            "token_amount": "1", "cny_amount": "735", "payment_nonce": "1", "created_at": 1, "expires_at": 2,
+            "status": 0, "synced_at": "2025-01-01T00:00:00Z", "escrow_tx_hash": null, "settlement_tx_hash": null,
+            "settlement_block": null, "finalized_at": null, "token": null, "pdf_filename": null,
+            "pdf_uploaded_at": null, "axiom_proof_id": null, "proof_generated_at": null, "proof_json": null
+        }))
+        .unwrap();
+        let mut expected = fields(serde_json::to_value(&trade).unwrap());
+        let mut columns: Vec<String> = TRADE_COLUMNS.iter().map(|c| c.to_string()).collect();
+        expected.sort();
+        columns.sort();
+        assert_eq!(columns, expected);
+    }
+}
@@ -0,0 +1,248 @@
+use axum::{
+    extract::{Path, Query, State},
+    Json,
+};
+use ethers::types::{H256, U256};
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+use std::str::FromStr;
+
+use crate::api::{
+    error::{ApiError, ApiResult},
+    state::AppState,
+};
+use crate::blockchain::address::EthAddress;
+use crate::blockchain::types::{
+    cancel_order_intent_signing_hash, order_intent_domain_separator, recover_typed_data_signer, OrderIntent,
+    ORDER_INTENT_DOMAIN_NAME, ORDER_INTENT_DOMAIN_VERSION,
+};
+use crate::db::models::DbOrderIntent;
+use crate::db::order_intents::NewOrderIntent;
+
+/// Intents may not be valid for longer than this
+const MAX_INTENT_LIFETIME_SECS: i64 = 30 * 24 * 3600;
+
+/// Request to post a signed order intent
+#[derive(Debug, Deserialize)]
+pub struct PostOrderIntentRequest {
+    pub seller: String,
+    pub token: String,
+    /// Token base units (uint256 decimal string)
+    pub amount: String,
+    /// CNY cents per whole token (uint256 decimal string)
+    pub exchange_rate: String,
+    /// keccak256(abi.encode(alipayId, alipayName)), 0x-prefixed
+    pub alipay_hash: String,
+    /// uint256 decimal string, unique per seller
+    pub nonce: String,
+    /// Unix timestamp
+    pub deadline: i64,
+    /// Seller's eth_signTypedData_v4 signature over the OrderIntent
+    pub signature: String,
+}
+
+/// Request to cancel an intent (signature over CancelOrderIntent(intentHash))
+#[derive(Debug, Deserialize)]
+pub struct CancelOrderIntentRequest {
+    pub signature: String,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct OrderIntentQuery {
+    pub token: Option<String>,
+    /// Max intents listed (default 100)
+    pub limit: Option<i64>,
+}
+
+/// EIP-712 domain wallets sign intents under
+#[derive(Debug, Serialize)]
+pub struct OrderIntentDomain {
+    pub name: &'static str,
+    pub version: &'static str,
+    pub chain_id: u64,
+    pub verifying_contract: EthAddress,
+}
+
+#[derive(Debug, Serialize)]
+pub struct OrderIntentsResponse {
+    pub domain: OrderIntentDomain,
+    pub intents: Vec<DbOrderIntent>,
+}
+
+/// Chain id, escrow and domain separator intents are verified against
+fn intent_domain(state: &AppState) -> ApiResult<(OrderIntentDomain, H256)> {
+    let client = state
+        .blockchain_client
+        .as_ref()
+        .ok_or_else(|| ApiError::ServiceUnavailable("Order intents require blockchain integration".to_string()))?;
+    let domain = OrderIntentDomain {
+        name: ORDER_INTENT_DOMAIN_NAME,
+        version: ORDER_INTENT_DOMAIN_VERSION,
+        chain_id: client.chain_id(),
+        verifying_contract: client.escrow_address().into(),
+    };
+    let separator = order_intent_domain_separator(domain.chain_id, client.escrow_address());
+    Ok((domain, separator))
+}
+
+/// Parse a positive uint256 that also fits the NUMERIC(78,0) binding
+fn parse_amount(value: &str, name: &str) -> ApiResult<(U256, Decimal)> {
+    let invalid = || ApiError::BadRequest(format!("Invalid {}: expected a positive integer, got {:?}", name, value));
+    let amount = U256::from_dec_str(value).map_err(|_| invalid())?;
+    let decimal = Decimal::from_str(value).map_err(|_| invalid())?;
+    if amount.is_zero() {
+        return Err(invalid());
+    }
+    Ok((amount, decimal))
+}
+
+fn parse_intent_hash(value: &str) -> ApiResult<H256> {
+    H256::from_str(value).map_err(|_| ApiError::BadRequest(format!("Invalid intent hash: {}", value)))
+}
+
+/// POST /api/order-intents
+/// Verify and list a seller-signed order intent before the escrow is funded
+pub async fn post_order_intent_handler(
+    State(state): State<AppState>,
+    Json(req): Json<PostOrderIntentRequest>,
+) -> ApiResult<Json<DbOrderIntent>> {
+    let (_, separator) = intent_domain(&state)?;
+
+    let seller: EthAddress = req.seller.parse()?;
+    let token: EthAddress = req.token.parse()?;
+    let (amount, amount_decimal) = parse_amount(&req.amount, "amount")?;
+    let (exchange_rate, rate_decimal) = parse_amount(&req.exchange_rate, "exchange_rate")?;
+    let nonce = U256::from_dec_str(&req.nonce)
+        .map_err(|_| ApiError::BadRequest(format!("Invalid nonce: {:?}", req.nonce)))?;
+    let nonce_decimal = Decimal::from_str(&req.nonce)
+        .map_err(|_| ApiError::BadRequest(format!("Invalid nonce: {:?}", req.nonce)))?;
+    let alipay_hash = H256::from_str(&req.alipay_hash)
+        .map_err(|_| ApiError::BadRequest(format!("Invalid alipay_hash: {}", req.alipay_hash)))?;
+
+    let now = chrono::Utc::now().timestamp();
+    if req.deadline <= now || req.deadline > now + MAX_INTENT_LIFETIME_SECS {
+        return Err(ApiError::BadRequest(format!(
+            "deadline must be in the future and at most {} days away",
+            MAX_INTENT_LIFETIME_SECS / 86400
+        )));
+    }
+
+    if state.db.get_token_policy().await?.is_suspended(&token) {
+        return Err(ApiError::TokenSuspended(token.to_checksum()));
+    }
+
+    let intent = OrderIntent {
+        seller: seller.to_address(),
+        token: token.to_address(),
+        amount,
+        exchange_rate,
+        alipay_hash,
+        nonce,
+        deadline: U256::from(req.deadline),
+    };
+    let signer = recover_typed_data_signer(intent.signing_hash(separator), &req.signature)
+        .map_err(|e| ApiError::BadRequest(e.to_string()))?;
+    if signer != intent.seller {
+        return Err(ApiError::Forbidden("Order intent must be signed by its seller".to_string()));
+    }
+
+    let created = state
+        .db
+        .create_order_intent(&NewOrderIntent {
+            intent_hash: format!("{:#x}", intent.struct_hash()),
+            seller,
+            token,
+            amount: amount_decimal,
+            exchange_rate: rate_decimal,
+            alipay_hash: format!("{:#x}", alipay_hash),
+            nonce: nonce_decimal,
+            deadline: req.deadline,
+            signature: req.signature,
+        })
+        .await?;
+    tracing::info!(
+        "📝 Order intent {} posted by {} ({} of {} at {})",
+        created.intent_hash, created.seller, created.amount, created.token, created.exchange_rate
+    );
+
+    Ok(Json(created))
+}
+
+/// GET /api/order-intents?token=
+/// Live (open, unexpired) intents, lowest rate first, with the EIP-712 domain to sign under
+pub async fn list_order_intents_handler(
+    State(state): State<AppState>,
+    Query(params): Query<OrderIntentQuery>,
+) -> ApiResult<Json<OrderIntentsResponse>> {
+    let (domain, _) = intent_domain(&state)?;
+    let token = params.token.as_deref().map(str::parse::<EthAddress>).transpose()?;
+    let limit = params.limit.unwrap_or(100).clamp(1, 500);
+
+    let intents = state
+        .db
+        .get_open_order_intents(token.as_ref(), chrono::Utc::now().timestamp(), limit)
+        .await?;
+
+    Ok(Json(OrderIntentsResponse { domain, intents }))
+}
+
+/// GET /api/order-intents/:intent_hash
+pub async fn get_order_intent_handler(
+    State(state): State<AppState>,
+    Path(intent_hash): Path<String>,
+) -> ApiResult<Json<DbOrderIntent>> {
+    let intent_hash = format!("{:#x}", parse_intent_hash(&intent_hash)?);
+    let intent = state
+        .db
+        .get_order_intent(&intent_hash)
+        .await?
+        .ok_or_else(|| ApiError::NotFound(format!("Order intent {} not found", intent_hash)))?;
+
+    Ok(Json(intent))
+}
+
+/// GET /api/order-intents/seller/:seller
+/// A seller's intents in any state, newest first
+pub async fn get_seller_order_intents_handler(
+    State(state): State<AppState>,
+    Path(seller): Path<String>,
+) -> ApiResult<Json<Vec<DbOrderIntent>>> {
+    let seller: EthAddress = seller.parse()?;
+    Ok(Json(state.db.get_order_intents_by_seller(&seller, 200).await?))
+}
+
+/// POST /api/order-intents/:intent_hash/cancel
+/// Withdraw an open intent. Must be signed by its seller; funded intents can't be cancelled.
+pub async fn cancel_order_intent_handler(
+    State(state): State<AppState>,
+    Path(intent_hash): Path<String>,
+    Json(req): Json<CancelOrderIntentRequest>,
+) -> ApiResult<Json<DbOrderIntent>> {
+    let (_, separator) = intent_domain(&state)?;
+    let hash = parse_intent_hash(&intent_hash)?;
+    let intent_hash = format!("{:#x}", hash);
+
+    let intent = state
+        .db
+        .get_order_intent(&intent_hash)
+        .await?
+        .ok_or_else(|| ApiError::NotFound(format!("Order intent {} not found", intent_hash)))?;
+
+    let signer = recover_typed_data_signer(cancel_order_intent_signing_hash(hash, separator), &req.signature)
+        .map_err(|e| ApiError::BadRequest(e.to_string()))?;
+    if signer != intent.seller.to_address() {
+        return Err(ApiError::Forbidden("Cancellation must be signed by the intent's seller".to_string()));
+    }
+
+    if !state.db.cancel_order_intent(&intent_hash).await? {
+        return Err(ApiError::BadRequest(format!("Order intent {} is already {}", intent_hash, intent.status)));
+    }
+    tracing::info!("📝 Order intent {} cancelled by {}", intent_hash, intent.seller);
+
+    state
+        .db
+        .get_order_intent(&intent_hash)
+        .await?
+        .map(Json)
+        .ok_or_else(|| ApiError::NotFound(format!("Order intent {} not found", intent_hash)))
+}
@@ -0,0 +1,319 @@
+use axum::{
+    extract::{Multipart, Path, Query, State},
+    http::{header, StatusCode},
+    response::{IntoResponse, Response},
+    Extension, Json,
+};
+use ethers::types::Signature;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::str::FromStr;
+
+use crate::api::{
+    api_keys::ApiKeyIdentity,
+    error::{ApiError, ApiResult},
+    state::AppState,
+};
+use crate::blockchain::address::EthAddress;
+use crate::blockchain::ids::TradeId;
+use crate::db::models::DbTradeMessage;
+use crate::db::trade_messages::{NewTradeMessage, ROLE_BUYER, ROLE_SELLER};
+
+/// Longest message body (characters)
+pub const MAX_MESSAGE_CHARS: usize = 2000;
+
+/// Max size of one attachment (1MB, under the default request body limit)
+pub const MAX_ATTACHMENT_SIZE: usize = 1024 * 1024;
+
+/// Messages a trade may hold in total
+const MAX_MESSAGES_PER_TRADE: i64 = 200;
+
+/// How old a message signature may be
+const MESSAGE_SIGNATURE_TTL_SECS: i64 = 600;
+
+/// Message a party signs (EIP-191 personal_sign) to read a trade's messages
+pub fn read_messages_message(trade_id: &str, signed_at: i64) -> String {
+    format!("zkAlipay trade messages\nTrade: {}\nSigned at: {}", trade_id, signed_at)
+}
+
+/// Message a party signs to post; `content` is `message_content_hash` of the body and attachment
+pub fn post_message_message(trade_id: &str, content: &str, signed_at: i64) -> String {
+    format!("zkAlipay trade message\nTrade: {}\nContent: {}\nSigned at: {}", trade_id, content, signed_at)
+}
+
+/// sha256(sha256(body) || sha256(attachment)), hex - binds the signature to what is posted
+pub fn message_content_hash(body: &str, attachment: Option<&[u8]>) -> String {
+    let mut hasher = Sha256::new();
+    hasher.update(Sha256::digest(body.as_bytes()));
+    hasher.update(Sha256::digest(attachment.unwrap_or_default()));
+    hex::encode(hasher.finalize())
+}
+
+/// Attachment type from its content: PNG, JPEG or PDF
+fn attachment_type(data: &[u8]) -> Option<&'static str> {
+    if data.starts_with(b"\x89PNG\r\n\x1a\n") {
+        Some("image/png")
+    } else if data.starts_with(&[0xff, 0xd8, 0xff]) {
+        Some("image/jpeg")
+    } else if data.starts_with(b"%PDF") {
+        Some("application/pdf")
+    } else {
+        None
+    }
+}
+
+#[derive(Debug, Deserialize)]
+pub struct TradeMessagesQuery {
+    /// Only messages with a larger id (for polling)
+    pub after: Option<i64>,
+    /// Unix timestamp included in the signed message (not needed with an API key)
+    pub signed_at: Option<i64>,
+    pub signature: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct TradeMessagesResponse {
+    pub trade_id: String,
+    /// Role of the reader on this trade
+    pub role: &'static str,
+    pub messages: Vec<DbTradeMessage>,
+}
+
+/// Who may talk on a trade: its buyer and the order's seller
+async fn trade_parties(state: &AppState, trade_id: &str) -> ApiResult<(EthAddress, EthAddress)> {
+    let trade = state.db.get_trade(trade_id).await?;
+    let order = state.db.get_order(&trade.order_id).await?;
+    Ok((trade.buyer, order.seller))
+}
+
+/// Authenticate a party of the trade by a wallet-bound API key or a fresh signature of
+/// `message(signed_at)`, returning its address and role
+async fn authenticate_party(
+    state: &AppState,
+    trade_id: &str,
+    api_key: Option<&ApiKeyIdentity>,
+    signed_at: Option<i64>,
+    signature: Option<&str>,
+    message: impl FnOnce(i64) -> String,
+) -> ApiResult<(EthAddress, &'static str)> {
+    let (buyer, seller) = trade_parties(state, trade_id).await?;
+    let role_of = |address: &EthAddress| {
+        if *address == buyer {
+            Some(ROLE_BUYER)
+        } else if *address == seller {
+            Some(ROLE_SELLER)
+        } else {
+            None
+        }
+    };
+
+    if let Some(key) = api_key {
+        if let Some(role) = role_of(&key.wallet) {
+            return Ok((key.wallet.clone(), role));
+        }
+    }
+
+    let (Some(signed_at), Some(signature)) = (signed_at, signature) else {
+        return Err(ApiError::Forbidden("Trade messages require the buyer's or seller's signature".to_string()));
+    };
+    let age = chrono::Utc::now().timestamp() - signed_at;
+    if !(-60..=MESSAGE_SIGNATURE_TTL_SECS).contains(&age) {
+        return Err(ApiError::BadRequest(format!(
+            "signed_at must be within the last {} seconds",
+            MESSAGE_SIGNATURE_TTL_SECS
+        )));
+    }
+
+    let signer: EthAddress = Signature::from_str(signature)
+        .and_then(|signature| signature.recover(message(signed_at).as_str()))
+        .map_err(|e| ApiError::BadRequest(format!("Invalid signature: {}", e)))?
+        .into();
+    let role = role_of(&signer)
+        .ok_or_else(|| ApiError::Forbidden("Only the trade's buyer and seller can use its messages".to_string()))?;
+    Ok((signer, role))
+}
+
+/// GET /api/trades/:trade_id/messages?after=&signed_at=&signature=
+/// The trade's messages, oldest first (buyer or seller only)
+pub async fn get_trade_messages_handler(
+    State(state): State<AppState>,
+    Path(trade_id): Path<String>,
+    api_key: Option<Extension<ApiKeyIdentity>>,
+    Query(params): Query<TradeMessagesQuery>,
+) -> ApiResult<Json<TradeMessagesResponse>> {
+    let trade_id = trade_id.parse::<TradeId>()?.to_string();
+    let (_, role) = authenticate_party(
+        &state,
+        &trade_id,
+        api_key.as_ref().map(|Extension(key)| key),
+        params.signed_at,
+        params.signature.as_deref(),
+        |signed_at| read_messages_message(&trade_id, signed_at),
+    )
+    .await?;
+
+    let messages = state
+        .db
+        .get_trade_messages(&trade_id, params.after.unwrap_or(0), MAX_MESSAGES_PER_TRADE)
+        .await?;
+
+    Ok(Json(TradeMessagesResponse { trade_id, role, messages }))
+}
+
+/// POST /api/trades/:trade_id/messages
+/// Post a message (multipart: body, optional attachment file, signed_at, signature).
+/// The signature covers `post_message_message` with the content hash.
+pub async fn post_trade_message_handler(
+    State(state): State<AppState>,
+    Path(trade_id): Path<String>,
+    api_key: Option<Extension<ApiKeyIdentity>>,
+    mut multipart: Multipart,
+) -> ApiResult<Json<DbTradeMessage>> {
+    let trade_id = trade_id.parse::<TradeId>()?.to_string();
+
+    let mut body = String::new();
+    let mut attachment: Option<(Option<String>, Vec<u8>)> = None;
+    let mut signed_at = None;
+    let mut signature = None;
+    while let Some(field) = multipart
+        .next_field()
+        .await
+        .map_err(|_| ApiError::BadRequest("Invalid multipart data".to_string()))?
+    {
+        let name = field.name().unwrap_or("").to_string();
+        match name.as_str() {
+            "attachment" => {
+                let filename = field.file_name().map(|s| s.chars().take(128).collect());
+                let data = field
+                    .bytes()
+                    .await
+                    .map_err(|_| ApiError::BadRequest("Failed to read attachment".to_string()))?;
+                attachment = Some((filename, data.to_vec()));
+            }
+            "body" | "signed_at" | "signature" => {
+                let value = field
+                    .text()
+                    .await
+                    .map_err(|_| ApiError::BadRequest(format!("Failed to read {}", name)))?;
+                match name.as_str() {
+                    "body" => body = value.trim().to_string(),
+                    "signed_at" => {
+                        signed_at = Some(value.trim().parse::<i64>().map_err(|_| {
+                            ApiError::BadRequest(format!("Invalid signed_at: {:?}", value))
+                        })?)
+                    }
+                    _ => signature = Some(value.trim().to_string()),
+                }
+            }
+            _ => {}
+        }
+    }
+
+    if body.chars().count() > MAX_MESSAGE_CHARS {
+        return Err(ApiError::BadRequest(format!("Message too long (max {} characters)", MAX_MESSAGE_CHARS)));
+    }
+    let attachment = match attachment {
+        Some((filename, data)) => {
+            if data.len() > MAX_ATTACHMENT_SIZE {
+                return Err(ApiError::BadRequest("Attachment too large (max 1MB)".to_string()));
+            }
+            let content_type = attachment_type(&data)
+                .ok_or_else(|| ApiError::BadRequest("Attachments must be PNG, JPEG or PDF".to_string()))?;
+            Some((filename, content_type, data))
+        }
+        None => None,
+    };
+    if body.is_empty() && attachment.is_none() {
+        return Err(ApiError::BadRequest("A message needs a body or an attachment".to_string()));
+    }
+
+    let content = message_content_hash(&body, attachment.as_ref().map(|(_, _, data)| data.as_slice()));
+    let (sender, role) = authenticate_party(
+        &state,
+        &trade_id,
+        api_key.as_ref().map(|Extension(key)| key),
+        signed_at,
+        signature.as_deref(),
+        |signed_at| post_message_message(&trade_id, &content, signed_at),
+    )
+    .await?;
+
+    if state.db.count_trade_messages(&trade_id).await? >= MAX_MESSAGES_PER_TRADE {
+        return Err(ApiError::BadRequest(format!(
+            "This trade already has {} messages",
+            MAX_MESSAGES_PER_TRADE
+        )));
+    }
+
+    let message = state
+        .db
+        .create_trade_message(&NewTradeMessage {
+            trade_id: trade_id.clone(),
+            sender,
+            role,
+            body,
+            attachment,
+        })
+        .await?;
+    tracing::info!("💬 Message {} on trade {} from the {}", message.id, trade_id, role);
+
+    Ok(Json(message))
+}
+
+/// GET /api/trades/:trade_id/messages/:message_id/attachment?signed_at=&signature=
+/// A message's attachment (same authentication as reading the messages)
+pub async fn get_message_attachment_handler(
+    State(state): State<AppState>,
+    Path((trade_id, message_id)): Path<(String, i64)>,
+    api_key: Option<Extension<ApiKeyIdentity>>,
+    Query(params): Query<TradeMessagesQuery>,
+) -> ApiResult<Response> {
+    let trade_id = trade_id.parse::<TradeId>()?.to_string();
+    authenticate_party(
+        &state,
+        &trade_id,
+        api_key.as_ref().map(|Extension(key)| key),
+        params.signed_at,
+        params.signature.as_deref(),
+        |signed_at| read_messages_message(&trade_id, signed_at),
+    )
+    .await?;
+
+    let attachment = state
+        .db
+        .get_message_attachment(&trade_id, message_id)
+        .await?
+        .ok_or_else(|| ApiError::NotFound(format!("Message {} has no attachment", message_id)))?;
+    let filename = attachment.name.unwrap_or_else(|| format!("attachment-{}", message_id)).replace('"', "");
+
+    Ok((
+        StatusCode::OK,
+        [
+            (header::CONTENT_TYPE, attachment.content_type),
+            (header::CONTENT_DISPOSITION, format!("attachment; filename=\"{}\"", filename)),
+        ],
+        attachment.data,
+    )
+        .into_response())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_message_content_hash() {
+        let text_only = message_content_hash("paid 735.00, nonce typo", None);
+        assert_eq!(text_only, message_content_hash("paid 735.00, nonce typo", Some(&[])));
+        assert_ne!(text_only, message_content_hash("paid 735.00, nonce typo", Some(b"%PDF-1.4")));
+        assert_ne!(text_only, message_content_hash("paid 736.00, nonce typo", None));
+    }
+
+    #[test]
+    fn test_attachment_type() {
+        assert_eq!(attachment_type(b"\x89PNG\r\n\x1a\n...."), Some("image/png"));
+        assert_eq!(attachment_type(&[0xff, 0xd8, 0xff, 0xe0]), Some("image/jpeg"));
+        assert_eq!(attachment_type(b"%PDF-1.7"), Some("application/pdf"));
+        assert_eq!(attachment_type(b"<html>"), None);
+    }
+}
@@ -1,30 +1,70 @@
 pub mod admin;
+pub mod analytics;
 pub mod buyer;
 pub mod debug;
+pub mod feed;
+pub mod fees;
+pub mod inspection;
+pub mod intents;
+pub mod messages;
+pub mod metrics;
+pub mod notifications;
+pub mod order_batch;
 pub mod orders;
+pub mod payments;
 pub mod pdf;
 pub mod proof;
+pub mod receipt;
+pub mod sandbox;
+pub mod settlement;
+pub mod support;
+pub mod tokens;
 pub mod generate_proof;
+#[cfg(test)]
+mod tests;
 
 use axum::{extract::State, Json};
 use chrono::Utc;
 
 use crate::api::{
+    clock::{server_time, TimeResponse, TimeSource},
     error::ApiResult,
     state::AppState,
-    types::HealthResponse,
+    types::{HealthResponse, StatusResponse},
 };
+use crate::db::token_status::TokenPolicy;
 
 pub use admin::{
-    get_config_handler, pause_contract_handler, unpause_contract_handler, update_config_handler,
-    update_verifier_handler, update_zkpdf_config_handler,
+    bump_pending_tx_handler, cancel_pending_tx_handler, clear_screening_handler, clear_template_flag_handler, create_api_key_handler, discard_dead_letter_handler, force_expire_handler, force_release_handler, get_alipay_blocklist_handler, get_anomalies_handler, get_config_handler, get_config_history_handler, get_maintenance_handler, get_order_staleness_handler, get_payment_windows_handler, get_pending_txs_handler, get_prover_budget_handler, get_prover_transcripts_handler, get_relayer_keys_handler, get_runtime_config_handler, get_screening_handler, get_spending_limits_handler, list_api_keys_handler, list_dead_letters_handler, pause_contract_handler, refresh_config_handler, reprocess_dead_letter_handler,
+    revoke_api_key_handler, rotate_relayer_handler, set_alipay_blocklist_handler, set_buyer_tier_handler, set_maintenance_handler, set_order_staleness_handler, set_payment_window_tier_handler, set_runtime_config_handler, set_spending_limit_tier_handler, set_token_status_handler, simulate_settlement_handler, unpause_contract_handler, update_config_handler, update_verifier_handler, update_zkpdf_config_handler,
 };
-pub use buyer::{execute_fill_handler, get_trade_handler, get_trades_by_buyer_handler, submit_proof_handler, submit_blockchain_proof_handler};
+pub use analytics::{funnel_handler, orderbook_history_handler, relayer_costs_handler};
+pub use buyer::{execute_fill_handler, get_trade_handler, get_trade_by_nonce_handler, get_trades_by_buyer_handler, submit_proof_handler, submit_blockchain_proof_handler};
 pub use debug::get_database_dump;
-pub use orders::{get_active_orders, get_order, match_buy_intent_handler};
-pub use pdf::{upload_pdf_handler, get_pdf_handler};
-pub use proof::get_proof_handler;
-pub use generate_proof::{generate_proof_handler, validate_pdf_axiom_handler};
+pub use feed::order_feed_handler;
+pub use fees::{get_fee_statement_handler, get_fees_handler};
+pub use inspection::{get_match_plan_handler, list_orders_handler, list_trades_handler};
+pub use intents::{cancel_order_intent_handler, get_order_intent_handler, get_seller_order_intents_handler, list_order_intents_handler, post_order_intent_handler};
+pub use messages::{get_message_attachment_handler, get_trade_messages_handler, post_trade_message_handler};
+pub use metrics::metrics_handler;
+pub use notifications::{get_notification_channels_handler, set_notification_channel_handler};
+pub use order_batch::{batch_create_calldata_handler, batch_withdraw_calldata_handler, withdraw_calldata_handler};
+pub use orders::{get_active_orders, get_order, match_buy_intent_handler, orderbook_summary_handler, refresh_order_handler, set_fill_limits_handler};
+pub use payments::{declare_trade_payment_handler, get_trade_payments_handler};
+pub use pdf::{bulk_upload_pdfs_handler, upload_buyer_pdf_handler, upload_pdf_handler, get_pdf_handler};
+pub use proof::{get_proof_handler, upload_proof_handler};
+pub use receipt::{get_attestation_handler, get_receipt_handler};
+pub use sandbox::create_sandbox_order_handler;
+pub use settlement::{get_settlement_job_handler, settle_trade_handler, settlement_job_events_handler};
+pub use support::{add_trade_note_handler, get_trade_support_handler, list_support_trades_handler, update_trade_support_handler};
+pub use tokens::get_token_status_handler;
+pub use generate_proof::{generate_proof_handler, get_validations_handler, validate_pdf_axiom_handler};
+
+/// GET /api/time
+/// Server time for client countdowns (latest block timestamp when available)
+pub async fn time_handler(State(state): State<AppState>) -> Json<TimeResponse> {
+    Json(server_time(&state).await.into())
+}
 
 /// Health check endpoint
 pub async fn health_check(State(state): State<AppState>) -> ApiResult<Json<HealthResponse>> {
@@ -35,16 +75,53 @@ pub async fn health_check(State(state): State<AppState>) -> ApiResult<Json<Healt
     };
 
     // Get orderbook status (DB-based matching)
-    let orderbook_status = match state.db.get_active_orders(Some(1)).await {
+    let orderbook_status = match state.db.get_active_orders(Some(1), &TokenPolicy::default()).await {
         Ok(_) => "active (DB-based)",
         Err(_) => "unavailable",
     };
 
+    let startup_issues = state.startup.issues();
+    let status = if startup_issues.is_empty() { "ok" } else { "degraded" };
+
     Ok(Json(HealthResponse {
-        status: "ok".to_string(),
+        status: status.to_string(),
         database: db_status.to_string(),
         orderbook: orderbook_status.to_string(),
         timestamp: Utc::now().to_rfc3339(),
+        market_paused: state.market_paused().await,
+        event_listener: state.metrics.listener.health(),
+        startup_issues,
+        maintenance: state.maintenance.status(Utc::now()),
     }))
 }
 
+
+/// GET /api/status
+/// Public platform status: chain connectivity, settlement and proof activity, liquidity
+/// per token, and banner-worthy conditions (flagged receipt templates, market pause, maintenance)
+pub async fn status_handler(State(state): State<AppState>) -> ApiResult<Json<StatusResponse>> {
+    let flagged_templates = state.db.get_flagged_templates().await?;
+    let market_paused = state.market_paused().await;
+    let mut maintenance = state.maintenance.status(Utc::now());
+    maintenance.updated_by = None;
+
+    let chain_connected = server_time(&state).await.source == TimeSource::Chain;
+    let last_settlement_at = state.db.get_last_settlement_at().await?;
+    let latency = state.db.get_proof_latency(Utc::now() - chrono::Duration::hours(1)).await?;
+    let policy = state.db.get_token_policy().await?;
+    let liquidity = state.db.get_token_liquidity(&policy).await?;
+
+    Ok(Json(StatusResponse {
+        banner: !flagged_templates.is_empty() || market_paused == Some(true) || maintenance.active || state.sandbox.is_some(),
+        sandbox: state.sandbox.is_some(),
+        chain_connected,
+        last_settlement_at,
+        avg_proof_latency_secs: latency.avg_latency_secs,
+        proofs_last_hour: latency.proofs,
+        liquidity,
+        flagged_templates,
+        market_paused,
+        maintenance,
+        timestamp: Utc::now().to_rfc3339(),
+    }))
+}
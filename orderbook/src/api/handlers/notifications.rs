@@ -0,0 +1,154 @@
+use axum::{
+    extract::{Path, State},
+    Json,
+};
+use ethers::types::Signature;
+use serde::{Deserialize, Serialize};
+use std::str::FromStr;
+
+use crate::api::{
+    error::{ApiError, ApiResult},
+    state::AppState,
+};
+use crate::blockchain::address::EthAddress;
+use crate::db::models::DbNotificationChannel;
+use crate::db::notification_channels::{NewNotificationChannel, CHANNELS};
+use crate::notifications::channels::validate_destination;
+use crate::notifications::NOTIFICATION_EVENTS;
+use crate::text_utils::mask_middle;
+
+/// How old a notification channel signature may be
+const CHANNEL_SIGNATURE_TTL_SECS: i64 = 600;
+
+/// A notification channel, with the destination masked
+#[derive(Debug, Serialize)]
+pub struct NotificationChannelDto {
+    pub channel: String,
+    pub destination: String,
+    /// Events delivered (null = all)
+    pub events: Option<Vec<String>>,
+    pub updated_at: chrono::DateTime<chrono::Utc>,
+}
+
+impl From<DbNotificationChannel> for NotificationChannelDto {
+    fn from(channel: DbNotificationChannel) -> Self {
+        let destination = mask_middle(&channel.destination, 3, 3, '*', 4).unwrap_or_else(|| "****".to_string());
+        Self {
+            channel: channel.channel,
+            destination,
+            events: channel.events,
+            updated_at: channel.updated_at,
+        }
+    }
+}
+
+/// Request to set or remove a notification channel, signed by the address
+#[derive(Debug, Deserialize)]
+pub struct SetNotificationChannelRequest {
+    /// email | sms | telegram
+    pub channel: String,
+    /// Email address, E.164 phone number or Telegram chat id (omit to remove the channel)
+    pub destination: Option<String>,
+    /// Events to deliver (omit for all, see NOTIFICATION_EVENTS)
+    pub events: Option<Vec<String>>,
+    /// Unix timestamp included in the signed message
+    pub signed_at: i64,
+    /// personal_sign signature over `notification_channel_message`
+    pub signature: String,
+}
+
+/// Message the address signs (EIP-191 personal_sign) to set or remove a channel
+pub fn notification_channel_message(
+    address: &str,
+    channel: &str,
+    destination: Option<&str>,
+    events: Option<&[String]>,
+    signed_at: i64,
+) -> String {
+    format!(
+        "zkAlipay notifications\nAddress: {}\nChannel: {}\nDestination: {}\nEvents: {}\nSigned at: {}",
+        address,
+        channel,
+        destination.unwrap_or("none"),
+        events.map(|events| events.join(",")).unwrap_or_else(|| "all".to_string()),
+        signed_at
+    )
+}
+
+/// GET /api/notifications/:address/channels
+/// The address's notification channels (destinations masked)
+pub async fn get_notification_channels_handler(
+    State(state): State<AppState>,
+    Path(address): Path<String>,
+) -> ApiResult<Json<Vec<NotificationChannelDto>>> {
+    let address = address.parse::<EthAddress>()?;
+    let channels = state.db.get_notification_channels(&address).await?;
+    Ok(Json(channels.into_iter().map(NotificationChannelDto::from).collect()))
+}
+
+/// POST /api/notifications/:address/channels
+/// Set (or with no destination, remove) one of the address's channels. Must be signed
+/// by the address. Channels whose adapter isn't configured are stored but not delivered.
+pub async fn set_notification_channel_handler(
+    State(state): State<AppState>,
+    Path(address): Path<String>,
+    Json(req): Json<SetNotificationChannelRequest>,
+) -> ApiResult<Json<Vec<NotificationChannelDto>>> {
+    let address = address.parse::<EthAddress>()?;
+    let channel = CHANNELS
+        .into_iter()
+        .find(|c| *c == req.channel)
+        .ok_or_else(|| ApiError::BadRequest(format!("Unknown channel {:?}: expected one of {:?}", req.channel, CHANNELS)))?;
+    if let Some(destination) = &req.destination {
+        validate_destination(channel, destination).map_err(ApiError::BadRequest)?;
+    }
+    if let Some(unknown) = req.events.iter().flatten().find(|e| !NOTIFICATION_EVENTS.contains(&e.as_str())) {
+        return Err(ApiError::BadRequest(format!("Unknown event {:?}: expected one of {:?}", unknown, NOTIFICATION_EVENTS)));
+    }
+
+    let age = chrono::Utc::now().timestamp() - req.signed_at;
+    if !(-60..=CHANNEL_SIGNATURE_TTL_SECS).contains(&age) {
+        return Err(ApiError::BadRequest(format!(
+            "signed_at must be within the last {} seconds",
+            CHANNEL_SIGNATURE_TTL_SECS
+        )));
+    }
+
+    let message = notification_channel_message(
+        address.as_str(),
+        channel,
+        req.destination.as_deref(),
+        req.events.as_deref(),
+        req.signed_at,
+    );
+    let signer = Signature::from_str(&req.signature)
+        .and_then(|signature| signature.recover(message.as_str()))
+        .map_err(|e| ApiError::BadRequest(format!("Invalid signature: {}", e)))?;
+    if signer != address.to_address() {
+        return Err(ApiError::Forbidden("Notification channels must be signed by the address".to_string()));
+    }
+
+    match req.destination {
+        Some(destination) => {
+            let new = NewNotificationChannel {
+                address: address.clone(),
+                channel,
+                destination,
+                events: req.events,
+                signed_at: req.signed_at,
+            };
+            if !state.db.set_notification_channel(&new).await? {
+                return Err(ApiError::BadRequest("A newer signature already set this channel".to_string()));
+            }
+            tracing::info!("📨 {} set its {} notification channel", address, channel);
+        }
+        None => {
+            if state.db.delete_notification_channel(&address, channel, req.signed_at).await? {
+                tracing::info!("📨 {} removed its {} notification channel", address, channel);
+            }
+        }
+    }
+
+    let channels = state.db.get_notification_channels(&address).await?;
+    Ok(Json(channels.into_iter().map(NotificationChannelDto::from).collect()))
+}
@@ -0,0 +1,264 @@
+use axum::{extract::{Path, State}, Json};
+use ethers::types::{H256, U256};
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+
+use crate::alipay::{mask_alipay_id, AlipayIdFormat};
+use crate::api::{
+    error::{ApiError, ApiResult},
+    state::AppState,
+};
+use crate::blockchain::address::EthAddress;
+use crate::blockchain::batch::{create_calls, withdraw_calls, BatchCall, OrderSpec, MAX_BATCH_ORDERS};
+use crate::blockchain::ids::OrderId;
+use crate::blockchain::types::decode_withdrawal_revert;
+
+#[derive(Debug, Deserialize)]
+pub struct BatchWithdrawItem {
+    pub order_id: String,
+    /// Token base units (default: the order's whole remaining amount)
+    pub amount: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct BatchWithdrawRequest {
+    pub seller: String,
+    pub withdrawals: Vec<BatchWithdrawItem>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct BatchCreateItem {
+    pub token: String,
+    /// Token base units
+    pub total_amount: String,
+    /// CNY cents per whole token
+    pub exchange_rate: String,
+    pub alipay_id: String,
+    pub alipay_name: String,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct BatchCreateRequest {
+    pub seller: String,
+    pub orders: Vec<BatchCreateItem>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct WithdrawCalldataRequest {
+    pub seller: String,
+    /// Token base units (default: everything withdrawable)
+    pub amount: Option<String>,
+}
+
+/// Outcome of withdrawAmount dry-run as the seller against the latest block
+#[derive(Debug, Serialize)]
+pub struct WithdrawSimulation {
+    pub success: bool,
+    /// Custom error the call reverted with
+    pub revert_error: Option<String>,
+    pub message: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct WithdrawCalldataResponse {
+    pub chain_id: u64,
+    pub order_id: String,
+    /// Account that must send the call (the escrow checks msg.sender)
+    pub from: EthAddress,
+    pub call: BatchCall,
+    /// Token base units
+    pub amount: String,
+    /// The order's on-chain remainingAmount
+    pub remaining: String,
+    /// Part of the remaining amount fills sent by the relayer but not yet mined will take
+    pub in_flight: String,
+    /// remaining - in_flight
+    pub withdrawable: String,
+    /// The withdrawal takes all that is withdrawable
+    pub full: bool,
+    pub simulation: WithdrawSimulation,
+}
+
+/// EIP-5792 wallet_sendCalls parameters for the batch
+#[derive(Debug, Serialize)]
+pub struct BatchCalldataResponse {
+    pub chain_id: u64,
+    /// Account that must send the batch (the escrow checks msg.sender)
+    pub from: EthAddress,
+    /// Calls in execution order
+    pub calls: Vec<BatchCall>,
+    /// The batch must execute in one transaction for the book to update atomically
+    pub atomic_required: bool,
+}
+
+fn check_batch_size(len: usize) -> ApiResult<()> {
+    if len == 0 || len > MAX_BATCH_ORDERS {
+        return Err(ApiError::BadRequest(format!("A batch must have 1-{} orders", MAX_BATCH_ORDERS)));
+    }
+    Ok(())
+}
+
+fn parse_positive(value: &str, name: &str) -> ApiResult<U256> {
+    match U256::from_dec_str(value) {
+        Ok(amount) if !amount.is_zero() => Ok(amount),
+        _ => Err(ApiError::BadRequest(format!("Invalid {}: expected a positive integer, got {:?}", name, value))),
+    }
+}
+
+/// POST /api/orders/batch-withdraw-calldata
+/// Calldata withdrawing from many of a seller's orders in one transaction
+pub async fn batch_withdraw_calldata_handler(
+    State(state): State<AppState>,
+    Json(req): Json<BatchWithdrawRequest>,
+) -> ApiResult<Json<BatchCalldataResponse>> {
+    let client = state
+        .blockchain_client
+        .as_ref()
+        .ok_or_else(|| ApiError::ServiceUnavailable("Batch calldata requires blockchain integration".to_string()))?;
+    let seller: EthAddress = req.seller.parse()?;
+    check_batch_size(req.withdrawals.len())?;
+
+    let mut seen = HashSet::new();
+    let mut withdrawals = Vec::with_capacity(req.withdrawals.len());
+    for item in &req.withdrawals {
+        let order_id = item.order_id.parse::<OrderId>()?;
+        if !seen.insert(order_id) {
+            return Err(ApiError::BadRequest(format!("Order {} is listed twice", order_id)));
+        }
+
+        let order = state.db.get_order(&order_id.to_string()).await?;
+        if order.seller != seller {
+            return Err(ApiError::Forbidden(format!("Order {} doesn't belong to {}", order_id, seller)));
+        }
+        let remaining = U256::from_dec_str(&order.remaining_amount).unwrap_or_default();
+        let amount = match &item.amount {
+            Some(amount) => parse_positive(amount, "amount")?,
+            None => remaining,
+        };
+        if amount.is_zero() || amount > remaining {
+            return Err(ApiError::BadRequest(format!(
+                "Order {} has {} remaining, can't withdraw {}",
+                order_id, remaining, amount
+            )));
+        }
+        withdrawals.push((H256(order_id.0), amount));
+    }
+
+    Ok(Json(BatchCalldataResponse {
+        chain_id: client.chain_id(),
+        from: seller,
+        calls: withdraw_calls(client.escrow_address(), &withdrawals),
+        atomic_required: true,
+    }))
+}
+
+/// POST /api/orders/:order_id/withdraw-calldata
+/// Calldata withdrawing from one of the seller's orders, checked against the liquidity
+/// fills in flight will take and dry-run on-chain
+pub async fn withdraw_calldata_handler(
+    State(state): State<AppState>,
+    Path(order_id): Path<String>,
+    Json(req): Json<WithdrawCalldataRequest>,
+) -> ApiResult<Json<WithdrawCalldataResponse>> {
+    let client = state
+        .blockchain_client
+        .as_ref()
+        .ok_or_else(|| ApiError::ServiceUnavailable("Withdrawal calldata requires blockchain integration".to_string()))?;
+    let seller: EthAddress = req.seller.parse()?;
+    let order_id = order_id.parse::<OrderId>()?;
+
+    let order = state.db.get_order(&order_id.to_string()).await?;
+    if order.seller != seller {
+        return Err(ApiError::Forbidden(format!("Order {} doesn't belong to {}", order_id, seller)));
+    }
+
+    // The DB lags fills that are mined but not yet processed, so read the escrow
+    let remaining = client
+        .get_order_remaining(order_id.0)
+        .await
+        .map_err(|e| ApiError::BlockchainError(e.to_string()))?;
+    let in_flight = client.fills_in_flight(order_id.0);
+    let withdrawable = remaining.saturating_sub(in_flight);
+    let amount = match &req.amount {
+        Some(amount) => parse_positive(amount, "amount")?,
+        None => withdrawable,
+    };
+    if amount.is_zero() || amount > withdrawable {
+        return Err(ApiError::BadRequest(format!(
+            "Order {} has {} remaining, {} of it taken by fills in flight: can't withdraw {}",
+            order_id, remaining, in_flight, amount
+        )));
+    }
+
+    let simulation = match client.simulate_withdrawal(order_id.0, amount, seller.to_address()).await {
+        Ok(()) => WithdrawSimulation { success: true, revert_error: None, message: None },
+        Err(e) => {
+            let error_msg = e.to_string();
+            match decode_withdrawal_revert(&error_msg) {
+                Some((name, message)) => WithdrawSimulation {
+                    success: false,
+                    revert_error: Some(name.to_string()),
+                    message: Some(message.to_string()),
+                },
+                None => WithdrawSimulation { success: false, revert_error: None, message: Some(error_msg) },
+            }
+        }
+    };
+
+    Ok(Json(WithdrawCalldataResponse {
+        chain_id: client.chain_id(),
+        order_id: order_id.to_string(),
+        from: seller,
+        call: withdraw_calls(client.escrow_address(), &[(H256(order_id.0), amount)]).remove(0),
+        amount: amount.to_string(),
+        remaining: remaining.to_string(),
+        in_flight: in_flight.to_string(),
+        withdrawable: withdrawable.to_string(),
+        full: amount == withdrawable,
+        simulation,
+    }))
+}
+
+/// POST /api/orders/batch-create-calldata
+/// Calldata approving the escrow and creating many orders in one transaction
+pub async fn batch_create_calldata_handler(
+    State(state): State<AppState>,
+    Json(req): Json<BatchCreateRequest>,
+) -> ApiResult<Json<BatchCalldataResponse>> {
+    let client = state
+        .blockchain_client
+        .as_ref()
+        .ok_or_else(|| ApiError::ServiceUnavailable("Batch calldata requires blockchain integration".to_string()))?;
+    let seller: EthAddress = req.seller.parse()?;
+    check_batch_size(req.orders.len())?;
+    let policy = state.db.get_token_policy().await?;
+
+    let mut orders = Vec::with_capacity(req.orders.len());
+    for item in &req.orders {
+        let token: EthAddress = item.token.parse()?;
+        if policy.is_suspended(&token) {
+            return Err(ApiError::TokenSuspended(token.to_checksum()));
+        }
+        // An ID the receipt masking can't render would leave the order unprovable
+        mask_alipay_id(&item.alipay_id, AlipayIdFormat::detect(&item.alipay_id))
+            .map_err(|e| ApiError::BadRequest(e.to_string()))?;
+        if item.alipay_name.trim().is_empty() {
+            return Err(ApiError::BadRequest("alipay_name is required".to_string()));
+        }
+
+        orders.push(OrderSpec {
+            token: token.to_address(),
+            total_amount: parse_positive(&item.total_amount, "total_amount")?,
+            exchange_rate: parse_positive(&item.exchange_rate, "exchange_rate")?,
+            alipay_id: item.alipay_id.clone(),
+            alipay_name: item.alipay_name.clone(),
+        });
+    }
+
+    Ok(Json(BatchCalldataResponse {
+        chain_id: client.chain_id(),
+        from: seller,
+        calls: create_calls(client.escrow_address(), &orders),
+        atomic_required: true,
+    }))
+}
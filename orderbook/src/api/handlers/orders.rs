@@ -2,15 +2,31 @@ use axum::{
     extract::{Path, Query, State},
     Json,
 };
+use ethers::types::{Signature, U256};
 use rust_decimal::Decimal;
 use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
 use std::str::FromStr;
+use uuid::Uuid;
 
 use crate::api::{
-    error::ApiResult,
+    display::{display_plan, format_cny, DisplayQuery, Locale, TokenDisplayRegistry},
+    error::{ApiError, ApiResult},
+    freshness::{ensure_fresh, sync_status, SyncStatus},
     state::AppState,
-    matching::{match_buy_intent, MatchPlan},
+    matching::{book_hash, match_buy_intent_with_priority, MatchPlan},
 };
+use crate::blockchain::address::EthAddress;
+use crate::blockchain::ids::OrderId;
+use crate::blockchain::types::fill_value_cny;
+use crate::cache::orderbook::ORDERBOOK_DEPTH;
+use crate::db::match_plans::NewMatchPlan;
+use crate::db::models::{DbOrder, DbSellerReputation, DbTokenSummary};
+use crate::db::token_status::TokenPolicy;
+use crate::spending_limits::{self, BuyerLimits};
+
+/// How long execute-fill accepts a match plan unless MATCH_PLAN_TTL_SECS says otherwise
+pub const DEFAULT_MATCH_PLAN_TTL_SECS: u64 = 300;
 
 /// Request to match a buy intent
 #[derive(Debug, Deserialize)]
@@ -23,6 +39,17 @@ pub struct MatchBuyRequest {
     
     /// Maximum exchange rate (CNY cents per token, optional)
     pub max_rate: Option<String>,
+    
+    /// Buyer address (optional): refuses a buyer at their spending limit and reports their room
+    pub buyer_address: Option<String>,
+
+    /// Leave out orders idle past the admin's staleness threshold (default false)
+    #[serde(default)]
+    pub exclude_stale: bool,
+
+    /// Separators of the plan's display amounts (en, zh, de or fr; default en)
+    #[serde(default)]
+    pub locale: Locale,
 }
 
 /// Query parameters for listing orders
@@ -33,20 +60,169 @@ pub struct OrderQueryParams {
     
     /// Filter by seller address (optional)
     pub seller: Option<String>,
+
+    /// Separators of display amounts (en, zh, de or fr; default en)
+    #[serde(default)]
+    pub locale: Locale,
+
+    /// Token amount (base units) to price each order at, see OrderDto::estimated_cny
+    pub amount: Option<String>,
+}
+
+/// Query parameters for a single order
+#[derive(Debug, Default, Deserialize)]
+pub struct OrderQuery {
+    /// Separators of display amounts (en, zh, de or fr; default en)
+    #[serde(default)]
+    pub locale: Locale,
+
+    /// Token amount (base units) to price the order at, see OrderDto::estimated_cny
+    pub amount: Option<String>,
+}
+
+/// Trade history of an order's seller
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct SellerReputation {
+    pub settled_trades: i64,
+    pub expired_trades: i64,
+    /// (settled + 1) / (settled + expired + 2), as ranked by the reputation order
+    /// priority: 0.5 without history
+    pub score: f64,
+}
+
+impl SellerReputation {
+    fn new(settled_trades: i64, expired_trades: i64) -> Self {
+        Self {
+            settled_trades,
+            expired_trades,
+            score: (settled_trades + 1) as f64 / (settled_trades + expired_trades + 2) as f64,
+        }
+    }
 }
 
 /// Order response DTO
 #[derive(Debug, Serialize)]
 pub struct OrderDto {
     pub order_id: String,
-    pub seller: String,
-    pub token: String,
+    pub seller: EthAddress,
+    pub token: EthAddress,
     pub total_amount: String,
     pub remaining_amount: String,
     pub exchange_rate: String,
     pub alipay_id: String,
     pub alipay_name: String,
+    pub alipay_id_format: String,
     pub created_at: i64,
+    /// Seller fill limits (token base units): fills below `min_fill` or off the
+    /// `lot_size` grid are refused unless they take the whole remaining amount
+    pub min_fill: Option<String>,
+    pub lot_size: Option<String>,
+    /// Token is delisted - the order can't be matched or filled until it's relisted
+    pub suspended: bool,
+    /// Display amounts ("100.00 USDC", rate as "7.35 CNY" per token); token amounts
+    /// only for registered tokens, see api::display
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub total_amount_display: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub remaining_amount_display: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub exchange_rate_display: Option<String>,
+    /// CNY per whole token as a plain decimal ("7.35"; exchange_rate is in cents)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub price_per_token_cny: Option<String>,
+    /// With ?amount=: CNY cents the buyer pays for that many base units, priced as
+    /// fillOrder does (only for registered tokens, it needs their decimals)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub estimated_cny: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub estimated_cny_display: Option<String>,
+    /// In order lists and single-order responses
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub seller_reputation: Option<SellerReputation>,
+}
+
+impl OrderDto {
+    pub(crate) fn new(order: DbOrder, policy: &TokenPolicy) -> Self {
+        Self {
+            suspended: policy.is_suspended(&order.token),
+            price_per_token_cny: Decimal::from_str(&order.exchange_rate)
+                .ok()
+                .and_then(|rate| rate.checked_div(Decimal::ONE_HUNDRED))
+                .map(|price| format!("{:.2}", price)),
+            order_id: order.order_id,
+            seller: order.seller,
+            token: order.token,
+            total_amount: order.total_amount,
+            remaining_amount: order.remaining_amount,
+            exchange_rate: order.exchange_rate,
+            alipay_id: order.alipay_id,
+            alipay_name: order.alipay_name,
+            alipay_id_format: order.alipay_id_format,
+            created_at: order.created_at,
+            min_fill: order.min_fill,
+            lot_size: order.lot_size,
+            total_amount_display: None,
+            remaining_amount_display: None,
+            exchange_rate_display: None,
+            estimated_cny: None,
+            estimated_cny_display: None,
+            seller_reputation: None,
+        }
+    }
+
+    /// Add the display amounts
+    pub(crate) fn with_display(mut self, tokens: &TokenDisplayRegistry, locale: Locale) -> Self {
+        if let Some(token) = tokens.get(&self.token) {
+            self.total_amount_display = token.format(&self.total_amount, locale);
+            self.remaining_amount_display = token.format(&self.remaining_amount, locale);
+        }
+        self.exchange_rate_display = format_cny(&self.exchange_rate, locale);
+        self
+    }
+
+    /// Price `amount` base units of the order's token, if the token is registered
+    pub(crate) fn with_estimate(mut self, amount: Option<U256>, tokens: &TokenDisplayRegistry, locale: Locale) -> Self {
+        let (Some(amount), Some(token)) = (amount, tokens.get(&self.token)) else {
+            return self;
+        };
+        if let Ok(rate) = U256::from_dec_str(&self.exchange_rate) {
+            let cny = fill_value_cny(amount, rate, token.decimals).to_string();
+            self.estimated_cny_display = format_cny(&cny, locale);
+            self.estimated_cny = Some(cny);
+        }
+        self
+    }
+
+    /// Add the seller's reputation from `reputations` (no entry: no trades yet)
+    pub(crate) fn with_reputation(mut self, reputations: &[DbSellerReputation]) -> Self {
+        let reputation = reputations.iter().find(|r| r.seller == self.seller);
+        self.seller_reputation = Some(SellerReputation::new(
+            reputation.map_or(0, |r| r.settled_trades),
+            reputation.map_or(0, |r| r.expired_trades),
+        ));
+        self
+    }
+}
+
+/// ?amount= of the order endpoints (token base units)
+fn parse_estimate_amount(amount: Option<&str>) -> ApiResult<Option<U256>> {
+    amount
+        .map(|amount| {
+            U256::from_dec_str(amount)
+                .map_err(|_| ApiError::BadRequest(format!("Invalid amount '{}': expected token base units", amount)))
+        })
+        .transpose()
+}
+
+/// The distinct sellers of `orders`' reputations, in one query
+async fn seller_reputations(state: &AppState, orders: &[DbOrder]) -> ApiResult<Vec<DbSellerReputation>> {
+    let mut sellers: Vec<EthAddress> = Vec::new();
+    for order in orders {
+        if !sellers.contains(&order.seller) {
+            sellers.push(order.seller.clone());
+        }
+    }
+    Ok(state.db.get_seller_reputations(&sellers).await?)
 }
 
 /// List of orders response
@@ -54,6 +230,84 @@ pub struct OrderDto {
 pub struct OrderListResponse {
     pub orders: Vec<OrderDto>,
     pub total: usize,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub market_paused: Option<bool>,
+    #[serde(flatten)]
+    pub sync: SyncStatus,
+}
+
+/// Match plan response with sync metadata
+#[derive(Debug, Serialize)]
+pub struct MatchResponse {
+    #[serde(flatten)]
+    pub plan: MatchPlan,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub market_paused: Option<bool>,
+    #[serde(flatten)]
+    pub sync: SyncStatus,
+    /// The buyer's tier, caps and usage (omitted without a buyer or when they aren't limited)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub spending_limit: Option<BuyerLimits>,
+    /// Pass to execute-fill; the plan is kept for audit (see db::match_plans)
+    pub plan_id: Uuid,
+    /// Unix time after which execute-fill refuses the plan
+    pub plan_expires_at: i64,
+}
+
+/// Book and last-24h settlement aggregates of one tradable token
+#[derive(Debug, Serialize)]
+pub struct TokenSummaryDto {
+    pub token: EthAddress,
+    /// Registered symbol (see api::display)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub symbol: Option<String>,
+    pub active_orders: i64,
+    /// Remaining amount of the active orders (token base units)
+    pub total_liquidity: String,
+    /// Lowest exchange rate on the book (CNY cents per token; None without active orders)
+    pub best_rate: Option<String>,
+    /// Trades whose settlement became final over the last 24 hours
+    pub settled_trades_24h: i64,
+    pub settled_volume_24h: String,
+    pub settled_volume_24h_cny: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub total_liquidity_display: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub best_rate_display: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub settled_volume_24h_display: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub settled_volume_24h_cny_display: Option<String>,
+}
+
+impl TokenSummaryDto {
+    fn new(summary: DbTokenSummary, tokens: &TokenDisplayRegistry, locale: Locale) -> Self {
+        let token = tokens.get(&summary.token);
+        Self {
+            symbol: token.map(|token| token.symbol.clone()),
+            total_liquidity_display: token.and_then(|token| token.format(&summary.total_liquidity, locale)),
+            best_rate_display: summary.best_rate.as_deref().and_then(|rate| format_cny(rate, locale)),
+            settled_volume_24h_display: token.and_then(|token| token.format(&summary.settled_volume_24h, locale)),
+            settled_volume_24h_cny_display: format_cny(&summary.settled_volume_24h_cny, locale),
+            token: summary.token,
+            active_orders: summary.active_orders,
+            total_liquidity: summary.total_liquidity,
+            best_rate: summary.best_rate,
+            settled_trades_24h: summary.settled_trades_24h,
+            settled_volume_24h: summary.settled_volume_24h,
+            settled_volume_24h_cny: summary.settled_volume_24h_cny,
+        }
+    }
+}
+
+/// Orderbook summary response
+#[derive(Debug, Serialize)]
+pub struct OrderbookSummaryResponse {
+    pub tokens: Vec<TokenSummaryDto>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub market_paused: Option<bool>,
+    #[serde(flatten)]
+    pub sync: SyncStatus,
 }
 
 /// Get list of active sell orders
@@ -61,26 +315,24 @@ pub async fn get_active_orders(
     State(state): State<AppState>,
     Query(params): Query<OrderQueryParams>,
 ) -> ApiResult<Json<OrderListResponse>> {
+    let amount = parse_estimate_amount(params.amount.as_deref())?;
+    let policy = state.db.get_token_policy().await?;
     let orders = if let Some(seller) = params.seller {
-        // Get orders by seller
-        state.db.get_orders_by_seller(&seller).await?
+        // Get orders by seller (suspended ones included, flagged)
+        state.db.get_orders_by_seller(&seller.parse()?).await?
     } else {
-        // Get all active orders
-        state.db.get_active_orders(params.limit).await?
+        // Get all active orders of tradable tokens
+        state.db.get_active_orders(params.limit, &policy).await?
     };
     
+    let reputations = seller_reputations(&state, &orders).await?;
     let order_dtos: Vec<OrderDto> = orders
         .into_iter()
-        .map(|o| OrderDto {
-            order_id: o.order_id,
-            seller: o.seller,
-            token: o.token,
-            total_amount: o.total_amount,
-            remaining_amount: o.remaining_amount,
-            exchange_rate: o.exchange_rate,
-            alipay_id: o.alipay_id,
-            alipay_name: o.alipay_name,
-            created_at: o.created_at,
+        .map(|o| {
+            OrderDto::new(o, &policy)
+                .with_display(&state.token_display, params.locale)
+                .with_estimate(amount, &state.token_display, params.locale)
+                .with_reputation(&reputations)
         })
         .collect();
     
@@ -89,6 +341,30 @@ pub async fn get_active_orders(
     Ok(Json(OrderListResponse {
         orders: order_dtos,
         total,
+        market_paused: state.market_paused().await,
+        sync: sync_status(&state).await?,
+    }))
+}
+
+/// Per-token counters for the landing page (no order lists): active orders, liquidity,
+/// best rate and 24h settled volume, aggregated by the database in one query
+pub async fn orderbook_summary_handler(
+    State(state): State<AppState>,
+    Query(params): Query<DisplayQuery>,
+) -> ApiResult<Json<OrderbookSummaryResponse>> {
+    let policy = state.db.get_token_policy().await?;
+    let summary = state
+        .db
+        .get_orderbook_summary(&policy, chrono::Utc::now() - chrono::Duration::hours(24))
+        .await?;
+
+    Ok(Json(OrderbookSummaryResponse {
+        tokens: summary
+            .into_iter()
+            .map(|summary| TokenSummaryDto::new(summary, &state.token_display, params.locale))
+            .collect(),
+        market_paused: state.market_paused().await,
+        sync: sync_status(&state).await?,
     }))
 }
 
@@ -96,45 +372,253 @@ pub async fn get_active_orders(
 pub async fn get_order(
     State(state): State<AppState>,
     Path(order_id): Path<String>,
+    Query(params): Query<OrderQuery>,
 ) -> ApiResult<Json<OrderDto>> {
-    let order = state.db.get_order(&order_id).await?;
+    let amount = parse_estimate_amount(params.amount.as_deref())?;
+    let order = state.db.get_order(&order_id.parse::<OrderId>()?.to_string()).await?;
+    let policy = state.db.get_token_policy().await?;
+    let reputations = seller_reputations(&state, std::slice::from_ref(&order)).await?;
     
-    Ok(Json(OrderDto {
-        order_id: order.order_id,
-        seller: order.seller,
-        token: order.token,
-        total_amount: order.total_amount,
-        remaining_amount: order.remaining_amount,
-        exchange_rate: order.exchange_rate,
-        alipay_id: order.alipay_id,
-        alipay_name: order.alipay_name,
-        created_at: order.created_at,
-    }))
+    Ok(Json(
+        OrderDto::new(order, &policy)
+            .with_display(&state.token_display, params.locale)
+            .with_estimate(amount, &state.token_display, params.locale)
+            .with_reputation(&reputations),
+    ))
 }
 
 /// Match a buy intent against available orders
 pub async fn match_buy_intent_handler(
     State(state): State<AppState>,
     Json(req): Json<MatchBuyRequest>,
-) -> ApiResult<Json<MatchPlan>> {
+) -> ApiResult<Json<MatchResponse>> {
+    // Don't hand out fills against remaining amounts the listener hasn't caught up on
+    let sync = sync_status(&state).await?;
+    ensure_fresh(&state, &sync)?;
+    let request = serde_json::json!({
+        "token_address": req.token_address,
+        "desired_amount": req.desired_amount,
+        "max_rate": req.max_rate,
+        "buyer_address": req.buyer_address,
+        "exclude_stale": req.exclude_stale,
+    });
+    
     // Parse desired amount
     let desired_amount = Decimal::from_str(&req.desired_amount)
         .map_err(|e| crate::api::error::ApiError::BadRequest(format!("Invalid amount: {}", e)))?;
     
     // Parse max rate if provided
-    let max_rate = if let Some(rate_str) = req.max_rate {
-        Some(Decimal::from_str(&rate_str)
+    let max_rate = if let Some(rate_str) = req.max_rate.as_deref() {
+        Some(Decimal::from_str(rate_str)
             .map_err(|e| crate::api::error::ApiError::BadRequest(format!("Invalid rate: {}", e)))?)
     } else {
         None
     };
     
-    // Fetch active orders from DB filtered by token address
-    let orders = state.db.get_active_orders_by_token(&req.token_address, Some(100)).await?;
+    // Delisted tokens aren't matched
+    let token: EthAddress = req.token_address.parse()?;
+    if state.db.get_token_policy().await?.is_suspended(&token) {
+        return Err(ApiError::TokenSuspended(token.to_checksum()));
+    }
+    
+    // The plan isn't priced in CNY until execute-fill reads the token's decimals, so only
+    // a buyer without any room left is refused here
+    let buyer = req.buyer_address.as_deref().map(str::parse::<EthAddress>).transpose()?;
+    let spending_limit = match &buyer {
+        Some(buyer) => spending_limits::check_buyer(&state.db, state.kyc_provider.as_deref(), buyer, 1)
+            .await?
+            .map_err(|exceeded| ApiError::SpendingLimitExceeded(Box::new(exceeded)))?,
+        None => None,
+    };
+    
+    // Active orders of the token in the deployment's priority, from memory when cached
+    let mut orders = match &state.orderbook_cache {
+        Some(cache) => {
+            let (orders, hit) = cache.orders(&state.db, &token, state.order_priority).await?;
+            if hit {
+                state.metrics.orderbook_cache.record_hit();
+            } else {
+                state.metrics.orderbook_cache.record_miss();
+            }
+            orders.as_ref().clone()
+        }
+        None => state.db.get_active_orders_by_token(&token, Some(ORDERBOOK_DEPTH), state.order_priority).await?,
+    };
+    
+    // Buyers who'd rather not lock a trade with a seller who may have walked away
+    if req.exclude_stale {
+        let settings = state.db.get_order_staleness_settings().await?;
+        let stale_before = chrono::Utc::now() - chrono::Duration::days(settings.stale_after_days as i64);
+        let stale: HashSet<String> = state
+            .db
+            .get_stale_orders(Some(&token), stale_before, None)
+            .await?
+            .into_iter()
+            .map(|order| order.order_id)
+            .collect();
+        orders.retain(|order| !stale.contains(&order.order_id));
+    }
     
     // Match buy intent
-    let match_plan = match_buy_intent(orders, desired_amount, max_rate)
+    let book_hash = book_hash(&orders);
+    let mut match_plan = match_buy_intent_with_priority(orders, desired_amount, max_rate, state.order_priority)
         .map_err(|e| crate::api::error::ApiError::BadRequest(e.to_string()))?;
+    display_plan(&mut match_plan, &state.token_display, req.locale);
+    
+    // Kept with the request and the book it was matched against, so the fills of its
+    // trades can be explained after the fact
+    let expires_at = chrono::Utc::now() + chrono::Duration::seconds(state.match_plan_ttl_secs as i64);
+    let plan_id = state
+        .db
+        .create_match_plan(&NewMatchPlan {
+            token,
+            buyer,
+            request: request.to_string(),
+            plan: serde_json::to_string(&match_plan).map_err(|e| ApiError::Internal(e.to_string()))?,
+            book_hash,
+            order_priority: state.order_priority.as_str().to_string(),
+            expires_at,
+        })
+        .await?;
     
-    Ok(Json(match_plan))
+    Ok(Json(MatchResponse {
+        plan: match_plan,
+        market_paused: state.market_paused().await,
+        sync,
+        spending_limit,
+        plan_id,
+        plan_expires_at: expires_at.timestamp(),
+    }))
+}
+
+/// How old a fill-limits signature may be
+const FILL_LIMITS_SIGNATURE_TTL_SECS: i64 = 600;
+
+/// Request to set an order's fill limits, signed by the seller
+#[derive(Debug, Deserialize)]
+pub struct SetFillLimitsRequest {
+    /// Minimum fill in token base units (omit to clear)
+    pub min_fill: Option<String>,
+    /// Lot size in token base units (omit to clear)
+    pub lot_size: Option<String>,
+    /// Unix timestamp included in the signed message
+    pub signed_at: i64,
+    /// Seller's personal_sign signature over `fill_limits_message`
+    pub signature: String,
+}
+
+/// Message the seller signs (EIP-191 personal_sign) to set fill limits
+pub fn fill_limits_message(order_id: &str, min_fill: Option<&str>, lot_size: Option<&str>, signed_at: i64) -> String {
+    format!(
+        "zkAlipay fill limits\nOrder: {}\nMin fill: {}\nLot size: {}\nSigned at: {}",
+        order_id,
+        min_fill.unwrap_or("none"),
+        lot_size.unwrap_or("none"),
+        signed_at
+    )
+}
+
+/// Parse a positive whole token amount
+fn parse_limit(value: Option<&str>, name: &str) -> ApiResult<Option<Decimal>> {
+    value
+        .map(|v| match Decimal::from_str(v) {
+            Ok(amount) if amount > Decimal::ZERO && amount.fract().is_zero() => Ok(amount),
+            _ => Err(ApiError::BadRequest(format!("Invalid {}: expected a positive integer amount, got {:?}", name, v))),
+        })
+        .transpose()
+}
+
+/// POST /api/orders/:order_id/fill-limits
+/// Set the order's minimum fill and lot size (orderbook-enforced seller preference,
+/// the contract doesn't know about them). Must be signed by the order's seller.
+pub async fn set_fill_limits_handler(
+    State(state): State<AppState>,
+    Path(order_id): Path<String>,
+    Json(req): Json<SetFillLimitsRequest>,
+) -> ApiResult<Json<OrderDto>> {
+    let order_id = order_id.parse::<OrderId>()?.to_string();
+    let min_fill = parse_limit(req.min_fill.as_deref(), "min_fill")?;
+    let lot_size = parse_limit(req.lot_size.as_deref(), "lot_size")?;
+
+    let age = chrono::Utc::now().timestamp() - req.signed_at;
+    if !(-60..=FILL_LIMITS_SIGNATURE_TTL_SECS).contains(&age) {
+        return Err(ApiError::BadRequest(format!(
+            "signed_at must be within the last {} seconds",
+            FILL_LIMITS_SIGNATURE_TTL_SECS
+        )));
+    }
+
+    let order = state.db.get_order(&order_id).await?;
+    let message = fill_limits_message(
+        &order_id,
+        min_fill.map(|m| m.to_string()).as_deref(),
+        lot_size.map(|l| l.to_string()).as_deref(),
+        req.signed_at,
+    );
+    let signer = Signature::from_str(&req.signature)
+        .and_then(|signature| signature.recover(message.as_str()))
+        .map_err(|e| ApiError::BadRequest(format!("Invalid signature: {}", e)))?;
+    if signer != order.seller.to_address() {
+        return Err(ApiError::Forbidden("Fill limits must be signed by the order's seller".to_string()));
+    }
+
+    if !state.db.set_order_fill_limits(&order_id, min_fill, lot_size, req.signed_at).await? {
+        return Err(ApiError::BadRequest("A newer fill-limits signature is already applied".to_string()));
+    }
+    tracing::info!("Fill limits for order {}: min_fill={:?}, lot_size={:?}", order_id, min_fill, lot_size);
+
+    let order = state.db.get_order(&order_id).await?;
+    let policy = state.db.get_token_policy().await?;
+    Ok(Json(OrderDto::new(order, &policy).with_display(&state.token_display, Locale::default())))
+}
+
+/// Request to refresh an order (mark it looked after), signed by the seller
+#[derive(Debug, Deserialize)]
+pub struct RefreshOrderRequest {
+    /// Unix timestamp included in the signed message
+    pub signed_at: i64,
+    /// Seller's personal_sign signature over `refresh_order_message`
+    pub signature: String,
+}
+
+/// Message the seller signs (EIP-191 personal_sign) to refresh an order
+pub fn refresh_order_message(order_id: &str, signed_at: i64) -> String {
+    format!("zkAlipay order refresh\nOrder: {}\nSigned at: {}", order_id, signed_at)
+}
+
+/// POST /api/orders/:order_id/refresh
+/// Confirm the seller still watches the order's Alipay account: resets its idle time so
+/// it isn't reported stale (nor skipped by exclude_stale matching). Must be signed by
+/// the order's seller.
+pub async fn refresh_order_handler(
+    State(state): State<AppState>,
+    Path(order_id): Path<String>,
+    Json(req): Json<RefreshOrderRequest>,
+) -> ApiResult<Json<OrderDto>> {
+    let order_id = order_id.parse::<OrderId>()?.to_string();
+
+    let age = chrono::Utc::now().timestamp() - req.signed_at;
+    if !(-60..=FILL_LIMITS_SIGNATURE_TTL_SECS).contains(&age) {
+        return Err(ApiError::BadRequest(format!(
+            "signed_at must be within the last {} seconds",
+            FILL_LIMITS_SIGNATURE_TTL_SECS
+        )));
+    }
+
+    let order = state.db.get_order(&order_id).await?;
+    let message = refresh_order_message(&order_id, req.signed_at);
+    let signer = Signature::from_str(&req.signature)
+        .and_then(|signature| signature.recover(message.as_str()))
+        .map_err(|e| ApiError::BadRequest(format!("Invalid signature: {}", e)))?;
+    if signer != order.seller.to_address() {
+        return Err(ApiError::Forbidden("Order refreshes must be signed by the order's seller".to_string()));
+    }
+
+    if !state.db.refresh_order(&order_id, req.signed_at).await? {
+        return Err(ApiError::BadRequest("A newer refresh signature is already applied".to_string()));
+    }
+    tracing::info!("Order {} refreshed by its seller", order_id);
+
+    let policy = state.db.get_token_policy().await?;
+    Ok(Json(OrderDto::new(order, &policy).with_display(&state.token_display, Locale::default())))
 }
@@ -0,0 +1,226 @@
+use axum::{
+    extract::{Path, State},
+    Extension, Json,
+};
+use chrono::{DateTime, Utc};
+use ethers::types::Signature;
+use serde::{Deserialize, Serialize};
+use std::str::FromStr;
+
+use crate::api::{
+    api_keys::ApiKeyIdentity,
+    error::{ApiError, ApiResult},
+    state::AppState,
+};
+use crate::blockchain::address::EthAddress;
+use crate::blockchain::ids::TradeId;
+use crate::db::models::{DbTrade, DbTradePayment};
+use crate::db::trade_payments::{NewTradePayment, AMOUNT_EXACT, AMOUNT_OVER, AMOUNT_SHORT};
+
+/// Declarations a trade may hold (a buyer correcting a typo declares again)
+const MAX_PAYMENTS_PER_TRADE: usize = 10;
+
+/// How old a declaration signature may be
+const PAYMENT_SIGNATURE_TTL_SECS: i64 = 600;
+
+/// Clock skew allowed between the buyer's declared transfer time and the chain's
+const PAID_AT_SKEW_SECS: i64 = 300;
+
+/// Message the buyer signs (EIP-191 personal_sign) to declare a transfer
+pub fn declare_payment_message(trade_id: &str, amount_cents: i64, paid_at: i64, signed_at: i64) -> String {
+    format!(
+        "zkAlipay payment declaration\nTrade: {}\nAmount (CNY cents): {}\nPaid at: {}\nSigned at: {}",
+        trade_id, amount_cents, paid_at, signed_at
+    )
+}
+
+/// How a declared amount compares with the amount the trade requires
+pub fn amount_status(amount: i64, required: i64) -> &'static str {
+    match amount.cmp(&required) {
+        std::cmp::Ordering::Equal => AMOUNT_EXACT,
+        std::cmp::Ordering::Less => AMOUNT_SHORT,
+        std::cmp::Ordering::Greater => AMOUNT_OVER,
+    }
+}
+
+/// A trade's cnyAmount in cents
+fn required_amount(trade: &DbTrade) -> ApiResult<i64> {
+    trade
+        .cny_amount
+        .parse()
+        .map_err(|_| ApiError::Internal(format!("Invalid CNY amount on trade {}: {}", trade.trade_id, trade.cny_amount)))
+}
+
+#[derive(Debug, Deserialize)]
+pub struct DeclarePaymentRequest {
+    /// CNY cents transferred
+    pub amount_cents: i64,
+    /// Unix timestamp of the transfer
+    pub paid_at: i64,
+    /// Unix timestamp included in the signed message (not needed with the buyer's API key)
+    pub signed_at: Option<i64>,
+    pub signature: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct TradePaymentsResponse {
+    pub trade_id: String,
+    /// CNY cents the trade requires
+    pub required_amount: i64,
+    /// Oldest first
+    pub payments: Vec<DbTradePayment>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct DeclarePaymentResponse {
+    #[serde(flatten)]
+    pub payment: DbTradePayment,
+    pub required_amount: i64,
+    /// Set when the amount doesn't match the trade
+    pub warning: Option<String>,
+}
+
+/// GET /api/trades/:trade_id/payments
+/// Transfers the buyer declared for the trade, oldest first
+pub async fn get_trade_payments_handler(
+    State(state): State<AppState>,
+    Path(trade_id): Path<String>,
+) -> ApiResult<Json<TradePaymentsResponse>> {
+    let trade_id = trade_id.parse::<TradeId>()?.to_string();
+    let trade = state.db.get_trade(&trade_id).await?;
+    let payments = state.db.get_trade_payments(&trade_id).await?;
+
+    Ok(Json(TradePaymentsResponse {
+        required_amount: required_amount(&trade)?,
+        trade_id,
+        payments,
+    }))
+}
+
+/// POST /api/trades/:trade_id/payments
+/// Declare an Alipay transfer made for the trade, before uploading its receipt (buyer
+/// only: the buyer's API key, or a signature of `declare_payment_message`). A declared
+/// amount other than the trade's is recorded but flagged, naming the buyer's other
+/// pending trade of that amount if there is one.
+pub async fn declare_trade_payment_handler(
+    State(state): State<AppState>,
+    Path(trade_id): Path<String>,
+    api_key: Option<Extension<ApiKeyIdentity>>,
+    Json(req): Json<DeclarePaymentRequest>,
+) -> ApiResult<Json<DeclarePaymentResponse>> {
+    let trade_id = trade_id.parse::<TradeId>()?.to_string();
+    let trade = state.db.get_trade(&trade_id).await?;
+
+    let is_buyer_key = api_key.as_ref().is_some_and(|Extension(key)| key.wallet == trade.buyer);
+    if !is_buyer_key {
+        let (Some(signed_at), Some(signature)) = (req.signed_at, req.signature.as_deref()) else {
+            return Err(ApiError::Forbidden("Declaring a payment requires the buyer's signature".to_string()));
+        };
+        let age = Utc::now().timestamp() - signed_at;
+        if !(-60..=PAYMENT_SIGNATURE_TTL_SECS).contains(&age) {
+            return Err(ApiError::BadRequest(format!(
+                "signed_at must be within the last {} seconds",
+                PAYMENT_SIGNATURE_TTL_SECS
+            )));
+        }
+        let message = declare_payment_message(&trade_id, req.amount_cents, req.paid_at, signed_at);
+        let signer: EthAddress = Signature::from_str(signature)
+            .and_then(|signature| signature.recover(message.as_str()))
+            .map_err(|e| ApiError::BadRequest(format!("Invalid signature: {}", e)))?
+            .into();
+        if signer != trade.buyer {
+            return Err(ApiError::Forbidden("Only the trade's buyer can declare its payments".to_string()));
+        }
+    }
+
+    if trade.status != 0 {
+        return Err(ApiError::BadRequest(format!("Trade {} is not pending", trade_id)));
+    }
+    if trade.pdf_uploaded_at.is_some() {
+        return Err(ApiError::BadRequest("The receipt is already uploaded - declare payments before uploading it".to_string()));
+    }
+    if req.amount_cents <= 0 {
+        return Err(ApiError::BadRequest("amount_cents must be positive".to_string()));
+    }
+    let now = Utc::now().timestamp();
+    if req.paid_at > now + 60 {
+        return Err(ApiError::BadRequest("paid_at is in the future".to_string()));
+    }
+    if req.paid_at < trade.created_at - PAID_AT_SKEW_SECS {
+        return Err(ApiError::BadRequest("paid_at is before the trade was created".to_string()));
+    }
+    let paid_at = DateTime::<Utc>::from_timestamp(req.paid_at, 0)
+        .ok_or_else(|| ApiError::BadRequest(format!("Invalid paid_at: {}", req.paid_at)))?;
+    if state.db.get_trade_payments(&trade_id).await?.len() >= MAX_PAYMENTS_PER_TRADE {
+        return Err(ApiError::BadRequest(format!("This trade already has {} declared payments", MAX_PAYMENTS_PER_TRADE)));
+    }
+
+    let required = required_amount(&trade)?;
+    let status = amount_status(req.amount_cents, required);
+    // The usual mix-up with multi-fill buys: this transfer was meant for another fill
+    let matches_trade_id = if status == AMOUNT_EXACT {
+        None
+    } else {
+        state
+            .db
+            .get_trades_by_buyer(&trade.buyer)
+            .await?
+            .into_iter()
+            .find(|other| {
+                other.status == 0
+                    && other.trade_id != trade_id
+                    && other.cny_amount.parse::<i64>().ok() == Some(req.amount_cents)
+            })
+            .map(|other| other.trade_id)
+    };
+
+    let payment = state
+        .db
+        .declare_trade_payment(&NewTradePayment {
+            trade_id: trade_id.clone(),
+            buyer: trade.buyer.clone(),
+            amount: req.amount_cents,
+            paid_at,
+            amount_status: status,
+            matches_trade_id,
+        })
+        .await?;
+    tracing::info!("🧾 Payment of {} cents declared on trade {} ({})", payment.amount, trade_id, status);
+
+    let warning = match (status, &payment.matches_trade_id) {
+        (AMOUNT_EXACT, _) => None,
+        (_, Some(other)) => Some(format!(
+            "This trade requires {} cents; {} cents is the amount of your trade {} - check which trade this transfer was for",
+            required, payment.amount, other
+        )),
+        (AMOUNT_SHORT, None) => Some(format!(
+            "This trade requires {} cents; {} cents is short and the receipt will not prove the payment",
+            required, payment.amount
+        )),
+        _ => Some(format!(
+            "This trade requires {} cents; {} cents is more than the trade's amount",
+            required, payment.amount
+        )),
+    };
+
+    Ok(Json(DeclarePaymentResponse {
+        payment,
+        required_amount: required,
+        warning,
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_amount_status_and_message() {
+        assert_eq!(amount_status(10_000, 10_000), AMOUNT_EXACT);
+        assert_eq!(amount_status(9_999, 10_000), AMOUNT_SHORT);
+        assert_eq!(amount_status(12_000, 10_000), AMOUNT_OVER);
+
+        let message = declare_payment_message("0xab", 10_000, 1_700_000_000, 1_700_000_100);
+        assert!(message.contains("Amount (CNY cents): 10000\nPaid at: 1700000000\n"));
+    }
+}
@@ -1,13 +1,93 @@
 use axum::{
-    extract::{Path, State, Multipart},
+    body::Body,
+    extract::{multipart::Field, Path, Query, State, Multipart},
     http::{header, StatusCode},
     response::{IntoResponse, Response},
-    Json,
+    Extension, Json,
 };
+use ethers::types::Signature;
+use futures::TryStreamExt;
 use serde::{Deserialize, Serialize};
-use tracing::{info, error};
+use std::str::FromStr;
+use std::sync::Arc;
+use tokio::sync::Semaphore;
+use tracing::{info, error, warn};
 
-use crate::api::{error::ApiResult, state::AppState, ApiError};
+use crate::api::{api_keys::ApiKeyIdentity, error::ApiResult, state::AppState, ApiError};
+use crate::api::handlers::generate_proof::{validate_trade_pdf, ValidatePdfAxiomResponse};
+use crate::blockchain::address::EthAddress;
+use crate::blockchain::ids::TradeId;
+use crate::db::blobs::TradeBlob;
+use crate::db::models::DbTrade;
+use crate::pdf_text::{extract_payment_nonces, PdfTextError};
+
+/// Max size of one PDF (10MB)
+pub const MAX_PDF_SIZE: usize = 10 * 1024 * 1024;
+
+/// Max files in one bulk upload
+pub const MAX_BULK_PDFS: usize = 20;
+
+/// Largest bulk upload request (receipts are a few hundred KB; each file is still
+/// refused past MAX_PDF_SIZE as it arrives)
+pub const MAX_BULK_UPLOAD_SIZE: usize = 50 * 1024 * 1024;
+
+/// How old a PDF upload signature may be
+const PDF_SIGNATURE_TTL_SECS: i64 = 600;
+
+/// Files matched and validated at once in a bulk upload (each validation is an Axiom execute call)
+const BULK_CONCURRENCY: usize = 4;
+
+/// Reject anything that isn't a PDF within the size limit
+fn check_pdf(data: &[u8]) -> ApiResult<()> {
+    // Simple magic number check
+    if !data.starts_with(b"%PDF") {
+        return Err(ApiError::BadRequest("File is not a valid PDF".to_string()));
+    }
+    if data.len() > MAX_PDF_SIZE {
+        return Err(ApiError::BadRequest("PDF file too large (max 10MB)".to_string()));
+    }
+    Ok(())
+}
+
+/// Message the buyer signs (EIP-191 personal_sign) to upload receipts for their trades
+pub fn pdf_upload_message(buyer: &EthAddress, signed_at: i64) -> String {
+    format!("zkAlipay PDF upload\nBuyer: {}\nSigned at: {}", buyer, signed_at)
+}
+
+/// Query parameters of the PDF upload routes
+#[derive(Debug, Default, Deserialize)]
+pub struct UploadPdfQuery {
+    /// Unix timestamp included in the signed message (not needed with the buyer's API key)
+    pub signed_at: Option<i64>,
+    pub signature: Option<String>,
+}
+
+/// Refuse an upload unless it comes from the buyer: their API key, or a recent signature
+/// of `pdf_upload_message` (a new PDF clears the trade's proof)
+fn check_pdf_uploader(buyer: &EthAddress, api_key: Option<&ApiKeyIdentity>, signed_at: Option<i64>, signature: Option<&str>) -> ApiResult<()> {
+    if api_key.is_some_and(|key| key.wallet == *buyer) {
+        return Ok(());
+    }
+    let (Some(signed_at), Some(signature)) = (signed_at, signature) else {
+        return Err(ApiError::Forbidden("Uploading PDFs requires the buyer's signature".to_string()));
+    };
+    let age = chrono::Utc::now().timestamp() - signed_at;
+    if !(-60..=PDF_SIGNATURE_TTL_SECS).contains(&age) {
+        return Err(ApiError::BadRequest(format!(
+            "signed_at must be within the last {} seconds",
+            PDF_SIGNATURE_TTL_SECS
+        )));
+    }
+    let message = pdf_upload_message(buyer, signed_at);
+    let signer: EthAddress = Signature::from_str(signature)
+        .and_then(|signature| signature.recover(message.as_str()))
+        .map_err(|e| ApiError::BadRequest(format!("Invalid signature: {}", e)))?
+        .into();
+    if signer != *buyer {
+        return Err(ApiError::Forbidden("Signature is not the buyer's".to_string()));
+    }
+    Ok(())
+}
 
 #[derive(Debug, Serialize, Deserialize)]
 pub struct UploadPdfResponse {
@@ -17,18 +97,8 @@ pub struct UploadPdfResponse {
     pub uploaded_at: String,
 }
 
-/// Upload PDF for a trade
-pub async fn upload_pdf_handler(
-    State(state): State<AppState>,
-    Path(trade_id): Path<String>,
-    mut multipart: Multipart,
-) -> ApiResult<Json<UploadPdfResponse>> {
-    info!("📤 Uploading PDF for trade {}", trade_id);
-
-    // Validate trade exists
-    let trade = state.db.get_trade(&trade_id).await?;
-    
-    // Extract PDF file from multipart data
+/// Read the `pdf` field of a single-file upload, returning (data, filename)
+async fn read_pdf_field(multipart: &mut Multipart) -> ApiResult<(Vec<u8>, String)> {
     let mut pdf_data: Option<Vec<u8>> = None;
     let mut filename: Option<String> = None;
     
@@ -45,15 +115,7 @@ pub async fn upload_pdf_handler(
                 ApiError::BadRequest("Failed to read PDF file".to_string())
             })?;
             
-            // Validate it's a PDF (simple magic number check)
-            if !data.starts_with(b"%PDF") {
-                return Err(ApiError::BadRequest("File is not a valid PDF".to_string()));
-            }
-            
-            // Limit PDF size to 10MB
-            if data.len() > 10 * 1024 * 1024 {
-                return Err(ApiError::BadRequest("PDF file too large (max 10MB)".to_string()));
-            }
+            check_pdf(&data)?;
             
             pdf_data = Some(data.to_vec());
         }
@@ -63,7 +125,32 @@ pub async fn upload_pdf_handler(
         ApiError::BadRequest("No PDF file provided".to_string())
     })?;
     
-    let filename = filename.unwrap_or_else(|| "payment.pdf".to_string());
+    Ok((pdf_data, filename.unwrap_or_else(|| "payment.pdf".to_string())))
+}
+
+/// Upload PDF for a trade
+/// Buyer only (the buyer's API key, or `signed_at` and a signature of `pdf_upload_message`)
+pub async fn upload_pdf_handler(
+    State(state): State<AppState>,
+    Path(trade_id): Path<String>,
+    api_key: Option<Extension<ApiKeyIdentity>>,
+    Query(params): Query<UploadPdfQuery>,
+    mut multipart: Multipart,
+) -> ApiResult<Json<UploadPdfResponse>> {
+    let trade_id = trade_id.parse::<TradeId>()?.to_string();
+    info!("📤 Uploading PDF for trade {}", trade_id);
+
+    // Validate trade exists; a settled trade's PDF and proof are final
+    let trade = state.db.get_trade(&trade_id).await?;
+    check_pdf_uploader(&trade.buyer, api_key.as_ref().map(|Extension(key)| key), params.signed_at, params.signature.as_deref())?;
+    if trade.status != 0 {
+        return Err(ApiError::BadRequest(format!("Trade {} is not pending", trade_id)));
+    }
+    
+    let (pdf_data, filename) = read_pdf_field(&mut multipart).await?;
+    
+    // Refuse a receipt whose readable payment nonce belongs to another trade
+    check_pdf_nonce(&trade, &pdf_nonces(&pdf_data).await?)?;
     
     info!("📄 Saving PDF: {} ({} bytes)", filename, pdf_data.len());
     
@@ -80,35 +167,364 @@ pub async fn upload_pdf_handler(
     }))
 }
 
+#[derive(Debug, Serialize)]
+pub struct MatchedPdfResponse {
+    #[serde(flatten)]
+    pub upload: UploadPdfResponse,
+    /// Payment nonce found in the PDF that identified the trade
+    pub payment_nonce: String,
+}
+
+/// POST /api/trades/buyer/:buyer_address/pdf
+/// Upload a PDF without choosing the trade: it is attached to the buyer's PENDING trade
+/// whose payment nonce appears in the PDF text. Buyer only, as for a single trade.
+pub async fn upload_buyer_pdf_handler(
+    State(state): State<AppState>,
+    Path(buyer_address): Path<String>,
+    api_key: Option<Extension<ApiKeyIdentity>>,
+    Query(params): Query<UploadPdfQuery>,
+    mut multipart: Multipart,
+) -> ApiResult<Json<MatchedPdfResponse>> {
+    let buyer_address: EthAddress = buyer_address.parse()?;
+    info!("📤 Uploading PDF for a pending trade of buyer {}", buyer_address);
+    check_pdf_uploader(&buyer_address, api_key.as_ref().map(|Extension(key)| key), params.signed_at, params.signature.as_deref())?;
+    
+    let (pdf_data, filename) = read_pdf_field(&mut multipart).await?;
+    let trade = match_trade_by_nonce(&state, &pdf_data, Some(&buyer_address)).await?;
+    
+    info!("🔎 PDF matched trade {} by nonce {}", trade.trade_id, trade.payment_nonce);
+    
+    let uploaded_at = state.db.save_trade_pdf(&trade.trade_id, &pdf_data, &filename).await?;
+    
+    Ok(Json(MatchedPdfResponse {
+        upload: UploadPdfResponse {
+            trade_id: trade.trade_id,
+            filename,
+            size: pdf_data.len(),
+            uploaded_at: uploaded_at.to_rfc3339(),
+        },
+        payment_nonce: trade.payment_nonce,
+    }))
+}
+
 /// Get PDF for a trade
 pub async fn get_pdf_handler(
     State(state): State<AppState>,
     Path(trade_id): Path<String>,
 ) -> ApiResult<Response> {
+    let trade_id = trade_id.parse::<TradeId>()?.to_string();
     info!("📥 Retrieving PDF for trade {}", trade_id);
     
-    let trade = state.db.get_trade(&trade_id).await?;
-    
-    let pdf_data = trade.pdf_file.ok_or_else(|| {
+    // Size first, then stream the file in chunks rather than loading it whole
+    let info = state.db.get_trade_blob_info(&trade_id, TradeBlob::Pdf).await?.ok_or_else(|| {
         ApiError::NotFound("No PDF uploaded for this trade".to_string())
     })?;
     
-    let filename = trade.pdf_filename.unwrap_or_else(|| "payment.pdf".to_string());
+    let filename = info.filename.clone().unwrap_or_else(|| "payment.pdf".to_string());
     
-    info!("✅ Returning PDF: {} ({} bytes)", filename, pdf_data.len());
+    info!("✅ Streaming PDF: {} ({} bytes)", filename, info.len);
+    
+    let len = info.len;
+    let body = state.db.stream_trade_blob(&trade_id, TradeBlob::Pdf, info)
+        .inspect_err(move |e| warn!("PDF download for trade {} aborted: {}", trade_id, e));
     
     // Return PDF as response
     Ok((
         StatusCode::OK,
         [
-            (header::CONTENT_TYPE, "application/pdf"),
+            (header::CONTENT_TYPE, "application/pdf".to_string()),
             (
                 header::CONTENT_DISPOSITION,
-                &format!("inline; filename=\"{}\"", filename),
+                format!("inline; filename=\"{}\"", filename),
             ),
+            (header::CONTENT_LENGTH, len.to_string()),
         ],
-        pdf_data,
+        Body::from_stream(body),
     )
         .into_response())
 }
 
+
+// ============================================================================
+// Bulk Upload
+// ============================================================================
+
+/// Query parameters of POST /api/pdfs/bulk
+#[derive(Debug, Deserialize)]
+pub struct BulkPdfQuery {
+    /// Buyer whose pending trades the files are for
+    pub buyer: String,
+    /// Unix timestamp included in the signed message (not needed with the buyer's API key)
+    pub signed_at: Option<i64>,
+    pub signature: Option<String>,
+}
+
+/// How a bulk-uploaded file was assigned to its trade
+const MATCHED_BY_TAG: &str = "tag";
+const MATCHED_BY_NONCE: &str = "nonce";
+
+#[derive(Debug, Serialize)]
+pub struct BulkPdfResult {
+    pub filename: String,
+    pub trade_id: Option<String>,
+    /// "tag" (pdf:<trade_id> field) or "nonce" (payment nonce found in the PDF text)
+    pub matched_by: Option<&'static str>,
+    pub uploaded: bool,
+    pub validation: Option<ValidatePdfAxiomResponse>,
+    pub error: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct BulkPdfResponse {
+    pub total: usize,
+    pub uploaded: usize,
+    pub valid: usize,
+    pub results: Vec<BulkPdfResult>,
+}
+
+struct BulkPdf {
+    filename: String,
+    tag: Option<String>,
+    data: Vec<u8>,
+}
+
+/// Candidate payment nonces from a PDF's text layer (extraction runs on a blocking thread)
+async fn pdf_nonces(data: &[u8]) -> ApiResult<Result<Vec<String>, PdfTextError>> {
+    let data = data.to_vec();
+    tokio::task::spawn_blocking(move || extract_payment_nonces(&data))
+        .await
+        .map_err(|e| ApiError::Internal(format!("PDF text extraction task failed: {}", e)))
+}
+
+/// Reject a receipt uploaded to the wrong trade: if nonces could be read, one must be the trade's
+fn check_pdf_nonce(trade: &DbTrade, nonces: &Result<Vec<String>, PdfTextError>) -> ApiResult<()> {
+    match nonces {
+        Ok(nonces) if !nonces.is_empty() && !nonces.contains(&trade.payment_nonce) => {
+            Err(ApiError::BadRequest(format!(
+                "PDF does not contain payment nonce {} of trade {}",
+                trade.payment_nonce, trade.trade_id
+            )))
+        }
+        _ => Ok(()),
+    }
+}
+
+/// The PENDING trade whose payment nonce appears in the PDF (optionally only this buyer's)
+async fn match_trade_by_nonce(state: &AppState, data: &[u8], buyer: Option<&EthAddress>) -> ApiResult<DbTrade> {
+    let nonces = pdf_nonces(data)
+        .await?
+        .map_err(|e| ApiError::BadRequest(format!("Cannot match PDF by nonce: {}", e)))?;
+
+    let mut matches = Vec::new();
+    for nonce in &nonces {
+        if let Some(trade) = state.db.get_trade_by_nonce(nonce).await? {
+            if trade.status == 0 && buyer.is_none_or(|buyer| *buyer == trade.buyer) {
+                matches.push(trade);
+            }
+        }
+    }
+
+    if matches.len() > 1 {
+        let trade_ids: Vec<&str> = matches.iter().map(|t| t.trade_id.as_str()).collect();
+        return Err(ApiError::BadRequest(format!(
+            "PDF matches several pending trades ({}), upload it to a specific trade",
+            trade_ids.join(", ")
+        )));
+    }
+
+    matches.pop().ok_or_else(|| {
+        ApiError::NotFound(format!(
+            "No pending trade matches the payment nonces in this PDF ({})",
+            if nonces.is_empty() { "none found".to_string() } else { nonces.join(", ") }
+        ))
+    })
+}
+
+/// Read a file field chunk by chunk, refusing it as soon as it passes MAX_PDF_SIZE
+/// rather than buffering it whole first
+async fn read_bulk_pdf(mut field: Field<'_>, filename: &str) -> ApiResult<Vec<u8>> {
+    let mut data = Vec::new();
+    while let Some(chunk) = field.chunk().await.map_err(|e| {
+        error!("Failed to read PDF bytes: {}", e);
+        ApiError::BadRequest(format!("Failed to read PDF file {}", filename))
+    })? {
+        if data.len() + chunk.len() > MAX_PDF_SIZE {
+            return Err(ApiError::BadRequest(format!("PDF file {} too large (max 10MB)", filename)));
+        }
+        data.extend_from_slice(&chunk);
+    }
+    Ok(data)
+}
+
+/// Assign a file to one of the buyer's PENDING trades: its tag if given, otherwise the
+/// payment nonce in its text
+async fn resolve_trade(state: &AppState, pdf: &BulkPdf, buyer: &EthAddress) -> ApiResult<(String, &'static str)> {
+    check_pdf(&pdf.data)?;
+
+    let Some(trade_id) = &pdf.tag else {
+        let trade = match_trade_by_nonce(state, &pdf.data, Some(buyer)).await?;
+        return Ok((trade.trade_id, MATCHED_BY_NONCE));
+    };
+
+    let trade = state.db.get_trade(&trade_id.parse::<TradeId>()?.to_string()).await?;
+    if trade.status != 0 {
+        return Err(ApiError::BadRequest(format!("Trade {} is not pending", trade_id)));
+    }
+    if *buyer != trade.buyer {
+        return Err(ApiError::BadRequest(format!("Trade {} belongs to a different buyer", trade_id)));
+    }
+    check_pdf_nonce(&trade, &pdf_nonces(&pdf.data).await?)?;
+
+    Ok((trade.trade_id, MATCHED_BY_TAG))
+}
+
+/// Save a matched file and run Axiom validation on it
+async fn upload_and_validate(state: &AppState, trade_id: &str, pdf: &BulkPdf) -> (bool, ApiResult<ValidatePdfAxiomResponse>) {
+    if let Err(e) = state.db.save_trade_pdf(trade_id, &pdf.data, &pdf.filename).await {
+        return (false, Err(e.into()));
+    }
+    (true, validate_trade_pdf(state, trade_id.to_string()).await)
+}
+
+/// POST /api/pdfs/bulk?buyer=<address>
+/// Upload and validate several payment PDFs for the buyer's pending trades at once.
+/// Buyer only (the buyer's API key, or `signed_at` and a signature of
+/// `pdf_upload_message`), checked before any file is read. Multipart fields:
+/// - `pdf:<trade_id>`: PDF for that trade
+/// - `pdf`: PDF matched to a pending trade by the payment nonce in its text
+///
+/// Files are processed concurrently (bounded); failures are reported per file.
+pub async fn bulk_upload_pdfs_handler(
+    State(state): State<AppState>,
+    api_key: Option<Extension<ApiKeyIdentity>>,
+    Query(params): Query<BulkPdfQuery>,
+    mut multipart: Multipart,
+) -> ApiResult<Json<BulkPdfResponse>> {
+    let buyer: EthAddress = params.buyer.parse()?;
+    check_pdf_uploader(&buyer, api_key.as_ref().map(|Extension(key)| key), params.signed_at, params.signature.as_deref())?;
+
+    let mut files = Vec::new();
+    while let Some(field) = multipart.next_field().await.map_err(|e| {
+        error!("Failed to read multipart field: {}", e);
+        ApiError::BadRequest("Invalid multipart data".to_string())
+    })? {
+        let field_name = field.name().unwrap_or("").to_string();
+
+        let tag = match field_name.as_str() {
+            "pdf" => None,
+            name => match name.strip_prefix("pdf:") {
+                Some(trade_id) => Some(trade_id.to_string()),
+                None => continue,
+            },
+        };
+
+        if files.len() == MAX_BULK_PDFS {
+            return Err(ApiError::BadRequest(format!("At most {} PDFs per bulk upload", MAX_BULK_PDFS)));
+        }
+
+        let filename = field.file_name()
+            .map(|s| s.to_string())
+            .unwrap_or_else(|| format!("payment-{}.pdf", files.len() + 1));
+        let data = read_bulk_pdf(field, &filename).await?;
+
+        files.push(BulkPdf { filename, tag, data });
+    }
+
+    if files.is_empty() {
+        return Err(ApiError::BadRequest("No PDF files provided".to_string()));
+    }
+
+    info!("📤 Bulk upload of {} PDFs (buyer: {})", files.len(), buyer);
+
+    let semaphore = Arc::new(Semaphore::new(BULK_CONCURRENCY));
+    let filenames: Vec<String> = files.iter().map(|pdf| pdf.filename.clone()).collect();
+
+    // Phase 1: assign every file to a trade
+    let handles: Vec<_> = files
+        .into_iter()
+        .map(|pdf| {
+            let state = state.clone();
+            let semaphore = semaphore.clone();
+            let buyer = buyer.clone();
+            tokio::spawn(async move {
+                let _permit = semaphore.acquire_owned().await.expect("semaphore is never closed");
+                let resolved = resolve_trade(&state, &pdf, &buyer).await;
+                (pdf, resolved)
+            })
+        })
+        .collect();
+
+    let mut results = Vec::with_capacity(handles.len());
+    let mut matched = Vec::new();
+    for (index, handle) in handles.into_iter().enumerate() {
+        let mut result = BulkPdfResult {
+            filename: filenames[index].clone(),
+            trade_id: None,
+            matched_by: None,
+            uploaded: false,
+            validation: None,
+            error: None,
+        };
+
+        match handle.await {
+            Ok((pdf, Ok((trade_id, matched_by)))) => {
+                // One receipt per trade - later files for the same trade are rejected
+                if results.iter().any(|r: &BulkPdfResult| r.trade_id.as_deref() == Some(trade_id.as_str())) {
+                    result.error = Some(format!("Another file in this batch is already assigned to trade {}", trade_id));
+                } else {
+                    matched.push((index, trade_id.clone(), pdf));
+                }
+                result.trade_id = Some(trade_id);
+                result.matched_by = Some(matched_by);
+            }
+            Ok((_, Err(e))) => result.error = Some(e.into_message()),
+            Err(e) => {
+                error!("Bulk PDF task for {} failed: {}", result.filename, e);
+                result.error = Some("Internal server error".to_string());
+            }
+        }
+        results.push(result);
+    }
+
+    // Phase 2: save and validate matched files
+    let handles: Vec<_> = matched
+        .into_iter()
+        .map(|(index, trade_id, pdf)| {
+            let state = state.clone();
+            let semaphore = semaphore.clone();
+            let handle = tokio::spawn(async move {
+                let _permit = semaphore.acquire_owned().await.expect("semaphore is never closed");
+                upload_and_validate(&state, &trade_id, &pdf).await
+            });
+            (index, handle)
+        })
+        .collect();
+
+    for (index, handle) in handles {
+        let result = &mut results[index];
+        match handle.await {
+            Ok((uploaded, validation)) => {
+                result.uploaded = uploaded;
+                match validation {
+                    Ok(validation) => result.validation = Some(validation),
+                    Err(e) => result.error = Some(e.into_message()),
+                }
+            }
+            Err(e) => {
+                error!("Bulk PDF task for {} failed: {}", result.filename, e);
+                result.error = Some("Internal server error".to_string());
+            }
+        }
+    }
+
+    let uploaded = results.iter().filter(|r| r.uploaded).count();
+    let valid = results.iter().filter(|r| r.validation.as_ref().is_some_and(|v| v.is_valid)).count();
+    info!("✅ Bulk upload done: {}/{} uploaded, {} valid", uploaded, results.len(), valid);
+
+    Ok(Json(BulkPdfResponse {
+        total: results.len(),
+        uploaded,
+        valid,
+        results,
+    }))
+}
@@ -1,10 +1,39 @@
 use axum::{
-    extract::{Path, State},
-    http::StatusCode,
+    body::{Body, Bytes},
+    extract::{Path, Query, State},
+    http::{header, StatusCode},
     response::IntoResponse,
-    Json,
+    Extension, Json,
 };
-use crate::api::{error::{ApiError, ApiResult}, state::AppState};
+use ethers::types::Signature;
+use futures::TryStreamExt;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::str::FromStr;
+
+use crate::api::{api_keys::ApiKeyIdentity, error::{ApiError, ApiResult}, state::AppState};
+use crate::api::handlers::buyer::check_public_values;
+use crate::axiom_prover::{parse_evm_proof, EvmProof, GeneratedProof};
+use crate::blockchain::address::EthAddress;
+use crate::blockchain::ids::TradeId;
+use crate::db::blobs::TradeBlob;
+use crate::proof_format::ProofFormat;
+
+/// Largest proof JSON accepted by POST /api/trades/:trade_id/proof
+/// (an Axiom EVM proof is a few KB of hex)
+pub const MAX_PROOF_JSON_SIZE: usize = 64 * 1024;
+
+/// Proof ID prefix of proofs uploaded by buyers, followed by the SHA-256 of the JSON
+pub const EXTERNAL_PROOF_PREFIX: &str = "external:";
+
+/// How old a proof upload signature may be
+const PROOF_SIGNATURE_TTL_SECS: i64 = 600;
+
+/// Message the buyer signs (EIP-191 personal_sign) to upload a proof; `proof` is the
+/// SHA-256 (hex) of the uploaded JSON
+pub fn upload_proof_message(trade_id: &str, proof: &str, signed_at: i64) -> String {
+    format!("zkAlipay proof upload\nTrade: {}\nProof: {}\nSigned at: {}", trade_id, proof, signed_at)
+}
 
 /// GET /api/trades/:trade_id/proof
 /// Download the Axiom EVM proof JSON file
@@ -12,37 +41,199 @@ pub async fn get_proof_handler(
     Path(trade_id): Path<String>,
     State(state): State<AppState>,
 ) -> ApiResult<impl IntoResponse> {
+    let trade_id = trade_id.parse::<TradeId>()?.to_string();
     tracing::info!("📥 Retrieving proof for trade {}", trade_id);
     
-    // Query trade from database
-    let trade = sqlx::query!(
-        r#"
-        SELECT proof_json, axiom_proof_id
-        FROM trades
-        WHERE "tradeId" = $1
-        "#,
-        trade_id
-    )
-    .fetch_optional(state.db.pool())
-    .await
-    .map_err(|e| ApiError::Database(e.to_string()))?
-    .ok_or_else(|| ApiError::NotFound(format!("Trade not found: {}", trade_id)))?;
-    
-    // Check if proof exists
-    let proof_json = trade.proof_json
+    // Size first, then stream the JSON in chunks rather than loading it whole
+    let info = state.db.get_trade_blob_info(&trade_id, TradeBlob::ProofJson).await?
         .ok_or_else(|| ApiError::NotFound("Proof not generated yet".to_string()))?;
     
-    tracing::info!(
-        "✅ Returning proof for trade {}, proof_id: {:?}", 
-        trade_id,
-        trade.axiom_proof_id
-    );
+    tracing::info!("✅ Streaming proof for trade {} ({} bytes)", trade_id, info.len);
+    
+    let len = info.len;
+    let body = state.db.stream_trade_blob(&trade_id, TradeBlob::ProofJson, info)
+        .inspect_err(move |e| tracing::warn!("Proof download for trade {} aborted: {}", trade_id, e));
     
     // Return as JSON with proper content type
     Ok((
         StatusCode::OK,
-        [("Content-Type", "application/json")],
-        proof_json
+        [
+            (header::CONTENT_TYPE, "application/json".to_string()),
+            (header::CONTENT_LENGTH, len.to_string()),
+        ],
+        Body::from_stream(body),
     ))
 }
 
+/// Response after uploading a proof
+#[derive(Debug, Serialize)]
+pub struct UploadProofResponse {
+    pub success: bool,
+    pub message: String,
+    pub proof_id: String,
+    pub proof_format: String,
+}
+
+/// Query parameters of POST /api/trades/:trade_id/proof
+#[derive(Debug, Default, Deserialize)]
+pub struct UploadProofQuery {
+    /// Unix timestamp included in the signed message (not needed with the buyer's API key)
+    pub signed_at: Option<i64>,
+    pub signature: Option<String>,
+}
+
+/// Parse an uploaded Axiom EVM proof JSON and check its component sizes against `format`
+pub fn parse_uploaded_proof(body: &[u8], format: &ProofFormat) -> ApiResult<GeneratedProof> {
+    let evm_proof: EvmProof = serde_json::from_slice(body)
+        .map_err(|e| ApiError::BadRequest(format!("Invalid proof JSON: {}", e)))?;
+    let proof_id = format!("{}{}", EXTERNAL_PROOF_PREFIX, hex::encode(Sha256::digest(body)));
+    parse_evm_proof(proof_id, evm_proof, format)
+        .map_err(|e| ApiError::BadRequest(format!("Invalid proof: {}", e)))
+}
+
+/// POST /api/trades/:trade_id/proof
+/// Store an Axiom EVM proof JSON the buyer generated themselves
+/// The proof must match the verifier's proof format, the escrow's app commitments and
+/// the trade's payment details; it then settles like a proof generated here. Buyer only
+/// (the buyer's API key, or a signature of `upload_proof_message`): the proof replaces
+/// whatever the trade holds.
+pub async fn upload_proof_handler(
+    Path(trade_id): Path<String>,
+    State(state): State<AppState>,
+    api_key: Option<Extension<ApiKeyIdentity>>,
+    Query(params): Query<UploadProofQuery>,
+    body: Bytes,
+) -> ApiResult<Json<UploadProofResponse>> {
+    let trade_id = trade_id.parse::<TradeId>()?.to_string();
+    tracing::info!("📤 Receiving external proof for trade {} ({} bytes)", trade_id, body.len());
+
+    let trade = state.db.get_trade(&trade_id).await?;
+    let is_buyer_key = api_key.as_ref().is_some_and(|Extension(key)| key.wallet == trade.buyer);
+    if !is_buyer_key {
+        let (Some(signed_at), Some(signature)) = (params.signed_at, params.signature.as_deref()) else {
+            return Err(ApiError::Forbidden("Uploading a proof requires the buyer's signature".to_string()));
+        };
+        let age = chrono::Utc::now().timestamp() - signed_at;
+        if !(-60..=PROOF_SIGNATURE_TTL_SECS).contains(&age) {
+            return Err(ApiError::BadRequest(format!(
+                "signed_at must be within the last {} seconds",
+                PROOF_SIGNATURE_TTL_SECS
+            )));
+        }
+        let message = upload_proof_message(&trade_id, &hex::encode(Sha256::digest(&body)), signed_at);
+        let signer: EthAddress = Signature::from_str(signature)
+            .and_then(|signature| signature.recover(message.as_str()))
+            .map_err(|e| ApiError::BadRequest(format!("Invalid signature: {}", e)))?
+            .into();
+        if signer != trade.buyer {
+            return Err(ApiError::Forbidden("Only the trade's buyer can upload its proof".to_string()));
+        }
+    }
+
+    if trade.status != 0 {
+        return Err(ApiError::BadRequest(format!(
+            "Trade is not pending (status={}) - a proof can no longer settle it",
+            trade.status
+        )));
+    }
+
+    let config = state
+        .chain_config()
+        .await
+        .ok_or_else(|| ApiError::ServiceUnavailable("Blockchain integration not enabled".to_string()))?
+        .map_err(|e| ApiError::BlockchainError(format!("Failed to get contract config: {}", e)))?;
+
+    // Sizes the escrow's current verifier accepts
+    let proof_format = state.proof_formats.for_verifier(&config.zk_verifier).clone();
+    let proof = parse_uploaded_proof(&body, &proof_format)?;
+
+    // A proof of another guest program (or VM) would only revert on-chain
+    if proof.app_exe_commit != config.app_exe_commit || proof.app_vm_commit != config.app_vm_commit {
+        tracing::warn!("⛔ External proof for trade {} has commitments the escrow doesn't accept", trade_id);
+        return Err(ApiError::BadRequest(format!(
+            "Proof commitments (app_exe_commit 0x{}, app_vm_commit 0x{}) don't match the escrow's (0x{}, 0x{})",
+            hex::encode(&proof.app_exe_commit),
+            hex::encode(&proof.app_vm_commit),
+            hex::encode(config.app_exe_commit),
+            hex::encode(config.app_vm_commit),
+        )));
+    }
+
+    let public_values: [u8; 32] = proof.user_public_values.as_slice().try_into()
+        .map_err(|_| ApiError::BadRequest("user_public_values must be 32 bytes".to_string()))?;
+    check_public_values(&state, &trade, &public_values).await?;
+
+    let proof_json = serde_json::to_string(&proof.full_json)
+        .map_err(|e| ApiError::Internal(format!("Failed to serialize proof: {}", e)))?;
+    let saved = state.db.save_trade_proof(
+        &trade_id,
+        trade.pdf_uploaded_at,
+        &proof.user_public_values,
+        &proof.accumulator,
+        &proof.proof_data,
+        &proof.proof_id,
+        &proof_json,
+        &proof.proof_format,
+    ).await?;
+    if !saved {
+        return Err(ApiError::BadRequest(
+            "A new PDF was uploaded while the proof was stored - upload the proof again".to_string(),
+        ));
+    }
+
+    tracing::info!("💾 External proof {} saved for trade {}", proof.proof_id, trade_id);
+
+    Ok(Json(UploadProofResponse {
+        success: true,
+        message: "Proof stored - it can now be submitted for settlement".to_string(),
+        proof_id: proof.proof_id,
+        proof_format: proof.proof_format,
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn proof_json(format: &ProofFormat, public_values_len: usize) -> String {
+        serde_json::json!({
+            "version": "v1.4",
+            "app_exe_commit": hex::encode([1u8; 32]),
+            "app_vm_commit": hex::encode([2u8; 32]),
+            "user_public_values": hex::encode(vec![3u8; public_values_len]),
+            "proof_data": {
+                "accumulator": hex::encode(vec![4u8; format.accumulator_len]),
+                "proof": hex::encode(vec![5u8; format.proof_data_len]),
+            },
+        })
+        .to_string()
+    }
+
+    #[test]
+    fn test_parse_uploaded_proof_checks_structure_and_sizes() {
+        let format = ProofFormat::halo2_v1();
+
+        let body = proof_json(&format, 32);
+        let proof = parse_uploaded_proof(body.as_bytes(), &format).unwrap();
+        assert_eq!(proof.app_exe_commit, vec![1u8; 32]);
+        assert_eq!(proof.user_public_values, vec![3u8; 32]);
+        assert_eq!(proof.proof_format, format.version);
+        // Same JSON, same ID
+        assert_eq!(proof.proof_id, parse_uploaded_proof(body.as_bytes(), &format).unwrap().proof_id);
+        assert!(proof.proof_id.starts_with(EXTERNAL_PROOF_PREFIX));
+
+        let short = proof_json(&format, 31);
+        assert!(matches!(parse_uploaded_proof(short.as_bytes(), &format), Err(ApiError::BadRequest(_))));
+
+        // Missing fields and wrong types are rejected before decoding
+        let missing = br#"{"version":"v1.4","app_exe_commit":"00"}"#;
+        assert!(matches!(parse_uploaded_proof(missing, &format), Err(ApiError::BadRequest(_))));
+        let mut wrong_type: serde_json::Value = serde_json::from_str(&body).unwrap();
+        wrong_type["proof_data"]["proof"] = serde_json::json!(42);
+        let wrong_type = wrong_type.to_string();
+        assert!(matches!(parse_uploaded_proof(wrong_type.as_bytes(), &format), Err(ApiError::BadRequest(_))));
+
+        let not_hex = body.replacen(&hex::encode([1u8; 32]), &"zz".repeat(32), 1);
+        assert!(matches!(parse_uploaded_proof(not_hex.as_bytes(), &format), Err(ApiError::BadRequest(_))));
+    }
+}
@@ -0,0 +1,84 @@
+use axum::{
+    extract::{Path, State},
+    http::{header, StatusCode},
+    response::{IntoResponse, Response},
+    Json,
+};
+
+use crate::api::{error::{ApiError, ApiResult}, state::AppState};
+use crate::blockchain::ids::TradeId;
+use crate::db::models::DbTradeAttestation;
+use crate::receipts::{issue_receipt, ReceiptError, SignedReceipt};
+
+impl From<ReceiptError> for ApiError {
+    fn from(err: ReceiptError) -> Self {
+        match err {
+            ReceiptError::Database(e) => e.into(),
+            ReceiptError::NotSettled(id) => ApiError::BadRequest(format!(
+                "Trade {} is not settled yet - receipts are issued after settlement",
+                id
+            )),
+            ReceiptError::Signing(msg) | ReceiptError::InvalidSignature(msg) => ApiError::Internal(msg),
+        }
+    }
+}
+
+/// GET /api/trades/:trade_id/receipt
+/// Download the signed settlement receipt for a trade
+/// Receipts are normally issued by the event listener on TradeSettled; trades settled
+/// before a signer was configured get their receipt issued lazily on first download.
+pub async fn get_receipt_handler(
+    Path(trade_id): Path<String>,
+    State(state): State<AppState>,
+) -> ApiResult<Response> {
+    let trade_id = trade_id.parse::<TradeId>()?.to_string();
+    tracing::info!("🧾 Retrieving receipt for trade {}", trade_id);
+
+    let stored = match state.db.get_trade_receipt(&trade_id).await? {
+        Some(receipt) => receipt,
+        None => {
+            let signer = state.receipt_signer
+                .as_ref()
+                .ok_or_else(|| ApiError::ServiceUnavailable(
+                    "Receipt signing not enabled".to_string()
+                ))?;
+
+            issue_receipt(state.db.pool(), signer, &trade_id).await?
+        }
+    };
+
+    let signed = SignedReceipt::try_from(stored)
+        .map_err(|e| ApiError::Internal(format!("Corrupt stored receipt: {}", e)))?;
+
+    let body = serde_json::to_string_pretty(&signed)
+        .map_err(|e| ApiError::Internal(format!("Failed to serialize receipt: {}", e)))?;
+
+    Ok((
+        StatusCode::OK,
+        [
+            (header::CONTENT_TYPE, "application/json".to_string()),
+            (
+                header::CONTENT_DISPOSITION,
+                format!("attachment; filename=\"receipt-{}.json\"", trade_id),
+            ),
+        ],
+        body,
+    )
+        .into_response())
+}
+
+/// GET /api/trades/:trade_id/attestation
+/// The trade's EAS attestation (UID and transaction once attested)
+/// Only trades settled while EAS attestations are enabled have one.
+pub async fn get_attestation_handler(
+    Path(trade_id): Path<String>,
+    State(state): State<AppState>,
+) -> ApiResult<Json<DbTradeAttestation>> {
+    let trade_id = trade_id.parse::<TradeId>()?.to_string();
+    let attestation = state
+        .db
+        .get_trade_attestation(&trade_id)
+        .await?
+        .ok_or_else(|| ApiError::NotFound(format!("No attestation for trade {}", trade_id)))?;
+    Ok(Json(attestation))
+}
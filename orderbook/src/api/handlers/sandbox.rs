@@ -0,0 +1,69 @@
+use axum::{extract::State, Json};
+use ethers::types::U256;
+use serde::Deserialize;
+
+use crate::alipay::{mask_alipay_id, AlipayIdFormat};
+use crate::api::{
+    error::{ApiError, ApiResult},
+    state::AppState,
+};
+use crate::blockchain::address::EthAddress;
+use crate::db::models::DbOrder;
+use crate::sandbox::{SandboxError, SandboxOrder};
+
+#[derive(Debug, Deserialize)]
+pub struct CreateSandboxOrderRequest {
+    pub seller: String,
+    pub token: String,
+    /// Token base units
+    pub total_amount: String,
+    /// CNY cents per whole token
+    pub exchange_rate: String,
+    pub alipay_id: String,
+    pub alipay_name: String,
+}
+
+fn parse_positive(value: &str, name: &str) -> ApiResult<U256> {
+    match U256::from_dec_str(value) {
+        Ok(amount) if !amount.is_zero() => Ok(amount),
+        _ => Err(ApiError::BadRequest(format!("Invalid {}: expected a positive integer, got {:?}", name, value))),
+    }
+}
+
+/// POST /api/sandbox/orders
+/// Create an order on the sandbox chain (sandbox mode only); it is tradable at once
+pub async fn create_sandbox_order_handler(
+    State(state): State<AppState>,
+    Json(req): Json<CreateSandboxOrderRequest>,
+) -> ApiResult<Json<DbOrder>> {
+    let sandbox = state
+        .sandbox
+        .as_ref()
+        .ok_or_else(|| ApiError::NotFound("Sandbox mode is not enabled".to_string()))?;
+
+    // The same checks the batch calldata endpoint makes before an order goes on-chain
+    let token: EthAddress = req.token.parse()?;
+    if state.db.get_token_policy().await?.is_suspended(&token) {
+        return Err(ApiError::TokenSuspended(token.to_checksum()));
+    }
+    mask_alipay_id(&req.alipay_id, AlipayIdFormat::detect(&req.alipay_id))
+        .map_err(|e| ApiError::BadRequest(e.to_string()))?;
+    if req.alipay_name.trim().is_empty() {
+        return Err(ApiError::BadRequest("alipay_name is required".to_string()));
+    }
+
+    let order = SandboxOrder {
+        seller: req.seller.parse()?,
+        token,
+        total_amount: parse_positive(&req.total_amount, "total_amount")?,
+        exchange_rate: parse_positive(&req.exchange_rate, "exchange_rate")?,
+        alipay_id: req.alipay_id,
+        alipay_name: req.alipay_name,
+    };
+    let order = sandbox.create_order(&state.db, order).await.map_err(|e| match e {
+        SandboxError::Database(e) => ApiError::from(e),
+        e => ApiError::BadRequest(e.to_string()),
+    })?;
+
+    Ok(Json(order))
+}
@@ -0,0 +1,176 @@
+use axum::{
+    extract::{Path, State},
+    http::StatusCode,
+    response::sse::{Event, KeepAlive, Sse},
+    Json,
+};
+use futures::stream::{self, Stream};
+use serde::Serialize;
+use std::convert::Infallible;
+use std::time::Duration;
+use uuid::Uuid;
+
+use crate::api::{
+    error::{ApiError, ApiResult},
+    settlement::{spawn_settlement_job, step_progress, SettlementStep, StepProgress, LEASE_OWNER},
+    state::AppState,
+};
+use crate::blockchain::ids::TradeId;
+use crate::db::models::DbSettlementJob;
+use crate::db::prover_transcripts::{TRANSCRIPT_EXECUTION, TRANSCRIPT_PROOF};
+use crate::db::settlement_jobs::{JOB_FAILED, JOB_RUNNING};
+
+/// How often the progress stream checks the job for changes
+const JOB_EVENTS_POLL_INTERVAL: Duration = Duration::from_secs(1);
+
+#[derive(Debug, Serialize)]
+pub struct SettlementJobResponse {
+    #[serde(flatten)]
+    pub job: DbSettlementJob,
+    pub steps: Vec<StepProgress>,
+    pub status_url: String,
+    pub events_url: String,
+}
+
+impl From<DbSettlementJob> for SettlementJobResponse {
+    fn from(job: DbSettlementJob) -> Self {
+        Self {
+            steps: step_progress(&job),
+            status_url: format!("/api/settlement-jobs/{}", job.job_id),
+            events_url: format!("/api/settlement-jobs/{}/events", job.job_id),
+            job,
+        }
+    }
+}
+
+/// POST /api/trades/:trade_id/settle
+/// Validate the uploaded PDF, generate the proof and submit it on-chain as one job.
+/// Returns the job handle; re-posting returns the running job or resumes a failed one
+/// from the step that failed.
+pub async fn settle_trade_handler(
+    State(state): State<AppState>,
+    Path(trade_id): Path<String>,
+) -> ApiResult<(StatusCode, Json<SettlementJobResponse>)> {
+    if state.blockchain_client.is_none() && state.sandbox.is_none() {
+        return Err(ApiError::ServiceUnavailable("Blockchain integration not enabled".to_string()));
+    }
+
+    let trade_id = trade_id.parse::<TradeId>()?.to_string();
+    let trade = state.db.get_trade(&trade_id).await?;
+    let latest = state.db.latest_settlement_job(&trade.trade_id).await?;
+
+    // Already running, or already through: report it rather than starting over
+    if let Some(job) = &latest {
+        if job.status != JOB_FAILED {
+            let status = if job.status == JOB_RUNNING { StatusCode::ACCEPTED } else { StatusCode::OK };
+            return Ok((status, Json(job.clone().into())));
+        }
+    }
+
+    if trade.status != 0 {
+        return Err(ApiError::BadRequest(format!("Trade {} is not pending", trade.trade_id)));
+    }
+    if trade.pdf_file.is_none() {
+        return Err(ApiError::BadRequest("No PDF uploaded for this trade".to_string()));
+    }
+    if state.market_paused().await == Some(true) {
+        return Err(ApiError::MarketPaused);
+    }
+    // Refuse (the job stays resumable) rather than failing at the prove step once credits are out
+    if let Some(budget) = &state.prover_budget {
+        let step = latest.as_ref().and_then(|job| job.step.parse().ok()).unwrap_or(SettlementStep::Validate);
+        let mut needed = 0;
+        if step <= SettlementStep::Validate {
+            needed += budget.cost(TRANSCRIPT_EXECUTION);
+        }
+        if step <= SettlementStep::Prove {
+            needed += budget.cost(TRANSCRIPT_PROOF);
+        }
+        budget.ensure_available(needed).await?;
+    }
+
+    let job = match latest {
+        Some(failed) if state.db.resume_settlement_job(failed.job_id, *LEASE_OWNER).await? => {
+            tracing::info!("🔁 Resuming settlement job {} for trade {} at step {}", failed.job_id, trade.trade_id, failed.step);
+            state.db.get_settlement_job(failed.job_id).await?.unwrap_or(failed)
+        }
+        _ => {
+            let (job, created) = state.db.create_or_get_settlement_job(&trade.trade_id, *LEASE_OWNER).await?;
+            if !created {
+                return Ok((StatusCode::ACCEPTED, Json(job.into())));
+            }
+            tracing::info!("🆕 Settlement job {} started for trade {}", job.job_id, trade.trade_id);
+            job
+        }
+    };
+
+    spawn_settlement_job(state, job.clone());
+
+    Ok((StatusCode::ACCEPTED, Json(job.into())))
+}
+
+async fn get_job(state: &AppState, job_id: Uuid) -> ApiResult<DbSettlementJob> {
+    state.db.get_settlement_job(job_id).await?
+        .ok_or_else(|| ApiError::NotFound(format!("Settlement job not found: {}", job_id)))
+}
+
+/// GET /api/settlement-jobs/:job_id
+/// Current step and status of a settlement job
+pub async fn get_settlement_job_handler(
+    State(state): State<AppState>,
+    Path(job_id): Path<Uuid>,
+) -> ApiResult<Json<SettlementJobResponse>> {
+    Ok(Json(get_job(&state, job_id).await?.into()))
+}
+
+/// GET /api/settlement-jobs/:job_id/events
+/// Server-sent events: a `progress` event whenever the job changes, ending once it
+/// has completed or failed
+pub async fn settlement_job_events_handler(
+    State(state): State<AppState>,
+    Path(job_id): Path<Uuid>,
+) -> ApiResult<Sse<impl Stream<Item = Result<Event, Infallible>>>> {
+    let job = get_job(&state, job_id).await?;
+
+    // (state, job to emit next, last emitted update, finished)
+    let events = stream::unfold(
+        (state, Some(job), None, false),
+        move |(state, mut next, mut last_update, finished)| async move {
+            if finished {
+                return None;
+            }
+
+            loop {
+                let job = match next.take() {
+                    Some(job) => job,
+                    None => {
+                        tokio::time::sleep(JOB_EVENTS_POLL_INTERVAL).await;
+                        match state.db.get_settlement_job(job_id).await {
+                            Ok(Some(job)) => job,
+                            Ok(None) => return None,
+                            Err(e) => {
+                                tracing::warn!("Settlement job {} progress poll failed: {}", job_id, e);
+                                continue;
+                            }
+                        }
+                    }
+                };
+
+                if last_update == Some(job.updated_at) {
+                    continue;
+                }
+                last_update = Some(job.updated_at);
+
+                let finished = job.status != JOB_RUNNING;
+                let event = Event::default()
+                    .event("progress")
+                    .json_data(SettlementJobResponse::from(job))
+                    .unwrap_or_else(|_| Event::default().event("error"));
+
+                return Some((Ok(event), (state, None, last_update, finished)));
+            }
+        },
+    );
+
+    Ok(Sse::new(events).keep_alive(KeepAlive::default()))
+}
@@ -0,0 +1,213 @@
+use axum::{
+    extract::{Path, Query, State},
+    http::HeaderMap,
+    Json,
+};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::api::{error::ApiError, state::AppState};
+use crate::blockchain::ids::TradeId;
+use crate::db::models::{DbTradeNote, DbTradePayment, DbTradeSupport};
+
+const MAX_CONTACT_LEN: usize = 256;
+const MAX_NOTE_LEN: usize = 4000;
+const MAX_TAGS: usize = 16;
+const MAX_TAG_LEN: usize = 32;
+
+#[derive(Debug, Deserialize)]
+pub struct UpdateTradeSupportRequest {
+    /// Buyer contact handle (omit to keep, "" to clear)
+    pub buyer_contact: Option<String>,
+    /// Replaces all tags (omit to keep)
+    pub tags: Option<Vec<String>>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct AddTradeNoteRequest {
+    pub note: String,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct SupportTradesQuery {
+    pub tag: String,
+    /// Max trades listed (default 100)
+    pub limit: Option<i64>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct TradeSupportResponse {
+    pub trade_id: String,
+    pub buyer_contact: Option<String>,
+    pub tags: Vec<String>,
+    pub updated_by: Option<String>,
+    /// None until contact or tags are first set
+    pub updated_at: Option<DateTime<Utc>>,
+    /// Oldest first
+    pub notes: Vec<DbTradeNote>,
+    /// Transfers the buyer declared, oldest first
+    pub payments: Vec<DbTradePayment>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct SupportTradesResponse {
+    pub tag: String,
+    pub trades: Vec<DbTradeSupport>,
+}
+
+/// Lowercase, sort and dedup tags; each is 1-32 letters, digits, '-' or '_'
+fn normalize_tags(tags: &[String]) -> Result<Vec<String>, ApiError> {
+    let mut normalized: Vec<String> = tags.iter().map(|t| t.trim().to_ascii_lowercase()).collect();
+    normalized.sort();
+    normalized.dedup();
+
+    if let Some(bad) = normalized.iter().find(|t| {
+        t.is_empty() || t.len() > MAX_TAG_LEN || !t.chars().all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_'))
+    }) {
+        return Err(ApiError::BadRequest(format!(
+            "Invalid tag '{}': expected 1-{} letters, digits, '-' or '_'",
+            bad, MAX_TAG_LEN
+        )));
+    }
+    if normalized.len() > MAX_TAGS {
+        return Err(ApiError::BadRequest(format!("At most {} tags per trade", MAX_TAGS)));
+    }
+    Ok(normalized)
+}
+
+async fn support_response(state: &AppState, trade_id: String) -> Result<TradeSupportResponse, ApiError> {
+    let support = state.db.get_trade_support(&trade_id).await?;
+    let notes = state.db.get_trade_notes(&trade_id).await?;
+    let payments = state.db.get_trade_payments(&trade_id).await?;
+
+    Ok(match support {
+        Some(s) => TradeSupportResponse {
+            trade_id,
+            buyer_contact: s.buyer_contact,
+            tags: s.tags,
+            updated_by: s.updated_by,
+            updated_at: Some(s.updated_at),
+            notes,
+            payments,
+        },
+        None => TradeSupportResponse {
+            trade_id,
+            buyer_contact: None,
+            tags: Vec::new(),
+            updated_by: None,
+            updated_at: None,
+            notes,
+            payments,
+        },
+    })
+}
+
+/// GET /api/admin/trades/:trade_id/support
+/// Contact, tags and notes operators recorded for a trade
+pub async fn get_trade_support_handler(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Path(trade_id): Path<String>,
+) -> Result<Json<TradeSupportResponse>, ApiError> {
+    state.admin_keys.require(&headers)?;
+    let trade_id = trade_id.parse::<TradeId>()?.to_string();
+
+    // 404 for unknown trades rather than an empty record
+    state.db.get_trade(&trade_id).await?;
+
+    support_response(&state, trade_id).await.map(Json)
+}
+
+/// POST /api/admin/trades/:trade_id/support
+/// Set the buyer contact and/or replace the tags of a trade
+pub async fn update_trade_support_handler(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Path(trade_id): Path<String>,
+    Json(req): Json<UpdateTradeSupportRequest>,
+) -> Result<Json<TradeSupportResponse>, ApiError> {
+    let admin = state.admin_keys.require(&headers)?;
+    let trade_id = trade_id.parse::<TradeId>()?.to_string();
+
+    if req.buyer_contact.as_ref().is_some_and(|c| c.trim().chars().count() > MAX_CONTACT_LEN) {
+        return Err(ApiError::BadRequest(format!("buyer_contact must be at most {} characters", MAX_CONTACT_LEN)));
+    }
+    let tags = req.tags.as_deref().map(normalize_tags).transpose()?;
+
+    let current = state.db.get_trade_support(&trade_id).await?;
+    let buyer_contact = match &req.buyer_contact {
+        Some(contact) => Some(contact.trim()).filter(|c| !c.is_empty()).map(str::to_string),
+        None => current.as_ref().and_then(|s| s.buyer_contact.clone()),
+    };
+    let tags = tags.unwrap_or_else(|| current.map(|s| s.tags).unwrap_or_default());
+
+    state
+        .db
+        .set_trade_support(&trade_id, buyer_contact.as_deref(), &tags, admin.as_deref())
+        .await?;
+    tracing::info!("🗂️ Support metadata for trade {} updated by {:?} (tags {:?})", trade_id, admin, tags);
+
+    support_response(&state, trade_id).await.map(Json)
+}
+
+/// POST /api/admin/trades/:trade_id/notes
+/// Append a support note to a trade (notes can't be edited or deleted)
+pub async fn add_trade_note_handler(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Path(trade_id): Path<String>,
+    Json(req): Json<AddTradeNoteRequest>,
+) -> Result<Json<DbTradeNote>, ApiError> {
+    let admin = state.admin_keys.require(&headers)?;
+    let trade_id = trade_id.parse::<TradeId>()?.to_string();
+
+    let note = req.note.trim();
+    if note.is_empty() || note.chars().count() > MAX_NOTE_LEN {
+        return Err(ApiError::BadRequest(format!("note must be 1-{} characters", MAX_NOTE_LEN)));
+    }
+
+    let note = state.db.add_trade_note(&trade_id, note, admin.as_deref()).await?;
+    tracing::info!("🗂️ Support note {} added to trade {} by {:?}", note.id, trade_id, admin);
+
+    Ok(Json(note))
+}
+
+/// GET /api/admin/support/trades?tag=
+/// Trades carrying a support tag, most recently updated first
+pub async fn list_support_trades_handler(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Query(params): Query<SupportTradesQuery>,
+) -> Result<Json<SupportTradesResponse>, ApiError> {
+    state.admin_keys.require(&headers)?;
+    let tag = normalize_tags(std::slice::from_ref(&params.tag))?.remove(0);
+    let limit = params.limit.unwrap_or(100).clamp(1, 500);
+
+    let trades = state.db.get_trades_with_support_tag(&tag, limit).await?;
+
+    Ok(Json(SupportTradesResponse { tag, trades }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn tags(values: &[&str]) -> Vec<String> {
+        values.iter().map(|v| v.to_string()).collect()
+    }
+
+    #[test]
+    fn test_normalize_tags() {
+        assert_eq!(
+            normalize_tags(&tags(&[" Payment-Delayed", "vip", "payment-delayed"])).unwrap(),
+            tags(&["payment-delayed", "vip"])
+        );
+        assert!(normalize_tags(&[]).unwrap().is_empty());
+
+        assert!(normalize_tags(&tags(&[""])).is_err());
+        assert!(normalize_tags(&tags(&["bank transfer"])).is_err());
+        assert!(normalize_tags(&tags(&[&"x".repeat(MAX_TAG_LEN + 1)])).is_err());
+        let too_many: Vec<String> = (0..=MAX_TAGS).map(|i| format!("t{}", i)).collect();
+        assert!(normalize_tags(&too_many).is_err());
+    }
+}
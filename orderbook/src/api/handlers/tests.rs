@@ -0,0 +1,670 @@
+// Handler tests
+// The order, trade, PDF and proof routes run through the full router against the
+// in-memory repositories (db::fake), so every response path - including the error
+// mapping of ApiError - is checked without a database. The sandbox chain stands in for
+// the escrow where a handler needs the contract config.
+
+use axum::{
+    body::{to_bytes, Body, Bytes},
+    http::{header, Request, StatusCode},
+    Router,
+};
+use chrono::Utc;
+use ethers::signers::{LocalWallet, Signer};
+use ethers::types::Address;
+use sha2::{Digest, Sha256};
+use sqlx::postgres::PgPoolOptions;
+use std::sync::Arc;
+use tower::ServiceExt;
+
+use crate::api::{create_router, AppState};
+use crate::api::confirmations::{AdminKeys, ADMIN_KEY_HEADER};
+use crate::api::display::TokenDisplayRegistry;
+use crate::api::handlers::orders::{fill_limits_message, refresh_order_message};
+use crate::api::handlers::pdf::{pdf_upload_message, MAX_PDF_SIZE};
+use crate::api::handlers::proof::upload_proof_message;
+use crate::db::fake::InMemoryRepository;
+use crate::db::models::{DbOrder, DbSyncState, DbTrade};
+use crate::db::Database;
+use crate::proof_format::ProofFormat;
+use crate::proof_inputs::ProofInputs;
+use crate::sandbox::SandboxChain;
+
+const SELLER_KEY: &str = "4c0883a69102937d6231471b5dbb6204fe5129617082792ae468d01a3f362318";
+const OTHER_KEY: &str = "59c6995e998f97a5a0044966f0945389dc9e86dae88c7a8412f4603b6b78690d";
+const BUYER_KEY: &str = "5de4111afa1a4b94908f83103eb1f1706367c2e68ca870fc3fb9a804cdab365a";
+const NONCE: &str = "12345678";
+const MAX_BODY: usize = 1024 * 1024;
+
+fn order_id() -> String {
+    format!("0x{}", "11".repeat(32))
+}
+
+fn trade_id() -> String {
+    format!("0x{}", "22".repeat(32))
+}
+
+fn buyer() -> String {
+    format!("0x{}", "bb".repeat(20))
+}
+
+fn seller() -> LocalWallet {
+    SELLER_KEY.parse().unwrap()
+}
+
+fn order(order_id: &str, remaining: &str) -> DbOrder {
+    DbOrder {
+        order_id: order_id.to_string(),
+        seller: seller().address().into(),
+        token: Address::repeat_byte(0xaa).into(),
+        total_amount: "1000000000".to_string(),
+        remaining_amount: remaining.to_string(),
+        exchange_rate: "735".to_string(),
+        alipay_id: "13945908941".to_string(),
+        alipay_name: "张三".to_string(),
+        created_at: Utc::now().timestamp() - 3600,
+        synced_at: Utc::now(),
+        alipay_id_format: "phone".to_string(),
+        min_fill: None,
+        lot_size: None,
+    }
+}
+
+fn trade() -> DbTrade {
+    let now = Utc::now().timestamp();
+    DbTrade {
+        trade_id: trade_id(),
+        order_id: order_id(),
+        buyer: buyer().parse().unwrap(),
+        token_amount: "100000000".to_string(),
+        cny_amount: "73500".to_string(),
+        payment_nonce: NONCE.to_string(),
+        created_at: now - 60,
+        expires_at: now + 840,
+        status: 0,
+        synced_at: Utc::now(),
+        escrow_tx_hash: None,
+        settlement_tx_hash: None,
+        settlement_block: None,
+        confirmations: 0,
+        finalized_at: None,
+        token: None,
+        pdf_file: None,
+        pdf_filename: None,
+        pdf_uploaded_at: None,
+        proof_user_public_values: None,
+        proof_accumulator: None,
+        proof_data: None,
+        axiom_proof_id: None,
+        proof_generated_at: None,
+        proof_json: None,
+        proof_format: None,
+    }
+}
+
+/// A store with one active order, one filled order and a PENDING trade of the first
+fn seeded() -> Arc<InMemoryRepository> {
+    let repo = InMemoryRepository::new();
+    repo.insert_order(order(&order_id(), "500000000"));
+    repo.insert_order(order(&format!("0x{}", "33".repeat(32)), "0"));
+    repo.insert_trade(trade());
+    repo.set_sync_state(DbSyncState {
+        contract_address: format!("0x{}", "ee".repeat(20)),
+        last_synced_block: 101,
+        last_synced_at: Utc::now(),
+    });
+    repo
+}
+
+/// API state over `repo`
+fn state(repo: &Arc<InMemoryRepository>) -> AppState {
+    // Routes under test never reach the pool
+    let pool = PgPoolOptions::new().connect_lazy("postgres://localhost/unused").unwrap();
+    AppState::from_database(Database::with_repositories(pool, repo.repositories()))
+}
+
+/// The API router over `repo` (and the sandbox chain's contract config if `sandbox`)
+fn app(repo: &Arc<InMemoryRepository>, sandbox: bool) -> Router {
+    let state = state(repo);
+    create_router(if sandbox { state.with_sandbox(SandboxChain::default()) } else { state })
+}
+
+async fn send(router: &Router, request: Request<Body>) -> (StatusCode, Bytes) {
+    let response = router.clone().oneshot(request).await.unwrap();
+    let status = response.status();
+    (status, to_bytes(response.into_body(), MAX_BODY).await.unwrap())
+}
+
+async fn get(router: &Router, uri: &str) -> (StatusCode, serde_json::Value) {
+    let (status, body) = send(router, Request::get(uri).body(Body::empty()).unwrap()).await;
+    (status, serde_json::from_slice(&body).unwrap())
+}
+
+async fn get_as_admin(router: &Router, uri: &str) -> (StatusCode, serde_json::Value) {
+    let request = Request::get(uri).header(ADMIN_KEY_HEADER, "secret").body(Body::empty()).unwrap();
+    let (status, body) = send(router, request).await;
+    (status, serde_json::from_slice(&body).unwrap())
+}
+
+async fn post_json(router: &Router, uri: &str, body: serde_json::Value) -> (StatusCode, serde_json::Value) {
+    let request = Request::post(uri)
+        .header(header::CONTENT_TYPE, "application/json")
+        .body(Body::from(body.to_string()))
+        .unwrap();
+    let (status, body) = send(router, request).await;
+    (status, serde_json::from_slice(&body).unwrap())
+}
+
+/// Multipart upload with one file field
+async fn upload(router: &Router, uri: &str, field: &str, data: &[u8]) -> (StatusCode, serde_json::Value) {
+    let mut body = format!(
+        "--boundary\r\nContent-Disposition: form-data; name=\"{}\"; filename=\"receipt.pdf\"\r\nContent-Type: application/pdf\r\n\r\n",
+        field
+    )
+    .into_bytes();
+    body.extend_from_slice(data);
+    body.extend_from_slice(b"\r\n--boundary--\r\n");
+    let request = Request::post(uri)
+        .header(header::CONTENT_TYPE, "multipart/form-data; boundary=boundary")
+        .body(Body::from(body))
+        .unwrap();
+    let (status, body) = send(router, request).await;
+    (status, serde_json::from_slice(&body).unwrap())
+}
+
+async fn sign(wallet: &LocalWallet, message: String) -> String {
+    format!("0x{}", wallet.sign_message(message).await.unwrap())
+}
+
+#[tokio::test]
+async fn test_order_routes() {
+    let repo = seeded();
+    let router = app(&repo, false);
+
+    // Filled orders aren't listed; sync metadata comes with the list
+    let (status, body) = get(&router, "/api/orders/active").await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body["total"], 1);
+    assert_eq!(body["orders"][0]["order_id"], order_id());
+    assert_eq!(body["orders"][0]["suspended"], false);
+    assert_eq!(body["last_synced_block"], 100);
+
+    // By seller, filled ones included
+    let seller = format!("{:?}", seller().address());
+    let (status, body) = get(&router, &format!("/api/orders/active?seller={}", seller)).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body["total"], 2);
+    let (status, _) = get(&router, "/api/orders/active?seller=0x1234").await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+
+    let (status, body) = get(&router, &format!("/api/orders/{}", order_id())).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body["remaining_amount"], "500000000");
+    assert_eq!(body["exchange_rate_display"], "7.35 CNY");
+
+    let (status, body) = get(&router, "/api/orders/not-an-id").await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+    assert!(body["request_id"].is_string());
+    let (status, body) = get(&router, &format!("/api/orders/0x{}", "44".repeat(32))).await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+    assert!(body["error"].as_str().unwrap().starts_with("Order not found"));
+
+    // Database failures are reported without their details
+    repo.set_unavailable(true);
+    let (status, body) = get(&router, &format!("/api/orders/{}", order_id())).await;
+    assert_eq!(status, StatusCode::INTERNAL_SERVER_ERROR);
+    assert_eq!(body["error"], "Database error");
+}
+
+#[tokio::test]
+async fn test_orders_are_priced_and_carry_the_sellers_reputation() {
+    let repo = seeded();
+    let mut settled = trade();
+    settled.trade_id = format!("0x{}", "55".repeat(32));
+    settled.payment_nonce = "87654321".to_string();
+    settled.status = 1;
+    repo.insert_trade(settled);
+    let tokens = TokenDisplayRegistry::parse(&format!("0x{}=USDC:6", "aa".repeat(20))).unwrap();
+    let router = create_router(state(&repo).with_token_display(tokens));
+
+    // 100 USDC at 7.35 CNY, priced as fillOrder does
+    let (status, body) = get(&router, &format!("/api/orders/{}?amount=100000000", order_id())).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body["price_per_token_cny"], "7.35");
+    assert_eq!((body["estimated_cny"].as_str(), body["estimated_cny_display"].as_str()), (Some("73500"), Some("735.00 CNY")));
+    // One settled trade, the pending one doesn't count
+    assert_eq!(body["seller_reputation"], serde_json::json!({ "settled_trades": 1, "expired_trades": 0, "score": 2.0 / 3.0 }));
+
+    let (status, body) = get(&router, "/api/orders/active?amount=1000000").await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body["orders"][0]["estimated_cny"], "735");
+    assert_eq!(body["orders"][0]["seller_reputation"]["settled_trades"], 1);
+
+    // Unregistered tokens can't be priced without their decimals; nothing is estimated unasked
+    let (_, body) = get(&app(&repo, false), &format!("/api/orders/{}?amount=100000000", order_id())).await;
+    assert!(body.get("estimated_cny").is_none());
+    assert_eq!(body["price_per_token_cny"], "7.35");
+    let (_, body) = get(&router, &format!("/api/orders/{}", order_id())).await;
+    assert!(body.get("estimated_cny").is_none());
+
+    let (status, _) = get(&router, &format!("/api/orders/{}?amount=1.5", order_id())).await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+}
+
+#[tokio::test]
+async fn test_fill_limits_and_refresh_require_the_sellers_signature() {
+    let repo = seeded();
+    let router = app(&repo, false);
+    let (seller, other) = (seller(), OTHER_KEY.parse::<LocalWallet>().unwrap());
+    let uri = format!("/api/orders/{}/fill-limits", order_id());
+    let signed_at = Utc::now().timestamp();
+    let limits = |signature: String, signed_at: i64| {
+        serde_json::json!({ "min_fill": "10000000", "lot_size": "1000000", "signed_at": signed_at, "signature": signature })
+    };
+    let message = |signed_at| fill_limits_message(&order_id(), Some("10000000"), Some("1000000"), signed_at);
+
+    let (status, body) = post_json(&router, &uri, limits(sign(&seller, message(signed_at)).await, signed_at)).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body["min_fill"], "10000000");
+    assert_eq!(body["lot_size"], "1000000");
+
+    // Replayed, expired, unreadable or someone else's signatures are refused
+    let (status, _) = post_json(&router, &uri, limits(sign(&seller, message(signed_at)).await, signed_at)).await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+    let expired = signed_at - 3600;
+    let (status, _) = post_json(&router, &uri, limits(sign(&seller, message(expired)).await, expired)).await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+    let (status, body) = post_json(&router, &uri, limits("0x1234".to_string(), signed_at + 1)).await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+    assert!(body["error"].as_str().unwrap().starts_with("Invalid signature"));
+    let (status, _) = post_json(&router, &uri, limits(sign(&other, message(signed_at + 1)).await, signed_at + 1)).await;
+    assert_eq!(status, StatusCode::FORBIDDEN);
+
+    let (status, _) = post_json(
+        &router,
+        &uri,
+        serde_json::json!({ "min_fill": "-5", "signed_at": signed_at, "signature": "0x00" }),
+    )
+    .await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+    let unknown = format!("0x{}", "44".repeat(32));
+    let (status, _) = post_json(&router, &format!("/api/orders/{}/fill-limits", unknown), limits("0x00".to_string(), signed_at)).await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+
+    // Refreshes follow the same rules
+    let uri = format!("/api/orders/{}/refresh", order_id());
+    let refresh = |signature: String| serde_json::json!({ "signed_at": signed_at, "signature": signature });
+    let (status, body) = post_json(&router, &uri, refresh(sign(&seller, refresh_order_message(&order_id(), signed_at)).await)).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body["order_id"], order_id());
+    let (status, _) = post_json(&router, &uri, refresh(sign(&seller, refresh_order_message(&order_id(), signed_at)).await)).await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+    let (status, _) = post_json(&router, &uri, refresh(sign(&other, refresh_order_message(&order_id(), signed_at)).await)).await;
+    assert_eq!(status, StatusCode::FORBIDDEN);
+}
+
+#[tokio::test]
+async fn test_trade_routes() {
+    let repo = seeded();
+    let router = app(&repo, false);
+
+    let (status, body) = get(&router, &format!("/api/trades/{}", trade_id())).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body["trade_id"], trade_id());
+    assert_eq!(body["payment_nonce"], NONCE);
+    // The order's token is joined in
+    assert_eq!(body["token"], ethers::utils::to_checksum(&Address::repeat_byte(0xaa), None));
+    let (status, _) = get(&router, "/api/trades/0x22").await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+    let (status, _) = get(&router, &format!("/api/trades/0x{}", "44".repeat(32))).await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+
+    let (status, body) = get(&router, &format!("/api/trades/by-nonce/{}", NONCE)).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body["trade_id"], trade_id());
+    let (status, _) = get(&router, "/api/trades/by-nonce/1234").await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+    let (status, _) = get(&router, "/api/trades/by-nonce/87654321").await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+
+    let (status, body) = get(&router, &format!("/api/trades/buyer/{}", buyer())).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body["trades"].as_array().unwrap().len(), 1);
+    let (status, body) = get(&router, &format!("/api/trades/buyer/0x{}", "cc".repeat(20))).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body["trades"].as_array().unwrap().len(), 0);
+    let (status, _) = get(&router, "/api/trades/buyer/not-an-address").await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+}
+
+/// `uri` with the query of `wallet`'s signature of a PDF upload at `signed_at`
+async fn signed_pdf_uri(uri: &str, wallet: &LocalWallet, signed_at: i64) -> String {
+    let signature = sign(wallet, pdf_upload_message(&wallet.address().into(), signed_at)).await;
+    let separator = if uri.contains('?') { '&' } else { '?' };
+    format!("{}{}signed_at={}&signature={}", uri, separator, signed_at, signature)
+}
+
+#[tokio::test]
+async fn test_pdf_routes() {
+    let repo = seeded();
+    let router = app(&repo, false);
+    let (buyer_wallet, other) = (BUYER_KEY.parse::<LocalWallet>().unwrap(), OTHER_KEY.parse::<LocalWallet>().unwrap());
+    let own_trade = DbTrade {
+        trade_id: format!("0x{}", "66".repeat(32)),
+        payment_nonce: "23456789".to_string(),
+        buyer: buyer_wallet.address().into(),
+        axiom_proof_id: Some("proof-0".to_string()),
+        ..trade()
+    };
+    repo.insert_trade(own_trade.clone());
+    let uri = format!("/api/trades/{}/pdf", own_trade.trade_id);
+    let now = Utc::now().timestamp();
+    let signed = signed_pdf_uri(&uri, &buyer_wallet, now).await;
+    let pdf = b"%PDF-1.4 not much of a receipt";
+
+    let (status, _) = get(&router, &uri).await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+
+    // Only the buyer replaces the PDF (and with it the trade's proof)
+    let (status, _) = upload(&router, &uri, "pdf", pdf).await;
+    assert_eq!(status, StatusCode::FORBIDDEN);
+    let (status, _) = upload(&router, &signed_pdf_uri(&uri, &other, now).await, "pdf", pdf).await;
+    assert_eq!(status, StatusCode::FORBIDDEN);
+    let (status, _) = upload(&router, &signed_pdf_uri(&uri, &buyer_wallet, now - 3600).await, "pdf", pdf).await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+    assert_eq!(repo.trade(&own_trade.trade_id).unwrap().axiom_proof_id.as_deref(), Some("proof-0"));
+
+    let (status, body) = upload(&router, &signed, "pdf", b"GIF89a").await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+    assert_eq!(body["error"], "File is not a valid PDF");
+    let (status, body) = upload(&router, &signed, "file", pdf).await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+    assert_eq!(body["error"], "No PDF file provided");
+    let (status, _) = upload(&router, &format!("/api/trades/0x{}/pdf", "44".repeat(32)), "pdf", pdf).await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+    let (status, _) = upload(&router, "/api/trades/0x22/pdf", "pdf", pdf).await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+
+    let (status, body) = upload(&router, &signed, "pdf", pdf).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body["filename"], "receipt.pdf");
+    assert_eq!(body["size"], pdf.len());
+
+    // Streamed back as stored
+    let response = router.clone().oneshot(Request::get(&uri).body(Body::empty()).unwrap()).await.unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    assert_eq!(response.headers()[header::CONTENT_TYPE], "application/pdf");
+    assert_eq!(response.headers()[header::CONTENT_DISPOSITION], "inline; filename=\"receipt.pdf\"");
+    assert_eq!(&to_bytes(response.into_body(), MAX_BODY).await.unwrap()[..], pdf);
+
+    // A settled trade keeps its receipt and proof
+    let settled_id = format!("0x{}", "55".repeat(32));
+    repo.insert_trade(DbTrade {
+        trade_id: settled_id.clone(),
+        payment_nonce: "87654321".to_string(),
+        status: 1,
+        axiom_proof_id: Some("proof-1".to_string()),
+        ..own_trade.clone()
+    });
+    let settled_uri = signed_pdf_uri(&format!("/api/trades/{}/pdf", settled_id), &buyer_wallet, now).await;
+    let (status, body) = upload(&router, &settled_uri, "pdf", pdf).await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+    assert_eq!(body["error"], format!("Trade {} is not pending", settled_id));
+    assert_eq!(repo.trade(&settled_id).unwrap().axiom_proof_id.as_deref(), Some("proof-1"));
+
+    // Matching by nonce among the buyer's trades takes the same signature
+    let buyer_uri = format!("/api/trades/buyer/{}/pdf", own_trade.buyer);
+    let (status, _) = upload(&router, &buyer_uri, "pdf", pdf).await;
+    assert_eq!(status, StatusCode::FORBIDDEN);
+    let (status, _) = upload(&router, &signed_pdf_uri(&buyer_uri, &other, now).await, "pdf", pdf).await;
+    assert_eq!(status, StatusCode::FORBIDDEN);
+    let (status, body) = upload(&router, &signed_pdf_uri(&buyer_uri, &buyer_wallet, now).await, "pdf", pdf).await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+    assert!(body["error"].as_str().unwrap().starts_with("Cannot match PDF by nonce"));
+}
+
+#[tokio::test]
+async fn test_bulk_pdf_upload() {
+    let repo = seeded();
+    let router = app(&repo, false);
+    let (buyer_wallet, other) = (BUYER_KEY.parse::<LocalWallet>().unwrap(), OTHER_KEY.parse::<LocalWallet>().unwrap());
+    let own_trade = DbTrade {
+        trade_id: format!("0x{}", "66".repeat(32)),
+        payment_nonce: "23456789".to_string(),
+        buyer: buyer_wallet.address().into(),
+        ..trade()
+    };
+    repo.insert_trade(own_trade.clone());
+    let pdf = b"%PDF-1.4 not much of a receipt";
+    let bulk_uri = format!("/api/pdfs/bulk?buyer={}", own_trade.buyer);
+    let signed_uri = |wallet: &LocalWallet, signed_at: i64| {
+        let (wallet, uri) = (wallet.clone(), bulk_uri.clone());
+        async move { signed_pdf_uri(&uri, &wallet, signed_at).await }
+    };
+    let now = Utc::now().timestamp();
+
+    // Only the buyer uploads, and with a fresh signature
+    let field = format!("pdf:{}", own_trade.trade_id);
+    let (status, _) = upload(&router, &bulk_uri, &field, pdf).await;
+    assert_eq!(status, StatusCode::FORBIDDEN);
+    let (status, _) = upload(&router, &signed_uri(&other, now).await, &field, pdf).await;
+    assert_eq!(status, StatusCode::FORBIDDEN);
+    let (status, _) = upload(&router, &signed_uri(&buyer_wallet, now - 3600).await, &field, pdf).await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+    assert!(repo.trade(&own_trade.trade_id).unwrap().pdf_file.is_none());
+
+    // A file past the size limit fails the upload as it arrives
+    let mut oversized = b"%PDF".to_vec();
+    oversized.resize(MAX_PDF_SIZE + 1, b' ');
+    let (status, body) = upload(&router, &signed_uri(&buyer_wallet, now).await, &field, &oversized).await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+    assert_eq!(body["error"], "PDF file receipt.pdf too large (max 10MB)");
+
+    // The buyer's own trade takes the file; another buyer's trade is refused per file
+    let (status, body) = upload(&router, &signed_uri(&buyer_wallet, now).await, &field, pdf).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body["uploaded"], 1);
+    assert_eq!(body["results"][0]["trade_id"], own_trade.trade_id);
+    assert_eq!(repo.trade(&own_trade.trade_id).unwrap().pdf_file.as_deref(), Some(&pdf[..]));
+    let (status, body) = upload(&router, &signed_uri(&buyer_wallet, now).await, &format!("pdf:{}", trade_id()), pdf).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body["uploaded"], 0);
+    assert_eq!(body["results"][0]["error"], format!("Trade {} belongs to a different buyer", trade_id()));
+}
+
+/// An EVM proof JSON of `format`'s sizes with the sandbox's (zero) commitments
+fn proof_json(format: &ProofFormat, public_values: &[u8]) -> serde_json::Value {
+    serde_json::json!({
+        "version": "v1.4",
+        "app_exe_commit": hex::encode([0u8; 32]),
+        "app_vm_commit": hex::encode([0u8; 32]),
+        "user_public_values": hex::encode(public_values),
+        "proof_data": {
+            "accumulator": hex::encode(vec![4u8; format.accumulator_len]),
+            "proof": hex::encode(vec![5u8; format.proof_data_len]),
+        },
+    })
+}
+
+/// `uri` with the query of `wallet`'s signature of the proof upload `proof`
+async fn signed_proof_uri(uri: &str, wallet: &LocalWallet, trade_id: &str, proof: &serde_json::Value) -> String {
+    let signed_at = Utc::now().timestamp();
+    let hash = hex::encode(Sha256::digest(proof.to_string().as_bytes()));
+    let signature = sign(wallet, upload_proof_message(trade_id, &hash, signed_at)).await;
+    format!("{}?signed_at={}&signature={}", uri, signed_at, signature)
+}
+
+#[tokio::test]
+async fn test_proof_routes() {
+    let repo = seeded();
+    let (buyer_wallet, other) = (BUYER_KEY.parse::<LocalWallet>().unwrap(), OTHER_KEY.parse::<LocalWallet>().unwrap());
+    let own_trade = DbTrade {
+        trade_id: format!("0x{}", "66".repeat(32)),
+        payment_nonce: "23456789".to_string(),
+        buyer: buyer_wallet.address().into(),
+        ..trade()
+    };
+    repo.insert_trade(own_trade.clone());
+    let uri = format!("/api/trades/{}/proof", own_trade.trade_id);
+    let signed = |wallet: &LocalWallet, proof: &serde_json::Value| {
+        let (wallet, proof) = (wallet.clone(), proof.clone());
+        let (uri, trade_id) = (uri.clone(), own_trade.trade_id.clone());
+        async move { signed_proof_uri(&uri, &wallet, &trade_id, &proof).await }
+    };
+    let format = ProofFormat::halo2_v1();
+    let expected = ProofInputs::for_trade(&order(&order_id(), "500000000"), &own_trade, [0u8; 32])
+        .unwrap()
+        .expected_hash()
+        .unwrap();
+    let proof = proof_json(&format, &expected);
+
+    let (status, body) = get(&app(&repo, false), &uri).await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+    assert_eq!(body["error"], "Proof not generated yet");
+
+    // Checking a proof takes the escrow's config
+    let (status, _) = post_json(&app(&repo, false), &signed(&buyer_wallet, &proof).await, proof.clone()).await;
+    assert_eq!(status, StatusCode::SERVICE_UNAVAILABLE);
+
+    // Only the buyer replaces the trade's proof, and only with the proof they signed
+    let router = app(&repo, true);
+    let (status, _) = post_json(&router, &uri, proof.clone()).await;
+    assert_eq!(status, StatusCode::FORBIDDEN);
+    let (status, _) = post_json(&router, &signed(&other, &proof).await, proof.clone()).await;
+    assert_eq!(status, StatusCode::FORBIDDEN);
+    let junk = proof_json(&format, &[9u8; 32]);
+    let (status, _) = post_json(&router, &signed(&buyer_wallet, &proof).await, junk.clone()).await;
+    assert_eq!(status, StatusCode::FORBIDDEN);
+
+    let invalid = serde_json::json!({ "version": "v1.4" });
+    let (status, body) = post_json(&router, &signed(&buyer_wallet, &invalid).await, invalid).await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+    assert!(body["error"].as_str().unwrap().starts_with("Invalid proof JSON"));
+    let mut other_program = proof.clone();
+    other_program["app_exe_commit"] = serde_json::json!(hex::encode([1u8; 32]));
+    let (status, _) = post_json(&router, &signed(&buyer_wallet, &other_program).await, other_program).await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+    let (status, body) = post_json(&router, &signed(&buyer_wallet, &junk).await, junk).await;
+    assert_eq!(status, StatusCode::UNPROCESSABLE_ENTITY);
+    assert_eq!(body["code"], "public_values_mismatch");
+    let (status, _) = post_json(&router, &format!("/api/trades/0x{}/proof", "44".repeat(32)), proof.clone()).await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+
+    let (status, body) = post_json(&router, &signed(&buyer_wallet, &proof).await, proof.clone()).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body["success"], true);
+    let stored = repo.trade(&own_trade.trade_id).unwrap();
+    assert_eq!(stored.proof_user_public_values, Some(expected.to_vec()));
+    assert_eq!(stored.axiom_proof_id.as_deref(), body["proof_id"].as_str());
+
+    let (status, body) = get(&app(&repo, false), &uri).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body["user_public_values"], hex::encode(expected));
+
+    // A settled trade takes no more proofs
+    let settled_id = format!("0x{}", "55".repeat(32));
+    repo.insert_trade(DbTrade { trade_id: settled_id.clone(), payment_nonce: "87654321".to_string(), status: 1, ..own_trade });
+    let settled_uri = format!("/api/trades/{}/proof", settled_id);
+    let (status, body) =
+        post_json(&router, &signed_proof_uri(&settled_uri, &buyer_wallet, &settled_id, &proof).await, proof).await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+    assert!(body["error"].as_str().unwrap().starts_with("Trade is not pending"));
+}
+
+#[tokio::test]
+async fn test_admin_inspection_routes() {
+    let repo = seeded();
+    let settled_id = format!("0x{}", "44".repeat(32));
+    repo.insert_trade(DbTrade {
+        trade_id: settled_id.clone(),
+        payment_nonce: "87654321".to_string(),
+        created_at: Utc::now().timestamp() - 600,
+        status: 1,
+        pdf_file: Some(b"%PDF".to_vec()),
+        proof_json: Some("{}".to_string()),
+        ..trade()
+    });
+    let router = create_router(state(&repo).with_admin_keys(AdminKeys::parse("ops:secret").unwrap()));
+
+    let (status, _) = get(&router, "/api/admin/orders").await;
+    assert_eq!(status, StatusCode::UNAUTHORIZED);
+
+    // Pages follow the cursor (ties on created_at broken by ID, descending)
+    let (status, body) = get_as_admin(&router, "/api/admin/orders?limit=1").await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body["rows"][0]["order_id"], format!("0x{}", "33".repeat(32)));
+    let cursor = body["next_cursor"].as_str().unwrap().to_string();
+    let (_, body) = get_as_admin(&router, &format!("/api/admin/orders?limit=1&cursor={}", cursor)).await;
+    assert_eq!(body["rows"][0]["order_id"], order_id());
+    assert!(body["next_cursor"].is_null());
+
+    let (_, body) = get_as_admin(&router, "/api/admin/orders?status=active&columns=order_id,remaining_amount").await;
+    assert_eq!(body["rows"], serde_json::json!([{ "order_id": order_id(), "remaining_amount": "500000000" }]));
+    for query in ["status=open", "columns=order_id,alipay_secret", "sort=seller", "order=up", "cursor=5:0x11"] {
+        let (status, _) = get_as_admin(&router, &format!("/api/admin/orders?{}", query)).await;
+        assert_eq!(status, StatusCode::BAD_REQUEST, "{}", query);
+    }
+
+    // Trades leave out the proof JSON unless selected, and never return the PDF
+    let (_, body) = get_as_admin(&router, "/api/admin/trades").await;
+    let rows = body["rows"].as_array().unwrap();
+    assert_eq!(rows.len(), 2);
+    assert_eq!(rows[0]["trade_id"], trade_id());
+    assert_eq!(rows[1]["token"], ethers::utils::to_checksum(&Address::repeat_byte(0xaa), None));
+    assert!(rows.iter().all(|row| row.get("proof_json").is_none() && row.get("pdf_file").is_none()));
+
+    let (_, body) = get_as_admin(&router, "/api/admin/trades?status=settled&columns=trade_id,proof_json").await;
+    assert_eq!(body["rows"], serde_json::json!([{ "trade_id": settled_id, "proof_json": "{}" }]));
+    let (_, body) = get_as_admin(&router, "/api/admin/trades?sort=created_at&order=asc&columns=trade_id").await;
+    assert_eq!(body["rows"][0]["trade_id"], settled_id);
+    let (status, _) = get_as_admin(&router, "/api/admin/trades?status=active").await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+}
+
+#[tokio::test]
+async fn test_match_plans_are_stored_and_checked_by_execute_fill() {
+    let repo = seeded();
+    let token = ethers::utils::to_checksum(&Address::repeat_byte(0xaa), None);
+    let router = create_router(
+        state(&repo).with_sandbox(SandboxChain::default()).with_admin_keys(AdminKeys::parse("ops:secret").unwrap()),
+    );
+
+    let (status, body) =
+        post_json(&router, "/api/match-intent", serde_json::json!({ "token_address": token, "desired_amount": "100000000" })).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body["fills"][0]["order_id"], order_id());
+    let plan_id = body["plan_id"].as_str().unwrap().to_string();
+    assert!(body["plan_expires_at"].as_i64().unwrap() > Utc::now().timestamp());
+
+    // The stored plan keeps the request and the book it was matched against
+    let (status, stored) = get_as_admin(&router, &format!("/api/admin/match-plans/{}", plan_id)).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(stored["request"]["desired_amount"], "100000000");
+    assert_eq!(stored["plan"]["fills"], body["fills"]);
+    assert_eq!(stored["book_hash"].as_str().unwrap().len(), 66);
+    let (status, _) = get_as_admin(&router, &format!("/api/admin/match-plans/{}", uuid::Uuid::new_v4())).await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+
+    // execute-fill refuses unknown plans and fills the plan didn't hand out
+    let plan = |fills: serde_json::Value| {
+        serde_json::json!({ "fills": fills, "total_filled": body["total_filled"], "fully_fillable": body["fully_fillable"] })
+    };
+    let (status, _) = post_json(
+        &router,
+        "/api/execute-fill",
+        serde_json::json!({ "match_plan": plan(body["fills"].clone()), "buyer_address": buyer(), "plan_id": uuid::Uuid::new_v4() }),
+    )
+    .await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+
+    let mut fills = body["fills"].clone();
+    fills[0]["fill_amount"] = serde_json::json!("500000000");
+    let (status, error) = post_json(
+        &router,
+        "/api/execute-fill",
+        serde_json::json!({ "match_plan": plan(fills), "buyer_address": buyer(), "plan_id": plan_id }),
+    )
+    .await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+    assert!(error.to_string().contains("Fills differ"), "{}", error);
+}
@@ -0,0 +1,46 @@
+use axum::{extract::State, Json};
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+
+use crate::api::{error::ApiResult, state::AppState};
+use crate::blockchain::address::EthAddress;
+use crate::db::token_status::TOKEN_ALLOWED;
+
+/// One allowlist / denylist entry
+#[derive(Debug, Serialize)]
+pub struct TokenStatusDto {
+    pub token: EthAddress,
+    pub status: String,
+    pub reason: Option<String>,
+    pub updated_at: DateTime<Utc>,
+}
+
+/// Token list response
+#[derive(Debug, Serialize)]
+pub struct TokenStatusResponse {
+    /// When true, only tokens listed as "allowed" can be traded
+    pub allowlist_active: bool,
+    pub tokens: Vec<TokenStatusDto>,
+}
+
+/// GET /api/tokens/status
+/// Admin-managed token allowlist / denylist. Orders of a suspended token are left out
+/// of /api/orders/active and can't be matched or filled.
+pub async fn get_token_status_handler(
+    State(state): State<AppState>,
+) -> ApiResult<Json<TokenStatusResponse>> {
+    let entries = state.db.list_token_statuses().await?;
+
+    Ok(Json(TokenStatusResponse {
+        allowlist_active: entries.iter().any(|e| e.status == TOKEN_ALLOWED),
+        tokens: entries
+            .into_iter()
+            .map(|e| TokenStatusDto {
+                token: e.token,
+                status: e.status,
+                reason: e.reason,
+                updated_at: e.updated_at,
+            })
+            .collect(),
+    }))
+}
@@ -0,0 +1,235 @@
+// Maintenance mode
+// During contract migrations the orderbook must stop taking writes (fills, proofs,
+// uploads) while reads, health checks and admin endpoints keep working. Maintenance is
+// on while the switch is set (POST /api/admin/maintenance, MAINTENANCE_MODE at startup)
+// or while a scheduled window is open; mutating requests then get 503 with code
+// "maintenance". Current state and upcoming windows are reported by /health.
+// State is per replica: toggle every replica, or set MAINTENANCE_MODE and restart.
+//
+// Configuration (env):
+// - MAINTENANCE_MODE: start with maintenance on (default false)
+// - MAINTENANCE_REASON: message returned while the switch is on
+// - MAINTENANCE_WINDOWS: comma-separated "<start>/<end>" RFC 3339 pairs
+
+use axum::{
+    extract::{Request, State},
+    http::Method,
+    middleware::Next,
+    response::Response,
+};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::sync::RwLock;
+
+use crate::api::{error::ApiError, state::AppState};
+
+/// A scheduled maintenance period
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MaintenanceWindow {
+    pub start: DateTime<Utc>,
+    pub end: DateTime<Utc>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub reason: Option<String>,
+}
+
+impl MaintenanceWindow {
+    /// Parse one "<start>/<end>" entry
+    pub fn parse(entry: &str) -> Result<Self, String> {
+        let invalid = || format!("MAINTENANCE_WINDOWS entries must be <start>/<end> in RFC 3339, got {:?}", entry);
+        let (start, end) = entry.trim().split_once('/').ok_or_else(invalid)?;
+        let parse = |s: &str| DateTime::parse_from_rfc3339(s.trim()).map(|t| t.with_timezone(&Utc)).map_err(|_| invalid());
+        let window = Self { start: parse(start)?, end: parse(end)?, reason: None };
+        window.validate()?;
+        Ok(window)
+    }
+
+    pub fn validate(&self) -> Result<(), String> {
+        if self.end <= self.start {
+            return Err(format!("Maintenance window ending {} must end after it starts", self.end.to_rfc3339()));
+        }
+        Ok(())
+    }
+
+    pub fn contains(&self, now: DateTime<Utc>) -> bool {
+        self.start <= now && now < self.end
+    }
+}
+
+/// Maintenance state as reported by /health and the admin endpoint
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct MaintenanceStatus {
+    /// Mutating endpoints are refused right now
+    pub active: bool,
+    /// The manual switch is on
+    pub enabled: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub reason: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub since: Option<DateTime<Utc>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub updated_by: Option<String>,
+    /// Open and future windows, earliest first
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub windows: Vec<MaintenanceWindow>,
+}
+
+#[derive(Debug, Default)]
+struct Switch {
+    enabled: bool,
+    reason: Option<String>,
+    since: Option<DateTime<Utc>>,
+    updated_by: Option<String>,
+    windows: Vec<MaintenanceWindow>,
+}
+
+/// Runtime maintenance switch and schedule
+#[derive(Debug, Default)]
+pub struct Maintenance {
+    switch: RwLock<Switch>,
+}
+
+impl Maintenance {
+    pub fn from_env() -> Result<Self, String> {
+        let enabled = std::env::var("MAINTENANCE_MODE")
+            .map(|v| matches!(v.trim().to_ascii_lowercase().as_str(), "1" | "true" | "yes"))
+            .unwrap_or(false);
+        let reason = std::env::var("MAINTENANCE_REASON").ok().filter(|r| !r.trim().is_empty());
+        let windows = match std::env::var("MAINTENANCE_WINDOWS") {
+            Ok(value) => value
+                .split(',')
+                .filter(|e| !e.trim().is_empty())
+                .map(MaintenanceWindow::parse)
+                .collect::<Result<Vec<_>, _>>()?,
+            Err(_) => Vec::new(),
+        };
+
+        let maintenance = Self::default();
+        maintenance.set(enabled, reason, None);
+        maintenance.set_windows(windows);
+        Ok(maintenance)
+    }
+
+    /// Turn the switch on or off
+    pub fn set(&self, enabled: bool, reason: Option<String>, updated_by: Option<String>) {
+        let mut switch = self.switch.write().unwrap();
+        if enabled != switch.enabled {
+            switch.since = enabled.then(Utc::now);
+        }
+        switch.enabled = enabled;
+        switch.reason = if enabled { reason } else { None };
+        switch.updated_by = updated_by;
+    }
+
+    /// Replace the scheduled windows
+    pub fn set_windows(&self, mut windows: Vec<MaintenanceWindow>) {
+        windows.sort_by_key(|w| w.start);
+        self.switch.write().unwrap().windows = windows;
+    }
+
+    pub fn status(&self, now: DateTime<Utc>) -> MaintenanceStatus {
+        let mut switch = self.switch.write().unwrap();
+        switch.windows.retain(|w| w.end > now);
+
+        MaintenanceStatus {
+            active: switch.enabled || switch.windows.iter().any(|w| w.contains(now)),
+            enabled: switch.enabled,
+            reason: switch.reason.clone(),
+            since: switch.since,
+            updated_by: switch.updated_by.clone(),
+            windows: switch.windows.clone(),
+        }
+    }
+
+    /// Why mutating requests are refused at `now`, and seconds until a window closes
+    /// (None when maintenance is off)
+    pub fn refusal(&self, now: DateTime<Utc>) -> Option<(String, Option<u64>)> {
+        let switch = self.switch.read().unwrap();
+        if switch.enabled {
+            let reason = switch.reason.clone().unwrap_or_else(|| "The API is in maintenance mode".to_string());
+            return Some((reason, None));
+        }
+        switch.windows.iter().find(|w| w.contains(now)).map(|w| {
+            let reason = w.reason.clone().unwrap_or_else(|| format!("Scheduled maintenance until {}", w.end.to_rfc3339()));
+            (reason, Some((w.end - now).num_seconds().max(1) as u64))
+        })
+    }
+}
+
+/// Requests that keep working during maintenance: reads, quotes (match-intent only
+/// reads the book) and admin endpoints, which run the migration itself
+pub fn allowed_during_maintenance(method: &Method, path: &str) -> bool {
+    method == Method::GET
+        || method == Method::HEAD
+        || method == Method::OPTIONS
+        || path == "/api/match-intent"
+        || path.starts_with("/api/admin/")
+}
+
+/// Middleware: refuse mutating requests while maintenance is active
+pub async fn reject_during_maintenance(
+    State(state): State<AppState>,
+    request: Request,
+    next: Next,
+) -> Result<Response, ApiError> {
+    if !allowed_during_maintenance(request.method(), request.uri().path()) {
+        if let Some((reason, retry_after)) = state.maintenance.refusal(Utc::now()) {
+            return Err(ApiError::Maintenance { reason, retry_after });
+        }
+    }
+    Ok(next.run(request).await)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::{Duration, TimeZone};
+
+    fn at(hour: u32) -> DateTime<Utc> {
+        Utc.with_ymd_and_hms(2025, 6, 1, hour, 0, 0).unwrap()
+    }
+
+    #[test]
+    fn test_window_parse() {
+        let window = MaintenanceWindow::parse(" 2025-06-01T02:00:00Z/2025-06-01T04:00:00+00:00 ").unwrap();
+        assert_eq!((window.start, window.end), (at(2), at(4)));
+        assert!(window.contains(at(2)));
+        assert!(!window.contains(at(4)));
+
+        assert!(MaintenanceWindow::parse("2025-06-01T02:00:00Z").is_err());
+        assert!(MaintenanceWindow::parse("2025-06-01T04:00:00Z/2025-06-01T02:00:00Z").is_err());
+    }
+
+    #[test]
+    fn test_switch_and_windows() {
+        let maintenance = Maintenance::default();
+        assert!(maintenance.refusal(at(1)).is_none());
+
+        maintenance.set_windows(vec![MaintenanceWindow { start: at(2), end: at(4), reason: None }]);
+        assert!(!maintenance.status(at(1)).active);
+        let (_, retry_after) = maintenance.refusal(at(3)).unwrap();
+        assert_eq!(retry_after, Some(3600));
+        assert!(maintenance.status(at(3)).active);
+
+        // Past windows drop out of the status
+        assert!(maintenance.status(at(5)).windows.is_empty());
+        assert!(maintenance.refusal(at(5)).is_none());
+
+        maintenance.set(true, Some("Migrating escrow".to_string()), Some("ops".to_string()));
+        assert_eq!(maintenance.refusal(at(5)), Some(("Migrating escrow".to_string(), None)));
+        let status = maintenance.status(at(5) + Duration::minutes(1));
+        assert!(status.active && status.enabled && status.since.is_some());
+
+        maintenance.set(false, Some("ignored".to_string()), None);
+        let status = maintenance.status(at(5));
+        assert!(!status.active && status.reason.is_none() && status.since.is_none());
+    }
+
+    #[test]
+    fn test_allowed_during_maintenance() {
+        assert!(allowed_during_maintenance(&Method::GET, "/api/orders/active"));
+        assert!(allowed_during_maintenance(&Method::POST, "/api/match-intent"));
+        assert!(allowed_during_maintenance(&Method::POST, "/api/admin/maintenance"));
+        assert!(!allowed_during_maintenance(&Method::POST, "/api/execute-fill"));
+        assert!(!allowed_during_maintenance(&Method::POST, "/api/submit-blockchain-proof"));
+    }
+}
@@ -1,9 +1,12 @@
 use rust_decimal::Decimal;
 use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
 use std::str::FromStr;
 use thiserror::Error;
 
+use crate::blockchain::address::EthAddress;
 use crate::db::models::DbOrder;
+use crate::db::orders::OrderPriority;
 
 #[derive(Debug, Error)]
 pub enum MatchError {
@@ -30,6 +33,10 @@ pub struct MatchPlan {
     
     /// Whether the full amount can be filled
     pub fully_fillable: bool,
+    
+    /// total_filled with the token's symbol (set by the API for registered tokens, see api::display)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub total_filled_display: Option<String>,
 }
 
 /// A single fill in the match plan
@@ -39,7 +46,7 @@ pub struct Fill {
     pub order_id: String,
     
     /// Seller address
-    pub seller: String,
+    pub seller: EthAddress,
     
     /// Amount to fill from this order (in token base units)
     pub fill_amount: String,  // Decimal as string
@@ -52,14 +59,91 @@ pub struct Fill {
     pub alipay_name: String,
     
     /// Token address
-    pub token: String,
+    pub token: EthAddress,
+    
+    /// fill_amount with the token's symbol, and the CNY the buyer pays for it (set by
+    /// the API for registered tokens, see api::display)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub fill_amount_display: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cny_amount_display: Option<String>,
+}
+
+/// Seller fill limits of an order (orderbook-enforced, not on-chain)
+/// A fill must be at least `min_fill` and a multiple of `lot_size`, unless it takes
+/// the order's whole remaining amount - so a tail below either can still be cleared.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct FillLimits {
+    pub min_fill: Option<Decimal>,
+    pub lot_size: Option<Decimal>,
+}
+
+impl FillLimits {
+    pub fn of(order: &DbOrder) -> MatchResult<Self> {
+        let parse = |value: &Option<String>, name: &str| {
+            value
+                .as_deref()
+                .map(|value| match Decimal::from_str(value) {
+                    Ok(limit) if limit > Decimal::ZERO => Ok(limit),
+                    Ok(limit) => Err(MatchError::ParseError(format!("Invalid {}: {} is not positive", name, limit))),
+                    Err(e) => Err(MatchError::ParseError(format!("Invalid {}: {}", name, e))),
+                })
+                .transpose()
+        };
+        Ok(Self {
+            min_fill: parse(&order.min_fill, "min fill")?,
+            lot_size: parse(&order.lot_size, "lot size")?,
+        })
+    }
+
+    /// Largest fill of at most `wanted` the limits allow (None if there is none)
+    pub fn max_fill(&self, wanted: Decimal, order_remaining: Decimal) -> Option<Decimal> {
+        if wanted >= order_remaining {
+            return Some(order_remaining);
+        }
+        let amount = match self.lot_size {
+            Some(lot) => wanted.checked_div(lot)?.floor().checked_mul(lot)?,
+            None => wanted,
+        };
+        if amount <= Decimal::ZERO || self.min_fill.is_some_and(|min| amount < min) {
+            return None;
+        }
+        Some(amount)
+    }
+
+    /// Why `amount` breaks the limits, if it does
+    pub fn check(&self, amount: Decimal, order_remaining: Decimal) -> Result<(), String> {
+        if amount == order_remaining {
+            return Ok(());
+        }
+        if let Some(min) = self.min_fill.filter(|min| amount < *min) {
+            return Err(format!("below the order's minimum fill of {}", min));
+        }
+        if let Some(lot) = self.lot_size.filter(|lot| !amount.checked_rem(*lot).is_some_and(|rest| rest.is_zero())) {
+            return Err(format!("not a multiple of the order's lot size of {}", lot));
+        }
+        Ok(())
+    }
 }
 
-/// Match a buy intent against available orders
+/// Match a buy intent against available orders (price-time priority)
+/// Orders whose fill limits can't be met by what's left of the intent are skipped.
 pub fn match_buy_intent(
     orders: Vec<DbOrder>,
     desired_amount: Decimal,
     max_rate: Option<Decimal>,
+) -> MatchResult<MatchPlan> {
+    match_buy_intent_with_priority(orders, desired_amount, max_rate, OrderPriority::PriceTime)
+}
+
+/// Match a buy intent against orders sorted by the repository in `priority` order
+/// Fills are taken in that order, except that under pro-rata a price level that can't
+/// be taken whole is split in proportion to the orders' remaining amounts.
+pub fn match_buy_intent_with_priority(
+    orders: Vec<DbOrder>,
+    desired_amount: Decimal,
+    max_rate: Option<Decimal>,
+    priority: OrderPriority,
 ) -> MatchResult<MatchPlan> {
     if desired_amount <= Decimal::ZERO {
         return Err(MatchError::InvalidAmount("Amount must be positive".to_string()));
@@ -68,14 +152,10 @@ pub fn match_buy_intent(
     let mut fills = Vec::new();
     let mut remaining = desired_amount;
     
-    for order in orders {
-        // Parse order rate
-        let order_rate = Decimal::from_str(&order.exchange_rate)
-            .map_err(|e| MatchError::ParseError(format!("Invalid exchange rate: {}", e)))?;
-        
+    for level in price_levels(orders)? {
         // Check max rate filter
         if let Some(max) = max_rate {
-            if order_rate > max {
+            if level.rate > max {
                 break;  // Orders are sorted by rate, so we can stop here
             }
         }
@@ -84,24 +164,29 @@ pub fn match_buy_intent(
             break;
         }
         
-        // Parse order remaining amount
-        let order_remaining = Decimal::from_str(&order.remaining_amount)
-            .map_err(|e| MatchError::ParseError(format!("Invalid remaining amount: {}", e)))?;
-        
-        // Calculate fill amount (minimum of remaining and order available)
-        let fill_amount = remaining.min(order_remaining);
+        let amounts = if priority == OrderPriority::ProRata && remaining < level.total()? {
+            level.pro_rata(remaining)?
+        } else {
+            level.in_order(remaining)?
+        };
         
-        fills.push(Fill {
-            order_id: order.order_id.clone(),
-            seller: order.seller.clone(),
-            fill_amount: fill_amount.to_string(),
-            exchange_rate: order_rate.to_string(),
-            alipay_id: order.alipay_id.clone(),
-            alipay_name: order.alipay_name.clone(),
-            token: order.token.clone(),
-        });
-        
-        remaining -= fill_amount;
+        for ((order, _), fill_amount) in level.orders.iter().zip(amounts) {
+            if fill_amount <= Decimal::ZERO {
+                continue;
+            }
+            fills.push(Fill {
+                order_id: order.order_id.clone(),
+                seller: order.seller.clone(),
+                fill_amount: fill_amount.to_string(),
+                exchange_rate: level.rate.to_string(),
+                alipay_id: order.alipay_id.clone(),
+                alipay_name: order.alipay_name.clone(),
+                token: order.token.clone(),
+                fill_amount_display: None,
+                cny_amount_display: None,
+            });
+            remaining -= fill_amount;
+        }
     }
     
     let total_filled = desired_amount - remaining;
@@ -118,19 +203,124 @@ pub fn match_buy_intent(
         fills,
         total_filled: total_filled.to_string(),
         fully_fillable,
+        total_filled_display: None,
     })
 }
 
+/// Orders at one exchange rate, with their parsed remaining amounts
+struct PriceLevel {
+    rate: Decimal,
+    orders: Vec<(DbOrder, Decimal)>,
+}
+
+impl PriceLevel {
+    /// Remaining amount of the level (refused if it overflows a Decimal)
+    fn total(&self) -> MatchResult<Decimal> {
+        self.orders
+            .iter()
+            .try_fold(Decimal::ZERO, |total, (_, remaining)| total.checked_add(*remaining))
+            .ok_or_else(|| MatchError::InvalidAmount("Price level liquidity is out of range".to_string()))
+    }
+
+    /// Fill amounts taking orders one after another, within each seller's fill limits
+    fn in_order(&self, mut wanted: Decimal) -> MatchResult<Vec<Decimal>> {
+        let mut amounts = Vec::with_capacity(self.orders.len());
+        for (order, order_remaining) in &self.orders {
+            let amount = match FillLimits::of(order)?.max_fill(wanted, *order_remaining) {
+                Some(amount) if wanted > Decimal::ZERO => amount,
+                _ => Decimal::ZERO,
+            };
+            wanted -= amount;
+            amounts.push(amount);
+        }
+        Ok(amounts)
+    }
+
+    /// Fill amounts splitting `wanted` (less than the level's total) in proportion to
+    /// remaining amounts, rounded down to whole base units and fill limits; what rounding
+    /// leaves over is topped up in level order
+    fn pro_rata(&self, wanted: Decimal) -> MatchResult<Vec<Decimal>> {
+        let total = self.total()?;
+        let mut leftover = wanted;
+        let mut amounts = Vec::with_capacity(self.orders.len());
+        for (order, order_remaining) in &self.orders {
+            // Decimal rounds the quotient's last digit, so a share can come out a unit
+            // over its exact floor: never allot more than the intent has left
+            let share = wanted
+                .checked_mul(*order_remaining)
+                .and_then(|product| product.checked_div(total))
+                .ok_or_else(|| MatchError::InvalidAmount("Pro-rata share is out of range".to_string()))?
+                .floor()
+                .min(leftover);
+            let amount = FillLimits::of(order)?.max_fill(share, *order_remaining).unwrap_or(Decimal::ZERO);
+            leftover -= amount;
+            amounts.push(amount);
+        }
+
+        for ((order, order_remaining), amount) in self.orders.iter().zip(amounts.iter_mut()) {
+            if leftover <= Decimal::ZERO {
+                break;
+            }
+            let limits = FillLimits::of(order)?;
+            if let Some(topped_up) = amount.checked_add(leftover).and_then(|wanted| limits.max_fill(wanted, *order_remaining)) {
+                if topped_up > *amount {
+                    leftover -= topped_up - *amount;
+                    *amount = topped_up;
+                }
+            }
+        }
+        Ok(amounts)
+    }
+}
+
+/// Group rate-sorted orders into consecutive price levels
+fn price_levels(orders: Vec<DbOrder>) -> MatchResult<Vec<PriceLevel>> {
+    let mut levels: Vec<PriceLevel> = Vec::new();
+    for order in orders {
+        let rate = Decimal::from_str(&order.exchange_rate)
+            .map_err(|e| MatchError::ParseError(format!("Invalid exchange rate: {}", e)))?;
+        let remaining = Decimal::from_str(&order.remaining_amount)
+            .map_err(|e| MatchError::ParseError(format!("Invalid remaining amount: {}", e)))?;
+        if rate.is_sign_negative() || remaining.is_sign_negative() {
+            return Err(MatchError::ParseError(format!("Order {} has a negative rate or remaining amount", order.order_id)));
+        }
+        match levels.last_mut() {
+            Some(level) if level.rate == rate => level.orders.push((order, remaining)),
+            _ => levels.push(PriceLevel { rate, orders: vec![(order, remaining)] }),
+        }
+    }
+    Ok(levels)
+}
+
+/// sha256 of the book a plan is matched against: each order's ID and the fields
+/// matching reads, in the order given ("0x"-prefixed hex)
+/// Two plans with the same hash saw the same orders with the same liquidity.
+pub fn book_hash(orders: &[DbOrder]) -> String {
+    let mut hasher = Sha256::new();
+    for order in orders {
+        hasher.update(format!(
+            "{}:{}:{}:{}:{}\n",
+            order.order_id,
+            order.remaining_amount,
+            order.exchange_rate,
+            order.min_fill.as_deref().unwrap_or(""),
+            order.lot_size.as_deref().unwrap_or("")
+        ));
+    }
+    format!("0x{}", hex::encode(hasher.finalize()))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
     use chrono::Utc;
+    use proptest::prelude::*;
     
     fn create_test_order(order_id: &str, remaining: &str, rate: &str) -> DbOrder {
         DbOrder {
             order_id: order_id.to_string(),
-            seller: "0x123".to_string(),
-            token: "0xUSDC".to_string(),
+            seller: "0x1111111111111111111111111111111111111111".parse().unwrap(),
+            token: "0x036cbd53842c5426634e7929541ec2318f3dcf7e".parse().unwrap(),
             total_amount: remaining.to_string(),
             remaining_amount: remaining.to_string(),
             exchange_rate: rate.to_string(),
@@ -138,6 +328,25 @@ mod tests {
             alipay_name: "Test Name".to_string(),
             created_at: 1234567890,
             synced_at: Utc::now(),
+            alipay_id_format: "phone".to_string(),
+            min_fill: None,
+            lot_size: None,
+        }
+    }
+    
+    #[test]
+    fn test_book_hash_changes_with_what_matching_reads() {
+        let book = vec![create_test_order("0x1", "100", "735"), create_test_order("0x2", "50", "740")];
+        let hash = book_hash(&book);
+        assert_eq!(hash.len(), 66);
+
+        let mut taken = book.clone();
+        taken[1].remaining_amount = "49".to_string();
+        let mut limited = book.clone();
+        limited[0].min_fill = Some("10".to_string());
+        let reordered = vec![book[1].clone(), book[0].clone()];
+        for other in [taken, limited, reordered, Vec::new()] {
+            assert_ne!(book_hash(&other), hash);
         }
     }
     
@@ -185,5 +394,128 @@ mod tests {
         assert_eq!(result.fills.len(), 2);  // Should only use first two
         assert_eq!(result.fully_fillable, false);  // Can't fill full amount
     }
+    
+    #[test]
+    fn test_match_respects_fill_limits() {
+        let mut min_fill = create_test_order("0x1", "50000000", "730");
+        min_fill.min_fill = Some("20000000".to_string());
+        let mut lots = create_test_order("0x2", "60000000", "735");
+        lots.lot_size = Some("10000000".to_string());
+        let orders = vec![min_fill, lots, create_test_order("0x3", "100000000", "740")];
+        
+        // 15 USDC: below 0x1's minimum, rounds to 10 in 0x2's lots, rest from 0x3
+        let result = match_buy_intent(orders.clone(), Decimal::from(15_000_000), None).unwrap();
+        let fills: Vec<_> = result.fills.iter().map(|f| (f.order_id.as_str(), f.fill_amount.as_str())).collect();
+        assert_eq!(fills, vec![("0x2", "10000000"), ("0x3", "5000000")]);
+        assert!(result.fully_fillable);
+        
+        // Taking an order's whole remainder is always allowed
+        let limits = FillLimits::of(&orders[1]).unwrap();
+        assert!(limits.check(Decimal::from(60_000_000), Decimal::from(60_000_000)).is_ok());
+        assert!(limits.check(Decimal::from(15_000_000), Decimal::from(60_000_000)).is_err());
+        assert!(FillLimits::of(&orders[0]).unwrap().check(Decimal::from(10_000_000), Decimal::from(50_000_000)).is_err());
+    }
+    
+    #[test]
+    fn test_match_pro_rata_splits_price_level() {
+        let orders = vec![
+            create_test_order("0x1", "30000000", "735"),
+            create_test_order("0x2", "90000000", "735"),
+            create_test_order("0x3", "100000000", "740"),
+        ];
+        let fills = |priority| {
+            let plan = match_buy_intent_with_priority(orders.clone(), Decimal::from(40_000_000), None, priority).unwrap();
+            plan.fills.into_iter().map(|f| (f.order_id, f.fill_amount)).collect::<Vec<_>>()
+        };
+        let fill = |id: &str, amount: &str| (id.to_string(), amount.to_string());
+        
+        // Oldest first takes 0x1 whole; pro-rata splits 40 as 1:3
+        assert_eq!(fills(OrderPriority::PriceTime), vec![fill("0x1", "30000000"), fill("0x2", "10000000")]);
+        assert_eq!(fills(OrderPriority::ProRata), vec![fill("0x1", "10000000"), fill("0x2", "30000000")]);
+        
+        // A level taken whole is the same either way, the rest continues at the next rate
+        let plan = match_buy_intent_with_priority(orders, Decimal::from(150_000_000), None, OrderPriority::ProRata).unwrap();
+        assert_eq!(plan.fills.len(), 3);
+        assert!(plan.fully_fillable);
+        
+        assert_eq!("pro-rata".parse::<OrderPriority>(), Ok(OrderPriority::ProRata));
+        assert!("fifo".parse::<OrderPriority>().is_err());
+    }
+    
+    #[test]
+    fn test_pro_rata_rounding_respects_lots() {
+        let mut lots = create_test_order("0x1", "50000000", "735");
+        lots.lot_size = Some("10000000".to_string());
+        let orders = vec![lots, create_test_order("0x2", "50000000", "735")];
+        
+        // 25 split 12.5/12.5: 0x1 rounds down to its 10 lot, 0x2 takes the rest
+        let plan = match_buy_intent_with_priority(orders, Decimal::from(25_000_000), None, OrderPriority::ProRata).unwrap();
+        let fills: Vec<_> = plan.fills.iter().map(|f| (f.order_id.as_str(), f.fill_amount.as_str())).collect();
+        assert_eq!(fills, vec![("0x1", "10000000"), ("0x2", "15000000")]);
+        assert!(plan.fully_fillable);
+    }
+    
+    /// Amounts as they may arrive from the DB or a request: token units, fractions, Decimal's
+    /// extremes, zero, negatives and garbage
+    fn amount() -> impl Strategy<Value = String> {
+        prop_oneof![
+            4 => "[1-9][0-9]{0,27}",
+            2 => "-?[0-9]{1,20}(\\.[0-9]{1,12})?",
+            1 => Just("0".to_string()),
+            1 => Just("79228162514264337593543950335".to_string()),
+            1 => Just("0.0000000000000000000000000001".to_string()),
+            1 => "\\PC{0,10}",
+        ]
+    }
+    
+    proptest! {
+        #[test]
+        fn prop_matching_never_panics_and_fills_add_up(
+            orders in proptest::collection::vec(
+                (amount(), amount(), proptest::option::of(amount()), proptest::option::of(amount())),
+                0..6,
+            ),
+            desired in amount(),
+            max_rate in proptest::option::of(amount()),
+            pro_rata in any::<bool>(),
+        ) {
+            let Ok(desired) = Decimal::from_str(&desired) else { return Ok(()) };
+            let max_rate = max_rate.and_then(|rate| Decimal::from_str(&rate).ok());
+            let mut orders: Vec<DbOrder> = orders
+                .into_iter()
+                .enumerate()
+                .map(|(i, (remaining, rate, min_fill, lot_size))| DbOrder {
+                    min_fill,
+                    lot_size,
+                    ..create_test_order(&format!("0x{}", i), &remaining, &rate)
+                })
+                .collect();
+            // The repository hands orders over best rate first
+            orders.sort_by_key(|order| Decimal::from_str(&order.exchange_rate).ok());
+            
+            let priority = if pro_rata { OrderPriority::ProRata } else { OrderPriority::PriceTime };
+            let Ok(plan) = match_buy_intent_with_priority(orders.clone(), desired, max_rate, priority) else { return Ok(()) };
+            
+            let mut filled = Decimal::ZERO;
+            for fill in &plan.fills {
+                let amount = Decimal::from_str(&fill.fill_amount).unwrap();
+                let order = orders.iter().find(|order| order.order_id == fill.order_id).unwrap();
+                prop_assert!(amount > Decimal::ZERO);
+                prop_assert!(amount <= Decimal::from_str(&order.remaining_amount).unwrap());
+                if let Some(max) = max_rate {
+                    prop_assert!(Decimal::from_str(&fill.exchange_rate).unwrap() <= max);
+                }
+                filled += amount;
+            }
+            prop_assert!(filled <= desired);
+            
+            // Decimal rounds past 28 digits, so the books only balance exactly in whole token units
+            let whole = |value: &String| Decimal::from_str(value).map(|value| value.fract().is_zero()).unwrap_or(true);
+            if desired.fract().is_zero()
+                && orders.iter().all(|order| whole(&order.remaining_amount) && order.lot_size.iter().all(whole))
+            {
+                prop_assert_eq!(filled, Decimal::from_str(&plan.total_filled).unwrap());
+            }
+        }
+    }
 }
-
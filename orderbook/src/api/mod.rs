@@ -1,12 +1,20 @@
+pub mod api_keys;
+pub mod clock;
+pub mod confirmations;
+pub mod display;
 pub mod error;
+pub mod freshness;
 pub mod handlers;
+pub mod maintenance;
 pub mod matching;
+pub mod request_id;
 pub mod routes;
+pub mod settlement;
 pub mod state;
 pub mod types;
 
 pub use error::{ApiError, ApiResult};
-pub use matching::{MatchPlan, Fill, match_buy_intent};
+pub use matching::{MatchPlan, Fill, match_buy_intent, match_buy_intent_with_priority};
 pub use routes::create_router;
 pub use state::AppState;
 
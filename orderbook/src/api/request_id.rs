@@ -0,0 +1,129 @@
+// Request IDs
+// A user reporting "Internal server error" gave support nothing to find in the logs.
+// Every request now gets an ID - the caller's X-Request-Id when it sent a usable one,
+// a fresh UUID otherwise - which is echoed in the X-Request-Id response header, added as
+// "request_id" to JSON error bodies and recorded on the request's tracing span, so every
+// log line of the request carries it.
+
+use axum::{
+    body::{to_bytes, Body},
+    extract::Request,
+    http::{header, HeaderMap, HeaderValue},
+    middleware::Next,
+    response::Response,
+};
+use tracing::Instrument;
+use uuid::Uuid;
+
+pub const REQUEST_ID_HEADER: &str = "x-request-id";
+
+/// Longest inbound request ID kept (longer ones are replaced)
+const MAX_REQUEST_ID_LEN: usize = 128;
+
+/// Largest JSON error body the request ID is added to (bigger ones only get the header)
+const MAX_TAGGED_BODY: usize = 64 * 1024;
+
+/// The request's ID, available to handlers as an extension
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RequestId(pub String);
+
+impl RequestId {
+    /// The caller's ID if it is short printable ASCII, else a new one
+    pub fn from_headers(headers: &HeaderMap) -> Self {
+        headers
+            .get(REQUEST_ID_HEADER)
+            .and_then(|value| value.to_str().ok())
+            .map(str::trim)
+            .filter(|id| !id.is_empty() && id.len() <= MAX_REQUEST_ID_LEN)
+            .filter(|id| id.bytes().all(|b| b.is_ascii_graphic()))
+            .map(|id| Self(id.to_string()))
+            .unwrap_or_else(|| Self(Uuid::new_v4().to_string()))
+    }
+
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+}
+
+/// Middleware: assign the request ID, run the request in its span and tag the response
+pub async fn assign_request_id(mut request: Request, next: Next) -> Response {
+    let request_id = RequestId::from_headers(request.headers());
+    let span = tracing::info_span!(
+        "request",
+        request_id = %request_id.as_str(),
+        method = %request.method(),
+        path = %request.uri().path(),
+    );
+    request.extensions_mut().insert(request_id.clone());
+
+    let response = next.run(request).instrument(span).await;
+    let (mut parts, body) = response.into_parts();
+    if let Ok(value) = HeaderValue::from_str(request_id.as_str()) {
+        parts.headers.insert(REQUEST_ID_HEADER, value);
+    }
+
+    let is_json = parts
+        .headers
+        .get(header::CONTENT_TYPE)
+        .and_then(|v| v.to_str().ok())
+        .is_some_and(|v| v.starts_with("application/json"));
+    if !is_json || !(parts.status.is_client_error() || parts.status.is_server_error()) {
+        return Response::from_parts(parts, body);
+    }
+
+    let Ok(bytes) = to_bytes(body, MAX_TAGGED_BODY).await else {
+        return Response::from_parts(parts, Body::empty());
+    };
+    let body = match serde_json::from_slice::<serde_json::Value>(&bytes) {
+        Ok(serde_json::Value::Object(mut object)) => {
+            object.insert("request_id".to_string(), serde_json::Value::String(request_id.0));
+            parts.headers.remove(header::CONTENT_LENGTH);
+            Body::from(serde_json::Value::Object(object).to_string())
+        }
+        _ => Body::from(bytes),
+    };
+    Response::from_parts(parts, body)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::{middleware, routing::get, Router};
+    use tower::ServiceExt;
+
+    use crate::api::error::ApiError;
+
+    fn router() -> Router {
+        Router::new()
+            .route("/ok", get(|| async { "ok" }))
+            .route("/fail", get(|| async { Err::<(), _>(ApiError::Internal("boom".to_string())) }))
+            .layer(middleware::from_fn(assign_request_id))
+    }
+
+    fn get_request(path: &str, request_id: Option<&str>) -> Request {
+        let mut builder = axum::http::Request::builder().uri(path);
+        if let Some(request_id) = request_id {
+            builder = builder.header(REQUEST_ID_HEADER, request_id);
+        }
+        builder.body(Body::empty()).unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_request_id_is_echoed_in_headers_and_error_bodies() {
+        let response = router().oneshot(get_request("/fail", Some("support-1234"))).await.unwrap();
+        assert_eq!(response.headers()[REQUEST_ID_HEADER], "support-1234");
+        let body = to_bytes(response.into_body(), MAX_TAGGED_BODY).await.unwrap();
+        let body: serde_json::Value = serde_json::from_slice(&body).unwrap();
+        assert_eq!(body["request_id"], "support-1234");
+        assert_eq!(body["error"], "Internal server error");
+
+        // Generated when missing or unusable, and successful bodies are left alone
+        let response = router().oneshot(get_request("/ok", Some("has spaces"))).await.unwrap();
+        let generated = response.headers()[REQUEST_ID_HEADER].to_str().unwrap().to_string();
+        assert!(Uuid::parse_str(&generated).is_ok());
+        assert_eq!(&to_bytes(response.into_body(), MAX_TAGGED_BODY).await.unwrap()[..], b"ok");
+
+        let response = router().oneshot(get_request("/ok", None)).await.unwrap();
+        assert_ne!(response.headers()[REQUEST_ID_HEADER], generated.as_str());
+    }
+}
@@ -1,10 +1,14 @@
 use axum::{
+    extract::DefaultBodyLimit,
+    http::HeaderName,
+    middleware,
     routing::{get, post},
     Router,
 };
 use tower_http::cors::{CorsLayer, Any};
 
-use crate::api::{handlers, state::AppState};
+use crate::api::{api_keys, handlers, maintenance, request_id, state::AppState};
+use crate::sandbox;
 
 /// Create the API router with all endpoints
 /// DB-based orderbook with direct query matching
@@ -12,15 +16,34 @@ pub fn create_router(state: AppState) -> Router {
     let cors = CorsLayer::new()
         .allow_origin(Any)
         .allow_methods(Any)
-        .allow_headers(Any);
+        .allow_headers(Any)
+        .expose_headers([HeaderName::from_static(request_id::REQUEST_ID_HEADER)]);
 
     Router::new()
         // Health check
         .route("/health", get(handlers::health_check))
+        .route("/metrics", get(handlers::metrics_handler))
+        .route("/api/time", get(handlers::time_handler))
+        .route("/api/status", get(handlers::status_handler))
         
         // Order endpoints
         .route("/api/orders/active", get(handlers::get_active_orders))
+        .route("/api/orderbook/summary", get(handlers::orderbook_summary_handler))
         .route("/api/orders/:order_id", get(handlers::get_order))
+        .route("/api/orders/:order_id/fill-limits", post(handlers::set_fill_limits_handler))
+        .route("/api/orders/:order_id/refresh", post(handlers::refresh_order_handler))
+        .route("/api/orders/:order_id/withdraw-calldata", post(handlers::withdraw_calldata_handler))
+        .route("/api/orders/batch-withdraw-calldata", post(handlers::batch_withdraw_calldata_handler))
+        .route("/api/orders/batch-create-calldata", post(handlers::batch_create_calldata_handler))
+        
+        // Off-chain order intents (EIP-712, posted before the escrow is funded)
+        .route("/api/order-intents", get(handlers::list_order_intents_handler).post(handlers::post_order_intent_handler))
+        .route("/api/order-intents/seller/:seller", get(handlers::get_seller_order_intents_handler))
+        .route("/api/order-intents/:intent_hash", get(handlers::get_order_intent_handler))
+        .route("/api/order-intents/:intent_hash/cancel", post(handlers::cancel_order_intent_handler))
+        
+        // Market-maker order feed (snapshot + sequenced updates, NDJSON)
+        .route("/api/feed/orders.ndjson", get(handlers::order_feed_handler))
         
         // Matching endpoint
         .route("/api/match-intent", post(handlers::match_buy_intent_handler))
@@ -29,30 +52,121 @@ pub fn create_router(state: AppState) -> Router {
         .route("/api/execute-fill", post(handlers::execute_fill_handler))
         .route("/api/trades/:trade_id", get(handlers::get_trade_handler))
         .route("/api/trades/buyer/:buyer_address", get(handlers::get_trades_by_buyer_handler))
+        .route("/api/trades/by-nonce/:nonce", get(handlers::get_trade_by_nonce_handler))
         .route("/api/submit-proof", post(handlers::submit_proof_handler))
         
         // PDF endpoints
         .route("/api/trades/:trade_id/pdf", post(handlers::upload_pdf_handler))
         .route("/api/trades/:trade_id/pdf", get(handlers::get_pdf_handler))
+        .route("/api/trades/buyer/:buyer_address/pdf", post(handlers::upload_buyer_pdf_handler))
+        .route(
+            "/api/pdfs/bulk",
+            post(handlers::bulk_upload_pdfs_handler)
+                .layer(DefaultBodyLimit::max(handlers::pdf::MAX_BULK_UPLOAD_SIZE)),
+        )
         
         // Proof endpoints
-        .route("/api/trades/:trade_id/proof", get(handlers::get_proof_handler))
+        .route(
+            "/api/trades/:trade_id/proof",
+            get(handlers::get_proof_handler)
+                .post(handlers::upload_proof_handler)
+                .layer(DefaultBodyLimit::max(handlers::proof::MAX_PROOF_JSON_SIZE)),
+        )
+        .route("/api/trades/:trade_id/receipt", get(handlers::get_receipt_handler))
+        .route("/api/trades/:trade_id/attestation", get(handlers::get_attestation_handler))
         .route("/api/validate-pdf-axiom", post(handlers::validate_pdf_axiom_handler))
+        .route("/api/trades/:trade_id/validations", get(handlers::get_validations_handler))
         .route("/api/generate-proof", post(handlers::generate_proof_handler))
         .route("/api/submit-blockchain-proof", post(handlers::submit_blockchain_proof_handler))
         
+        // Alipay transfers the buyer declares per trade (before uploading the receipt)
+        .route("/api/trades/:trade_id/payments", get(handlers::get_trade_payments_handler).post(handlers::declare_trade_payment_handler))
+        
+        // Buyer/seller messages on a trade
+        .route("/api/trades/:trade_id/messages", get(handlers::get_trade_messages_handler).post(handlers::post_trade_message_handler))
+        .route("/api/trades/:trade_id/messages/:message_id/attachment", get(handlers::get_message_attachment_handler))
+        
+        // Settlement pipeline (validate -> prove -> verify -> submit as one job)
+        .route("/api/trades/:trade_id/settle", post(handlers::settle_trade_handler))
+        .route("/api/settlement-jobs/:job_id", get(handlers::get_settlement_job_handler))
+        .route("/api/settlement-jobs/:job_id/events", get(handlers::settlement_job_events_handler))
+        
+        // Per-user notification channels (email, SMS, Telegram)
+        .route(
+            "/api/notifications/:address/channels",
+            get(handlers::get_notification_channels_handler).post(handlers::set_notification_channel_handler),
+        )
+        
+        // Token allowlist / denylist
+        .route("/api/tokens/status", get(handlers::get_token_status_handler))
+        
+        // Analytics endpoints
+        .route("/api/analytics/relayer-costs", get(handlers::relayer_costs_handler))
+        .route("/api/analytics/orderbook-history", get(handlers::orderbook_history_handler))
+        .route("/api/analytics/funnel", get(handlers::funnel_handler))
+        
+        // Fee endpoints
+        .route("/api/fees", get(handlers::get_fees_handler))
+        .route("/api/fees/:address", get(handlers::get_fee_statement_handler))
+        
         // Debug endpoint
         .route("/api/debug/database", get(handlers::get_database_dump))
         
+        // Sandbox mode (404 otherwise)
+        .route("/api/sandbox/orders", post(handlers::create_sandbox_order_handler))
+        
         // Admin endpoints
         .route("/api/admin/config", get(handlers::get_config_handler))
+        .route("/api/admin/refresh-config", post(handlers::refresh_config_handler))
+        .route("/api/admin/config-history", get(handlers::get_config_history_handler))
         .route("/api/admin/update-config", post(handlers::update_config_handler))
         .route("/api/admin/update-verifier", post(handlers::update_verifier_handler))
         .route("/api/admin/update-zkpdf-config", post(handlers::update_zkpdf_config_handler))
         .route("/api/admin/pause", post(handlers::pause_contract_handler))
         .route("/api/admin/unpause", post(handlers::unpause_contract_handler))
+        .route("/api/admin/simulate-settlement", post(handlers::simulate_settlement_handler))
+        .route("/api/admin/force-expire", post(handlers::force_expire_handler))
+        .route("/api/admin/force-release", post(handlers::force_release_handler))
+        .route("/api/admin/relayer-keys", get(handlers::get_relayer_keys_handler))
+        .route("/api/admin/rotate-relayer", post(handlers::rotate_relayer_handler))
+        .route("/api/admin/pending-txs", get(handlers::get_pending_txs_handler))
+        .route("/api/admin/pending-txs/:tx_hash/bump", post(handlers::bump_pending_tx_handler))
+        .route("/api/admin/pending-txs/:tx_hash/cancel", post(handlers::cancel_pending_tx_handler))
+        .route("/api/admin/token-status", post(handlers::set_token_status_handler))
+        .route("/api/admin/alipay-blocklist", get(handlers::get_alipay_blocklist_handler).post(handlers::set_alipay_blocklist_handler))
+        .route("/api/admin/anomalies", get(handlers::get_anomalies_handler))
+        .route("/api/admin/anomalies/templates/:template/clear", post(handlers::clear_template_flag_handler))
+        .route("/api/admin/payment-windows", get(handlers::get_payment_windows_handler).post(handlers::set_payment_window_tier_handler))
+        .route("/api/admin/order-staleness", get(handlers::get_order_staleness_handler).post(handlers::set_order_staleness_handler))
+        .route("/api/admin/spending-limits", get(handlers::get_spending_limits_handler).post(handlers::set_spending_limit_tier_handler))
+        .route("/api/admin/buyer-tiers", post(handlers::set_buyer_tier_handler))
+        .route("/api/admin/screening", get(handlers::get_screening_handler))
+        .route("/api/admin/screening/clear", post(handlers::clear_screening_handler))
+        .route("/api/admin/dead-letters", get(handlers::list_dead_letters_handler))
+        .route("/api/admin/dead-letters/:id/reprocess", post(handlers::reprocess_dead_letter_handler))
+        .route("/api/admin/dead-letters/:id/discard", post(handlers::discard_dead_letter_handler))
+        .route("/api/admin/maintenance", get(handlers::get_maintenance_handler).post(handlers::set_maintenance_handler))
+        .route("/api/admin/api-keys", get(handlers::list_api_keys_handler).post(handlers::create_api_key_handler))
+        .route("/api/admin/api-keys/:key_id/revoke", post(handlers::revoke_api_key_handler))
+        .route("/api/admin/trades/:trade_id/support", get(handlers::get_trade_support_handler).post(handlers::update_trade_support_handler))
+        .route("/api/admin/trades/:trade_id/notes", post(handlers::add_trade_note_handler))
+        .route("/api/admin/trades/:trade_id/prover-transcripts", get(handlers::get_prover_transcripts_handler))
+        .route("/api/admin/prover-budget", get(handlers::get_prover_budget_handler))
+        .route("/api/admin/runtime-config", get(handlers::get_runtime_config_handler).post(handlers::set_runtime_config_handler))
+        .route("/api/admin/support/trades", get(handlers::list_support_trades_handler))
+        .route("/api/admin/orders", get(handlers::list_orders_handler))
+        .route("/api/admin/trades", get(handlers::list_trades_handler))
+        .route("/api/admin/match-plans/:plan_id", get(handlers::get_match_plan_handler))
         
+        // Market-maker API keys (x-api-key), optional for every route
+        .layer(middleware::from_fn_with_state(state.clone(), api_keys::authenticate_api_key))
+        // Maintenance mode refuses writes before any other work is done
+        .layer(middleware::from_fn_with_state(state.clone(), maintenance::reject_during_maintenance))
+        // Sandbox responses are marked (header, "sandbox": true in JSON objects)
+        .layer(middleware::from_fn_with_state(state.clone(), sandbox::mark_sandbox_responses))
         .layer(cors)
+        // Outermost, so every response and log line of a request carries its X-Request-Id
+        .layer(middleware::from_fn(request_id::assign_request_id))
         .with_state(state)
 }
 
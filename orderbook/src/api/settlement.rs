@@ -0,0 +1,297 @@
+// Settlement jobs: validate -> prove -> verify -> submit as one resumable pipeline
+// Each step reuses the standalone endpoint logic; the job's current step is persisted
+// before moving on, so a failed job resumes from the step that failed. A running job is
+// leased to the process running it, which renews the lease until the job ends; jobs
+// whose lease lapsed (their process stopped or died) are claimed by one other process
+// and picked up again there. The verify step mines the
+// submission on an anvil fork first (see blockchain::fork_check) and passes straight
+// through when no fork is configured.
+
+use serde::Serialize;
+use std::fmt;
+use std::str::FromStr;
+use std::sync::LazyLock;
+use std::time::Duration;
+use uuid::Uuid;
+
+use crate::api::{
+    error::{ApiError, ApiResult},
+    handlers::{
+        buyer::submit_trade_proof,
+        generate_proof::{generate_trade_proof, validate_trade_pdf},
+    },
+    state::AppState,
+};
+use crate::blockchain::fork_check::{ForkCheckError, ForkVerifier};
+use crate::blockchain::ids::TradeId;
+use crate::blockchain::settlement_batch::ProofSubmission;
+use crate::db::{
+    DbResult,
+    models::DbSettlementJob,
+    settlement_jobs::{JOB_FAILED, JOB_LEASE_SECS, JOB_RUNNING},
+};
+
+/// Lease owner of the jobs this process runs
+pub static LEASE_OWNER: LazyLock<Uuid> = LazyLock::new(Uuid::new_v4);
+
+/// How often a running job's lease is renewed (well within JOB_LEASE_SECS)
+const LEASE_RENEW_INTERVAL: Duration = Duration::from_secs(JOB_LEASE_SECS as u64 / 4);
+
+/// How often jobs with a lapsed lease are looked for
+pub const STALE_JOB_CHECK_INTERVAL: Duration = Duration::from_secs(JOB_LEASE_SECS as u64);
+
+/// Pipeline steps, in order
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum SettlementStep {
+    Validate,
+    Prove,
+    Verify,
+    Submit,
+    Done,
+}
+
+impl SettlementStep {
+    pub const PIPELINE: [SettlementStep; 4] =
+        [SettlementStep::Validate, SettlementStep::Prove, SettlementStep::Verify, SettlementStep::Submit];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            SettlementStep::Validate => "validate",
+            SettlementStep::Prove => "prove",
+            SettlementStep::Verify => "verify",
+            SettlementStep::Submit => "submit",
+            SettlementStep::Done => "done",
+        }
+    }
+}
+
+impl fmt::Display for SettlementStep {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for SettlementStep {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "validate" => Ok(SettlementStep::Validate),
+            "prove" => Ok(SettlementStep::Prove),
+            "verify" => Ok(SettlementStep::Verify),
+            "submit" => Ok(SettlementStep::Submit),
+            "done" => Ok(SettlementStep::Done),
+            other => Err(format!("Unknown settlement step: {}", other)),
+        }
+    }
+}
+
+/// Progress of one step for display: done | running | failed | pending
+#[derive(Debug, Clone, Serialize)]
+pub struct StepProgress {
+    pub step: &'static str,
+    pub state: &'static str,
+}
+
+/// Per-step progress of a job, derived from its persisted step and status
+pub fn step_progress(job: &DbSettlementJob) -> Vec<StepProgress> {
+    let current = job.step.parse().unwrap_or(SettlementStep::Validate);
+
+    SettlementStep::PIPELINE
+        .iter()
+        .map(|step| StepProgress {
+            step: step.as_str(),
+            state: match step.cmp(&current) {
+                std::cmp::Ordering::Less => "done",
+                std::cmp::Ordering::Greater => "pending",
+                std::cmp::Ordering::Equal if job.status == JOB_FAILED => "failed",
+                std::cmp::Ordering::Equal if job.status == JOB_RUNNING => "running",
+                std::cmp::Ordering::Equal => "pending",
+            },
+        })
+        .collect()
+}
+
+/// Run a job (leased to this process) in the background until it completes or fails,
+/// renewing its lease meanwhile; the job is abandoned if the lease is lost to another process
+pub fn spawn_settlement_job(state: AppState, job: DbSettlementJob) {
+    tokio::spawn(async move {
+        let result = tokio::select! {
+            result = run_settlement_job(&state, &job) => result,
+            () = hold_lease(&state, &job) => {
+                tracing::warn!("⚠️  Settlement job {} for trade {} was claimed by another process - stopping here",
+                    job.job_id, job.trade_id);
+                return;
+            }
+        };
+
+        match result {
+            Ok(tx_hash) => {
+                tracing::info!("✅ Settlement job {} for trade {} submitted: {}", job.job_id, job.trade_id, tx_hash);
+            }
+            Err(e) => {
+                let message = e.into_message();
+                tracing::warn!("❌ Settlement job {} for trade {} failed: {}", job.job_id, job.trade_id, message);
+                if let Err(e) = state.db.fail_settlement_job(job.job_id, &message).await {
+                    tracing::error!("Failed to record settlement job {} failure: {}", job.job_id, e);
+                }
+            }
+        }
+    });
+}
+
+/// Renew this process's lease on a job; returns once the lease is no longer ours
+async fn hold_lease(state: &AppState, job: &DbSettlementJob) {
+    let mut ticker = tokio::time::interval(LEASE_RENEW_INTERVAL);
+    ticker.tick().await;
+    loop {
+        ticker.tick().await;
+        match state.db.renew_settlement_job_lease(job.job_id, *LEASE_OWNER).await {
+            Ok(true) => {}
+            Ok(false) => return,
+            // Retried on the next tick; the lease only lapses after several misses
+            Err(e) => tracing::warn!("Failed to renew the lease of settlement job {}: {}", job.job_id, e),
+        }
+    }
+}
+
+/// Execute the remaining steps of a job, persisting progress after each; returns the submission tx hash
+async fn run_settlement_job(state: &AppState, job: &DbSettlementJob) -> ApiResult<String> {
+    let mut step: SettlementStep = job.step.parse().map_err(ApiError::Internal)?;
+
+    tracing::info!("⚙️ Settlement job {} for trade {} running from step {} (attempt {})",
+        job.job_id, job.trade_id, step, job.attempts);
+
+    loop {
+        match step {
+            SettlementStep::Validate => {
+                let validation = validate_trade_pdf(state, job.trade_id.clone()).await?;
+                if !validation.is_valid {
+                    return Err(ApiError::BadRequest(
+                        "PDF validation failed - hash mismatch. Upload the correct receipt and resume.".to_string(),
+                    ));
+                }
+                step = SettlementStep::Prove;
+            }
+            SettlementStep::Prove => {
+                // A proof stored by an earlier attempt (or /api/generate-proof) is reused
+                let trade = state.db.get_trade(&job.trade_id).await?;
+                let proof_id = match (trade.proof_data, trade.axiom_proof_id) {
+                    (Some(_), Some(proof_id)) => proof_id,
+                    _ => generate_trade_proof(state, job.trade_id.clone())
+                        .await?
+                        .proof_id
+                        .unwrap_or_default(),
+                };
+                state.db.set_settlement_job_proof_id(job.job_id, &proof_id).await?;
+                step = SettlementStep::Verify;
+            }
+            SettlementStep::Verify => {
+                if let Some(verifier) = &state.fork_verifier {
+                    verify_on_fork(state, verifier, &job.trade_id).await?;
+                }
+                step = SettlementStep::Submit;
+            }
+            SettlementStep::Submit => {
+                let trade = state.db.get_trade(&job.trade_id).await?;
+                let tx_hash = match trade.status {
+                    // Settled by an earlier attempt whose result wasn't recorded
+                    1 => trade.settlement_tx_hash.unwrap_or_default(),
+                    2 => return Err(ApiError::BadRequest("Trade has expired".to_string())),
+                    _ => submit_trade_proof(state, &job.trade_id.parse()?).await?.tx_hash,
+                };
+                state.db.complete_settlement_job(job.job_id, &tx_hash).await?;
+                return Ok(tx_hash);
+            }
+            SettlementStep::Done => {
+                return Ok(job.tx_hash.clone().unwrap_or_default());
+            }
+        }
+
+        state.db.set_settlement_job_step(job.job_id, step.as_str()).await?;
+    }
+}
+
+/// Mine the trade's stored proof on the fork; a revert fails the job before any gas is spent
+async fn verify_on_fork(state: &AppState, verifier: &ForkVerifier, trade_id: &str) -> ApiResult<()> {
+    let trade = state.db.get_trade(trade_id).await?;
+    if trade.status != 0 {
+        // Already settled or expired - the submit step reports it
+        return Ok(());
+    }
+    let (Some(user_public_values), Some(accumulator), Some(proof)) =
+        (trade.proof_user_public_values, trade.proof_accumulator, trade.proof_data)
+    else {
+        return Err(ApiError::BadRequest("Proof not yet generated for this trade".to_string()));
+    };
+    let user_public_values: [u8; 32] = user_public_values
+        .try_into()
+        .map_err(|_| ApiError::BadRequest("Invalid user public values length".to_string()))?;
+    let submission = ProofSubmission {
+        trade_id: trade_id.parse::<TradeId>()?.0,
+        user_public_values,
+        accumulator,
+        proof,
+    };
+
+    match verifier.verify(&submission).await {
+        Ok(verification) => {
+            tracing::info!(
+                "🍴 Proof for trade {} settles on a fork of block {} ({} gas)",
+                trade_id, verification.fork_block, verification.gas_used
+            );
+            Ok(())
+        }
+        Err(e @ ForkCheckError::Fork(_)) => Err(ApiError::ServiceUnavailable(e.to_string())),
+        Err(e) => {
+            tracing::warn!("🍴 Proof for trade {} failed the fork check: {}", trade_id, e);
+            Err(ApiError::BadRequest(format!("{} - the proof was not submitted", e)))
+        }
+    }
+}
+
+/// Claim and restart running jobs whose lease lapsed (left by a previous shutdown or a
+/// replica that died); returns how many were resumed
+pub async fn resume_interrupted_jobs(state: &AppState) -> DbResult<usize> {
+    let jobs = state.db.claim_stale_settlement_jobs(*LEASE_OWNER).await?;
+    let count = jobs.len();
+
+    for job in jobs {
+        tracing::info!("♻️ Resuming settlement job {} for trade {} at step {}", job.job_id, job.trade_id, job.step);
+        spawn_settlement_job(state.clone(), job);
+    }
+
+    Ok(count)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Utc;
+
+    fn job(step: &str, status: &str) -> DbSettlementJob {
+        DbSettlementJob {
+            job_id: uuid::Uuid::new_v4(),
+            trade_id: format!("0x{}", "11".repeat(32)),
+            status: status.to_string(),
+            step: step.to_string(),
+            attempts: 1,
+            proof_id: None,
+            tx_hash: None,
+            error: None,
+            created_at: Utc::now(),
+            updated_at: Utc::now(),
+            completed_at: None,
+        }
+    }
+
+    #[test]
+    fn test_step_progress() {
+        let states = |job: &DbSettlementJob| step_progress(job).iter().map(|p| p.state).collect::<Vec<_>>();
+
+        assert_eq!(states(&job("validate", "running")), ["running", "pending", "pending", "pending"]);
+        assert_eq!(states(&job("prove", "failed")), ["done", "failed", "pending", "pending"]);
+        assert_eq!(states(&job("verify", "failed")), ["done", "done", "failed", "pending"]);
+        assert_eq!(states(&job("done", "completed")), ["done", "done", "done", "done"]);
+    }
+}
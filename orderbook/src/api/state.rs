@@ -1,8 +1,43 @@
+use serde::{Deserialize, Serialize};
 use std::sync::Arc;
-use std::collections::HashMap;
-use tokio::sync::RwLock;
+use std::time::Duration;
+use crate::api::api_keys::ApiKeyRegistry;
+use crate::api::clock::{ChainClock, DEFAULT_EXPIRY_WARNING_SECS};
+use crate::api::confirmations::{AdminConfirmations, AdminKeys};
+use crate::api::display::TokenDisplayRegistry;
+use crate::api::handlers::orders::DEFAULT_MATCH_PLAN_TTL_SECS;
+use crate::cache::{MemoryCache, OrderbookCache, SharedCache};
 use crate::db::Database;
+use crate::db::orders::OrderPriority;
+use crate::db::pool::PoolConfig;
 use crate::blockchain::client::EthereumClient;
+use crate::blockchain::client::EthereumClientError;
+use crate::blockchain::config_cache::{ContractConfig, ContractConfigCache};
+use crate::blockchain::fill_locks::FillLocks;
+use crate::blockchain::fork_check::ForkVerifier;
+use crate::blockchain::settlement_batch::SettlementBatcher;
+use crate::fees::FeeEngine;
+use crate::receipts::ReceiptSigner;
+use crate::metrics::Metrics;
+use crate::proof_format::ProofFormatRegistry;
+use crate::proof_programs::ProofProgramRegistry;
+use crate::startup::StartupReport;
+use crate::api::maintenance::Maintenance;
+use crate::axiom_prover::budget::ProverBudget;
+use crate::axiom_prover::poller::{PollConfig, PollScheduler};
+use crate::sandbox::SandboxChain;
+use crate::screening::Screening;
+use crate::spending_limits::KycProvider;
+
+/// Input streams generated during validation, tagged with the Axiom program they target
+/// and the PDF upload they were built from
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CachedInputStreams {
+    pub program_id: String,
+    pub streams: Vec<String>,
+    #[serde(default)]
+    pub pdf_uploaded_at: Option<chrono::DateTime<chrono::Utc>>,
+}
 
 /// Shared application state
 /// Uses DB-based orderbook (no in-memory cache)
@@ -14,27 +49,147 @@ pub struct AppState {
     /// Blockchain client for Ethereum interaction (optional for testing)
     pub blockchain_client: Option<Arc<EthereumClient>>,
     
-    /// In-memory cache for input streams (trade_id -> 46 hex strings)
-    /// Used to avoid regenerating input streams between validation and proof generation
-    pub input_streams_cache: Arc<RwLock<HashMap<String, Vec<String>>>>,
+    /// Groups proof submissions into Multicall3 batches (optional, see settlement_batch)
+    pub settlement_batcher: Option<Arc<SettlementBatcher>>,
+    
+    /// Serializes fills per order so overlapping match plans don't race (see fill_locks)
+    pub fill_locks: Arc<FillLocks>,
+    
+    /// Dry-runs submissions on an anvil fork before the real one (optional, see fork_check)
+    pub fork_verifier: Option<Arc<ForkVerifier>>,
+    
+    /// Cache shared between replicas (Redis) or per-process (memory), see cache
+    /// Holds input streams between validation and proof generation (cache::input_streams_key)
+    pub cache: Arc<dyn SharedCache>,
+    
+    /// In-memory replica of matchable orders (optional, matching reads Postgres without it)
+    pub orderbook_cache: Option<Arc<OrderbookCache>>,
+    
+    /// Known guest programs and their input stream layouts (see proof_programs)
+    pub proof_programs: Arc<ProofProgramRegistry>,
+    
+    /// Proof component sizes per verifier (see proof_format)
+    pub proof_formats: Arc<ProofFormatRegistry>,
+    
+    /// Token symbols and decimals for display amounts in responses (see api::display)
+    pub token_display: Arc<TokenDisplayRegistry>,
+    
+    /// Cached contract configuration (TTL-based, invalidated by config-change events)
+    pub contract_config: Arc<ContractConfigCache>,
+    
+    /// Axiom credit tracking; jobs are refused once the budget is spent (optional, see axiom_prover::budget)
+    pub prover_budget: Option<Arc<ProverBudget>>,
+    
+    /// Paces Axiom status polls of all in-flight jobs under one rate cap (see axiom_prover::poller)
+    pub poll_scheduler: Arc<PollScheduler>,
+    
+    /// Signer for settlement receipts (optional, requires an attestation or relayer key)
+    pub receipt_signer: Option<Arc<ReceiptSigner>>,
+    
+    /// Operator fee schedule (optional, fee accounting is off when unset)
+    pub fee_engine: Option<Arc<FeeEngine>>,
+    
+    /// Process metrics shared with background tasks (exported at /metrics)
+    pub metrics: Arc<Metrics>,
+    
+    /// Refuse matching when order data is staler than this (None = never refuse)
+    pub max_sync_staleness_secs: Option<u64>,
+    
+    /// Chain-time source for trade countdowns and /api/time
+    pub chain_clock: Arc<ChainClock>,
+    
+    /// Flag PENDING trades as expiring_soon when fewer than this many seconds remain
+    pub expiry_warning_secs: u64,
+    
+    /// Admin keys for dual-confirmed actions (empty = confirmation token only)
+    pub admin_keys: Arc<AdminKeys>,
+    
+    /// Pending force-expire / force-release confirmations
+    pub admin_confirmations: Arc<AdminConfirmations>,
+    
+    /// Market-maker API key cache and rate limits (see api::api_keys)
+    pub api_keys: Arc<ApiKeyRegistry>,
+    
+    /// Result of the startup checks (degraded findings are reported by /health)
+    pub startup: Arc<StartupReport>,
+    
+    /// Maintenance switch and scheduled windows (see api::maintenance)
+    pub maintenance: Arc<Maintenance>,
+    
+    /// Fake chain and instant mock prover (sandbox mode, see sandbox)
+    pub sandbox: Option<Arc<SandboxChain>>,
+    
+    /// How orders at the same rate are prioritized when matching
+    pub order_priority: OrderPriority,
+    
+    /// How long execute-fill accepts a plan /api/match-intent handed out
+    pub match_plan_ttl_secs: u64,
+    
+    /// Sanctions / abuse screening of buyers and sellers (no-op by default, see screening)
+    pub screening: Arc<Screening>,
+    
+    /// External KYC check that can raise a buyer's spending tier (optional, see spending_limits)
+    pub kyc_provider: Option<Arc<dyn KycProvider>>,
 }
 
 impl AppState {
-    /// Create new app state
+    /// Create new app state, applying pending migrations
     pub async fn new(database_url: &str) -> Result<Self, Box<dyn std::error::Error>> {
+        Self::connect(database_url, true, &PoolConfig::default()).await
+    }
+    
+    /// Create new app state; with `auto_migrate` false the schema must already be
+    /// current (applied by `api-server migrate`, see startup::check_migrations)
+    pub async fn connect(database_url: &str, auto_migrate: bool, pool: &PoolConfig) -> Result<Self, Box<dyn std::error::Error>> {
         // Connect to database
-        let db = Database::new(database_url).await?;
+        let db = Database::connect(database_url, pool).await?;
         
         // Run migrations
-        db.migrate().await?;
+        if auto_migrate {
+            db.migrate().await?;
+        } else {
+            tracing::info!("Skipping migrations on startup");
+        }
         
         tracing::info!("App state initialized (DB-based orderbook with direct queries)");
         
-        Ok(Self {
+        Ok(Self::from_database(db))
+    }
+    
+    /// App state over an open database, every optional integration off
+    /// (handler tests use it with in-memory repositories, see db::fake)
+    pub fn from_database(db: Database) -> Self {
+        Self {
             db: Arc::new(db),
             blockchain_client: None,
-            input_streams_cache: Arc::new(RwLock::new(HashMap::new())),
-        })
+            settlement_batcher: None,
+            fill_locks: Arc::new(FillLocks::default()),
+            fork_verifier: None,
+            cache: Arc::new(MemoryCache::new()),
+            orderbook_cache: None,
+            proof_programs: Arc::new(ProofProgramRegistry::default()),
+            proof_formats: Arc::new(ProofFormatRegistry::default()),
+            token_display: Arc::new(TokenDisplayRegistry::default()),
+            contract_config: Arc::new(ContractConfigCache::default()),
+            prover_budget: None,
+            poll_scheduler: PollScheduler::spawn(PollConfig::default()),
+            receipt_signer: None,
+            fee_engine: None,
+            metrics: Arc::new(Metrics::new()),
+            max_sync_staleness_secs: None,
+            chain_clock: Arc::new(ChainClock::new()),
+            expiry_warning_secs: DEFAULT_EXPIRY_WARNING_SECS,
+            admin_keys: Arc::new(AdminKeys::default()),
+            admin_confirmations: Arc::new(AdminConfirmations::default()),
+            api_keys: Arc::new(ApiKeyRegistry::new()),
+            startup: Arc::new(StartupReport::default()),
+            maintenance: Arc::new(Maintenance::default()),
+            sandbox: None,
+            order_priority: OrderPriority::default(),
+            match_plan_ttl_secs: DEFAULT_MATCH_PLAN_TTL_SECS,
+            screening: Arc::new(Screening::default()),
+            kyc_provider: None,
+        }
     }
     
     /// Set blockchain client (optional, for blockchain integration)
@@ -42,4 +197,158 @@ impl AppState {
         self.blockchain_client = Some(client);
         self
     }
+    
+    /// Set settlement batcher (optional, batches proof submissions)
+    pub fn with_settlement_batcher(mut self, batcher: Arc<SettlementBatcher>) -> Self {
+        self.settlement_batcher = Some(batcher);
+        self
+    }
+    
+    /// Set fork verifier (optional, adds the settlement pipeline's verify step)
+    pub fn with_fork_verifier(mut self, verifier: Arc<ForkVerifier>) -> Self {
+        self.fork_verifier = Some(verifier);
+        self
+    }
+    
+    /// Set prover budget (optional, tracks Axiom credits and refuses jobs it can't cover)
+    pub fn with_prover_budget(mut self, budget: Arc<ProverBudget>) -> Self {
+        self.prover_budget = Some(budget);
+        self
+    }
+    
+    /// Set the Axiom poll scheduler (replaces the default-configured one)
+    pub fn with_poll_scheduler(mut self, poller: Arc<PollScheduler>) -> Self {
+        self.poll_scheduler = poller;
+        self
+    }
+    
+    /// Set receipt signer (optional, enables signed settlement receipts)
+    pub fn with_receipt_signer(mut self, signer: Arc<ReceiptSigner>) -> Self {
+        self.receipt_signer = Some(signer);
+        self
+    }
+    
+    /// Set fee engine (optional, enables fee accruals and /api/fees statements)
+    pub fn with_fee_engine(mut self, engine: Arc<FeeEngine>) -> Self {
+        self.fee_engine = Some(engine);
+        self
+    }
+    
+    /// Set admin keys (enables two-key confirmation of force actions)
+    pub fn with_admin_keys(mut self, keys: AdminKeys) -> Self {
+        self.admin_keys = Arc::new(keys);
+        self
+    }
+    
+    /// Set the shared cache (e.g. Redis, so replicas share validation results)
+    pub fn with_cache(mut self, cache: Arc<dyn SharedCache>) -> Self {
+        self.cache = cache;
+        self
+    }
+    
+    /// Set the in-memory order book used by matching (its listener must be running)
+    pub fn with_orderbook_cache(mut self, cache: Arc<OrderbookCache>) -> Self {
+        self.orderbook_cache = Some(cache);
+        self
+    }
+    
+    /// Set staleness limit for matching (optional, see api::freshness)
+    pub fn with_max_sync_staleness(mut self, secs: u64) -> Self {
+        self.max_sync_staleness_secs = Some(secs);
+        self
+    }
+    
+    /// Set the expiring_soon threshold for trade responses
+    pub fn with_expiry_warning(mut self, secs: u64) -> Self {
+        self.expiry_warning_secs = secs;
+        self
+    }
+    
+    /// Set contract config snapshot lifetime
+    pub fn with_contract_config_ttl(mut self, ttl: Duration) -> Self {
+        self.contract_config = Arc::new(ContractConfigCache::new(ttl));
+        self
+    }
+    
+    /// Set the guest program registry
+    pub fn with_proof_programs(mut self, registry: ProofProgramRegistry) -> Self {
+        self.proof_programs = Arc::new(registry);
+        self
+    }
+    
+    /// Set the proof format registry
+    pub fn with_proof_formats(mut self, registry: ProofFormatRegistry) -> Self {
+        self.proof_formats = Arc::new(registry);
+        self
+    }
+    
+    /// Set the token display registry
+    pub fn with_token_display(mut self, registry: TokenDisplayRegistry) -> Self {
+        self.token_display = Arc::new(registry);
+        self
+    }
+    
+    /// Set the startup check report
+    pub fn with_startup_report(mut self, report: StartupReport) -> Self {
+        self.startup = Arc::new(report);
+        self
+    }
+    
+    /// Set the maintenance switch and schedule
+    pub fn with_maintenance(mut self, maintenance: Maintenance) -> Self {
+        self.maintenance = Arc::new(maintenance);
+        self
+    }
+    
+    /// Run against the sandbox chain instead of the escrow contract
+    pub fn with_sandbox(mut self, sandbox: SandboxChain) -> Self {
+        self.sandbox = Some(Arc::new(sandbox));
+        self
+    }
+    
+    /// Set the matching priority within a price level
+    pub fn with_order_priority(mut self, priority: OrderPriority) -> Self {
+        self.order_priority = priority;
+        self
+    }
+    
+    /// Set how long match plans can be executed
+    pub fn with_match_plan_ttl(mut self, secs: u64) -> Self {
+        self.match_plan_ttl_secs = secs;
+        self
+    }
+    
+    /// Set the address screening provider
+    pub fn with_screening(mut self, screening: Arc<Screening>) -> Self {
+        self.screening = screening;
+        self
+    }
+    
+    /// Set the KYC provider asked when a buyer hits a spending limit
+    pub fn with_kyc_provider(mut self, provider: Arc<dyn KycProvider>) -> Self {
+        self.kyc_provider = Some(provider);
+        self
+    }
+    
+    /// Contract config from the cached snapshot, or the sandbox's fixed one
+    /// None when blockchain integration is disabled
+    pub async fn chain_config(&self) -> Option<Result<ContractConfig, EthereumClientError>> {
+        if let Some(sandbox) = &self.sandbox {
+            return Some(Ok(sandbox.config()));
+        }
+        let client = self.blockchain_client.as_ref()?;
+        Some(self.contract_config.get(client).await)
+    }
+    
+    /// Whether the contract is paused, from the cached config
+    /// None when blockchain integration is disabled or the config can't be fetched
+    pub async fn market_paused(&self) -> Option<bool> {
+        match self.chain_config().await? {
+            Ok(config) => Some(config.paused),
+            Err(e) => {
+                tracing::warn!("Failed to read paused state: {}", e);
+                None
+            }
+        }
+    }
 }
@@ -1,5 +1,11 @@
+use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 
+use crate::api::maintenance::MaintenanceStatus;
+use crate::db::models::{DbFlaggedTemplate, DbTokenLiquidity};
+use crate::metrics::ListenerHealth;
+use crate::startup::StartupCheck;
+
 /// Health check response
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct HealthResponse {
@@ -7,6 +13,44 @@ pub struct HealthResponse {
     pub database: String,
     pub orderbook: String,
     pub timestamp: String,
+    /// Whether the escrow contract is paused (omitted when blockchain integration is disabled)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub market_paused: Option<bool>,
+    /// Event listener progress (omitted when blockchain integration is disabled)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub event_listener: Option<ListenerHealth>,
+    /// Startup checks that did not pass (status is "degraded" when any are listed)
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub startup_issues: Vec<StartupCheck>,
+    /// Maintenance switch and scheduled windows (writes are refused while active)
+    #[serde(default)]
+    pub maintenance: MaintenanceStatus,
+}
+
+/// Public platform status: safe to expose, unlike /health internals
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StatusResponse {
+    /// Whether the frontend should show a warning banner
+    pub banner: bool,
+    /// Sandbox mode: fake chain and mock proofs, nothing here is real
+    pub sandbox: bool,
+    /// Whether the chain RPC is reachable (false when blockchain integration is disabled)
+    pub chain_connected: bool,
+    /// When the latest settlement became final
+    pub last_settlement_at: Option<DateTime<Utc>>,
+    /// Mean time from receipt upload to generated proof over the last hour
+    pub avg_proof_latency_secs: Option<f64>,
+    /// Proofs generated over the last hour
+    pub proofs_last_hour: i64,
+    /// Active liquidity per tradable token
+    pub liquidity: Vec<DbTokenLiquidity>,
+    /// Receipt templates with a settlement mismatch spike: proofs of receipts on
+    /// these layouts are likely to fail until an admin clears the flag
+    pub flagged_templates: Vec<DbFlaggedTemplate>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub market_paused: Option<bool>,
+    pub maintenance: MaintenanceStatus,
+    pub timestamp: String,
 }
 
 /// Generic success response
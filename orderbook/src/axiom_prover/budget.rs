@@ -0,0 +1,210 @@
+// Axiom credit budget
+// Axiom bills every execution (validation) and proof against the account's credits,
+// and a job submitted once they're gone fails partway through a settlement. Every job
+// Axiom accepts is recorded in prover_usage with its configured cost, along with the
+// balance Axiom reported in a response header when one is configured. Before a new
+// job starts, the month's usage is checked against the monthly budget (and the last
+// reported balance): when it can't be covered the job is refused up front, and a
+// refused settlement job is resumed once credits are available again.
+//
+// Configuration (env):
+// - AXIOM_MONTHLY_CREDITS: credits per calendar month, UTC (unset = no budget; usage is still recorded)
+// - AXIOM_EXECUTION_CREDITS: credits one execution costs (default 1)
+// - AXIOM_PROOF_CREDITS: credits one proof costs (default 10)
+// - AXIOM_CREDITS_HEADER: response header carrying Axiom's remaining balance (unset = not read)
+
+use chrono::{DateTime, Datelike, TimeZone, Utc};
+use reqwest::header::{HeaderMap, HeaderName};
+use serde::Serialize;
+use std::sync::Arc;
+use thiserror::Error;
+
+use crate::db::prover_transcripts::TRANSCRIPT_EXECUTION;
+use crate::db::prover_usage::NewProverUsage;
+use crate::db::{Database, DbError};
+
+const DEFAULT_EXECUTION_CREDITS: i64 = 1;
+const DEFAULT_PROOF_CREDITS: i64 = 10;
+
+#[derive(Error, Debug)]
+pub enum BudgetError {
+    #[error("Invalid prover budget config: {0}")]
+    Config(String),
+    #[error("Prover budget exhausted: {needed} credits needed, {remaining} left until {resets_at}")]
+    Exhausted { needed: i64, remaining: i64, resets_at: DateTime<Utc> },
+    #[error("Database error: {0}")]
+    Database(#[from] DbError),
+}
+
+/// Credit balance for the current month (GET /api/admin/prover-budget)
+#[derive(Debug, Clone, Serialize)]
+pub struct BudgetBalance {
+    pub period_start: DateTime<Utc>,
+    pub resets_at: DateTime<Utc>,
+    /// None = no monthly budget configured
+    pub monthly_credits: Option<i64>,
+    pub credits_used: i64,
+    pub executions: i64,
+    pub proofs: i64,
+    /// Latest balance Axiom reported this month
+    pub reported_remaining: Option<i64>,
+    pub reported_at: Option<DateTime<Utc>>,
+    /// Lower of the budget left and the reported balance (None = unlimited)
+    pub remaining: Option<i64>,
+    pub execution_credits: i64,
+    pub proof_credits: i64,
+}
+
+pub struct ProverBudget {
+    db: Arc<Database>,
+    monthly_credits: Option<i64>,
+    execution_credits: i64,
+    proof_credits: i64,
+    remaining_header: Option<HeaderName>,
+}
+
+impl ProverBudget {
+    pub fn new(db: Arc<Database>, monthly_credits: Option<i64>, execution_credits: i64, proof_credits: i64) -> Self {
+        Self { db, monthly_credits, execution_credits, proof_credits, remaining_header: None }
+    }
+
+    /// Read Axiom's remaining balance from `header` of submit responses
+    pub fn with_remaining_header(mut self, header: HeaderName) -> Self {
+        self.remaining_header = Some(header);
+        self
+    }
+
+    /// AXIOM_MONTHLY_CREDITS, AXIOM_EXECUTION_CREDITS, AXIOM_PROOF_CREDITS and AXIOM_CREDITS_HEADER
+    pub fn from_env(db: Arc<Database>) -> Result<Self, BudgetError> {
+        let credits = |name: &str| -> Result<Option<i64>, BudgetError> {
+            match std::env::var(name) {
+                Ok(value) => match value.trim().parse::<i64>() {
+                    Ok(credits) if credits >= 0 => Ok(Some(credits)),
+                    _ => Err(BudgetError::Config(format!("{} must be a non-negative integer, got {:?}", name, value))),
+                },
+                Err(_) => Ok(None),
+            }
+        };
+
+        let budget = Self::new(
+            db,
+            credits("AXIOM_MONTHLY_CREDITS")?,
+            credits("AXIOM_EXECUTION_CREDITS")?.unwrap_or(DEFAULT_EXECUTION_CREDITS),
+            credits("AXIOM_PROOF_CREDITS")?.unwrap_or(DEFAULT_PROOF_CREDITS),
+        );
+        match std::env::var("AXIOM_CREDITS_HEADER") {
+            Ok(name) if !name.trim().is_empty() => {
+                let header = HeaderName::from_bytes(name.trim().to_ascii_lowercase().as_bytes())
+                    .map_err(|_| BudgetError::Config(format!("AXIOM_CREDITS_HEADER is not a header name: {:?}", name)))?;
+                Ok(budget.with_remaining_header(header))
+            }
+            _ => Ok(budget),
+        }
+    }
+
+    pub fn monthly_credits(&self) -> Option<i64> {
+        self.monthly_credits
+    }
+
+    /// Credits a job of `kind` (TRANSCRIPT_EXECUTION | TRANSCRIPT_PROOF) costs
+    pub fn cost(&self, kind: &str) -> i64 {
+        if kind == TRANSCRIPT_EXECUTION {
+            self.execution_credits
+        } else {
+            self.proof_credits
+        }
+    }
+
+    /// Axiom's remaining balance from a response's headers, if configured and present
+    pub fn reported_remaining(&self, headers: &HeaderMap) -> Option<i64> {
+        let value = headers.get(self.remaining_header.as_ref()?)?.to_str().ok()?;
+        // Some gateways report fractional credits
+        value.trim().parse::<f64>().ok().map(|credits| credits.floor() as i64)
+    }
+
+    /// This month's usage and what is left of it
+    pub async fn balance(&self) -> Result<BudgetBalance, BudgetError> {
+        let (period_start, resets_at) = month_bounds(Utc::now());
+        let usage = self.db.get_prover_usage_summary(period_start).await?;
+
+        Ok(BudgetBalance {
+            period_start,
+            resets_at,
+            monthly_credits: self.monthly_credits,
+            credits_used: usage.credits_used,
+            executions: usage.executions,
+            proofs: usage.proofs,
+            remaining: remaining_credits(self.monthly_credits, usage.credits_used, usage.reported_remaining),
+            reported_remaining: usage.reported_remaining,
+            reported_at: usage.reported_at,
+            execution_credits: self.execution_credits,
+            proof_credits: self.proof_credits,
+        })
+    }
+
+    /// Refuse work needing `needed` credits the balance can't cover
+    pub async fn ensure_available(&self, needed: i64) -> Result<(), BudgetError> {
+        if self.monthly_credits.is_none() && self.remaining_header.is_none() {
+            return Ok(());
+        }
+        let balance = self.balance().await?;
+        match balance.remaining {
+            Some(remaining) if remaining < needed => Err(BudgetError::Exhausted {
+                needed,
+                remaining: remaining.max(0),
+                resets_at: balance.resets_at,
+            }),
+            _ => Ok(()),
+        }
+    }
+
+    /// Record a job Axiom accepted (failures to record are only logged)
+    pub async fn record(&self, kind: &'static str, trade_id: &str, axiom_id: &str, reported_remaining: Option<i64>) {
+        let usage = NewProverUsage {
+            trade_id: trade_id.to_string(),
+            kind,
+            axiom_id: axiom_id.to_string(),
+            credits: self.cost(kind),
+            reported_remaining,
+        };
+        if let Err(e) = self.db.record_prover_usage(&usage).await {
+            tracing::warn!("Failed to record {} credits for trade {}: {}", kind, trade_id, e);
+        }
+    }
+}
+
+/// Start of `now`'s month and of the next one (UTC)
+fn month_bounds(now: DateTime<Utc>) -> (DateTime<Utc>, DateTime<Utc>) {
+    let (year, month) = (now.year(), now.month());
+    let (next_year, next_month) = if month == 12 { (year + 1, 1) } else { (year, month + 1) };
+    let start = Utc.with_ymd_and_hms(year, month, 1, 0, 0, 0).unwrap();
+    let next = Utc.with_ymd_and_hms(next_year, next_month, 1, 0, 0, 0).unwrap();
+    (start, next)
+}
+
+/// Credits left: the lower of the budget left and Axiom's reported balance
+fn remaining_credits(monthly: Option<i64>, used: i64, reported: Option<i64>) -> Option<i64> {
+    let budget_left = monthly.map(|monthly| monthly - used);
+    match (budget_left, reported) {
+        (Some(left), Some(reported)) => Some(left.min(reported)),
+        (left, reported) => left.or(reported),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_month_bounds_and_remaining_credits() {
+        let (start, next) = month_bounds(Utc.with_ymd_and_hms(2026, 12, 17, 9, 30, 0).unwrap());
+        assert_eq!(start, Utc.with_ymd_and_hms(2026, 12, 1, 0, 0, 0).unwrap());
+        assert_eq!(next, Utc.with_ymd_and_hms(2027, 1, 1, 0, 0, 0).unwrap());
+
+        assert_eq!(remaining_credits(None, 40, None), None);
+        assert_eq!(remaining_credits(Some(100), 40, None), Some(60));
+        assert_eq!(remaining_credits(None, 40, Some(25)), Some(25));
+        assert_eq!(remaining_credits(Some(100), 40, Some(25)), Some(25));
+        assert_eq!(remaining_credits(Some(100), 120, Some(25)), Some(-20));
+    }
+}
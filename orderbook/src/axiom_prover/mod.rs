@@ -1,9 +1,22 @@
+pub mod budget;
+pub mod poller;
+pub mod transcript;
+
 use anyhow::{Result, anyhow};
 use reqwest;
 use serde::{Deserialize, Serialize};
+use std::sync::{Arc, Mutex};
 use std::time::Duration;
 use tokio::time::sleep;
 
+use crate::db::Database;
+use crate::db::prover_transcripts::{TRANSCRIPT_EXECUTION, TRANSCRIPT_PROOF};
+use crate::hexutil;
+use crate::proof_format::ProofFormat;
+use budget::ProverBudget;
+use poller::{PollBudgets, PollScheduler, UnknownStateBudget, DEFAULT_MAX_UNKNOWN_POLLS};
+use transcript::{input_metadata, Transcript};
+
 const AXIOM_API_BASE: &str = "https://api.axiom.xyz";
 
 /// Axiom Prover client
@@ -12,6 +25,14 @@ pub struct AxiomProver {
     config_id: String,
     program_id: String,
     client: reqwest::Client,
+    /// Where job transcripts are stored (None = not recorded)
+    transcripts: Option<Arc<Database>>,
+    /// Where credits spent are recorded (None = not tracked)
+    budget: Option<Arc<ProverBudget>>,
+    /// Balance Axiom reported with the last submit response
+    reported_remaining: Mutex<Option<i64>>,
+    /// Shared pacing of status polls (None = this client sleeps on its own)
+    poller: Option<Arc<PollScheduler>>,
 }
 
 impl AxiomProver {
@@ -21,24 +42,114 @@ impl AxiomProver {
             config_id,
             program_id,
             client: reqwest::Client::new(),
+            transcripts: None,
+            budget: None,
+            reported_remaining: Mutex::new(None),
+            poller: None,
+        }
+    }
+    
+    /// Record a transcript of every job in prover_transcripts
+    pub fn with_transcripts(mut self, db: Arc<Database>) -> Self {
+        self.transcripts = Some(db);
+        self
+    }
+    
+    /// Record the credits of every job Axiom accepts against `budget`
+    pub fn with_budget(mut self, budget: Arc<ProverBudget>) -> Self {
+        self.budget = Some(budget);
+        self
+    }
+    
+    /// Pace status polls through `poller`, shared with every other job
+    pub fn with_poll_scheduler(mut self, poller: Arc<PollScheduler>) -> Self {
+        self.poller = Some(poller);
+        self
+    }
+    
+    /// Wait for the next status poll, about `delay` from now
+    async fn wait_to_poll(&self, delay: Duration) {
+        match &self.poller {
+            Some(poller) => poller.wait(delay).await,
+            None => sleep(delay).await,
+        }
+    }
+    
+    /// Budget of consecutive unknown-state polls for one job
+    fn unknown_state_budget(&self) -> UnknownStateBudget {
+        UnknownStateBudget::new(self.poller.as_ref().map_or(DEFAULT_MAX_UNKNOWN_POLLS, |poller| poller.max_unknown_polls()))
+    }
+    
+    /// How long each kind of job is polled (the scheduler's current budgets, or the defaults)
+    fn poll_budgets(&self) -> PollBudgets {
+        self.poller.as_ref().map_or_else(PollBudgets::default, |poller| poller.budgets())
+    }
+    
+    /// Axiom rate limited a poll: hold all polls (or just this job's) for the Retry-After time
+    fn back_off_polls(&self, headers: &reqwest::header::HeaderMap, fallback: Duration) -> Duration {
+        let pause = headers
+            .get(reqwest::header::RETRY_AFTER)
+            .and_then(|value| value.to_str().ok())
+            .and_then(|value| value.trim().parse().ok())
+            .map(Duration::from_secs)
+            .unwrap_or(fallback);
+        tracing::warn!("⏳ Axiom rate limited a status poll, backing off {}s", pause.as_secs());
+        if let Some(poller) = &self.poller {
+            poller.back_off(pause);
+        }
+        pause
+    }
+    
+    /// Charge a job that reached Axiom, whatever its outcome
+    async fn record_usage(&self, kind: &'static str, trade_id: &str, transcript: &Transcript) {
+        let (Some(budget), Some(axiom_id)) = (&self.budget, transcript.axiom_id()) else {
+            return;
+        };
+        let reported_remaining = *self.reported_remaining.lock().unwrap();
+        budget.record(kind, trade_id, axiom_id, reported_remaining).await;
+    }
+    
+    /// Note the balance Axiom reported in a submit response's headers
+    fn note_reported_remaining(&self, headers: &reqwest::header::HeaderMap) {
+        if let Some(remaining) = self.budget.as_ref().and_then(|budget| budget.reported_remaining(headers)) {
+            *self.reported_remaining.lock().unwrap() = Some(remaining);
+        }
+    }
+    
+    /// Store a finished job's transcript (failures to store are only logged)
+    async fn save_transcript(&self, transcript: Transcript, error: Option<&anyhow::Error>) {
+        let Some(db) = &self.transcripts else {
+            return;
+        };
+        let record = transcript.finish(error.map(|e| e.to_string()));
+        if let Err(e) = db.record_prover_transcript(&record).await {
+            tracing::warn!("Failed to record {} transcript for trade {}: {}", record.kind, record.trade_id, e);
         }
     }
     
     /// Execute program (fast validation mode) - returns output hash only
     pub async fn execute_program(&self, trade_id: &str, input_streams: Vec<String>) -> Result<Vec<u8>> {
+        let mut transcript = Transcript::new(TRANSCRIPT_EXECUTION, trade_id, &self.program_id, &self.api_key);
+        let result = self.run_execution(trade_id, input_streams, &mut transcript).await;
+        self.record_usage(TRANSCRIPT_EXECUTION, trade_id, &transcript).await;
+        self.save_transcript(transcript, result.as_ref().err()).await;
+        result
+    }
+    
+    async fn run_execution(&self, trade_id: &str, input_streams: Vec<String>, transcript: &mut Transcript) -> Result<Vec<u8>> {
         tracing::info!("⚡ [{}] Starting Axiom program execution (validation mode)", trade_id);
         tracing::info!("📋 [{}] Input streams count: {}", trade_id, input_streams.len());
         
         // Step 1: Submit execution request
-        let execution_id = self.submit_execution_request(input_streams).await?;
+        let execution_id = self.submit_execution_request(input_streams, transcript).await?;
         tracing::info!("📤 [{}] Execution request submitted, execution_id: {}", trade_id, execution_id);
         
         // Step 2: Poll for completion
-        self.poll_execution_status(&execution_id).await?;
+        self.poll_execution_status(&execution_id, transcript).await?;
         tracing::info!("✅ [{}] Execution completed: {}", trade_id, execution_id);
         
         // Step 3: Get execution result
-        let result = self.get_execution_result(&execution_id).await?;
+        let result = self.get_execution_result(&execution_id, transcript).await?;
         
         // Log the full response for debugging
         tracing::debug!("📊 [{}] Full execution result: {}", trade_id, serde_json::to_string_pretty(&result).unwrap_or_else(|_| "Failed to serialize".to_string()));
@@ -58,7 +169,7 @@ impl AxiomProver {
         // public_values can be either a hex string or an array of numbers
         let public_values = if let Some(hex_str) = result["public_values"].as_str() {
             // It's a hex string
-            hex::decode(hex_str.trim_start_matches("0x"))?
+            hexutil::decode(hex_str)?
         } else if let Some(array) = result["public_values"].as_array() {
             // It's an array of numbers (bytes)
             array.iter()
@@ -81,30 +192,52 @@ impl AxiomProver {
     }
     
     /// Generate EVM proof - orchestrates the full flow
-    pub async fn generate_evm_proof(&self, trade_id: &str, input_streams: Vec<String>) -> Result<GeneratedProof> {
+    /// The proof must have the component sizes of `format` (the verifier's).
+    pub async fn generate_evm_proof(&self, trade_id: &str, input_streams: Vec<String>, format: &ProofFormat) -> Result<GeneratedProof> {
+        let mut transcript = Transcript::new(TRANSCRIPT_PROOF, trade_id, &self.program_id, &self.api_key);
+        let result = self.run_evm_proof(trade_id, input_streams, format, &mut transcript).await;
+        self.record_usage(TRANSCRIPT_PROOF, trade_id, &transcript).await;
+        self.save_transcript(transcript, result.as_ref().err()).await;
+        result
+    }
+    
+    async fn run_evm_proof(
+        &self,
+        trade_id: &str,
+        input_streams: Vec<String>,
+        format: &ProofFormat,
+        transcript: &mut Transcript,
+    ) -> Result<GeneratedProof> {
         tracing::info!("🚀 [{}] Starting Axiom EVM proof generation", trade_id);
         tracing::info!("📋 [{}] Input streams count: {}", trade_id, input_streams.len());
         
         // Step 1: Submit proof request
-        let proof_id = self.submit_proof_request(input_streams).await?;
+        let proof_id = self.submit_proof_request(input_streams, transcript).await?;
         tracing::info!("📤 [{}] Proof request submitted, proof_id: {}", trade_id, proof_id);
         
         // Step 2: Poll for completion
-        self.poll_proof_status(&proof_id).await?;
+        self.poll_proof_status(&proof_id, transcript).await?;
         tracing::info!("✅ [{}] Proof generation completed: {}", trade_id, proof_id);
         
         // Step 3: Download proof
-        let evm_proof = self.download_evm_proof(&proof_id).await?;
+        let evm_proof = self.download_evm_proof(&proof_id, transcript).await?;
         tracing::info!("📥 [{}] Proof downloaded", trade_id);
         
         // Step 4: Parse into GeneratedProof
-        let generated_proof = parse_evm_proof(proof_id, evm_proof)?;
+        let generated_proof = parse_evm_proof(proof_id, evm_proof, format)?;
         
         Ok(generated_proof)
     }
     
     /// Submit a proof generation request to Axiom
-    async fn submit_proof_request(&self, input_streams: Vec<String>) -> Result<String> {
+    async fn submit_proof_request(&self, input_streams: Vec<String>, transcript: &mut Transcript) -> Result<String> {
+        // Both program_id AND proof_type must be query parameters!
+        let query = [
+            ("program_id", self.program_id.as_str()),
+            ("proof_type", "evm"),  // CRITICAL: Must be in query params, not body!
+        ];
+        transcript.request("POST", "/v1/proofs".to_string(), Some(input_metadata(&input_streams, &query)));
+        
         // Request body only contains input - NO proof_type here
         let request_body = serde_json::json!({
             "input": input_streams,  // Direct list
@@ -112,37 +245,40 @@ impl AxiomProver {
         
         let response = self.client
             .post(format!("{}/v1/proofs", AXIOM_API_BASE))
-            // Both program_id AND proof_type must be query parameters!
-            .query(&[
-                ("program_id", self.program_id.as_str()),
-                ("proof_type", "evm"),  // CRITICAL: Must be in query params, not body!
-            ])
+            .query(&query)
             .header("Axiom-API-Key", &self.api_key)
             .header("Content-Type", "application/json")
             .json(&request_body)
             .send()
             .await?;
         
-        if !response.status().is_success() {
-            let status = response.status();
-            let error_text = response.text().await?;
-            return Err(anyhow!("Failed to submit proof request ({}): {}", status, error_text));
+        let status = response.status();
+        self.note_reported_remaining(response.headers());
+        let response_text = response.text().await?;
+        transcript.response(status.as_u16(), &response_text);
+        if !status.is_success() {
+            return Err(anyhow!("Failed to submit proof request ({}): {}", status, response_text));
         }
         
         // Log the response body for debugging
-        let response_text = response.text().await?;
         tracing::info!("Axiom API response: {}", response_text);
         
         let submit_response: ProofSubmitResponse = serde_json::from_str(&response_text)
             .map_err(|e| anyhow!("Failed to parse Axiom response: {}. Response: {}", e, response_text))?;
+        transcript.set_axiom_id(&submit_response.id);
         Ok(submit_response.id)  // Use "id" field
     }
     
-    /// Poll proof status until completion or timeout
-    async fn poll_proof_status(&self, proof_id: &str) -> Result<()> {
-        let max_attempts = 120; // 120 attempts * 10 seconds = 20 minutes max
+    /// Poll proof status until completion, timeout or too many unknown states in a row
+    /// Only the terminal poll and unknown-state polls are recorded in the transcript
+    async fn poll_proof_status(&self, proof_id: &str, transcript: &mut Transcript) -> Result<()> {
+        let budget = self.poll_budgets().evm_proof;
+        let max_attempts = budget.max_attempts;
         let mut attempt = 0;
-        let mut delay_secs = 10;
+        let mut in_progress = 0;
+        let mut delay = budget.delay(1);
+        let mut wait = Duration::ZERO;
+        let mut unknown = self.unknown_state_budget();
         
         loop {
             attempt += 1;
@@ -150,21 +286,32 @@ impl AxiomProver {
                 return Err(anyhow!("Proof generation timed out after {} attempts", max_attempts));
             }
             
-            // Poll status
+            // Poll status when the scheduler grants this job's turn
+            self.wait_to_poll(wait).await;
+            let path = format!("/v1/proofs/{}", proof_id);
             let response = self.client
-                .get(format!("{}/v1/proofs/{}", AXIOM_API_BASE, proof_id))
+                .get(format!("{}{}", AXIOM_API_BASE, path))
                 .header("Axiom-API-Key", &self.api_key)
                 .send()
                 .await?;
             
-            if !response.status().is_success() {
-                let status = response.status();
-                let error_text = response.text().await?;
-                return Err(anyhow!("Failed to poll proof status ({}): {}", status, error_text));
+            let status = response.status();
+            if status == reqwest::StatusCode::TOO_MANY_REQUESTS {
+                wait = self.back_off_polls(response.headers(), delay);
+                continue;
+            }
+            let response_text = response.text().await?;
+            let record = |transcript: &mut Transcript| {
+                transcript.request("GET", path.clone(), None);
+                transcript.response(status.as_u16(), &response_text);
+                transcript.polls(attempt);
+            };
+            if !status.is_success() {
+                record(transcript);
+                return Err(anyhow!("Failed to poll proof status ({}): {}", status, response_text));
             }
             
             // Log response for debugging
-            let response_text = response.text().await?;
             tracing::info!("📊 Status poll response: {}", response_text);
             
             let status_response: ProofStatusResponse = serde_json::from_str(&response_text)
@@ -175,47 +322,56 @@ impl AxiomProver {
             match status_response.state.as_str() {
                 // According to Axiom API docs, the terminal success state is "Succeeded"
                 "Succeeded" => {
+                    record(transcript);
                     tracing::info!("✅ Proof completed after {} attempts", attempt);
                     return Ok(());
                 }
                 "Failed" => {
+                    record(transcript);
                     let error_msg = status_response.error_message.unwrap_or_else(|| "Unknown error".to_string());
                     return Err(anyhow!("Proof generation failed: {}", error_msg));
                 }
                 // Valid in-progress states from Axiom API
                 "Queued" | "Executing" | "Executed" | "AppProving" | "AppProvingDone" | "PostProcessing" => {
                     tracing::info!("⏳ Proof status: {} (attempt {}/{})", status_response.state, attempt, max_attempts);
-                    sleep(Duration::from_secs(delay_secs)).await;
+                    unknown.known();
                     
-                    // Exponential backoff (cap at 30 seconds)
-                    if delay_secs < 30 {
-                        delay_secs = (delay_secs * 3 / 2).min(30);
-                    }
+                    // Backoff per the budget (base delay, growing up to its cap)
+                    in_progress += 1;
+                    delay = budget.delay(in_progress);
+                    wait = delay;
                 }
-                _ => {
-                    tracing::warn!("Unknown proof status: {}", status_response.state);
-                    sleep(Duration::from_secs(delay_secs)).await;
+                state => {
+                    tracing::warn!("⚠️  Unknown proof status: {} (attempt {}/{})", state, attempt, max_attempts);
+                    record(transcript);
+                    transcript.unexpected_state(state);
+                    unknown.unknown(TRANSCRIPT_PROOF, proof_id, state)?;
+                    wait = delay;
                 }
             }
         }
     }
     
     /// Download the completed EVM proof
-    async fn download_evm_proof(&self, proof_id: &str) -> Result<EvmProof> {
+    async fn download_evm_proof(&self, proof_id: &str, transcript: &mut Transcript) -> Result<EvmProof> {
         // According to Axiom API docs: GET /v1/proofs/{proof_id}/proof/{proof_type}
+        let path = format!("/v1/proofs/{}/proof/evm", proof_id);
+        transcript.request("GET", path.clone(), None);
         let response = self.client
-            .get(format!("{}/v1/proofs/{}/proof/evm", AXIOM_API_BASE, proof_id))
+            .get(format!("{}{}", AXIOM_API_BASE, path))
             .header("Axiom-API-Key", &self.api_key)
             .send()
             .await?;
         
-        if !response.status().is_success() {
-            let status = response.status();
-            let error_text = response.text().await?;
-            return Err(anyhow!("Failed to download EVM proof ({}): {}", status, error_text));
+        let status = response.status();
+        let response_text = response.text().await?;
+        transcript.response(status.as_u16(), &response_text);
+        if !status.is_success() {
+            return Err(anyhow!("Failed to download EVM proof ({}): {}", status, response_text));
         }
         
-        let evm_proof: EvmProof = response.json().await?;
+        let evm_proof: EvmProof = serde_json::from_str(&response_text)
+            .map_err(|e| anyhow!("Failed to parse EVM proof: {}", e))?;
         Ok(evm_proof)
     }
 }
@@ -242,53 +398,42 @@ pub struct EvmProof {
     pub version: String,
     pub app_exe_commit: String,      // 32 bytes hex (without 0x)
     pub app_vm_commit: String,       // 32 bytes hex (without 0x)
-    pub user_public_values: String,  // 32 bytes hex (without 0x)
+    pub user_public_values: String,  // hex (without 0x), sizes per ProofFormat
     pub proof_data: ProofData,
 }
 
 #[derive(Debug, Deserialize)]
 pub struct ProofData {
-    pub accumulator: String,  // 384 bytes hex (without 0x)
-    pub proof: String,        // 1376 bytes hex (without 0x)
+    pub accumulator: String,  // hex (without 0x)
+    pub proof: String,        // hex (without 0x)
 }
 
 /// High-level struct for proof generation result
 #[derive(Debug)]
 pub struct GeneratedProof {
     pub proof_id: String,
-    pub user_public_values: Vec<u8>,  // 32 bytes
-    pub accumulator: Vec<u8>,          // 384 bytes
-    pub proof_data: Vec<u8>,           // 1376 bytes
+    pub user_public_values: Vec<u8>,  // Sizes per proof_format
+    pub accumulator: Vec<u8>,
+    pub proof_data: Vec<u8>,
     pub app_exe_commit: Vec<u8>,       // 32 bytes
     pub app_vm_commit: Vec<u8>,        // 32 bytes
     pub full_json: serde_json::Value,  // Full proof JSON
+    pub proof_format: String,          // ProofFormat version the components were checked against
 }
 
 /// Parse EVM proof into format ready for smart contract submission
-fn parse_evm_proof(proof_id: String, evm_proof: EvmProof) -> Result<GeneratedProof> {
-    // Helper to decode hex string (with or without 0x prefix)
-    fn decode_hex(s: &str) -> Result<Vec<u8>> {
-        let s = s.strip_prefix("0x").unwrap_or(s);
-        hex::decode(s).map_err(|e| anyhow!("Failed to decode hex: {}", e))
-    }
-    
-    // Decode all fields
-    let user_public_values = decode_hex(&evm_proof.user_public_values)?;
-    let accumulator = decode_hex(&evm_proof.proof_data.accumulator)?;
-    let proof_data = decode_hex(&evm_proof.proof_data.proof)?;
-    let app_exe_commit = decode_hex(&evm_proof.app_exe_commit)?;
-    let app_vm_commit = decode_hex(&evm_proof.app_vm_commit)?;
+/// (also checks proofs buyers generated themselves, see POST /api/trades/:trade_id/proof)
+pub fn parse_evm_proof(proof_id: String, evm_proof: EvmProof, format: &ProofFormat) -> Result<GeneratedProof> {
+    // Decode all fields (with or without 0x prefix)
+    let decode_hex = |name: &str, s: &str| hexutil::decode(s).map_err(|e| anyhow!("Failed to decode {}: {}", name, e));
+    let user_public_values = decode_hex("user_public_values", &evm_proof.user_public_values)?;
+    let accumulator = decode_hex("accumulator", &evm_proof.proof_data.accumulator)?;
+    let proof_data = decode_hex("proof", &evm_proof.proof_data.proof)?;
+    let app_exe_commit = decode_hex("app_exe_commit", &evm_proof.app_exe_commit)?;
+    let app_vm_commit = decode_hex("app_vm_commit", &evm_proof.app_vm_commit)?;
     
     // Validate lengths
-    if user_public_values.len() != 32 {
-        return Err(anyhow!("Invalid user_public_values length: expected 32, got {}", user_public_values.len()));
-    }
-    if accumulator.len() != 384 {
-        return Err(anyhow!("Invalid accumulator length: expected 384, got {}", accumulator.len()));
-    }
-    if proof_data.len() != 1376 {
-        return Err(anyhow!("Invalid proof length: expected 1376, got {}", proof_data.len()));
-    }
+    format.validate(&user_public_values, &accumulator, &proof_data)?;
     if app_exe_commit.len() != 32 {
         return Err(anyhow!("Invalid app_exe_commit length: expected 32, got {}", app_exe_commit.len()));
     }
@@ -316,6 +461,7 @@ fn parse_evm_proof(proof_id: String, evm_proof: EvmProof) -> Result<GeneratedPro
         app_exe_commit,
         app_vm_commit,
         full_json,
+        proof_format: format.version.clone(),
     })
 }
 
@@ -325,30 +471,34 @@ fn parse_evm_proof(proof_id: String, evm_proof: EvmProof) -> Result<GeneratedPro
 
 impl AxiomProver {
     /// Submit an execution request to Axiom (fast validation mode)
-    async fn submit_execution_request(&self, input_streams: Vec<String>) -> Result<String> {
+    async fn submit_execution_request(&self, input_streams: Vec<String>, transcript: &mut Transcript) -> Result<String> {
+        let query = [
+            ("program_id", self.program_id.as_str()),
+            ("mode", "pure"),  // pure mode = only public values
+        ];
+        transcript.request("POST", "/v1/executions".to_string(), Some(input_metadata(&input_streams, &query)));
+        
         let request_body = serde_json::json!({
             "input": input_streams,
         });
         
         let response = self.client
             .post(format!("{}/v1/executions", AXIOM_API_BASE))
-            .query(&[
-                ("program_id", self.program_id.as_str()),
-                ("mode", "pure"),  // pure mode = only public values
-            ])
+            .query(&query)
             .header("Axiom-API-Key", &self.api_key)
             .header("Content-Type", "application/json")
             .json(&request_body)
             .send()
             .await?;
         
-        if !response.status().is_success() {
-            let status = response.status();
-            let error_text = response.text().await?;
-            return Err(anyhow!("Failed to submit execution request ({}): {}", status, error_text));
+        let status = response.status();
+        self.note_reported_remaining(response.headers());
+        let response_text = response.text().await?;
+        transcript.response(status.as_u16(), &response_text);
+        if !status.is_success() {
+            return Err(anyhow!("Failed to submit execution request ({}): {}", status, response_text));
         }
         
-        let response_text = response.text().await?;
         tracing::debug!("Axiom Execution API response: {}", response_text);
         
         let submit_response: serde_json::Value = serde_json::from_str(&response_text)
@@ -357,15 +507,21 @@ impl AxiomProver {
         let execution_id = submit_response["id"]
             .as_str()
             .ok_or_else(|| anyhow!("Missing 'id' field in execution response"))?;
+        transcript.set_axiom_id(execution_id);
         
         Ok(execution_id.to_string())
     }
     
-    /// Poll execution status until completion or timeout
-    async fn poll_execution_status(&self, execution_id: &str) -> Result<()> {
-        let max_attempts = 60; // 60 attempts * 10 seconds = 10 minutes max
+    /// Poll execution status until completion, timeout or too many unknown states in a row
+    /// Only the terminal poll and unknown-state polls are recorded in the transcript
+    async fn poll_execution_status(&self, execution_id: &str, transcript: &mut Transcript) -> Result<()> {
+        let budget = self.poll_budgets().execute;
+        let max_attempts = budget.max_attempts;
         let mut attempt = 0;
-        let mut delay_secs = 10;
+        let mut in_progress = 0;
+        let mut delay = budget.delay(1);
+        let mut wait = Duration::ZERO;
+        let mut unknown = self.unknown_state_budget();
         
         loop {
             attempt += 1;
@@ -373,19 +529,30 @@ impl AxiomProver {
                 return Err(anyhow!("Execution timed out after {} attempts", max_attempts));
             }
             
+            self.wait_to_poll(wait).await;
+            let path = format!("/v1/executions/{}", execution_id);
             let response = self.client
-                .get(format!("{}/v1/executions/{}", AXIOM_API_BASE, execution_id))
+                .get(format!("{}{}", AXIOM_API_BASE, path))
                 .header("Axiom-API-Key", &self.api_key)
                 .send()
                 .await?;
             
-            if !response.status().is_success() {
-                let status = response.status();
-                let error_text = response.text().await?;
-                return Err(anyhow!("Failed to poll execution status ({}): {}", status, error_text));
+            let http_status = response.status();
+            if http_status == reqwest::StatusCode::TOO_MANY_REQUESTS {
+                wait = self.back_off_polls(response.headers(), delay);
+                continue;
             }
-            
             let response_text = response.text().await?;
+            let record = |transcript: &mut Transcript| {
+                transcript.request("GET", path.clone(), None);
+                transcript.response(http_status.as_u16(), &response_text);
+                transcript.polls(attempt);
+            };
+            if !http_status.is_success() {
+                record(transcript);
+                return Err(anyhow!("Failed to poll execution status ({}): {}", http_status, response_text));
+            }
+            
             let status_response: serde_json::Value = serde_json::from_str(&response_text)
                 .map_err(|e| anyhow!("Failed to parse execution status: {}. Response: {}", e, response_text))?;
             
@@ -397,10 +564,12 @@ impl AxiomProver {
             
             match status {
                 "Succeeded" => {
+                    record(transcript);
                     tracing::info!("✅ Execution completed after {} attempts", attempt);
                     return Ok(());
                 }
                 "Failed" => {
+                    record(transcript);
                     let error_msg = status_response["error_message"]
                         .as_str()
                         .unwrap_or("Unknown error");
@@ -409,35 +578,120 @@ impl AxiomProver {
                 // In-progress states
                 "Queued" | "Executing" | "Executed" | "Running" | "Pending" => {
                     tracing::info!("⏳ Execution status: {} (attempt {}/{})", status, attempt, max_attempts);
-                    sleep(Duration::from_secs(delay_secs)).await;
+                    unknown.known();
                     
-                    // Exponential backoff (cap at 30 seconds)
-                    if delay_secs < 30 {
-                        delay_secs = (delay_secs * 3 / 2).min(30);
-                    }
+                    // Backoff per the budget (base delay, growing up to its cap)
+                    in_progress += 1;
+                    delay = budget.delay(in_progress);
+                    wait = delay;
                 }
                 _ => {
                     tracing::warn!("⚠️  Unknown execution status: {} - Full response: {}", status, response_text);
-                    sleep(Duration::from_secs(delay_secs)).await;
+                    record(transcript);
+                    transcript.unexpected_state(status);
+                    unknown.unknown(TRANSCRIPT_EXECUTION, execution_id, status)?;
+                    wait = delay;
                 }
             }
         }
     }
     
     /// Get execution result (includes public_values)
-    async fn get_execution_result(&self, execution_id: &str) -> Result<serde_json::Value> {
+    async fn get_execution_result(&self, execution_id: &str, transcript: &mut Transcript) -> Result<serde_json::Value> {
+        let path = format!("/v1/executions/{}", execution_id);
+        transcript.request("GET", path.clone(), None);
         let response = self.client
-            .get(format!("{}/v1/executions/{}", AXIOM_API_BASE, execution_id))
+            .get(format!("{}{}", AXIOM_API_BASE, path))
             .header("Axiom-API-Key", &self.api_key)
             .send()
             .await?;
         
-        if !response.status().is_success() {
-            let status = response.status();
-            let error_text = response.text().await?;
-            return Err(anyhow!("Failed to get execution result ({}): {}", status, error_text));
+        let status = response.status();
+        let response_text = response.text().await?;
+        transcript.response(status.as_u16(), &response_text);
+        if !status.is_success() {
+            return Err(anyhow!("Failed to get execution result ({}): {}", status, response_text));
         }
         
-        Ok(response.json().await?)
+        Ok(serde_json::from_str(&response_text)?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use proptest::prelude::*;
+
+    fn format() -> ProofFormat {
+        ProofFormat {
+            version: "test".to_string(),
+            user_public_values_len: 32,
+            accumulator_len: 8,
+            proof_data_len: 16,
+            verifier: None,
+        }
+    }
+
+    fn evm_proof(fields: [String; 5]) -> EvmProof {
+        let [app_exe_commit, app_vm_commit, user_public_values, accumulator, proof] = fields;
+        EvmProof {
+            version: "v1.4".to_string(),
+            app_exe_commit,
+            app_vm_commit,
+            user_public_values,
+            proof_data: ProofData { accumulator, proof },
+        }
+    }
+
+    /// Hex of `bytes` as a prover or buyer might write it: optional prefix, any case, padding
+    fn written(bytes: &[u8], prefix: bool, upper: bool, pad: &str) -> String {
+        let digits = if upper { hex::encode_upper(bytes) } else { hex::encode(bytes) };
+        format!("{}{}{}{}", pad, if prefix { "0x" } else { "" }, digits, pad)
+    }
+
+    proptest! {
+        #[test]
+        fn prop_components_decode_however_written(
+            commit in any::<[u8; 32]>(),
+            public_values in any::<[u8; 32]>(),
+            accumulator in proptest::collection::vec(any::<u8>(), 8),
+            proof in proptest::collection::vec(any::<u8>(), 16),
+            prefix in any::<bool>(),
+            upper in any::<bool>(),
+            pad in "[ \\n]{0,2}",
+        ) {
+            let fields = [&commit[..], &commit[..], &public_values[..], &accumulator[..], &proof[..]]
+                .map(|bytes| written(bytes, prefix, upper, &pad));
+            let parsed = parse_evm_proof("id".to_string(), evm_proof(fields.clone()), &format()).unwrap();
+            prop_assert_eq!(parsed.app_exe_commit, commit.to_vec());
+            prop_assert_eq!(parsed.user_public_values, public_values.to_vec());
+            prop_assert_eq!(parsed.accumulator, accumulator);
+            prop_assert_eq!(parsed.proof_data, proof);
+            // The stored JSON keeps the fields as uploaded
+            prop_assert_eq!(parsed.full_json["proof_data"]["proof"].as_str(), Some(fields[4].as_str()));
+        }
+
+        #[test]
+        fn prop_wrong_sizes_are_rejected(field in 0usize..5, extra in 1usize..4, shorter in any::<bool>()) {
+            let lens = [32, 32, 32, 8, 16];
+            let fields = std::array::from_fn(|i| {
+                let len = match (i == field, shorter) {
+                    (false, _) => lens[i],
+                    (true, false) => lens[i] + extra,
+                    (true, true) => lens[i] - extra,
+                };
+                hex::encode(vec![0xab; len])
+            });
+            prop_assert!(parse_evm_proof("id".to_string(), evm_proof(fields), &format()).is_err());
+        }
+
+        #[test]
+        fn prop_arbitrary_fields_never_panic(fields in proptest::array::uniform5("(0[xX]){0,2}[0-9a-fA-Fg ]{0,70}")) {
+            // Whatever is accepted has exactly the format's sizes
+            if let Ok(parsed) = parse_evm_proof("id".to_string(), evm_proof(fields), &format()) {
+                prop_assert_eq!(parsed.app_vm_commit.len(), 32);
+                prop_assert!(format().validate(&parsed.user_public_values, &parsed.accumulator, &parsed.proof_data).is_ok());
+            }
+        }
     }
 }
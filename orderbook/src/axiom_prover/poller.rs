@@ -0,0 +1,388 @@
+// Shared scheduler for Axiom status polls
+// Every execution and proof job polls Axiom until its job finishes, each with its own
+// 10-30s backoff; with many jobs in flight those polls add up and bunch together. Jobs
+// instead ask the scheduler for their next poll: one task orders the requests by when
+// each job wants to poll (its backoff plus jitter, so jobs started together drift apart)
+// and grants them no faster than the global cap. When Axiom answers a poll with 429,
+// the whole scheduler pauses for the Retry-After time rather than every job retrying.
+// A job whose polls keep answering with a state this client doesn't know (an API change,
+// or a job stuck in some internal state) used to be polled as in progress until its
+// timeout; after AXIOM_MAX_UNKNOWN_POLLS such polls in a row it fails with
+// UnexpectedStates, and the states seen are kept in the job's transcript.
+// How long a job is polled was hardcoded (executions 60 polls, proofs 120, 10s growing
+// to 30s); each operation now has a PollBudget, set from the environment and adjustable
+// at runtime with POST /api/admin/runtime-config (until the next restart). A changed
+// budget applies to jobs started after the change.
+//
+// Configuration (env):
+// - AXIOM_MAX_POLLS_PER_MINUTE: status polls across all jobs (default 60)
+// - AXIOM_POLL_JITTER_PCT: random spread applied to each job's delay, +/- percent (default 20)
+// - AXIOM_MAX_UNKNOWN_POLLS: consecutive polls in an unknown state before a job fails (default 6)
+// - AXIOM_<EXECUTE|PROOF>_MAX_ATTEMPTS: polls before a job times out (default 60 / 120)
+// - AXIOM_<EXECUTE|PROOF>_BASE_DELAY_SECS: delay after the first in-progress poll (default 10)
+// - AXIOM_<EXECUTE|PROOF>_BACKOFF_FACTOR: delay growth per in-progress poll (default 1.5)
+// - AXIOM_<EXECUTE|PROOF>_MAX_DELAY_SECS: cap on the delay (default 30)
+
+use serde::{Deserialize, Serialize};
+use std::cmp::Reverse;
+use std::collections::{BinaryHeap, HashMap};
+use std::sync::{Arc, RwLock};
+use std::time::Duration;
+use tokio::sync::{mpsc, oneshot};
+use tokio::time::{sleep, sleep_until, Instant};
+
+pub const DEFAULT_MAX_POLLS_PER_MINUTE: u32 = 60;
+pub const DEFAULT_POLL_JITTER_PCT: u32 = 20;
+pub const DEFAULT_MAX_UNKNOWN_POLLS: u32 = 6;
+
+/// How long one kind of job is polled before it times out
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct PollBudget {
+    pub max_attempts: u32,
+    pub base_delay_secs: u64,
+    /// Delay growth per in-progress poll (1.0 = fixed delay)
+    pub backoff_factor: f64,
+    pub max_delay_secs: u64,
+}
+
+impl PollBudget {
+    /// Executions: 60 polls, about 10 minutes
+    pub const EXECUTE: Self = Self { max_attempts: 60, base_delay_secs: 10, backoff_factor: 1.5, max_delay_secs: 30 };
+    /// EVM proofs: 120 polls, about 20 minutes
+    pub const EVM_PROOF: Self = Self { max_attempts: 120, base_delay_secs: 10, backoff_factor: 1.5, max_delay_secs: 30 };
+
+    /// Override `defaults` with AXIOM_<prefix>_* variables (defaults kept when unset or invalid)
+    fn from_env(prefix: &str, defaults: Self) -> Self {
+        let var = |name: &str| std::env::var(format!("AXIOM_{}_{}", prefix, name)).ok();
+        let budget = Self {
+            max_attempts: var("MAX_ATTEMPTS").and_then(|v| v.parse().ok()).unwrap_or(defaults.max_attempts),
+            base_delay_secs: var("BASE_DELAY_SECS").and_then(|v| v.parse().ok()).unwrap_or(defaults.base_delay_secs),
+            backoff_factor: var("BACKOFF_FACTOR").and_then(|v| v.parse().ok()).unwrap_or(defaults.backoff_factor),
+            max_delay_secs: var("MAX_DELAY_SECS").and_then(|v| v.parse().ok()).unwrap_or(defaults.max_delay_secs),
+        };
+        match budget.validate() {
+            Ok(()) => budget,
+            Err(e) => {
+                tracing::warn!("Ignoring AXIOM_{}_* poll settings: {}", prefix, e);
+                defaults
+            }
+        }
+    }
+
+    pub fn validate(&self) -> Result<(), String> {
+        if self.max_attempts == 0 {
+            return Err("max_attempts must be positive".to_string());
+        }
+        if self.base_delay_secs == 0 || self.base_delay_secs > self.max_delay_secs {
+            return Err("base_delay_secs must be positive and at most max_delay_secs".to_string());
+        }
+        if !self.backoff_factor.is_finite() || !(1.0..=10.0).contains(&self.backoff_factor) {
+            return Err("backoff_factor must be between 1 and 10".to_string());
+        }
+        Ok(())
+    }
+
+    /// Delay after the job's `in_progress`-th in-progress poll (1 = the first)
+    pub fn delay(&self, in_progress: u32) -> Duration {
+        let growth = self.backoff_factor.powi(in_progress.saturating_sub(1).min(64) as i32);
+        let secs = (self.base_delay_secs as f64 * growth).min(self.max_delay_secs as f64);
+        Duration::from_secs_f64(secs)
+    }
+}
+
+/// Poll budgets per operation
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct PollBudgets {
+    pub execute: PollBudget,
+    pub evm_proof: PollBudget,
+}
+
+impl Default for PollBudgets {
+    fn default() -> Self {
+        Self { execute: PollBudget::EXECUTE, evm_proof: PollBudget::EVM_PROOF }
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct PollConfig {
+    pub max_polls_per_minute: u32,
+    pub jitter_pct: u32,
+    pub max_unknown_polls: u32,
+    pub budgets: PollBudgets,
+}
+
+impl Default for PollConfig {
+    fn default() -> Self {
+        Self {
+            max_polls_per_minute: DEFAULT_MAX_POLLS_PER_MINUTE,
+            jitter_pct: DEFAULT_POLL_JITTER_PCT,
+            max_unknown_polls: DEFAULT_MAX_UNKNOWN_POLLS,
+            budgets: PollBudgets::default(),
+        }
+    }
+}
+
+impl PollConfig {
+    /// AXIOM_MAX_POLLS_PER_MINUTE, AXIOM_POLL_JITTER_PCT, AXIOM_MAX_UNKNOWN_POLLS and the
+    /// AXIOM_EXECUTE_* / AXIOM_PROOF_* budgets (defaults when unset or invalid)
+    pub fn from_env() -> Self {
+        let max_polls_per_minute = std::env::var("AXIOM_MAX_POLLS_PER_MINUTE")
+            .ok()
+            .and_then(|v| v.parse().ok())
+            .filter(|polls| *polls > 0)
+            .unwrap_or(DEFAULT_MAX_POLLS_PER_MINUTE);
+        let jitter_pct = std::env::var("AXIOM_POLL_JITTER_PCT")
+            .ok()
+            .and_then(|v| v.parse().ok())
+            .unwrap_or(DEFAULT_POLL_JITTER_PCT)
+            .min(100);
+        let max_unknown_polls = std::env::var("AXIOM_MAX_UNKNOWN_POLLS")
+            .ok()
+            .and_then(|v| v.parse().ok())
+            .filter(|polls| *polls > 0)
+            .unwrap_or(DEFAULT_MAX_UNKNOWN_POLLS);
+        let budgets = PollBudgets {
+            execute: PollBudget::from_env("EXECUTE", PollBudget::EXECUTE),
+            evm_proof: PollBudget::from_env("PROOF", PollBudget::EVM_PROOF),
+        };
+        Self { max_polls_per_minute, jitter_pct, max_unknown_polls, budgets }
+    }
+
+    /// Least time between two polls
+    fn min_interval(&self) -> Duration {
+        Duration::from_secs(60) / self.max_polls_per_minute.max(1)
+    }
+}
+
+enum Command {
+    /// A job wants to poll at `due`; `ready` fires when it may
+    Poll { due: Instant, ready: oneshot::Sender<()> },
+    /// Axiom is rate limiting: grant nothing for this long
+    Pause(Duration),
+}
+
+pub struct PollScheduler {
+    sender: mpsc::UnboundedSender<Command>,
+    /// The scheduler's settings, with the budgets as last set
+    config: RwLock<PollConfig>,
+}
+
+impl PollScheduler {
+    /// Spawn the scheduling loop
+    pub fn spawn(config: PollConfig) -> Arc<Self> {
+        let (sender, receiver) = mpsc::unbounded_channel();
+        tokio::spawn(run(config.clone(), receiver));
+        Arc::new(Self { sender, config: RwLock::new(config) })
+    }
+
+    /// Current settings
+    pub fn config(&self) -> PollConfig {
+        self.config.read().unwrap().clone()
+    }
+
+    /// Consecutive unknown-state polls a job may see before it fails
+    pub fn max_unknown_polls(&self) -> u32 {
+        self.config.read().unwrap().max_unknown_polls
+    }
+
+    /// Poll budgets for jobs starting now
+    pub fn budgets(&self) -> PollBudgets {
+        self.config.read().unwrap().budgets
+    }
+
+    /// Replace the poll budgets (jobs already polling keep theirs)
+    pub fn set_budgets(&self, budgets: PollBudgets) {
+        self.config.write().unwrap().budgets = budgets;
+    }
+
+    /// Wait until the job may poll again, about `delay` from now
+    pub async fn wait(&self, delay: Duration) {
+        let jitter_pct = self.config.read().unwrap().jitter_pct;
+        let delay = jittered(delay, jitter_pct, ethers::core::rand::random::<f64>());
+        let (ready, granted) = oneshot::channel();
+        let sent = self.sender.send(Command::Poll { due: Instant::now() + delay, ready });
+        if sent.is_err() || granted.await.is_err() {
+            // Scheduler gone: fall back to the job's own delay
+            sleep(delay).await;
+        }
+    }
+
+    /// Hold every job's polls for `duration` (Axiom answered 429)
+    pub fn back_off(&self, duration: Duration) {
+        let _ = self.sender.send(Command::Pause(duration));
+    }
+}
+
+/// A job kept reporting states this client doesn't know
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+#[error("Axiom {kind} {job_id} reported an unknown state {polls} polls in a row ({}) - giving up", .states.join(", "))]
+pub struct UnexpectedStates {
+    pub kind: &'static str,
+    pub job_id: String,
+    pub polls: u32,
+    /// Distinct unknown states seen, in order
+    pub states: Vec<String>,
+}
+
+/// Counts a job's consecutive polls in an unknown state
+#[derive(Debug)]
+pub struct UnknownStateBudget {
+    max_polls: u32,
+    streak: u32,
+    states: Vec<String>,
+}
+
+impl UnknownStateBudget {
+    pub fn new(max_polls: u32) -> Self {
+        Self { max_polls: max_polls.max(1), streak: 0, states: Vec::new() }
+    }
+
+    /// A poll answered with a known in-progress state
+    pub fn known(&mut self) {
+        self.streak = 0;
+    }
+
+    /// A poll answered with an unknown state; Err once the budget is used up
+    pub fn unknown(&mut self, kind: &'static str, job_id: &str, state: &str) -> Result<(), UnexpectedStates> {
+        self.streak += 1;
+        if !self.states.iter().any(|seen| seen == state) {
+            self.states.push(state.to_string());
+        }
+        if self.streak < self.max_polls {
+            return Ok(());
+        }
+        Err(UnexpectedStates {
+            kind,
+            job_id: job_id.to_string(),
+            polls: self.streak,
+            states: self.states.clone(),
+        })
+    }
+}
+
+/// Grant polls in due order, at most one per `min_interval`
+async fn run(config: PollConfig, mut receiver: mpsc::UnboundedReceiver<Command>) {
+    let min_interval = config.min_interval();
+    let mut queue: BinaryHeap<Reverse<(Instant, u64)>> = BinaryHeap::new();
+    let mut waiting: HashMap<u64, oneshot::Sender<()>> = HashMap::new();
+    let mut next_id = 0u64;
+    let mut next_slot = Instant::now();
+
+    loop {
+        let next_grant = queue.peek().map(|Reverse((due, _))| (*due).max(next_slot));
+        let idle = Instant::now() + Duration::from_secs(3600);
+
+        tokio::select! {
+            command = receiver.recv() => match command {
+                Some(Command::Poll { due, ready }) => {
+                    next_id += 1;
+                    queue.push(Reverse((due, next_id)));
+                    waiting.insert(next_id, ready);
+                }
+                Some(Command::Pause(duration)) => {
+                    tracing::warn!("⏸️  Axiom status polls paused for {}s", duration.as_secs());
+                    next_slot = next_slot.max(Instant::now() + duration);
+                }
+                None => break,
+            },
+            _ = sleep_until(next_grant.unwrap_or(idle)), if next_grant.is_some() => {
+                let Some(Reverse((_, id))) = queue.pop() else { continue };
+                // A job that gave up (request dropped) doesn't use up a slot
+                if let Some(ready) = waiting.remove(&id) {
+                    if ready.send(()).is_ok() {
+                        next_slot = Instant::now() + min_interval;
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// `delay` spread by up to +/- `jitter_pct` percent; `unit` is uniform in [0, 1)
+fn jittered(delay: Duration, jitter_pct: u32, unit: f64) -> Duration {
+    let spread = f64::from(jitter_pct.min(100)) / 100.0;
+    delay.mul_f64((1.0 + spread * (2.0 * unit - 1.0)).max(0.0))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_jittered_delay_stays_in_range() {
+        let secs = |delay: Duration| (delay.as_secs_f64() * 1000.0).round() / 1000.0;
+        let delay = Duration::from_secs(10);
+        assert_eq!(secs(jittered(delay, 20, 0.0)), 8.0);
+        assert_eq!(secs(jittered(delay, 20, 0.5)), 10.0);
+        assert!(jittered(delay, 20, 0.999) < Duration::from_secs(12));
+        assert_eq!(secs(jittered(delay, 0, 0.9)), 10.0);
+        assert_eq!(jittered(Duration::ZERO, 20, 0.9), Duration::ZERO);
+
+        let config = PollConfig { max_polls_per_minute: 120, jitter_pct: 20, ..PollConfig::default() };
+        assert_eq!(config.min_interval(), Duration::from_millis(500));
+    }
+
+    #[test]
+    fn test_poll_budget_delays_and_env_overrides() {
+        // The former hardcoded schedule: 10s growing by half up to 30s
+        let delays: Vec<f64> = (1..=5).map(|poll| PollBudget::EXECUTE.delay(poll).as_secs_f64()).collect();
+        assert_eq!(delays, [10.0, 15.0, 22.5, 30.0, 30.0]);
+        let fixed = PollBudget { backoff_factor: 1.0, ..PollBudget::EVM_PROOF };
+        assert_eq!(fixed.delay(50), Duration::from_secs(10));
+
+        std::env::set_var("AXIOM_TESTOP_MAX_ATTEMPTS", "240");
+        std::env::set_var("AXIOM_TESTOP_BACKOFF_FACTOR", "2");
+        let budget = PollBudget::from_env("TESTOP", PollBudget::EVM_PROOF);
+        assert_eq!(budget.max_attempts, 240);
+        assert_eq!(budget.backoff_factor, 2.0);
+        assert_eq!(budget.base_delay_secs, 10);
+
+        // An invalid combination keeps the defaults
+        std::env::set_var("AXIOM_BADOP_BASE_DELAY_SECS", "60");
+        assert_eq!(PollBudget::from_env("BADOP", PollBudget::EXECUTE), PollBudget::EXECUTE);
+        assert!(PollBudget { backoff_factor: 0.5, ..PollBudget::EXECUTE }.validate().is_err());
+        assert!(PollBudget { max_attempts: 0, ..PollBudget::EXECUTE }.validate().is_err());
+    }
+
+    #[test]
+    fn test_unknown_state_budget_counts_consecutive_polls() {
+        let mut budget = UnknownStateBudget::new(3);
+        assert!(budget.unknown("proof", "proof_1", "Paging").is_ok());
+        assert!(budget.unknown("proof", "proof_1", "Paging").is_ok());
+        // A known state in between starts the count over
+        budget.known();
+        assert!(budget.unknown("proof", "proof_1", "Migrating").is_ok());
+        assert!(budget.unknown("proof", "proof_1", "Paging").is_ok());
+        let exhausted = budget.unknown("proof", "proof_1", "Paging").unwrap_err();
+        assert_eq!(exhausted.polls, 3);
+        assert_eq!(exhausted.states, ["Paging", "Migrating"]);
+        assert!(exhausted.to_string().contains("(Paging, Migrating)"));
+    }
+
+    #[tokio::test]
+    async fn test_polls_are_spaced_by_the_global_cap() {
+        // 600 per minute: one poll every 100ms
+        let scheduler = PollScheduler::spawn(PollConfig { max_polls_per_minute: 600, jitter_pct: 0, ..PollConfig::default() });
+        let start = Instant::now();
+
+        let polls: Vec<_> = (0..3)
+            .map(|_| {
+                let scheduler = scheduler.clone();
+                tokio::spawn(async move {
+                    scheduler.wait(Duration::ZERO).await;
+                    Instant::now() - start
+                })
+            })
+            .collect();
+        let mut granted = Vec::new();
+        for poll in polls {
+            granted.push(poll.await.unwrap());
+        }
+        granted.sort();
+
+        // Three jobs due at once are granted one slot apart
+        assert!(granted[0] < Duration::from_millis(50));
+        assert!(granted[1] >= Duration::from_millis(100));
+        assert!(granted[2] >= Duration::from_millis(200));
+    }
+}
@@ -0,0 +1,174 @@
+// Transcript of one Axiom job (execution or proof)
+// Records each request's endpoint and metadata and the responses that ended a step:
+// the submit response, the terminal status poll (with the number of polls before it),
+// every poll that reported an unknown state, the download, and any error body. Input streams hold the receipt PDF, so only their
+// count and size are kept; the API key is redacted from everything recorded.
+
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+
+use crate::db::prover_transcripts::{NewProverTranscript, TRANSCRIPT_FAILED, TRANSCRIPT_SUCCEEDED};
+
+/// Longest response body kept per entry
+const MAX_BODY_LEN: usize = 8 * 1024;
+const REDACTED: &str = "[REDACTED]";
+
+#[derive(Debug, Clone, Serialize)]
+pub struct TranscriptEntry {
+    pub at: DateTime<Utc>,
+    pub method: &'static str,
+    pub path: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub request: Option<serde_json::Value>,
+    pub status: Option<u16>,
+    pub response: Option<String>,
+    /// Status polls it took to reach this response
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub polls: Option<u32>,
+    /// State this client doesn't know, reported by this poll
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub unexpected_state: Option<String>,
+}
+
+pub struct Transcript {
+    kind: &'static str,
+    trade_id: String,
+    program_id: String,
+    axiom_id: Option<String>,
+    started_at: DateTime<Utc>,
+    entries: Vec<TranscriptEntry>,
+    secret: String,
+}
+
+impl Transcript {
+    /// `secret` (the API key) is redacted from every recorded body
+    pub fn new(kind: &'static str, trade_id: &str, program_id: &str, secret: &str) -> Self {
+        Self {
+            kind,
+            trade_id: trade_id.to_string(),
+            program_id: program_id.to_string(),
+            axiom_id: None,
+            started_at: Utc::now(),
+            entries: Vec::new(),
+            secret: secret.to_string(),
+        }
+    }
+
+    /// Axiom's id for the job, once submitted
+    pub fn set_axiom_id(&mut self, axiom_id: &str) {
+        self.axiom_id = Some(axiom_id.to_string());
+    }
+
+    /// Axiom's id for the job (None if it was never submitted)
+    pub fn axiom_id(&self) -> Option<&str> {
+        self.axiom_id.as_deref()
+    }
+
+    /// Record a request; its response is recorded with `response`
+    pub fn request(&mut self, method: &'static str, path: String, request: Option<serde_json::Value>) {
+        self.entries.push(TranscriptEntry {
+            at: Utc::now(),
+            method,
+            path,
+            request,
+            status: None,
+            response: None,
+            polls: None,
+            unexpected_state: None,
+        });
+    }
+
+    /// Record the response to the last request
+    pub fn response(&mut self, status: u16, body: &str) {
+        let body = self.redact(body);
+        if let Some(entry) = self.entries.last_mut() {
+            entry.status = Some(status);
+            entry.response = Some(body);
+        }
+    }
+
+    /// Record the number of polls behind the last response
+    pub fn polls(&mut self, polls: u32) {
+        if let Some(entry) = self.entries.last_mut() {
+            entry.polls = Some(polls);
+        }
+    }
+
+    /// Flag the last response as reporting a state this client doesn't know
+    pub fn unexpected_state(&mut self, state: &str) {
+        if let Some(entry) = self.entries.last_mut() {
+            entry.unexpected_state = Some(state.to_string());
+        }
+    }
+
+    pub fn entries(&self) -> &[TranscriptEntry] {
+        &self.entries
+    }
+
+    fn redact(&self, text: &str) -> String {
+        let text = if self.secret.is_empty() { text.to_string() } else { text.replace(&self.secret, REDACTED) };
+        if text.len() <= MAX_BODY_LEN {
+            return text;
+        }
+        let mut end = MAX_BODY_LEN;
+        while !text.is_char_boundary(end) {
+            end -= 1;
+        }
+        format!("{}… ({} bytes truncated)", &text[..end], text.len() - end)
+    }
+
+    /// The record to store, given the job's error (None if it succeeded)
+    pub fn finish(self, error: Option<String>) -> NewProverTranscript {
+        let entries = serde_json::to_string(&self.entries).unwrap_or_else(|_| "[]".to_string());
+        NewProverTranscript {
+            status: if error.is_none() { TRANSCRIPT_SUCCEEDED } else { TRANSCRIPT_FAILED },
+            error: error.map(|e| self.redact(&e)),
+            trade_id: self.trade_id,
+            kind: self.kind,
+            program_id: self.program_id,
+            axiom_id: self.axiom_id,
+            entries,
+            started_at: self.started_at,
+        }
+    }
+}
+
+/// Metadata of a job's input streams (the streams themselves aren't recorded)
+pub fn input_metadata(input_streams: &[String], query: &[(&str, &str)]) -> serde_json::Value {
+    let query: serde_json::Map<String, serde_json::Value> =
+        query.iter().map(|(k, v)| (k.to_string(), serde_json::Value::from(*v))).collect();
+    serde_json::json!({
+        "query": query,
+        "input_streams": input_streams.len(),
+        "input_bytes": input_streams.iter().map(String::len).sum::<usize>(),
+        "headers": { "Axiom-API-Key": REDACTED },
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::prover_transcripts::TRANSCRIPT_PROOF;
+
+    #[test]
+    fn test_transcript_redacts_and_truncates() {
+        let mut transcript = Transcript::new(TRANSCRIPT_PROOF, "0xab", "prg_1", "sk_live_secret");
+        transcript.request("POST", "/v1/proofs".to_string(), Some(input_metadata(&["00ff".to_string()], &[("proof_type", "evm")])));
+        transcript.response(401, r#"{"error":"invalid key sk_live_secret"}"#);
+        transcript.set_axiom_id("proof_1");
+        transcript.request("GET", "/v1/proofs/proof_1".to_string(), None);
+        transcript.response(200, &"x".repeat(MAX_BODY_LEN + 10));
+        transcript.polls(3);
+
+        let entries = transcript.entries();
+        assert_eq!(entries[0].response.as_deref(), Some(r#"{"error":"invalid key [REDACTED]"}"#));
+        assert_eq!(entries[0].request.as_ref().unwrap()["input_bytes"], 4);
+        assert!(entries[1].response.as_deref().unwrap().ends_with("(10 bytes truncated)"));
+        assert_eq!(entries[1].polls, Some(3));
+
+        let record = transcript.finish(Some("failed with sk_live_secret".to_string()));
+        assert_eq!((record.status, record.axiom_id.as_deref()), (TRANSCRIPT_FAILED, Some("proof_1")));
+        assert_eq!(record.error.as_deref(), Some("failed with [REDACTED]"));
+        assert!(!record.entries.contains("sk_live_secret"));
+    }
+}
@@ -5,8 +5,11 @@ use tokio::time;
 use tracing::{error, info, warn};
 use tracing_subscriber;
 
-use zkalipay_orderbook::blockchain::client::EthereumClient;
-use zkalipay_orderbook::blockchain::types;
+use zkalipay_orderbook::blockchain::client::{EthereumClient, EthereumClientError};
+use zkalipay_orderbook::blockchain::signer::signers_from_env;
+use zkalipay_orderbook::blockchain::ids::TradeId;
+use zkalipay_orderbook::db::leader::{LeaderLock, LOCK_AUTO_CANCEL};
+use zkalipay_orderbook::db::relayer_spend::{NewRelayerSpend, ACTION_CANCEL_EXPIRED};
 use zkalipay_orderbook::db::Database;
 
 #[tokio::main]
@@ -27,9 +30,6 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
     let escrow_address = env::var("ESCROW_CONTRACT_ADDRESS")
         .expect("ESCROW_CONTRACT_ADDRESS must be set");
     
-    let relayer_private_key = env::var("RELAYER_PRIVATE_KEY")
-        .expect("RELAYER_PRIVATE_KEY must be set");
-
     // Hardcoded Base Sepolia configuration
     let rpc_url = "https://sepolia.base.org";
     let chain_id: u64 = 84532; // Base Sepolia Chain ID
@@ -46,9 +46,9 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
     // Initialize blockchain client
     info!("⛓️  Connecting to blockchain...");
     let blockchain_client = Arc::new(
-        EthereumClient::new(
+        EthereumClient::with_signers(
             &rpc_url,
-            &relayer_private_key,
+            signers_from_env(chain_id).await?,
             escrow_address,
             chain_id,
         )
@@ -63,9 +63,30 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
 
     info!("🚀 Auto-cancel service running. Checking for expired trades every 60 seconds...");
 
+    // Only one instance cancels at a time; the others retry the lock every tick
+    let mut leader: Option<LeaderLock> = None;
+
     loop {
         interval.tick().await;
 
+        let is_leader = match leader.as_mut() {
+            Some(lock) => lock.is_held().await,
+            None => false,
+        };
+        if !is_leader {
+            leader = match LeaderLock::try_acquire(db.pool(), "auto-cancel", LOCK_AUTO_CANCEL).await {
+                Ok(lock) => lock,
+                Err(e) => {
+                    warn!("⚠️  Auto-cancel leader election failed: {}", e);
+                    None
+                }
+            };
+            if leader.is_none() {
+                tracing::debug!("Another auto-cancel instance is leading - skipping this round");
+                continue;
+            }
+        }
+
         match check_and_cancel_expired_trades(&db, &blockchain_client).await {
             Ok(cancelled_count) => {
                 if cancelled_count > 0 {
@@ -76,6 +97,13 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
                 error!("❌ Error checking/cancelling expired trades: {}", e);
             }
         }
+
+        // Quotes nobody executed explain no trade
+        match db.prune_expired_match_plans(chrono::Utc::now()).await {
+            Ok(0) => {}
+            Ok(count) => info!("🧹 Deleted {} expired match plan(s)", count),
+            Err(e) => warn!("⚠️  Failed to delete expired match plans: {}", e),
+        }
     }
 }
 
@@ -121,9 +149,8 @@ async fn check_and_cancel_expired_trades(
             trade_id_str, expires_at
         );
 
-        // Convert trade ID from hex string to bytes32
-        let trade_id_bytes = match types::trade_id_to_bytes32(trade_id_str) {
-            Ok(bytes) => bytes,
+        let trade_id = match trade_id_str.parse::<TradeId>() {
+            Ok(trade_id) => trade_id,
             Err(e) => {
                 error!("❌ Invalid trade ID format {}: {}", trade_id_str, e);
                 continue;
@@ -131,15 +158,19 @@ async fn check_and_cancel_expired_trades(
         };
 
         // Call smart contract to cancel the trade
-        match blockchain_client.cancel_expired_trade(trade_id_bytes).await {
-            Ok(tx_hash) => {
+        match blockchain_client.cancel_expired_trade(trade_id.0).await {
+            Ok(relayer_tx) => {
                 info!(
                     "✅ Trade {} cancelled successfully. TX: {:#x}",
-                    trade_id_str, tx_hash
+                    trade_id_str, relayer_tx.tx_hash
                 );
+                record_spend(db, relayer_tx.spend(ACTION_CANCEL_EXPIRED, Some(trade_id_str.clone()), None, false)).await;
                 cancelled_count += 1;
             }
             Err(e) => {
+                if let EthereumClientError::TransactionReverted(tx) = &e {
+                    record_spend(db, tx.spend(ACTION_CANCEL_EXPIRED, Some(trade_id_str.clone()), None, true)).await;
+                }
                 // Log error but continue with other trades
                 // (trade might have already been cancelled, settled, or other edge case)
                 warn!(
@@ -153,3 +184,9 @@ async fn check_and_cancel_expired_trades(
     Ok(cancelled_count)
 }
 
+/// Record relayer gas spend (logged on failure, the cancel is already mined)
+async fn record_spend(db: &Database, spend: NewRelayerSpend) {
+    if let Err(e) = db.record_relayer_spend(&spend).await {
+        warn!("⚠️  Failed to record relayer spend for tx {}: {}", spend.tx_hash, e);
+    }
+}
@@ -0,0 +1,117 @@
+// Rebuild the orderbook database from the escrow's events (disaster recovery)
+//
+// Usage: rebuild-from-chain [--from-block N] [--schema NAME] [--wipe] [--sample N]
+//
+// Replays every event from the deployment block (--from-block, or ESCROW_DEPLOY_BLOCK)
+// into a freshly migrated schema (chain_rebuild by default), then checks a sample of
+// orders and trades against on-chain storage. Exits non-zero if anything differs.
+// See src/blockchain/rebuild.rs for how to promote the rebuilt schema.
+
+use std::env;
+use std::sync::Arc;
+use tracing::{error, info, warn};
+use tracing_subscriber;
+
+use zkalipay_orderbook::blockchain::rebuild::{self, DEFAULT_REBUILD_SCHEMA, DEFAULT_SAMPLE_SIZE};
+use zkalipay_orderbook::fees::FeeEngine;
+
+struct Args {
+    from_block: Option<u64>,
+    schema: String,
+    wipe: bool,
+    sample: i64,
+}
+
+fn parse_args() -> Result<Args, String> {
+    let mut args = Args {
+        from_block: None,
+        schema: DEFAULT_REBUILD_SCHEMA.to_string(),
+        wipe: false,
+        sample: DEFAULT_SAMPLE_SIZE,
+    };
+    let mut iter = env::args().skip(1);
+    while let Some(arg) = iter.next() {
+        let mut value = |name: &str| iter.next().ok_or_else(|| format!("{} needs a value", name));
+        match arg.as_str() {
+            "--from-block" => {
+                let block = value("--from-block")?;
+                args.from_block = Some(block.parse().map_err(|_| format!("Invalid --from-block {}", block))?);
+            }
+            "--schema" => args.schema = value("--schema")?,
+            "--sample" => {
+                let sample = value("--sample")?;
+                args.sample = sample.parse().map_err(|_| format!("Invalid --sample {}", sample))?;
+            }
+            "--wipe" => args.wipe = true,
+            other => return Err(format!("Unknown argument {}", other)),
+        }
+    }
+    Ok(args)
+}
+
+#[tokio::main]
+async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    // Initialize logging
+    tracing_subscriber::fmt()
+        .with_target(false)
+        .with_thread_ids(false)
+        .with_level(true)
+        .init();
+
+    let args = parse_args()?;
+
+    // Load configuration from environment variables
+    let database_url = env::var("DATABASE_URL")
+        .expect("DATABASE_URL must be set");
+
+    let escrow_address: ethers::types::Address = env::var("ESCROW_CONTRACT_ADDRESS")
+        .expect("ESCROW_CONTRACT_ADDRESS must be set")
+        .parse()
+        .expect("Invalid ESCROW_CONTRACT_ADDRESS");
+
+    let from_block = match args.from_block {
+        Some(block) => block,
+        None => env::var("ESCROW_DEPLOY_BLOCK")
+            .map_err(|_| "Pass --from-block or set ESCROW_DEPLOY_BLOCK to the escrow's deployment block")?
+            .parse()?,
+    };
+
+    // Hardcoded Base Sepolia configuration
+    let rpc_url = "https://sepolia.base.org";
+
+    // Fees accrue on replayed settlements exactly as they did live
+    let fee_engine = FeeEngine::from_env()?.map(Arc::new);
+
+    info!("🛟 Rebuilding escrow {:#x} into schema {} from block {}", escrow_address, args.schema, from_block);
+    if args.wipe {
+        warn!("⚠️  --wipe: schema {} will be dropped first", args.schema);
+    }
+
+    let db = rebuild::prepare_schema(&database_url, &args.schema, args.wipe).await?;
+    let synced_block = rebuild::replay(&db, rpc_url, escrow_address, from_block, fee_engine).await?;
+    info!("✅ Replayed events up to block {}", synced_block);
+
+    let report = rebuild::verify(&db, rpc_url, escrow_address, synced_block, args.sample).await?;
+    info!(
+        "🔍 Checked {} orders and {} trades against block {}",
+        report.orders_checked, report.trades_checked, synced_block
+    );
+    for mismatch in &report.mismatches {
+        error!("❌ {}", mismatch);
+    }
+    if report.pending_dead_letters > 0 {
+        error!("❌ {} events could not be applied (see dead_letter_events in {})", report.pending_dead_letters, args.schema);
+    }
+    if !report.is_ok() {
+        return Err(format!("Rebuild of schema {} does not match the chain", args.schema).into());
+    }
+
+    info!("🎉 Schema {} matches the chain", args.schema);
+    if args.schema != "public" {
+        info!(
+            "To promote it: ALTER SCHEMA public RENAME TO public_old; ALTER SCHEMA {} RENAME TO public;",
+            args.schema
+        );
+    }
+    Ok(())
+}
@@ -0,0 +1,153 @@
+// Ethereum address newtype shared by models, repositories and API types
+// Stored and compared as 0x + 40 lowercase hex (the form in the database, see
+// migrations/014); serialized as EIP-55 checksummed strings in API responses.
+
+use ethers::types::Address;
+use serde::{de, Deserialize, Deserializer, Serialize, Serializer};
+use sqlx::encode::IsNull;
+use sqlx::error::BoxDynError;
+use sqlx::postgres::{PgArgumentBuffer, PgTypeInfo, PgValueRef};
+use sqlx::{Decode, Encode, Postgres, Type};
+use std::fmt;
+use std::str::FromStr;
+use thiserror::Error;
+
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+pub enum AddressError {
+    #[error("Invalid address '{0}': expected 40 hex characters")]
+    Malformed(String),
+    #[error("Invalid address '{0}': EIP-55 checksum mismatch")]
+    Checksum(String),
+}
+
+/// Normalized Ethereum address (lowercase, 0x-prefixed)
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct EthAddress(String);
+
+impl EthAddress {
+    /// Parse any case with an optional 0x/0X prefix. Mixed-case input must carry a
+    /// valid EIP-55 checksum (a typo in a checksummed address is rejected, not stored).
+    pub fn parse(address: &str) -> Result<Self, AddressError> {
+        let trimmed = address.trim();
+        let hex = trimmed
+            .strip_prefix("0x")
+            .or_else(|| trimmed.strip_prefix("0X"))
+            .unwrap_or(trimmed);
+        if hex.len() != 40 || !hex.chars().all(|c| c.is_ascii_hexdigit()) {
+            return Err(AddressError::Malformed(address.to_string()));
+        }
+
+        let normalized = Self(format!("0x{}", hex.to_lowercase()));
+        let mixed_case = hex.chars().any(|c| c.is_ascii_lowercase()) && hex.chars().any(|c| c.is_ascii_uppercase());
+        if mixed_case && normalized.to_checksum()[2..] != *hex {
+            return Err(AddressError::Checksum(address.to_string()));
+        }
+        Ok(normalized)
+    }
+
+    /// Stored form: 0x + 40 lowercase hex
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+
+    /// EIP-55 mixed-case form, as returned by the API
+    pub fn to_checksum(&self) -> String {
+        ethers::utils::to_checksum(&self.to_address(), None)
+    }
+
+    pub fn to_address(&self) -> Address {
+        // Validated on construction
+        Address::from_str(&self.0).expect("EthAddress holds 40 hex characters")
+    }
+}
+
+impl From<Address> for EthAddress {
+    fn from(address: Address) -> Self {
+        Self(format!("{:#x}", address))
+    }
+}
+
+impl FromStr for EthAddress {
+    type Err = AddressError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::parse(s)
+    }
+}
+
+/// Lowercase stored form (logs, cache keys, SQL)
+impl fmt::Display for EthAddress {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+impl Serialize for EthAddress {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&self.to_checksum())
+    }
+}
+
+impl<'de> Deserialize<'de> for EthAddress {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let s = String::deserialize(deserializer)?;
+        Self::parse(&s).map_err(de::Error::custom)
+    }
+}
+
+// Postgres: stored as VARCHAR/TEXT, re-validated on read
+
+impl Type<Postgres> for EthAddress {
+    fn type_info() -> PgTypeInfo {
+        <String as Type<Postgres>>::type_info()
+    }
+
+    fn compatible(ty: &PgTypeInfo) -> bool {
+        <String as Type<Postgres>>::compatible(ty)
+    }
+}
+
+impl Encode<'_, Postgres> for EthAddress {
+    fn encode_by_ref(&self, buf: &mut PgArgumentBuffer) -> IsNull {
+        <&str as Encode<Postgres>>::encode(self.as_str(), buf)
+    }
+}
+
+impl<'r> Decode<'r, Postgres> for EthAddress {
+    fn decode(value: PgValueRef<'r>) -> Result<Self, BoxDynError> {
+        let s = <&str as Decode<Postgres>>::decode(value)?;
+        Ok(Self::parse(s)?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const STORED: &str = "0x036cbd53842c5426634e7929541ec2318f3dcf7e";
+    const CHECKSUMMED: &str = "0x036CbD53842c5426634e7929541eC2318f3dCF7e";
+
+    #[test]
+    fn test_parse_normalizes() {
+        for input in [STORED, CHECKSUMMED, " 036CBD53842C5426634E7929541EC2318F3DCF7E ", "0X036CBD53842C5426634E7929541EC2318F3DCF7E"] {
+            assert_eq!(EthAddress::parse(input).unwrap().as_str(), STORED, "{}", input);
+        }
+
+        // Wrong length / non-hex / bad checksum (one letter's case flipped)
+        assert!(matches!(EthAddress::parse("0x036cbd"), Err(AddressError::Malformed(_))));
+        assert!(matches!(EthAddress::parse(&STORED.replace('c', "g")), Err(AddressError::Malformed(_))));
+        assert!(matches!(
+            EthAddress::parse("0x036cbD53842c5426634e7929541eC2318f3dCF7e"),
+            Err(AddressError::Checksum(_))
+        ));
+    }
+
+    #[test]
+    fn test_serializes_checksummed() {
+        let address = EthAddress::parse(STORED).unwrap();
+        assert_eq!(address.to_string(), STORED);
+        assert_eq!(serde_json::to_string(&address).unwrap(), format!("\"{}\"", CHECKSUMMED));
+        assert_eq!(serde_json::from_str::<EthAddress>(&format!("\"{}\"", CHECKSUMMED)).unwrap(), address);
+        assert_eq!(EthAddress::from(address.to_address()), address);
+    }
+}
@@ -0,0 +1,111 @@
+// Bulk order management calldata
+// Market makers reprice by withdrawing many orders and creating new ones. The escrow
+// authorizes the seller by msg.sender (withdrawAmount checks order.seller, and
+// createAndLockOrder records msg.sender as seller and pulls tokens from it), so the
+// calls can't go through a Multicall3 aggregate - the aggregator would be the sender.
+// Instead the batch is returned as an EIP-5792 call list for wallet_sendCalls with
+// atomicRequired, which executes it in one transaction from the seller's own account
+// (smart account or EIP-7702). The event listener applies all order events of one
+// transaction in a single database transaction, so the book never shows half a batch.
+
+use ethers::abi::AbiEncode;
+use ethers::types::{Address, Bytes, H256, U256};
+use serde::Serialize;
+use std::collections::BTreeMap;
+
+use super::{ApproveCall, CreateAndLockOrderCall, WithdrawAmountCall};
+
+/// Largest batch the calldata endpoints build
+pub const MAX_BATCH_ORDERS: usize = 50;
+
+/// One call of an EIP-5792 batch (quantities and data hex-encoded)
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct BatchCall {
+    pub to: Address,
+    pub data: Bytes,
+    pub value: U256,
+}
+
+impl BatchCall {
+    fn new(to: Address, data: Vec<u8>) -> Self {
+        Self { to, data: Bytes::from(data), value: U256::zero() }
+    }
+}
+
+/// An order to create in a batch
+#[derive(Debug, Clone)]
+pub struct OrderSpec {
+    pub token: Address,
+    pub total_amount: U256,
+    pub exchange_rate: U256,
+    pub alipay_id: String,
+    pub alipay_name: String,
+}
+
+/// withdrawAmount calls for (order id, amount) pairs, in order
+pub fn withdraw_calls(escrow: Address, withdrawals: &[(H256, U256)]) -> Vec<BatchCall> {
+    withdrawals
+        .iter()
+        .map(|(order_id, amount)| {
+            let call = WithdrawAmountCall { order_id: order_id.0, amount: *amount };
+            BatchCall::new(escrow, call.encode())
+        })
+        .collect()
+}
+
+/// One approve per token for the batch's total, then a createAndLockOrder per order
+/// (approve sets the allowance, so a leftover allowance from earlier is replaced, not added to)
+pub fn create_calls(escrow: Address, orders: &[OrderSpec]) -> Vec<BatchCall> {
+    let mut totals: BTreeMap<Address, U256> = BTreeMap::new();
+    for order in orders {
+        let total = totals.entry(order.token).or_default();
+        *total = total.saturating_add(order.total_amount);
+    }
+
+    let approvals = totals.into_iter().map(|(token, amount)| {
+        BatchCall::new(token, ApproveCall { spender: escrow, amount }.encode())
+    });
+    let creates = orders.iter().map(|order| {
+        let call = CreateAndLockOrderCall {
+            token: order.token,
+            total_amount: order.total_amount,
+            exchange_rate: order.exchange_rate,
+            alipay_id: order.alipay_id.clone(),
+            alipay_name: order.alipay_name.clone(),
+        };
+        BatchCall::new(escrow, call.encode())
+    });
+
+    approvals.chain(creates).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ethers::abi::AbiDecode;
+
+    #[test]
+    fn test_batch_calls() {
+        let escrow = Address::repeat_byte(0xee);
+        let usdc = Address::repeat_byte(0x01);
+        let order = |amount: u64| OrderSpec {
+            token: usdc,
+            total_amount: U256::from(amount),
+            exchange_rate: U256::from(735),
+            alipay_id: "13945908941".to_string(),
+            alipay_name: "张三".to_string(),
+        };
+
+        let calls = create_calls(escrow, &[order(100), order(250)]);
+        assert_eq!(calls.len(), 3);
+        assert_eq!(calls[0].to, usdc);
+        let approve = ApproveCall::decode(&calls[0].data).unwrap();
+        assert_eq!((approve.spender, approve.amount), (escrow, U256::from(350)));
+        let create = CreateAndLockOrderCall::decode(&calls[2].data).unwrap();
+        assert_eq!((calls[2].to, create.total_amount), (escrow, U256::from(250)));
+
+        let calls = withdraw_calls(escrow, &[(H256::repeat_byte(0x0a), U256::from(5))]);
+        let withdraw = WithdrawAmountCall::decode(&calls[0].data).unwrap();
+        assert_eq!((withdraw.order_id, withdraw.amount), ([0x0a; 32], U256::from(5)));
+    }
+}
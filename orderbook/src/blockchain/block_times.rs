@@ -0,0 +1,63 @@
+// Block timestamp cache
+// Synced orders and trades are stamped with their block's timestamp - the time the
+// contract itself records as createdAt - rather than the server clock, so backfills
+// and dead-letter replays keep the real on-chain times (syncedAt stays wall-clock).
+// Events of one query range share a handful of blocks, so a small cache of recent
+// block -> timestamp lookups saves most of the eth_getBlockByNumber calls.
+
+use std::collections::BTreeMap;
+use std::sync::Mutex;
+
+/// Blocks remembered by the listener's cache
+pub const DEFAULT_BLOCK_TIME_CACHE_SIZE: usize = 256;
+
+/// Recent block -> unix timestamp lookups; the lowest blocks are evicted first
+#[derive(Debug)]
+pub struct BlockTimeCache {
+    capacity: usize,
+    times: Mutex<BTreeMap<u64, i64>>,
+}
+
+impl Default for BlockTimeCache {
+    fn default() -> Self {
+        Self::new(DEFAULT_BLOCK_TIME_CACHE_SIZE)
+    }
+}
+
+impl BlockTimeCache {
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            capacity: capacity.max(1),
+            times: Mutex::new(BTreeMap::new()),
+        }
+    }
+
+    pub fn get(&self, block: u64) -> Option<i64> {
+        self.times.lock().unwrap().get(&block).copied()
+    }
+
+    pub fn insert(&self, block: u64, timestamp: i64) {
+        let mut times = self.times.lock().unwrap();
+        times.insert(block, timestamp);
+        while times.len() > self.capacity {
+            times.pop_first();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_block_time_cache_evicts_lowest_blocks() {
+        let cache = BlockTimeCache::new(2);
+        cache.insert(100, 1_700_000_000);
+        cache.insert(102, 1_700_000_004);
+        cache.insert(101, 1_700_000_002);
+
+        assert_eq!(cache.get(100), None);
+        assert_eq!(cache.get(101), Some(1_700_000_002));
+        assert_eq!(cache.get(102), Some(1_700_000_004));
+    }
+}
@@ -1,10 +1,20 @@
 use ethers::prelude::*;
 use ethers::providers::{Http, Provider};
-use ethers::signers::{LocalWallet, Signer};
+use ethers::signers::Signer;
+use std::sync::atomic::{AtomicUsize, Ordering};
 use std::sync::Arc;
 use thiserror::Error;
 
+use super::eas::eas_abi;
+use super::events::{TRADE_EXPIRED_SIGNATURE, TRADE_SETTLED_SIGNATURE};
+use super::ids::{OrderId, TradeId};
+use super::pending_txs::{PendingTx, PendingTxs, KIND_CANCEL};
+use super::read_cache::{ContractReadCache, READ_ORDER_PRICING, READ_PAYMENT_WINDOW, READ_PUBLIC_KEY_DER_HASH};
+use super::settlement_batch::{aggregate3_calls, multicall3_abi, ProofSubmission};
+use super::signer::{local_signer, DynSigner, TxSigner};
 use super::ZkAliPayEscrow;
+use crate::db::relayer_spend::{NewRelayerSpend, ACTION_CANCEL_EXPIRED, ACTION_EAS_ATTEST, ACTION_FILL_ORDER, ACTION_SUBMIT_PROOF};
+use crate::latency::{PhaseTimer, PHASE_CONFIRMATION, PHASE_GAS_ESTIMATION, PHASE_SEND};
 
 #[derive(Error, Debug)]
 pub enum EthereumClientError {
@@ -16,13 +26,112 @@ pub enum EthereumClientError {
     WalletError(String),
     #[error("Transaction failed: {0}")]
     TransactionFailed(String),
+    /// Mined but reverted - gas was still spent
+    #[error("Transaction reverted: {:#x}", .0.tx_hash)]
+    TransactionReverted(Box<RelayerTx>),
 }
 
+type EscrowContract = ZkAliPayEscrow<SignerMiddleware<Provider<Http>, DynSigner>>;
+
+/// One configured relayer key and the contract bound to it
+struct RelayerSigner {
+    signer: DynSigner,
+    escrow_contract: EscrowContract,
+}
+
+/// On-chain state of a relayer key
+#[derive(Debug, Clone)]
+pub struct RelayerKeyStatus {
+    pub address: Address,
+    pub active: bool,
+    /// Next nonce including transactions still in the mempool
+    pub pending_nonce: U256,
+    /// Next nonce as of the latest block
+    pub confirmed_nonce: U256,
+    pub balance_wei: U256,
+}
+
+impl RelayerKeyStatus {
+    /// Transactions sent from this key that are not yet mined
+    pub fn in_flight(&self) -> U256 {
+        self.pending_nonce.saturating_sub(self.confirmed_nonce)
+    }
+}
+
+/// A mined relayer transaction and what it cost
+#[derive(Debug, Clone, Copy)]
+pub struct RelayerTx {
+    pub tx_hash: H256,
+    pub relayer: Address,
+    pub gas_used: U256,
+    pub effective_gas_price: U256,
+    pub block_number: Option<u64>,
+}
+
+impl RelayerTx {
+    fn from_receipt(receipt: &TransactionReceipt) -> Self {
+        Self {
+            tx_hash: receipt.transaction_hash,
+            relayer: receipt.from,
+            gas_used: receipt.gas_used.unwrap_or_default(),
+            effective_gas_price: receipt.effective_gas_price.unwrap_or_default(),
+            block_number: receipt.block_number.map(|n| n.as_u64()),
+        }
+    }
+
+    /// Fee paid in wei (gasUsed * effectiveGasPrice)
+    pub fn cost_wei(&self) -> U256 {
+        self.gas_used.saturating_mul(self.effective_gas_price)
+    }
+
+    /// Spend record attributing this transaction to a trade/order
+    pub fn spend(&self, action: &'static str, trade_id: Option<String>, order_id: Option<String>, reverted: bool) -> NewRelayerSpend {
+        NewRelayerSpend {
+            tx_hash: format!("{:#x}", self.tx_hash),
+            action,
+            trade_id,
+            order_id,
+            relayer: format!("{:#x}", self.relayer),
+            gas_used: self.gas_used.to_string(),
+            effective_gas_price: self.effective_gas_price.to_string(),
+            cost_wei: self.cost_wei().to_string(),
+            reverted,
+            block_number: self.block_number.map(|n| n as i64),
+        }
+    }
+}
+
+/// Manual gas settings for incident response (unset fields use the usual estimate)
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct GasOverride {
+    /// Gas limit instead of estimate + 20%
+    pub gas_limit: Option<U256>,
+    /// Gas price in wei (max fee for EIP-1559 transactions)
+    pub gas_price: Option<U256>,
+}
+
+/// A trade as stored by the escrow contract
+#[derive(Debug, Clone)]
+pub struct OnchainTrade {
+    /// False if the contract has no such trade (zero buyer)
+    pub exists: bool,
+    /// TradeStatus: 0=PENDING, 1=SETTLED, 2=EXPIRED
+    pub status: u8,
+    pub expires_at: u64,
+}
+
+/// Relayer client. Holds one or more relayer keys with exactly one active signer;
+/// every transaction is sent from the active key, and `rotate_to` switches keys
+/// without a restart (transactions already sent from the old key still confirm).
 pub struct EthereumClient {
     provider: Arc<Provider<Http>>,
-    wallet: LocalWallet,
-    escrow_contract: ZkAliPayEscrow<SignerMiddleware<Provider<Http>, LocalWallet>>,
+    signers: Vec<RelayerSigner>,
+    active: AtomicUsize,
     chain_id: u64,
+    /// Cached contract reads (see read_cache)
+    reads: Arc<ContractReadCache>,
+    /// Sent transactions awaiting their receipt (see pending_txs)
+    pending: Arc<PendingTxs>,
 }
 
 impl EthereumClient {
@@ -32,38 +141,230 @@ impl EthereumClient {
         escrow_address: Address,
         chain_id: u64,
     ) -> Result<Self, EthereumClientError> {
+        Self::with_keys(rpc_url, &[private_key.to_string()], escrow_address, chain_id).await
+    }
+
+    /// Create a client with several local relayer keys; the first one starts out active
+    pub async fn with_keys(
+        rpc_url: &str,
+        private_keys: &[String],
+        escrow_address: Address,
+        chain_id: u64,
+    ) -> Result<Self, EthereumClientError> {
+        let signers = private_keys
+            .iter()
+            .map(|key| local_signer(key, chain_id))
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(|e| EthereumClientError::WalletError(e.to_string()))?;
+
+        Self::with_signers(rpc_url, signers, escrow_address, chain_id).await
+    }
+
+    /// Create a client from relayer signers of any backend; the first one starts out active
+    pub async fn with_signers(
+        rpc_url: &str,
+        tx_signers: Vec<Arc<dyn TxSigner>>,
+        escrow_address: Address,
+        chain_id: u64,
+    ) -> Result<Self, EthereumClientError> {
+        if tx_signers.is_empty() {
+            return Err(EthereumClientError::WalletError("No relayer keys configured".to_string()));
+        }
+
         // Create provider
         let provider = Provider::<Http>::try_from(rpc_url)
             .map_err(|e| EthereumClientError::ProviderError(e.to_string()))?;
 
-        // Create wallet
-        let wallet: LocalWallet = private_key
-            .parse()
-            .map_err(|e| EthereumClientError::WalletError(format!("Invalid private key: {}", e)))?;
-        let wallet = wallet.with_chain_id(chain_id);
+        let mut signers: Vec<RelayerSigner> = Vec::with_capacity(tx_signers.len());
+        for tx_signer in tx_signers {
+            let signer = DynSigner::new(tx_signer);
 
-        // Create signer middleware
-        let client = SignerMiddleware::new(provider.clone(), wallet.clone());
-        let client = Arc::new(client);
+            if signers.iter().any(|s| s.signer.address() == signer.address()) {
+                return Err(EthereumClientError::WalletError(format!(
+                    "Duplicate relayer key {:#x}",
+                    signer.address()
+                )));
+            }
+
+            // Create signer middleware and contract instance
+            let client = Arc::new(SignerMiddleware::new(provider.clone(), signer.clone()));
+            let escrow_contract = ZkAliPayEscrow::new(escrow_address, client);
 
-        // Create contract instance
-        let escrow_contract = ZkAliPayEscrow::new(escrow_address, client.clone());
+            signers.push(RelayerSigner { signer, escrow_contract });
+        }
 
         Ok(Self {
             provider: Arc::new(provider),
-            wallet,
-            escrow_contract,
+            signers,
+            active: AtomicUsize::new(0),
             chain_id,
+            reads: Arc::new(ContractReadCache::default()),
+            pending: Arc::new(PendingTxs::default()),
         })
     }
 
+    /// Replace the default read cache (TTL, size, metrics)
+    pub fn with_read_cache(mut self, reads: ContractReadCache) -> Self {
+        self.reads = Arc::new(reads);
+        self
+    }
+
+    /// The read cache, for the event listener to invalidate on config changes
+    pub fn read_cache(&self) -> Arc<ContractReadCache> {
+        self.reads.clone()
+    }
+
+    fn active_signer(&self) -> &RelayerSigner {
+        &self.signers[self.active.load(Ordering::Acquire)]
+    }
+
+    /// Escrow contract bound to the active relayer key
+    fn escrow(&self) -> &EscrowContract {
+        &self.active_signer().escrow_contract
+    }
+
+    /// Make `address` the active relayer key. Returns the previously active address.
+    pub fn rotate_to(&self, address: Address) -> Result<Address, EthereumClientError> {
+        let index = self
+            .signers
+            .iter()
+            .position(|s| s.signer.address() == address)
+            .ok_or_else(|| EthereumClientError::WalletError(format!("Relayer key {:#x} is not configured", address)))?;
+
+        let previous = self.active.swap(index, Ordering::AcqRel);
+        let previous = self.signers[previous].signer.address();
+        tracing::warn!("🔑 Relayer key rotated: {:#x} -> {:#x}", previous, address);
+        Ok(previous)
+    }
+
+    /// Addresses of all configured relayer keys (active key included)
+    pub fn relayer_addresses(&self) -> Vec<Address> {
+        self.signers.iter().map(|s| s.signer.address()).collect()
+    }
+
+    /// Nonce state and balance of every configured relayer key
+    pub async fn relayer_key_statuses(&self) -> Result<Vec<RelayerKeyStatus>, EthereumClientError> {
+        let active = self.relayer_address();
+        let mut statuses = Vec::with_capacity(self.signers.len());
+
+        for address in self.relayer_addresses() {
+            let provider_err = |e: ProviderError| EthereumClientError::ProviderError(e.to_string());
+            let pending_nonce = self
+                .provider
+                .get_transaction_count(address, Some(BlockNumber::Pending.into()))
+                .await
+                .map_err(provider_err)?;
+            let confirmed_nonce = self
+                .provider
+                .get_transaction_count(address, Some(BlockNumber::Latest.into()))
+                .await
+                .map_err(provider_err)?;
+            let balance_wei = self
+                .provider
+                .get_balance(address, None)
+                .await
+                .map_err(provider_err)?;
+
+            statuses.push(RelayerKeyStatus {
+                address,
+                active: address == active,
+                pending_nonce,
+                confirmed_nonce,
+                balance_wei,
+            });
+        }
+
+        Ok(statuses)
+    }
+
+    /// Record a sent transaction as pending (its fields come back from the node)
+    async fn track_sent(&self, tx_hash: H256, kind: &str, trade_id: Option<String>, order_id: Option<String>) -> Option<PendingTx> {
+        match self.provider.get_transaction(tx_hash).await {
+            Ok(Some(tx)) => {
+                let pending = PendingTx::new(&tx, kind, trade_id, order_id);
+                self.pending.insert(pending.clone());
+                Some(pending)
+            }
+            Ok(None) => {
+                tracing::warn!("{} tx {:#x} not found right after sending, not tracked", kind, tx_hash);
+                None
+            }
+            Err(e) => {
+                tracing::warn!("Failed to fetch {} tx {:#x}, not tracked: {}", kind, tx_hash, e);
+                None
+            }
+        }
+    }
+
+    /// Transactions sent and not yet mined, oldest nonce first
+    /// Entries the relayers' mined nonces have passed are dropped first.
+    pub async fn pending_transactions(&self) -> Result<Vec<PendingTx>, EthereumClientError> {
+        for address in self.relayer_addresses() {
+            let confirmed_nonce = self
+                .provider
+                .get_transaction_count(address, Some(BlockNumber::Latest.into()))
+                .await
+                .map_err(|e| EthereumClientError::ProviderError(e.to_string()))?;
+            self.pending.prune_mined(address, confirmed_nonce);
+        }
+        Ok(self.pending.list())
+    }
+
+    /// Tokens of the order that the relayer's unmined fillOrder transactions will take
+    pub fn fills_in_flight(&self, order_id: [u8; 32]) -> U256 {
+        self.pending.fills_in_flight(&OrderId::from(order_id).to_string())
+    }
+
+    /// Replace a pending transaction at its nonce: the same call with more gas, or an
+    /// empty self-transfer if `cancel` (see PendingTx::replacement). Returns the
+    /// replacement, which is tracked like any sent transaction.
+    pub async fn replace_pending(
+        &self,
+        tx_hash: H256,
+        cancel: bool,
+        min_gas_price: Option<U256>,
+    ) -> Result<PendingTx, EthereumClientError> {
+        let original = self
+            .pending
+            .get(&tx_hash)
+            .ok_or_else(|| EthereumClientError::TransactionFailed(format!("Transaction {:#x} is not pending", tx_hash)))?;
+        let signer = self
+            .signers
+            .iter()
+            .find(|s| s.signer.address() == original.relayer)
+            .ok_or_else(|| EthereumClientError::WalletError(format!("Relayer key {:#x} is not configured", original.relayer)))?;
+
+        let replacement = original.replacement(cancel, min_gas_price);
+        let client = signer.escrow_contract.client();
+        let replacement_hash = client
+            .send_transaction(replacement, None)
+            .await
+            .map_err(|e| EthereumClientError::TransactionFailed(format!("Replacement of {:#x} failed: {}", tx_hash, e)))?
+            .tx_hash();
+
+        let kind = if cancel { KIND_CANCEL } else { original.kind.as_str() };
+        tracing::warn!(
+            "⛽ Replaced {} tx {:#x} (nonce {}) with {:#x}",
+            original.kind, tx_hash, original.nonce, replacement_hash
+        );
+        let mut replacement = self
+            .track_sent(replacement_hash, kind, original.trade_id.clone(), original.order_id.clone())
+            .await
+            .ok_or_else(|| EthereumClientError::TransactionFailed(format!("Replacement sent as {:#x} but not found", replacement_hash)))?;
+        replacement.replaces = Some(tx_hash);
+        self.pending.insert(replacement.clone());
+        Ok(replacement)
+    }
+
     /// Fill an order (buyer calling this to initiate a trade)
+    /// Gas estimation, sending and the wait for the receipt are timed on `timer`.
     pub async fn fill_order(
         &self,
         order_id: [u8; 32],
         fill_amount: U256,
         buyer_address: Address,
-    ) -> Result<(H256, [u8; 32], String), EthereumClientError> {
+        timer: &PhaseTimer,
+    ) -> Result<(RelayerTx, [u8; 32], String), EthereumClientError> {
         tracing::info!(
             "Calling fillOrder: order_id={}, fill_amount={}, buyer={}",
             hex::encode(order_id),
@@ -72,35 +373,36 @@ impl EthereumClient {
         );
 
         let mut call = self
-            .escrow_contract
+            .escrow()
             .fill_order(order_id, buyer_address, fill_amount);
 
         // Estimate gas
-        let gas_estimate = call
-            .estimate_gas()
+        let gas_estimate = timer
+            .time(PHASE_GAS_ESTIMATION, call.estimate_gas())
             .await
             .map_err(|e| EthereumClientError::ContractError(format!("Gas estimation failed: {}", e)))?;
 
         // Send transaction with gas limit
         call = call.gas(gas_estimate * 120 / 100); // 20% buffer
-        let pending_tx = call
-            .send()
+        let pending_tx = timer
+            .time(PHASE_SEND, call.send())
             .await
             .map_err(|e| EthereumClientError::TransactionFailed(format!("fillOrder failed: {}", e)))?;
 
         let tx_hash = pending_tx.tx_hash();
         tracing::info!("fillOrder tx sent: {:#x}", tx_hash);
+        self.track_sent(tx_hash, ACTION_FILL_ORDER, None, Some(OrderId::from(order_id).to_string())).await;
 
         // Wait for confirmation
-        let receipt = pending_tx
+        let receipt = timer
+            .time(PHASE_CONFIRMATION, pending_tx)
             .await
             .map_err(|e| EthereumClientError::TransactionFailed(format!("Transaction receipt error: {}", e)))?
             .ok_or_else(|| EthereumClientError::TransactionFailed("No receipt returned".to_string()))?;
 
+        self.pending.remove(&tx_hash);
         if receipt.status != Some(U64::from(1)) {
-            return Err(EthereumClientError::TransactionFailed(
-                "Transaction reverted".to_string(),
-            ));
+            return Err(EthereumClientError::TransactionReverted(Box::new(RelayerTx::from_receipt(&receipt))));
         }
 
         tracing::info!("fillOrder tx confirmed: {:#x}", tx_hash);
@@ -108,7 +410,7 @@ impl EthereumClient {
         // Decode trade ID and nonce from logs
         let (trade_id, payment_nonce) = self.decode_trade_created_event(&receipt)?;
 
-        Ok((tx_hash, trade_id, payment_nonce))
+        Ok((RelayerTx::from_receipt(&receipt), trade_id, payment_nonce))
     }
 
     /// Submit payment proof (buyer calling this after sending Alipay payment)
@@ -119,7 +421,23 @@ impl EthereumClient {
         user_public_values: [u8; 32],
         accumulator: Vec<u8>,
         proof: Vec<u8>,
-    ) -> Result<H256, EthereumClientError> {
+    ) -> Result<RelayerTx, EthereumClientError> {
+        self.submit_payment_proof_with_gas(trade_id, user_public_values, accumulator, proof, GasOverride::default(), |_| {})
+            .await
+    }
+
+    /// submitPaymentProof with manual gas settings
+    /// `on_sent` is called with the tx hash as soon as the transaction is sent, before
+    /// waiting for its receipt.
+    pub async fn submit_payment_proof_with_gas(
+        &self,
+        trade_id: [u8; 32],
+        user_public_values: [u8; 32],
+        accumulator: Vec<u8>,
+        proof: Vec<u8>,
+        gas: GasOverride,
+        on_sent: impl FnOnce(H256) + Send,
+    ) -> Result<RelayerTx, EthereumClientError> {
         tracing::info!(
             "Calling submitPaymentProof: trade_id={}, user_public_values={}, accumulator_len={}, proof_len={}",
             hex::encode(trade_id),
@@ -131,7 +449,7 @@ impl EthereumClient {
         let accumulator_bytes = Bytes::from(accumulator.clone());
         let proof_bytes = Bytes::from(proof.clone());
         let mut call = self
-            .escrow_contract
+            .escrow()
             .submit_payment_proof(trade_id, user_public_values, accumulator_bytes, proof_bytes);
 
         // Estimate gas
@@ -143,7 +461,10 @@ impl EthereumClient {
             })?;
 
         // Send transaction with gas limit
-        call = call.gas(gas_estimate * 120 / 100); // 20% buffer
+        call = call.gas(gas.gas_limit.unwrap_or(gas_estimate * 120 / 100)); // 20% buffer
+        if let Some(gas_price) = gas.gas_price {
+            call = call.gas_price(gas_price);
+        }
         let tx = call
             .send()
             .await
@@ -153,6 +474,8 @@ impl EthereumClient {
 
         let tx_hash = tx.tx_hash();
         tracing::info!("submitPaymentProof tx sent: {:#x}", tx_hash);
+        self.track_sent(tx_hash, ACTION_SUBMIT_PROOF, Some(TradeId::from(trade_id).to_string()), None).await;
+        on_sent(tx_hash);
 
         // Wait for confirmation
         let receipt = tx
@@ -164,28 +487,198 @@ impl EthereumClient {
                 EthereumClientError::TransactionFailed("No receipt returned".to_string())
             })?;
 
+        self.pending.remove(&tx_hash);
         if receipt.status != Some(U64::from(1)) {
-            return Err(EthereumClientError::TransactionFailed(
-                "Transaction reverted".to_string(),
-            ));
+            return Err(EthereumClientError::TransactionReverted(Box::new(RelayerTx::from_receipt(&receipt))));
         }
 
         tracing::info!("submitPaymentProof tx confirmed: {:#x}", tx_hash);
 
-        Ok(tx_hash)
+        Ok(RelayerTx::from_receipt(&receipt))
+    }
+
+    /// Dry-run submitPaymentProof via eth_call against the latest block (no transaction sent)
+    /// Returns ContractError with the revert data if the call would revert
+    pub async fn simulate_payment_proof(
+        &self,
+        trade_id: [u8; 32],
+        user_public_values: [u8; 32],
+        accumulator: Vec<u8>,
+        proof: Vec<u8>,
+    ) -> Result<(), EthereumClientError> {
+        self.escrow()
+            .submit_payment_proof(trade_id, user_public_values, Bytes::from(accumulator), Bytes::from(proof))
+            .call()
+            .await
+            .map_err(|e| EthereumClientError::ContractError(format!("submitPaymentProof simulation reverted: {}", e)))
+    }
+
+    /// Dry-run withdrawAmount as `seller` via eth_call against the latest block
+    /// Returns ContractError with the revert data if the call would revert
+    pub async fn simulate_withdrawal(
+        &self,
+        order_id: [u8; 32],
+        amount: U256,
+        seller: Address,
+    ) -> Result<(), EthereumClientError> {
+        self.escrow()
+            .withdraw_amount(order_id, amount)
+            .from(seller)
+            .call()
+            .await
+            .map_err(|e| EthereumClientError::ContractError(format!("withdrawAmount simulation reverted: {}", e)))
+    }
+
+    /// Submit several payment proofs in one transaction through Multicall3's aggregate3
+    /// Each call may fail on its own; returns the transaction and the trades it settled
+    /// (from their TradeSettled logs). `on_sent` is called with the tx hash once sent.
+    pub async fn submit_payment_proof_batch(
+        &self,
+        multicall: Address,
+        proofs: &[ProofSubmission],
+        on_sent: impl FnOnce(H256) + Send,
+    ) -> Result<(RelayerTx, Vec<[u8; 32]>), EthereumClientError> {
+        use super::TradeSettledFilter;
+
+        tracing::info!("Calling aggregate3 with {} submitPaymentProof calls via {:#x}", proofs.len(), multicall);
+
+        let escrow = self.escrow().address();
+        let multicall = Contract::new(multicall, multicall3_abi(), self.escrow().client());
+        let mut call = multicall
+            .method::<_, Vec<(bool, Bytes)>>("aggregate3", (aggregate3_calls(escrow, proofs),))
+            .map_err(|e| EthereumClientError::ContractError(e.to_string()))?;
+
+        // Estimate gas
+        let gas_estimate = call
+            .estimate_gas()
+            .await
+            .map_err(|e| EthereumClientError::ContractError(format!("Gas estimation failed: {}", e)))?;
+
+        // Send transaction with gas limit
+        call = call.gas(gas_estimate * 120 / 100); // 20% buffer
+        let tx = call
+            .send()
+            .await
+            .map_err(|e| EthereumClientError::TransactionFailed(format!("aggregate3 failed: {}", e)))?;
+
+        let tx_hash = tx.tx_hash();
+        tracing::info!("aggregate3 tx sent: {:#x}", tx_hash);
+        self.track_sent(tx_hash, "aggregate3", None, None).await;
+        on_sent(tx_hash);
+
+        // Wait for confirmation
+        let receipt = tx
+            .await
+            .map_err(|e| EthereumClientError::TransactionFailed(format!("Transaction receipt error: {}", e)))?
+            .ok_or_else(|| EthereumClientError::TransactionFailed("No receipt returned".to_string()))?;
+
+        self.pending.remove(&tx_hash);
+        if receipt.status != Some(U64::from(1)) {
+            return Err(EthereumClientError::TransactionReverted(Box::new(RelayerTx::from_receipt(&receipt))));
+        }
+
+        let settled: Vec<[u8; 32]> = receipt
+            .logs
+            .iter()
+            .filter(|log| log.address == escrow)
+            .filter_map(|log| {
+                self.escrow()
+                    .decode_event::<TradeSettledFilter>("TradeSettled", log.topics.clone(), log.data.clone())
+                    .ok()
+            })
+            .map(|event| event.trade_id)
+            .collect();
+
+        tracing::info!("aggregate3 tx confirmed: {:#x} ({}/{} settled)", tx_hash, settled.len(), proofs.len());
+
+        Ok((RelayerTx::from_receipt(&receipt), settled))
+    }
+
+    /// Write an EAS attestation of `schema` about `recipient` (see blockchain::eas)
+    /// Returns the transaction and the attestation UID from its Attested log
+    pub async fn attest(
+        &self,
+        eas: Address,
+        schema: [u8; 32],
+        recipient: Address,
+        data: Bytes,
+    ) -> Result<(RelayerTx, [u8; 32]), EthereumClientError> {
+        tracing::info!("Calling EAS attest: schema=0x{} recipient={:#x}", hex::encode(schema), recipient);
+
+        // AttestationRequestData: no expiration, revocable, no referenced attestation, no value
+        let request = (schema, (recipient, 0u64, true, [0u8; 32], data, U256::zero()));
+        let contract = Contract::new(eas, eas_abi(), self.escrow().client());
+        let mut call = contract
+            .method::<_, [u8; 32]>("attest", (request,))
+            .map_err(|e| EthereumClientError::ContractError(e.to_string()))?;
+
+        // Estimate gas
+        let gas_estimate = call
+            .estimate_gas()
+            .await
+            .map_err(|e| EthereumClientError::ContractError(format!("Gas estimation failed: {}", e)))?;
+
+        // Send transaction with gas limit
+        call = call.gas(gas_estimate * 120 / 100); // 20% buffer
+        let tx = call
+            .send()
+            .await
+            .map_err(|e| EthereumClientError::TransactionFailed(format!("attest failed: {}", e)))?;
+
+        let tx_hash = tx.tx_hash();
+        tracing::info!("attest tx sent: {:#x}", tx_hash);
+        self.track_sent(tx_hash, ACTION_EAS_ATTEST, None, None).await;
+
+        // Wait for confirmation
+        let receipt = tx
+            .await
+            .map_err(|e| EthereumClientError::TransactionFailed(format!("Transaction receipt error: {}", e)))?
+            .ok_or_else(|| EthereumClientError::TransactionFailed("No receipt returned".to_string()))?;
+
+        self.pending.remove(&tx_hash);
+        if receipt.status != Some(U64::from(1)) {
+            return Err(EthereumClientError::TransactionReverted(Box::new(RelayerTx::from_receipt(&receipt))));
+        }
+
+        // Attested(recipient, attester, uid, schemaUID): uid is the only non-indexed field
+        let attested_topic = H256::from(ethers::utils::keccak256("Attested(address,address,bytes32,bytes32)"));
+        let uid = receipt
+            .logs
+            .iter()
+            .find(|log| log.address == eas && log.topics.first() == Some(&attested_topic))
+            .and_then(|log| log.data.get(..32))
+            .map(|uid| {
+                let mut bytes = [0u8; 32];
+                bytes.copy_from_slice(uid);
+                bytes
+            })
+            .ok_or_else(|| EthereumClientError::ContractError(format!("attest tx {:#x} emitted no Attested event", tx_hash)))?;
+
+        tracing::info!("attest tx confirmed: {:#x} (uid 0x{})", tx_hash, hex::encode(uid));
+
+        Ok((RelayerTx::from_receipt(&receipt), uid))
     }
 
     /// Cancel expired trade (anyone can call)
     pub async fn cancel_expired_trade(
         &self,
         trade_id: [u8; 32],
-    ) -> Result<H256, EthereumClientError> {
+    ) -> Result<RelayerTx, EthereumClientError> {
+        self.cancel_expired_trade_with_gas(trade_id, GasOverride::default()).await
+    }
+
+    /// cancelExpiredTrade with manual gas settings
+    pub async fn cancel_expired_trade_with_gas(
+        &self,
+        trade_id: [u8; 32],
+        gas: GasOverride,
+    ) -> Result<RelayerTx, EthereumClientError> {
         tracing::info!(
             "Calling cancelExpiredTrade: trade_id={}",
             hex::encode(trade_id)
         );
 
-        let mut call = self.escrow_contract.cancel_expired_trade(trade_id);
+        let mut call = self.escrow().cancel_expired_trade(trade_id);
 
         // Estimate gas
         let gas_estimate = call
@@ -196,7 +689,10 @@ impl EthereumClient {
             })?;
 
         // Send transaction with gas limit
-        call = call.gas(gas_estimate * 120 / 100); // 20% buffer
+        call = call.gas(gas.gas_limit.unwrap_or(gas_estimate * 120 / 100)); // 20% buffer
+        if let Some(gas_price) = gas.gas_price {
+            call = call.gas_price(gas_price);
+        }
         let tx = call
             .send()
             .await
@@ -206,6 +702,7 @@ impl EthereumClient {
 
         let tx_hash = tx.tx_hash();
         tracing::info!("cancelExpiredTrade tx sent: {:#x}", tx_hash);
+        self.track_sent(tx_hash, ACTION_CANCEL_EXPIRED, Some(TradeId::from(trade_id).to_string()), None).await;
 
         // Wait for confirmation
         let receipt = tx
@@ -217,15 +714,14 @@ impl EthereumClient {
                 EthereumClientError::TransactionFailed("No receipt returned".to_string())
             })?;
 
+        self.pending.remove(&tx_hash);
         if receipt.status != Some(U64::from(1)) {
-            return Err(EthereumClientError::TransactionFailed(
-                "Transaction reverted".to_string(),
-            ));
+            return Err(EthereumClientError::TransactionReverted(Box::new(RelayerTx::from_receipt(&receipt))));
         }
 
         tracing::info!("cancelExpiredTrade tx confirmed: {:#x}", tx_hash);
 
-        Ok(tx_hash)
+        Ok(RelayerTx::from_receipt(&receipt))
     }
 
     /// Decode TradeCreated event from receipt to get trade_id and payment_nonce
@@ -236,7 +732,7 @@ impl EthereumClient {
         use super::TradeCreatedFilter;
 
         for log in &receipt.logs {
-            if let Ok(event) = self.escrow_contract.decode_event::<TradeCreatedFilter>(
+            if let Ok(event) = self.escrow().decode_event::<TradeCreatedFilter>(
                 "TradeCreated",
                 log.topics.clone(),
                 log.data.clone(),
@@ -292,14 +788,20 @@ impl EthereumClient {
         "trade-0000000000000000".to_string()
     }
 
+    /// Address of the active relayer key
     pub fn relayer_address(&self) -> Address {
-        self.wallet.address()
+        self.active_signer().signer.address()
     }
 
     pub fn chain_id(&self) -> u64 {
         self.chain_id
     }
 
+    /// Address of the escrow contract
+    pub fn escrow_address(&self) -> Address {
+        self.escrow().address()
+    }
+
     /// Get current block number
     pub async fn get_block_number(&self) -> Result<u64, EthereumClientError> {
         let block_number = self
@@ -310,10 +812,39 @@ impl EthereumClient {
         Ok(block_number.as_u64())
     }
 
+    /// Block a transaction was mined in (None if it isn't mined, e.g. dropped by a reorg)
+    pub async fn transaction_block(&self, tx_hash: H256) -> Result<Option<u64>, EthereumClientError> {
+        let receipt = self
+            .provider
+            .get_transaction_receipt(tx_hash)
+            .await
+            .map_err(|e| EthereumClientError::ProviderError(e.to_string()))?;
+        Ok(receipt.and_then(|r| r.block_number).map(|n| n.as_u64()))
+    }
+
+    /// Get the timestamp of the latest block (unix seconds)
+    pub async fn get_latest_block_timestamp(&self) -> Result<u64, EthereumClientError> {
+        let block = self
+            .provider
+            .get_block(BlockNumber::Latest)
+            .await
+            .map_err(|e| EthereumClientError::ProviderError(e.to_string()))?
+            .ok_or_else(|| EthereumClientError::ProviderError("Latest block not found".to_string()))?;
+        Ok(block.timestamp.as_u64())
+    }
+
+    /// Deployed bytecode at the escrow address (empty if no contract is deployed there)
+    pub async fn escrow_code(&self) -> Result<Bytes, EthereumClientError> {
+        self.provider
+            .get_code(self.escrow().address(), None)
+            .await
+            .map_err(|e| EthereumClientError::ProviderError(e.to_string()))
+    }
+
     /// Check if order exists on blockchain
     pub async fn order_exists(&self, order_id: [u8; 32]) -> Result<bool, EthereumClientError> {
         let order = self
-            .escrow_contract
+            .escrow()
             .orders(order_id)
             .call()
             .await
@@ -323,10 +854,55 @@ impl EthereumClient {
         Ok(order.3 > U256::zero()) // order.3 is remainingAmount
     }
 
+    /// Get on-chain pricing for an order: (exchangeRate, tokenDecimals)
+    /// These are the values fillOrder uses to compute the CNY value of a fill; both are
+    /// fixed when the order is created, so they are cached
+    pub async fn get_order_pricing(&self, order_id: [u8; 32]) -> Result<(U256, u8), EthereumClientError> {
+        if let Some(pricing) = self.reads.observe(READ_ORDER_PRICING, self.reads.order_pricing.get(&order_id)) {
+            return Ok(pricing);
+        }
+
+        let order = self
+            .escrow()
+            .orders(order_id)
+            .call()
+            .await
+            .map_err(|e| EthereumClientError::ContractError(e.to_string()))?;
+
+        // order tuple: (orderId, seller, token, totalAmount, remainingAmount, exchangeRate, alipayId, alipayName, createdAt, tokenDecimals)
+        if order.1 == Address::zero() {
+            return Err(EthereumClientError::ContractError(format!(
+                "Order 0x{} not found on-chain",
+                hex::encode(order_id)
+            )));
+        }
+
+        self.reads.order_pricing.insert(order_id, (order.5, order.9));
+        Ok((order.5, order.9))
+    }
+
+    /// Get an order's current on-chain remainingAmount (never cached, it changes with every fill)
+    pub async fn get_order_remaining(&self, order_id: [u8; 32]) -> Result<U256, EthereumClientError> {
+        let order = self
+            .escrow()
+            .orders(order_id)
+            .call()
+            .await
+            .map_err(|e| EthereumClientError::ContractError(e.to_string()))?;
+
+        if order.1 == Address::zero() {
+            return Err(EthereumClientError::ContractError(format!(
+                "Order 0x{} not found on-chain",
+                hex::encode(order_id)
+            )));
+        }
+        Ok(order.4)
+    }
+
     /// Check if trade exists on blockchain
     pub async fn trade_exists(&self, trade_id: [u8; 32]) -> Result<bool, EthereumClientError> {
         let trade = self
-            .escrow_contract
+            .escrow()
             .trades(trade_id)
             .call()
             .await
@@ -337,6 +913,40 @@ impl EthereumClient {
         Ok(trade.6 > U256::zero()) // trade.6 is tokenAmount
     }
 
+    /// Read a trade straight from the contract (bypasses the DB mirror)
+    pub async fn get_onchain_trade(&self, trade_id: [u8; 32]) -> Result<OnchainTrade, EthereumClientError> {
+        let (_, _, buyer, _, _, _, _, expires_at, status) = self
+            .escrow()
+            .trades(trade_id)
+            .call()
+            .await
+            .map_err(|e| EthereumClientError::ContractError(e.to_string()))?;
+
+        Ok(OnchainTrade {
+            exists: buyer != Address::zero(),
+            status,
+            expires_at: expires_at.low_u64(),
+        })
+    }
+
+    /// TradeSettled / TradeExpired logs of a trade between two blocks (inclusive)
+    pub async fn trade_outcome_logs(&self, trade_id: [u8; 32], from_block: u64, to_block: u64) -> Result<Vec<Log>, EthereumClientError> {
+        let filter = Filter::new()
+            .address(self.escrow_address())
+            .topic0(vec![
+                H256::from(ethers::utils::keccak256(TRADE_SETTLED_SIGNATURE)),
+                H256::from(ethers::utils::keccak256(TRADE_EXPIRED_SIGNATURE)),
+            ])
+            .topic1(H256::from(trade_id))
+            .from_block(from_block)
+            .to_block(to_block);
+
+        self.provider
+            .get_logs(&filter)
+            .await
+            .map_err(|e| EthereumClientError::ProviderError(e.to_string()))
+    }
+
     // ============ Admin Functions ============
 
     /// Update contract configuration (minTradeValueCny, maxTradeValueCny, paymentWindow)
@@ -354,7 +964,7 @@ impl EthereumClient {
             payment_window
         );
 
-        let mut call = self.escrow_contract.update_config(
+        let mut call = self.escrow().update_config(
             U256::from(min_trade_value_cny),
             U256::from(max_trade_value_cny),
             U256::from(payment_window),
@@ -379,6 +989,7 @@ impl EthereumClient {
 
         let tx_hash = tx.tx_hash();
         tracing::info!("updateConfig tx sent: {:#x}", tx_hash);
+        self.track_sent(tx_hash, "updateConfig", None, None).await;
 
         // Wait for confirmation
         let receipt = tx
@@ -390,6 +1001,7 @@ impl EthereumClient {
                 EthereumClientError::TransactionFailed("No receipt returned".to_string())
             })?;
 
+        self.pending.remove(&tx_hash);
         if receipt.status != Some(U64::from(1)) {
             return Err(EthereumClientError::TransactionFailed(
                 "Transaction reverted".to_string(),
@@ -397,6 +1009,7 @@ impl EthereumClient {
         }
 
         tracing::info!("updateConfig tx confirmed: {:#x}", tx_hash);
+        self.reads.invalidate_config();
 
         Ok(tx_hash)
     }
@@ -408,7 +1021,7 @@ impl EthereumClient {
     ) -> Result<H256, EthereumClientError> {
         tracing::info!("Calling updateZkVerifier: verifier={:?}", new_verifier);
 
-        let mut call = self.escrow_contract.update_zk_verifier(new_verifier);
+        let mut call = self.escrow().update_zk_verifier(new_verifier);
 
         // Estimate gas
         let gas_estimate = call
@@ -429,6 +1042,7 @@ impl EthereumClient {
 
         let tx_hash = tx.tx_hash();
         tracing::info!("updateZkVerifier tx sent: {:#x}", tx_hash);
+        self.track_sent(tx_hash, "updateZkVerifier", None, None).await;
 
         // Wait for confirmation
         let receipt = tx
@@ -440,6 +1054,7 @@ impl EthereumClient {
                 EthereumClientError::TransactionFailed("No receipt returned".to_string())
             })?;
 
+        self.pending.remove(&tx_hash);
         if receipt.status != Some(U64::from(1)) {
             return Err(EthereumClientError::TransactionFailed(
                 "Transaction reverted".to_string(),
@@ -455,7 +1070,7 @@ impl EthereumClient {
     pub async fn pause_contract(&self) -> Result<H256, EthereumClientError> {
         tracing::info!("Calling pause");
 
-        let mut call = self.escrow_contract.pause();
+        let mut call = self.escrow().pause();
 
         // Estimate gas
         let gas_estimate = call
@@ -476,6 +1091,7 @@ impl EthereumClient {
 
         let tx_hash = tx.tx_hash();
         tracing::info!("pause tx sent: {:#x}", tx_hash);
+        self.track_sent(tx_hash, "pause", None, None).await;
 
         // Wait for confirmation
         let receipt = tx
@@ -487,6 +1103,7 @@ impl EthereumClient {
                 EthereumClientError::TransactionFailed("No receipt returned".to_string())
             })?;
 
+        self.pending.remove(&tx_hash);
         if receipt.status != Some(U64::from(1)) {
             return Err(EthereumClientError::TransactionFailed(
                 "Transaction reverted".to_string(),
@@ -502,7 +1119,7 @@ impl EthereumClient {
     pub async fn unpause_contract(&self) -> Result<H256, EthereumClientError> {
         tracing::info!("Calling unpause");
 
-        let mut call = self.escrow_contract.unpause();
+        let mut call = self.escrow().unpause();
 
         // Estimate gas
         let gas_estimate = call
@@ -523,6 +1140,7 @@ impl EthereumClient {
 
         let tx_hash = tx.tx_hash();
         tracing::info!("unpause tx sent: {:#x}", tx_hash);
+        self.track_sent(tx_hash, "unpause", None, None).await;
 
         // Wait for confirmation
         let receipt = tx
@@ -534,6 +1152,7 @@ impl EthereumClient {
                 EthereumClientError::TransactionFailed("No receipt returned".to_string())
             })?;
 
+        self.pending.remove(&tx_hash);
         if receipt.status != Some(U64::from(1)) {
             return Err(EthereumClientError::TransactionFailed(
                 "Transaction reverted".to_string(),
@@ -559,7 +1178,7 @@ impl EthereumClient {
             hex::encode(app_vm_commit)
         );
 
-        let mut call = self.escrow_contract.update_zk_pdf_config(
+        let mut call = self.escrow().update_zk_pdf_config(
             public_key_der_hash,
             app_exe_commit,
             app_vm_commit,
@@ -584,6 +1203,7 @@ impl EthereumClient {
 
         let tx_hash = tx.tx_hash();
         tracing::info!("updateZkPDFConfig tx sent: {:#x}", tx_hash);
+        self.track_sent(tx_hash, "updateZkPDFConfig", None, None).await;
 
         // Wait for confirmation
         let receipt = tx
@@ -595,6 +1215,7 @@ impl EthereumClient {
                 EthereumClientError::TransactionFailed("No receipt returned".to_string())
             })?;
 
+        self.pending.remove(&tx_hash);
         if receipt.status != Some(U64::from(1)) {
             return Err(EthereumClientError::TransactionFailed(
                 "Transaction reverted".to_string(),
@@ -602,28 +1223,40 @@ impl EthereumClient {
         }
 
         tracing::info!("updateZkPDFConfig tx confirmed: {:#x}", tx_hash);
+        self.reads.public_key_der_hash.clear();
 
         Ok(tx_hash)
     }
 
-    /// Get payment window from contract
+    /// Get payment window from contract (cached)
     pub async fn get_payment_window(&self) -> Result<U256, EthereumClientError> {
-        self.escrow_contract
+        if let Some(window) = self.reads.observe(READ_PAYMENT_WINDOW, self.reads.payment_window.get(&())) {
+            return Ok(window);
+        }
+
+        let window = self.escrow()
             .payment_window()
             .call()
             .await
-            .map_err(|e| EthereumClientError::ContractError(e.to_string()))
+            .map_err(|e| EthereumClientError::ContractError(e.to_string()))?;
+        self.reads.payment_window.insert((), window);
+        Ok(window)
     }
 
-    /// Get public key DER hash from contract
+    /// Get public key DER hash from contract (cached)
     pub async fn get_public_key_der_hash(&self) -> Result<[u8; 32], EthereumClientError> {
+        if let Some(hash) = self.reads.observe(READ_PUBLIC_KEY_DER_HASH, self.reads.public_key_der_hash.get(&())) {
+            return Ok(hash);
+        }
+
         tracing::debug!("🔍 Fetching public key DER hash from contract...");
-        let hash = self.escrow_contract
+        let hash = self.escrow()
             .public_key_der_hash()
             .call()
             .await
             .map_err(|e| EthereumClientError::ContractError(e.to_string()))?;
         tracing::debug!("✅ Fetched public key DER hash: {}", hex::encode(hash));
+        self.reads.set_public_key_der_hash(hash);
         Ok(hash)
     }
 
@@ -631,62 +1264,104 @@ impl EthereumClient {
     /// Returns: (minTradeValueCny, maxTradeValueCny, paymentWindow, paused, zkVerifier, publicKeyDerHash, appExeCommit, appVmCommit)
     pub async fn get_contract_config(&self) -> Result<(U256, U256, U256, bool, Address, [u8; 32], [u8; 32], [u8; 32]), EthereumClientError> {
         let min_trade = self
-            .escrow_contract
+            .escrow()
             .min_trade_value_cny()
             .call()
             .await
             .map_err(|e| EthereumClientError::ContractError(e.to_string()))?;
 
         let max_trade = self
-            .escrow_contract
+            .escrow()
             .max_trade_value_cny()
             .call()
             .await
             .map_err(|e| EthereumClientError::ContractError(e.to_string()))?;
 
         let payment_window = self
-            .escrow_contract
+            .escrow()
             .payment_window()
             .call()
             .await
             .map_err(|e| EthereumClientError::ContractError(e.to_string()))?;
 
         let paused = self
-            .escrow_contract
+            .escrow()
             .paused()
             .call()
             .await
             .map_err(|e| EthereumClientError::ContractError(e.to_string()))?;
 
         let zk_verifier = self
-            .escrow_contract
+            .escrow()
             .zk_verifier()
             .call()
             .await
             .map_err(|e| EthereumClientError::ContractError(e.to_string()))?;
 
         let public_key_der_hash = self
-            .escrow_contract
+            .escrow()
             .public_key_der_hash()
             .call()
             .await
             .map_err(|e| EthereumClientError::ContractError(e.to_string()))?;
 
         let app_exe_commit = self
-            .escrow_contract
+            .escrow()
             .app_exe_commit()
             .call()
             .await
             .map_err(|e| EthereumClientError::ContractError(e.to_string()))?;
 
         let app_vm_commit = self
-            .escrow_contract
+            .escrow()
             .app_vm_commit()
             .call()
             .await
             .map_err(|e| EthereumClientError::ContractError(e.to_string()))?;
 
+        // A full fetch refreshes the cached reads too
+        self.reads.payment_window.insert((), payment_window);
+        self.reads.set_public_key_der_hash(public_key_der_hash);
+
         Ok((min_trade, max_trade, payment_window, paused, zk_verifier, public_key_der_hash, app_exe_commit, app_vm_commit))
     }
 }
 
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Well-known anvil dev keys
+    const KEY_A: &str = "ac0974bec39a17e36ba4a6b4d238ff944bacb478cbed5efcae784d7bf4f2ff80";
+    const KEY_B: &str = "59c6995e998f97a5a0044966f0945389dc9e86dae88c7a8412f4603b6b78690d";
+
+    #[tokio::test]
+    async fn test_rotate_relayer_key() {
+        let client = EthereumClient::with_keys(
+            "http://localhost:8545",
+            &[KEY_A.to_string(), KEY_B.to_string()],
+            Address::zero(),
+            31337,
+        )
+        .await
+        .unwrap();
+
+        let [a, b] = client.relayer_addresses()[..] else { panic!("expected two keys") };
+        assert_eq!(client.relayer_address(), a);
+
+        assert_eq!(client.rotate_to(b).unwrap(), a);
+        assert_eq!(client.relayer_address(), b);
+        assert!(client.rotate_to(Address::repeat_byte(0x42)).is_err());
+        assert_eq!(client.relayer_address(), b);
+
+        let duplicate = EthereumClient::with_keys(
+            "http://localhost:8545",
+            &[KEY_A.to_string(), KEY_A.to_string()],
+            Address::zero(),
+            31337,
+        )
+        .await;
+        assert!(duplicate.is_err());
+    }
+}
@@ -0,0 +1,110 @@
+// Cached snapshot of on-chain contract configuration
+// Every getter is a separate eth_call, so handlers read this snapshot instead of the contract.
+// The snapshot is refetched when older than the TTL, when the event listener sees a
+// config-change event, or when an admin forces a refresh.
+
+use chrono::{DateTime, Utc};
+use ethers::types::{Address, U256};
+use std::time::Duration;
+use tokio::sync::RwLock;
+use tokio::time::Instant;
+
+use super::client::{EthereumClient, EthereumClientError};
+use super::types::TradeLimits;
+
+/// Default snapshot lifetime
+pub const DEFAULT_CONFIG_TTL_SECS: u64 = 300;
+
+/// Snapshot of ZkAliPayEscrow configuration
+#[derive(Debug, Clone)]
+pub struct ContractConfig {
+    pub min_trade_value_cny: U256,
+    pub max_trade_value_cny: U256,
+    pub payment_window: U256,
+    pub paused: bool,
+    pub zk_verifier: Address,
+    pub public_key_der_hash: [u8; 32],
+    pub app_exe_commit: [u8; 32],
+    pub app_vm_commit: [u8; 32],
+    pub fetched_at: DateTime<Utc>,
+}
+
+impl ContractConfig {
+    /// Fetch a fresh snapshot from the contract
+    pub async fn fetch(client: &EthereumClient) -> Result<Self, EthereumClientError> {
+        let (min_trade, max_trade, payment_window, paused, zk_verifier, public_key_der_hash, app_exe_commit, app_vm_commit) =
+            client.get_contract_config().await?;
+
+        Ok(Self {
+            min_trade_value_cny: min_trade,
+            max_trade_value_cny: max_trade,
+            payment_window,
+            paused,
+            zk_verifier,
+            public_key_der_hash,
+            app_exe_commit,
+            app_vm_commit,
+            fetched_at: Utc::now(),
+        })
+    }
+
+    pub fn trade_limits(&self) -> TradeLimits {
+        TradeLimits {
+            min_cny: self.min_trade_value_cny,
+            max_cny: self.max_trade_value_cny,
+        }
+    }
+}
+
+/// TTL cache around a single ContractConfig snapshot (shared by handlers and the listener)
+pub struct ContractConfigCache {
+    ttl: Duration,
+    snapshot: RwLock<Option<(ContractConfig, Instant)>>,
+}
+
+impl ContractConfigCache {
+    pub fn new(ttl: Duration) -> Self {
+        Self {
+            ttl,
+            snapshot: RwLock::new(None),
+        }
+    }
+
+    /// Get the cached snapshot, fetching it if missing or expired
+    pub async fn get(&self, client: &EthereumClient) -> Result<ContractConfig, EthereumClientError> {
+        if let Some((config, loaded_at)) = self.snapshot.read().await.as_ref() {
+            if loaded_at.elapsed() < self.ttl {
+                return Ok(config.clone());
+            }
+        }
+
+        self.refresh(client).await
+    }
+
+    /// Refetch the snapshot from the contract unconditionally
+    pub async fn refresh(&self, client: &EthereumClient) -> Result<ContractConfig, EthereumClientError> {
+        let config = ContractConfig::fetch(client).await?;
+        *self.snapshot.write().await = Some((config.clone(), Instant::now()));
+
+        tracing::debug!("🔄 Contract config snapshot refreshed");
+        Ok(config)
+    }
+
+    /// Apply a known change to the current snapshot in place (no-op if nothing is cached)
+    pub async fn update(&self, apply: impl FnOnce(&mut ContractConfig)) {
+        if let Some((config, _)) = self.snapshot.write().await.as_mut() {
+            apply(config);
+        }
+    }
+
+    /// Drop the snapshot so the next read refetches (used when config changes on-chain)
+    pub async fn invalidate(&self) {
+        *self.snapshot.write().await = None;
+    }
+}
+
+impl Default for ContractConfigCache {
+    fn default() -> Self {
+        Self::new(Duration::from_secs(DEFAULT_CONFIG_TTL_SECS))
+    }
+}
@@ -0,0 +1,175 @@
+// Settlement confirmation tracking
+// TradeSettled is indexed as soon as the settlement tx is mined (1 confirmation), but
+// exchanges integrating the orderbook want N-block finality. The tracker re-reads the
+// receipt of every settled-but-unfinalized trade, records its confirmation depth on the
+// trade, and finalizes it (finalizedAt, trade_settled webhook) once the depth is reached.
+// Re-reading the receipt each pass follows reorgs: a tx that moved blocks restarts its
+// count, one that dropped out goes back to 0 confirmations until it is mined again.
+//
+// Configuration (env):
+// - SETTLEMENT_CONFIRMATIONS: depth at which a settlement is final (default 12)
+// - SETTLEMENT_CONFIRMATION_SCAN_SECS: how often settlements are checked (default 15)
+
+use ethers::types::H256;
+use std::sync::Arc;
+use tokio::task::JoinHandle;
+use tokio::time::{interval, Duration};
+
+use super::client::EthereumClient;
+use crate::db::confirmations::{ConfirmationRepository, PostgresConfirmationRepository};
+use crate::notifications::{NotificationEvent, Notifier};
+
+/// Default finality depth (~24s on Base)
+pub const DEFAULT_SETTLEMENT_CONFIRMATIONS: u64 = 12;
+
+/// Default interval between scans
+pub const DEFAULT_CONFIRMATION_SCAN_SECS: u64 = 15;
+
+/// Maximum settlements checked per scan
+const SCAN_BATCH: i64 = 200;
+
+/// Confirmations of a tx mined in `block` with the chain at `head` (the mining block counts as one)
+pub fn confirmations(head: u64, block: u64) -> i32 {
+    if head < block {
+        // Lagging RPC node behind the one that served the receipt
+        return 1;
+    }
+    (head - block + 1).min(i32::MAX as u64) as i32
+}
+
+pub struct ConfirmationTracker {
+    client: Arc<EthereumClient>,
+    repo: PostgresConfirmationRepository,
+    notifier: Option<Arc<dyn Notifier>>,
+    depth: u64,
+    scan_interval: Duration,
+}
+
+impl ConfirmationTracker {
+    pub fn new(client: Arc<EthereumClient>, db_pool: sqlx::PgPool, depth: u64, scan_interval: Duration) -> Self {
+        Self {
+            client,
+            repo: PostgresConfirmationRepository::new(db_pool),
+            notifier: None,
+            depth: depth.max(1),
+            scan_interval,
+        }
+    }
+
+    /// Load depth and scan interval from the environment
+    pub fn from_env(client: Arc<EthereumClient>, db_pool: sqlx::PgPool) -> Self {
+        fn env_u64(key: &str) -> Option<u64> {
+            std::env::var(key).ok().and_then(|v| v.parse().ok())
+        }
+
+        let depth = env_u64("SETTLEMENT_CONFIRMATIONS").unwrap_or(DEFAULT_SETTLEMENT_CONFIRMATIONS);
+        let scan_secs = env_u64("SETTLEMENT_CONFIRMATION_SCAN_SECS").unwrap_or(DEFAULT_CONFIRMATION_SCAN_SECS);
+        Self::new(client, db_pool, depth, Duration::from_secs(scan_secs.max(1)))
+    }
+
+    /// Send a trade_settled event when a settlement becomes final
+    pub fn with_notifier(mut self, notifier: Arc<dyn Notifier>) -> Self {
+        self.notifier = Some(notifier);
+        self
+    }
+
+    /// Spawn the scan loop as a background task
+    pub fn spawn(self) -> JoinHandle<()> {
+        tokio::spawn(async move { self.run().await })
+    }
+
+    async fn run(self) {
+        tracing::info!("🧱 Tracking settlement confirmations to depth {}", self.depth);
+        let mut ticker = interval(self.scan_interval);
+        loop {
+            ticker.tick().await;
+            match self.scan().await {
+                Ok(0) => {}
+                Ok(finalized) => tracing::info!("🧱 Finalized {} settlement(s)", finalized),
+                Err(e) => tracing::warn!("🧱 Confirmation scan failed: {}", e),
+            }
+        }
+    }
+
+    /// Refresh the confirmation depth of every unfinalized settlement
+    /// Returns the number of settlements finalized.
+    pub async fn scan(&self) -> Result<usize, String> {
+        let pending = self.repo.list_unfinalized(SCAN_BATCH).await.map_err(|e| e.to_string())?;
+        if pending.is_empty() {
+            return Ok(0);
+        }
+        let head = self.client.get_block_number().await.map_err(|e| e.to_string())?;
+
+        let mut finalized = 0;
+        for settlement in pending {
+            let trade_id = settlement.trade_id.clone();
+            let tx_hash: H256 = match settlement.settlement_tx_hash.parse() {
+                Ok(hash) => hash,
+                Err(_) => {
+                    tracing::warn!("🧱 Trade {} has an invalid settlement tx hash {}", trade_id, settlement.settlement_tx_hash);
+                    continue;
+                }
+            };
+
+            let block = match self.client.transaction_block(tx_hash).await {
+                Ok(block) => block,
+                Err(e) => {
+                    tracing::warn!("🧱 Failed to fetch settlement receipt for trade {}: {}", trade_id, e);
+                    continue;
+                }
+            };
+
+            let Some(block) = block else {
+                if settlement.settlement_block.is_some() {
+                    tracing::warn!("🧱 Settlement tx {} of trade {} is no longer mined (reorg?)", settlement.settlement_tx_hash, trade_id);
+                }
+                self.repo.update(&trade_id, None, 0).await.map_err(|e| e.to_string())?;
+                continue;
+            };
+
+            let count = confirmations(head, block);
+            if (count as u64) < self.depth {
+                if settlement.settlement_block != Some(block as i64) || settlement.confirmations != count {
+                    self.repo.update(&trade_id, Some(block as i64), count).await.map_err(|e| e.to_string())?;
+                }
+                continue;
+            }
+
+            // Only the replica that flips finalizedAt notifies
+            if !self.repo.finalize(&trade_id, block as i64, count).await.map_err(|e| e.to_string())? {
+                continue;
+            }
+            finalized += 1;
+            tracing::info!("🧱 Settlement of trade {} final at {} confirmations (block {})", trade_id, count, block);
+
+            if let Some(notifier) = &self.notifier {
+                let event = NotificationEvent::TradeSettled {
+                    trade_id: settlement.trade_id,
+                    order_id: settlement.order_id,
+                    buyer: settlement.buyer,
+                    settlement_tx_hash: settlement.settlement_tx_hash,
+                    settlement_block: block,
+                    confirmations: count,
+                };
+                if let Err(e) = notifier.notify(&event).await {
+                    tracing::warn!("🧱 Failed to send trade_settled for trade {}: {}", trade_id, e);
+                }
+            }
+        }
+
+        Ok(finalized)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_confirmations() {
+        assert_eq!(confirmations(100, 100), 1);
+        assert_eq!(confirmations(111, 100), 12);
+        // Head from a node lagging the receipt's
+        assert_eq!(confirmations(99, 100), 1);
+    }
+}
@@ -0,0 +1,300 @@
+// EAS attestations of settled trades
+// Downstream protocols can't read our database, and the escrow only keeps a trade's
+// status. With a schema configured, every settled trade also gets an Ethereum
+// Attestation Service attestation (recipient: the buyer) summarizing it: trade, order,
+// parties, token and CNY amounts, the hash of the Axiom proof id and the settlement
+// transaction. The event listener queues the trade on TradeSettled; the attester waits
+// for the settlement to be final (see confirmations), then has the relayer send EAS's
+// attest and records the UID. Gas is attributed to the trade like any relayer action.
+//
+// The schema must be registered once with the SchemaRegistry as ATTESTATION_SCHEMA
+// (no resolver, revocable); its UID is the EAS_SCHEMA_UID to configure.
+//
+// Configuration (env):
+// - EAS_SCHEMA_UID: UID of the registered schema (unset disables attestations)
+// - EAS_CONTRACT_ADDRESS: EAS contract (default the OP Stack predeploy, as on Base)
+// - EAS_ATTESTATION_SCAN_SECS: how often queued trades are attested (default 30)
+// - EAS_MAX_ATTEMPTS: attempts per trade before it is left as failed (default 5)
+
+use ethers::abi::{Abi, Token};
+use ethers::types::{Address, Bytes, H256, U256};
+use std::sync::Arc;
+use thiserror::Error;
+use tokio::task::JoinHandle;
+use tokio::time::{interval, Duration};
+
+use super::client::{EthereumClient, EthereumClientError, RelayerTx};
+use crate::db::models::{DbOrder, DbTrade};
+use crate::db::relayer_spend::ACTION_EAS_ATTEST;
+use crate::db::trade_attestations::{
+    PostgresTradeAttestationRepository, TradeAttestationRepository, ATTESTATION_LEASE_SECS,
+};
+use crate::db::{Database, DbError};
+
+/// EAS predeploy on OP Stack chains (Base, Base Sepolia)
+pub const DEFAULT_EAS_ADDRESS: &str = "0x4200000000000000000000000000000000000021";
+
+pub const DEFAULT_ATTESTATION_SCAN_SECS: u64 = 30;
+pub const DEFAULT_ATTESTATION_MAX_ATTEMPTS: i32 = 5;
+
+/// Schema the attestation data is encoded with
+pub const ATTESTATION_SCHEMA: &str = "bytes32 tradeId,bytes32 orderId,address seller,address buyer,address token,\
+uint256 tokenAmount,uint256 cnyAmount,bytes32 proofIdHash,bytes32 settlementTxHash";
+
+/// Trades attested per scan
+const SCAN_BATCH: usize = 20;
+
+#[derive(Error, Debug)]
+pub enum EasError {
+    #[error("Invalid EAS config: {0}")]
+    Config(String),
+    #[error("Database error: {0}")]
+    Database(#[from] DbError),
+    #[error("Trade {0} can't be attested: {1}")]
+    Trade(String, String),
+    #[error("Chain error: {0}")]
+    Chain(#[from] EthereumClientError),
+}
+
+#[derive(Debug, Clone)]
+pub struct EasConfig {
+    pub eas: Address,
+    pub schema: [u8; 32],
+    pub scan_interval: Duration,
+    pub max_attempts: i32,
+}
+
+impl EasConfig {
+    /// EAS_SCHEMA_UID, EAS_CONTRACT_ADDRESS, EAS_ATTESTATION_SCAN_SECS and EAS_MAX_ATTEMPTS
+    /// (None when no schema is set)
+    pub fn from_env() -> Result<Option<Self>, EasError> {
+        let schema = match std::env::var("EAS_SCHEMA_UID") {
+            Ok(uid) if !uid.trim().is_empty() => uid
+                .trim()
+                .parse::<H256>()
+                .map_err(|_| EasError::Config(format!("EAS_SCHEMA_UID is not a bytes32: {:?}", uid)))?,
+            _ => return Ok(None),
+        };
+        let eas = std::env::var("EAS_CONTRACT_ADDRESS")
+            .ok()
+            .filter(|address| !address.trim().is_empty())
+            .unwrap_or_else(|| DEFAULT_EAS_ADDRESS.to_string());
+        let eas = eas
+            .trim()
+            .parse::<Address>()
+            .map_err(|_| EasError::Config(format!("EAS_CONTRACT_ADDRESS is not an address: {:?}", eas)))?;
+        let scan_secs = std::env::var("EAS_ATTESTATION_SCAN_SECS")
+            .ok()
+            .and_then(|v| v.parse::<u64>().ok())
+            .unwrap_or(DEFAULT_ATTESTATION_SCAN_SECS);
+        let max_attempts = std::env::var("EAS_MAX_ATTEMPTS")
+            .ok()
+            .and_then(|v| v.parse::<i32>().ok())
+            .filter(|attempts| *attempts > 0)
+            .unwrap_or(DEFAULT_ATTESTATION_MAX_ATTEMPTS);
+
+        Ok(Some(Self {
+            eas,
+            schema: schema.0,
+            scan_interval: Duration::from_secs(scan_secs.max(1)),
+            max_attempts,
+        }))
+    }
+}
+
+/// EAS contract ABI (attest and the Attested event)
+pub(crate) fn eas_abi() -> Abi {
+    ethers::abi::parse_abi(&[
+        "function attest((bytes32,(address,uint64,bool,bytes32,bytes,uint256))) payable returns (bytes32)",
+        "event Attested(address indexed recipient, address indexed attester, bytes32 uid, bytes32 indexed schemaUID)",
+    ])
+    .expect("EAS ABI is valid")
+}
+
+/// What an attestation says about a settled trade (fields of ATTESTATION_SCHEMA)
+#[derive(Debug, Clone, PartialEq)]
+pub struct TradeSummary {
+    pub trade_id: [u8; 32],
+    pub order_id: [u8; 32],
+    pub seller: Address,
+    pub buyer: Address,
+    pub token: Address,
+    pub token_amount: U256,
+    pub cny_amount: U256,
+    /// keccak256 of the Axiom proof id (zero when the proof isn't on record)
+    pub proof_id_hash: [u8; 32],
+    pub settlement_tx_hash: [u8; 32],
+}
+
+impl TradeSummary {
+    pub fn from_trade(trade: &DbTrade, order: &DbOrder) -> Result<Self, EasError> {
+        let invalid = |field: &str, value: &str| EasError::Trade(trade.trade_id.clone(), format!("invalid {} {:?}", field, value));
+        let bytes32 = |field: &str, value: &str| value.parse::<H256>().map(|h| h.0).map_err(|_| invalid(field, value));
+        let amount = |field: &str, value: &str| U256::from_dec_str(value).map_err(|_| invalid(field, value));
+
+        let settlement_tx_hash = trade
+            .settlement_tx_hash
+            .as_deref()
+            .ok_or_else(|| EasError::Trade(trade.trade_id.clone(), "no settlement transaction on record".to_string()))?;
+
+        Ok(Self {
+            trade_id: bytes32("trade id", &trade.trade_id)?,
+            order_id: bytes32("order id", &trade.order_id)?,
+            seller: order.seller.to_address(),
+            buyer: trade.buyer.to_address(),
+            token: order.token.to_address(),
+            token_amount: amount("token amount", &trade.token_amount)?,
+            cny_amount: amount("CNY amount", &trade.cny_amount)?,
+            proof_id_hash: trade
+                .axiom_proof_id
+                .as_deref()
+                .map(|proof_id| ethers::utils::keccak256(proof_id.as_bytes()))
+                .unwrap_or_default(),
+            settlement_tx_hash: bytes32("settlement tx hash", settlement_tx_hash)?,
+        })
+    }
+
+    /// ABI-encoded attestation data
+    pub fn encode(&self) -> Bytes {
+        Bytes::from(ethers::abi::encode(&[
+            Token::FixedBytes(self.trade_id.to_vec()),
+            Token::FixedBytes(self.order_id.to_vec()),
+            Token::Address(self.seller),
+            Token::Address(self.buyer),
+            Token::Address(self.token),
+            Token::Uint(self.token_amount),
+            Token::Uint(self.cny_amount),
+            Token::FixedBytes(self.proof_id_hash.to_vec()),
+            Token::FixedBytes(self.settlement_tx_hash.to_vec()),
+        ]))
+    }
+}
+
+pub struct EasAttester {
+    client: Arc<EthereumClient>,
+    db: Arc<Database>,
+    repo: PostgresTradeAttestationRepository,
+    config: EasConfig,
+}
+
+impl EasAttester {
+    pub fn new(client: Arc<EthereumClient>, db: Arc<Database>, config: EasConfig) -> Self {
+        let repo = PostgresTradeAttestationRepository::new(db.pool().clone());
+        Self { client, db, repo, config }
+    }
+
+    /// Spawn the scan loop as a background task
+    pub fn spawn(self) -> JoinHandle<()> {
+        tokio::spawn(async move { self.run().await })
+    }
+
+    async fn run(self) {
+        tracing::info!("🪪 Attesting settled trades with EAS schema 0x{}", hex::encode(self.config.schema));
+        let mut ticker = interval(self.config.scan_interval);
+        loop {
+            ticker.tick().await;
+            match self.scan().await {
+                Ok(0) => {}
+                Ok(attested) => tracing::info!("🪪 Attested {} settled trade(s)", attested),
+                Err(e) => tracing::warn!("🪪 Attestation scan failed: {}", e),
+            }
+        }
+    }
+
+    /// Attest queued trades whose settlement is final
+    /// Returns the number of trades attested.
+    pub async fn scan(&self) -> Result<usize, EasError> {
+        let mut attested = 0;
+        for _ in 0..SCAN_BATCH {
+            let Some(claim) = self.repo.claim_next(ATTESTATION_LEASE_SECS).await? else {
+                break;
+            };
+            let trade_id = claim.trade_id;
+
+            match self.attest(&trade_id).await {
+                Ok((tx, uid)) => {
+                    let uid = format!("0x{}", hex::encode(uid));
+                    self.repo.complete(&trade_id, &uid, &format!("{:#x}", tx.tx_hash)).await?;
+                    tracing::info!("🪪 Trade {} attested: {} (tx {:#x})", trade_id, uid, tx.tx_hash);
+                    attested += 1;
+                }
+                Err(e) => {
+                    let tx_hash = match &e {
+                        EasError::Chain(EthereumClientError::TransactionReverted(tx)) => Some(format!("{:#x}", tx.tx_hash)),
+                        _ => None,
+                    };
+                    tracing::warn!("🪪 Failed to attest trade {} (attempt {}): {}", trade_id, claim.attempts, e);
+                    self.repo.fail(&trade_id, &e.to_string(), tx_hash.as_deref(), self.config.max_attempts).await?;
+                }
+            }
+        }
+        Ok(attested)
+    }
+
+    /// Send the trade's attestation and record the gas against it
+    async fn attest(&self, trade_id: &str) -> Result<(RelayerTx, [u8; 32]), EasError> {
+        let trade = self.db.get_trade(trade_id).await?;
+        if trade.status != 1 {
+            return Err(EasError::Trade(trade_id.to_string(), "not settled".to_string()));
+        }
+        let order = self.db.get_order(&trade.order_id).await?;
+        let summary = TradeSummary::from_trade(&trade, &order)?;
+
+        let result = self
+            .client
+            .attest(self.config.eas, self.config.schema, summary.buyer, summary.encode())
+            .await;
+        let spend = match &result {
+            Ok((tx, _)) => Some(tx.spend(ACTION_EAS_ATTEST, Some(trade_id.to_string()), None, false)),
+            Err(EthereumClientError::TransactionReverted(tx)) => {
+                Some(tx.spend(ACTION_EAS_ATTEST, Some(trade_id.to_string()), None, true))
+            }
+            Err(_) => None,
+        };
+        if let Some(spend) = spend {
+            if let Err(e) = self.db.record_relayer_spend(&spend).await {
+                tracing::warn!("Failed to record attestation gas for trade {}: {}", trade_id, e);
+            }
+        }
+        result.map_err(EasError::from)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ethers::abi::ParamType;
+
+    #[test]
+    fn test_attestation_data_matches_schema() {
+        let summary = TradeSummary {
+            trade_id: [1u8; 32],
+            order_id: [2u8; 32],
+            seller: Address::repeat_byte(3),
+            buyer: Address::repeat_byte(4),
+            token: Address::repeat_byte(5),
+            token_amount: U256::from(100_000_000u64),
+            cny_amount: U256::from(73_500u64),
+            proof_id_hash: ethers::utils::keccak256("proof_1"),
+            settlement_tx_hash: [6u8; 32],
+        };
+
+        let types: Vec<ParamType> = ATTESTATION_SCHEMA
+            .split(',')
+            .map(|field| match field.split_whitespace().next() {
+                Some("bytes32") => ParamType::FixedBytes(32),
+                Some("address") => ParamType::Address,
+                Some("uint256") => ParamType::Uint(256),
+                other => panic!("unexpected schema type {:?}", other),
+            })
+            .collect();
+        let tokens = ethers::abi::decode(&types, &summary.encode()).unwrap();
+        assert_eq!(tokens.len(), 9);
+        assert_eq!(tokens[3], Token::Address(summary.buyer));
+        assert_eq!(tokens[6], Token::Uint(U256::from(73_500u64)));
+        assert_eq!(tokens[7], Token::FixedBytes(summary.proof_id_hash.to_vec()));
+
+        assert!(eas_abi().function("attest").is_ok());
+        assert!(DEFAULT_EAS_ADDRESS.parse::<Address>().is_ok());
+    }
+}
@@ -1,15 +1,36 @@
 use ethers::prelude::*;
 use ethers::providers::{Http, Provider};
+use rust_decimal::Decimal;
+use std::collections::{BTreeMap, HashSet};
+use std::str::FromStr;
 use std::sync::Arc;
 use thiserror::Error;
-use tokio::time::{interval, Duration};
-
-use super::{OrderCreatedAndLockedFilter, OrderPartiallyWithdrawnFilter, TradeCreatedFilter, ProofSubmittedFilter, TradeSettledFilter, TradeExpiredFilter};
+use tokio::time::{interval, Duration, Instant};
+
+use super::block_times::BlockTimeCache;
+use super::config_cache::ContractConfigCache;
+use super::read_cache::ContractReadCache;
+use super::ids::{OrderId, TradeId};
+use super::types::alipay_hash;
+use super::{ZkAliPayEscrowEvents, OrderCreatedAndLockedFilter, OrderPartiallyWithdrawnFilter, TradeCreatedFilter, ProofSubmittedFilter, TradeSettledFilter, TradeExpiredFilter};
 use crate::db::{
+    config_events::{ConfigEventRepository, PostgresConfigEventRepository},
+    confirmations::{ConfirmationRepository, PostgresConfirmationRepository},
+    dead_letters::{DeadLetterRepository, NewDeadLetter, PostgresDeadLetterRepository},
     models::{DbOrder, DbTrade},
-    orders::{OrderRepository, PostgresOrderRepository},
+    order_intents::{OrderIntentRepository, PostgresOrderIntentRepository},
+    orders::{OrderChange, OrderRepository, PostgresOrderRepository},
+    processed_events::{EventKey, PostgresProcessedEventRepository, ProcessedEventRepository},
+    screening::{CONTEXT_ORDER_SYNC, ROLE_SELLER},
+    trade_attestations::{PostgresTradeAttestationRepository, TradeAttestationRepository},
     trades::{TradeRepository, PostgresTradeRepository},
 };
+use crate::alipay::AlipayIdFormat;
+use crate::fees::{accrue_fee, FeeEngine};
+use crate::metrics::Metrics;
+use crate::notifications::sellers::SellerNotifier;
+use crate::receipts::{issue_receipt, ReceiptSigner};
+use crate::screening::Screening;
 
 #[derive(Error, Debug)]
 pub enum EventListenerError {
@@ -25,12 +46,69 @@ pub enum EventListenerError {
 const BLOCKS_PER_QUERY: u64 = 8;       // Process 8 blocks at a time
 const MAX_REORG_DEPTH: u64 = 2;        // Wait 2 blocks for finality
 const POLL_INTERVAL_SECS: u64 = 6;     // Poll every 6 seconds
+const MAX_CATCH_UP_FAILURES: u32 = 5;  // Consecutive failed steps before catch_up gives up
+
+/// How long a claimed log is remembered; the live listener only resyncs the last few
+/// blocks, so a log older than this isn't seen again
+const PROCESSED_EVENT_RETENTION_DAYS: i64 = 30;
+/// How often claims past retention are deleted
+const PROCESSED_EVENT_PRUNE_INTERVAL: Duration = Duration::from_secs(60 * 60);
+
+/// Event types as counted in metrics and stored in dead_letter_events
+pub const EVENT_ORDER_BATCH: &str = "OrderBatch";
+pub const EVENT_ORDER_CREATED: &str = "OrderCreatedAndLocked";
+pub const EVENT_ORDER_WITHDRAWN: &str = "OrderPartiallyWithdrawn";
+pub const EVENT_TRADE_CREATED: &str = "TradeCreated";
+pub const EVENT_PROOF_SUBMITTED: &str = "ProofSubmitted";
+pub const EVENT_TRADE_SETTLED: &str = "TradeSettled";
+pub const EVENT_TRADE_EXPIRED: &str = "TradeExpired";
+pub const EVENT_CONFIG: &str = "ConfigEvent";
+
+/// Signatures of the handled events (topic0 is their keccak256, see blockchain::fixtures)
+pub const ORDER_CREATED_SIGNATURE: &str = "OrderCreatedAndLocked(bytes32,address,address,uint256,uint256,string,string)";
+pub const ORDER_WITHDRAWN_SIGNATURE: &str = "OrderPartiallyWithdrawn(bytes32,uint256,uint256)";
+pub const TRADE_CREATED_SIGNATURE: &str = "TradeCreated(bytes32,bytes32,address,address,uint256,uint256,string,uint256)";
+pub const PROOF_SUBMITTED_SIGNATURE: &str = "ProofSubmitted(bytes32,bytes32)";
+pub const TRADE_SETTLED_SIGNATURE: &str = "TradeSettled(bytes32)";
+pub const TRADE_EXPIRED_SIGNATURE: &str = "TradeExpired(bytes32,bytes32,uint256)";
+
+/// Outcome of handling a log (or a bulk order transaction's logs)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Handled {
+    /// First handling: its side effects were applied
+    Applied,
+    /// Seen before (a resync): only the idempotent mirror writes ran again
+    Replayed,
+}
+
+/// Queued dead letters replayed per poll
+const DEAD_LETTER_REPLAY_BATCH: i64 = 20;
+
+/// Admin config-change events recorded in contract_config_events
+pub const CONFIG_EVENT_SIGNATURES: [&str; 5] = [
+    "ConfigUpdated(uint256,uint256)",
+    "ZkVerifierUpdated(address,address)",
+    "ZkPDFConfigUpdated(bytes32,bytes32,bytes32)",
+    "Paused(address)",
+    "Unpaused(address)",
+];
 
 pub struct EventListener {
     provider: Arc<Provider<Http>>,
     contract_address: Address,
     db_pool: sqlx::PgPool,
     start_block: u64,
+    receipt_signer: Option<Arc<ReceiptSigner>>,
+    fee_engine: Option<Arc<FeeEngine>>,
+    metrics: Arc<Metrics>,
+    config_cache: Option<Arc<ContractConfigCache>>,
+    read_cache: Option<Arc<ContractReadCache>>,
+    screening: Option<Arc<Screening>>,
+    seller_notifier: Option<Arc<SellerNotifier>>,
+    /// Queue settled trades for their EAS attestation (see blockchain::eas)
+    attest_settlements: bool,
+    block_times: BlockTimeCache,
+    blocks_per_query: u64,
 }
 
 impl EventListener {
@@ -74,14 +152,110 @@ impl EventListener {
             contract_address,
             db_pool,
             start_block,
+            receipt_signer: None,
+            fee_engine: None,
+            metrics: Arc::new(Metrics::new()),
+            config_cache: None,
+            read_cache: None,
+            screening: None,
+            seller_notifier: None,
+            attest_settlements: false,
+            block_times: BlockTimeCache::default(),
+            blocks_per_query: BLOCKS_PER_QUERY,
         })
     }
 
+    /// Report sync progress (head, synced block, lag) into shared metrics
+    pub fn with_metrics(self, metrics: Arc<Metrics>) -> Self {
+        metrics.listener.record_synced(self.start_block.saturating_sub(1));
+        Self { metrics, ..self }
+    }
+
+    /// Issue signed settlement receipts when TradeSettled events are processed
+    pub fn with_receipt_signer(mut self, signer: Arc<ReceiptSigner>) -> Self {
+        self.receipt_signer = Some(signer);
+        self
+    }
+
+    /// Accrue operator fees when TradeSettled events are processed
+    pub fn with_fee_engine(mut self, engine: Arc<FeeEngine>) -> Self {
+        self.fee_engine = Some(engine);
+        self
+    }
+
+    /// Invalidate the shared contract config snapshot when config-change events are seen
+    pub fn with_config_cache(mut self, cache: Arc<ContractConfigCache>) -> Self {
+        self.config_cache = Some(cache);
+        self
+    }
+
+    /// Invalidate the relayer client's cached contract reads when config-change events are seen
+    pub fn with_read_cache(mut self, cache: Arc<ContractReadCache>) -> Self {
+        self.read_cache = Some(cache);
+        self
+    }
+
+    /// Screen the sellers of new orders (a flagged seller's orders stay out of matching)
+    pub fn with_screening(mut self, screening: Arc<Screening>) -> Self {
+        self.screening = Some(screening);
+        self
+    }
+
+    /// Notify sellers of trades created on their orders
+    pub fn with_seller_notifier(mut self, notifier: Arc<SellerNotifier>) -> Self {
+        self.seller_notifier = Some(notifier);
+        self
+    }
+
+    /// Queue settled trades for EAS attestation when TradeSettled events are processed
+    pub fn with_attestations(mut self) -> Self {
+        self.attest_settlements = true;
+        self
+    }
+
+    /// Known block timestamp, saving its RPC lookup (replaying captured logs offline)
+    pub fn with_block_time(self, block: u64, timestamp: i64) -> Self {
+        self.block_times.insert(block, timestamp);
+        self
+    }
+
+    /// Blocks fetched per sync step (larger ranges suit replays far behind the head)
+    pub fn with_blocks_per_query(mut self, blocks: u64) -> Self {
+        self.blocks_per_query = blocks.max(1);
+        self
+    }
+
+    /// Sync until the safe head is reached, then return the last block synced
+    /// (one-shot, for rebuilds - `start` keeps following the chain). Gives up after
+    /// MAX_CATCH_UP_FAILURES consecutive failed steps.
+    pub async fn catch_up(&mut self) -> Result<u64, EventListenerError> {
+        let mut failures = 0;
+        loop {
+            let from = self.start_block;
+            match self.sync_events().await {
+                Ok(()) if self.start_block == from => return Ok(from.saturating_sub(1)),
+                Ok(()) => {
+                    failures = 0;
+                    tracing::info!("⏪ Synced up to block {}", self.start_block - 1);
+                }
+                Err(e) => {
+                    failures += 1;
+                    if failures >= MAX_CATCH_UP_FAILURES {
+                        return Err(e);
+                    }
+                    tracing::warn!("⚠️  Sync from block {} failed ({}/{}): {}", from, failures, MAX_CATCH_UP_FAILURES, e);
+                    tokio::time::sleep(Duration::from_secs(POLL_INTERVAL_SECS)).await;
+                }
+            }
+        }
+    }
+
     /// Start the event listener (runs indefinitely)
     pub async fn start(&mut self) -> Result<(), EventListenerError> {
         tracing::info!("🚀 Starting event listener...");
 
         let mut poll_interval = interval(Duration::from_secs(POLL_INTERVAL_SECS));
+        let mut last_prune: Option<Instant> = None;
 
         loop {
             poll_interval.tick().await;
@@ -90,6 +264,21 @@ impl EventListener {
                 tracing::error!("❌ Event sync error: {}", e);
                 // Continue polling even on error
             }
+
+            if last_prune.is_none_or(|at| at.elapsed() >= PROCESSED_EVENT_PRUNE_INTERVAL) {
+                last_prune = Some(Instant::now());
+                self.prune_processed_events().await;
+            }
+        }
+    }
+
+    /// Forget claims of logs past retention (non-critical: failures are logged)
+    async fn prune_processed_events(&self) {
+        let before = chrono::Utc::now() - chrono::Duration::days(PROCESSED_EVENT_RETENTION_DAYS);
+        match PostgresProcessedEventRepository::new(self.db_pool.clone()).prune(before).await {
+            Ok(0) => {}
+            Ok(count) => tracing::info!("🧹 Pruned {} processed event claims", count),
+            Err(e) => tracing::warn!("⚠️  Processed event prune failed: {}", e),
         }
     }
 
@@ -102,6 +291,11 @@ impl EventListener {
             .map_err(|e| EventListenerError::ProviderError(e.to_string()))?
             .as_u64();
 
+        self.metrics.listener.record_poll(current_block);
+
+        // Replay dead letters an admin queued
+        self.replay_dead_letters().await?;
+
         // Apply reorg protection (don't process very recent blocks)
         let safe_block = current_block.saturating_sub(MAX_REORG_DEPTH);
 
@@ -111,7 +305,7 @@ impl EventListener {
         }
 
         // Process blocks in chunks
-        let to_block = std::cmp::min(self.start_block + BLOCKS_PER_QUERY, safe_block);
+        let to_block = std::cmp::min(self.start_block + self.blocks_per_query, safe_block);
 
         tracing::debug!(
             "📊 Syncing blocks {} to {} (current: {})",
@@ -120,12 +314,16 @@ impl EventListener {
             current_block
         );
 
+        // Apply bulk order batches (several order events in one transaction) atomically
+        let batched = self.process_order_batches(self.start_block, to_block)
+            .await?;
+
         // Process OrderCreatedAndLocked events
-        self.process_order_created_events(self.start_block, to_block)
+        self.process_order_created_events(self.start_block, to_block, &batched)
             .await?;
 
         // Process OrderPartiallyWithdrawn events
-        self.process_order_withdrawn_events(self.start_block, to_block)
+        self.process_order_withdrawn_events(self.start_block, to_block, &batched)
             .await?;
 
         // Process TradeCreated events
@@ -144,14 +342,233 @@ impl EventListener {
         self.process_trade_expired_events(self.start_block, to_block)
             .await?;
 
+        // Process admin config-change events
+        self.process_config_events(self.start_block, to_block)
+            .await?;
+
         // Update last synced block
         self.start_block = to_block + 1;
         Self::save_last_synced_block(&self.db_pool, &self.contract_address, self.start_block)
             .await?;
+        self.metrics.listener.record_synced(to_block);
+
+        Ok(())
+    }
+
+    /// Timestamp of the log's block (cached, see blockchain::block_times)
+    async fn block_timestamp(&self, log: &Log) -> Result<i64, EventListenerError> {
+        let block = log
+            .block_number
+            .ok_or_else(|| EventListenerError::EventDecodeError("Log has no block number".to_string()))?
+            .as_u64();
+        if let Some(timestamp) = self.block_times.get(block) {
+            return Ok(timestamp);
+        }
+
+        let timestamp = self
+            .provider
+            .get_block(block)
+            .await
+            .map_err(|e| EventListenerError::ProviderError(e.to_string()))?
+            .ok_or_else(|| EventListenerError::ProviderError(format!("Block {} not found", block)))?
+            .timestamp
+            .as_u64() as i64;
+        self.block_times.insert(block, timestamp);
+        Ok(timestamp)
+    }
+
+    // ================================================================
+    // DEAD LETTERS: failed handlings are kept instead of dropped
+    // ================================================================
+
+    /// Count a handling's outcome (a replay only as such); a failed one's logs go to
+    /// dead_letter_events
+    async fn record_handling(&self, event_type: &str, logs: &[Log], result: Result<Handled, EventListenerError>) {
+        match &result {
+            Ok(Handled::Replayed) => {
+                self.metrics.listener.record_replay(event_type);
+                tracing::info!("🔁 {} already applied, side effects skipped", event_type);
+            }
+            _ => self.metrics.listener.record_event(event_type, result.is_ok()),
+        }
+        let (Err(e), Some(first)) = (result, logs.first()) else {
+            return;
+        };
+        tracing::error!("❌ Failed to handle {}: {}", event_type, e);
+
+        let key = event_key(event_type, first);
+        let dead_letter = NewDeadLetter {
+            event_type: key.event_type,
+            block_number: key.block_number,
+            tx_hash: key.tx_hash,
+            log_index: key.log_index,
+            logs: serde_json::to_string(logs).unwrap_or_default(),
+            error: e.to_string(),
+        };
+        let repo = PostgresDeadLetterRepository::new(self.db_pool.clone());
+        match repo.record(&dead_letter).await {
+            Ok(recorded) => tracing::warn!("📮 {} in tx {} dead-lettered as #{}", event_type, dead_letter.tx_hash, recorded.id),
+            Err(e) => tracing::error!("❌ Failed to dead-letter {} in tx {}: {}", event_type, dead_letter.tx_hash, e),
+        }
+    }
+
+    /// Replay the dead letters queued for reprocessing
+    async fn replay_dead_letters(&self) -> Result<(), EventListenerError> {
+        let repo = PostgresDeadLetterRepository::new(self.db_pool.clone());
+        let queued = repo
+            .queued(DEAD_LETTER_REPLAY_BATCH)
+            .await
+            .map_err(|e| EventListenerError::DatabaseError(e.to_string()))?;
+
+        for dead_letter in queued {
+            let result = match serde_json::from_str::<Vec<Log>>(&dead_letter.logs) {
+                Ok(logs) => self.handle_logs(&dead_letter.event_type, logs).await,
+                Err(e) => Err(EventListenerError::EventDecodeError(e.to_string())),
+            };
+            match &result {
+                Ok(_) => tracing::info!("📮 Dead letter #{} ({}) reprocessed", dead_letter.id, dead_letter.event_type),
+                Err(e) => tracing::warn!("📮 Dead letter #{} ({}) failed again: {}", dead_letter.id, dead_letter.event_type, e),
+            }
+            repo.finish_replay(dead_letter.id, result.err().map(|e| e.to_string()).as_deref())
+                .await
+                .map_err(|e| EventListenerError::DatabaseError(e.to_string()))?;
+        }
 
         Ok(())
     }
 
+    /// Handle stored logs of `event_type` (one log, or a bulk order transaction's logs)
+    /// Used by dead-letter replay, and by the golden tests to replay captured logs.
+    pub async fn handle_logs(&self, event_type: &str, mut logs: Vec<Log>) -> Result<Handled, EventListenerError> {
+        if event_type == EVENT_ORDER_BATCH {
+            let tx_hash = logs.first().and_then(|log| log.transaction_hash).unwrap_or_default();
+            return self.handle_order_batch(tx_hash, logs).await;
+        }
+
+        let log = match (logs.pop(), logs.is_empty()) {
+            (Some(log), true) => log,
+            _ => return Err(EventListenerError::EventDecodeError(format!("{} needs exactly one log", event_type))),
+        };
+        match event_type {
+            EVENT_ORDER_CREATED => self.handle_order_created(log).await,
+            EVENT_ORDER_WITHDRAWN => self.handle_order_withdrawn(log).await,
+            EVENT_TRADE_CREATED => self.handle_trade_created(log).await.map(|(_, handled)| handled),
+            EVENT_PROOF_SUBMITTED => self.handle_proof_submitted(log).await,
+            EVENT_TRADE_SETTLED => self.handle_trade_settled(log).await,
+            EVENT_TRADE_EXPIRED => self.handle_trade_expired(log).await,
+            EVENT_CONFIG => self.handle_config_event(log).await,
+            other => Err(EventListenerError::EventDecodeError(format!("Unknown event type {}", other))),
+        }
+    }
+
+    /// Claim a log whose side effects are about to be applied (see db::processed_events)
+    async fn claim(&self, key: &EventKey) -> Result<Handled, EventListenerError> {
+        let first = PostgresProcessedEventRepository::new(self.db_pool.clone())
+            .claim(key)
+            .await
+            .map_err(|e| EventListenerError::DatabaseError(e.to_string()))?;
+        Ok(if first { Handled::Applied } else { Handled::Replayed })
+    }
+
+    /// Claim a log and apply its order changes at once: never twice, never claimed unapplied
+    async fn claim_applying(&self, key: &EventKey, changes: &[OrderChange]) -> Result<Handled, EventListenerError> {
+        match PostgresProcessedEventRepository::new(self.db_pool.clone()).claim_applying(key, changes).await {
+            Ok(true) => Ok(Handled::Applied),
+            Ok(false) => Ok(Handled::Replayed),
+            Err(e) => {
+                tracing::error!("❌ Database update failed: {}", e);
+                Err(EventListenerError::DatabaseError(e.to_string()))
+            }
+        }
+    }
+
+    // ================================================================
+    // EVENT HANDLER: Bulk order batches (see blockchain::batch)
+    // ================================================================
+
+    /// Apply the order events of every transaction that created and/or withdrew more
+    /// than one order in one database transaction, so the book switches from the old
+    /// orders to the new ones at once. Returns those transactions: the per-event
+    /// handlers skip them.
+    async fn process_order_batches(
+        &self,
+        from_block: u64,
+        to_block: u64,
+    ) -> Result<HashSet<H256>, EventListenerError> {
+        let filter = Filter::new()
+            .address(self.contract_address)
+            .events([ORDER_CREATED_SIGNATURE, ORDER_WITHDRAWN_SIGNATURE])
+            .from_block(from_block)
+            .to_block(to_block);
+
+        let logs = self
+            .provider
+            .get_logs(&filter)
+            .await
+            .map_err(|e| EventListenerError::ProviderError(e.to_string()))?;
+
+        let mut by_tx: BTreeMap<(u64, H256), Vec<Log>> = BTreeMap::new();
+        for log in logs {
+            if let (Some(block), Some(tx_hash)) = (log.block_number, log.transaction_hash) {
+                by_tx.entry((block.as_u64(), tx_hash)).or_default().push(log);
+            }
+        }
+
+        let mut batched = HashSet::new();
+        for ((_, tx_hash), mut logs) in by_tx.into_iter().filter(|(_, logs)| logs.len() > 1) {
+            logs.sort_by_key(|log| log.log_index);
+            batched.insert(tx_hash);
+            let result = self.handle_order_batch(tx_hash, logs.clone()).await;
+            self.record_handling(EVENT_ORDER_BATCH, &logs, result).await;
+        }
+
+        Ok(batched)
+    }
+
+    /// Apply one transaction's order events atomically
+    async fn handle_order_batch(&self, tx_hash: H256, logs: Vec<Log>) -> Result<Handled, EventListenerError> {
+        let key = logs
+            .first()
+            .map(|log| event_key(EVENT_ORDER_BATCH, log))
+            .ok_or_else(|| EventListenerError::EventDecodeError("Empty order batch".to_string()))?;
+        let mut changes = Vec::with_capacity(logs.len());
+        let mut created = Vec::new();
+        for log in logs {
+            if log.topics.first() == Some(&OrderCreatedAndLockedFilter::signature()) {
+                let created_at = self.block_timestamp(&log).await?;
+                let event: OrderCreatedAndLockedFilter = ethers::contract::parse_log(log)
+                    .map_err(|e| EventListenerError::EventDecodeError(e.to_string()))?;
+                let order = order_from_event(&event, created_at);
+                changes.push(OrderChange::Create(order.clone()));
+                created.push(order);
+            } else {
+                let event: OrderPartiallyWithdrawnFilter = ethers::contract::parse_log(log)
+                    .map_err(|e| EventListenerError::EventDecodeError(e.to_string()))?;
+                changes.push(OrderChange::Withdraw {
+                    order_id: OrderId::from(event.order_id).to_string(),
+                    amount: event.withdrawn_amount.to_string(),
+                });
+            }
+        }
+
+        if self.claim_applying(&key, &changes).await? == Handled::Replayed {
+            return Ok(Handled::Replayed);
+        }
+        tracing::info!(
+            "✅ Order batch {:#x} applied: {} created, {} withdrawn",
+            tx_hash,
+            created.len(),
+            changes.len() - created.len()
+        );
+
+        for order in &created {
+            self.link_order_intent(order).await;
+            self.screen_seller(order).await;
+        }
+
+        Ok(Handled::Applied)
+    }
+
     // ================================================================
     // EVENT HANDLER: OrderCreatedAndLocked
     // ================================================================
@@ -161,10 +578,11 @@ impl EventListener {
         &self,
         from_block: u64,
         to_block: u64,
+        batched: &HashSet<H256>,
     ) -> Result<(), EventListenerError> {
         let filter = Filter::new()
             .address(self.contract_address)
-            .event("OrderCreatedAndLocked(bytes32,address,address,uint256,uint256,string,string)")
+            .event(ORDER_CREATED_SIGNATURE)
             .from_block(from_block)
             .to_block(to_block);
 
@@ -178,23 +596,26 @@ impl EventListener {
             tracing::info!("📦 Found {} OrderCreatedAndLocked events", logs.len());
         }
 
-        for log in logs {
-            if let Err(e) = self.handle_order_created(log).await {
-                tracing::error!("❌ Failed to handle OrderCreatedAndLocked: {}", e);
-            }
+        for log in logs.into_iter().filter(|log| !log.transaction_hash.is_some_and(|h| batched.contains(&h))) {
+            let result = self.handle_order_created(log.clone()).await;
+            self.record_handling(EVENT_ORDER_CREATED, &[log], result).await;
         }
 
         Ok(())
     }
 
     /// Handle a single OrderCreatedAndLocked event
-    async fn handle_order_created(&self, log: Log) -> Result<(), EventListenerError> {
+    async fn handle_order_created(&self, log: Log) -> Result<Handled, EventListenerError> {
+        // On-chain creation time (the contract's createdAt is block.timestamp)
+        let created_at = self.block_timestamp(&log).await?;
+        let key = event_key(EVENT_ORDER_CREATED, &log);
+
         // Decode event
         let event: OrderCreatedAndLockedFilter = ethers::contract::parse_log(log)
             .map_err(|e| EventListenerError::EventDecodeError(e.to_string()))?;
 
         // Format order ID as 0x-prefixed hex string (full 32 bytes = 66 chars with 0x)
-        let order_id = format!("0x{}", hex::encode(event.order_id));
+        let order_id = OrderId::from(event.order_id).to_string();
 
         tracing::info!(
             "📦 OrderCreatedAndLocked:\n  \
@@ -219,19 +640,7 @@ impl EventListener {
         // ============================================================
         
         let order_repo = PostgresOrderRepository::new(self.db_pool.clone());
-        
-        let db_order = DbOrder {
-            order_id: order_id.clone(),
-            seller: format!("{:#x}", event.seller).to_lowercase(),
-            token: format!("{:#x}", event.token).to_lowercase(),
-            total_amount: event.total_amount.to_string(),
-            remaining_amount: event.total_amount.to_string(), // Initially equals totalAmount
-            exchange_rate: event.exchange_rate.to_string(),
-            alipay_id: event.alipay_id.clone(),
-            alipay_name: event.alipay_name.clone(),
-            created_at: chrono::Utc::now().timestamp(),
-            synced_at: chrono::Utc::now(),
-        };
+        let db_order = order_from_event(&event, created_at);
 
         match order_repo.create(&db_order).await {
             Ok(_) => {
@@ -243,7 +652,55 @@ impl EventListener {
             }
         }
 
-        Ok(())
+        if self.claim(&key).await? == Handled::Replayed {
+            return Ok(Handled::Replayed);
+        }
+        self.link_order_intent(&db_order).await;
+        self.screen_seller(&db_order).await;
+
+        Ok(Handled::Applied)
+    }
+
+    /// Screen a new order's seller; the order is mirrored either way, a flag only keeps
+    /// it out of matching (non-critical: failures are logged)
+    async fn screen_seller(&self, order: &DbOrder) {
+        let Some(screening) = &self.screening else {
+            return;
+        };
+        match screening
+            .check(&self.db_pool, &order.seller, ROLE_SELLER, CONTEXT_ORDER_SYNC, Some(&order.order_id))
+            .await
+        {
+            Ok(Ok(())) => {}
+            Ok(Err(refusal)) => tracing::warn!(
+                "🚫 Order {} synced but kept out of matching: seller flagged (refusal #{})",
+                order.order_id,
+                refusal.id
+            ),
+            Err(e) => tracing::error!("❌ Failed to screen the seller of order {}: {}", order.order_id, e),
+        }
+    }
+
+    /// Link a new order to the seller's intent with the same terms ("post then fund")
+    async fn link_order_intent(&self, order: &DbOrder) {
+        let intent_repo = PostgresOrderIntentRepository::new(self.db_pool.clone());
+        let funded = intent_repo
+            .mark_funded(
+                &order.seller,
+                &order.token,
+                Decimal::from_str(&order.total_amount).unwrap_or_default(),
+                Decimal::from_str(&order.exchange_rate).unwrap_or_default(),
+                &format!("{:#x}", alipay_hash(&order.alipay_id, &order.alipay_name)),
+                &order.order_id,
+                chrono::Utc::now().timestamp(),
+            )
+            .await;
+        match funded {
+            Ok(Some(intent_hash)) => tracing::info!("📝 Order {} funds intent {}", order.order_id, intent_hash),
+            Ok(None) => {}
+            // Non-critical: the order itself is synced
+            Err(e) => tracing::error!("❌ Failed to link order {} to an intent: {}", order.order_id, e),
+        }
     }
 
     // ================================================================
@@ -255,10 +712,11 @@ impl EventListener {
         &self,
         from_block: u64,
         to_block: u64,
+        batched: &HashSet<H256>,
     ) -> Result<(), EventListenerError> {
         let filter = Filter::new()
             .address(self.contract_address)
-            .event("OrderPartiallyWithdrawn(bytes32,uint256,uint256)")
+            .event(ORDER_WITHDRAWN_SIGNATURE)
             .from_block(from_block)
             .to_block(to_block);
 
@@ -272,23 +730,24 @@ impl EventListener {
             tracing::info!("📦 Found {} OrderPartiallyWithdrawn events", logs.len());
         }
 
-        for log in logs {
-            if let Err(e) = self.handle_order_withdrawn(log).await {
-                tracing::error!("❌ Failed to handle OrderPartiallyWithdrawn: {}", e);
-            }
+        for log in logs.into_iter().filter(|log| !log.transaction_hash.is_some_and(|h| batched.contains(&h))) {
+            let result = self.handle_order_withdrawn(log.clone()).await;
+            self.record_handling(EVENT_ORDER_WITHDRAWN, &[log], result).await;
         }
 
         Ok(())
     }
 
     /// Handle a single OrderPartiallyWithdrawn event
-    async fn handle_order_withdrawn(&self, log: Log) -> Result<(), EventListenerError> {
+    async fn handle_order_withdrawn(&self, log: Log) -> Result<Handled, EventListenerError> {
+        let key = event_key(EVENT_ORDER_WITHDRAWN, &log);
+
         // Decode event
         let event: OrderPartiallyWithdrawnFilter = ethers::contract::parse_log(log)
             .map_err(|e| EventListenerError::EventDecodeError(e.to_string()))?;
 
         // Format order ID as 0x-prefixed hex string (full 32 bytes = 66 chars with 0x)
-        let order_id = format!("0x{}", hex::encode(event.order_id));
+        let order_id = OrderId::from(event.order_id).to_string();
 
         tracing::info!(
             "💸 OrderPartiallyWithdrawn:\n  \
@@ -304,26 +763,18 @@ impl EventListener {
         // DATABASE SYNC: Adjust order remaining amount (subtract withdrawn amount)
         // ============================================================
         
-        let order_repo = PostgresOrderRepository::new(self.db_pool.clone());
-        
-        // Use negative delta to subtract withdrawn amount
-        let delta = format!("-{}", event.withdrawn_amount);
-        
-        match order_repo.adjust_remaining_amount(&order_id, &delta).await {
-            Ok(_) => {
-                tracing::info!(
-                    "✅ Order {} remaining amount adjusted by {} (withdrawn)",
-                    order_id,
-                    event.withdrawn_amount
-                );
-            }
-            Err(e) => {
-                tracing::error!("❌ Database update failed: {}", e);
-                return Err(EventListenerError::DatabaseError(e.to_string()));
-            }
+        // Claimed with the adjustment: a resync must not subtract it again
+        let change = OrderChange::Withdraw { order_id: order_id.clone(), amount: event.withdrawn_amount.to_string() };
+        let handled = self.claim_applying(&key, &[change]).await?;
+        if handled == Handled::Applied {
+            tracing::info!(
+                "✅ Order {} remaining amount adjusted by -{} (withdrawn)",
+                order_id,
+                event.withdrawn_amount
+            );
         }
 
-        Ok(())
+        Ok(handled)
     }
 
     // ================================================================
@@ -338,7 +789,7 @@ impl EventListener {
     ) -> Result<(), EventListenerError> {
         let filter = Filter::new()
             .address(self.contract_address)
-            .event("TradeCreated(bytes32,bytes32,address,address,uint256,uint256,string,uint256)")
+            .event(TRADE_CREATED_SIGNATURE)
             .from_block(from_block)
             .to_block(to_block);
 
@@ -353,28 +804,38 @@ impl EventListener {
         }
 
         for log in logs {
-            if let Err(e) = self.handle_trade_created(log).await {
-                tracing::error!("❌ Failed to handle TradeCreated: {}", e);
+            let result = self.handle_trade_created(log.clone()).await;
+            if let (Ok((trade_id, Handled::Applied)), Some(notifier)) = (&result, &self.seller_notifier) {
+                // Delivered in the background so a slow webhook doesn't hold up syncing
+                let (trade_id, notifier) = (trade_id.clone(), notifier.clone());
+                tokio::spawn(async move {
+                    if let Err(e) = notifier.notify_trade(&trade_id).await {
+                        tracing::warn!("📨 Seller notice for trade {} failed: {}", trade_id, e);
+                    }
+                });
             }
+            self.record_handling(EVENT_TRADE_CREATED, &[log], result.map(|(_, handled)| handled)).await;
         }
 
         Ok(())
     }
 
-    /// Handle a single TradeCreated event
-    async fn handle_trade_created(&self, log: Log) -> Result<(), EventListenerError> {
+    /// Handle a single TradeCreated event, returning the trade ID
+    async fn handle_trade_created(&self, log: Log) -> Result<(String, Handled), EventListenerError> {
         // Extract transaction hash for escrowTxHash
         let tx_hash = log.transaction_hash
             .map(|h| format!("{:#x}", h))
             .unwrap_or_default();
+        let created_at = self.block_timestamp(&log).await?;
+        let key = event_key(EVENT_TRADE_CREATED, &log);
 
         // Decode event
         let event: TradeCreatedFilter = ethers::contract::parse_log(log)
             .map_err(|e| EventListenerError::EventDecodeError(e.to_string()))?;
 
         // Format IDs as 0x-prefixed hex strings
-        let trade_id = format!("0x{}", hex::encode(event.trade_id));
-        let order_id = format!("0x{}", hex::encode(event.order_id));
+        let trade_id = TradeId::from(event.trade_id).to_string();
+        let order_id = OrderId::from(event.order_id).to_string();
 
         tracing::info!(
             "💱 TradeCreated:\n  \
@@ -400,21 +861,51 @@ impl EventListener {
         // DATABASE SYNC 1: Create trade record
         // ============================================================
         
+        crate::blockchain::types::validate_payment_nonce(&event.payment_nonce)
+            .map_err(|e| EventListenerError::EventDecodeError(format!("Trade {}: {}", trade_id, e)))?;
+
         let trade_repo = PostgresTradeRepository::new(self.db_pool.clone());
+
+        // Nonces are trade ID mod 10^8, so distinct trades can collide. The unique
+        // constraint would reject the insert; surface which trade holds the nonce.
+        if let Some(existing) = trade_repo
+            .get_by_nonce(&event.payment_nonce)
+            .await
+            .map_err(|e| EventListenerError::DatabaseError(e.to_string()))?
+        {
+            if existing.trade_id != trade_id {
+                tracing::error!(
+                    "🚨 Payment nonce collision: {} (new trade {}, existing trade {})",
+                    event.payment_nonce,
+                    trade_id,
+                    existing.trade_id
+                );
+                return Err(EventListenerError::DatabaseError(
+                    crate::db::DbError::NonceCollision {
+                        nonce: event.payment_nonce.clone(),
+                        existing_trade_id: existing.trade_id,
+                    }
+                    .to_string(),
+                ));
+            }
+        }
         
         let db_trade = DbTrade {
             trade_id: trade_id.clone(),
             order_id: order_id.clone(),
-            buyer: format!("{:#x}", event.buyer).to_lowercase(),
+            buyer: event.buyer.into(),
             token_amount: event.token_amount.to_string(),
             cny_amount: event.cny_amount.to_string(),
             payment_nonce: event.payment_nonce.clone(),
-            created_at: chrono::Utc::now().timestamp(),
+            created_at,
             expires_at: event.expires_at.as_u64() as i64,
             status: 0, // PENDING
             synced_at: chrono::Utc::now(),
             escrow_tx_hash: Some(tx_hash),
             settlement_tx_hash: None,
+            settlement_block: None,
+            confirmations: 0,
+            finalized_at: None,
             token: None, // Not available from event (would need separate query)
             pdf_file: None,
             pdf_filename: None,
@@ -425,6 +916,7 @@ impl EventListener {
             axiom_proof_id: None,
             proof_generated_at: None,
             proof_json: None,
+            proof_format: None,
         };
 
         match trade_repo.create(&db_trade).await {
@@ -441,26 +933,19 @@ impl EventListener {
         // DATABASE SYNC 2: Adjust order remaining amount (subtract)
         // ============================================================
         
-        let order_repo = PostgresOrderRepository::new(self.db_pool.clone());
-        
-        // Use negative delta to subtract token amount from order
+        // Use negative delta to subtract token amount from order, claimed with the
+        // adjustment so a resync doesn't subtract it again
         let delta = format!("-{}", event.token_amount);
-        
-        match order_repo.adjust_remaining_amount(&order_id, &delta).await {
-            Ok(_) => {
-                tracing::info!(
-                    "✅ Order {} remaining amount adjusted by {} (trade filled)",
-                    order_id,
-                    event.token_amount
-                );
-            }
-            Err(e) => {
-                tracing::error!("❌ Database update failed: {}", e);
-                return Err(EventListenerError::DatabaseError(e.to_string()));
-            }
+        let handled = self.claim_applying(&key, &[OrderChange::Adjust { order_id: order_id.clone(), delta }]).await?;
+        if handled == Handled::Applied {
+            tracing::info!(
+                "✅ Order {} remaining amount adjusted by -{} (trade filled)",
+                order_id,
+                event.token_amount
+            );
         }
 
-        Ok(())
+        Ok((trade_id, handled))
     }
 
     // ================================================================
@@ -475,9 +960,7 @@ impl EventListener {
     ) -> Result<(), EventListenerError> {
         let filter = Filter::new()
             .address(self.contract_address)
-            .topic0(H256::from_slice(
-                &ethers::utils::keccak256("ProofSubmitted(bytes32,bytes32)"),
-            ))
+            .topic0(H256::from(ethers::utils::keccak256(PROOF_SUBMITTED_SIGNATURE)))
             .from_block(from_block)
             .to_block(to_block);
 
@@ -492,23 +975,23 @@ impl EventListener {
         }
 
         for log in logs {
-            if let Err(e) = self.handle_proof_submitted(log).await {
-                tracing::error!("Failed to handle ProofSubmitted event: {}", e);
-                // Continue processing other events
-            }
+            let result = self.handle_proof_submitted(log.clone()).await;
+            self.record_handling(EVENT_PROOF_SUBMITTED, &[log], result).await;
         }
 
         Ok(())
     }
 
     /// Handle a single ProofSubmitted event
-    async fn handle_proof_submitted(&self, log: Log) -> Result<(), EventListenerError> {
+    async fn handle_proof_submitted(&self, log: Log) -> Result<Handled, EventListenerError> {
+        let key = event_key(EVENT_PROOF_SUBMITTED, &log);
+
         // Decode event
         let event: ProofSubmittedFilter = ethers::contract::parse_log(log)
             .map_err(|e| EventListenerError::EventDecodeError(e.to_string()))?;
 
         // Format trade ID as 0x-prefixed hex string
-        let trade_id = format!("0x{}", hex::encode(event.trade_id));
+        let trade_id = TradeId::from(event.trade_id).to_string();
         
         // Format proof hash as 0x-prefixed hex string
         let proof_hash = format!("0x{}", hex::encode(event.proof_hash));
@@ -537,7 +1020,7 @@ impl EventListener {
             }
         }
 
-        Ok(())
+        self.claim(&key).await
     }
 
     // ================================================================
@@ -552,9 +1035,7 @@ impl EventListener {
     ) -> Result<(), EventListenerError> {
         let filter = Filter::new()
             .address(self.contract_address)
-            .topic0(H256::from_slice(
-                &ethers::utils::keccak256("TradeSettled(bytes32)"),
-            ))
+            .topic0(H256::from(ethers::utils::keccak256(TRADE_SETTLED_SIGNATURE)))
             .from_block(from_block)
             .to_block(to_block);
 
@@ -565,28 +1046,28 @@ impl EventListener {
             .map_err(|e| EventListenerError::ProviderError(e.to_string()))?;
 
         for log in logs {
-            if let Err(e) = self.handle_trade_settled(log).await {
-                tracing::error!("Failed to handle TradeSettled event: {}", e);
-                // Continue processing other events
-            }
+            let result = self.handle_trade_settled(log.clone()).await;
+            self.record_handling(EVENT_TRADE_SETTLED, &[log], result).await;
         }
 
         Ok(())
     }
 
     /// Handle a single TradeSettled event
-    async fn handle_trade_settled(&self, log: Log) -> Result<(), EventListenerError> {
+    async fn handle_trade_settled(&self, log: Log) -> Result<Handled, EventListenerError> {
         // Extract transaction hash for settlementTxHash
         let tx_hash = log.transaction_hash
             .map(|h| format!("{:#x}", h))
             .unwrap_or_default();
+        let block_number = log.block_number.map(|n| n.as_u64() as i64);
+        let key = event_key(EVENT_TRADE_SETTLED, &log);
 
         // Decode event
         let event: TradeSettledFilter = ethers::contract::parse_log(log)
             .map_err(|e| EventListenerError::EventDecodeError(e.to_string()))?;
 
         // Format trade ID as 0x-prefixed hex string
-        let trade_id = format!("0x{}", hex::encode(event.trade_id));
+        let trade_id = TradeId::from(event.trade_id).to_string();
 
         tracing::info!(
             "✅ TradeSettled:\n  \
@@ -625,8 +1106,70 @@ impl EventListener {
                 }
             }
         }
+
+        // Start confirmation tracking at one confirmation (see blockchain::confirmations)
+        if let Some(block) = block_number {
+            let confirmation_repo = PostgresConfirmationRepository::new(self.db_pool.clone());
+            if let Err(e) = confirmation_repo.update(&trade_id, Some(block), 1).await {
+                // Non-critical: the tracker looks the block up from the receipt
+                tracing::error!("❌ Failed to record settlement block for trade {}: {}", trade_id, e);
+            }
+        }
+
+        // Everything here is idempotent per trade, so a replay retries whatever failed
+        // the first time; the claim only tells replays apart in the event counts
+
+        // ============================================================
+        // RECEIPT: Issue signed settlement attestation
+        // ============================================================
         
-        Ok(())
+        if let Some(signer) = &self.receipt_signer {
+            match issue_receipt(&self.db_pool, signer, &trade_id).await {
+                Ok(receipt) => {
+                    tracing::info!("🧾 Trade {} receipt issued (signer {})", trade_id, receipt.signer);
+                }
+                Err(e) => {
+                    // Non-critical: receipt is issued lazily on first download
+                    tracing::error!("❌ Failed to issue receipt for trade {}: {}", trade_id, e);
+                }
+            }
+        }
+
+        // ============================================================
+        // FEES: Accrue operator fee against the paying side
+        // ============================================================
+
+        if let Some(engine) = &self.fee_engine {
+            match accrue_fee(&self.db_pool, engine, &trade_id).await {
+                Ok(quote) => {
+                    tracing::info!(
+                        "💸 Trade {} fee accrued ({}): {} token units / {} CNY cents",
+                        trade_id,
+                        engine.payer.as_str(),
+                        quote.token_fee,
+                        quote.cny_fee
+                    );
+                }
+                Err(e) => {
+                    // Non-critical: settlement is already recorded
+                    tracing::error!("❌ Failed to accrue fee for trade {}: {}", trade_id, e);
+                }
+            }
+        }
+
+        // ============================================================
+        // ATTESTATION: Queue the trade's EAS attestation (sent once final)
+        // ============================================================
+
+        if self.attest_settlements {
+            let attestation_repo = PostgresTradeAttestationRepository::new(self.db_pool.clone());
+            if let Err(e) = attestation_repo.enqueue(&trade_id).await {
+                // Non-critical: the settlement itself is recorded
+                tracing::error!("❌ Failed to queue attestation for trade {}: {}", trade_id, e);
+            }
+        }
+        
+        self.claim(&key).await
     }
 
     // ================================================================
@@ -641,9 +1184,7 @@ impl EventListener {
     ) -> Result<(), EventListenerError> {
         let filter = Filter::new()
             .address(self.contract_address)
-            .topic0(H256::from_slice(
-                &ethers::utils::keccak256("TradeExpired(bytes32,bytes32,uint256)"),
-            ))
+            .topic0(H256::from(ethers::utils::keccak256(TRADE_EXPIRED_SIGNATURE)))
             .from_block(from_block)
             .to_block(to_block);
 
@@ -654,24 +1195,24 @@ impl EventListener {
             .map_err(|e| EventListenerError::ProviderError(e.to_string()))?;
 
         for log in logs {
-            if let Err(e) = self.handle_trade_expired(log).await {
-                tracing::error!("Failed to handle TradeExpired event: {}", e);
-                // Continue processing other events
-            }
+            let result = self.handle_trade_expired(log.clone()).await;
+            self.record_handling(EVENT_TRADE_EXPIRED, &[log], result).await;
         }
 
         Ok(())
     }
 
     /// Handle a single TradeExpired event
-    async fn handle_trade_expired(&self, log: Log) -> Result<(), EventListenerError> {
+    async fn handle_trade_expired(&self, log: Log) -> Result<Handled, EventListenerError> {
+        let key = event_key(EVENT_TRADE_EXPIRED, &log);
+
         // Decode event
         let event: TradeExpiredFilter = ethers::contract::parse_log(log)
             .map_err(|e| EventListenerError::EventDecodeError(e.to_string()))?;
 
         // Format IDs as 0x-prefixed hex strings
-        let trade_id = format!("0x{}", hex::encode(event.trade_id));
-        let order_id = format!("0x{}", hex::encode(event.order_id));
+        let trade_id = TradeId::from(event.trade_id).to_string();
+        let order_id = OrderId::from(event.order_id).to_string();
 
         tracing::info!(
             "⏰ TradeExpired:\n  \
@@ -704,28 +1245,134 @@ impl EventListener {
         // DATABASE SYNC 2: Adjust order remaining amount (add back)
         // ============================================================
         
-        let order_repo = PostgresOrderRepository::new(self.db_pool.clone());
-        
-        // Use positive delta to add token amount back to order
+        // Use positive delta to add token amount back to order, claimed with the
+        // adjustment so a resync doesn't add it again
         let delta = event.token_amount.to_string();
-        
-        match order_repo.adjust_remaining_amount(&order_id, &delta).await {
-            Ok(_) => {
-                tracing::info!(
-                    "✅ Order {} remaining amount adjusted by +{} (trade expired)",
-                    order_id,
-                    event.token_amount
-                );
-            }
-            Err(e) => {
-                tracing::error!("❌ Database update failed: {}", e);
-                return Err(EventListenerError::DatabaseError(e.to_string()));
-            }
+        let handled = self.claim_applying(&key, &[OrderChange::Adjust { order_id: order_id.clone(), delta }]).await?;
+        if handled == Handled::Applied {
+            tracing::info!(
+                "✅ Order {} remaining amount adjusted by +{} (trade expired)",
+                order_id,
+                event.token_amount
+            );
+        }
+
+        Ok(handled)
+    }
+
+    // ================================================================
+    // EVENT HANDLER: Contract config changes
+    // (ConfigUpdated, ZkVerifierUpdated, ZkPDFConfigUpdated, Paused, Unpaused)
+    // ================================================================
+
+    /// Process admin config-change events
+    async fn process_config_events(
+        &self,
+        from_block: u64,
+        to_block: u64,
+    ) -> Result<(), EventListenerError> {
+        let topics: Vec<H256> = CONFIG_EVENT_SIGNATURES
+            .iter()
+            .map(|sig| H256::from(ethers::utils::keccak256(sig)))
+            .collect();
+
+        let filter = Filter::new()
+            .address(self.contract_address)
+            .topic0(topics)
+            .from_block(from_block)
+            .to_block(to_block);
+
+        let logs = self
+            .provider
+            .get_logs(&filter)
+            .await
+            .map_err(|e| EventListenerError::ProviderError(e.to_string()))?;
+
+        for log in logs {
+            let result = self.handle_config_event(log.clone()).await;
+            self.record_handling(EVENT_CONFIG, &[log], result).await;
         }
 
         Ok(())
     }
 
+    /// Handle a single config-change event: update the cached config and record it
+    async fn handle_config_event(&self, log: Log) -> Result<Handled, EventListenerError> {
+        let key = event_key(EVENT_CONFIG, &log);
+
+        let event = ZkAliPayEscrowEvents::decode_log(&ethers::abi::RawLog::from(log))
+            .map_err(|e| EventListenerError::EventDecodeError(e.to_string()))?;
+
+        let (event_name, details) = match event {
+            ZkAliPayEscrowEvents::ConfigUpdatedFilter(e) => {
+                // The event doesn't carry every field (e.g. min trade value), so refetch on next read
+                if let Some(cache) = &self.config_cache {
+                    cache.invalidate().await;
+                }
+                if let Some(reads) = &self.read_cache {
+                    reads.invalidate_config();
+                }
+                ("ConfigUpdated", serde_json::json!({
+                    "max_trade_value_cny": e.max_order_size.to_string(),
+                    "payment_window": e.payment_window.to_string(),
+                }))
+            }
+            ZkAliPayEscrowEvents::ZkVerifierUpdatedFilter(e) => {
+                if let Some(cache) = &self.config_cache {
+                    cache.update(|c| c.zk_verifier = e.new_verifier).await;
+                }
+                ("ZkVerifierUpdated", serde_json::json!({
+                    "old_verifier": format!("{:#x}", e.old_verifier),
+                    "new_verifier": format!("{:#x}", e.new_verifier),
+                }))
+            }
+            ZkAliPayEscrowEvents::ZkPDFConfigUpdatedFilter(e) => {
+                if let Some(cache) = &self.config_cache {
+                    cache.update(|c| {
+                        c.public_key_der_hash = e.public_key_der_hash;
+                        c.app_exe_commit = e.app_exe_commit;
+                        c.app_vm_commit = e.app_vm_commit;
+                    }).await;
+                }
+                if let Some(reads) = &self.read_cache {
+                    reads.set_public_key_der_hash(e.public_key_der_hash);
+                }
+                ("ZkPDFConfigUpdated", serde_json::json!({
+                    "public_key_der_hash": format!("0x{}", hex::encode(e.public_key_der_hash)),
+                    "app_exe_commit": format!("0x{}", hex::encode(e.app_exe_commit)),
+                    "app_vm_commit": format!("0x{}", hex::encode(e.app_vm_commit)),
+                }))
+            }
+            ZkAliPayEscrowEvents::PausedFilter(e) => {
+                if let Some(cache) = &self.config_cache {
+                    cache.update(|c| c.paused = true).await;
+                }
+                ("Paused", serde_json::json!({ "account": format!("{:#x}", e.account) }))
+            }
+            ZkAliPayEscrowEvents::UnpausedFilter(e) => {
+                if let Some(cache) = &self.config_cache {
+                    cache.update(|c| c.paused = false).await;
+                }
+                ("Unpaused", serde_json::json!({ "account": format!("{:#x}", e.account) }))
+            }
+            other => {
+                return Err(EventListenerError::EventDecodeError(format!(
+                    "Unexpected event in config filter: {:?}",
+                    other
+                )));
+            }
+        };
+
+        tracing::info!("⚙️  {}: {} (tx {})", event_name, details, key.tx_hash);
+
+        PostgresConfigEventRepository::new(self.db_pool.clone())
+            .create(event_name, &details.to_string(), key.block_number, &key.tx_hash, key.log_index)
+            .await
+            .map_err(|e| EventListenerError::DatabaseError(e.to_string()))?;
+
+        self.claim(&key).await
+    }
+
     // ================================================================
     // DATABASE HELPERS: Track last synced block
     // ================================================================
@@ -736,15 +1383,15 @@ impl EventListener {
         contract_address: &Address,
     ) -> Result<u64, EventListenerError> {
         let addr = format!("{:#x}", contract_address).to_lowercase();
-        let row: (i64,) = sqlx::query_as(
+        let last_synced_block = sqlx::query_scalar!(
             "SELECT last_synced_block FROM event_sync_state WHERE contract_address = $1",
+            addr
         )
-        .bind(&addr)
         .fetch_one(pool)
         .await
         .map_err(|e| EventListenerError::DatabaseError(e.to_string()))?;
 
-        Ok(row.0 as u64)
+        Ok(last_synced_block as u64)
     }
 
     /// Save the last synced block to database
@@ -754,14 +1401,14 @@ impl EventListener {
         block: u64,
     ) -> Result<(), EventListenerError> {
         let addr = format!("{:#x}", contract_address).to_lowercase();
-        sqlx::query(
+        sqlx::query!(
             "INSERT INTO event_sync_state (contract_address, last_synced_block) 
              VALUES ($1, $2) 
              ON CONFLICT (contract_address) 
-             DO UPDATE SET last_synced_block = $2",
+             DO UPDATE SET last_synced_block = $2, last_synced_at = NOW()",
+            addr,
+            block as i64
         )
-        .bind(&addr)
-        .bind(block as i64)
         .execute(pool)
         .await
         .map_err(|e| EventListenerError::DatabaseError(e.to_string()))?;
@@ -769,3 +1416,32 @@ impl EventListener {
         Ok(())
     }
 }
+
+/// Claim key of a log: its transaction hash and index
+fn event_key(event_type: &str, log: &Log) -> EventKey {
+    EventKey {
+        event_type: event_type.to_string(),
+        block_number: log.block_number.map(|b| b.as_u64() as i64).unwrap_or_default(),
+        tx_hash: format!("{:#x}", log.transaction_hash.unwrap_or_default()),
+        log_index: log.log_index.map(|i| i.as_u64() as i64).unwrap_or_default(),
+    }
+}
+
+/// Order row for an OrderCreatedAndLocked event
+fn order_from_event(event: &OrderCreatedAndLockedFilter, created_at: i64) -> DbOrder {
+    DbOrder {
+        order_id: OrderId::from(event.order_id).to_string(),
+        seller: event.seller.into(),
+        token: event.token.into(),
+        total_amount: event.total_amount.to_string(),
+        remaining_amount: event.total_amount.to_string(), // Initially equals totalAmount
+        exchange_rate: event.exchange_rate.to_string(),
+        alipay_id: event.alipay_id.clone(),
+        alipay_name: event.alipay_name.clone(),
+        created_at,
+        synced_at: chrono::Utc::now(),
+        alipay_id_format: AlipayIdFormat::detect(&event.alipay_id).to_string(),
+        min_fill: None,
+        lot_size: None,
+    }
+}
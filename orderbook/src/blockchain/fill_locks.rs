@@ -0,0 +1,75 @@
+// Per-order fill serialization
+// Two buyers whose match plans overlap on an order used to send fillOrder for it at the
+// same time; whichever was mined second reverted once the first had taken the liquidity,
+// after its gas estimation and submission had been paid for. The relayer now holds the
+// order's lock from the remaining-amount check until the fill's receipt, so fills of one
+// order go out one at a time while fills of different orders still run in parallel.
+// The locks are per process: fills sent by another replica are only caught by the
+// remaining-amount check.
+
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use tokio::sync::OwnedMutexGuard;
+
+use super::ids::OrderId;
+
+/// Held while a fill of the order is checked and sent
+pub type FillGuard = OwnedMutexGuard<()>;
+
+/// One lock per order with a fill in flight
+#[derive(Debug, Default)]
+pub struct FillLocks {
+    locks: Mutex<HashMap<OrderId, Arc<tokio::sync::Mutex<()>>>>,
+}
+
+impl FillLocks {
+    /// Wait until no other fill of `order_id` is in flight
+    pub async fn lock(&self, order_id: &OrderId) -> FillGuard {
+        let lock = {
+            let mut locks = self.locks.lock().unwrap();
+            // Drop locks nobody holds or waits for (only the map references them)
+            locks.retain(|_, lock| Arc::strong_count(lock) > 1);
+            locks.entry(*order_id).or_default().clone()
+        };
+        lock.lock_owned().await
+    }
+
+    /// Orders with a fill in flight or waiting
+    pub fn len(&self) -> usize {
+        self.locks.lock().unwrap().values().filter(|lock| Arc::strong_count(lock) > 1).count()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    #[tokio::test]
+    async fn test_fills_of_one_order_are_serialized() {
+        let locks = Arc::new(FillLocks::default());
+        let order: OrderId = format!("0x{}", "11".repeat(32)).parse().unwrap();
+        let other: OrderId = format!("0x{}", "22".repeat(32)).parse().unwrap();
+
+        let guard = locks.lock(&order).await;
+        // Another order isn't held up
+        let other_guard = tokio::time::timeout(Duration::from_millis(100), locks.lock(&other)).await.unwrap();
+
+        let waiting = tokio::spawn({
+            let locks = locks.clone();
+            async move { locks.lock(&order).await }
+        });
+        tokio::time::sleep(Duration::from_millis(50)).await;
+        assert!(!waiting.is_finished());
+        assert_eq!(locks.len(), 2);
+
+        drop(guard);
+        drop(waiting.await.unwrap());
+        drop(other_guard);
+        assert!(locks.is_empty());
+    }
+}
@@ -0,0 +1,445 @@
+// Captured contract event logs
+// Raw logs (topics and ABI-encoded data, as eth_getLogs returns them) for every event
+// type the listener handles, with the values they encode. The bytes are fixed: a
+// changed event signature (topic0) or parameter order/type in the ABI or in the
+// listener's filters makes the golden tests fail - the decode tests below and the
+// replay test in tests/db_tests.rs, which runs LIFECYCLE and CONFIG_CHANGES through
+// EventListener::handle_logs and checks the rows they leave - instead of logs silently
+// failing to decode in production.
+//
+// LIFECYCLE is one order's history in block order: created (1,000 tokens at 7.35 CNY),
+// a 100-token trade that is proven and settled, a 50-token trade that expires, and a
+// 200-token withdrawal, leaving 700 tokens.
+
+use ethers::types::{Bytes, Log, H256, U256};
+
+use super::events::{
+    EVENT_CONFIG, EVENT_ORDER_CREATED, EVENT_ORDER_WITHDRAWN, EVENT_PROOF_SUBMITTED, EVENT_TRADE_CREATED,
+    EVENT_TRADE_EXPIRED, EVENT_TRADE_SETTLED,
+};
+
+/// A raw contract log and the listener event type it is handled as
+#[derive(Debug, Clone, Copy)]
+pub struct EventFixture {
+    pub event_type: &'static str,
+    pub signature: &'static str,
+    pub topics: &'static [&'static str],
+    pub data: &'static str,
+    pub block_number: u64,
+    pub tx_hash: &'static str,
+    pub log_index: u64,
+}
+
+impl EventFixture {
+    /// The log as the provider returns it
+    pub fn log(&self) -> Log {
+        Log {
+            address: CONTRACT.parse().expect("fixture contract address"),
+            topics: self.topics.iter().map(|topic| topic.parse().expect("fixture topic")).collect(),
+            data: self.data.parse::<Bytes>().expect("fixture data"),
+            block_number: Some(self.block_number.into()),
+            transaction_hash: Some(self.tx_hash.parse::<H256>().expect("fixture tx hash")),
+            log_index: Some(U256::from(self.log_index)),
+            ..Default::default()
+        }
+    }
+}
+
+/// Escrow the fixtures were emitted by
+pub const CONTRACT: &str = "0x5fbdb2315678afecb367f032d93f642f64180aa3";
+
+// Values the fixtures encode
+pub const ORDER_ID: &str = "0x03987614efcfe1a6e71587e58498b1bd329545b73760e805fd71e25d59e0ed66";
+pub const TRADE_ID: &str = "0x4cfd2294b1b53a67fa0ef80a3045b482c40746c8d24a7fc9e13fb8d0603ba2f2";
+pub const EXPIRED_TRADE_ID: &str = "0x55221325d683a81cb4784c312f9dac9f4e59df9eb6191c63bbe25921671afc62";
+pub const PROOF_HASH: &str = "0x7fedc5d1ec36d788da1ae17f5858380b5f9d758b308a6e5d5c5f17048a152abf";
+pub const SELLER: &str = "0x70997970c51812dc3a010c7d01b50e0d17dc79c8";
+pub const BUYER: &str = "0x3c44cdddb6a900fa2b585dd299e03d12fa4293bc";
+pub const TOKEN: &str = "0x036cbd53842c5426634e7929541ec2318f3dcf7e";
+pub const OWNER: &str = "0xf39fd6e51aad88f6f4ce6ab8827279cfffb92266";
+pub const OLD_VERIFIER: &str = "0x5fbdb2315678afecb367f032d93f642f64180aa3";
+pub const NEW_VERIFIER: &str = "0xe7f1725e7734ce288f8367e1bb143e90bb3f0512";
+pub const PUBLIC_KEY_DER_HASH: &str = "0xa6c533c9643484a954a7798bfee429b0b119de312cdfa4e0a30accd4991666ab";
+pub const APP_EXE_COMMIT: &str = "0x9cd90835917fab28cfb184a820044dec4758fb9a1b6781dcd1936f67a0e792ed";
+pub const APP_VM_COMMIT: &str = "0xbadd4e78b0d8993a5bf935de32ae7eda9e92150e9a23bb356fec73343b9cf4b7";
+pub const ALIPAY_ID: &str = "13800138000";
+pub const ALIPAY_NAME: &str = "张三";
+pub const TRADE_NONCE: &str = "48151623";
+pub const EXPIRED_TRADE_NONCE: &str = "42424242";
+
+/// Timestamps of the blocks whose handlers stamp rows with them
+pub const BLOCK_TIMES: [(u64, i64); 3] = [(100, 1_718_000_000), (101, 1_718_000_012), (104, 1_718_000_048)];
+
+pub const ORDER_CREATED: EventFixture = EventFixture {
+    event_type: EVENT_ORDER_CREATED,
+    signature: "OrderCreatedAndLocked(bytes32,address,address,uint256,uint256,string,string)",
+    topics: &[
+        "0xb2950a7fb38b25d07f8dfc3ed49d71143616918ef29f49fbc252d1df1b4b62f3",
+        "0x03987614efcfe1a6e71587e58498b1bd329545b73760e805fd71e25d59e0ed66",
+        "0x00000000000000000000000070997970c51812dc3a010c7d01b50e0d17dc79c8",
+        "0x000000000000000000000000036cbd53842c5426634e7929541ec2318f3dcf7e",
+    ],
+    data: concat!(
+        "0x",
+        "000000000000000000000000000000000000000000000000000000003b9aca00",
+        "00000000000000000000000000000000000000000000000000000000000002df",
+        "0000000000000000000000000000000000000000000000000000000000000080",
+        "00000000000000000000000000000000000000000000000000000000000000c0",
+        "000000000000000000000000000000000000000000000000000000000000000b",
+        "3133383030313338303030000000000000000000000000000000000000000000",
+        "0000000000000000000000000000000000000000000000000000000000000006",
+        "e5bca0e4b8890000000000000000000000000000000000000000000000000000"
+    ),
+    block_number: 100,
+    tx_hash: "0x4f6f58f339b51979081c1339b9d4542907741d335b56964a4dca5d0fe7c904d5",
+    log_index: 0,
+};
+
+pub const TRADE_CREATED: EventFixture = EventFixture {
+    event_type: EVENT_TRADE_CREATED,
+    signature: "TradeCreated(bytes32,bytes32,address,address,uint256,uint256,string,uint256)",
+    topics: &[
+        "0x7da12809aa3d9b9dd63eb15f6dd4dcf8fb8048f02a3dde3abf03b472289e218f",
+        "0x4cfd2294b1b53a67fa0ef80a3045b482c40746c8d24a7fc9e13fb8d0603ba2f2",
+        "0x03987614efcfe1a6e71587e58498b1bd329545b73760e805fd71e25d59e0ed66",
+        "0x0000000000000000000000003c44cdddb6a900fa2b585dd299e03d12fa4293bc",
+    ],
+    data: concat!(
+        "0x",
+        "000000000000000000000000036cbd53842c5426634e7929541ec2318f3dcf7e",
+        "0000000000000000000000000000000000000000000000000000000005f5e100",
+        "0000000000000000000000000000000000000000000000000000000000011f1c",
+        "00000000000000000000000000000000000000000000000000000000000000a0",
+        "0000000000000000000000000000000000000000000000000000000066669d10",
+        "0000000000000000000000000000000000000000000000000000000000000008",
+        "3438313531363233000000000000000000000000000000000000000000000000"
+    ),
+    block_number: 101,
+    tx_hash: "0xc94813135a260e8755eb7a141d983e82346a08dd9217de257195971565e89288",
+    log_index: 0,
+};
+
+pub const PROOF_SUBMITTED: EventFixture = EventFixture {
+    event_type: EVENT_PROOF_SUBMITTED,
+    signature: "ProofSubmitted(bytes32,bytes32)",
+    topics: &[
+        "0x7a47e9847f34fd5c9a8cbe3dacf9926ef72d82d83a9c28a4044ecc80353c115f",
+        "0x4cfd2294b1b53a67fa0ef80a3045b482c40746c8d24a7fc9e13fb8d0603ba2f2",
+    ],
+    data: concat!(
+        "0x",
+        "7fedc5d1ec36d788da1ae17f5858380b5f9d758b308a6e5d5c5f17048a152abf"
+    ),
+    block_number: 102,
+    tx_hash: "0x5c3f7068169df3acc3c54bb05fef631431f0687bfb840b91859bf4e72f40e4a9",
+    log_index: 0,
+};
+
+pub const TRADE_SETTLED: EventFixture = EventFixture {
+    event_type: EVENT_TRADE_SETTLED,
+    signature: "TradeSettled(bytes32)",
+    topics: &[
+        "0xc00a7697d7b89194c96bfa130753296c02f33448167ad061fedd39d4a4df77fc",
+        "0x4cfd2294b1b53a67fa0ef80a3045b482c40746c8d24a7fc9e13fb8d0603ba2f2",
+    ],
+    data: "0x",
+    block_number: 103,
+    tx_hash: "0x754716a377ca89a045b471f24228c66cbc2435148eee82ca4456e52577be93e7",
+    log_index: 0,
+};
+
+pub const EXPIRING_TRADE_CREATED: EventFixture = EventFixture {
+    event_type: EVENT_TRADE_CREATED,
+    signature: "TradeCreated(bytes32,bytes32,address,address,uint256,uint256,string,uint256)",
+    topics: &[
+        "0x7da12809aa3d9b9dd63eb15f6dd4dcf8fb8048f02a3dde3abf03b472289e218f",
+        "0x55221325d683a81cb4784c312f9dac9f4e59df9eb6191c63bbe25921671afc62",
+        "0x03987614efcfe1a6e71587e58498b1bd329545b73760e805fd71e25d59e0ed66",
+        "0x0000000000000000000000003c44cdddb6a900fa2b585dd299e03d12fa4293bc",
+    ],
+    data: concat!(
+        "0x",
+        "000000000000000000000000036cbd53842c5426634e7929541ec2318f3dcf7e",
+        "0000000000000000000000000000000000000000000000000000000002faf080",
+        "0000000000000000000000000000000000000000000000000000000000008f8e",
+        "00000000000000000000000000000000000000000000000000000000000000a0",
+        "0000000000000000000000000000000000000000000000000000000066669d34",
+        "0000000000000000000000000000000000000000000000000000000000000008",
+        "3432343234323432000000000000000000000000000000000000000000000000"
+    ),
+    block_number: 104,
+    tx_hash: "0xbbef06569b5fc89962dbb4f60b195b050ef60754d942e593dba560551fd61cdb",
+    log_index: 0,
+};
+
+pub const TRADE_EXPIRED: EventFixture = EventFixture {
+    event_type: EVENT_TRADE_EXPIRED,
+    signature: "TradeExpired(bytes32,bytes32,uint256)",
+    topics: &[
+        "0xe8bf8cb38bf5f2c3f667ddb2da5a2102e36076876e664f9a9b7b9ac023b9ca3e",
+        "0x55221325d683a81cb4784c312f9dac9f4e59df9eb6191c63bbe25921671afc62",
+        "0x03987614efcfe1a6e71587e58498b1bd329545b73760e805fd71e25d59e0ed66",
+    ],
+    data: concat!(
+        "0x",
+        "0000000000000000000000000000000000000000000000000000000002faf080"
+    ),
+    block_number: 180,
+    tx_hash: "0x3353cd71b05ae85afbaaca679caf8fb08843fedd78f6d1636d526a406fb4c6f2",
+    log_index: 0,
+};
+
+pub const ORDER_WITHDRAWN: EventFixture = EventFixture {
+    event_type: EVENT_ORDER_WITHDRAWN,
+    signature: "OrderPartiallyWithdrawn(bytes32,uint256,uint256)",
+    topics: &[
+        "0xd51d74a779099a0492e0eb19d230e974d6cf5623cd5cce7c367262e09556e9b5",
+        "0x03987614efcfe1a6e71587e58498b1bd329545b73760e805fd71e25d59e0ed66",
+    ],
+    data: concat!(
+        "0x",
+        "000000000000000000000000000000000000000000000000000000000bebc200",
+        "0000000000000000000000000000000000000000000000000000000029b92700"
+    ),
+    block_number: 181,
+    tx_hash: "0xa8b6ccd784670381a0978494479f64521d259338ffed5aa82dff496981bdd8ca",
+    log_index: 0,
+};
+
+pub const CONFIG_UPDATED: EventFixture = EventFixture {
+    event_type: EVENT_CONFIG,
+    signature: "ConfigUpdated(uint256,uint256)",
+    topics: &[
+        "0x0936b3616d5072fa8f7381ef8e7c35d39bed9ecfb2184e908a56027ad5c0b6bc",
+    ],
+    data: concat!(
+        "0x",
+        "00000000000000000000000000000000000000000000000000000000006ddd00",
+        "0000000000000000000000000000000000000000000000000000000000000384"
+    ),
+    block_number: 182,
+    tx_hash: "0x80b47072d06d4341bc09f4a35e1dc3caef5e2b6be8e0add310eb4b62ce81778b",
+    log_index: 0,
+};
+
+pub const ZK_VERIFIER_UPDATED: EventFixture = EventFixture {
+    event_type: EVENT_CONFIG,
+    signature: "ZkVerifierUpdated(address,address)",
+    topics: &[
+        "0xf335fdec5c467dfdc8bca7991b97cb3bc62c88c8467dedce3044baff0527cad6",
+        "0x0000000000000000000000005fbdb2315678afecb367f032d93f642f64180aa3",
+        "0x000000000000000000000000e7f1725e7734ce288f8367e1bb143e90bb3f0512",
+    ],
+    data: "0x",
+    block_number: 183,
+    tx_hash: "0x13780c89b7e0062a95b50c73fe18dfed4ca501fafb2ff064234db39ab167fbd6",
+    log_index: 0,
+};
+
+pub const ZK_PDF_CONFIG_UPDATED: EventFixture = EventFixture {
+    event_type: EVENT_CONFIG,
+    signature: "ZkPDFConfigUpdated(bytes32,bytes32,bytes32)",
+    topics: &[
+        "0x8e26a4d6d5e5fff2597d3cffd592949d56c44e31ae67d2d9034ece64b43ad765",
+    ],
+    data: concat!(
+        "0x",
+        "a6c533c9643484a954a7798bfee429b0b119de312cdfa4e0a30accd4991666ab",
+        "9cd90835917fab28cfb184a820044dec4758fb9a1b6781dcd1936f67a0e792ed",
+        "badd4e78b0d8993a5bf935de32ae7eda9e92150e9a23bb356fec73343b9cf4b7"
+    ),
+    block_number: 184,
+    tx_hash: "0xad179b905163e90a2117b56e2c16aceca4b7d7aa98dee961a901c7f7746dba10",
+    log_index: 0,
+};
+
+pub const PAUSED: EventFixture = EventFixture {
+    event_type: EVENT_CONFIG,
+    signature: "Paused(address)",
+    topics: &[
+        "0x62e78cea01bee320cd4e420270b5ea74000d11b0c9f74754ebdbfc544b05a258",
+    ],
+    data: concat!(
+        "0x",
+        "000000000000000000000000f39fd6e51aad88f6f4ce6ab8827279cfffb92266"
+    ),
+    block_number: 185,
+    tx_hash: "0x26932f3d2628fe4021db155e32b41b6a4ca85aa0a00a2e1846d81cd3a7841a26",
+    log_index: 0,
+};
+
+pub const UNPAUSED: EventFixture = EventFixture {
+    event_type: EVENT_CONFIG,
+    signature: "Unpaused(address)",
+    topics: &[
+        "0x5db9ee0a495bf2e6ff9c91a7834c1ba4fdd244a5e8aa4e537bd38aeae4b073aa",
+    ],
+    data: concat!(
+        "0x",
+        "000000000000000000000000f39fd6e51aad88f6f4ce6ab8827279cfffb92266"
+    ),
+    block_number: 186,
+    tx_hash: "0x96c9d3ef3578178b3f9a879135e11dc1dd98f7b0fc104b88f09acde2a58db29a",
+    log_index: 0,
+};
+
+/// One order's events, in block order
+pub const LIFECYCLE: [EventFixture; 7] = [
+    ORDER_CREATED,
+    TRADE_CREATED,
+    PROOF_SUBMITTED,
+    TRADE_SETTLED,
+    EXPIRING_TRADE_CREATED,
+    TRADE_EXPIRED,
+    ORDER_WITHDRAWN,
+];
+
+/// Admin config-change events, in block order
+pub const CONFIG_CHANGES: [EventFixture; 5] = [CONFIG_UPDATED, ZK_VERIFIER_UPDATED, ZK_PDF_CONFIG_UPDATED, PAUSED, UNPAUSED];
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::blockchain::events::{
+        CONFIG_EVENT_SIGNATURES, ORDER_CREATED_SIGNATURE, ORDER_WITHDRAWN_SIGNATURE, PROOF_SUBMITTED_SIGNATURE,
+        TRADE_CREATED_SIGNATURE, TRADE_EXPIRED_SIGNATURE, TRADE_SETTLED_SIGNATURE,
+    };
+    use crate::blockchain::ZkAliPayEscrowEvents;
+    use ethers::contract::EthLogDecode;
+    use ethers::types::Address;
+
+    fn decode(fixture: &EventFixture) -> ZkAliPayEscrowEvents {
+        ZkAliPayEscrowEvents::decode_log(&ethers::abi::RawLog::from(fixture.log()))
+            .unwrap_or_else(|e| panic!("{} doesn't decode: {}", fixture.signature, e))
+    }
+
+    fn h256(hex: &str) -> [u8; 32] {
+        hex.parse::<H256>().unwrap().0
+    }
+
+    fn address(hex: &str) -> Address {
+        hex.parse().unwrap()
+    }
+
+    #[test]
+    fn test_fixture_topics_match_listener_filters() {
+        for fixture in LIFECYCLE.iter().chain(&CONFIG_CHANGES) {
+            // The listener filters on the signature the captured topic0 hashes
+            let filtered = match fixture.event_type {
+                EVENT_ORDER_CREATED => vec![ORDER_CREATED_SIGNATURE],
+                EVENT_ORDER_WITHDRAWN => vec![ORDER_WITHDRAWN_SIGNATURE],
+                EVENT_TRADE_CREATED => vec![TRADE_CREATED_SIGNATURE],
+                EVENT_PROOF_SUBMITTED => vec![PROOF_SUBMITTED_SIGNATURE],
+                EVENT_TRADE_SETTLED => vec![TRADE_SETTLED_SIGNATURE],
+                EVENT_TRADE_EXPIRED => vec![TRADE_EXPIRED_SIGNATURE],
+                EVENT_CONFIG => CONFIG_EVENT_SIGNATURES.to_vec(),
+                other => panic!("Unhandled event type {}", other),
+            };
+            assert!(filtered.contains(&fixture.signature), "{} isn't filtered for", fixture.signature);
+            assert_eq!(
+                H256::from(ethers::utils::keccak256(fixture.signature)),
+                fixture.topics[0].parse::<H256>().unwrap(),
+                "{} no longer hashes to the captured topic0",
+                fixture.signature
+            );
+        }
+    }
+
+    #[test]
+    fn test_lifecycle_fixtures_decode_to_golden_values() {
+        match decode(&ORDER_CREATED) {
+            ZkAliPayEscrowEvents::OrderCreatedAndLockedFilter(e) => {
+                assert_eq!(e.order_id, h256(ORDER_ID));
+                assert_eq!(e.seller, address(SELLER));
+                assert_eq!(e.token, address(TOKEN));
+                assert_eq!(e.total_amount, U256::from(1_000_000_000u64));
+                assert_eq!(e.exchange_rate, U256::from(735));
+                assert_eq!(e.alipay_id, ALIPAY_ID);
+                assert_eq!(e.alipay_name, ALIPAY_NAME);
+            }
+            other => panic!("OrderCreatedAndLocked decoded as {:?}", other),
+        }
+        match decode(&TRADE_CREATED) {
+            ZkAliPayEscrowEvents::TradeCreatedFilter(e) => {
+                assert_eq!(e.trade_id, h256(TRADE_ID));
+                assert_eq!(e.order_id, h256(ORDER_ID));
+                assert_eq!(e.buyer, address(BUYER));
+                assert_eq!(e.token, address(TOKEN));
+                assert_eq!(e.token_amount, U256::from(100_000_000u64));
+                assert_eq!(e.cny_amount, U256::from(73_500));
+                assert_eq!(e.payment_nonce, TRADE_NONCE);
+                assert_eq!(e.expires_at, U256::from(1_718_000_912u64));
+            }
+            other => panic!("TradeCreated decoded as {:?}", other),
+        }
+        match decode(&EXPIRING_TRADE_CREATED) {
+            ZkAliPayEscrowEvents::TradeCreatedFilter(e) => {
+                assert_eq!(e.trade_id, h256(EXPIRED_TRADE_ID));
+                assert_eq!(e.token_amount, U256::from(50_000_000u64));
+                assert_eq!(e.payment_nonce, EXPIRED_TRADE_NONCE);
+            }
+            other => panic!("TradeCreated decoded as {:?}", other),
+        }
+        match decode(&PROOF_SUBMITTED) {
+            ZkAliPayEscrowEvents::ProofSubmittedFilter(e) => {
+                assert_eq!(e.trade_id, h256(TRADE_ID));
+                assert_eq!(e.proof_hash, h256(PROOF_HASH));
+            }
+            other => panic!("ProofSubmitted decoded as {:?}", other),
+        }
+        match decode(&TRADE_SETTLED) {
+            ZkAliPayEscrowEvents::TradeSettledFilter(e) => assert_eq!(e.trade_id, h256(TRADE_ID)),
+            other => panic!("TradeSettled decoded as {:?}", other),
+        }
+        match decode(&TRADE_EXPIRED) {
+            ZkAliPayEscrowEvents::TradeExpiredFilter(e) => {
+                assert_eq!(e.trade_id, h256(EXPIRED_TRADE_ID));
+                assert_eq!(e.order_id, h256(ORDER_ID));
+                assert_eq!(e.token_amount, U256::from(50_000_000u64));
+            }
+            other => panic!("TradeExpired decoded as {:?}", other),
+        }
+        match decode(&ORDER_WITHDRAWN) {
+            ZkAliPayEscrowEvents::OrderPartiallyWithdrawnFilter(e) => {
+                assert_eq!(e.order_id, h256(ORDER_ID));
+                assert_eq!(e.withdrawn_amount, U256::from(200_000_000u64));
+                assert_eq!(e.new_remaining_amount, U256::from(700_000_000u64));
+            }
+            other => panic!("OrderPartiallyWithdrawn decoded as {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_config_fixtures_decode_to_golden_values() {
+        match decode(&CONFIG_UPDATED) {
+            ZkAliPayEscrowEvents::ConfigUpdatedFilter(e) => {
+                assert_eq!(e.max_order_size, U256::from(7_200_000u64));
+                assert_eq!(e.payment_window, U256::from(900));
+            }
+            other => panic!("ConfigUpdated decoded as {:?}", other),
+        }
+        match decode(&ZK_VERIFIER_UPDATED) {
+            ZkAliPayEscrowEvents::ZkVerifierUpdatedFilter(e) => {
+                assert_eq!(e.old_verifier, address(OLD_VERIFIER));
+                assert_eq!(e.new_verifier, address(NEW_VERIFIER));
+            }
+            other => panic!("ZkVerifierUpdated decoded as {:?}", other),
+        }
+        match decode(&ZK_PDF_CONFIG_UPDATED) {
+            ZkAliPayEscrowEvents::ZkPDFConfigUpdatedFilter(e) => {
+                assert_eq!(e.public_key_der_hash, h256(PUBLIC_KEY_DER_HASH));
+                assert_eq!(e.app_exe_commit, h256(APP_EXE_COMMIT));
+                assert_eq!(e.app_vm_commit, h256(APP_VM_COMMIT));
+            }
+            other => panic!("ZkPDFConfigUpdated decoded as {:?}", other),
+        }
+        match decode(&PAUSED) {
+            ZkAliPayEscrowEvents::PausedFilter(e) => assert_eq!(e.account, address(OWNER)),
+            other => panic!("Paused decoded as {:?}", other),
+        }
+        match decode(&UNPAUSED) {
+            ZkAliPayEscrowEvents::UnpausedFilter(e) => assert_eq!(e.account, address(OWNER)),
+            other => panic!("Unpaused decoded as {:?}", other),
+        }
+    }
+}
@@ -0,0 +1,127 @@
+// Settlement dry runs on a local fork
+// A proof that doesn't match the verifier or the trade's payment commitment only shows
+// up as a reverted submitPaymentProof, after the relayer paid for it. With a fork
+// configured, the settlement pipeline first mines the submission on an anvil fork of
+// the target chain (`anvil --fork-url <rpc>`): the fork is reset to the chain's latest
+// block, the relayer is impersonated and funded there, and the proof passes only if
+// the transaction succeeds and emits TradeSettled for the trade. Nothing reaches the
+// real chain, and the fork's gas figure is logged for comparison.
+//
+// Configuration (env):
+// - SETTLEMENT_FORK_RPC_URL: RPC of the anvil fork (unset disables the check)
+
+use ethers::abi::AbiEncode;
+use ethers::providers::{Http, Middleware, Provider};
+use ethers::types::{Address, Bytes, TransactionRequest, H256, U256, U64};
+use thiserror::Error;
+use tokio::sync::Mutex;
+
+use super::settlement_batch::ProofSubmission;
+use super::SubmitPaymentProofCall;
+
+/// Balance the impersonated relayer gets on the fork (100 ETH)
+const FORK_RELAYER_BALANCE_WEI: u128 = 100_000_000_000_000_000_000;
+
+#[derive(Error, Debug)]
+pub enum ForkCheckError {
+    #[error("Fork RPC error: {0}")]
+    Fork(String),
+    #[error("submitPaymentProof reverted on the fork: {0}")]
+    Reverted(String),
+    #[error("submitPaymentProof succeeded on the fork without settling trade 0x{0}")]
+    NotSettled(String),
+}
+
+/// A submission that settled its trade on the fork
+#[derive(Debug, Clone)]
+pub struct ForkVerification {
+    /// Chain block the fork was reset to
+    pub fork_block: u64,
+    pub gas_used: U256,
+}
+
+pub struct ForkVerifier {
+    provider: Provider<Http>,
+    escrow: Address,
+    relayer: Address,
+    /// One check at a time: each resets the fork
+    lock: Mutex<()>,
+}
+
+impl ForkVerifier {
+    pub fn new(fork_rpc_url: &str, escrow: Address, relayer: Address) -> Result<Self, ForkCheckError> {
+        let provider = Provider::<Http>::try_from(fork_rpc_url).map_err(|e| ForkCheckError::Fork(e.to_string()))?;
+        Ok(Self { provider, escrow, relayer, lock: Mutex::new(()) })
+    }
+
+    /// SETTLEMENT_FORK_RPC_URL (None when unset)
+    pub fn from_env(escrow: Address, relayer: Address) -> Result<Option<Self>, ForkCheckError> {
+        match std::env::var("SETTLEMENT_FORK_RPC_URL") {
+            Ok(url) if !url.trim().is_empty() => Self::new(url.trim(), escrow, relayer).map(Some),
+            _ => Ok(None),
+        }
+    }
+
+    /// Mine the proof's submitPaymentProof on the fork
+    pub async fn verify(&self, proof: &ProofSubmission) -> Result<ForkVerification, ForkCheckError> {
+        let _guard = self.lock.lock().await;
+
+        // Re-fork at the latest block, so trades created since the last check exist
+        self.anvil("anvil_reset", serde_json::json!([{ "forking": {} }])).await?;
+        self.anvil("anvil_impersonateAccount", serde_json::json!([self.relayer])).await?;
+        self.anvil(
+            "anvil_setBalance",
+            serde_json::json!([self.relayer, U256::from(FORK_RELAYER_BALANCE_WEI)]),
+        )
+        .await?;
+        let fork_block = self.provider.get_block_number().await.map_err(|e| ForkCheckError::Fork(e.to_string()))?;
+
+        let call = SubmitPaymentProofCall {
+            trade_id: proof.trade_id,
+            user_public_values: proof.user_public_values,
+            accumulator: Bytes::from(proof.accumulator.clone()),
+            proof: Bytes::from(proof.proof.clone()),
+        };
+        let tx = TransactionRequest::new().from(self.relayer).to(self.escrow).data(call.encode());
+
+        // eth_call first: a revert reason is only reported there
+        if let Err(e) = self.provider.call(&tx.clone().into(), None).await {
+            return Err(ForkCheckError::Reverted(e.to_string()));
+        }
+
+        let receipt = self
+            .provider
+            .send_transaction(tx, None)
+            .await
+            .map_err(|e| ForkCheckError::Reverted(e.to_string()))?
+            .await
+            .map_err(|e| ForkCheckError::Fork(e.to_string()))?
+            .ok_or_else(|| ForkCheckError::Fork("fork dropped the transaction".to_string()))?;
+        if receipt.status != Some(U64::from(1)) {
+            return Err(ForkCheckError::Reverted(format!("transaction {:#x} failed", receipt.transaction_hash)));
+        }
+
+        let settled_topic = H256::from(ethers::utils::keccak256("TradeSettled(bytes32)"));
+        let settled = receipt.logs.iter().any(|log| {
+            log.address == self.escrow
+                && log.topics.first() == Some(&settled_topic)
+                && log.topics.get(1) == Some(&H256::from(proof.trade_id))
+        });
+        if !settled {
+            return Err(ForkCheckError::NotSettled(hex::encode(proof.trade_id)));
+        }
+
+        Ok(ForkVerification {
+            fork_block: fork_block.as_u64(),
+            gas_used: receipt.gas_used.unwrap_or_default(),
+        })
+    }
+
+    async fn anvil(&self, method: &str, params: serde_json::Value) -> Result<(), ForkCheckError> {
+        self.provider
+            .request::<_, serde_json::Value>(method, params)
+            .await
+            .map_err(|e| ForkCheckError::Fork(format!("{}: {}", method, e)))?;
+        Ok(())
+    }
+}
@@ -0,0 +1,135 @@
+// bytes32 identifiers of escrow orders and trades
+// Parsed from 0x-prefixed hex (any case) and displayed as 0x + 64 lowercase hex,
+// the form the event listener stores - so an ID that parses also matches its row.
+
+use serde::{de, Deserialize, Deserializer, Serialize, Serializer};
+use std::fmt;
+use std::str::FromStr;
+use thiserror::Error;
+
+use crate::hexutil;
+
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+#[error("Invalid {kind} '{value}': expected 0x followed by 64 hex characters")]
+pub struct IdError {
+    pub kind: &'static str,
+    pub value: String,
+}
+
+fn parse_bytes32(kind: &'static str, s: &str) -> Result<[u8; 32], IdError> {
+    let invalid = || IdError { kind, value: s.to_string() };
+    // IDs always carry the prefix (hexutil makes it optional)
+    let trimmed = s.trim();
+    if !trimmed.starts_with("0x") && !trimmed.starts_with("0X") {
+        return Err(invalid());
+    }
+    hexutil::decode_fixed::<32>(trimmed).map_err(|_| invalid())
+}
+
+macro_rules! bytes32_id {
+    ($(#[$doc:meta])* $name:ident, $kind:literal) => {
+        $(#[$doc])*
+        #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+        pub struct $name(pub [u8; 32]);
+
+        impl FromStr for $name {
+            type Err = IdError;
+
+            fn from_str(s: &str) -> Result<Self, Self::Err> {
+                parse_bytes32($kind, s).map(Self)
+            }
+        }
+
+        impl fmt::Display for $name {
+            fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                write!(f, "0x{}", hex::encode(self.0))
+            }
+        }
+
+        impl From<[u8; 32]> for $name {
+            fn from(bytes: [u8; 32]) -> Self {
+                Self(bytes)
+            }
+        }
+
+        impl Serialize for $name {
+            fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+                serializer.collect_str(self)
+            }
+        }
+
+        impl<'de> Deserialize<'de> for $name {
+            fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+                String::deserialize(deserializer)?.parse().map_err(de::Error::custom)
+            }
+        }
+    };
+}
+
+bytes32_id!(
+    /// Escrow order ID (bytes32 orderId)
+    OrderId,
+    "order ID"
+);
+
+bytes32_id!(
+    /// Escrow trade ID (bytes32 tradeId)
+    TradeId,
+    "trade ID"
+);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use proptest::prelude::*;
+
+    #[test]
+    fn test_id_round_trip() {
+        let trade_id = TradeId([0xab; 32]);
+        let displayed = trade_id.to_string();
+        assert_eq!(displayed, format!("0x{}", "ab".repeat(32)));
+        assert_eq!(displayed.parse::<TradeId>().unwrap(), trade_id);
+        assert_eq!(displayed.to_uppercase().parse::<TradeId>().unwrap(), trade_id);
+        assert_eq!(serde_json::to_value(trade_id).unwrap(), serde_json::json!(displayed));
+
+        let order_id = OrderId([1u8; 32]);
+        assert_eq!(order_id.to_string().parse::<OrderId>().unwrap(), order_id);
+    }
+
+    #[test]
+    fn test_malformed_ids_rejected() {
+        let full = "01".repeat(32);
+        for bad in [full.as_str(), "0x", "0x0101", &format!("0x{}00", full), &format!("0x{}", "zz".repeat(32))] {
+            let err = bad.parse::<OrderId>().unwrap_err();
+            assert_eq!(err.kind, "order ID");
+        }
+        assert!(serde_json::from_value::<TradeId>(serde_json::json!("trade_1")).is_err());
+    }
+
+    proptest! {
+        #[test]
+        fn prop_ids_round_trip_in_any_case(bytes in any::<[u8; 32]>(), upper in any::<bool>(), pad in "[ \t]{0,2}") {
+            let id = OrderId(bytes);
+            let displayed = id.to_string();
+            let input = if upper { format!("0X{}", displayed[2..].to_uppercase()) } else { displayed.clone() };
+            prop_assert_eq!(format!("{}{}{}", pad, input, pad).parse::<OrderId>(), Ok(id));
+            // Without the prefix it isn't an ID
+            prop_assert!(displayed[2..].parse::<OrderId>().is_err());
+        }
+
+        #[test]
+        fn prop_whatever_parses_is_displayed_canonically(s in "\\PC{0,80}") {
+            // Arbitrary input never panics; anything accepted is 0x + 64 hex digits of it
+            if let Ok(id) = s.parse::<TradeId>() {
+                prop_assert_eq!(id.to_string(), s.trim().to_lowercase());
+                prop_assert_eq!(id.to_string().len(), 66);
+            }
+        }
+
+        #[test]
+        fn prop_wrong_length_ids_are_rejected(digits in "[0-9a-fA-F]{0,70}") {
+            let parsed = format!("0x{}", digits).parse::<TradeId>();
+            prop_assert_eq!(parsed.is_ok(), digits.len() == 64);
+        }
+    }
+}
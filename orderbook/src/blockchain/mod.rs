@@ -1,8 +1,25 @@
 // Blockchain integration module
 // Phase 2.3.b: Ethereum client and event listener
 
+pub mod address;
+pub mod batch;
+pub mod block_times;
 pub mod client;
+pub mod config_cache;
+pub mod confirmations;
+pub mod eas;
 pub mod events;
+pub mod fill_locks;
+pub mod fixtures;
+pub mod fork_check;
+pub mod ids;
+pub mod pending_txs;
+pub mod read_cache;
+pub mod rebuild;
+pub mod settlement_batch;
+pub mod signer;
+pub mod supervisor;
+pub mod trade_drift;
 pub mod types;
 
 use ethers::prelude::abigen;
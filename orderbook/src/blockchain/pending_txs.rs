@@ -0,0 +1,243 @@
+// Relayer transactions in flight
+// When Base Sepolia got congested, fills and proof submissions sat in the mempool with
+// nothing to show which transaction was stuck, at what nonce or gas price - only a
+// growing gap between the relayer's pending and mined nonces. The client now records
+// every transaction it sends until its receipt arrives (GET /api/admin/pending-txs),
+// and a stuck one can be replaced at the same nonce: re-sent with more gas ("bump") or
+// swapped for an empty self-transfer ("cancel"). Transactions whose receipt wait failed
+// stay listed until the relayer's mined nonce passes them.
+
+use chrono::{DateTime, Utc};
+use ethers::abi::AbiDecode;
+use ethers::types::{
+    transaction::eip2718::TypedTransaction, Address, Bytes, Eip1559TransactionRequest, Transaction,
+    TransactionRequest, H256, U256,
+};
+use std::collections::HashMap;
+use std::sync::Mutex;
+
+use super::FillOrderCall;
+use crate::db::relayer_spend::ACTION_FILL_ORDER;
+
+/// Kind of an empty self-transfer replacing a stuck transaction
+pub const KIND_CANCEL: &str = "cancel";
+
+/// Gas of a plain transfer (what a cancellation needs)
+const TRANSFER_GAS: u64 = 21_000;
+
+/// A sent relayer transaction without a receipt yet
+#[derive(Debug, Clone)]
+pub struct PendingTx {
+    pub tx_hash: H256,
+    /// Contract method (relayer_spend ACTION_* for trade actions) or KIND_CANCEL
+    pub kind: String,
+    pub trade_id: Option<String>,
+    pub order_id: Option<String>,
+    pub relayer: Address,
+    pub nonce: U256,
+    pub gas_limit: U256,
+    /// Gas price, or max fee per gas for EIP-1559 transactions
+    pub gas_price: U256,
+    /// Max priority fee per gas (EIP-1559 transactions only)
+    pub priority_fee: Option<U256>,
+    pub to: Option<Address>,
+    pub value: U256,
+    pub data: Bytes,
+    pub sent_at: DateTime<Utc>,
+    /// The transaction this one replaced (bump or cancel)
+    pub replaces: Option<H256>,
+}
+
+impl PendingTx {
+    pub fn new(tx: &Transaction, kind: &str, trade_id: Option<String>, order_id: Option<String>) -> Self {
+        Self {
+            tx_hash: tx.hash,
+            kind: kind.to_string(),
+            trade_id,
+            order_id,
+            relayer: tx.from,
+            nonce: tx.nonce,
+            gas_limit: tx.gas,
+            gas_price: tx.max_fee_per_gas.or(tx.gas_price).unwrap_or_default(),
+            priority_fee: tx.max_priority_fee_per_gas,
+            to: tx.to,
+            value: tx.value,
+            data: tx.input.clone(),
+            sent_at: Utc::now(),
+            replaces: None,
+        }
+    }
+
+    /// A transaction at the same nonce that nodes accept in this one's place: the same
+    /// call (or an empty self-transfer if `cancel`) with fees bumped by at least 12.5%,
+    /// and the gas price raised to `min_gas_price` if that is higher
+    pub fn replacement(&self, cancel: bool, min_gas_price: Option<U256>) -> TypedTransaction {
+        let gas_price = bumped(self.gas_price).max(min_gas_price.unwrap_or_default());
+        let (to, value, data, gas) = if cancel {
+            (self.relayer, U256::zero(), Bytes::default(), U256::from(TRANSFER_GAS))
+        } else {
+            (self.to.unwrap_or_default(), self.value, self.data.clone(), self.gas_limit)
+        };
+
+        match self.priority_fee {
+            Some(priority_fee) => Eip1559TransactionRequest::new()
+                .from(self.relayer)
+                .to(to)
+                .value(value)
+                .data(data)
+                .gas(gas)
+                .nonce(self.nonce)
+                .max_fee_per_gas(gas_price)
+                .max_priority_fee_per_gas(bumped(priority_fee).min(gas_price))
+                .into(),
+            None => TransactionRequest::new()
+                .from(self.relayer)
+                .to(to)
+                .value(value)
+                .data(data)
+                .gas(gas)
+                .nonce(self.nonce)
+                .gas_price(gas_price)
+                .into(),
+        }
+    }
+
+    pub fn age_secs(&self) -> i64 {
+        (Utc::now() - self.sent_at).num_seconds()
+    }
+}
+
+/// Fee a replacement needs: nodes refuse replacements bumped by less than 10%
+pub fn bumped(fee: U256) -> U256 {
+    fee.saturating_add(fee / 8).saturating_add(U256::one())
+}
+
+/// The relayer's transactions in flight, by hash
+#[derive(Debug, Default)]
+pub struct PendingTxs {
+    txs: Mutex<HashMap<H256, PendingTx>>,
+}
+
+impl PendingTxs {
+    pub fn insert(&self, tx: PendingTx) {
+        self.txs.lock().unwrap().insert(tx.tx_hash, tx);
+    }
+
+    /// Forget a transaction once its receipt arrived
+    pub fn remove(&self, tx_hash: &H256) {
+        self.txs.lock().unwrap().remove(tx_hash);
+    }
+
+    pub fn get(&self, tx_hash: &H256) -> Option<PendingTx> {
+        self.txs.lock().unwrap().get(tx_hash).cloned()
+    }
+
+    /// Forget `relayer`'s transactions below its mined nonce (mined, or replaced by one that was)
+    pub fn prune_mined(&self, relayer: Address, confirmed_nonce: U256) {
+        self.txs
+            .lock()
+            .unwrap()
+            .retain(|_, tx| tx.relayer != relayer || tx.nonce >= confirmed_nonce);
+    }
+
+    /// Tokens of `order_id` that fillOrder transactions in flight will take
+    /// Only the latest transaction at each nonce counts (a bump replaces, a cancel drops it).
+    pub fn fills_in_flight(&self, order_id: &str) -> U256 {
+        let mut latest: HashMap<(Address, U256), PendingTx> = HashMap::new();
+        for tx in self.list() {
+            latest.insert((tx.relayer, tx.nonce), tx);
+        }
+        latest
+            .values()
+            .filter(|tx| tx.kind == ACTION_FILL_ORDER && tx.order_id.as_deref() == Some(order_id))
+            .filter_map(|tx| FillOrderCall::decode(&tx.data).ok())
+            .fold(U256::zero(), |total, call| total.saturating_add(call.fill_amount))
+    }
+
+    /// All transactions, by relayer and nonce (replacements after what they replace)
+    pub fn list(&self) -> Vec<PendingTx> {
+        let mut txs: Vec<PendingTx> = self.txs.lock().unwrap().values().cloned().collect();
+        txs.sort_by_key(|tx| (tx.relayer, tx.nonce, tx.sent_at));
+        txs
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn pending(relayer: Address, nonce: u64, priority_fee: Option<u64>) -> PendingTx {
+        PendingTx {
+            tx_hash: H256::random(),
+            kind: "fillOrder".to_string(),
+            trade_id: None,
+            order_id: Some("0x11".to_string()),
+            relayer,
+            nonce: U256::from(nonce),
+            gas_limit: U256::from(200_000),
+            gas_price: U256::from(1_000_000_000u64),
+            priority_fee: priority_fee.map(U256::from),
+            to: Some(Address::repeat_byte(0xee)),
+            value: U256::zero(),
+            data: Bytes::from(vec![1, 2, 3]),
+            sent_at: Utc::now(),
+            replaces: None,
+        }
+    }
+
+    #[test]
+    fn test_replacement_keeps_nonce_and_bumps_fees() {
+        let relayer = Address::repeat_byte(0x11);
+        let tx = pending(relayer, 7, Some(100));
+
+        let bump = tx.replacement(false, None);
+        assert_eq!(bump.nonce(), Some(&U256::from(7)));
+        assert_eq!(bump.data(), Some(&tx.data));
+        let TypedTransaction::Eip1559(bump) = bump else { panic!("expected an EIP-1559 replacement") };
+        assert_eq!(bump.max_fee_per_gas, Some(U256::from(1_125_000_001u64)));
+        assert_eq!(bump.max_priority_fee_per_gas, Some(U256::from(113)));
+
+        // A requested price above the minimum bump wins
+        let legacy = pending(relayer, 7, None).replacement(false, Some(U256::from(5_000_000_000u64)));
+        assert_eq!(legacy.gas_price(), Some(U256::from(5_000_000_000u64)));
+
+        let cancel = tx.replacement(true, None);
+        assert_eq!(cancel.to_addr(), Some(&relayer));
+        assert_eq!(cancel.gas(), Some(&U256::from(TRANSFER_GAS)));
+        assert_eq!(cancel.nonce(), Some(&U256::from(7)));
+    }
+
+    #[test]
+    fn test_prune_mined_drops_only_that_relayers_lower_nonces() {
+        let (relayer, other) = (Address::repeat_byte(0x11), Address::repeat_byte(0x22));
+        let txs = PendingTxs::default();
+        for tx in [pending(relayer, 4, None), pending(relayer, 5, None), pending(other, 1, None)] {
+            txs.insert(tx);
+        }
+
+        txs.prune_mined(relayer, U256::from(5));
+        let left: Vec<_> = txs.list().iter().map(|tx| (tx.relayer, tx.nonce.as_u64())).collect();
+        assert_eq!(left, vec![(relayer, 5), (other, 1)]);
+    }
+
+    #[test]
+    fn test_fills_in_flight_counts_the_latest_tx_per_nonce() {
+        let relayer = Address::repeat_byte(0x11);
+        let fill = |nonce: u64, amount: u64| {
+            let call = FillOrderCall { order_id: [0x11; 32], buyer: Address::repeat_byte(0xbb), fill_amount: U256::from(amount) };
+            PendingTx { kind: ACTION_FILL_ORDER.to_string(), data: Bytes::from(ethers::abi::AbiEncode::encode(call)), ..pending(relayer, nonce, None) }
+        };
+        let txs = PendingTxs::default();
+        txs.insert(fill(1, 100));
+        txs.insert(fill(2, 40));
+        assert_eq!(txs.fills_in_flight("0x11"), U256::from(140));
+        assert_eq!(txs.fills_in_flight("0x22"), U256::zero());
+
+        // A bump at nonce 2 replaces its fill, a cancel at nonce 1 drops it
+        let bump = PendingTx { sent_at: Utc::now() + chrono::Duration::seconds(1), ..fill(2, 40) };
+        txs.insert(bump);
+        let cancel = PendingTx { kind: KIND_CANCEL.to_string(), sent_at: Utc::now() + chrono::Duration::seconds(1), ..pending(relayer, 1, None) };
+        txs.insert(cancel);
+        assert_eq!(txs.fills_in_flight("0x11"), U256::from(40));
+    }
+}
@@ -0,0 +1,202 @@
+// Read-through cache for contract reads that rarely change
+// The public key DER hash, the payment window and an order's pricing (exchange rate and
+// token decimals, fixed when the order is created) are read on every validation, proof
+// and fill, each an eth_call. EthereumClient answers them from this cache instead and
+// only calls the contract when an entry is missing or older than the TTL. Entries are
+// also dropped (or replaced) when the event listener sees the config change on-chain,
+// and when the relayer itself sends the change. Hits and misses are exported per read.
+//
+// Configuration (env):
+// - CONTRACT_READ_CACHE_TTL_SECS: entry lifetime (default 300, 0 disables caching)
+// - CONTRACT_READ_CACHE_MAX_ORDERS: orders whose pricing is kept (default 10000)
+
+use ethers::types::U256;
+use std::collections::HashMap;
+use std::hash::Hash;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use crate::metrics::Metrics;
+
+pub const DEFAULT_READ_CACHE_TTL_SECS: u64 = 300;
+pub const DEFAULT_READ_CACHE_MAX_ORDERS: usize = 10_000;
+
+/// Read names used as the metrics label
+pub const READ_PUBLIC_KEY_DER_HASH: &str = "public_key_der_hash";
+pub const READ_PAYMENT_WINDOW: &str = "payment_window";
+pub const READ_ORDER_PRICING: &str = "order_pricing";
+
+/// TTL map of one kind of read, holding at most `capacity` entries
+pub struct ReadCache<K, V> {
+    ttl: Duration,
+    capacity: usize,
+    entries: Mutex<HashMap<K, (V, Instant)>>,
+}
+
+impl<K: Eq + Hash + Clone, V: Clone> ReadCache<K, V> {
+    pub fn new(ttl: Duration, capacity: usize) -> Self {
+        Self { ttl, capacity, entries: Mutex::new(HashMap::new()) }
+    }
+
+    /// The cached value, unless missing or expired
+    pub fn get(&self, key: &K) -> Option<V> {
+        let entries = self.entries.lock().unwrap();
+        let (value, loaded_at) = entries.get(key)?;
+        (loaded_at.elapsed() < self.ttl).then(|| value.clone())
+    }
+
+    pub fn insert(&self, key: K, value: V) {
+        if self.ttl.is_zero() || self.capacity == 0 {
+            return;
+        }
+        let mut entries = self.entries.lock().unwrap();
+        if entries.len() >= self.capacity && !entries.contains_key(&key) {
+            // Expired entries go first; when everything is fresh, the oldest does
+            entries.retain(|_, (_, loaded_at)| loaded_at.elapsed() < self.ttl);
+            if entries.len() >= self.capacity {
+                let oldest = entries.iter().min_by_key(|(_, (_, loaded_at))| *loaded_at).map(|(k, _)| k.clone());
+                if let Some(oldest) = oldest {
+                    entries.remove(&oldest);
+                }
+            }
+        }
+        entries.insert(key, (value, Instant::now()));
+    }
+
+    pub fn invalidate(&self, key: &K) {
+        self.entries.lock().unwrap().remove(key);
+    }
+
+    pub fn clear(&self) {
+        self.entries.lock().unwrap().clear();
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct ReadCacheConfig {
+    pub ttl: Duration,
+    pub max_orders: usize,
+}
+
+impl Default for ReadCacheConfig {
+    fn default() -> Self {
+        Self {
+            ttl: Duration::from_secs(DEFAULT_READ_CACHE_TTL_SECS),
+            max_orders: DEFAULT_READ_CACHE_MAX_ORDERS,
+        }
+    }
+}
+
+impl ReadCacheConfig {
+    /// CONTRACT_READ_CACHE_TTL_SECS and CONTRACT_READ_CACHE_MAX_ORDERS (defaults when unset or invalid)
+    pub fn from_env() -> Self {
+        let defaults = Self::default();
+        let ttl = std::env::var("CONTRACT_READ_CACHE_TTL_SECS")
+            .ok()
+            .and_then(|v| v.parse().ok())
+            .map(Duration::from_secs)
+            .unwrap_or(defaults.ttl);
+        let max_orders = std::env::var("CONTRACT_READ_CACHE_MAX_ORDERS")
+            .ok()
+            .and_then(|v| v.parse().ok())
+            .unwrap_or(defaults.max_orders);
+        Self { ttl, max_orders }
+    }
+}
+
+/// The cached reads of one EthereumClient (shared with the event listener for invalidation)
+pub struct ContractReadCache {
+    pub(crate) public_key_der_hash: ReadCache<(), [u8; 32]>,
+    pub(crate) payment_window: ReadCache<(), U256>,
+    /// Order ID -> (exchangeRate, tokenDecimals)
+    pub(crate) order_pricing: ReadCache<[u8; 32], (U256, u8)>,
+    metrics: Option<Arc<Metrics>>,
+}
+
+impl ContractReadCache {
+    pub fn new(config: &ReadCacheConfig) -> Self {
+        Self {
+            public_key_der_hash: ReadCache::new(config.ttl, 1),
+            payment_window: ReadCache::new(config.ttl, 1),
+            order_pricing: ReadCache::new(config.ttl, config.max_orders),
+            metrics: None,
+        }
+    }
+
+    /// Count hits and misses per read in shared metrics
+    pub fn with_metrics(mut self, metrics: Arc<Metrics>) -> Self {
+        self.metrics = Some(metrics);
+        self
+    }
+
+    /// Count a lookup of `read` and pass its result through
+    pub(crate) fn observe<V>(&self, read: &'static str, value: Option<V>) -> Option<V> {
+        if let Some(metrics) = &self.metrics {
+            metrics.contract_reads.record(read, value.is_some());
+        }
+        value
+    }
+
+    /// ConfigUpdated: the payment window may have changed
+    pub fn invalidate_config(&self) {
+        self.payment_window.clear();
+    }
+
+    /// ZkPDFConfigUpdated carries the new hash, so no refetch is needed
+    pub fn set_public_key_der_hash(&self, hash: [u8; 32]) {
+        self.public_key_der_hash.insert((), hash);
+    }
+}
+
+impl Default for ContractReadCache {
+    fn default() -> Self {
+        Self::new(&ReadCacheConfig::default())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_read_cache_expiry_and_capacity() {
+        let cache: ReadCache<u8, u32> = ReadCache::new(Duration::from_secs(60), 2);
+        assert_eq!(cache.get(&1), None);
+        cache.insert(1, 10);
+        std::thread::sleep(Duration::from_millis(2));
+        cache.insert(2, 20);
+        assert_eq!(cache.get(&1), Some(10));
+
+        // Full: the oldest entry makes room
+        cache.insert(3, 30);
+        assert_eq!(cache.get(&1), None);
+        assert_eq!(cache.get(&2), Some(20));
+        assert_eq!(cache.get(&3), Some(30));
+
+        cache.invalidate(&3);
+        assert_eq!(cache.get(&3), None);
+
+        let expired: ReadCache<u8, u32> = ReadCache::new(Duration::ZERO, 2);
+        expired.insert(1, 10);
+        assert_eq!(expired.get(&1), None);
+    }
+
+    #[test]
+    fn test_contract_read_cache_counts_hits_and_misses() {
+        let metrics = Arc::new(Metrics::new());
+        let cache = ContractReadCache::default().with_metrics(metrics.clone());
+
+        assert_eq!(cache.observe(READ_PAYMENT_WINDOW, cache.payment_window.get(&())), None);
+        cache.payment_window.insert((), U256::from(900));
+        assert_eq!(cache.observe(READ_PAYMENT_WINDOW, cache.payment_window.get(&())), Some(U256::from(900)));
+
+        cache.invalidate_config();
+        assert_eq!(cache.payment_window.get(&()), None);
+        cache.set_public_key_der_hash([7u8; 32]);
+        assert_eq!(cache.public_key_der_hash.get(&()), Some([7u8; 32]));
+
+        let text = metrics.render();
+        assert!(text.contains("zkalipay_contract_read_cache_hits_total{read=\"payment_window\"} 1\n"));
+        assert!(text.contains("zkalipay_contract_read_cache_misses_total{read=\"payment_window\"} 1\n"));
+    }
+}
@@ -0,0 +1,275 @@
+// Disaster recovery: rebuild the database from the chain
+// Orders and trades are mirrors of the escrow's storage, so a lost database can be
+// rebuilt by replaying every contract event from the deployment block through the
+// same EventListener that keeps it in sync. By default the replay writes to a shadow
+// schema (migrated from scratch), leaving whatever is in public untouched; once it
+// verifies, an operator promotes it with
+//   ALTER SCHEMA public RENAME TO public_old; ALTER SCHEMA chain_rebuild RENAME TO public;
+// `--wipe` with schema public drops and rebuilds public in place instead.
+//
+// After the replay a random sample of orders and trades is compared with the escrow's
+// storage at the last replayed block. Off-chain data (proofs, receipts, messages, API
+// keys, screenings, ...) isn't on chain and is not recovered.
+
+use ethers::providers::{Http, Provider};
+use ethers::types::Address;
+use std::sync::Arc;
+use thiserror::Error;
+
+use super::events::{EventListener, EventListenerError};
+use super::ZkAliPayEscrow;
+use crate::db::{Database, DbError};
+use crate::fees::FeeEngine;
+
+/// Schema a rebuild writes to unless told otherwise
+pub const DEFAULT_REBUILD_SCHEMA: &str = "chain_rebuild";
+/// Orders and trades compared with the chain after a rebuild
+pub const DEFAULT_SAMPLE_SIZE: i64 = 50;
+/// Blocks fetched per step (the live listener's 8 would take days from deployment)
+pub const REBUILD_BLOCKS_PER_QUERY: u64 = 2_000;
+
+#[derive(Error, Debug)]
+pub enum RebuildError {
+    #[error("Invalid schema name {0:?}: use lowercase letters, digits and underscores")]
+    InvalidSchema(String),
+    #[error("Refusing to rebuild schema public without --wipe")]
+    PublicWithoutWipe,
+    #[error("Schema {0} already has tables: pass --wipe to drop it first")]
+    SchemaNotEmpty(String),
+    #[error("Database error: {0}")]
+    Database(#[from] DbError),
+    #[error("Event replay failed: {0}")]
+    Replay(#[from] EventListenerError),
+    #[error("Contract call failed: {0}")]
+    Contract(String),
+}
+
+/// Outcome of comparing a rebuilt database with the chain
+#[derive(Debug, Default)]
+pub struct VerificationReport {
+    pub orders_checked: usize,
+    pub trades_checked: usize,
+    /// One line per sampled row that differs from the chain
+    pub mismatches: Vec<String>,
+    /// Events the replay couldn't apply (see dead_letter_events)
+    pub pending_dead_letters: i64,
+}
+
+impl VerificationReport {
+    pub fn is_ok(&self) -> bool {
+        self.mismatches.is_empty() && self.pending_dead_letters == 0
+    }
+}
+
+/// Schema names are interpolated into DDL, so only plain identifiers are allowed
+fn validate_schema(schema: &str) -> Result<(), RebuildError> {
+    let valid = !schema.is_empty()
+        && schema.len() <= 63
+        && !schema.starts_with(|c: char| c.is_ascii_digit())
+        && !schema.starts_with("pg_")
+        && schema.chars().all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '_');
+    if valid {
+        Ok(())
+    } else {
+        Err(RebuildError::InvalidSchema(schema.to_string()))
+    }
+}
+
+/// Create (or with `wipe`, recreate) `schema` and migrate it; returns a database
+/// whose search path is that schema
+pub async fn prepare_schema(database_url: &str, schema: &str, wipe: bool) -> Result<Database, RebuildError> {
+    validate_schema(schema)?;
+    if schema == "public" && !wipe {
+        return Err(RebuildError::PublicWithoutWipe);
+    }
+
+    let admin = Database::new(database_url).await?;
+    if wipe {
+        tracing::warn!("🧨 Dropping schema {}", schema);
+        sqlx::query(&format!("DROP SCHEMA IF EXISTS {} CASCADE", schema))
+            .execute(admin.pool())
+            .await
+            .map_err(DbError::from)?;
+    } else {
+        let tables: i64 = sqlx::query_scalar(
+            "SELECT COUNT(*) FROM information_schema.tables WHERE table_schema = $1",
+        )
+        .bind(schema)
+        .fetch_one(admin.pool())
+        .await
+        .map_err(DbError::from)?;
+        if tables > 0 {
+            return Err(RebuildError::SchemaNotEmpty(schema.to_string()));
+        }
+    }
+    sqlx::query(&format!("CREATE SCHEMA IF NOT EXISTS {}", schema))
+        .execute(admin.pool())
+        .await
+        .map_err(DbError::from)?;
+    admin.pool().close().await;
+
+    let db = Database::with_schema(database_url, schema).await?;
+    let applied = db.migrate().await?;
+    tracing::info!("🗃️  Schema {} ready ({} migrations applied)", schema, applied);
+    Ok(db)
+}
+
+/// Replay every escrow event from `from_block` up to the safe head
+/// Returns the last block replayed.
+pub async fn replay(
+    db: &Database,
+    rpc_url: &str,
+    escrow: Address,
+    from_block: u64,
+    fee_engine: Option<Arc<FeeEngine>>,
+) -> Result<u64, RebuildError> {
+    let listener = EventListener::new(rpc_url, escrow, db.pool().clone(), Some(from_block))
+        .await?
+        .with_blocks_per_query(REBUILD_BLOCKS_PER_QUERY);
+    let mut listener = match fee_engine {
+        Some(engine) => listener.with_fee_engine(engine),
+        None => listener,
+    };
+
+    tracing::info!("⏪ Replaying escrow {:#x} events from block {}", escrow, from_block);
+    Ok(listener.catch_up().await?)
+}
+
+/// Compare up to `sample` random orders and trades with the escrow's storage at `block`
+pub async fn verify(
+    db: &Database,
+    rpc_url: &str,
+    escrow: Address,
+    block: u64,
+    sample: i64,
+) -> Result<VerificationReport, RebuildError> {
+    let provider = Provider::<Http>::try_from(rpc_url).map_err(|e| RebuildError::Contract(e.to_string()))?;
+    let contract = ZkAliPayEscrow::new(escrow, Arc::new(provider));
+    let mut report = VerificationReport::default();
+
+    let orders = sqlx::query!(
+        r#"
+        SELECT "orderId", seller::TEXT AS "seller!", token::TEXT AS "token!",
+               "totalAmount"::TEXT AS "total_amount!", "remainingAmount"::TEXT AS "remaining_amount!",
+               "exchangeRate"::TEXT AS "exchange_rate!", "alipayId", "alipayName", "createdAt"
+        FROM orders
+        ORDER BY random()
+        LIMIT $1
+        "#,
+        sample
+    )
+    .fetch_all(db.pool())
+    .await
+    .map_err(DbError::from)?;
+
+    for order in orders {
+        let order_id = parse_bytes32(&order.orderId)?;
+        // (orderId, seller, token, totalAmount, remainingAmount, exchangeRate, alipayId, alipayName, createdAt, tokenDecimals)
+        let (_, seller, token, total, remaining, rate, alipay_id, alipay_name, created_at, _) = contract
+            .orders(order_id)
+            .block(block)
+            .call()
+            .await
+            .map_err(|e| RebuildError::Contract(e.to_string()))?;
+
+        let fields = [
+            ("seller", order.seller.to_lowercase(), format!("{:#x}", seller)),
+            ("token", order.token.to_lowercase(), format!("{:#x}", token)),
+            ("totalAmount", order.total_amount, total.to_string()),
+            ("remainingAmount", order.remaining_amount, remaining.to_string()),
+            ("exchangeRate", order.exchange_rate, rate.to_string()),
+            ("alipayId", order.alipayId, alipay_id),
+            ("alipayName", order.alipayName, alipay_name),
+            ("createdAt", order.createdAt.to_string(), created_at.to_string()),
+        ];
+        report.mismatches.extend(mismatches("order", &order.orderId, &fields));
+        report.orders_checked += 1;
+    }
+
+    let trades = sqlx::query!(
+        r#"
+        SELECT "tradeId", "orderId", buyer::TEXT AS "buyer!",
+               "tokenAmount"::TEXT AS "token_amount!", "cnyAmount"::TEXT AS "cny_amount!",
+               "paymentNonce", "createdAt", "expiresAt", "status"
+        FROM trades
+        ORDER BY random()
+        LIMIT $1
+        "#,
+        sample
+    )
+    .fetch_all(db.pool())
+    .await
+    .map_err(DbError::from)?;
+
+    for trade in trades {
+        let trade_id = parse_bytes32(&trade.tradeId)?;
+        // (tradeId, orderId, buyer, tokenAmount, cnyAmount, paymentNonce, createdAt, expiresAt, status)
+        let (_, order_id, buyer, token_amount, cny_amount, nonce, created_at, expires_at, status) = contract
+            .trades(trade_id)
+            .block(block)
+            .call()
+            .await
+            .map_err(|e| RebuildError::Contract(e.to_string()))?;
+
+        let fields = [
+            ("orderId", trade.orderId.to_lowercase(), format!("0x{}", hex::encode(order_id))),
+            ("buyer", trade.buyer.to_lowercase(), format!("{:#x}", buyer)),
+            ("tokenAmount", trade.token_amount, token_amount.to_string()),
+            ("cnyAmount", trade.cny_amount, cny_amount.to_string()),
+            ("paymentNonce", trade.paymentNonce, nonce),
+            ("createdAt", trade.createdAt.to_string(), created_at.to_string()),
+            ("expiresAt", trade.expiresAt.to_string(), expires_at.to_string()),
+            ("status", trade.status.to_string(), status.to_string()),
+        ];
+        report.mismatches.extend(mismatches("trade", &trade.tradeId, &fields));
+        report.trades_checked += 1;
+    }
+
+    report.pending_dead_letters = sqlx::query_scalar!(
+        r#"SELECT COUNT(*) AS "count!" FROM dead_letter_events WHERE "status" = 'pending'"#
+    )
+    .fetch_one(db.pool())
+    .await
+    .map_err(DbError::from)?;
+
+    Ok(report)
+}
+
+fn parse_bytes32(id: &str) -> Result<[u8; 32], RebuildError> {
+    let bytes = hex::decode(id.trim_start_matches("0x")).map_err(|e| RebuildError::Contract(format!("{}: {}", id, e)))?;
+    bytes
+        .try_into()
+        .map_err(|_| RebuildError::Contract(format!("{} is not 32 bytes", id)))
+}
+
+/// "kind id: field db=… chain=…" for every field that differs
+fn mismatches(kind: &str, id: &str, fields: &[(&str, String, String)]) -> Vec<String> {
+    fields
+        .iter()
+        .filter(|(_, db, chain)| db != chain)
+        .map(|(field, db, chain)| format!("{} {}: {} db={} chain={}", kind, id, field, db, chain))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_rebuild_schema_names_and_mismatches() {
+        assert!(validate_schema(DEFAULT_REBUILD_SCHEMA).is_ok());
+        assert!(validate_schema("public").is_ok());
+        for invalid in ["", "Chain", "rebuild; DROP SCHEMA public", "1st", "pg_temp", "a-b"] {
+            assert!(validate_schema(invalid).is_err(), "{:?} should be rejected", invalid);
+        }
+
+        let fields = [
+            ("remainingAmount", "500".to_string(), "400".to_string()),
+            ("exchangeRate", "720".to_string(), "720".to_string()),
+        ];
+        assert_eq!(
+            mismatches("order", "0xab", &fields),
+            vec!["order 0xab: remainingAmount db=500 chain=400".to_string()]
+        );
+    }
+}
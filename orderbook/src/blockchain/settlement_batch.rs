@@ -0,0 +1,284 @@
+// Settlement batching
+// Every settled trade used to be its own submitPaymentProof transaction. submitPaymentProof
+// doesn't look at msg.sender, so during busy periods the relayer groups the proofs that
+// become ready within about one block and sends them as a single Multicall3 aggregate3
+// transaction, each call allowed to fail on its own. The trades a batch settled are read
+// from its TradeSettled logs; the others (and every trade of a batch that couldn't be
+// sent or reverted as a whole) are submitted individually, which reports their revert
+// reason as before. A batch whose outcome is unknown (sent, but the receipt wait failed)
+// isn't retried, since it may still be mined.
+//
+// Configuration (env):
+// - SETTLEMENT_BATCH_SIZE: most proofs per transaction (unset or below 2 disables batching)
+// - SETTLEMENT_BATCH_WINDOW_MS: how long a batch collects proofs (default 2000, one Base block)
+// - MULTICALL3_ADDRESS: Multicall3 deployment (default the canonical 0xcA11...CA11)
+
+use ethers::abi::{Abi, AbiEncode};
+use ethers::types::{Address, Bytes, H256, U256};
+use futures::future::join_all;
+use std::sync::Arc;
+use tokio::sync::{mpsc, oneshot};
+use tokio::time::{timeout_at, Duration, Instant};
+
+use super::client::{EthereumClient, EthereumClientError, GasOverride, RelayerTx};
+use super::SubmitPaymentProofCall;
+
+/// Canonical Multicall3 address (same on every chain it is deployed to)
+pub const MULTICALL3_ADDRESS: &str = "0xcA11bde05977b3631167028862bE2a173976CA11";
+/// Default time a batch collects proofs
+pub const DEFAULT_BATCH_WINDOW_MS: u64 = 2_000;
+
+/// A stored proof ready for submitPaymentProof
+#[derive(Debug, Clone)]
+pub struct ProofSubmission {
+    pub trade_id: [u8; 32],
+    pub user_public_values: [u8; 32],
+    pub accumulator: Vec<u8>,
+    pub proof: Vec<u8>,
+}
+
+/// Multicall3's aggregate3, the only function the relayer calls on it
+pub(crate) fn multicall3_abi() -> Abi {
+    ethers::abi::parse_abi(&[
+        "function aggregate3((address,bool,bytes)[] calls) payable returns ((bool,bytes)[] returnData)",
+    ])
+    .expect("aggregate3 ABI is valid")
+}
+
+/// aggregate3 calls (target, allowFailure, callData): one submitPaymentProof per proof
+pub(crate) fn aggregate3_calls(escrow: Address, proofs: &[ProofSubmission]) -> Vec<(Address, bool, Bytes)> {
+    proofs
+        .iter()
+        .map(|proof| {
+            let call = SubmitPaymentProofCall {
+                trade_id: proof.trade_id,
+                user_public_values: proof.user_public_values,
+                accumulator: Bytes::from(proof.accumulator.clone()),
+                proof: Bytes::from(proof.proof.clone()),
+            };
+            (escrow, true, Bytes::from(call.encode()))
+        })
+        .collect()
+}
+
+/// Each trade's share of a batch transaction's gas (the remainder goes to the first)
+fn gas_shares(tx: &RelayerTx, trades: usize) -> Vec<RelayerTx> {
+    let trades = trades.max(1);
+    let share = tx.gas_used / U256::from(trades);
+    let remainder = tx.gas_used - share * U256::from(trades);
+    (0..trades)
+        .map(|i| RelayerTx { gas_used: if i == 0 { share + remainder } else { share }, ..*tx })
+        .collect()
+}
+
+#[derive(Debug, Clone)]
+pub struct BatchConfig {
+    pub multicall: Address,
+    pub max_size: usize,
+    pub window: Duration,
+}
+
+impl BatchConfig {
+    /// SETTLEMENT_BATCH_SIZE, SETTLEMENT_BATCH_WINDOW_MS and MULTICALL3_ADDRESS
+    /// (None when batching is disabled)
+    pub fn from_env() -> Option<Self> {
+        let max_size = std::env::var("SETTLEMENT_BATCH_SIZE").ok().and_then(|v| v.parse::<usize>().ok())?;
+        if max_size < 2 {
+            return None;
+        }
+        let window_ms = std::env::var("SETTLEMENT_BATCH_WINDOW_MS")
+            .ok()
+            .and_then(|v| v.parse().ok())
+            .unwrap_or(DEFAULT_BATCH_WINDOW_MS);
+        let multicall = std::env::var("MULTICALL3_ADDRESS").unwrap_or_else(|_| MULTICALL3_ADDRESS.to_string());
+        let multicall = match multicall.parse() {
+            Ok(address) => address,
+            Err(_) => {
+                tracing::warn!("⚠️  Invalid MULTICALL3_ADDRESS {}, settlement batching disabled", multicall);
+                return None;
+            }
+        };
+        Some(Self { multicall, max_size, window: Duration::from_millis(window_ms) })
+    }
+}
+
+/// Called with the hash of every transaction sent for a proof (batch, then fallback)
+pub type OnSent = Arc<dyn Fn(H256) + Send + Sync>;
+
+/// How a proof submitted through the batcher ended up
+pub struct BatchedSubmission {
+    /// The transaction that settled the trade, or why it wasn't settled
+    /// (a batch transaction's gas_used is this trade's share)
+    pub result: Result<RelayerTx, EthereumClientError>,
+    /// This trade's share of a batch transaction it rode in without settling
+    pub unsettled_batch: Option<RelayerTx>,
+}
+
+impl BatchedSubmission {
+    fn failed(error: EthereumClientError) -> Self {
+        Self { result: Err(error), unsettled_batch: None }
+    }
+}
+
+struct Pending {
+    proof: ProofSubmission,
+    on_sent: OnSent,
+    reply: oneshot::Sender<BatchedSubmission>,
+}
+
+pub struct SettlementBatcher {
+    sender: mpsc::Sender<Pending>,
+}
+
+impl SettlementBatcher {
+    /// Spawn the batching loop
+    pub fn spawn(client: Arc<EthereumClient>, config: BatchConfig) -> Arc<Self> {
+        let (sender, receiver) = mpsc::channel(config.max_size * 4);
+        tokio::spawn(run(client, config, receiver));
+        Arc::new(Self { sender })
+    }
+
+    /// Submit a proof with the next batch and wait for its outcome
+    pub async fn submit(&self, proof: ProofSubmission, on_sent: OnSent) -> BatchedSubmission {
+        let stopped = || EthereumClientError::TransactionFailed("Settlement batcher stopped".to_string());
+        let (reply, outcome) = oneshot::channel();
+        if self.sender.send(Pending { proof, on_sent, reply }).await.is_err() {
+            return BatchedSubmission::failed(stopped());
+        }
+        outcome.await.unwrap_or_else(|_| BatchedSubmission::failed(stopped()))
+    }
+}
+
+/// Collect proofs for one window (or until the batch is full) and settle them
+async fn run(client: Arc<EthereumClient>, config: BatchConfig, mut receiver: mpsc::Receiver<Pending>) {
+    while let Some(first) = receiver.recv().await {
+        let mut batch = vec![first];
+        let deadline = Instant::now() + config.window;
+        while batch.len() < config.max_size {
+            match timeout_at(deadline, receiver.recv()).await {
+                Ok(Some(pending)) => batch.push(pending),
+                _ => break,
+            }
+        }
+        // The next batch collects while this one confirms
+        tokio::spawn(settle(client.clone(), config.multicall, batch));
+    }
+}
+
+async fn settle(client: Arc<EthereumClient>, multicall: Address, batch: Vec<Pending>) {
+    if batch.len() == 1 {
+        let pending = batch.into_iter().next().expect("batch has one proof");
+        let result = submit_individually(&client, &pending).await;
+        let _ = pending.reply.send(BatchedSubmission { result, unsettled_batch: None });
+        return;
+    }
+
+    let proofs: Vec<ProofSubmission> = batch.iter().map(|pending| pending.proof.clone()).collect();
+    let mut sent = false;
+    let result = client
+        .submit_payment_proof_batch(multicall, &proofs, |tx_hash| {
+            sent = true;
+            for pending in &batch {
+                (pending.on_sent)(tx_hash);
+            }
+        })
+        .await;
+
+    // (pending proof, this trade's share of the batch) for individual submission
+    let retry: Vec<(Pending, Option<RelayerTx>)> = match result {
+        Ok((tx, settled)) => {
+            tracing::info!(
+                "📦 Settlement batch {:#x}: {}/{} trades settled",
+                tx.tx_hash,
+                settled.len(),
+                batch.len()
+            );
+            let shares = gas_shares(&tx, batch.len());
+            let mut retry = Vec::new();
+            for (pending, share) in batch.into_iter().zip(shares) {
+                if settled.contains(&pending.proof.trade_id) {
+                    let _ = pending.reply.send(BatchedSubmission { result: Ok(share), unsettled_batch: None });
+                } else {
+                    retry.push((pending, Some(share)));
+                }
+            }
+            retry
+        }
+        Err(EthereumClientError::TransactionReverted(tx)) => {
+            tracing::warn!("📦 Settlement batch {:#x} reverted, submitting {} proofs individually", tx.tx_hash, batch.len());
+            let shares = gas_shares(&tx, batch.len());
+            batch.into_iter().zip(shares.into_iter().map(Some)).collect()
+        }
+        Err(e) if sent => {
+            tracing::warn!("📦 Settlement batch of {} proofs has an unknown outcome: {}", batch.len(), e);
+            for pending in batch {
+                let error = EthereumClientError::TransactionFailed(e.to_string());
+                let _ = pending.reply.send(BatchedSubmission::failed(error));
+            }
+            return;
+        }
+        Err(e) => {
+            tracing::warn!("📦 Settlement batch of {} proofs not sent ({}), submitting individually", batch.len(), e);
+            batch.into_iter().map(|pending| (pending, None)).collect()
+        }
+    };
+
+    join_all(retry.into_iter().map(|(pending, unsettled_batch)| {
+        let client = client.clone();
+        async move {
+            let result = submit_individually(&client, &pending).await;
+            let _ = pending.reply.send(BatchedSubmission { result, unsettled_batch });
+        }
+    }))
+    .await;
+}
+
+async fn submit_individually(client: &EthereumClient, pending: &Pending) -> Result<RelayerTx, EthereumClientError> {
+    let proof = pending.proof.clone();
+    client
+        .submit_payment_proof_with_gas(
+            proof.trade_id,
+            proof.user_public_values,
+            proof.accumulator,
+            proof.proof,
+            GasOverride::default(),
+            |tx_hash| (pending.on_sent)(tx_hash),
+        )
+        .await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ethers::abi::AbiDecode;
+
+    #[test]
+    fn test_batch_calls_and_gas_shares() {
+        let escrow = Address::repeat_byte(0xee);
+        let proof = |byte: u8| ProofSubmission {
+            trade_id: [byte; 32],
+            user_public_values: [7; 32],
+            accumulator: vec![1; 384],
+            proof: vec![2; 1376],
+        };
+
+        let calls = aggregate3_calls(escrow, &[proof(0x0a), proof(0x0b)]);
+        assert_eq!(calls.len(), 2);
+        let (target, allow_failure, data) = &calls[1];
+        assert_eq!((*target, *allow_failure), (escrow, true));
+        let call = SubmitPaymentProofCall::decode(data).unwrap();
+        assert_eq!((call.trade_id, call.proof.len()), ([0x0b; 32], 1376));
+        assert!(multicall3_abi().function("aggregate3").is_ok());
+
+        let tx = RelayerTx {
+            tx_hash: H256::repeat_byte(0x01),
+            relayer: Address::repeat_byte(0x02),
+            gas_used: U256::from(1_000_001),
+            effective_gas_price: U256::from(1_000_000_000u64),
+            block_number: Some(10),
+        };
+        let shares = gas_shares(&tx, 3);
+        assert_eq!(shares.iter().map(|s| s.gas_used.as_u64()).collect::<Vec<_>>(), vec![333_335, 333_333, 333_333]);
+        assert!(shares.iter().all(|s| s.tx_hash == tx.tx_hash));
+    }
+}
@@ -0,0 +1,347 @@
+// Relayer transaction signing
+// The relayer key no longer has to live on the API host: transactions are signed through
+// the TxSigner trait, backed by a local key, an AWS KMS key, or a remote signing service.
+//
+// Configuration (env):
+// - RELAYER_SIGNER: local (default) | aws-kms | remote
+// - local:   RELAYER_PRIVATE_KEY, optional RELAYER_STANDBY_KEYS (comma-separated)
+// - aws-kms: RELAYER_KMS_KEY_ID, optional RELAYER_STANDBY_KMS_KEY_IDS (requires the aws-kms feature;
+//            region and credentials come from the standard AWS environment)
+// - remote:  RELAYER_REMOTE_SIGNER_URL, RELAYER_REMOTE_SIGNER_ADDRESSES (comma-separated, first is active)
+//
+// Remote signer protocol: POST {url}/sign with {"address": "0x..", "digest": "0x<32 bytes>"}
+// returns {"signature": "0x<r||s||v>"} (65 bytes, v = 0/1 or 27/28). Every signature is
+// checked to recover to the requested address before it is used.
+
+use async_trait::async_trait;
+use ethers::signers::{to_eip155_v, LocalWallet, Signer, WalletError};
+use ethers::types::transaction::eip2718::TypedTransaction;
+use ethers::types::transaction::eip712::Eip712;
+use ethers::types::{Address, Signature, H256};
+use ethers::utils::hash_message;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::sync::Arc;
+use thiserror::Error;
+
+use crate::hexutil;
+
+#[derive(Error, Debug)]
+pub enum TxSignerError {
+    #[error("Local signer error: {0}")]
+    Local(#[from] WalletError),
+    #[error("AWS KMS signer error: {0}")]
+    Kms(String),
+    #[error("Remote signer error: {0}")]
+    Remote(String),
+    #[error("Signer configuration error: {0}")]
+    Config(String),
+    #[error("Unsupported signing operation: {0}")]
+    Unsupported(String),
+}
+
+/// Signs relayer transactions
+/// Object-safe counterpart of ethers' `Signer`, so the backend can be chosen at runtime.
+#[async_trait]
+pub trait TxSigner: Send + Sync + fmt::Debug {
+    fn address(&self) -> Address;
+
+    fn chain_id(&self) -> u64;
+
+    /// Sign a transaction (EIP-155 `v`)
+    async fn sign_transaction(&self, tx: &TypedTransaction) -> Result<Signature, TxSignerError>;
+
+    /// Sign an EIP-191 personal message
+    async fn sign_message(&self, message: &[u8]) -> Result<Signature, TxSignerError>;
+}
+
+#[async_trait]
+impl TxSigner for LocalWallet {
+    fn address(&self) -> Address {
+        Signer::address(self)
+    }
+
+    fn chain_id(&self) -> u64 {
+        Signer::chain_id(self)
+    }
+
+    async fn sign_transaction(&self, tx: &TypedTransaction) -> Result<Signature, TxSignerError> {
+        Ok(Signer::sign_transaction(self, tx).await?)
+    }
+
+    async fn sign_message(&self, message: &[u8]) -> Result<Signature, TxSignerError> {
+        Ok(Signer::sign_message(self, message).await?)
+    }
+}
+
+#[cfg(feature = "aws-kms")]
+#[async_trait]
+impl TxSigner for ethers::signers::AwsSigner {
+    fn address(&self) -> Address {
+        Signer::address(self)
+    }
+
+    fn chain_id(&self) -> u64 {
+        Signer::chain_id(self)
+    }
+
+    async fn sign_transaction(&self, tx: &TypedTransaction) -> Result<Signature, TxSignerError> {
+        Signer::sign_transaction(self, tx)
+            .await
+            .map_err(|e| TxSignerError::Kms(e.to_string()))
+    }
+
+    async fn sign_message(&self, message: &[u8]) -> Result<Signature, TxSignerError> {
+        Signer::sign_message(self, message)
+            .await
+            .map_err(|e| TxSignerError::Kms(e.to_string()))
+    }
+}
+
+#[derive(Debug, Serialize)]
+struct RemoteSignRequest {
+    address: String,
+    digest: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct RemoteSignResponse {
+    signature: String,
+}
+
+/// Signer backed by a remote signing service that signs raw 32-byte digests
+#[derive(Debug, Clone)]
+pub struct RemoteSigner {
+    http: reqwest::Client,
+    url: String,
+    address: Address,
+    chain_id: u64,
+}
+
+impl RemoteSigner {
+    pub fn new(url: &str, address: Address, chain_id: u64) -> Self {
+        Self {
+            http: reqwest::Client::new(),
+            url: url.trim_end_matches('/').to_string(),
+            address,
+            chain_id,
+        }
+    }
+
+    /// Sign a digest remotely; returned `v` is 27/28
+    async fn sign_digest(&self, digest: H256) -> Result<Signature, TxSignerError> {
+        let response = self
+            .http
+            .post(format!("{}/sign", self.url))
+            .json(&RemoteSignRequest {
+                address: format!("{:#x}", self.address),
+                digest: format!("{:#x}", digest),
+            })
+            .send()
+            .await
+            .map_err(|e| TxSignerError::Remote(e.to_string()))?
+            .error_for_status()
+            .map_err(|e| TxSignerError::Remote(e.to_string()))?
+            .json::<RemoteSignResponse>()
+            .await
+            .map_err(|e| TxSignerError::Remote(format!("Invalid response: {}", e)))?;
+
+        let bytes = hexutil::decode(&response.signature)
+            .map_err(|e| TxSignerError::Remote(format!("Invalid signature hex: {}", e)))?;
+        let mut signature = Signature::try_from(bytes.as_slice())
+            .map_err(|e| TxSignerError::Remote(format!("Invalid signature: {}", e)))?;
+        if signature.v < 27 {
+            signature.v += 27;
+        }
+
+        // Never hand out a signature from the wrong key
+        let recovered = signature
+            .recover(digest)
+            .map_err(|e| TxSignerError::Remote(format!("Unrecoverable signature: {}", e)))?;
+        if recovered != self.address {
+            return Err(TxSignerError::Remote(format!(
+                "Signature recovers to {:#x}, expected {:#x}",
+                recovered, self.address
+            )));
+        }
+
+        Ok(signature)
+    }
+}
+
+#[async_trait]
+impl TxSigner for RemoteSigner {
+    fn address(&self) -> Address {
+        self.address
+    }
+
+    fn chain_id(&self) -> u64 {
+        self.chain_id
+    }
+
+    async fn sign_transaction(&self, tx: &TypedTransaction) -> Result<Signature, TxSignerError> {
+        // Same as LocalWallet: sighash over the tx with its chain id, then EIP-155 v
+        let chain_id = tx.chain_id().map(|id| id.as_u64()).unwrap_or(self.chain_id);
+        let mut tx = tx.clone();
+        tx.set_chain_id(chain_id);
+
+        let mut signature = self.sign_digest(tx.sighash()).await?;
+        signature.v = to_eip155_v(signature.v as u8 - 27, chain_id);
+        Ok(signature)
+    }
+
+    async fn sign_message(&self, message: &[u8]) -> Result<Signature, TxSignerError> {
+        self.sign_digest(hash_message(message)).await
+    }
+}
+
+/// Adapter that lets any TxSigner drive ethers' SignerMiddleware
+#[derive(Debug, Clone)]
+pub struct DynSigner(Arc<dyn TxSigner>);
+
+impl DynSigner {
+    pub fn new(signer: Arc<dyn TxSigner>) -> Self {
+        Self(signer)
+    }
+}
+
+#[async_trait]
+impl Signer for DynSigner {
+    type Error = TxSignerError;
+
+    async fn sign_message<S: Send + Sync + AsRef<[u8]>>(&self, message: S) -> Result<Signature, Self::Error> {
+        self.0.sign_message(message.as_ref()).await
+    }
+
+    async fn sign_transaction(&self, tx: &TypedTransaction) -> Result<Signature, Self::Error> {
+        self.0.sign_transaction(tx).await
+    }
+
+    async fn sign_typed_data<T: Eip712 + Send + Sync>(&self, _payload: &T) -> Result<Signature, Self::Error> {
+        Err(TxSignerError::Unsupported("EIP-712 signing is not used by the relayer".to_string()))
+    }
+
+    fn address(&self) -> Address {
+        self.0.address()
+    }
+
+    fn chain_id(&self) -> u64 {
+        self.0.chain_id()
+    }
+
+    /// The chain id is fixed when the underlying signer is created
+    fn with_chain_id<T: Into<u64>>(self, _chain_id: T) -> Self {
+        self
+    }
+}
+
+/// Parse a raw private key into a local signer
+pub fn local_signer(private_key: &str, chain_id: u64) -> Result<Arc<dyn TxSigner>, TxSignerError> {
+    let wallet: LocalWallet = private_key
+        .trim()
+        .parse()
+        .map_err(|e| TxSignerError::Config(format!("Invalid private key: {}", e)))?;
+    Ok(Arc::new(wallet.with_chain_id(chain_id)))
+}
+
+fn env_list(name: &str) -> Vec<String> {
+    std::env::var(name)
+        .map(|value| {
+            value
+                .split(',')
+                .map(str::trim)
+                .filter(|v| !v.is_empty())
+                .map(String::from)
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+fn required_env(name: &str) -> Result<String, TxSignerError> {
+    std::env::var(name).map_err(|_| TxSignerError::Config(format!("{} must be set", name)))
+}
+
+/// Build the relayer signers (active first, then standbys) from RELAYER_SIGNER and friends
+pub async fn signers_from_env(chain_id: u64) -> Result<Vec<Arc<dyn TxSigner>>, TxSignerError> {
+    let backend = std::env::var("RELAYER_SIGNER").unwrap_or_else(|_| "local".to_string());
+
+    match backend.trim().to_ascii_lowercase().as_str() {
+        "local" => {
+            let mut keys = vec![required_env("RELAYER_PRIVATE_KEY")?];
+            keys.extend(env_list("RELAYER_STANDBY_KEYS"));
+            keys.iter().map(|key| local_signer(key, chain_id)).collect()
+        }
+        "aws-kms" => {
+            let mut key_ids = vec![required_env("RELAYER_KMS_KEY_ID")?];
+            key_ids.extend(env_list("RELAYER_STANDBY_KMS_KEY_IDS"));
+            kms_signers(&key_ids, chain_id).await
+        }
+        "remote" => {
+            let url = required_env("RELAYER_REMOTE_SIGNER_URL")?;
+            let addresses = env_list("RELAYER_REMOTE_SIGNER_ADDRESSES");
+            if addresses.is_empty() {
+                return Err(TxSignerError::Config("RELAYER_REMOTE_SIGNER_ADDRESSES must be set".to_string()));
+            }
+            addresses
+                .iter()
+                .map(|address| {
+                    let address: Address = address
+                        .parse()
+                        .map_err(|_| TxSignerError::Config(format!("Invalid remote signer address {}", address)))?;
+                    Ok(Arc::new(RemoteSigner::new(&url, address, chain_id)) as Arc<dyn TxSigner>)
+                })
+                .collect()
+        }
+        other => Err(TxSignerError::Config(format!(
+            "Unknown RELAYER_SIGNER {:?} (expected local, aws-kms or remote)",
+            other
+        ))),
+    }
+}
+
+#[cfg(feature = "aws-kms")]
+async fn kms_signers(key_ids: &[String], chain_id: u64) -> Result<Vec<Arc<dyn TxSigner>>, TxSignerError> {
+    let kms = rusoto_kms::KmsClient::new(rusoto_core::Region::default());
+    let mut signers: Vec<Arc<dyn TxSigner>> = Vec::with_capacity(key_ids.len());
+    for key_id in key_ids {
+        let signer = ethers::signers::AwsSigner::new(kms.clone(), key_id, chain_id)
+            .await
+            .map_err(|e| TxSignerError::Kms(format!("{}: {}", key_id, e)))?;
+        signers.push(Arc::new(signer));
+    }
+    Ok(signers)
+}
+
+#[cfg(not(feature = "aws-kms"))]
+async fn kms_signers(_key_ids: &[String], _chain_id: u64) -> Result<Vec<Arc<dyn TxSigner>>, TxSignerError> {
+    Err(TxSignerError::Config(
+        "RELAYER_SIGNER=aws-kms requires building with the aws-kms feature".to_string(),
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ethers::types::TransactionRequest;
+
+    // Well-known anvil dev key
+    const KEY: &str = "ac0974bec39a17e36ba4a6b4d238ff944bacb478cbed5efcae784d7bf4f2ff80";
+
+    #[tokio::test]
+    async fn test_dyn_signer_matches_local_wallet() {
+        let wallet: LocalWallet = KEY.parse::<LocalWallet>().unwrap().with_chain_id(84532u64);
+        let signer = DynSigner::new(local_signer(KEY, 84532).unwrap());
+        assert_eq!(Signer::address(&signer), Signer::address(&wallet));
+
+        let tx: TypedTransaction = TransactionRequest::new()
+            .to(Address::repeat_byte(0x11))
+            .value(1)
+            .nonce(0)
+            .into();
+        let expected = Signer::sign_transaction(&wallet, &tx).await.unwrap();
+        assert_eq!(Signer::sign_transaction(&signer, &tx).await.unwrap(), expected);
+
+        let signature = Signer::sign_message(&signer, b"zkAliPay").await.unwrap();
+        assert_eq!(signature.recover("zkAliPay").unwrap(), Signer::address(&wallet));
+    }
+}
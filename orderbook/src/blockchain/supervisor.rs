@@ -0,0 +1,293 @@
+use ethers::types::Address;
+use std::sync::Arc;
+use tokio::task::JoinHandle;
+use tokio::time::{interval, sleep, Duration, Instant};
+
+use super::config_cache::ContractConfigCache;
+use super::read_cache::ContractReadCache;
+use super::events::{EventListener, EventListenerError};
+use crate::db::leader::{LeaderLock, DEFAULT_LEADER_RETRY, LOCK_EVENT_LISTENER};
+use crate::fees::FeeEngine;
+use crate::metrics::Metrics;
+use crate::notifications::sellers::SellerNotifier;
+use crate::receipts::ReceiptSigner;
+use crate::screening::Screening;
+
+type ListenerTask = JoinHandle<Result<(), EventListenerError>>;
+
+/// Supervision tuning (all values overridable via environment)
+#[derive(Debug, Clone)]
+pub struct SupervisorConfig {
+    /// Restart the listener when lag stays above this many blocks
+    pub max_lag_blocks: u64,
+    /// How long lag may stay above the threshold before restarting
+    pub lag_grace: Duration,
+    /// Restart the listener when no head poll succeeded for this long
+    pub stall_timeout: Duration,
+    /// How often the supervisor checks listener health
+    pub check_interval: Duration,
+    /// First restart delay (doubles on each consecutive failure)
+    pub initial_backoff: Duration,
+    /// Upper bound for restart delay
+    pub max_backoff: Duration,
+    /// A run lasting longer than this resets the backoff
+    pub healthy_after: Duration,
+    /// How often a standby instance retries the leader lock
+    pub leader_retry: Duration,
+}
+
+impl Default for SupervisorConfig {
+    fn default() -> Self {
+        Self {
+            max_lag_blocks: 300, // ~10 minutes on Base (2s blocks)
+            lag_grace: Duration::from_secs(120),
+            stall_timeout: Duration::from_secs(180),
+            check_interval: Duration::from_secs(15),
+            initial_backoff: Duration::from_secs(2),
+            max_backoff: Duration::from_secs(300),
+            healthy_after: Duration::from_secs(600),
+            leader_retry: DEFAULT_LEADER_RETRY,
+        }
+    }
+}
+
+impl SupervisorConfig {
+    /// Load from LISTENER_* environment variables, falling back to defaults
+    pub fn from_env() -> Self {
+        fn env_u64(key: &str) -> Option<u64> {
+            std::env::var(key).ok().and_then(|v| v.parse().ok())
+        }
+
+        let defaults = Self::default();
+        Self {
+            max_lag_blocks: env_u64("LISTENER_MAX_LAG_BLOCKS").unwrap_or(defaults.max_lag_blocks),
+            lag_grace: env_u64("LISTENER_LAG_GRACE_SECS").map(Duration::from_secs).unwrap_or(defaults.lag_grace),
+            stall_timeout: env_u64("LISTENER_STALL_TIMEOUT_SECS").map(Duration::from_secs).unwrap_or(defaults.stall_timeout),
+            check_interval: defaults.check_interval,
+            initial_backoff: defaults.initial_backoff,
+            max_backoff: env_u64("LISTENER_MAX_BACKOFF_SECS").map(Duration::from_secs).unwrap_or(defaults.max_backoff),
+            healthy_after: defaults.healthy_after,
+            leader_retry: env_u64("LISTENER_LEADER_RETRY_SECS").map(Duration::from_secs).unwrap_or(defaults.leader_retry),
+        }
+    }
+}
+
+/// Keeps the event listener alive: restarts it with exponential backoff when the
+/// task exits, panics, stalls, or falls too far behind the chain head.
+/// Only the instance holding the listener leader lock runs it (see db::leader);
+/// other instances stand by and take over when the leader's session ends.
+pub struct ListenerSupervisor {
+    rpc_url: String,
+    contract_address: Address,
+    db_pool: sqlx::PgPool,
+    receipt_signer: Option<Arc<ReceiptSigner>>,
+    fee_engine: Option<Arc<FeeEngine>>,
+    config_cache: Option<Arc<ContractConfigCache>>,
+    read_cache: Option<Arc<ContractReadCache>>,
+    screening: Option<Arc<Screening>>,
+    seller_notifier: Option<Arc<SellerNotifier>>,
+    attest_settlements: bool,
+    metrics: Arc<Metrics>,
+    config: SupervisorConfig,
+}
+
+impl ListenerSupervisor {
+    pub fn new(
+        rpc_url: &str,
+        contract_address: Address,
+        db_pool: sqlx::PgPool,
+        metrics: Arc<Metrics>,
+        config: SupervisorConfig,
+    ) -> Self {
+        Self {
+            rpc_url: rpc_url.to_string(),
+            contract_address,
+            db_pool,
+            receipt_signer: None,
+            fee_engine: None,
+            config_cache: None,
+            read_cache: None,
+            screening: None,
+            seller_notifier: None,
+            attest_settlements: false,
+            metrics,
+            config,
+        }
+    }
+
+    pub fn with_receipt_signer(mut self, signer: Arc<ReceiptSigner>) -> Self {
+        self.receipt_signer = Some(signer);
+        self
+    }
+
+    pub fn with_fee_engine(mut self, engine: Arc<FeeEngine>) -> Self {
+        self.fee_engine = Some(engine);
+        self
+    }
+
+    pub fn with_config_cache(mut self, cache: Arc<ContractConfigCache>) -> Self {
+        self.config_cache = Some(cache);
+        self
+    }
+
+    pub fn with_read_cache(mut self, cache: Arc<ContractReadCache>) -> Self {
+        self.read_cache = Some(cache);
+        self
+    }
+
+    pub fn with_screening(mut self, screening: Arc<Screening>) -> Self {
+        self.screening = Some(screening);
+        self
+    }
+
+    pub fn with_seller_notifier(mut self, notifier: Arc<SellerNotifier>) -> Self {
+        self.seller_notifier = Some(notifier);
+        self
+    }
+
+    pub fn with_attestations(mut self) -> Self {
+        self.attest_settlements = true;
+        self
+    }
+
+    /// Spawn the supervision loop as a background task
+    pub fn spawn(self) -> JoinHandle<()> {
+        self.metrics.listener.set_enabled();
+        tokio::spawn(async move { self.run().await })
+    }
+
+    async fn run(self) {
+        let mut backoff = self.config.initial_backoff;
+
+        loop {
+            let mut lock = LeaderLock::acquire(&self.db_pool, "event listener", LOCK_EVENT_LISTENER, self.config.leader_retry).await;
+            self.metrics.listener.set_leader(true);
+            let started = Instant::now();
+
+            let reason = match self.start_listener().await {
+                Ok(mut handle) => {
+                    tracing::info!("🎧 Event listener task started");
+                    self.metrics.listener.set_running(true);
+                    let reason = self.watch(&mut handle, &mut lock).await;
+                    handle.abort();
+                    self.metrics.listener.set_running(false);
+                    reason
+                }
+                Err(e) => format!("failed to initialize: {}", e),
+            };
+
+            // Step down while backing off so a healthy standby can take over
+            lock.release().await;
+            self.metrics.listener.set_leader(false);
+
+            // A long healthy run means the next failure is unrelated - start backoff over
+            if started.elapsed() >= self.config.healthy_after {
+                backoff = self.config.initial_backoff;
+            }
+
+            tracing::error!("❌ Event listener stopped ({}), restarting in {:?}", reason, backoff);
+            self.metrics.listener.record_restart(&reason);
+
+            sleep(backoff).await;
+            backoff = (backoff * 2).min(self.config.max_backoff);
+        }
+    }
+
+    /// Build a fresh listener (resumes from the last synced block in the DB) and spawn it
+    async fn start_listener(&self) -> Result<ListenerTask, EventListenerError> {
+        let listener = EventListener::new(&self.rpc_url, self.contract_address, self.db_pool.clone(), None)
+            .await?
+            .with_metrics(self.metrics.clone());
+
+        let listener = match &self.receipt_signer {
+            Some(signer) => listener.with_receipt_signer(signer.clone()),
+            None => listener,
+        };
+
+        let listener = match &self.fee_engine {
+            Some(engine) => listener.with_fee_engine(engine.clone()),
+            None => listener,
+        };
+
+        let listener = match &self.config_cache {
+            Some(cache) => listener.with_config_cache(cache.clone()),
+            None => listener,
+        };
+
+        let listener = match &self.read_cache {
+            Some(cache) => listener.with_read_cache(cache.clone()),
+            None => listener,
+        };
+
+        let listener = match &self.screening {
+            Some(screening) => listener.with_screening(screening.clone()),
+            None => listener,
+        };
+
+        let listener = match &self.seller_notifier {
+            Some(notifier) => listener.with_seller_notifier(notifier.clone()),
+            None => listener,
+        };
+
+        let mut listener = if self.attest_settlements {
+            listener.with_attestations()
+        } else {
+            listener
+        };
+
+        Ok(tokio::spawn(async move { listener.start().await }))
+    }
+
+    /// Wait until the listener task ends or becomes unhealthy; returns the restart reason
+    async fn watch(&self, handle: &mut ListenerTask, lock: &mut LeaderLock) -> String {
+        let mut check = interval(self.config.check_interval);
+        let mut lagging_since: Option<Instant> = None;
+        let watch_started = Instant::now();
+
+        loop {
+            tokio::select! {
+                result = &mut *handle => {
+                    return match result {
+                        Ok(Ok(())) => "task exited".to_string(),
+                        Ok(Err(e)) => format!("task failed: {}", e),
+                        Err(e) if e.is_panic() => "task panicked".to_string(),
+                        Err(e) => format!("task aborted: {}", e),
+                    };
+                }
+                _ = check.tick() => {
+                    // Another instance may have taken over after our session dropped
+                    if !lock.is_held().await {
+                        return "lost leader lock".to_string();
+                    }
+
+                    let listener = &self.metrics.listener;
+
+                    // Stall: no successful head poll for too long (e.g. hung RPC call)
+                    let last_poll_age = listener
+                        .last_poll_at()
+                        .map(|ts| (chrono::Utc::now().timestamp() - ts).max(0) as u64)
+                        .unwrap_or_else(|| watch_started.elapsed().as_secs());
+                    if last_poll_age > self.config.stall_timeout.as_secs()
+                        && watch_started.elapsed() > self.config.stall_timeout
+                    {
+                        return format!("stalled: no head poll for {}s", last_poll_age);
+                    }
+
+                    // Lag: sustained distance from chain head above the threshold
+                    let lag = listener.lag_blocks();
+                    if lag > self.config.max_lag_blocks {
+                        let since = *lagging_since.get_or_insert_with(Instant::now);
+                        if since.elapsed() >= self.config.lag_grace {
+                            return format!(
+                                "lag {} blocks above threshold {} for {:?}",
+                                lag, self.config.max_lag_blocks, self.config.lag_grace
+                            );
+                        }
+                    } else {
+                        lagging_since = None;
+                    }
+                }
+            }
+        }
+    }
+}
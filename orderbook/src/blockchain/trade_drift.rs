@@ -0,0 +1,238 @@
+// Trade status drift
+// A TradeSettled or TradeExpired event the listener missed leaves the trade PENDING
+// here while the escrow has resolved it: the buyer sees an unsettled trade, and once it
+// is past its expiry auto-cancel tries to cancel it, paying gas for a revert. The
+// checker compares PENDING trades older than a few minutes with the escrow's storage.
+// Each resolved one is counted (zkalipay_trade_drift_total) and repaired: the missed
+// event is looked up from the trade's fill block on and queued as a dead letter, so the
+// listener applies it with everything else it does for that event (settlement tx, fees,
+// receipt, liquidity returned to the order). Without an event only the status is fixed.
+//
+// Configuration (env):
+// - TRADE_DRIFT_MIN_AGE_MINS: only trades PENDING for longer are checked (default 10)
+// - TRADE_DRIFT_SCAN_SECS: how often PENDING trades are checked (default 300)
+
+use chrono::Utc;
+use ethers::types::{Log, H256};
+use std::sync::Arc;
+use tokio::task::JoinHandle;
+use tokio::time::{interval, Duration};
+
+use super::client::EthereumClient;
+use super::events::{EVENT_TRADE_EXPIRED, EVENT_TRADE_SETTLED, TRADE_SETTLED_SIGNATURE};
+use super::ids::TradeId;
+use crate::db::dead_letters::{DeadLetterRepository, NewDeadLetter, PostgresDeadLetterRepository};
+use crate::db::models::DbPendingTrade;
+use crate::db::trade_drift::{PostgresTradeDriftRepository, TradeDriftRepository};
+use crate::metrics::Metrics;
+
+/// Default age below which PENDING trades aren't checked (events may still be in flight)
+pub const DEFAULT_DRIFT_MIN_AGE_MINS: u64 = 10;
+
+/// Default interval between scans
+pub const DEFAULT_DRIFT_SCAN_SECS: u64 = 300;
+
+/// Recorded as the resolver of the dead letters the checker queues
+pub const DRIFT_RESOLVER: &str = "trade-drift";
+
+/// Maximum trades checked per scan
+const SCAN_BATCH: i64 = 100;
+
+/// Blocks per log query when searching for the missed event
+const LOG_SEARCH_STEP: u64 = 2_000;
+
+/// Blocks searched back from the head when the fill block is unknown (~1 day on Base)
+const LOG_SEARCH_FALLBACK: u64 = 43_200;
+
+/// Label of an on-chain TradeStatus the DB should have followed (None for PENDING)
+pub fn drift_status(status: u8) -> Option<&'static str> {
+    match status {
+        1 => Some("settled"),
+        2 => Some("expired"),
+        _ => None,
+    }
+}
+
+pub struct TradeDriftChecker {
+    client: Arc<EthereumClient>,
+    repo: PostgresTradeDriftRepository,
+    dead_letters: PostgresDeadLetterRepository,
+    metrics: Arc<Metrics>,
+    min_age: Duration,
+    scan_interval: Duration,
+}
+
+impl TradeDriftChecker {
+    pub fn new(
+        client: Arc<EthereumClient>,
+        db_pool: sqlx::PgPool,
+        metrics: Arc<Metrics>,
+        min_age: Duration,
+        scan_interval: Duration,
+    ) -> Self {
+        Self {
+            client,
+            repo: PostgresTradeDriftRepository::new(db_pool.clone()),
+            dead_letters: PostgresDeadLetterRepository::new(db_pool),
+            metrics,
+            min_age,
+            scan_interval,
+        }
+    }
+
+    /// Load the minimum age and scan interval from the environment
+    pub fn from_env(client: Arc<EthereumClient>, db_pool: sqlx::PgPool, metrics: Arc<Metrics>) -> Self {
+        fn env_u64(key: &str) -> Option<u64> {
+            std::env::var(key).ok().and_then(|v| v.parse().ok())
+        }
+
+        let min_age_mins = env_u64("TRADE_DRIFT_MIN_AGE_MINS").unwrap_or(DEFAULT_DRIFT_MIN_AGE_MINS);
+        let scan_secs = env_u64("TRADE_DRIFT_SCAN_SECS").unwrap_or(DEFAULT_DRIFT_SCAN_SECS);
+        Self::new(
+            client,
+            db_pool,
+            metrics,
+            Duration::from_secs(min_age_mins * 60),
+            Duration::from_secs(scan_secs.max(1)),
+        )
+    }
+
+    /// Spawn the scan loop as a background task
+    pub fn spawn(self) -> JoinHandle<()> {
+        tokio::spawn(async move { self.run().await })
+    }
+
+    async fn run(self) {
+        tracing::info!("🩹 Checking PENDING trades older than {:?} against the escrow", self.min_age);
+        let mut ticker = interval(self.scan_interval);
+        loop {
+            ticker.tick().await;
+            match self.scan().await {
+                Ok(0) => {}
+                Ok(drifted) => tracing::warn!("🩹 {} PENDING trade(s) were already resolved on-chain", drifted),
+                Err(e) => tracing::warn!("🩹 Trade drift scan failed: {}", e),
+            }
+        }
+    }
+
+    /// Compare old PENDING trades with the escrow and repair the ones it has resolved
+    /// Returns the number of drifted trades found.
+    pub async fn scan(&self) -> Result<usize, String> {
+        let created_before = Utc::now().timestamp() - self.min_age.as_secs() as i64;
+        let pending = self.repo.list_pending_before(created_before, SCAN_BATCH).await.map_err(|e| e.to_string())?;
+        self.metrics.trade_drift.record_checked(pending.len() as u64);
+
+        let mut drifted = 0;
+        for trade in pending {
+            let trade_id: TradeId = match trade.trade_id.parse() {
+                Ok(trade_id) => trade_id,
+                Err(e) => {
+                    tracing::warn!("🩹 Skipping trade with invalid ID {}: {}", trade.trade_id, e);
+                    continue;
+                }
+            };
+            let onchain = match self.client.get_onchain_trade(trade_id.0).await {
+                Ok(onchain) => onchain,
+                Err(e) => {
+                    tracing::warn!("🩹 Failed to read trade {} from the escrow: {}", trade.trade_id, e);
+                    continue;
+                }
+            };
+            let Some(status) = drift_status(onchain.status).filter(|_| onchain.exists) else {
+                continue;
+            };
+
+            drifted += 1;
+            self.metrics.trade_drift.record_drift(status);
+            tracing::warn!("🩹 Trade {} is PENDING here but {} on-chain", trade.trade_id, status);
+
+            if let Err(e) = self.repair(&trade, trade_id, onchain.status).await {
+                tracing::warn!("🩹 Failed to repair trade {}: {}", trade.trade_id, e);
+            }
+        }
+
+        Ok(drifted)
+    }
+
+    /// Queue the missed event for the listener, or set the status if it can't be found
+    async fn repair(&self, trade: &DbPendingTrade, trade_id: TradeId, status: u8) -> Result<(), String> {
+        match self.find_outcome_log(trade, trade_id).await? {
+            Some(log) => {
+                let dead_letter = missed_event(log).ok_or("event log without a tx hash")?;
+                let recorded = self.dead_letters.record(&dead_letter).await.map_err(|e| e.to_string())?;
+                self.dead_letters.queue(recorded.id, Some(DRIFT_RESOLVER)).await.map_err(|e| e.to_string())?;
+                tracing::info!(
+                    "🩹 Queued missed {} of trade {} (tx {}) as dead letter #{}",
+                    dead_letter.event_type, trade.trade_id, dead_letter.tx_hash, recorded.id
+                );
+            }
+            None => {
+                let updated = self.repo.resolve_pending(&trade.trade_id, status as i32).await.map_err(|e| e.to_string())?;
+                if updated {
+                    tracing::warn!("🩹 No event found for trade {}, set its status to {} only", trade.trade_id, status);
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// The trade's TradeSettled / TradeExpired log, searched from its fill block to the head
+    async fn find_outcome_log(&self, trade: &DbPendingTrade, trade_id: TradeId) -> Result<Option<Log>, String> {
+        let head = self.client.get_block_number().await.map_err(|e| e.to_string())?;
+        let fill_block = match trade.escrow_tx_hash.as_deref().and_then(|hash| hash.parse::<H256>().ok()) {
+            Some(hash) => self.client.transaction_block(hash).await.map_err(|e| e.to_string())?,
+            None => None,
+        };
+
+        let mut from = fill_block.unwrap_or_else(|| head.saturating_sub(LOG_SEARCH_FALLBACK));
+        while from <= head {
+            let to = (from + LOG_SEARCH_STEP - 1).min(head);
+            let logs = self.client.trade_outcome_logs(trade_id.0, from, to).await.map_err(|e| e.to_string())?;
+            if let Some(log) = logs.into_iter().next() {
+                return Ok(Some(log));
+            }
+            from = to + 1;
+        }
+        Ok(None)
+    }
+}
+
+/// Dead letter replaying a missed TradeSettled / TradeExpired log
+fn missed_event(log: Log) -> Option<NewDeadLetter> {
+    let settled = H256::from(ethers::utils::keccak256(TRADE_SETTLED_SIGNATURE));
+    let event_type = if log.topics.first() == Some(&settled) { EVENT_TRADE_SETTLED } else { EVENT_TRADE_EXPIRED };
+    Some(NewDeadLetter {
+        event_type: event_type.to_string(),
+        block_number: log.block_number.map(|b| b.as_u64() as i64).unwrap_or_default(),
+        tx_hash: format!("{:#x}", log.transaction_hash?),
+        log_index: log.log_index.map(|i| i.as_u64() as i64).unwrap_or_default(),
+        logs: serde_json::to_string(&[&log]).ok()?,
+        error: "Missed by the listener (found by the trade drift check)".to_string(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::blockchain::fixtures::LIFECYCLE;
+
+    #[test]
+    fn test_missed_event_replays_the_log() {
+        assert_eq!(drift_status(0), None);
+        assert_eq!(drift_status(1), Some("settled"));
+        assert_eq!(drift_status(2), Some("expired"));
+
+        let outcomes: Vec<_> = LIFECYCLE
+            .iter()
+            .filter(|f| f.event_type == EVENT_TRADE_SETTLED || f.event_type == EVENT_TRADE_EXPIRED)
+            .collect();
+        assert_eq!(outcomes.len(), 2);
+        for fixture in outcomes {
+            let dead_letter = missed_event(fixture.log()).unwrap();
+            assert_eq!(dead_letter.event_type, fixture.event_type);
+            assert_eq!(dead_letter.block_number, fixture.block_number as i64);
+            let logs: Vec<Log> = serde_json::from_str(&dead_letter.logs).unwrap();
+            assert_eq!(logs, vec![fixture.log()]);
+        }
+    }
+}
@@ -5,6 +5,8 @@ use ethers::abi::Token;
 use serde::{Deserialize, Serialize};
 use anyhow::Result;
 
+use crate::hexutil::{self, encode_0x};
+
 /// Payment details structure for contract interaction
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct PaymentDetails {
@@ -38,56 +40,212 @@ pub fn encode_payment_details(
     Ok(encoded)
 }
 
-/// Convert order ID string to bytes32
-pub fn order_id_to_bytes32(order_id: &str) -> Result<[u8; 32]> {
-    // Remove "0x" or "ord_" prefix if present
-    let hex_str = order_id
-        .strip_prefix("0x")
-        .or_else(|| order_id.strip_prefix("ord_"))
-        .unwrap_or(order_id);
-    
-    // Parse as hex
-    let bytes = hex::decode(hex_str)?;
-    
-    if bytes.len() != 32 {
-        return Err(anyhow::anyhow!("Order ID must be 32 bytes, got {}", bytes.len()));
+/// Length of payment nonces generated by the contract (_generate8DigitNonce)
+pub const PAYMENT_NONCE_LEN: usize = 8;
+
+/// Validate payment nonce format: exactly 8 ASCII digits
+pub fn validate_payment_nonce(nonce: &str) -> Result<()> {
+    if nonce.len() != PAYMENT_NONCE_LEN || !nonce.bytes().all(|b| b.is_ascii_digit()) {
+        return Err(anyhow::anyhow!(
+            "Payment nonce must be {} digits, got {:?}",
+            PAYMENT_NONCE_LEN,
+            nonce
+        ));
     }
-    
-    let mut result = [0u8; 32];
-    result.copy_from_slice(&bytes);
-    
-    Ok(result)
+    Ok(())
 }
 
-/// Convert trade ID string to bytes32
-pub fn trade_id_to_bytes32(trade_id: &str) -> Result<[u8; 32]> {
-    // Remove "0x" or "trade_" prefix if present
-    let hex_str = trade_id
-        .strip_prefix("0x")
-        .or_else(|| trade_id.strip_prefix("trade_"))
-        .unwrap_or(trade_id);
-    
-    // Parse as hex
-    let bytes = hex::decode(hex_str)?;
-    
-    if bytes.len() != 32 {
-        return Err(anyhow::anyhow!("Trade ID must be 32 bytes, got {}", bytes.len()));
+/// Contract trade value limits in CNY cents (minTradeValueCny, maxTradeValueCny)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TradeLimits {
+    pub min_cny: U256,
+    pub max_cny: U256,
+}
+
+impl TradeLimits {
+    /// Check a fill's CNY value against the limits, mirroring fillOrder's
+    /// AmountBelowMinimum / AmountTooLarge checks
+    pub fn check(&self, fill_value_cny: U256) -> std::result::Result<(), String> {
+        if fill_value_cny < self.min_cny {
+            return Err(format!(
+                "below the contract minimum of {} CNY",
+                format_cny_cents(self.min_cny)
+            ));
+        }
+        if fill_value_cny > self.max_cny {
+            return Err(format!(
+                "above the contract maximum of {} CNY",
+                format_cny_cents(self.max_cny)
+            ));
+        }
+        Ok(())
     }
-    
-    let mut result = [0u8; 32];
-    result.copy_from_slice(&bytes);
-    
-    Ok(result)
 }
 
-/// Convert bytes32 to hex string with prefix
-pub fn bytes32_to_order_id(bytes: [u8; 32]) -> String {
-    format!("ord_{}", hex::encode(bytes))
+/// CNY value (cents) of a fill, computed exactly as the contract does:
+/// fillAmount * exchangeRate / 10^tokenDecimals (integer division)
+pub fn fill_value_cny(fill_amount: U256, exchange_rate: U256, token_decimals: u8) -> U256 {
+    fill_amount.saturating_mul(exchange_rate) / U256::exp10(token_decimals as usize)
+}
+
+/// Format CNY cents as a decimal string (e.g., 70000 -> "700.00")
+pub fn format_cny_cents(cents: U256) -> String {
+    let hundred = U256::from(100);
+    format!("{}.{:02}", cents / hundred, (cents % hundred).as_u64())
+}
+
+/// Custom errors submitPaymentProof can revert with, and what they mean for the buyer
+pub const SETTLEMENT_REVERTS: &[(&str, &str)] = &[
+    (
+        "PaymentDetailsMismatch",
+        "Proof verification failed: Payment details do not match the trade. \
+         The proof was rejected by the smart contract.",
+    ),
+    (
+        "TradeNotPending",
+        "This trade is no longer pending. It may have already been settled or expired.",
+    ),
+    ("TradeAlreadySettled", "This trade has already been settled."),
+    ("TradeExpired", "This trade has expired and cannot be settled."),
+    ("NotAuthorized", "You are not authorized to submit proof for this trade."),
+    (
+        "ProofVerificationFailed",
+        "The zkPDF verifier rejected the proof. It may have been generated for a different appExeCommit/appVmCommit.",
+    ),
+    ("TradeNotFound", "This trade does not exist on-chain."),
+    ("EnforcedPause", "The contract is paused."),
+];
+
+/// Custom errors withdrawAmount can revert with, and what they mean for the seller
+pub const WITHDRAWAL_REVERTS: &[(&str, &str)] = &[
+    ("NotAuthorized", "Only the order's seller can withdraw from it."),
+    ("OrderNotFound", "This order does not exist on-chain."),
+    (
+        "WithdrawalExceedsAvailable",
+        "The amount is more than the order has left. Pending trades keep their share until they settle or expire.",
+    ),
+    ("AmountExceedsAvailable", "The amount is more than the order has left."),
+    ("TransferFailed", "The token transfer back to the seller failed."),
+    ("EnforcedPause", "The contract is paused."),
+];
+
+/// Match a settlement revert in a provider error message, by error name or 4-byte selector
+/// Returns (error name, explanation)
+pub fn decode_settlement_revert(error_msg: &str) -> Option<(&'static str, &'static str)> {
+    decode_revert(SETTLEMENT_REVERTS, error_msg)
+}
+
+/// Match a withdrawAmount revert in a provider error message, like decode_settlement_revert
+pub fn decode_withdrawal_revert(error_msg: &str) -> Option<(&'static str, &'static str)> {
+    decode_revert(WITHDRAWAL_REVERTS, error_msg)
+}
+
+fn decode_revert(reverts: &[(&'static str, &'static str)], error_msg: &str) -> Option<(&'static str, &'static str)> {
+    reverts.iter().copied().find(|(name, _)| {
+        let selector = encode_0x(ethers::utils::id(format!("{}()", name)));
+        error_msg.contains(&selector) || error_msg.contains(name)
+    })
+}
+
+/// EIP-712 domain of off-chain order intents (verifyingContract is the escrow)
+pub const ORDER_INTENT_DOMAIN_NAME: &str = "zkAliPay";
+pub const ORDER_INTENT_DOMAIN_VERSION: &str = "1";
+
+/// EIP-712 type strings for order intents and their cancellation
+pub const ORDER_INTENT_TYPE: &str = "OrderIntent(address seller,address token,uint256 amount,uint256 exchangeRate,bytes32 alipayHash,uint256 nonce,uint256 deadline)";
+pub const CANCEL_ORDER_INTENT_TYPE: &str = "CancelOrderIntent(bytes32 intentHash)";
+
+/// Seller-signed promise to create an escrow order with these terms ("post then fund"):
+/// the orderbook lists it before the seller funds the escrow
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct OrderIntent {
+    pub seller: Address,
+    pub token: Address,
+    /// Token base units
+    pub amount: U256,
+    /// CNY cents per whole token
+    pub exchange_rate: U256,
+    /// `alipay_hash(alipay_id, alipay_name)` - the payee stays private until funding
+    pub alipay_hash: H256,
+    /// Per-seller nonce, lets a seller post several intents with identical terms
+    pub nonce: U256,
+    /// Unix timestamp after which the intent is void
+    pub deadline: U256,
+}
+
+/// Commitment to the payee an order will carry: keccak256(abi.encode(alipayId, alipayName))
+pub fn alipay_hash(alipay_id: &str, alipay_name: &str) -> H256 {
+    let encoded = ethers::abi::encode(&[
+        Token::String(alipay_id.to_string()),
+        Token::String(alipay_name.to_string()),
+    ]);
+    H256::from(ethers::utils::keccak256(encoded))
+}
+
+/// EIP-712 domain separator for order intents on `chain_id`
+pub fn order_intent_domain_separator(chain_id: u64, escrow: Address) -> H256 {
+    let domain = ethers::types::transaction::eip712::EIP712Domain {
+        name: Some(ORDER_INTENT_DOMAIN_NAME.to_string()),
+        version: Some(ORDER_INTENT_DOMAIN_VERSION.to_string()),
+        chain_id: Some(U256::from(chain_id)),
+        verifying_contract: Some(escrow),
+        salt: None,
+    };
+    H256::from(domain.separator())
 }
 
-/// Convert bytes32 to hex string with prefix
-pub fn bytes32_to_trade_id(bytes: [u8; 32]) -> String {
-    format!("trade_{}", hex::encode(bytes))
+/// keccak256("\x19\x01" || domainSeparator || structHash)
+fn typed_data_digest(domain_separator: H256, struct_hash: H256) -> H256 {
+    let mut preimage = Vec::with_capacity(66);
+    preimage.extend_from_slice(&[0x19, 0x01]);
+    preimage.extend_from_slice(domain_separator.as_bytes());
+    preimage.extend_from_slice(struct_hash.as_bytes());
+    H256::from(ethers::utils::keccak256(preimage))
+}
+
+impl OrderIntent {
+    /// EIP-712 hashStruct, also used as the intent's id
+    pub fn struct_hash(&self) -> H256 {
+        let encoded = ethers::abi::encode(&[
+            Token::FixedBytes(ethers::utils::keccak256(ORDER_INTENT_TYPE).to_vec()),
+            Token::Address(self.seller),
+            Token::Address(self.token),
+            Token::Uint(self.amount),
+            Token::Uint(self.exchange_rate),
+            Token::FixedBytes(self.alipay_hash.as_bytes().to_vec()),
+            Token::Uint(self.nonce),
+            Token::Uint(self.deadline),
+        ]);
+        H256::from(ethers::utils::keccak256(encoded))
+    }
+
+    /// Digest the seller signs (eth_signTypedData_v4)
+    pub fn signing_hash(&self, domain_separator: H256) -> H256 {
+        typed_data_digest(domain_separator, self.struct_hash())
+    }
+}
+
+/// Digest a seller signs to cancel the intent with `intent_hash`
+pub fn cancel_order_intent_signing_hash(intent_hash: H256, domain_separator: H256) -> H256 {
+    let encoded = ethers::abi::encode(&[
+        Token::FixedBytes(ethers::utils::keccak256(CANCEL_ORDER_INTENT_TYPE).to_vec()),
+        Token::FixedBytes(intent_hash.as_bytes().to_vec()),
+    ]);
+    typed_data_digest(domain_separator, H256::from(ethers::utils::keccak256(encoded)))
+}
+
+/// Address that signed an EIP-712 digest (65-byte hex signature, v = 27/28 or 0/1)
+pub fn recover_typed_data_signer(digest: H256, signature: &str) -> Result<Address> {
+    let bytes = hexutil::decode(signature)
+        .map_err(|_| anyhow::anyhow!("Signature must be hex"))?;
+    let mut signature = Signature::try_from(bytes.as_slice())
+        .map_err(|e| anyhow::anyhow!("Invalid signature: {}", e))?;
+    if signature.v < 27 {
+        signature.v += 27;
+    }
+    signature
+        .recover(digest)
+        .map_err(|e| anyhow::anyhow!("Invalid signature: {}", e))
 }
 
 #[cfg(test)]
@@ -111,32 +269,79 @@ mod tests {
     }
     
     #[test]
-    fn test_order_id_conversion() {
-        let bytes = [1u8; 32];
-        let order_id = bytes32_to_order_id(bytes);
-        
-        assert!(order_id.starts_with("ord_"));
-        assert_eq!(order_id.len(), 4 + 64); // "ord_" + 64 hex chars
-        
-        // Convert back
-        let bytes_back = order_id_to_bytes32(&order_id).unwrap();
-        assert_eq!(bytes, bytes_back);
-        
-        println!("✅ Order ID conversion: {}", order_id);
+    fn test_validate_payment_nonce() {
+        assert!(validate_payment_nonce("00012345").is_ok());
+        assert!(validate_payment_nonce("1234567").is_err());
+        assert!(validate_payment_nonce("123456789").is_err());
+        assert!(validate_payment_nonce("1234567a").is_err());
+        assert!(validate_payment_nonce("１２３４５６７８").is_err()); // full-width digits
     }
     
     #[test]
-    fn test_trade_id_conversion() {
-        let bytes = [2u8; 32];
-        let trade_id = bytes32_to_trade_id(bytes);
-        
-        assert!(trade_id.starts_with("trade_"));
-        assert_eq!(trade_id.len(), 6 + 64); // "trade_" + 64 hex chars
-        
-        // Convert back
-        let bytes_back = trade_id_to_bytes32(&trade_id).unwrap();
-        assert_eq!(bytes, bytes_back);
-        
-        println!("✅ Trade ID conversion: {}", trade_id);
+    fn test_fill_value_against_limits() {
+        let limits = TradeLimits {
+            min_cny: U256::from(70_000),     // 700 CNY
+            max_cny: U256::from(7_200_000),  // 72,000 CNY
+        };
+
+        // 100 USDC (6 decimals) at 7.35 CNY = 735.00 CNY
+        let value = fill_value_cny(U256::from(100_000_000u64), U256::from(735), 6);
+        assert_eq!(value, U256::from(73_500));
+        assert!(limits.check(value).is_ok());
+
+        // 50 USDC at 7.35 CNY = 367.50 CNY (too small)
+        let value = fill_value_cny(U256::from(50_000_000u64), U256::from(735), 6);
+        let err = limits.check(value).unwrap_err();
+        assert!(err.contains("700.00"));
+
+        // 10,000 WETH-like units with 18 decimals
+        let value = fill_value_cny(U256::exp10(22), U256::from(735), 18);
+        assert!(limits.check(value).is_err());
+    }
+    
+    #[test]
+    fn test_decode_settlement_revert() {
+        let (name, _) = decode_settlement_revert("execution reverted: custom error 0x826d29e4").unwrap();
+        assert_eq!(name, "PaymentDetailsMismatch");
+        let (name, _) = decode_settlement_revert("Contract call reverted with data: 0x5f3f6cfc").unwrap();
+        assert_eq!(name, "TradeNotPending");
+        let (name, _) = decode_settlement_revert("revert: ProofVerificationFailed()").unwrap();
+        assert_eq!(name, "ProofVerificationFailed");
+        assert!(decode_settlement_revert("connection refused").is_none());
+
+        let selector = encode_0x(ethers::utils::id("WithdrawalExceedsAvailable()"));
+        let (name, _) = decode_withdrawal_revert(&format!("execution reverted: custom error {}", selector)).unwrap();
+        assert_eq!(name, "WithdrawalExceedsAvailable");
+    }
+
+    #[test]
+    fn test_order_intent_signature() {
+        let wallet: LocalWallet = "4c0883a69102937d6231471b5dbb6204fe5129617082792ae468d01a3f362318".parse().unwrap();
+        let escrow: Address = "0x1111111111111111111111111111111111111111".parse().unwrap();
+        let domain = order_intent_domain_separator(84532, escrow);
+        let intent = OrderIntent {
+            seller: wallet.address(),
+            token: "0x036cbd53842c5426634e7929541ec2318f3dcf7e".parse().unwrap(),
+            amount: U256::from(100_000_000u64),
+            exchange_rate: U256::from(735),
+            alipay_hash: alipay_hash("13800138000", "Zhang San"),
+            nonce: U256::zero(),
+            deadline: U256::from(1_900_000_000u64),
+        };
+
+        let digest = intent.signing_hash(domain);
+        let signature = wallet.sign_hash(digest).unwrap();
+        let recovered = recover_typed_data_signer(digest, &format!("0x{}", signature)).unwrap();
+        assert_eq!(recovered, wallet.address());
+
+        // Any change to the terms, the chain or the escrow changes the signer
+        let tampered = OrderIntent { exchange_rate: U256::from(736), ..intent.clone() };
+        let other_recovered = recover_typed_data_signer(tampered.signing_hash(domain), &signature.to_string()).unwrap();
+        assert_ne!(other_recovered, wallet.address());
+        assert_ne!(domain, order_intent_domain_separator(8453, escrow));
+
+        let cancel = cancel_order_intent_signing_hash(intent.struct_hash(), domain);
+        assert_ne!(cancel, digest);
+        assert!(recover_typed_data_signer(digest, "0x1234").is_err());
     }
 }
@@ -0,0 +1,91 @@
+// Per-process cache (single instance deployments and tests)
+
+use async_trait::async_trait;
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use super::{CacheError, SharedCache};
+
+#[derive(Debug, Default)]
+pub struct MemoryCache {
+    entries: Mutex<HashMap<String, (Vec<u8>, Instant)>>, // key -> (value, expires)
+}
+
+impl MemoryCache {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Insert unless a live entry exists (or always, when `overwrite`); expired entries are purged
+    fn insert(&self, key: &str, value: Vec<u8>, ttl: Duration, overwrite: bool) -> bool {
+        let mut entries = self.entries.lock().unwrap();
+        let now = Instant::now();
+        entries.retain(|_, (_, expires)| *expires > now);
+
+        if !overwrite && entries.contains_key(key) {
+            return false;
+        }
+        entries.insert(key.to_string(), (value, now + ttl));
+        true
+    }
+}
+
+#[async_trait]
+impl SharedCache for MemoryCache {
+    async fn get(&self, key: &str) -> Result<Option<Vec<u8>>, CacheError> {
+        let entries = self.entries.lock().unwrap();
+        Ok(entries
+            .get(key)
+            .filter(|(_, expires)| *expires > Instant::now())
+            .map(|(value, _)| value.clone()))
+    }
+
+    async fn set(&self, key: &str, value: Vec<u8>, ttl: Duration) -> Result<(), CacheError> {
+        self.insert(key, value, ttl, true);
+        Ok(())
+    }
+
+    async fn set_if_absent(&self, key: &str, value: Vec<u8>, ttl: Duration) -> Result<bool, CacheError> {
+        Ok(self.insert(key, value, ttl, false))
+    }
+
+    async fn delete(&self, key: &str) -> Result<(), CacheError> {
+        self.entries.lock().unwrap().remove(key);
+        Ok(())
+    }
+
+    fn backend(&self) -> &'static str {
+        "memory"
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Arc;
+
+    #[tokio::test]
+    async fn test_memory_cache_ttl_and_reservation() {
+        let cache: Arc<dyn SharedCache> = Arc::new(MemoryCache::new());
+        let minute = Duration::from_secs(60);
+
+        cache.set_json("a", &vec!["s1".to_string()], minute).await;
+        assert_eq!(cache.get_json::<Vec<String>>("a").await, Some(vec!["s1".to_string()]));
+
+        // Only the first reservation wins while it's live
+        assert!(cache.set_if_absent("r", b"x".to_vec(), minute).await.unwrap());
+        assert!(!cache.set_if_absent("r", b"y".to_vec(), minute).await.unwrap());
+        cache.delete("r").await.unwrap();
+        assert!(cache.set_if_absent("r", b"y".to_vec(), minute).await.unwrap());
+
+        // Expired entries are misses and can be reserved again
+        cache.set("e", b"old".to_vec(), Duration::ZERO).await.unwrap();
+        assert!(cache.get("e").await.unwrap().is_none());
+        assert!(cache.set_if_absent("e", b"new".to_vec(), minute).await.unwrap());
+
+        // Undecodable entries read as misses
+        cache.set("bad", b"not json".to_vec(), minute).await.unwrap();
+        assert!(cache.get_json::<Vec<String>>("bad").await.is_none());
+    }
+}
@@ -0,0 +1,103 @@
+// Shared cache for state that several API replicas must agree on
+// Validation builds input streams that proof generation reuses, possibly on another
+// replica; with REDIS_URL set all replicas share one Redis, otherwise the cache is
+// per-process (fine for a single instance). Entries always carry a TTL, and a cache
+// failure degrades to a miss - callers must be able to recompute.
+
+pub mod memory;
+pub mod orderbook;
+#[cfg(feature = "redis")]
+pub mod redis;
+
+use async_trait::async_trait;
+use serde::{de::DeserializeOwned, Serialize};
+use std::sync::Arc;
+use std::time::Duration;
+use thiserror::Error;
+
+pub use memory::MemoryCache;
+pub use orderbook::OrderbookCache;
+
+/// How long validated input streams are kept for proof generation
+pub const INPUT_STREAMS_TTL: Duration = Duration::from_secs(24 * 60 * 60);
+
+#[derive(Error, Debug)]
+pub enum CacheError {
+    #[error("Cache backend error: {0}")]
+    Backend(String),
+    #[error("Invalid cache config: {0}")]
+    Config(String),
+}
+
+/// Key-value store with per-entry TTL, shared between replicas when backed by Redis
+#[async_trait]
+pub trait SharedCache: Send + Sync {
+    async fn get(&self, key: &str) -> Result<Option<Vec<u8>>, CacheError>;
+
+    async fn set(&self, key: &str, value: Vec<u8>, ttl: Duration) -> Result<(), CacheError>;
+
+    /// Store only if the key is absent (reservations); true if this call stored it
+    async fn set_if_absent(&self, key: &str, value: Vec<u8>, ttl: Duration) -> Result<bool, CacheError>;
+
+    async fn delete(&self, key: &str) -> Result<(), CacheError>;
+
+    /// Backend name for logs
+    fn backend(&self) -> &'static str;
+}
+
+impl dyn SharedCache {
+    /// Typed read; backend errors and undecodable entries are treated as misses
+    pub async fn get_json<T: DeserializeOwned>(&self, key: &str) -> Option<T> {
+        match self.get(key).await {
+            Ok(Some(bytes)) => match serde_json::from_slice(&bytes) {
+                Ok(value) => Some(value),
+                Err(e) => {
+                    tracing::warn!("Discarding undecodable cache entry {}: {}", key, e);
+                    None
+                }
+            },
+            Ok(None) => None,
+            Err(e) => {
+                tracing::warn!("Cache read of {} failed: {}", key, e);
+                None
+            }
+        }
+    }
+
+    /// Typed write; failures are only logged (the value can be recomputed)
+    pub async fn set_json<T: Serialize>(&self, key: &str, value: &T, ttl: Duration) {
+        let bytes = match serde_json::to_vec(value) {
+            Ok(bytes) => bytes,
+            Err(e) => {
+                tracing::warn!("Failed to encode cache entry {}: {}", key, e);
+                return;
+            }
+        };
+        if let Err(e) = self.set(key, bytes, ttl).await {
+            tracing::warn!("Cache write of {} failed: {}", key, e);
+        }
+    }
+}
+
+/// Key for a trade's validated input streams
+pub fn input_streams_key(trade_id: &str) -> String {
+    format!("input_streams:{}", trade_id.to_lowercase())
+}
+
+/// Redis when REDIS_URL is set (requires the redis feature), in-memory otherwise
+pub async fn cache_from_env() -> Result<Arc<dyn SharedCache>, CacheError> {
+    match std::env::var("REDIS_URL") {
+        Ok(url) => redis_cache(&url).await,
+        Err(_) => Ok(Arc::new(MemoryCache::new())),
+    }
+}
+
+#[cfg(feature = "redis")]
+async fn redis_cache(url: &str) -> Result<Arc<dyn SharedCache>, CacheError> {
+    Ok(Arc::new(redis::RedisCache::connect(url).await?))
+}
+
+#[cfg(not(feature = "redis"))]
+async fn redis_cache(_url: &str) -> Result<Arc<dyn SharedCache>, CacheError> {
+    Err(CacheError::Config("REDIS_URL requires building with the redis feature".to_string()))
+}
@@ -0,0 +1,223 @@
+// In-memory order book for matching
+// Every match used to read up to 100 orders of the token from Postgres. The replica
+// keeps each token's matchable orders in memory - the rows, filters and order of
+// Database::get_active_orders_by_token - and drops them when Postgres announces a
+// change on "orderbook_changed" (migrations/033_orderbook_notify.sql); the next match
+// reloads the token. While the notification connection is down nothing is served from
+// memory, since changes may be missed. Entries also expire after a TTL as a backstop.
+//
+// Configuration (env):
+// - ORDERBOOK_CACHE_TTL_SECS: lifetime of a cached book (default 30, 0 disables the cache)
+
+use sqlx::postgres::{PgListener, PgPool};
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::{Arc, RwLock};
+use std::time::{Duration, Instant};
+use tokio::task::JoinHandle;
+
+use crate::blockchain::address::EthAddress;
+use crate::db::models::DbOrder;
+use crate::db::orders::OrderPriority;
+use crate::db::{Database, DbResult};
+
+/// Channel the order book triggers notify on
+pub const ORDERBOOK_CHANNEL: &str = "orderbook_changed";
+/// Default lifetime of a cached book
+pub const DEFAULT_ORDERBOOK_CACHE_TTL_SECS: u64 = 30;
+/// Orders loaded per token (the best ones in matching priority)
+pub const ORDERBOOK_DEPTH: i64 = 100;
+/// Wait before reconnecting a dropped notification connection
+const RECONNECT_DELAY: Duration = Duration::from_secs(5);
+
+struct CachedBook {
+    priority: OrderPriority,
+    orders: Arc<Vec<DbOrder>>,
+    loaded_at: Instant,
+}
+
+pub struct OrderbookCache {
+    books: RwLock<HashMap<EthAddress, CachedBook>>,
+    /// Bumped by every invalidation; a load only fills the cache if none happened meanwhile
+    generation: AtomicU64,
+    /// Whether change notifications are being received
+    listening: AtomicBool,
+    ttl: Duration,
+}
+
+impl OrderbookCache {
+    pub fn new(ttl: Duration) -> Self {
+        Self {
+            books: RwLock::new(HashMap::new()),
+            generation: AtomicU64::new(0),
+            listening: AtomicBool::new(false),
+            ttl,
+        }
+    }
+
+    /// ORDERBOOK_CACHE_TTL_SECS (None when set to 0)
+    pub fn from_env() -> Option<Self> {
+        let ttl_secs = std::env::var("ORDERBOOK_CACHE_TTL_SECS")
+            .ok()
+            .and_then(|v| v.parse().ok())
+            .unwrap_or(DEFAULT_ORDERBOOK_CACHE_TTL_SECS);
+        (ttl_secs > 0).then(|| Self::new(Duration::from_secs(ttl_secs)))
+    }
+
+    /// The token's cached book, if fresh
+    pub fn get(&self, token: &EthAddress, priority: OrderPriority) -> Option<Arc<Vec<DbOrder>>> {
+        if !self.listening.load(Ordering::Acquire) {
+            return None;
+        }
+        let books = self.books.read().unwrap();
+        books
+            .get(token)
+            .filter(|book| book.priority == priority && book.loaded_at.elapsed() < self.ttl)
+            .map(|book| book.orders.clone())
+    }
+
+    /// The token's matchable orders, from memory or loaded from Postgres
+    /// Returns the orders and whether they came from memory.
+    pub async fn orders(
+        &self,
+        db: &Database,
+        token: &EthAddress,
+        priority: OrderPriority,
+    ) -> DbResult<(Arc<Vec<DbOrder>>, bool)> {
+        if let Some(orders) = self.get(token, priority) {
+            return Ok((orders, true));
+        }
+        let generation = self.generation.load(Ordering::Acquire);
+        let orders = db.get_active_orders_by_token(token, Some(ORDERBOOK_DEPTH), priority).await?;
+        Ok((self.insert(token, priority, orders, generation), false))
+    }
+
+    /// Cache a book loaded at `generation`, unless it was invalidated since (the load
+    /// may have read the rows from before the change)
+    fn insert(&self, token: &EthAddress, priority: OrderPriority, orders: Vec<DbOrder>, generation: u64) -> Arc<Vec<DbOrder>> {
+        let orders = Arc::new(orders);
+        let mut books = self.books.write().unwrap();
+        if self.listening.load(Ordering::Acquire) && self.generation.load(Ordering::Acquire) == generation {
+            books.insert(
+                token.clone(),
+                CachedBook { priority, orders: orders.clone(), loaded_at: Instant::now() },
+            );
+        }
+        orders
+    }
+
+    /// Drop one token's book
+    pub fn invalidate(&self, token: &EthAddress) {
+        let mut books = self.books.write().unwrap();
+        self.generation.fetch_add(1, Ordering::AcqRel);
+        books.remove(token);
+    }
+
+    /// Drop every book
+    pub fn clear(&self) {
+        let mut books = self.books.write().unwrap();
+        self.generation.fetch_add(1, Ordering::AcqRel);
+        books.clear();
+    }
+
+    /// Apply an "orderbook_changed" payload: a token, or '*' for all of them
+    fn apply(&self, payload: &str) {
+        match payload.parse::<EthAddress>() {
+            Ok(token) => self.invalidate(&token),
+            Err(_) => self.clear(),
+        }
+    }
+
+    fn set_listening(&self, listening: bool) {
+        // Cleared either way: changes may have been missed while not listening
+        self.clear();
+        self.listening.store(listening, Ordering::Release);
+    }
+
+    /// Spawn the LISTEN loop that keeps the cache fresh (reconnects on failure)
+    pub fn spawn_listener(self: Arc<Self>, pool: PgPool) -> JoinHandle<()> {
+        tokio::spawn(async move {
+            loop {
+                if let Err(e) = self.listen(&pool).await {
+                    tracing::warn!("📚 Order book notifications failed: {}", e);
+                }
+                self.set_listening(false);
+                tokio::time::sleep(RECONNECT_DELAY).await;
+            }
+        })
+    }
+
+    /// Receive notifications until the connection drops
+    async fn listen(&self, pool: &PgPool) -> DbResult<()> {
+        let mut listener = PgListener::connect_with(pool).await?;
+        listener.listen(ORDERBOOK_CHANNEL).await?;
+        self.set_listening(true);
+        tracing::info!("📚 In-memory order book listening on {}", ORDERBOOK_CHANNEL);
+
+        while let Some(notification) = listener.try_recv().await? {
+            self.apply(notification.payload());
+        }
+        tracing::warn!("📚 Order book notification connection lost");
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Utc;
+
+    fn order(token: &EthAddress) -> DbOrder {
+        DbOrder {
+            order_id: format!("0x{}", "11".repeat(32)),
+            seller: "0x1111111111111111111111111111111111111111".parse().unwrap(),
+            token: token.clone(),
+            total_amount: "1000000".to_string(),
+            remaining_amount: "1000000".to_string(),
+            exchange_rate: "720".to_string(),
+            alipay_id: "13800138000".to_string(),
+            alipay_name: "Seller".to_string(),
+            created_at: 0,
+            synced_at: Utc::now(),
+            alipay_id_format: "phone".to_string(),
+            min_fill: None,
+            lot_size: None,
+        }
+    }
+
+    #[test]
+    fn test_orderbook_cache_invalidation() {
+        let cache = OrderbookCache::new(Duration::from_secs(60));
+        let usdc: EthAddress = "0x2222222222222222222222222222222222222222".parse().unwrap();
+        let usdt: EthAddress = "0x3333333333333333333333333333333333333333".parse().unwrap();
+        let priority = OrderPriority::PriceTime;
+
+        // Nothing is cached until notifications are flowing
+        cache.insert(&usdc, priority, vec![order(&usdc)], cache.generation.load(Ordering::Acquire));
+        assert!(cache.get(&usdc, priority).is_none());
+
+        cache.set_listening(true);
+        cache.insert(&usdc, priority, vec![order(&usdc)], cache.generation.load(Ordering::Acquire));
+        cache.insert(&usdt, priority, vec![order(&usdt)], cache.generation.load(Ordering::Acquire));
+        assert_eq!(cache.get(&usdc, priority).unwrap().len(), 1);
+        assert!(cache.get(&usdc, OrderPriority::PriceSize).is_none());
+
+        // A change to one token leaves the other cached
+        cache.apply(usdc.as_str());
+        assert!(cache.get(&usdc, priority).is_none());
+        assert!(cache.get(&usdt, priority).is_some());
+
+        // A load that started before a change isn't cached
+        let stale = cache.generation.load(Ordering::Acquire);
+        cache.apply("*");
+        assert!(cache.get(&usdt, priority).is_none());
+        cache.insert(&usdc, priority, vec![order(&usdc)], stale);
+        assert!(cache.get(&usdc, priority).is_none());
+
+        // Losing the connection drops everything
+        cache.insert(&usdc, priority, vec![order(&usdc)], cache.generation.load(Ordering::Acquire));
+        cache.set_listening(false);
+        cache.set_listening(true);
+        assert!(cache.get(&usdc, priority).is_none());
+    }
+}
@@ -0,0 +1,79 @@
+// Redis-backed cache shared by all API replicas (REDIS_URL, redis feature)
+
+use async_trait::async_trait;
+use redis::aio::ConnectionManager;
+use redis::AsyncCommands;
+use std::time::Duration;
+
+use super::{CacheError, SharedCache};
+
+/// Namespace for all keys, so the Redis instance can be shared with other services
+const KEY_PREFIX: &str = "zkalipay:";
+
+impl From<redis::RedisError> for CacheError {
+    fn from(err: redis::RedisError) -> Self {
+        CacheError::Backend(err.to_string())
+    }
+}
+
+pub struct RedisCache {
+    // Multiplexed and reconnecting; cheap to clone per command
+    conn: ConnectionManager,
+}
+
+impl RedisCache {
+    pub async fn connect(url: &str) -> Result<Self, CacheError> {
+        let client = redis::Client::open(url).map_err(|e| CacheError::Config(e.to_string()))?;
+        let conn = ConnectionManager::new(client).await?;
+        Ok(Self { conn })
+    }
+
+    fn key(key: &str) -> String {
+        format!("{}{}", KEY_PREFIX, key)
+    }
+}
+
+/// Redis expiry in milliseconds (0 would be rejected)
+fn ttl_millis(ttl: Duration) -> u64 {
+    (ttl.as_millis() as u64).max(1)
+}
+
+#[async_trait]
+impl SharedCache for RedisCache {
+    async fn get(&self, key: &str) -> Result<Option<Vec<u8>>, CacheError> {
+        Ok(self.conn.clone().get(Self::key(key)).await?)
+    }
+
+    async fn set(&self, key: &str, value: Vec<u8>, ttl: Duration) -> Result<(), CacheError> {
+        redis::cmd("SET")
+            .arg(Self::key(key))
+            .arg(value)
+            .arg("PX")
+            .arg(ttl_millis(ttl))
+            .query_async::<_, ()>(&mut self.conn.clone())
+            .await?;
+        Ok(())
+    }
+
+    async fn set_if_absent(&self, key: &str, value: Vec<u8>, ttl: Duration) -> Result<bool, CacheError> {
+        // SET NX replies OK when stored and nil when the key exists
+        let stored: Option<String> = redis::cmd("SET")
+            .arg(Self::key(key))
+            .arg(value)
+            .arg("NX")
+            .arg("PX")
+            .arg(ttl_millis(ttl))
+            .query_async(&mut self.conn.clone())
+            .await?;
+        Ok(stored.is_some())
+    }
+
+    async fn delete(&self, key: &str) -> Result<(), CacheError> {
+        self.conn.clone().del::<_, ()>(Self::key(key)).await?;
+        Ok(())
+    }
+
+    fn backend(&self) -> &'static str {
+        "redis"
+    }
+}
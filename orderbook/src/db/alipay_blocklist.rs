@@ -0,0 +1,152 @@
+use async_trait::async_trait;
+use sqlx::PgPool;
+
+use super::DbResult;
+use super::models::{DbAlipayBlocklistChange, DbBlockedAlipayId};
+
+/// Audit actions
+pub const ACTION_BLOCK: &str = "block";
+pub const ACTION_UNBLOCK: &str = "unblock";
+
+/// Form Alipay IDs are listed and compared in (the migration's CHECK enforces it)
+pub fn normalize_alipay_id(alipay_id: &str) -> String {
+    alipay_id.trim().to_lowercase()
+}
+
+/// Repository for the operator-managed Alipay account blocklist
+#[async_trait]
+pub trait AlipayBlocklistRepository: Send + Sync {
+    /// List an account (updating the reason if it is listed already), with an audit entry
+    async fn block(&self, alipay_id: &str, reason: Option<&str>, actor: Option<&str>) -> DbResult<DbBlockedAlipayId>;
+
+    /// Take an account off the list, with an audit entry; false if it wasn't listed
+    async fn unblock(&self, alipay_id: &str, reason: Option<&str>, actor: Option<&str>) -> DbResult<bool>;
+
+    async fn is_blocked(&self, alipay_id: &str) -> DbResult<bool>;
+
+    async fn list(&self) -> DbResult<Vec<DbBlockedAlipayId>>;
+
+    /// Latest changes, newest first
+    async fn audit(&self, limit: i64) -> DbResult<Vec<DbAlipayBlocklistChange>>;
+}
+
+pub struct PostgresAlipayBlocklistRepository {
+    pool: PgPool,
+}
+
+impl PostgresAlipayBlocklistRepository {
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+}
+
+#[async_trait]
+impl AlipayBlocklistRepository for PostgresAlipayBlocklistRepository {
+    async fn block(&self, alipay_id: &str, reason: Option<&str>, actor: Option<&str>) -> DbResult<DbBlockedAlipayId> {
+        let alipay_id = normalize_alipay_id(alipay_id);
+        let mut tx = self.pool.begin().await?;
+
+        let entry = sqlx::query_as!(
+            DbBlockedAlipayId,
+            r#"
+            INSERT INTO alipay_blocklist ("alipayId", "reason", "addedBy")
+            VALUES ($1, $2, $3)
+            ON CONFLICT ("alipayId") DO UPDATE
+            SET "reason" = EXCLUDED."reason"
+            RETURNING "alipayId" as alipay_id, "reason", "addedBy" as added_by, "addedAt" as added_at
+            "#,
+            alipay_id,
+            reason,
+            actor
+        )
+        .fetch_one(&mut *tx)
+        .await?;
+
+        sqlx::query!(
+            r#"
+            INSERT INTO alipay_blocklist_audit ("alipayId", "action", "reason", "actor")
+            VALUES ($1, $2, $3, $4)
+            "#,
+            alipay_id,
+            ACTION_BLOCK,
+            reason,
+            actor
+        )
+        .execute(&mut *tx)
+        .await?;
+
+        tx.commit().await?;
+        Ok(entry)
+    }
+
+    async fn unblock(&self, alipay_id: &str, reason: Option<&str>, actor: Option<&str>) -> DbResult<bool> {
+        let alipay_id = normalize_alipay_id(alipay_id);
+        let mut tx = self.pool.begin().await?;
+
+        let result = sqlx::query!(r#"DELETE FROM alipay_blocklist WHERE "alipayId" = $1"#, alipay_id)
+            .execute(&mut *tx)
+            .await?;
+        if result.rows_affected() == 0 {
+            return Ok(false);
+        }
+
+        sqlx::query!(
+            r#"
+            INSERT INTO alipay_blocklist_audit ("alipayId", "action", "reason", "actor")
+            VALUES ($1, $2, $3, $4)
+            "#,
+            alipay_id,
+            ACTION_UNBLOCK,
+            reason,
+            actor
+        )
+        .execute(&mut *tx)
+        .await?;
+
+        tx.commit().await?;
+        Ok(true)
+    }
+
+    async fn is_blocked(&self, alipay_id: &str) -> DbResult<bool> {
+        let blocked = sqlx::query_scalar!(
+            r#"SELECT EXISTS (SELECT 1 FROM alipay_blocklist WHERE "alipayId" = $1) as "blocked!""#,
+            normalize_alipay_id(alipay_id)
+        )
+        .fetch_one(&self.pool)
+        .await?;
+
+        Ok(blocked)
+    }
+
+    async fn list(&self) -> DbResult<Vec<DbBlockedAlipayId>> {
+        let entries = sqlx::query_as!(
+            DbBlockedAlipayId,
+            r#"
+            SELECT "alipayId" as alipay_id, "reason", "addedBy" as added_by, "addedAt" as added_at
+            FROM alipay_blocklist
+            ORDER BY "addedAt" DESC
+            "#
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(entries)
+    }
+
+    async fn audit(&self, limit: i64) -> DbResult<Vec<DbAlipayBlocklistChange>> {
+        let changes = sqlx::query_as!(
+            DbAlipayBlocklistChange,
+            r#"
+            SELECT "id", "alipayId" as alipay_id, "action", "reason", "actor", "createdAt" as created_at
+            FROM alipay_blocklist_audit
+            ORDER BY "id" DESC
+            LIMIT $1
+            "#,
+            limit
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(changes)
+    }
+}
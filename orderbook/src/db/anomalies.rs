@@ -0,0 +1,187 @@
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use sqlx::PgPool;
+
+use super::DbResult;
+use super::models::{DbFailureStats, DbFlaggedTemplate};
+use crate::blockchain::address::EthAddress;
+
+/// Stages an outcome is recorded at
+pub const STAGE_VALIDATION: &str = "validation";
+pub const STAGE_SUBMISSION: &str = "submission";
+
+/// Outcomes
+pub const OUTCOME_OK: &str = "ok";
+pub const OUTCOME_MISMATCH: &str = "mismatch";
+pub const OUTCOME_PROOF_FAILED: &str = "proof_failed";
+
+/// Repository for settlement outcomes and flagged PDF templates
+#[async_trait]
+pub trait AnomalyRepository: Send + Sync {
+    async fn record(&self, trade_id: &str, seller: &EthAddress, template: &str, stage: &str, outcome: &str) -> DbResult<()>;
+
+    /// Template of the trade's latest recorded outcome (its receipt's template)
+    async fn template_for_trade(&self, trade_id: &str) -> DbResult<Option<String>>;
+
+    /// Outcome counts per template since `since`
+    async fn template_stats(&self, since: DateTime<Utc>) -> DbResult<Vec<DbFailureStats>>;
+
+    /// Outcome counts per seller since `since`
+    async fn seller_stats(&self, since: DateTime<Utc>) -> DbResult<Vec<DbFailureStats>>;
+
+    /// Flag a template; None if it is already flagged, or an admin cleared its flag
+    /// after `quiet_since` (the failures that triggered it are acknowledged)
+    async fn flag(&self, template: &str, mismatch_rate: f64, samples: i64, quiet_since: DateTime<Utc>) -> DbResult<Option<DbFlaggedTemplate>>;
+
+    /// Templates currently flagged, newest first
+    async fn active_flags(&self) -> DbResult<Vec<DbFlaggedTemplate>>;
+
+    /// Clear a template's flag; false if it isn't flagged
+    async fn clear(&self, template: &str, cleared_by: Option<&str>) -> DbResult<bool>;
+}
+
+pub struct PostgresAnomalyRepository {
+    pool: PgPool,
+}
+
+impl PostgresAnomalyRepository {
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+}
+
+#[async_trait]
+impl AnomalyRepository for PostgresAnomalyRepository {
+    async fn record(&self, trade_id: &str, seller: &EthAddress, template: &str, stage: &str, outcome: &str) -> DbResult<()> {
+        sqlx::query!(
+            r#"
+            INSERT INTO settlement_outcomes ("tradeId", "seller", "template", "stage", "outcome")
+            VALUES ($1, $2, $3, $4, $5)
+            "#,
+            trade_id,
+            seller.as_str(),
+            template,
+            stage,
+            outcome
+        )
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    async fn template_for_trade(&self, trade_id: &str) -> DbResult<Option<String>> {
+        let template = sqlx::query_scalar!(
+            r#"
+            SELECT "template" FROM settlement_outcomes
+            WHERE "tradeId" = $1
+            ORDER BY "recordedAt" DESC
+            LIMIT 1
+            "#,
+            trade_id
+        )
+        .fetch_optional(&self.pool)
+        .await?;
+
+        Ok(template)
+    }
+
+    async fn template_stats(&self, since: DateTime<Utc>) -> DbResult<Vec<DbFailureStats>> {
+        let stats = sqlx::query_as!(
+            DbFailureStats,
+            r#"
+            SELECT "template" as "key!",
+                   COUNT(*) as "samples!",
+                   COUNT(*) FILTER (WHERE "outcome" = 'mismatch') as "mismatches!",
+                   COUNT(*) FILTER (WHERE "outcome" = 'proof_failed') as "proof_failures!"
+            FROM settlement_outcomes
+            WHERE "recordedAt" >= $1
+            GROUP BY "template"
+            ORDER BY COUNT(*) DESC
+            "#,
+            since
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(stats)
+    }
+
+    async fn seller_stats(&self, since: DateTime<Utc>) -> DbResult<Vec<DbFailureStats>> {
+        let stats = sqlx::query_as!(
+            DbFailureStats,
+            r#"
+            SELECT "seller" as "key!",
+                   COUNT(*) as "samples!",
+                   COUNT(*) FILTER (WHERE "outcome" = 'mismatch') as "mismatches!",
+                   COUNT(*) FILTER (WHERE "outcome" = 'proof_failed') as "proof_failures!"
+            FROM settlement_outcomes
+            WHERE "recordedAt" >= $1
+            GROUP BY "seller"
+            ORDER BY COUNT(*) DESC
+            "#,
+            since
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(stats)
+    }
+
+    async fn flag(&self, template: &str, mismatch_rate: f64, samples: i64, quiet_since: DateTime<Utc>) -> DbResult<Option<DbFlaggedTemplate>> {
+        let flagged = sqlx::query_as!(
+            DbFlaggedTemplate,
+            r#"
+            INSERT INTO flagged_templates ("template", "mismatchRate", "samples")
+            SELECT $1::VARCHAR, $2::DOUBLE PRECISION, $3::BIGINT
+            WHERE NOT EXISTS (
+                SELECT 1 FROM flagged_templates WHERE "template" = $1 AND "clearedAt" > $4
+            )
+            ON CONFLICT ("template") WHERE "clearedAt" IS NULL DO NOTHING
+            RETURNING "id", "template", "mismatchRate" as mismatch_rate, "samples",
+                      "flaggedAt" as flagged_at, "clearedAt" as cleared_at, "clearedBy" as cleared_by
+            "#,
+            template,
+            mismatch_rate,
+            samples,
+            quiet_since
+        )
+        .fetch_optional(&self.pool)
+        .await?;
+
+        Ok(flagged)
+    }
+
+    async fn active_flags(&self) -> DbResult<Vec<DbFlaggedTemplate>> {
+        let flags = sqlx::query_as!(
+            DbFlaggedTemplate,
+            r#"
+            SELECT "id", "template", "mismatchRate" as mismatch_rate, "samples",
+                   "flaggedAt" as flagged_at, "clearedAt" as cleared_at, "clearedBy" as cleared_by
+            FROM flagged_templates
+            WHERE "clearedAt" IS NULL
+            ORDER BY "flaggedAt" DESC
+            "#
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(flags)
+    }
+
+    async fn clear(&self, template: &str, cleared_by: Option<&str>) -> DbResult<bool> {
+        let result = sqlx::query!(
+            r#"
+            UPDATE flagged_templates
+            SET "clearedAt" = NOW(), "clearedBy" = $2
+            WHERE "template" = $1 AND "clearedAt" IS NULL
+            "#,
+            template,
+            cleared_by
+        )
+        .execute(&self.pool)
+        .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+}
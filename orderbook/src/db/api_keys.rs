@@ -0,0 +1,134 @@
+use async_trait::async_trait;
+use sqlx::PgPool;
+use uuid::Uuid;
+
+use super::DbResult;
+use super::models::DbApiKey;
+use crate::blockchain::address::EthAddress;
+
+/// Key scopes
+pub const SCOPE_READ: &str = "read";
+pub const SCOPE_TRADE: &str = "trade";
+
+/// A key to insert (hash and prefix computed by the caller from the secret)
+#[derive(Debug, Clone)]
+pub struct NewApiKey {
+    pub key_hash: String,
+    pub key_prefix: String,
+    pub name: String,
+    pub wallet: EthAddress,
+    pub scopes: Vec<String>,
+    pub rate_limit_per_minute: i32,
+    pub created_by: Option<String>,
+}
+
+/// Repository for market-maker API keys
+#[async_trait]
+pub trait ApiKeyRepository: Send + Sync {
+    async fn create(&self, key: &NewApiKey) -> DbResult<DbApiKey>;
+
+    /// Active (not revoked) key with this secret hash
+    async fn find_active_by_hash(&self, key_hash: &str) -> DbResult<Option<DbApiKey>>;
+
+    async fn list(&self) -> DbResult<Vec<DbApiKey>>;
+
+    /// Revoke a key; false if it doesn't exist or was already revoked
+    async fn revoke(&self, key_id: Uuid) -> DbResult<bool>;
+
+    async fn touch(&self, key_id: Uuid) -> DbResult<()>;
+}
+
+pub struct PostgresApiKeyRepository {
+    pool: PgPool,
+}
+
+impl PostgresApiKeyRepository {
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+}
+
+#[async_trait]
+impl ApiKeyRepository for PostgresApiKeyRepository {
+    async fn create(&self, key: &NewApiKey) -> DbResult<DbApiKey> {
+        let created = sqlx::query_as!(
+            DbApiKey,
+            r#"
+            INSERT INTO api_keys ("keyId", "keyHash", "keyPrefix", "name", "wallet", "scopes", "rateLimitPerMinute", "createdBy")
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8)
+            RETURNING "keyId" as key_id, "keyHash" as key_hash, "keyPrefix" as key_prefix, "name",
+                      "wallet" as "wallet: EthAddress", "scopes", "rateLimitPerMinute" as rate_limit_per_minute,
+                      "createdBy" as created_by, "createdAt" as created_at,
+                      "lastUsedAt" as last_used_at, "revokedAt" as revoked_at
+            "#,
+            Uuid::new_v4(),
+            key.key_hash,
+            key.key_prefix,
+            key.name,
+            key.wallet.as_str(),
+            &key.scopes,
+            key.rate_limit_per_minute,
+            key.created_by
+        )
+        .fetch_one(&self.pool)
+        .await?;
+
+        Ok(created)
+    }
+
+    async fn find_active_by_hash(&self, key_hash: &str) -> DbResult<Option<DbApiKey>> {
+        let key = sqlx::query_as!(
+            DbApiKey,
+            r#"
+            SELECT "keyId" as key_id, "keyHash" as key_hash, "keyPrefix" as key_prefix, "name",
+                   "wallet" as "wallet: EthAddress", "scopes", "rateLimitPerMinute" as rate_limit_per_minute,
+                   "createdBy" as created_by, "createdAt" as created_at,
+                   "lastUsedAt" as last_used_at, "revokedAt" as revoked_at
+            FROM api_keys
+            WHERE "keyHash" = $1 AND "revokedAt" IS NULL
+            "#,
+            key_hash
+        )
+        .fetch_optional(&self.pool)
+        .await?;
+
+        Ok(key)
+    }
+
+    async fn list(&self) -> DbResult<Vec<DbApiKey>> {
+        let keys = sqlx::query_as!(
+            DbApiKey,
+            r#"
+            SELECT "keyId" as key_id, "keyHash" as key_hash, "keyPrefix" as key_prefix, "name",
+                   "wallet" as "wallet: EthAddress", "scopes", "rateLimitPerMinute" as rate_limit_per_minute,
+                   "createdBy" as created_by, "createdAt" as created_at,
+                   "lastUsedAt" as last_used_at, "revokedAt" as revoked_at
+            FROM api_keys
+            ORDER BY "createdAt" DESC
+            "#
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(keys)
+    }
+
+    async fn revoke(&self, key_id: Uuid) -> DbResult<bool> {
+        let result = sqlx::query!(
+            r#"UPDATE api_keys SET "revokedAt" = NOW() WHERE "keyId" = $1 AND "revokedAt" IS NULL"#,
+            key_id
+        )
+        .execute(&self.pool)
+        .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    async fn touch(&self, key_id: Uuid) -> DbResult<()> {
+        sqlx::query!(r#"UPDATE api_keys SET "lastUsedAt" = NOW() WHERE "keyId" = $1"#, key_id)
+            .execute(&self.pool)
+            .await?;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,142 @@
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use futures::stream::{self, Stream};
+use sqlx::PgPool;
+use std::sync::Arc;
+
+use super::{DbError, DbResult};
+
+/// Bytes read per query when streaming a blob: a download holds at most one chunk
+/// in memory and only reads the next once the client has taken the previous one
+pub const BLOB_CHUNK_SIZE: i64 = 256 * 1024;
+
+/// Large per-trade columns served as downloads
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TradeBlob {
+    /// Uploaded payment PDF (pdf_file, versioned by pdf_uploaded_at)
+    Pdf,
+    /// Axiom proof JSON (proof_json, versioned by proof_generated_at)
+    ProofJson,
+}
+
+/// Size and version of a stored blob
+#[derive(Debug, Clone)]
+pub struct BlobInfo {
+    pub len: i64,
+    /// Upload / generation time: chunks are only read while it still matches, so a
+    /// replaced blob ends the download instead of splicing two files together
+    pub version: DateTime<Utc>,
+    pub filename: Option<String>,
+}
+
+/// Chunked reads of trade blobs, so downloads don't load whole files into memory
+#[async_trait]
+pub trait TradeBlobRepository: Send + Sync {
+    /// Blob size and version; Ok(None) if the trade has no such blob yet
+    async fn info(&self, trade_id: &str, blob: TradeBlob) -> DbResult<Option<BlobInfo>>;
+
+    /// Bytes [offset, offset + len) of the blob; None if it changed since `version`
+    async fn chunk(&self, trade_id: &str, blob: TradeBlob, version: DateTime<Utc>, offset: i64, len: i64) -> DbResult<Option<Vec<u8>>>;
+}
+
+pub struct PostgresTradeBlobRepository {
+    pool: PgPool,
+}
+
+impl PostgresTradeBlobRepository {
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+}
+
+/// The blob as a stream of chunks from `repo`, read lazily as the consumer polls
+pub fn stream(repo: Arc<dyn TradeBlobRepository>, trade_id: String, blob: TradeBlob, info: BlobInfo) -> impl Stream<Item = DbResult<Vec<u8>>> + Send {
+    stream::unfold(Some(0i64), move |offset| {
+        let trade_id = trade_id.clone();
+        let repo = repo.clone();
+        let version = info.version;
+        let total = info.len;
+        async move {
+            let offset = offset.filter(|offset| *offset < total)?;
+            let len = BLOB_CHUNK_SIZE.min(total - offset);
+            match repo.chunk(&trade_id, blob, version, offset, len).await {
+                Ok(Some(bytes)) if bytes.len() as i64 == len => Some((Ok(bytes), Some(offset + len))),
+                Ok(_) => Some((
+                    Err(DbError::InvalidInput(format!("{:?} of trade {} changed during download", blob, trade_id))),
+                    None,
+                )),
+                Err(e) => Some((Err(e), None)),
+            }
+        }
+    })
+}
+
+#[async_trait]
+impl TradeBlobRepository for PostgresTradeBlobRepository {
+    async fn info(&self, trade_id: &str, blob: TradeBlob) -> DbResult<Option<BlobInfo>> {
+        let row = match blob {
+            TradeBlob::Pdf => sqlx::query!(
+                r#"
+                SELECT octet_length(pdf_file)::BIGINT as len, pdf_uploaded_at as version, pdf_filename as filename
+                FROM trades
+                WHERE "tradeId" = $1
+                "#,
+                trade_id
+            )
+            .fetch_optional(&self.pool)
+            .await?
+            .map(|r| (r.len, r.version, r.filename)),
+            TradeBlob::ProofJson => sqlx::query!(
+                r#"
+                SELECT octet_length(proof_json)::BIGINT as len, proof_generated_at as version
+                FROM trades
+                WHERE "tradeId" = $1
+                "#,
+                trade_id
+            )
+            .fetch_optional(&self.pool)
+            .await?
+            .map(|r| (r.len, r.version, None)),
+        };
+
+        let (len, version, filename) = row.ok_or_else(|| DbError::TradeNotFound(trade_id.to_string()))?;
+        Ok(match (len, version) {
+            (Some(len), Some(version)) => Some(BlobInfo { len, version, filename }),
+            _ => None,
+        })
+    }
+
+    async fn chunk(&self, trade_id: &str, blob: TradeBlob, version: DateTime<Utc>, offset: i64, len: i64) -> DbResult<Option<Vec<u8>>> {
+        // substring() positions are 1-based
+        let bytes = match blob {
+            TradeBlob::Pdf => sqlx::query_scalar!(
+                r#"
+                SELECT substring(pdf_file FROM ($3::BIGINT + 1)::INT FOR $4::BIGINT::INT) as "chunk!"
+                FROM trades
+                WHERE "tradeId" = $1 AND pdf_uploaded_at = $2 AND pdf_file IS NOT NULL
+                "#,
+                trade_id,
+                version,
+                offset,
+                len
+            )
+            .fetch_optional(&self.pool)
+            .await?,
+            TradeBlob::ProofJson => sqlx::query_scalar!(
+                r#"
+                SELECT substring(convert_to(proof_json, 'UTF8') FROM ($3::BIGINT + 1)::INT FOR $4::BIGINT::INT) as "chunk!"
+                FROM trades
+                WHERE "tradeId" = $1 AND proof_generated_at = $2 AND proof_json IS NOT NULL
+                "#,
+                trade_id,
+                version,
+                offset,
+                len
+            )
+            .fetch_optional(&self.pool)
+            .await?,
+        };
+
+        Ok(bytes)
+    }
+}
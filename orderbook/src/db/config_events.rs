@@ -0,0 +1,85 @@
+use async_trait::async_trait;
+use sqlx::PgPool;
+
+use super::DbResult;
+use super::models::DbConfigEvent;
+
+/// Repository for contract config-change history
+#[async_trait]
+pub trait ConfigEventRepository: Send + Sync {
+    /// Record a config-change event (idempotent per txHash + logIndex)
+    async fn create(
+        &self,
+        event_name: &str,
+        details: &str,
+        block_number: i64,
+        tx_hash: &str,
+        log_index: i64,
+    ) -> DbResult<()>;
+
+    /// Most recent config-change events, newest first
+    async fn list_recent(&self, limit: i64) -> DbResult<Vec<DbConfigEvent>>;
+}
+
+pub struct PostgresConfigEventRepository {
+    pool: PgPool,
+}
+
+impl PostgresConfigEventRepository {
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+}
+
+#[async_trait]
+impl ConfigEventRepository for PostgresConfigEventRepository {
+    async fn create(
+        &self,
+        event_name: &str,
+        details: &str,
+        block_number: i64,
+        tx_hash: &str,
+        log_index: i64,
+    ) -> DbResult<()> {
+        sqlx::query!(
+            r#"
+            INSERT INTO contract_config_events ("eventName", "details", "blockNumber", "txHash", "logIndex")
+            VALUES ($1, $2, $3, $4, $5)
+            ON CONFLICT ("txHash", "logIndex") DO NOTHING
+            "#,
+            event_name,
+            details,
+            block_number,
+            tx_hash,
+            log_index
+        )
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    async fn list_recent(&self, limit: i64) -> DbResult<Vec<DbConfigEvent>> {
+        let events = sqlx::query_as!(
+            DbConfigEvent,
+            r#"
+            SELECT
+                "id" as "id!",
+                "eventName" as "event_name!",
+                "details" as "details!",
+                "blockNumber" as "block_number!",
+                "txHash" as "tx_hash!",
+                "logIndex" as "log_index!",
+                "syncedAt" as "synced_at!"
+            FROM contract_config_events
+            ORDER BY "blockNumber" DESC, "logIndex" DESC
+            LIMIT $1
+            "#,
+            limit
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(events)
+    }
+}
@@ -0,0 +1,90 @@
+use async_trait::async_trait;
+use sqlx::PgPool;
+
+use super::DbResult;
+use super::models::DbUnfinalizedSettlement;
+use crate::blockchain::address::EthAddress;
+
+/// Repository for settlement confirmation depth (columns of the trades table)
+#[async_trait]
+pub trait ConfirmationRepository: Send + Sync {
+    /// Settled trades with a known settlement tx that aren't finalized yet, oldest first
+    async fn list_unfinalized(&self, limit: i64) -> DbResult<Vec<DbUnfinalizedSettlement>>;
+
+    /// Record the settlement tx's current block and confirmation count
+    async fn update(&self, trade_id: &str, settlement_block: Option<i64>, confirmations: i32) -> DbResult<()>;
+
+    /// Mark the settlement final; false if it already was (another replica got there first)
+    async fn finalize(&self, trade_id: &str, settlement_block: i64, confirmations: i32) -> DbResult<bool>;
+}
+
+pub struct PostgresConfirmationRepository {
+    pool: PgPool,
+}
+
+impl PostgresConfirmationRepository {
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+}
+
+#[async_trait]
+impl ConfirmationRepository for PostgresConfirmationRepository {
+    async fn list_unfinalized(&self, limit: i64) -> DbResult<Vec<DbUnfinalizedSettlement>> {
+        let settlements = sqlx::query_as!(
+            DbUnfinalizedSettlement,
+            r#"
+            SELECT
+                "tradeId" as trade_id,
+                "orderId" as order_id,
+                "buyer" as "buyer: EthAddress",
+                "settlementTxHash" as "settlement_tx_hash!",
+                "settlementBlock" as settlement_block,
+                "confirmations"
+            FROM trades
+            WHERE "status" = 1 AND "finalizedAt" IS NULL AND "settlementTxHash" IS NOT NULL
+            ORDER BY "settlementBlock" ASC NULLS LAST
+            LIMIT $1
+            "#,
+            limit
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(settlements)
+    }
+
+    async fn update(&self, trade_id: &str, settlement_block: Option<i64>, confirmations: i32) -> DbResult<()> {
+        sqlx::query!(
+            r#"
+            UPDATE trades
+            SET "settlementBlock" = $2, "confirmations" = $3
+            WHERE "tradeId" = $1 AND "finalizedAt" IS NULL
+            "#,
+            trade_id,
+            settlement_block,
+            confirmations
+        )
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    async fn finalize(&self, trade_id: &str, settlement_block: i64, confirmations: i32) -> DbResult<bool> {
+        let result = sqlx::query!(
+            r#"
+            UPDATE trades
+            SET "settlementBlock" = $2, "confirmations" = $3, "finalizedAt" = NOW()
+            WHERE "tradeId" = $1 AND "finalizedAt" IS NULL
+            "#,
+            trade_id,
+            settlement_block,
+            confirmations
+        )
+        .execute(&self.pool)
+        .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+}
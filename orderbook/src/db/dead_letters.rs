@@ -0,0 +1,181 @@
+use async_trait::async_trait;
+use sqlx::PgPool;
+
+use super::DbResult;
+use super::models::DbDeadLetter;
+
+/// Dead letter states
+pub const DEAD_LETTER_PENDING: &str = "pending";
+pub const DEAD_LETTER_QUEUED: &str = "queued";
+pub const DEAD_LETTER_REPROCESSED: &str = "reprocessed";
+pub const DEAD_LETTER_DISCARDED: &str = "discarded";
+
+/// A failed event handling to keep
+#[derive(Debug, Clone)]
+pub struct NewDeadLetter {
+    pub event_type: String,
+    pub block_number: i64,
+    pub tx_hash: String,
+    pub log_index: i64,
+    /// Raw logs as JSON
+    pub logs: String,
+    pub error: String,
+}
+
+/// Repository for contract events the listener failed to apply
+#[async_trait]
+pub trait DeadLetterRepository: Send + Sync {
+    /// Keep a failed event; failing again on the same log bumps its attempts and
+    /// makes it pending again
+    async fn record(&self, dead_letter: &NewDeadLetter) -> DbResult<DbDeadLetter>;
+
+    async fn get(&self, id: i64) -> DbResult<Option<DbDeadLetter>>;
+
+    /// Entries in `status` (any if None), oldest first
+    async fn list(&self, status: Option<&str>, limit: i64) -> DbResult<Vec<DbDeadLetter>>;
+
+    /// Queue a pending entry for the listener to replay; false if it isn't pending
+    async fn queue(&self, id: i64, resolved_by: Option<&str>) -> DbResult<bool>;
+
+    /// Give up on a pending entry; false if it isn't pending
+    async fn discard(&self, id: i64, resolved_by: Option<&str>) -> DbResult<bool>;
+
+    /// Queued entries, oldest first
+    async fn queued(&self, limit: i64) -> DbResult<Vec<DbDeadLetter>>;
+
+    /// Record a replay: reprocessed on success, pending again with the error otherwise
+    async fn finish_replay(&self, id: i64, error: Option<&str>) -> DbResult<()>;
+}
+
+pub struct PostgresDeadLetterRepository {
+    pool: PgPool,
+}
+
+impl PostgresDeadLetterRepository {
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+}
+
+#[async_trait]
+impl DeadLetterRepository for PostgresDeadLetterRepository {
+    async fn record(&self, dead_letter: &NewDeadLetter) -> DbResult<DbDeadLetter> {
+        let recorded = sqlx::query_as!(
+            DbDeadLetter,
+            r#"
+            INSERT INTO dead_letter_events ("eventType", "blockNumber", "txHash", "logIndex", "logs", "error")
+            VALUES ($1, $2, $3, $4, $5, $6)
+            ON CONFLICT ("txHash", "logIndex") DO UPDATE
+            SET "error" = EXCLUDED."error", "attempts" = dead_letter_events."attempts" + 1,
+                "status" = 'pending', "updatedAt" = NOW()
+            RETURNING "id", "eventType" as event_type, "blockNumber" as block_number, "txHash" as tx_hash,
+                      "logIndex" as log_index, "logs", "error", "attempts", "status",
+                      "resolvedBy" as resolved_by, "createdAt" as created_at, "updatedAt" as updated_at
+            "#,
+            dead_letter.event_type,
+            dead_letter.block_number,
+            dead_letter.tx_hash,
+            dead_letter.log_index,
+            dead_letter.logs,
+            dead_letter.error
+        )
+        .fetch_one(&self.pool)
+        .await?;
+
+        Ok(recorded)
+    }
+
+    async fn get(&self, id: i64) -> DbResult<Option<DbDeadLetter>> {
+        let dead_letter = sqlx::query_as!(
+            DbDeadLetter,
+            r#"
+            SELECT "id", "eventType" as event_type, "blockNumber" as block_number, "txHash" as tx_hash,
+                   "logIndex" as log_index, "logs", "error", "attempts", "status",
+                   "resolvedBy" as resolved_by, "createdAt" as created_at, "updatedAt" as updated_at
+            FROM dead_letter_events
+            WHERE "id" = $1
+            "#,
+            id
+        )
+        .fetch_optional(&self.pool)
+        .await?;
+
+        Ok(dead_letter)
+    }
+
+    async fn list(&self, status: Option<&str>, limit: i64) -> DbResult<Vec<DbDeadLetter>> {
+        let dead_letters = sqlx::query_as!(
+            DbDeadLetter,
+            r#"
+            SELECT "id", "eventType" as event_type, "blockNumber" as block_number, "txHash" as tx_hash,
+                   "logIndex" as log_index, "logs", "error", "attempts", "status",
+                   "resolvedBy" as resolved_by, "createdAt" as created_at, "updatedAt" as updated_at
+            FROM dead_letter_events
+            WHERE $1::TEXT IS NULL OR "status" = $1
+            ORDER BY "id"
+            LIMIT $2
+            "#,
+            status,
+            limit
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(dead_letters)
+    }
+
+    async fn queue(&self, id: i64, resolved_by: Option<&str>) -> DbResult<bool> {
+        let result = sqlx::query!(
+            r#"
+            UPDATE dead_letter_events
+            SET "status" = 'queued', "resolvedBy" = $2, "updatedAt" = NOW()
+            WHERE "id" = $1 AND "status" = 'pending'
+            "#,
+            id,
+            resolved_by
+        )
+        .execute(&self.pool)
+        .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    async fn discard(&self, id: i64, resolved_by: Option<&str>) -> DbResult<bool> {
+        let result = sqlx::query!(
+            r#"
+            UPDATE dead_letter_events
+            SET "status" = 'discarded', "resolvedBy" = $2, "updatedAt" = NOW()
+            WHERE "id" = $1 AND "status" = 'pending'
+            "#,
+            id,
+            resolved_by
+        )
+        .execute(&self.pool)
+        .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    async fn queued(&self, limit: i64) -> DbResult<Vec<DbDeadLetter>> {
+        self.list(Some(DEAD_LETTER_QUEUED), limit).await
+    }
+
+    async fn finish_replay(&self, id: i64, error: Option<&str>) -> DbResult<()> {
+        sqlx::query!(
+            r#"
+            UPDATE dead_letter_events
+            SET "status" = CASE WHEN $2::TEXT IS NULL THEN 'reprocessed' ELSE 'pending' END,
+                "error" = COALESCE($2, "error"),
+                "attempts" = "attempts" + 1,
+                "updatedAt" = NOW()
+            WHERE "id" = $1 AND "status" = 'queued'
+            "#,
+            id,
+            error
+        )
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,102 @@
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use sqlx::PgPool;
+
+use super::DbResult;
+use super::models::DbJournalEvent;
+
+/// Event types written by the journal triggers (migrations/032_event_journal.sql)
+pub const EVENT_ORDER_CREATED: &str = "order_created";
+pub const EVENT_ORDER_UPDATED: &str = "order_updated";
+pub const EVENT_TRADE_CREATED: &str = "trade_created";
+pub const EVENT_TRADE_SETTLED: &str = "trade_settled";
+pub const EVENT_TRADE_EXPIRED: &str = "trade_expired";
+
+/// Repository for the lifecycle event outbox
+#[async_trait]
+pub trait EventJournalRepository: Send + Sync {
+    /// Oldest unpublished events, in journal order
+    async fn unpublished(&self, limit: i64) -> DbResult<Vec<DbJournalEvent>>;
+
+    /// Stamp events as accepted by the sink
+    async fn mark_published(&self, ids: &[i64]) -> DbResult<u64>;
+
+    /// Number of events not yet published
+    async fn backlog(&self) -> DbResult<i64>;
+
+    /// Delete events published before `before`
+    async fn prune(&self, before: DateTime<Utc>) -> DbResult<u64>;
+}
+
+pub struct PostgresEventJournalRepository {
+    pool: PgPool,
+}
+
+impl PostgresEventJournalRepository {
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+}
+
+#[async_trait]
+impl EventJournalRepository for PostgresEventJournalRepository {
+    async fn unpublished(&self, limit: i64) -> DbResult<Vec<DbJournalEvent>> {
+        let events = sqlx::query_as!(
+            DbJournalEvent,
+            r#"
+            SELECT
+                "id",
+                "eventType" as event_type,
+                "aggregateId" as aggregate_id,
+                "payload"::TEXT as "payload!",
+                "occurredAt" as occurred_at,
+                "publishedAt" as published_at
+            FROM event_journal
+            WHERE "publishedAt" IS NULL
+            ORDER BY "id"
+            LIMIT $1
+            "#,
+            limit
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(events)
+    }
+
+    async fn mark_published(&self, ids: &[i64]) -> DbResult<u64> {
+        let result = sqlx::query!(
+            r#"
+            UPDATE event_journal
+            SET "publishedAt" = NOW()
+            WHERE "id" = ANY($1) AND "publishedAt" IS NULL
+            "#,
+            ids
+        )
+        .execute(&self.pool)
+        .await?;
+
+        Ok(result.rows_affected())
+    }
+
+    async fn backlog(&self) -> DbResult<i64> {
+        let count = sqlx::query_scalar!(
+            r#"SELECT COUNT(*) AS "count!" FROM event_journal WHERE "publishedAt" IS NULL"#
+        )
+        .fetch_one(&self.pool)
+        .await?;
+
+        Ok(count)
+    }
+
+    async fn prune(&self, before: DateTime<Utc>) -> DbResult<u64> {
+        let result = sqlx::query!(
+            r#"DELETE FROM event_journal WHERE "publishedAt" < $1"#,
+            before
+        )
+        .execute(&self.pool)
+        .await?;
+
+        Ok(result.rows_affected())
+    }
+}
@@ -0,0 +1,68 @@
+use async_trait::async_trait;
+use sqlx::PgPool;
+
+use super::DbResult;
+use super::models::DbAxiomExecution;
+
+/// Repository for cached Axiom execute-mode results
+#[async_trait]
+pub trait ExecutionRepository: Send + Sync {
+    /// Cached result for these input streams on this program, if any
+    async fn get(&self, input_hash: &str, program_id: &str) -> DbResult<Option<DbAxiomExecution>>;
+
+    /// Record an execution result (first result for an input hash wins)
+    async fn create(&self, input_hash: &str, program_id: &str, trade_id: &str, actual_hash: &str) -> DbResult<()>;
+}
+
+pub struct PostgresExecutionRepository {
+    pool: PgPool,
+}
+
+impl PostgresExecutionRepository {
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+}
+
+#[async_trait]
+impl ExecutionRepository for PostgresExecutionRepository {
+    async fn get(&self, input_hash: &str, program_id: &str) -> DbResult<Option<DbAxiomExecution>> {
+        let execution = sqlx::query_as!(
+            DbAxiomExecution,
+            r#"
+            SELECT
+                "inputHash" as "input_hash!",
+                "programId" as "program_id!",
+                "tradeId" as "trade_id!",
+                "actualHash" as "actual_hash!",
+                "executedAt" as "executed_at!"
+            FROM axiom_executions
+            WHERE "inputHash" = $1 AND "programId" = $2
+            "#,
+            input_hash,
+            program_id
+        )
+        .fetch_optional(&self.pool)
+        .await?;
+
+        Ok(execution)
+    }
+
+    async fn create(&self, input_hash: &str, program_id: &str, trade_id: &str, actual_hash: &str) -> DbResult<()> {
+        sqlx::query!(
+            r#"
+            INSERT INTO axiom_executions ("inputHash", "programId", "tradeId", "actualHash")
+            VALUES ($1, $2, $3, $4)
+            ON CONFLICT ("inputHash", "programId") DO NOTHING
+            "#,
+            input_hash,
+            program_id,
+            trade_id,
+            actual_hash
+        )
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+}
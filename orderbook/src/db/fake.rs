@@ -0,0 +1,579 @@
+// In-memory repositories for handler tests
+// The API's order, trade, PDF and proof handlers reach the database only through the
+// traits in db::Repositories, so tests run them against this store instead of Postgres:
+// seed it with orders and trades, build a Database with `Database::with_repositories`
+// (on a lazy pool that is never connected) and call the router. Semantics follow the
+// Postgres queries closely enough for the API: ordering, not-found errors, replay
+// protection of signed requests and blob versions; matching priorities beyond price
+// then age are not modelled.
+
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use rust_decimal::Decimal;
+use std::collections::HashMap;
+use std::str::FromStr;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex, MutexGuard};
+use uuid::Uuid;
+
+use super::blobs::{BlobInfo, TradeBlob, TradeBlobRepository};
+use super::inspection::{InspectionRepository, OrderPage, OrderState, PageCursor, TradePage};
+use super::match_plans::{MatchPlanRepository, NewMatchPlan};
+use super::models::{DbMatchPlan, DbOrder, DbSellerReputation, DbStaleOrder, DbStalenessSettings, DbSyncState, DbTokenStatus, DbTrade, DbValidationAttempt};
+use super::order_staleness::OrderStalenessRepository;
+use super::orders::{OrderChange, OrderPriority, OrderRepository};
+use super::sync_state::SyncStateRepository;
+use super::token_status::{TokenPolicy, TokenStatusRepository};
+use super::trades::TradeRepository;
+use super::validations::{NewValidationAttempt, ValidationAttemptRepository};
+use super::{DbError, DbResult, Repositories};
+use crate::blockchain::address::EthAddress;
+
+#[derive(Default)]
+struct Store {
+    orders: Vec<DbOrder>,
+    trades: Vec<DbTrade>,
+    token_status: Vec<DbTokenStatus>,
+    staleness: Option<DbStalenessSettings>,
+    /// Last signed_at applied per order, for set_fill_limits and refresh
+    fill_limits_signed_at: HashMap<String, i64>,
+    refreshed: HashMap<String, (i64, DateTime<Utc>)>,
+    stale_notified: HashMap<String, DateTime<Utc>>,
+    validations: Vec<DbValidationAttempt>,
+    sync_state: Option<DbSyncState>,
+    match_plans: Vec<DbMatchPlan>,
+}
+
+/// Every repository of db::Repositories, backed by one in-memory store
+#[derive(Default)]
+pub struct InMemoryRepository {
+    store: Mutex<Store>,
+    /// Every query fails while set (the API's database error path)
+    unavailable: AtomicBool,
+}
+
+fn amount(value: &str) -> Decimal {
+    Decimal::from_str(value).unwrap_or_default()
+}
+
+/// Rows after the cursor in (sort value, ID) order, as the keyset queries return them
+fn keyset_page<T>(mut rows: Vec<T>, key: impl Fn(&T) -> (Decimal, String), descending: bool, after: &Option<PageCursor>, limit: i64) -> Vec<T> {
+    if let Some(cursor) = after {
+        let cursor = (amount(&cursor.value), cursor.id.clone());
+        rows.retain(|row| if descending { key(row) < cursor } else { key(row) > cursor });
+    }
+    rows.sort_by_key(&key);
+    if descending {
+        rows.reverse();
+    }
+    rows.truncate(limit.max(0) as usize);
+    rows
+}
+
+impl InMemoryRepository {
+    pub fn new() -> Arc<Self> {
+        Arc::new(Self::default())
+    }
+
+    /// The store behind every trait of `Repositories`
+    pub fn repositories(self: &Arc<Self>) -> Repositories {
+        Repositories {
+            orders: self.clone(),
+            trades: self.clone(),
+            token_status: self.clone(),
+            order_staleness: self.clone(),
+            blobs: self.clone(),
+            validations: self.clone(),
+            sync_state: self.clone(),
+            inspection: self.clone(),
+            match_plans: self.clone(),
+        }
+    }
+
+    pub fn insert_order(&self, order: DbOrder) {
+        self.store.lock().unwrap().orders.push(order);
+    }
+
+    pub fn insert_trade(&self, trade: DbTrade) {
+        self.store.lock().unwrap().trades.push(trade);
+    }
+
+    pub fn set_sync_state(&self, state: DbSyncState) {
+        self.store.lock().unwrap().sync_state = Some(state);
+    }
+
+    /// Current copy of a trade (None if unknown)
+    pub fn trade(&self, trade_id: &str) -> Option<DbTrade> {
+        self.store.lock().unwrap().trades.iter().find(|t| t.trade_id == trade_id).cloned()
+    }
+
+    /// Make every query fail (or work again)
+    pub fn set_unavailable(&self, unavailable: bool) {
+        self.unavailable.store(unavailable, Ordering::SeqCst);
+    }
+
+    fn store(&self) -> DbResult<MutexGuard<'_, Store>> {
+        if self.unavailable.load(Ordering::SeqCst) {
+            return Err(DbError::SqlxError(sqlx::Error::PoolTimedOut));
+        }
+        Ok(self.store.lock().unwrap())
+    }
+
+    fn with_trade<T>(&self, trade_id: &str, f: impl FnOnce(&mut DbTrade) -> T) -> DbResult<T> {
+        let mut store = self.store()?;
+        let trade = store
+            .trades
+            .iter_mut()
+            .find(|t| t.trade_id == trade_id)
+            .ok_or_else(|| DbError::TradeNotFound(trade_id.to_string()))?;
+        Ok(f(trade))
+    }
+
+    fn with_order<T>(&self, order_id: &str, f: impl FnOnce(&mut DbOrder) -> T) -> DbResult<T> {
+        let mut store = self.store()?;
+        let order = store
+            .orders
+            .iter_mut()
+            .find(|o| o.order_id == order_id)
+            .ok_or_else(|| DbError::OrderNotFound(order_id.to_string()))?;
+        Ok(f(order))
+    }
+
+    /// The trade with its order's token, as the Postgres queries join it
+    fn joined(store: &Store, trade: &DbTrade) -> DbTrade {
+        let mut trade = trade.clone();
+        trade.token = store.orders.iter().find(|o| o.order_id == trade.order_id).map(|o| o.token.clone());
+        trade
+    }
+}
+
+#[async_trait]
+impl OrderRepository for InMemoryRepository {
+    async fn create(&self, order: &DbOrder) -> DbResult<()> {
+        let mut store = self.store()?;
+        if !store.orders.iter().any(|o| o.order_id == order.order_id) {
+            store.orders.push(order.clone());
+        }
+        Ok(())
+    }
+
+    async fn adjust_remaining_amount(&self, order_id: &str, delta: &str) -> DbResult<()> {
+        let delta = Decimal::from_str(delta).map_err(|e| DbError::InvalidInput(e.to_string()))?;
+        self.with_order(order_id, |order| {
+            order.remaining_amount = (amount(&order.remaining_amount) + delta).to_string();
+        })
+    }
+
+    async fn apply_batch(&self, changes: &[OrderChange]) -> DbResult<()> {
+        for change in changes {
+            match change {
+                OrderChange::Create(order) => OrderRepository::create(self, order).await?,
+                OrderChange::Withdraw { order_id, amount } => {
+                    self.adjust_remaining_amount(order_id, &format!("-{}", amount)).await?
+                }
+                OrderChange::Adjust { order_id, delta } => self.adjust_remaining_amount(order_id, delta).await?,
+            }
+        }
+        Ok(())
+    }
+
+    async fn get_active_orders(&self, limit: Option<i64>, policy: &TokenPolicy) -> DbResult<Vec<DbOrder>> {
+        let store = self.store()?;
+        let mut orders: Vec<DbOrder> = store
+            .orders
+            .iter()
+            .filter(|o| amount(&o.remaining_amount) > Decimal::ZERO && !policy.is_suspended(&o.token))
+            .cloned()
+            .collect();
+        orders.sort_by_key(|o| (amount(&o.exchange_rate), o.created_at));
+        orders.truncate(limit.unwrap_or(i64::MAX).max(0) as usize);
+        Ok(orders)
+    }
+
+    async fn get_active_orders_by_token(&self, token: &EthAddress, limit: Option<i64>, _priority: OrderPriority) -> DbResult<Vec<DbOrder>> {
+        let mut orders = self.get_active_orders(None, &TokenPolicy::default()).await?;
+        orders.retain(|o| o.token == *token);
+        orders.truncate(limit.unwrap_or(i64::MAX).max(0) as usize);
+        Ok(orders)
+    }
+
+    async fn get(&self, order_id: &str) -> DbResult<DbOrder> {
+        self.with_order(order_id, |order| order.clone())
+    }
+
+    async fn get_by_seller(&self, seller: &EthAddress) -> DbResult<Vec<DbOrder>> {
+        let store = self.store()?;
+        let mut orders: Vec<DbOrder> = store.orders.iter().filter(|o| o.seller == *seller).cloned().collect();
+        orders.sort_by_key(|o| std::cmp::Reverse(o.created_at));
+        Ok(orders)
+    }
+
+    async fn set_fill_limits(&self, order_id: &str, min_fill: Option<Decimal>, lot_size: Option<Decimal>, signed_at: i64) -> DbResult<bool> {
+        let mut store = self.store()?;
+        if store.fill_limits_signed_at.get(order_id).is_some_and(|applied| *applied >= signed_at) {
+            return Ok(false);
+        }
+        let Some(order) = store.orders.iter_mut().find(|o| o.order_id == order_id) else {
+            return Ok(false);
+        };
+        order.min_fill = min_fill.map(|m| m.to_string());
+        order.lot_size = lot_size.map(|l| l.to_string());
+        store.fill_limits_signed_at.insert(order_id.to_string(), signed_at);
+        Ok(true)
+    }
+
+    async fn seller_reputations(&self, sellers: &[EthAddress]) -> DbResult<Vec<DbSellerReputation>> {
+        let store = self.store()?;
+        let mut reputations: HashMap<EthAddress, DbSellerReputation> = HashMap::new();
+        for trade in &store.trades {
+            let Some(order) = store.orders.iter().find(|o| o.order_id == trade.order_id && sellers.contains(&o.seller)) else {
+                continue;
+            };
+            let reputation = reputations.entry(order.seller.clone()).or_insert_with(|| DbSellerReputation {
+                seller: order.seller.clone(),
+                settled_trades: 0,
+                expired_trades: 0,
+            });
+            match trade.status {
+                1 => reputation.settled_trades += 1,
+                2 => reputation.expired_trades += 1,
+                _ => {}
+            }
+        }
+        Ok(reputations.into_values().collect())
+    }
+}
+
+#[async_trait]
+impl TradeRepository for InMemoryRepository {
+    async fn create(&self, trade: &DbTrade) -> DbResult<()> {
+        let mut store = self.store()?;
+        if !store.trades.iter().any(|t| t.trade_id == trade.trade_id) {
+            store.trades.push(trade.clone());
+        }
+        Ok(())
+    }
+
+    async fn get(&self, trade_id: &str) -> DbResult<DbTrade> {
+        let store = self.store()?;
+        store
+            .trades
+            .iter()
+            .find(|t| t.trade_id == trade_id)
+            .map(|t| Self::joined(&store, t))
+            .ok_or_else(|| DbError::TradeNotFound(trade_id.to_string()))
+    }
+
+    async fn get_by_nonce(&self, payment_nonce: &str) -> DbResult<Option<DbTrade>> {
+        let store = self.store()?;
+        Ok(store.trades.iter().find(|t| t.payment_nonce == payment_nonce).map(|t| Self::joined(&store, t)))
+    }
+
+    async fn get_by_buyer(&self, buyer: &EthAddress) -> DbResult<Vec<DbTrade>> {
+        let store = self.store()?;
+        let mut trades: Vec<DbTrade> = store
+            .trades
+            .iter()
+            .filter(|t| t.buyer == *buyer)
+            .map(|t| Self::joined(&store, t))
+            .collect();
+        trades.sort_by_key(|t| std::cmp::Reverse(t.created_at));
+        Ok(trades)
+    }
+
+    async fn update_status(&self, trade_id: &str, new_status: i32) -> DbResult<()> {
+        self.with_trade(trade_id, |trade| trade.status = new_status)
+    }
+
+    async fn update_proof_hash(&self, trade_id: &str, _proof_hash: &str) -> DbResult<()> {
+        self.with_trade(trade_id, |_| ())
+    }
+
+    async fn update_settlement_tx(&self, trade_id: &str, settlement_tx_hash: &str) -> DbResult<()> {
+        self.with_trade(trade_id, |trade| trade.settlement_tx_hash = Some(settlement_tx_hash.to_string()))
+    }
+
+    async fn save_pdf(&self, trade_id: &str, pdf_data: &[u8], filename: &str) -> DbResult<DateTime<Utc>> {
+        let uploaded_at = Utc::now();
+        let pending = self.with_trade(trade_id, |trade| {
+            if trade.status != 0 {
+                return false;
+            }
+            trade.pdf_file = Some(pdf_data.to_vec());
+            trade.pdf_filename = Some(filename.to_string());
+            trade.pdf_uploaded_at = Some(uploaded_at);
+            // The proof of the previous PDF is cleared (Postgres archives it)
+            trade.proof_user_public_values = None;
+            trade.proof_accumulator = None;
+            trade.proof_data = None;
+            trade.axiom_proof_id = None;
+            trade.proof_generated_at = None;
+            trade.proof_json = None;
+            trade.proof_format = None;
+            true
+        })?;
+        if !pending {
+            return Err(DbError::InvalidInput(format!("Trade {} is not pending", trade_id)));
+        }
+        Ok(uploaded_at)
+    }
+
+    async fn save_proof(&self, trade_id: &str, pdf_uploaded_at: Option<DateTime<Utc>>, user_public_values: &[u8], accumulator: &[u8], proof_data: &[u8], axiom_proof_id: &str, proof_json: &str, proof_format: &str) -> DbResult<bool> {
+        self.with_trade(trade_id, |trade| {
+            if trade.pdf_uploaded_at != pdf_uploaded_at {
+                return false;
+            }
+            trade.proof_user_public_values = Some(user_public_values.to_vec());
+            trade.proof_accumulator = Some(accumulator.to_vec());
+            trade.proof_data = Some(proof_data.to_vec());
+            trade.axiom_proof_id = Some(axiom_proof_id.to_string());
+            trade.proof_generated_at = Some(Utc::now());
+            trade.proof_json = Some(proof_json.to_string());
+            trade.proof_format = Some(proof_format.to_string());
+            true
+        })
+    }
+}
+
+#[async_trait]
+impl TokenStatusRepository for InMemoryRepository {
+    async fn set(&self, token: &EthAddress, status: &str, reason: Option<&str>, updated_by: Option<&str>) -> DbResult<DbTokenStatus> {
+        let entry = DbTokenStatus {
+            token: token.clone(),
+            status: status.to_string(),
+            reason: reason.map(str::to_string),
+            updated_by: updated_by.map(str::to_string),
+            updated_at: Utc::now(),
+        };
+        let mut store = self.store()?;
+        store.token_status.retain(|e| e.token != *token);
+        store.token_status.push(entry.clone());
+        Ok(entry)
+    }
+
+    async fn remove(&self, token: &EthAddress) -> DbResult<bool> {
+        let mut store = self.store()?;
+        let before = store.token_status.len();
+        store.token_status.retain(|e| e.token != *token);
+        Ok(store.token_status.len() < before)
+    }
+
+    async fn list(&self) -> DbResult<Vec<DbTokenStatus>> {
+        Ok(self.store()?.token_status.clone())
+    }
+}
+
+#[async_trait]
+impl OrderStalenessRepository for InMemoryRepository {
+    async fn settings(&self) -> DbResult<DbStalenessSettings> {
+        let store = self.store()?;
+        Ok(store.staleness.clone().unwrap_or_else(|| DbStalenessSettings {
+            stale_after_days: 14,
+            notify_every_days: 7,
+            updated_by: None,
+            updated_at: Utc::now(),
+        }))
+    }
+
+    async fn set_settings(&self, stale_after_days: i32, notify_every_days: i32, updated_by: Option<&str>) -> DbResult<DbStalenessSettings> {
+        let settings = DbStalenessSettings {
+            stale_after_days,
+            notify_every_days,
+            updated_by: updated_by.map(str::to_string),
+            updated_at: Utc::now(),
+        };
+        self.store()?.staleness = Some(settings.clone());
+        Ok(settings)
+    }
+
+    async fn refresh(&self, order_id: &str, signed_at: i64) -> DbResult<bool> {
+        let mut store = self.store()?;
+        if store.refreshed.get(order_id).is_some_and(|(applied, _)| *applied >= signed_at) {
+            return Ok(false);
+        }
+        store.refreshed.insert(order_id.to_string(), (signed_at, Utc::now()));
+        Ok(true)
+    }
+
+    async fn list_stale(
+        &self,
+        token: Option<&EthAddress>,
+        stale_before: DateTime<Utc>,
+        notified_before: Option<DateTime<Utc>>,
+        limit: Option<i64>,
+    ) -> DbResult<Vec<DbStaleOrder>> {
+        let store = self.store()?;
+        let mut stale: Vec<DbStaleOrder> = store
+            .orders
+            .iter()
+            .filter(|o| amount(&o.remaining_amount) > Decimal::ZERO && token.is_none_or(|t| o.token == *t))
+            .map(|o| DbStaleOrder {
+                order_id: o.order_id.clone(),
+                seller: o.seller.clone(),
+                token: o.token.clone(),
+                remaining_amount: o.remaining_amount.clone(),
+                last_activity_at: store
+                    .refreshed
+                    .get(&o.order_id)
+                    .map(|(_, at)| *at)
+                    .unwrap_or_else(|| DateTime::from_timestamp(o.created_at, 0).unwrap_or_default()),
+                stale_notified_at: store.stale_notified.get(&o.order_id).copied(),
+            })
+            .filter(|o| o.last_activity_at < stale_before)
+            .filter(|o| notified_before.is_none_or(|before| o.stale_notified_at.is_none_or(|at| at < before)))
+            .collect();
+        stale.sort_by_key(|o| o.last_activity_at);
+        stale.truncate(limit.unwrap_or(i64::MAX).max(0) as usize);
+        Ok(stale)
+    }
+
+    async fn mark_notified(&self, order_id: &str) -> DbResult<()> {
+        self.store()?.stale_notified.insert(order_id.to_string(), Utc::now());
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl TradeBlobRepository for InMemoryRepository {
+    async fn info(&self, trade_id: &str, blob: TradeBlob) -> DbResult<Option<BlobInfo>> {
+        let trade = TradeRepository::get(self, trade_id).await?;
+        Ok(match blob {
+            TradeBlob::Pdf => trade.pdf_file.zip(trade.pdf_uploaded_at).map(|(data, version)| BlobInfo {
+                len: data.len() as i64,
+                version,
+                filename: trade.pdf_filename,
+            }),
+            TradeBlob::ProofJson => trade.proof_json.zip(trade.proof_generated_at).map(|(json, version)| BlobInfo {
+                len: json.len() as i64,
+                version,
+                filename: None,
+            }),
+        })
+    }
+
+    async fn chunk(&self, trade_id: &str, blob: TradeBlob, version: DateTime<Utc>, offset: i64, len: i64) -> DbResult<Option<Vec<u8>>> {
+        let trade = TradeRepository::get(self, trade_id).await?;
+        let (data, current) = match blob {
+            TradeBlob::Pdf => (trade.pdf_file, trade.pdf_uploaded_at),
+            TradeBlob::ProofJson => (trade.proof_json.map(String::into_bytes), trade.proof_generated_at),
+        };
+        Ok(data.filter(|_| current == Some(version)).map(|data| {
+            let start = (offset.max(0) as usize).min(data.len());
+            let end = start.saturating_add(len.max(0) as usize).min(data.len());
+            data[start..end].to_vec()
+        }))
+    }
+}
+
+#[async_trait]
+impl ValidationAttemptRepository for InMemoryRepository {
+    async fn create(&self, attempt: &NewValidationAttempt<'_>) -> DbResult<()> {
+        let mut store = self.store()?;
+        let id = store.validations.len() as i64 + 1;
+        store.validations.push(DbValidationAttempt {
+            id,
+            trade_id: attempt.trade_id.to_string(),
+            source: attempt.source.to_string(),
+            program_id: attempt.program_id.map(str::to_string),
+            input_hash: attempt.input_hash.map(str::to_string),
+            expected_hash: attempt.expected_hash.map(str::to_string),
+            actual_hash: attempt.actual_hash.map(str::to_string),
+            is_valid: attempt.is_valid,
+            error: attempt.error.map(str::to_string),
+            attempted_at: Utc::now(),
+        });
+        Ok(())
+    }
+
+    async fn list_for_trade(&self, trade_id: &str) -> DbResult<Vec<DbValidationAttempt>> {
+        let store = self.store()?;
+        Ok(store.validations.iter().rev().filter(|a| a.trade_id == trade_id).cloned().collect())
+    }
+}
+
+#[async_trait]
+impl SyncStateRepository for InMemoryRepository {
+    async fn latest(&self) -> DbResult<Option<DbSyncState>> {
+        Ok(self.store()?.sync_state.clone())
+    }
+}
+
+#[async_trait]
+impl InspectionRepository for InMemoryRepository {
+    async fn page_orders(&self, page: &OrderPage) -> DbResult<Vec<DbOrder>> {
+        let store = self.store()?;
+        let orders = store
+            .orders
+            .iter()
+            .filter(|o| match page.status {
+                Some(OrderState::Active) => amount(&o.remaining_amount) > Decimal::ZERO,
+                Some(OrderState::Filled) => amount(&o.remaining_amount).is_zero(),
+                None => true,
+            })
+            .cloned()
+            .collect();
+        let key = |o: &DbOrder| (amount(&page.sort.value(o)), o.order_id.clone());
+        Ok(keyset_page(orders, key, page.descending, &page.after, page.limit))
+    }
+
+    async fn page_trades(&self, page: &TradePage) -> DbResult<Vec<DbTrade>> {
+        let store = self.store()?;
+        let trades = store
+            .trades
+            .iter()
+            .filter(|t| page.status.is_none_or(|status| t.status == status))
+            .map(|t| DbTrade {
+                pdf_file: None,
+                proof_user_public_values: None,
+                proof_accumulator: None,
+                proof_data: None,
+                proof_json: t.proof_json.clone().filter(|_| page.with_proof_json),
+                ..Self::joined(&store, t)
+            })
+            .collect();
+        let key = |t: &DbTrade| (amount(&page.sort.value(t)), t.trade_id.clone());
+        Ok(keyset_page(trades, key, page.descending, &page.after, page.limit))
+    }
+}
+
+#[async_trait]
+impl MatchPlanRepository for InMemoryRepository {
+    async fn create(&self, plan: &NewMatchPlan) -> DbResult<Uuid> {
+        let plan_id = Uuid::new_v4();
+        self.store()?.match_plans.push(DbMatchPlan {
+            plan_id,
+            token: plan.token.clone(),
+            buyer: plan.buyer.clone(),
+            request: plan.request.clone(),
+            plan: plan.plan.clone(),
+            book_hash: plan.book_hash.clone(),
+            order_priority: plan.order_priority.clone(),
+            created_at: Utc::now(),
+            expires_at: plan.expires_at,
+            executed_by: None,
+            trade_ids: Vec::new(),
+            executed_at: None,
+        });
+        Ok(plan_id)
+    }
+
+    async fn get(&self, plan_id: Uuid) -> DbResult<Option<DbMatchPlan>> {
+        Ok(self.store()?.match_plans.iter().find(|p| p.plan_id == plan_id).cloned())
+    }
+
+    async fn record_execution(&self, plan_id: Uuid, buyer: &EthAddress, trade_ids: &[String]) -> DbResult<()> {
+        if let Some(plan) = self.store()?.match_plans.iter_mut().find(|p| p.plan_id == plan_id) {
+            plan.executed_by = Some(buyer.clone());
+            plan.trade_ids.extend_from_slice(trade_ids);
+            plan.executed_at = Some(Utc::now());
+        }
+        Ok(())
+    }
+
+    async fn prune_expired(&self, before: DateTime<Utc>) -> DbResult<u64> {
+        let mut store = self.store()?;
+        let count = store.match_plans.len();
+        store.match_plans.retain(|p| p.expires_at >= before || p.executed_at.is_some());
+        Ok((count - store.match_plans.len()) as u64)
+    }
+}
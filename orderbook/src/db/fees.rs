@@ -0,0 +1,132 @@
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use rust_decimal::Decimal;
+use sqlx::PgPool;
+use std::str::FromStr;
+
+use super::{DbError, DbResult};
+use crate::blockchain::address::EthAddress;
+use super::models::{DbFeeAccrual, DbFeeTotal};
+
+/// Fee accrual for a settled trade
+#[derive(Debug, Clone)]
+pub struct NewFeeAccrual {
+    pub trade_id: String,
+    pub order_id: String,
+    pub payer_role: &'static str,     // seller | buyer
+    pub payer: EthAddress,
+    pub token: EthAddress,
+    pub token_fee: String,            // decimal string
+    pub cny_fee: String,              // CNY cents, decimal string
+    pub schedule: String,
+}
+
+/// Repository for operator fee accruals
+#[async_trait]
+pub trait FeeAccrualRepository: Send + Sync {
+    /// Record a trade's accrual (ignored if the trade already has one)
+    async fn create(&self, accrual: &NewFeeAccrual) -> DbResult<()>;
+
+    /// Totals per payer and token in [from, to), largest CNY total first
+    async fn totals_by_payer(&self, from: DateTime<Utc>, to: DateTime<Utc>, limit: i64) -> DbResult<Vec<DbFeeTotal>>;
+
+    /// Accruals of one payer in [from, to), newest first
+    async fn list_for_payer(&self, payer: &EthAddress, from: DateTime<Utc>, to: DateTime<Utc>) -> DbResult<Vec<DbFeeAccrual>>;
+}
+
+pub struct PostgresFeeAccrualRepository {
+    pool: PgPool,
+}
+
+impl PostgresFeeAccrualRepository {
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+}
+
+fn decimal(name: &str, value: &str) -> DbResult<Decimal> {
+    Decimal::from_str(value).map_err(|e| DbError::InvalidInput(format!("Invalid {}: {}", name, e)))
+}
+
+#[async_trait]
+impl FeeAccrualRepository for PostgresFeeAccrualRepository {
+    async fn create(&self, accrual: &NewFeeAccrual) -> DbResult<()> {
+        sqlx::query!(
+            r#"
+            INSERT INTO fee_accruals (
+                "tradeId", "orderId", "payerRole", "payer", "token",
+                "tokenFee", "cnyFee", "schedule"
+            )
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8)
+            ON CONFLICT ("tradeId") DO NOTHING
+            "#,
+            accrual.trade_id,
+            accrual.order_id,
+            accrual.payer_role,
+            accrual.payer.as_str(),
+            accrual.token.as_str(),
+            decimal("token fee", &accrual.token_fee)?,
+            decimal("CNY fee", &accrual.cny_fee)?,
+            accrual.schedule
+        )
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    async fn totals_by_payer(&self, from: DateTime<Utc>, to: DateTime<Utc>, limit: i64) -> DbResult<Vec<DbFeeTotal>> {
+        let totals = sqlx::query_as!(
+            DbFeeTotal,
+            r#"
+            SELECT
+                "payer" as "payer!: EthAddress",
+                "payerRole" as "payer_role!",
+                "token" as "token!: EthAddress",
+                COUNT(*) as "trade_count!",
+                SUM("tokenFee")::TEXT as "token_fee!",
+                SUM("cnyFee")::TEXT as "cny_fee!"
+            FROM fee_accruals
+            WHERE "accruedAt" >= $1 AND "accruedAt" < $2
+            GROUP BY "payer", "payerRole", "token"
+            ORDER BY SUM("cnyFee") DESC
+            LIMIT $3
+            "#,
+            from,
+            to,
+            limit
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(totals)
+    }
+
+    async fn list_for_payer(&self, payer: &EthAddress, from: DateTime<Utc>, to: DateTime<Utc>) -> DbResult<Vec<DbFeeAccrual>> {
+        let accruals = sqlx::query_as!(
+            DbFeeAccrual,
+            r#"
+            SELECT
+                "tradeId" as "trade_id!",
+                "orderId" as "order_id!",
+                "payerRole" as "payer_role!",
+                "payer" as "payer!: EthAddress",
+                "token" as "token!: EthAddress",
+                "tokenFee"::TEXT as "token_fee!",
+                "cnyFee"::TEXT as "cny_fee!",
+                "schedule" as "schedule!",
+                "accruedAt" as "accrued_at!"
+            FROM fee_accruals
+            WHERE "payer" = $1 AND "accruedAt" >= $2 AND "accruedAt" < $3
+            ORDER BY "accruedAt" DESC
+            "#,
+            payer.as_str(),
+            from,
+            to
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(accruals)
+    }
+}
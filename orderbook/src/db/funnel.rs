@@ -0,0 +1,71 @@
+use async_trait::async_trait;
+use sqlx::PgPool;
+
+use super::DbResult;
+use super::models::DbLifecycleFunnel;
+use crate::blockchain::address::EthAddress;
+
+/// Aggregates for the lifecycle funnel
+#[async_trait]
+pub trait FunnelRepository: Send + Sync {
+    /// Funnel of the orders created in [from, to) (unix seconds) and every trade on them
+    async fn lifecycle(&self, token: Option<&EthAddress>, from: i64, to: i64) -> DbResult<DbLifecycleFunnel>;
+}
+
+pub struct PostgresFunnelRepository {
+    pool: PgPool,
+}
+
+impl PostgresFunnelRepository {
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+}
+
+#[async_trait]
+impl FunnelRepository for PostgresFunnelRepository {
+    async fn lifecycle(&self, token: Option<&EthAddress>, from: i64, to: i64) -> DbResult<DbLifecycleFunnel> {
+        // A later stage implies the earlier ones even when their trace is gone (a
+        // re-uploaded PDF clears the proof, settled trades needn't have a local validation)
+        let funnel = sqlx::query_as!(
+            DbLifecycleFunnel,
+            r#"
+            WITH cohort AS (
+                SELECT "orderId" FROM orders
+                WHERE "createdAt" >= $1 AND "createdAt" < $2
+                AND ($3::TEXT IS NULL OR token = $3)
+            ),
+            stages AS (
+                SELECT
+                    t."orderId",
+                    t."status" = 1 as settled,
+                    t."status" = 2 as expired,
+                    t.proof_generated_at IS NOT NULL as proven,
+                    EXISTS (
+                        SELECT 1 FROM validation_attempts v WHERE v."tradeId" = t."tradeId" AND v."isValid"
+                    ) as validated,
+                    t.pdf_uploaded_at IS NOT NULL as pdf_uploaded
+                FROM trades t
+                JOIN cohort c ON c."orderId" = t."orderId"
+            )
+            SELECT
+                (SELECT COUNT(*) FROM cohort) as "orders_created!",
+                COUNT(DISTINCT "orderId") as "orders_matched!",
+                COUNT(*) as "trades_filled!",
+                COUNT(*) FILTER (WHERE pdf_uploaded OR validated OR proven OR settled) as "pdf_uploaded!",
+                COUNT(*) FILTER (WHERE validated OR proven OR settled) as "validated!",
+                COUNT(*) FILTER (WHERE proven OR settled) as "proven!",
+                COUNT(*) FILTER (WHERE settled) as "settled!",
+                COUNT(*) FILTER (WHERE expired) as "expired!"
+            FROM stages
+            "#,
+            from,
+            to,
+            token.map(EthAddress::as_str)
+        )
+        .fetch_one(&self.pool)
+        .await?;
+
+        Ok(funnel)
+    }
+}
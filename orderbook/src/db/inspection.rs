@@ -0,0 +1,297 @@
+// Paged inspection of orders and trades (GET /api/admin/orders, /api/admin/trades)
+// /api/debug/database returns both tables whole, which stopped being usable - or safe to
+// run against production - once they grew: every request read every PDF and proof. These
+// queries return one page at a time, keyed on the sort column plus the row ID (a cursor
+// stays valid while rows are inserted), filter by status and leave out the binary columns,
+// and the proof JSON unless it is asked for.
+
+use async_trait::async_trait;
+use sqlx::{PgPool, Postgres, QueryBuilder};
+use std::fmt;
+use std::str::FromStr;
+
+use super::models::{DbOrder, DbTrade};
+use super::DbResult;
+
+/// Columns of /api/admin/orders (DbOrder's JSON fields)
+pub const ORDER_COLUMNS: &[&str] = &[
+    "order_id", "seller", "token", "total_amount", "remaining_amount", "exchange_rate", "alipay_id",
+    "alipay_name", "created_at", "synced_at", "alipay_id_format", "min_fill", "lot_size",
+];
+
+/// Columns of /api/admin/trades (DbTrade's JSON fields; PDFs and proof bytes never leave
+/// the database through it)
+pub const TRADE_COLUMNS: &[&str] = &[
+    "trade_id", "order_id", "buyer", "token_amount", "cny_amount", "payment_nonce", "created_at",
+    "expires_at", "status", "synced_at", "escrow_tx_hash", "settlement_tx_hash", "settlement_block",
+    "confirmations", "finalized_at", "token", "pdf_filename", "pdf_uploaded_at", "axiom_proof_id",
+    "proof_generated_at", "proof_json", "proof_format",
+];
+
+/// Trade columns only returned when selected explicitly (tens of KB per row)
+pub const TRADE_HEAVY_COLUMNS: &[&str] = &["proof_json"];
+
+/// TradeStatus of a status filter name
+pub fn trade_status(name: &str) -> Option<i32> {
+    match name {
+        "pending" => Some(0),
+        "settled" => Some(1),
+        "expired" => Some(2),
+        _ => None,
+    }
+}
+
+/// Implicit order status: active while liquidity remains
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OrderState {
+    Active,
+    Filled,
+}
+
+impl FromStr for OrderState {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "active" => Ok(OrderState::Active),
+            "filled" => Ok(OrderState::Filled),
+            other => Err(format!("Invalid status '{}': expected active or filled", other)),
+        }
+    }
+}
+
+/// Column an order page is sorted by (ties broken by order ID)
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum OrderSort {
+    #[default]
+    CreatedAt,
+    RemainingAmount,
+}
+
+impl OrderSort {
+    fn column(self) -> &'static str {
+        match self {
+            OrderSort::CreatedAt => r#""createdAt""#,
+            OrderSort::RemainingAmount => r#""remainingAmount""#,
+        }
+    }
+
+    /// The order's value of the sort column (an integer)
+    pub fn value(self, order: &DbOrder) -> String {
+        match self {
+            OrderSort::CreatedAt => order.created_at.to_string(),
+            OrderSort::RemainingAmount => order.remaining_amount.clone(),
+        }
+    }
+}
+
+impl FromStr for OrderSort {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "created_at" => Ok(OrderSort::CreatedAt),
+            "remaining_amount" => Ok(OrderSort::RemainingAmount),
+            other => Err(format!("Invalid sort '{}': expected created_at or remaining_amount", other)),
+        }
+    }
+}
+
+/// Column a trade page is sorted by (ties broken by trade ID)
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum TradeSort {
+    #[default]
+    CreatedAt,
+    ExpiresAt,
+    CnyAmount,
+}
+
+impl TradeSort {
+    fn column(self) -> &'static str {
+        match self {
+            TradeSort::CreatedAt => r#"t."createdAt""#,
+            TradeSort::ExpiresAt => r#"t."expiresAt""#,
+            TradeSort::CnyAmount => r#"t."cnyAmount""#,
+        }
+    }
+
+    /// The trade's value of the sort column (an integer)
+    pub fn value(self, trade: &DbTrade) -> String {
+        match self {
+            TradeSort::CreatedAt => trade.created_at.to_string(),
+            TradeSort::ExpiresAt => trade.expires_at.to_string(),
+            TradeSort::CnyAmount => trade.cny_amount.clone(),
+        }
+    }
+}
+
+impl FromStr for TradeSort {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "created_at" => Ok(TradeSort::CreatedAt),
+            "expires_at" => Ok(TradeSort::ExpiresAt),
+            "cny_amount" => Ok(TradeSort::CnyAmount),
+            other => Err(format!("Invalid sort '{}': expected created_at, expires_at or cny_amount", other)),
+        }
+    }
+}
+
+/// Position after the last row of a page: its sort value and ID ("<value>:<id>")
+/// Only meaningful with the sort and direction of the page it came from.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PageCursor {
+    /// Non-negative integer (sort columns are timestamps and uint256 amounts)
+    pub value: String,
+    pub id: String,
+}
+
+impl PageCursor {
+    pub fn new(value: &str, id: &str) -> Self {
+        Self { value: value.to_string(), id: id.to_string() }
+    }
+}
+
+impl fmt::Display for PageCursor {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}:{}", self.value, self.id)
+    }
+}
+
+impl FromStr for PageCursor {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid = || format!("Invalid cursor '{}'", s);
+        let (value, id) = s.split_once(':').ok_or_else(invalid)?;
+        if value.is_empty() || !value.bytes().all(|b| b.is_ascii_digit()) {
+            return Err(invalid());
+        }
+        let hex = id.strip_prefix("0x").ok_or_else(invalid)?;
+        if hex.len() != 64 || !hex.bytes().all(|b| b.is_ascii_hexdigit()) {
+            return Err(invalid());
+        }
+        Ok(Self { value: value.to_string(), id: id.to_lowercase() })
+    }
+}
+
+/// One page of orders
+#[derive(Debug, Clone, Default)]
+pub struct OrderPage {
+    pub status: Option<OrderState>,
+    pub sort: OrderSort,
+    pub descending: bool,
+    pub after: Option<PageCursor>,
+    pub limit: i64,
+}
+
+/// One page of trades
+#[derive(Debug, Clone, Default)]
+pub struct TradePage {
+    /// TradeStatus (see trade_status)
+    pub status: Option<i32>,
+    pub sort: TradeSort,
+    pub descending: bool,
+    pub after: Option<PageCursor>,
+    pub limit: i64,
+    /// Read proof_json (left NULL otherwise)
+    pub with_proof_json: bool,
+}
+
+#[async_trait]
+pub trait InspectionRepository: Send + Sync {
+    /// Orders of a page, in page order
+    async fn page_orders(&self, page: &OrderPage) -> DbResult<Vec<DbOrder>>;
+
+    /// Trades of a page, in page order, without PDFs and proof bytes
+    async fn page_trades(&self, page: &TradePage) -> DbResult<Vec<DbTrade>>;
+}
+
+pub struct PostgresInspectionRepository {
+    pool: PgPool,
+}
+
+impl PostgresInspectionRepository {
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+}
+
+/// `AND (<column>, <id>) > (cursor)` (< descending) and the ORDER BY / LIMIT of a page
+fn push_keyset(query: &mut QueryBuilder<'_, Postgres>, column: &str, id: &str, descending: bool, after: &Option<PageCursor>, limit: i64) {
+    let (op, direction) = if descending { ("<", "DESC") } else { (">", "ASC") };
+    if let Some(cursor) = after {
+        query.push(format!(" AND ({}, {}) {} (", column, id, op));
+        query.push_bind(cursor.value.clone());
+        query.push("::numeric, ");
+        query.push_bind(cursor.id.clone());
+        query.push(")");
+    }
+    query.push(format!(" ORDER BY {} {}, {} {} LIMIT ", column, direction, id, direction));
+    query.push_bind(limit);
+}
+
+#[async_trait]
+impl InspectionRepository for PostgresInspectionRepository {
+    async fn page_orders(&self, page: &OrderPage) -> DbResult<Vec<DbOrder>> {
+        let mut query = QueryBuilder::<Postgres>::new(
+            r#"
+            SELECT "orderId", seller, token, "totalAmount"::TEXT AS "totalAmount",
+                "remainingAmount"::TEXT AS "remainingAmount", "exchangeRate"::TEXT AS "exchangeRate",
+                "alipayId", "alipayName", "createdAt", "syncedAt", "alipayIdFormat",
+                "minFill"::TEXT AS "minFill", "lotSize"::TEXT AS "lotSize"
+            FROM orders
+            WHERE TRUE
+            "#,
+        );
+        if let Some(state) = page.status {
+            query.push(match state {
+                OrderState::Active => r#" AND "remainingAmount" > 0"#,
+                OrderState::Filled => r#" AND "remainingAmount" = 0"#,
+            });
+        }
+        push_keyset(&mut query, page.sort.column(), r#""orderId""#, page.descending, &page.after, page.limit);
+
+        Ok(query.build_query_as::<DbOrder>().fetch_all(&self.pool).await?)
+    }
+
+    async fn page_trades(&self, page: &TradePage) -> DbResult<Vec<DbTrade>> {
+        let mut query = QueryBuilder::<Postgres>::new(
+            r#"
+            SELECT t."tradeId", t."orderId", t.buyer, t."tokenAmount"::TEXT AS "tokenAmount",
+                t."cnyAmount"::TEXT AS "cnyAmount", t."paymentNonce", t."createdAt", t."expiresAt", t.status,
+                t."syncedAt", t."escrowTxHash", t."settlementTxHash", t."settlementBlock", t.confirmations,
+                t."finalizedAt", o.token, NULL::BYTEA AS pdf_file, t.pdf_filename, t.pdf_uploaded_at,
+                NULL::BYTEA AS proof_user_public_values, NULL::BYTEA AS proof_accumulator,
+                NULL::BYTEA AS proof_data, t.axiom_proof_id, t.proof_generated_at, t."proofFormat",
+            "#,
+        );
+        query.push(if page.with_proof_json { "t.proof_json" } else { "NULL::TEXT AS proof_json" });
+        query.push(r#" FROM trades t LEFT JOIN orders o ON o."orderId" = t."orderId" WHERE TRUE"#);
+        if let Some(status) = page.status {
+            query.push(" AND t.status = ").push_bind(status);
+        }
+        push_keyset(&mut query, page.sort.column(), r#"t."tradeId""#, page.descending, &page.after, page.limit);
+
+        Ok(query.build_query_as::<DbTrade>().fetch_all(&self.pool).await?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_cursor_round_trip() {
+        let id = format!("0x{}", "ab".repeat(32));
+        let cursor = PageCursor::new("1700000000", &id);
+        assert_eq!(cursor.to_string().parse::<PageCursor>(), Ok(cursor));
+
+        let upper = format!("0x{}", "AB".repeat(32));
+        assert_eq!(format!("5:{}", upper).parse::<PageCursor>().unwrap().id, id);
+        for bad in ["", "5", "x:0x11", &format!("-5:{}", id), &format!("1.5:{}", id), &format!("5:{}", "ab".repeat(32)), "5:0x11", &format!("5:0x{}", "zz".repeat(32))] {
+            assert!(bad.parse::<PageCursor>().is_err(), "{}", bad);
+        }
+    }
+}
@@ -0,0 +1,98 @@
+// Leader election for single-writer background services
+// Several api-server (or auto-cancel) instances may run side by side; only the holder
+// of a Postgres session-level advisory lock processes events / cancels trades. The lock
+// lives on a dedicated connection (not a pooled one, which would keep it after being
+// returned to the pool) and is released by Postgres as soon as that session ends, so a
+// crashed leader is replaced by a standby on its next attempt.
+
+use sqlx::postgres::{PgConnection, PgPool};
+use sqlx::Connection;
+use std::time::Duration;
+
+use super::DbResult;
+
+/// Advisory lock keys (one per single-writer service)
+pub const LOCK_EVENT_LISTENER: i64 = 0x7a6b_616c_0001;
+pub const LOCK_AUTO_CANCEL: i64 = 0x7a6b_616c_0002;
+pub const LOCK_EVENT_JOURNAL: i64 = 0x7a6b_616c_0003;
+/// Held while migrations run (see Database::migrate), not a leader election
+pub const LOCK_MIGRATIONS: i64 = 0x7a6b_616c_0004;
+
+/// How often standbys retry the lock (and leaders re-check it)
+pub const DEFAULT_LEADER_RETRY: Duration = Duration::from_secs(10);
+
+/// Held leadership; dropping it closes the session and releases the lock
+pub struct LeaderLock {
+    name: &'static str,
+    key: i64,
+    conn: PgConnection,
+}
+
+impl LeaderLock {
+    /// Take the lock if no other session holds it
+    pub async fn try_acquire(pool: &PgPool, name: &'static str, key: i64) -> DbResult<Option<Self>> {
+        let mut conn = PgConnection::connect_with(&pool.connect_options()).await?;
+
+        let locked = sqlx::query_scalar!(r#"SELECT pg_try_advisory_lock($1) AS "locked!""#, key)
+            .fetch_one(&mut conn)
+            .await?;
+
+        if locked {
+            tracing::info!("👑 Acquired {} leadership (advisory lock {:#x})", name, key);
+            Ok(Some(Self { name, key, conn }))
+        } else {
+            let _ = conn.close().await;
+            Ok(None)
+        }
+    }
+
+    /// Wait until the lock is ours, retrying every `retry`
+    pub async fn acquire(pool: &PgPool, name: &'static str, key: i64, retry: Duration) -> Self {
+        let mut logged_standby = false;
+        loop {
+            match Self::try_acquire(pool, name, key).await {
+                Ok(Some(lock)) => return lock,
+                Ok(None) if !logged_standby => {
+                    tracing::info!("⏸️  Another instance leads {} - standing by", name);
+                    logged_standby = true;
+                }
+                Ok(None) => {}
+                Err(e) => tracing::warn!("⚠️  {} leader election failed: {}", name, e),
+            }
+            tokio::time::sleep(retry).await;
+        }
+    }
+
+    /// Whether this session still holds the lock (false once the connection is lost)
+    pub async fn is_held(&mut self) -> bool {
+        let held = sqlx::query_scalar!(
+            r#"
+            SELECT EXISTS(
+                SELECT 1 FROM pg_locks
+                WHERE locktype = 'advisory'
+                  AND pid = pg_backend_pid()
+                  AND granted
+                  AND objsubid = 1
+                  AND ((classid::bigint << 32) | objid::bigint) = $1
+            ) AS "held!"
+            "#,
+            self.key
+        )
+        .fetch_one(&mut self.conn)
+        .await;
+
+        match held {
+            Ok(held) => held,
+            Err(e) => {
+                tracing::warn!("⚠️  Lost {} leadership: {}", self.name, e);
+                false
+            }
+        }
+    }
+
+    /// Give up leadership explicitly (e.g. on shutdown)
+    pub async fn release(self) {
+        let _ = self.conn.close().await;
+        tracing::info!("Released {} leadership", self.name);
+    }
+}
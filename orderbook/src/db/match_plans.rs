@@ -0,0 +1,127 @@
+// Match plans handed out by /api/match-intent (GET /api/admin/match-plans/:plan_id)
+// A trade records the fill but not why the matcher chose it. Each plan is kept with the
+// request it answered, a hash of the order book it was matched against and the deployment's
+// order priority; execute-fill checks the fills it is sent against the stored plan and
+// appends the trades it created, so an incident can be traced back to the plan behind it.
+// Plans that expire unexecuted explain no trade; the auto-cancel service deletes them.
+
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use sqlx::PgPool;
+use uuid::Uuid;
+
+use super::DbResult;
+use super::models::DbMatchPlan;
+use crate::blockchain::address::EthAddress;
+
+/// A plan to keep as it is returned by /api/match-intent
+#[derive(Debug, Clone)]
+pub struct NewMatchPlan {
+    pub token: EthAddress,
+    pub buyer: Option<EthAddress>,
+    /// Request parameters as JSON
+    pub request: String,
+    /// The MatchPlan as JSON
+    pub plan: String,
+    /// See api::matching::book_hash
+    pub book_hash: String,
+    pub order_priority: String,
+    pub expires_at: DateTime<Utc>,
+}
+
+/// Repository for the match plans handed out to buyers (audit and replay)
+#[async_trait]
+pub trait MatchPlanRepository: Send + Sync {
+    /// Keep a plan, returning its ID
+    async fn create(&self, plan: &NewMatchPlan) -> DbResult<Uuid>;
+
+    async fn get(&self, plan_id: Uuid) -> DbResult<Option<DbMatchPlan>>;
+
+    /// Append the trades an execute-fill of the plan created
+    async fn record_execution(&self, plan_id: Uuid, buyer: &EthAddress, trade_ids: &[String]) -> DbResult<()>;
+
+    /// Delete plans that expired before `before` without trades, returning how many
+    async fn prune_expired(&self, before: DateTime<Utc>) -> DbResult<u64>;
+}
+
+pub struct PostgresMatchPlanRepository {
+    pool: PgPool,
+}
+
+impl PostgresMatchPlanRepository {
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+}
+
+#[async_trait]
+impl MatchPlanRepository for PostgresMatchPlanRepository {
+    async fn create(&self, plan: &NewMatchPlan) -> DbResult<Uuid> {
+        let plan_id = Uuid::new_v4();
+        sqlx::query!(
+            r#"
+            INSERT INTO match_plans ("planId", "token", "buyer", "request", "plan", "bookHash", "orderPriority", "expiresAt")
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8)
+            "#,
+            plan_id,
+            plan.token.as_str(),
+            plan.buyer.as_ref().map(EthAddress::as_str),
+            plan.request,
+            plan.plan,
+            plan.book_hash,
+            plan.order_priority,
+            plan.expires_at
+        )
+        .execute(&self.pool)
+        .await?;
+
+        Ok(plan_id)
+    }
+
+    async fn get(&self, plan_id: Uuid) -> DbResult<Option<DbMatchPlan>> {
+        let plan = sqlx::query_as!(
+            DbMatchPlan,
+            r#"
+            SELECT "planId" as plan_id, "token" as "token: EthAddress", "buyer" as "buyer: EthAddress",
+                   "request", "plan", "bookHash" as book_hash, "orderPriority" as order_priority,
+                   "createdAt" as created_at, "expiresAt" as expires_at,
+                   "executedBy" as "executed_by: EthAddress", "tradeIds" as trade_ids, "executedAt" as executed_at
+            FROM match_plans
+            WHERE "planId" = $1
+            "#,
+            plan_id
+        )
+        .fetch_optional(&self.pool)
+        .await?;
+
+        Ok(plan)
+    }
+
+    async fn record_execution(&self, plan_id: Uuid, buyer: &EthAddress, trade_ids: &[String]) -> DbResult<()> {
+        sqlx::query!(
+            r#"
+            UPDATE match_plans
+            SET "executedBy" = $2, "tradeIds" = "tradeIds" || $3::TEXT[], "executedAt" = NOW()
+            WHERE "planId" = $1
+            "#,
+            plan_id,
+            buyer.as_str(),
+            trade_ids
+        )
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    async fn prune_expired(&self, before: DateTime<Utc>) -> DbResult<u64> {
+        let result = sqlx::query!(
+            r#"DELETE FROM match_plans WHERE "expiresAt" < $1 AND "executedAt" IS NULL"#,
+            before
+        )
+        .execute(&self.pool)
+        .await?;
+
+        Ok(result.rows_affected())
+    }
+}
@@ -1,18 +1,98 @@
+pub mod alipay_blocklist;
+pub mod anomalies;
+pub mod api_keys;
+pub mod blobs;
+pub mod config_events;
+pub mod confirmations;
+pub mod dead_letters;
+pub mod event_journal;
+pub mod executions;
+#[cfg(test)]
+pub mod fake;
+pub mod fees;
+pub mod funnel;
+pub mod inspection;
+pub mod leader;
+pub mod match_plans;
 pub mod models;
+pub mod notification_channels;
+pub mod order_feed;
+pub mod order_intents;
+pub mod order_staleness;
+pub mod orderbook_snapshots;
 pub mod orders;
+pub mod payment_windows;
+pub mod platform_status;
+pub mod pool;
+pub mod processed_events;
+pub mod proof_submissions;
+pub mod prover_transcripts;
+pub mod prover_usage;
+pub mod receipts;
+pub mod relayer_spend;
+pub mod reminders;
+pub mod screening;
+pub mod settlement_jobs;
+pub mod spending_limits;
+pub mod sync_state;
+pub mod token_status;
+pub mod trade_attestations;
+pub mod trade_drift;
+pub mod trade_messages;
+pub mod trade_payments;
+pub mod trade_support;
 pub mod trades;
+pub mod validations;
+pub mod webhook_deliveries;
 
-use sqlx::postgres::{PgPool, PgPoolOptions};
-use std::time::Duration;
+use sqlx::postgres::{PgConnectOptions, PgConnection, PgPool};
+use std::str::FromStr;
+use std::sync::Arc;
+use sqlx::Connection;
 use thiserror::Error;
 use chrono::{DateTime, Utc};
+use alipay_blocklist::AlipayBlocklistRepository;
+use anomalies::AnomalyRepository;
+use api_keys::ApiKeyRepository;
+use blobs::TradeBlobRepository;
+use config_events::ConfigEventRepository;
+use dead_letters::DeadLetterRepository;
+use executions::ExecutionRepository;
+use fees::FeeAccrualRepository;
+use funnel::FunnelRepository;
+use inspection::InspectionRepository;
+use match_plans::MatchPlanRepository;
+use notification_channels::NotificationChannelRepository;
+use order_feed::OrderFeedRepository;
+use order_intents::OrderIntentRepository;
+use order_staleness::OrderStalenessRepository;
+use orderbook_snapshots::OrderbookSnapshotRepository;
 use orders::OrderRepository;
+use payment_windows::PaymentWindowRepository;
+use platform_status::PlatformStatusRepository;
+use pool::PoolConfig;
+use proof_submissions::ProofSubmissionRepository;
+use prover_transcripts::ProverTranscriptRepository;
+use prover_usage::ProverUsageRepository;
+use receipts::ReceiptRepository;
+use relayer_spend::RelayerSpendRepository;
+use screening::ScreeningRepository;
+use settlement_jobs::SettlementJobRepository;
+use spending_limits::SpendingLimitRepository;
+use sync_state::SyncStateRepository;
+use token_status::TokenStatusRepository;
+use trade_attestations::TradeAttestationRepository;
+use trade_messages::TradeMessageRepository;
+use trade_payments::TradePaymentRepository;
+use trade_support::TradeSupportRepository;
 use trades::TradeRepository;
+use validations::ValidationAttemptRepository;
+use crate::blockchain::address::EthAddress;
 
 #[derive(Debug, Error)]
 pub enum DbError {
     #[error("Database error: {0}")]
-    SqlxError(#[from] sqlx::Error),
+    SqlxError(sqlx::Error),
     
     #[error("Migration error: {0}")]
     MigrationError(#[from] sqlx::migrate::MigrateError),
@@ -25,28 +105,127 @@ pub enum DbError {
     
     #[error("Invalid input: {0}")]
     InvalidInput(String),
+    
+    #[error("Payment nonce {nonce} already used by trade {existing_trade_id}")]
+    NonceCollision { nonce: String, existing_trade_id: String },
+    
+    /// A schema invariant (CHECK constraint or trigger, see migrations) rejected the write
+    #[error("Constraint {constraint} violated: {message}")]
+    ConstraintViolation { constraint: String, message: String },
+}
+
+/// Postgres SQLSTATE for check_violation
+const CHECK_VIOLATION: &str = "23514";
+
+impl From<sqlx::Error> for DbError {
+    fn from(err: sqlx::Error) -> Self {
+        if let sqlx::Error::Database(db_err) = &err {
+            if db_err.code().as_deref() == Some(CHECK_VIOLATION) {
+                return DbError::ConstraintViolation {
+                    constraint: db_err.constraint().unwrap_or("unknown").to_string(),
+                    message: db_err.message().to_string(),
+                };
+            }
+        }
+        DbError::SqlxError(err)
+    }
 }
 
 pub type DbResult<T> = Result<T, DbError>;
 
+/// Migrations embedded in the binary (applied by `migrate`, compared at startup)
+pub static MIGRATOR: sqlx::migrate::Migrator = sqlx::migrate!("./migrations");
+
+/// A row of sqlx's migration bookkeeping table
+#[derive(Debug, Clone)]
+pub struct AppliedMigration {
+    pub version: i64,
+    pub success: bool,
+    pub checksum: Vec<u8>,
+}
+
+/// Successfully applied migrations (0 before the bookkeeping table exists)
+async fn applied_migration_count(conn: &mut PgConnection) -> DbResult<i64> {
+    let exists = sqlx::query_scalar!(r#"SELECT to_regclass('_sqlx_migrations') IS NOT NULL AS "exists!""#)
+        .fetch_one(&mut *conn)
+        .await?;
+    if !exists {
+        return Ok(0);
+    }
+    let count = sqlx::query_scalar!(r#"SELECT COUNT(*) AS "count!" FROM _sqlx_migrations WHERE success"#)
+        .fetch_one(&mut *conn)
+        .await?;
+    Ok(count)
+}
+
+/// Repositories behind the API's order, trade, PDF and proof queries (and the sync
+/// state and validation history those handlers report)
+/// Postgres in production; handler tests swap in an in-memory fake (db::fake).
+#[derive(Clone)]
+pub struct Repositories {
+    pub orders: Arc<dyn OrderRepository>,
+    pub trades: Arc<dyn TradeRepository>,
+    pub token_status: Arc<dyn TokenStatusRepository>,
+    pub order_staleness: Arc<dyn OrderStalenessRepository>,
+    pub blobs: Arc<dyn TradeBlobRepository>,
+    pub validations: Arc<dyn ValidationAttemptRepository>,
+    pub sync_state: Arc<dyn SyncStateRepository>,
+    pub inspection: Arc<dyn InspectionRepository>,
+    pub match_plans: Arc<dyn MatchPlanRepository>,
+}
+
+impl Repositories {
+    pub fn postgres(pool: &PgPool) -> Self {
+        Self {
+            orders: Arc::new(orders::PostgresOrderRepository::new(pool.clone())),
+            trades: Arc::new(trades::PostgresTradeRepository::new(pool.clone())),
+            token_status: Arc::new(token_status::PostgresTokenStatusRepository::new(pool.clone())),
+            order_staleness: Arc::new(order_staleness::PostgresOrderStalenessRepository::new(pool.clone())),
+            blobs: Arc::new(blobs::PostgresTradeBlobRepository::new(pool.clone())),
+            validations: Arc::new(validations::PostgresValidationAttemptRepository::new(pool.clone())),
+            sync_state: Arc::new(sync_state::PostgresSyncStateRepository::new(pool.clone())),
+            inspection: Arc::new(inspection::PostgresInspectionRepository::new(pool.clone())),
+            match_plans: Arc::new(match_plans::PostgresMatchPlanRepository::new(pool.clone())),
+        }
+    }
+}
+
 /// Database connection manager for on-chain event tracking
 pub struct Database {
     pool: PgPool,
+    repos: Repositories,
 }
 
 impl Database {
-    /// Create a new database connection from URL
+    /// Create a new database connection from URL (default pool settings)
     pub async fn new(database_url: &str) -> DbResult<Self> {
-        let pool = PgPoolOptions::new()
-            .max_connections(10)
-            .min_connections(2)
-            .acquire_timeout(Duration::from_secs(30))
-            .idle_timeout(Duration::from_secs(600))
-            .max_lifetime(Duration::from_secs(1800))
-            .connect(database_url)
-            .await?;
+        Self::connect(database_url, &PoolConfig::default()).await
+    }
+
+    /// Create a new database connection from URL with the given pool settings
+    pub async fn connect(database_url: &str, config: &PoolConfig) -> DbResult<Self> {
+        let pool = config.connect(PgConnectOptions::from_str(database_url)?).await?;
+        Ok(Self::from_pool(pool))
+    }
+
+    /// Connect with `schema` as the search path, so every table (migrations included)
+    /// lives in that schema instead of public
+    pub async fn with_schema(database_url: &str, schema: &str) -> DbResult<Self> {
+        let options = PgConnectOptions::from_str(database_url)?.options([("search_path", schema)]);
+        let pool = PoolConfig::default().connect(options).await?;
+        Ok(Self::from_pool(pool))
+    }
+
+    /// Use an open pool, with the Postgres repositories
+    pub fn from_pool(pool: PgPool) -> Self {
+        let repos = Repositories::postgres(&pool);
+        Self { pool, repos }
+    }
 
-        Ok(Self { pool })
+    /// Use `repos` for the API's order, trade, PDF and proof queries; everything else
+    /// still goes to `pool` (which may be lazy, i.e. never connected)
+    pub fn with_repositories(pool: PgPool, repos: Repositories) -> Self {
+        Self { pool, repos }
     }
 
     /// Get the connection pool
@@ -54,18 +233,56 @@ impl Database {
         &self.pool
     }
 
-    /// Run database migrations
-    pub async fn migrate(&self) -> DbResult<()> {
-        sqlx::migrate!("./migrations")
-            .run(&self.pool)
+    /// Run pending migrations, one replica at a time
+    /// Replicas starting together queue on an advisory lock held by a dedicated session
+    /// (released by Postgres if this process dies mid-upgrade); the first applies the
+    /// migrations and the rest find nothing left to do. Returns the number applied.
+    pub async fn migrate(&self) -> DbResult<usize> {
+        let mut conn = PgConnection::connect_with(&self.pool.connect_options()).await?;
+
+        let locked = sqlx::query_scalar!(r#"SELECT pg_try_advisory_lock($1) AS "locked!""#, leader::LOCK_MIGRATIONS)
+            .fetch_one(&mut conn)
             .await?;
-        Ok(())
+        if !locked {
+            tracing::info!("⏳ Another instance is migrating the database - waiting");
+            sqlx::query("SELECT pg_advisory_lock($1)")
+                .bind(leader::LOCK_MIGRATIONS)
+                .execute(&mut conn)
+                .await?;
+        }
+
+        let result = async {
+            let before = applied_migration_count(&mut conn).await?;
+            MIGRATOR.run(&mut conn).await?;
+            let after = applied_migration_count(&mut conn).await?;
+            Ok::<_, DbError>((after - before).max(0) as usize)
+        }
+        .await;
+
+        // Closing the session releases the lock
+        let _ = conn.close().await;
+        let applied = result?;
+        if applied > 0 {
+            tracing::info!("🗃️  Applied {} migrations", applied);
+        }
+        Ok(applied)
+    }
+
+    /// Migrations recorded in the database, oldest first
+    pub async fn applied_migrations(&self) -> DbResult<Vec<AppliedMigration>> {
+        let migrations = sqlx::query_as!(
+            AppliedMigration,
+            "SELECT version, success, checksum FROM _sqlx_migrations ORDER BY version"
+        )
+        .fetch_all(&self.pool)
+        .await?;
+        Ok(migrations)
     }
 
     /// Health check - verify database is accessible
     pub async fn health_check(&self) -> DbResult<()> {
-        sqlx::query("SELECT 1")
-            .execute(&self.pool)
+        sqlx::query!("SELECT 1 as one")
+            .fetch_one(&self.pool)
             .await?;
         Ok(())
     }
@@ -75,45 +292,699 @@ impl Database {
         self.pool.close().await;
     }
     
-    /// Get all active orders (convenience method for API)
-    pub async fn get_active_orders(&self, limit: Option<i64>) -> DbResult<Vec<models::DbOrder>> {
-        let repo = orders::PostgresOrderRepository::new(self.pool.clone());
-        repo.get_active_orders(limit).await
+    /// Get all active orders of tradable tokens (convenience method for API)
+    pub async fn get_active_orders(&self, limit: Option<i64>, policy: &token_status::TokenPolicy) -> DbResult<Vec<models::DbOrder>> {
+        self.repos.orders.get_active_orders(limit, policy).await
     }
     
-    /// Get active orders filtered by token (convenience method for API)
-    pub async fn get_active_orders_by_token(&self, token_address: &str, limit: Option<i64>) -> DbResult<Vec<models::DbOrder>> {
-        let repo = orders::PostgresOrderRepository::new(self.pool.clone());
-        repo.get_active_orders_by_token(token_address, limit).await
+    /// Get active orders filtered by token, in matching priority (convenience method for API)
+    pub async fn get_active_orders_by_token(&self, token: &EthAddress, limit: Option<i64>, priority: orders::OrderPriority) -> DbResult<Vec<models::DbOrder>> {
+        self.repos.orders.get_active_orders_by_token(token, limit, priority).await
     }
     
     /// Get single order by ID (convenience method for API)
     pub async fn get_order(&self, order_id: &str) -> DbResult<models::DbOrder> {
-        let repo = orders::PostgresOrderRepository::new(self.pool.clone());
-        repo.get(order_id).await
+        self.repos.orders.get(order_id).await
     }
     
     /// Get orders by seller (convenience method for API)
-    pub async fn get_orders_by_seller(&self, seller: &str) -> DbResult<Vec<models::DbOrder>> {
-        let repo = orders::PostgresOrderRepository::new(self.pool.clone());
-        repo.get_by_seller(seller).await
+    pub async fn get_orders_by_seller(&self, seller: &EthAddress) -> DbResult<Vec<models::DbOrder>> {
+        self.repos.orders.get_by_seller(seller).await
+    }
+    
+    /// Settled and expired trade counts of sellers (convenience method for API)
+    pub async fn get_seller_reputations(&self, sellers: &[EthAddress]) -> DbResult<Vec<models::DbSellerReputation>> {
+        self.repos.orders.seller_reputations(sellers).await
+    }
+    
+    /// Set an order's seller fill limits (convenience method for API)
+    pub async fn set_order_fill_limits(&self, order_id: &str, min_fill: Option<rust_decimal::Decimal>, lot_size: Option<rust_decimal::Decimal>, signed_at: i64) -> DbResult<bool> {
+        self.repos.orders.set_fill_limits(order_id, min_fill, lot_size, signed_at).await
     }
     
     /// Get single trade by ID (convenience method for API)
     pub async fn get_trade(&self, trade_id: &str) -> DbResult<models::DbTrade> {
-        let repo = trades::PostgresTradeRepository::new(self.pool.clone());
-        repo.get(trade_id).await
+        self.repos.trades.get(trade_id).await
+    }
+    
+    /// Get trade by payment nonce (convenience method for API)
+    pub async fn get_trade_by_nonce(&self, payment_nonce: &str) -> DbResult<Option<models::DbTrade>> {
+        self.repos.trades.get_by_nonce(payment_nonce).await
     }
     
     /// Save PDF for a trade (convenience method for API)
     pub async fn save_trade_pdf(&self, trade_id: &str, pdf_data: &[u8], filename: &str) -> DbResult<DateTime<Utc>> {
-        let repo = trades::PostgresTradeRepository::new(self.pool.clone());
-        repo.save_pdf(trade_id, pdf_data, filename).await
+        self.repos.trades.save_pdf(trade_id, pdf_data, filename).await
+    }
+    
+    /// Save proof for a trade unless its PDF was replaced (convenience method for API)
+    pub async fn save_trade_proof(&self, trade_id: &str, pdf_uploaded_at: Option<DateTime<Utc>>, user_public_values: &[u8], accumulator: &[u8], proof_data: &[u8], axiom_proof_id: &str, proof_json: &str, proof_format: &str) -> DbResult<bool> {
+        self.repos.trades.save_proof(trade_id, pdf_uploaded_at, user_public_values, accumulator, proof_data, axiom_proof_id, proof_json, proof_format).await
+    }
+    
+    /// Get the most recently advanced event sync state (None if the listener never ran)
+    pub async fn get_sync_state(&self) -> DbResult<Option<models::DbSyncState>> {
+        self.repos.sync_state.latest().await
+    }
+    
+    /// One page of orders for operators (convenience method for API)
+    pub async fn page_orders(&self, page: &inspection::OrderPage) -> DbResult<Vec<models::DbOrder>> {
+        self.repos.inspection.page_orders(page).await
+    }
+    
+    /// One page of trades for operators, without blobs (convenience method for API)
+    pub async fn page_trades(&self, page: &inspection::TradePage) -> DbResult<Vec<models::DbTrade>> {
+        self.repos.inspection.page_trades(page).await
+    }
+    
+    /// Keep a match plan handed out to a buyer (convenience method for API)
+    pub async fn create_match_plan(&self, plan: &match_plans::NewMatchPlan) -> DbResult<uuid::Uuid> {
+        self.repos.match_plans.create(plan).await
+    }
+    
+    /// Get a match plan by ID (convenience method for API)
+    pub async fn get_match_plan(&self, plan_id: uuid::Uuid) -> DbResult<Option<models::DbMatchPlan>> {
+        self.repos.match_plans.get(plan_id).await
+    }
+    
+    /// Record the trades executed from a match plan (convenience method for API)
+    pub async fn record_match_plan_execution(&self, plan_id: uuid::Uuid, buyer: &EthAddress, trade_ids: &[String]) -> DbResult<()> {
+        self.repos.match_plans.record_execution(plan_id, buyer, trade_ids).await
+    }
+    
+    /// Delete match plans that expired before `before` without being executed
+    pub async fn prune_expired_match_plans(&self, before: DateTime<Utc>) -> DbResult<u64> {
+        self.repos.match_plans.prune_expired(before).await
+    }
+    
+    /// Get recent contract config-change events (convenience method for API)
+    pub async fn get_config_events(&self, limit: i64) -> DbResult<Vec<models::DbConfigEvent>> {
+        let repo = config_events::PostgresConfigEventRepository::new(self.pool.clone());
+        repo.list_recent(limit).await
+    }
+    
+    /// Get all trades for a buyer, newest first (convenience method for API)
+    pub async fn get_trades_by_buyer(&self, buyer: &EthAddress) -> DbResult<Vec<models::DbTrade>> {
+        self.repos.trades.get_by_buyer(buyer).await
+    }
+    
+    /// Get cached Axiom execution result for an input hash (convenience method for API)
+    pub async fn get_axiom_execution(&self, input_hash: &str, program_id: &str) -> DbResult<Option<models::DbAxiomExecution>> {
+        let repo = executions::PostgresExecutionRepository::new(self.pool.clone());
+        repo.get(input_hash, program_id).await
+    }
+    
+    /// Save Axiom execution result (convenience method for API)
+    pub async fn save_axiom_execution(&self, input_hash: &str, program_id: &str, trade_id: &str, actual_hash: &str) -> DbResult<()> {
+        let repo = executions::PostgresExecutionRepository::new(self.pool.clone());
+        repo.create(input_hash, program_id, trade_id, actual_hash).await
+    }
+    
+    /// Record a PDF validation attempt (convenience method for API)
+    pub async fn save_validation_attempt(&self, attempt: &validations::NewValidationAttempt<'_>) -> DbResult<()> {
+        self.repos.validations.create(attempt).await
+    }
+    
+    /// Get validation history for a trade, newest first (convenience method for API)
+    pub async fn get_validation_attempts(&self, trade_id: &str) -> DbResult<Vec<models::DbValidationAttempt>> {
+        self.repos.validations.list_for_trade(trade_id).await
+    }
+    
+    /// Get signed settlement receipt for a trade (convenience method for API)
+    pub async fn get_trade_receipt(&self, trade_id: &str) -> DbResult<Option<models::DbTradeReceipt>> {
+        let repo = receipts::PostgresReceiptRepository::new(self.pool.clone());
+        repo.get(trade_id).await
+    }
+    
+    /// Save signed settlement receipt (convenience method for API)
+    pub async fn save_trade_receipt(&self, receipt: &models::DbTradeReceipt) -> DbResult<()> {
+        let repo = receipts::PostgresReceiptRepository::new(self.pool.clone());
+        repo.create(receipt).await
+    }
+    
+    /// Record gas spent by a relayer transaction (convenience method for API)
+    pub async fn record_relayer_spend(&self, spend: &relayer_spend::NewRelayerSpend) -> DbResult<()> {
+        let repo = relayer_spend::PostgresRelayerSpendRepository::new(self.pool.clone());
+        repo.create(spend).await
+    }
+    
+    /// Relayer gas spend per action since `since` (convenience method for API)
+    pub async fn get_relayer_costs_by_action(&self, since: DateTime<Utc>) -> DbResult<Vec<models::DbActionRelayerCost>> {
+        let repo = relayer_spend::PostgresRelayerSpendRepository::new(self.pool.clone());
+        repo.totals_by_action(since).await
+    }
+    
+    /// Relayer gas spend per trade since `since`, most expensive first (convenience method for API)
+    pub async fn get_relayer_costs_by_trade(&self, since: DateTime<Utc>, limit: i64) -> DbResult<Vec<models::DbTradeRelayerCost>> {
+        let repo = relayer_spend::PostgresRelayerSpendRepository::new(self.pool.clone());
+        repo.totals_by_trade(since, limit).await
+    }
+    
+    /// Number of trades with relayer spend since `since` (convenience method for API)
+    pub async fn count_relayer_cost_trades(&self, since: DateTime<Utc>) -> DbResult<i64> {
+        let repo = relayer_spend::PostgresRelayerSpendRepository::new(self.pool.clone());
+        repo.trade_count(since).await
+    }
+    
+    /// Operator fee totals per payer and token in [from, to) (convenience method for API)
+    pub async fn get_fee_totals(&self, from: DateTime<Utc>, to: DateTime<Utc>, limit: i64) -> DbResult<Vec<models::DbFeeTotal>> {
+        let repo = fees::PostgresFeeAccrualRepository::new(self.pool.clone());
+        repo.totals_by_payer(from, to, limit).await
+    }
+    
+    /// Fee accruals of one payer in [from, to), newest first (convenience method for API)
+    pub async fn get_fee_accruals(&self, payer: &EthAddress, from: DateTime<Utc>, to: DateTime<Utc>) -> DbResult<Vec<models::DbFeeAccrual>> {
+        let repo = fees::PostgresFeeAccrualRepository::new(self.pool.clone());
+        repo.list_for_payer(payer, from, to).await
+    }
+    
+    /// Get a settlement job (convenience method for API)
+    pub async fn get_settlement_job(&self, job_id: uuid::Uuid) -> DbResult<Option<models::DbSettlementJob>> {
+        let repo = settlement_jobs::PostgresSettlementJobRepository::new(self.pool.clone());
+        repo.get(job_id).await
+    }
+    
+    /// Most recent settlement job of a trade (convenience method for API)
+    pub async fn latest_settlement_job(&self, trade_id: &str) -> DbResult<Option<models::DbSettlementJob>> {
+        let repo = settlement_jobs::PostgresSettlementJobRepository::new(self.pool.clone());
+        repo.latest_for_trade(trade_id).await
+    }
+    
+    /// Start a settlement job leased to `owner`, or get the running one (convenience method for API)
+    pub async fn create_or_get_settlement_job(&self, trade_id: &str, owner: uuid::Uuid) -> DbResult<(models::DbSettlementJob, bool)> {
+        let repo = settlement_jobs::PostgresSettlementJobRepository::new(self.pool.clone());
+        repo.create_or_get_running(trade_id, owner).await
+    }
+    
+    /// Restart a failed settlement job leased to `owner` (convenience method for API)
+    pub async fn resume_settlement_job(&self, job_id: uuid::Uuid, owner: uuid::Uuid) -> DbResult<bool> {
+        let repo = settlement_jobs::PostgresSettlementJobRepository::new(self.pool.clone());
+        repo.resume(job_id, owner).await
+    }
+    
+    /// Lease running settlement jobs whose lease lapsed to `owner` (convenience method for API)
+    pub async fn claim_stale_settlement_jobs(&self, owner: uuid::Uuid) -> DbResult<Vec<models::DbSettlementJob>> {
+        let repo = settlement_jobs::PostgresSettlementJobRepository::new(self.pool.clone());
+        repo.claim_stale(owner).await
+    }
+    
+    /// Extend `owner`'s lease on a running settlement job (convenience method for API)
+    pub async fn renew_settlement_job_lease(&self, job_id: uuid::Uuid, owner: uuid::Uuid) -> DbResult<bool> {
+        let repo = settlement_jobs::PostgresSettlementJobRepository::new(self.pool.clone());
+        repo.renew_lease(job_id, owner).await
+    }
+    
+    /// Persist a settlement job's next step (convenience method for API)
+    pub async fn set_settlement_job_step(&self, job_id: uuid::Uuid, step: &str) -> DbResult<()> {
+        let repo = settlement_jobs::PostgresSettlementJobRepository::new(self.pool.clone());
+        repo.set_step(job_id, step).await
+    }
+    
+    /// Record the proof a settlement job submits (convenience method for API)
+    pub async fn set_settlement_job_proof_id(&self, job_id: uuid::Uuid, proof_id: &str) -> DbResult<()> {
+        let repo = settlement_jobs::PostgresSettlementJobRepository::new(self.pool.clone());
+        repo.set_proof_id(job_id, proof_id).await
+    }
+    
+    /// Mark a settlement job completed (convenience method for API)
+    pub async fn complete_settlement_job(&self, job_id: uuid::Uuid, tx_hash: &str) -> DbResult<()> {
+        let repo = settlement_jobs::PostgresSettlementJobRepository::new(self.pool.clone());
+        repo.complete(job_id, tx_hash).await
+    }
+    
+    /// Mark a settlement job failed (convenience method for API)
+    pub async fn fail_settlement_job(&self, job_id: uuid::Uuid, error: &str) -> DbResult<()> {
+        let repo = settlement_jobs::PostgresSettlementJobRepository::new(self.pool.clone());
+        repo.fail(job_id, error).await
+    }
+    
+    /// Token allowlist / denylist entries (convenience method for API)
+    pub async fn list_token_statuses(&self) -> DbResult<Vec<models::DbTokenStatus>> {
+        self.repos.token_status.list().await
+    }
+    
+    /// Current token policy for matching (convenience method for API)
+    pub async fn get_token_policy(&self) -> DbResult<token_status::TokenPolicy> {
+        self.repos.token_status.policy().await
+    }
+    
+    /// Put a token on the allowlist or denylist (convenience method for API)
+    pub async fn set_token_status(&self, token: &EthAddress, status: &str, reason: Option<&str>, updated_by: Option<&str>) -> DbResult<models::DbTokenStatus> {
+        self.repos.token_status.set(token, status, reason, updated_by).await
+    }
+    
+    /// Take a token off both lists (convenience method for API)
+    pub async fn remove_token_status(&self, token: &EthAddress) -> DbResult<bool> {
+        self.repos.token_status.remove(token).await
+    }
+    
+    /// Blocklisted seller Alipay accounts (convenience method for API)
+    pub async fn list_blocked_alipay_ids(&self) -> DbResult<Vec<models::DbBlockedAlipayId>> {
+        let repo = alipay_blocklist::PostgresAlipayBlocklistRepository::new(self.pool.clone());
+        repo.list().await
+    }
+    
+    /// Whether a seller Alipay account is blocklisted (convenience method for API)
+    pub async fn is_alipay_id_blocked(&self, alipay_id: &str) -> DbResult<bool> {
+        let repo = alipay_blocklist::PostgresAlipayBlocklistRepository::new(self.pool.clone());
+        repo.is_blocked(alipay_id).await
+    }
+    
+    /// Blocklist a seller Alipay account (convenience method for API)
+    pub async fn block_alipay_id(&self, alipay_id: &str, reason: Option<&str>, actor: Option<&str>) -> DbResult<models::DbBlockedAlipayId> {
+        let repo = alipay_blocklist::PostgresAlipayBlocklistRepository::new(self.pool.clone());
+        repo.block(alipay_id, reason, actor).await
+    }
+    
+    /// Take a seller Alipay account off the blocklist (convenience method for API)
+    pub async fn unblock_alipay_id(&self, alipay_id: &str, reason: Option<&str>, actor: Option<&str>) -> DbResult<bool> {
+        let repo = alipay_blocklist::PostgresAlipayBlocklistRepository::new(self.pool.clone());
+        repo.unblock(alipay_id, reason, actor).await
+    }
+    
+    /// Latest Alipay blocklist changes (convenience method for API)
+    pub async fn get_alipay_blocklist_audit(&self, limit: i64) -> DbResult<Vec<models::DbAlipayBlocklistChange>> {
+        let repo = alipay_blocklist::PostgresAlipayBlocklistRepository::new(self.pool.clone());
+        repo.audit(limit).await
+    }
+    
+    /// Payment window tiers (convenience method for API)
+    pub async fn list_payment_window_tiers(&self) -> DbResult<Vec<models::DbPaymentWindowTier>> {
+        let repo = payment_windows::PostgresPaymentWindowRepository::new(self.pool.clone());
+        repo.list().await
+    }
+    
+    /// Current payment window policy for fills (convenience method for API)
+    pub async fn get_payment_window_policy(&self) -> DbResult<payment_windows::PaymentWindowPolicy> {
+        let repo = payment_windows::PostgresPaymentWindowRepository::new(self.pool.clone());
+        repo.policy().await
+    }
+    
+    /// Set a payment window tier (convenience method for API)
+    pub async fn set_payment_window_tier(&self, token: Option<&EthAddress>, min_value_cny: i64, window_secs: i64, updated_by: Option<&str>) -> DbResult<models::DbPaymentWindowTier> {
+        let repo = payment_windows::PostgresPaymentWindowRepository::new(self.pool.clone());
+        repo.set(token, min_value_cny, window_secs, updated_by).await
+    }
+    
+    /// Remove a payment window tier (convenience method for API)
+    pub async fn remove_payment_window_tier(&self, token: Option<&EthAddress>, min_value_cny: i64) -> DbResult<bool> {
+        let repo = payment_windows::PostgresPaymentWindowRepository::new(self.pool.clone());
+        repo.remove(token, min_value_cny).await
+    }
+    
+    /// Addresses flagged by screening (convenience method for API)
+    pub async fn list_flagged_addresses(&self) -> DbResult<Vec<models::DbScreeningVerdict>> {
+        let repo = screening::PostgresScreeningRepository::new(self.pool.clone());
+        repo.flagged().await
+    }
+    
+    /// Latest screening refusals, newest first (convenience method for API)
+    pub async fn get_screening_refusals(&self, limit: i64) -> DbResult<Vec<models::DbScreeningRefusal>> {
+        let repo = screening::PostgresScreeningRepository::new(self.pool.clone());
+        repo.refusals(limit).await
+    }
+    
+    /// Drop an address's cached screening verdict (convenience method for API)
+    pub async fn clear_screening_verdict(&self, address: &EthAddress) -> DbResult<bool> {
+        let repo = screening::PostgresScreeningRepository::new(self.pool.clone());
+        repo.clear(address).await
+    }
+    
+    /// Spending limit tiers (convenience method for API)
+    pub async fn list_spending_limit_tiers(&self) -> DbResult<Vec<models::DbSpendingLimitTier>> {
+        let repo = spending_limits::PostgresSpendingLimitRepository::new(self.pool.clone());
+        repo.list_tiers().await
+    }
+    
+    /// Set the caps of a spending limit tier (convenience method for API)
+    pub async fn set_spending_limit_tier(&self, tier: i32, daily_limit_cny: Option<i64>, monthly_limit_cny: Option<i64>, updated_by: Option<&str>) -> DbResult<models::DbSpendingLimitTier> {
+        let repo = spending_limits::PostgresSpendingLimitRepository::new(self.pool.clone());
+        repo.set_tier(tier, daily_limit_cny, monthly_limit_cny, updated_by).await
+    }
+    
+    /// Remove a spending limit tier (convenience method for API)
+    pub async fn remove_spending_limit_tier(&self, tier: i32) -> DbResult<bool> {
+        let repo = spending_limits::PostgresSpendingLimitRepository::new(self.pool.clone());
+        repo.remove_tier(tier).await
+    }
+    
+    /// Put a buyer in a spending limit tier, 0 being the default (convenience method for API)
+    pub async fn set_buyer_tier(&self, buyer: &EthAddress, tier: i32, source: &str, reference: Option<&str>, updated_by: Option<&str>) -> DbResult<()> {
+        let repo = spending_limits::PostgresSpendingLimitRepository::new(self.pool.clone());
+        repo.set_buyer_tier(buyer, tier, source, reference, updated_by).await
+    }
+    
+    /// A buyer's tier assignment, None for tier 0 (convenience method for API)
+    pub async fn get_buyer_tier(&self, buyer: &EthAddress) -> DbResult<Option<models::DbBuyerTier>> {
+        let repo = spending_limits::PostgresSpendingLimitRepository::new(self.pool.clone());
+        repo.buyer_tier(buyer).await
+    }
+    
+    /// Buyers above tier 0 (convenience method for API)
+    pub async fn list_buyer_tiers(&self) -> DbResult<Vec<models::DbBuyerTier>> {
+        let repo = spending_limits::PostgresSpendingLimitRepository::new(self.pool.clone());
+        repo.list_buyer_tiers().await
+    }
+    
+    /// CNY value of a buyer's trades over the last 24 hours and 30 days (convenience method for API)
+    pub async fn get_buyer_volume(&self, buyer: &EthAddress) -> DbResult<models::DbBuyerVolume> {
+        let repo = spending_limits::PostgresSpendingLimitRepository::new(self.pool.clone());
+        repo.buyer_volume(buyer).await
+    }
+    
+    /// Post a buyer/seller message on a trade (convenience method for API)
+    pub async fn create_trade_message(&self, message: &trade_messages::NewTradeMessage) -> DbResult<models::DbTradeMessage> {
+        let repo = trade_messages::PostgresTradeMessageRepository::new(self.pool.clone());
+        repo.create(message).await
+    }
+    
+    /// A trade's messages after `after_id`, oldest first (convenience method for API)
+    pub async fn get_trade_messages(&self, trade_id: &str, after_id: i64, limit: i64) -> DbResult<Vec<models::DbTradeMessage>> {
+        let repo = trade_messages::PostgresTradeMessageRepository::new(self.pool.clone());
+        repo.list(trade_id, after_id, limit).await
+    }
+    
+    /// Number of messages on a trade (convenience method for API)
+    pub async fn count_trade_messages(&self, trade_id: &str) -> DbResult<i64> {
+        let repo = trade_messages::PostgresTradeMessageRepository::new(self.pool.clone());
+        repo.count(trade_id).await
+    }
+    
+    /// Attachment of a trade message (convenience method for API)
+    pub async fn get_message_attachment(&self, trade_id: &str, message_id: i64) -> DbResult<Option<models::DbMessageAttachment>> {
+        let repo = trade_messages::PostgresTradeMessageRepository::new(self.pool.clone());
+        repo.attachment(trade_id, message_id).await
+    }
+    
+    /// Record a payment the buyer declared for a trade (convenience method for API)
+    pub async fn declare_trade_payment(&self, payment: &trade_payments::NewTradePayment) -> DbResult<models::DbTradePayment> {
+        let repo = trade_payments::PostgresTradePaymentRepository::new(self.pool.clone());
+        repo.create(payment).await
+    }
+    
+    /// A trade's declared payments, oldest first (convenience method for API)
+    pub async fn get_trade_payments(&self, trade_id: &str) -> DbResult<Vec<models::DbTradePayment>> {
+        let repo = trade_payments::PostgresTradePaymentRepository::new(self.pool.clone());
+        repo.list(trade_id).await
+    }
+    
+    /// EAS attestation of a settled trade, if queued (convenience method for API)
+    pub async fn get_trade_attestation(&self, trade_id: &str) -> DbResult<Option<models::DbTradeAttestation>> {
+        let repo = trade_attestations::PostgresTradeAttestationRepository::new(self.pool.clone());
+        repo.get(trade_id).await
+    }
+    
+    /// Stale-order thresholds (convenience method for API)
+    pub async fn get_order_staleness_settings(&self) -> DbResult<models::DbStalenessSettings> {
+        self.repos.order_staleness.settings().await
+    }
+    
+    /// Set the stale-order thresholds (convenience method for API)
+    pub async fn set_order_staleness_settings(&self, stale_after_days: i32, notify_every_days: i32, updated_by: Option<&str>) -> DbResult<models::DbStalenessSettings> {
+        self.repos.order_staleness.set_settings(stale_after_days, notify_every_days, updated_by).await
+    }
+    
+    /// Active orders idle since before `stale_before`, optionally of one token (convenience method for API)
+    pub async fn get_stale_orders(&self, token: Option<&EthAddress>, stale_before: DateTime<Utc>, limit: Option<i64>) -> DbResult<Vec<models::DbStaleOrder>> {
+        self.repos.order_staleness.list_stale(token, stale_before, None, limit).await
+    }
+    
+    /// Record the seller's refresh of an order (convenience method for API)
+    pub async fn refresh_order(&self, order_id: &str, signed_at: i64) -> DbResult<bool> {
+        self.repos.order_staleness.refresh(order_id, signed_at).await
+    }
+    
+    /// Dead-lettered contract events (convenience method for API)
+    pub async fn list_dead_letters(&self, status: Option<&str>, limit: i64) -> DbResult<Vec<models::DbDeadLetter>> {
+        let repo = dead_letters::PostgresDeadLetterRepository::new(self.pool.clone());
+        repo.list(status, limit).await
+    }
+    
+    /// One dead-lettered event (convenience method for API)
+    pub async fn get_dead_letter(&self, id: i64) -> DbResult<Option<models::DbDeadLetter>> {
+        let repo = dead_letters::PostgresDeadLetterRepository::new(self.pool.clone());
+        repo.get(id).await
+    }
+    
+    /// Queue a dead-lettered event for replay (convenience method for API)
+    pub async fn queue_dead_letter(&self, id: i64, resolved_by: Option<&str>) -> DbResult<bool> {
+        let repo = dead_letters::PostgresDeadLetterRepository::new(self.pool.clone());
+        repo.queue(id, resolved_by).await
+    }
+    
+    /// Give up on a dead-lettered event (convenience method for API)
+    pub async fn discard_dead_letter(&self, id: i64, resolved_by: Option<&str>) -> DbResult<bool> {
+        let repo = dead_letters::PostgresDeadLetterRepository::new(self.pool.clone());
+        repo.discard(id, resolved_by).await
+    }
+    
+    /// Active liquidity per tradable token (convenience method for API)
+    pub async fn get_token_liquidity(&self, policy: &token_status::TokenPolicy) -> DbResult<Vec<models::DbTokenLiquidity>> {
+        let repo = platform_status::PostgresPlatformStatusRepository::new(self.pool.clone());
+        repo.liquidity(policy).await
+    }
+    
+    /// Per-token book and settlement aggregates (convenience method for API)
+    pub async fn get_orderbook_summary(&self, policy: &token_status::TokenPolicy, settled_since: DateTime<Utc>) -> DbResult<Vec<models::DbTokenSummary>> {
+        let repo = platform_status::PostgresPlatformStatusRepository::new(self.pool.clone());
+        repo.summary(policy, settled_since).await
+    }
+    
+    /// When the latest settlement became final (convenience method for API)
+    pub async fn get_last_settlement_at(&self) -> DbResult<Option<DateTime<Utc>>> {
+        let repo = platform_status::PostgresPlatformStatusRepository::new(self.pool.clone());
+        repo.last_settlement_at().await
+    }
+    
+    /// Proof count and mean upload-to-proof latency since `since` (convenience method for API)
+    pub async fn get_proof_latency(&self, since: DateTime<Utc>) -> DbResult<models::DbProofLatency> {
+        let repo = platform_status::PostgresPlatformStatusRepository::new(self.pool.clone());
+        repo.proof_latency(since).await
+    }
+    
+    /// Live API key by secret hash (convenience method for API)
+    pub async fn find_api_key(&self, key_hash: &str) -> DbResult<Option<models::DbApiKey>> {
+        let repo = api_keys::PostgresApiKeyRepository::new(self.pool.clone());
+        repo.find_active_by_hash(key_hash).await
+    }
+    
+    /// Record that an API key was used (convenience method for API)
+    pub async fn touch_api_key(&self, key_id: uuid::Uuid) -> DbResult<()> {
+        let repo = api_keys::PostgresApiKeyRepository::new(self.pool.clone());
+        repo.touch(key_id).await
+    }
+    
+    /// Issue an API key (convenience method for API)
+    pub async fn create_api_key(&self, key: &api_keys::NewApiKey) -> DbResult<models::DbApiKey> {
+        let repo = api_keys::PostgresApiKeyRepository::new(self.pool.clone());
+        repo.create(key).await
+    }
+    
+    /// All API keys, newest first (convenience method for API)
+    pub async fn list_api_keys(&self) -> DbResult<Vec<models::DbApiKey>> {
+        let repo = api_keys::PostgresApiKeyRepository::new(self.pool.clone());
+        repo.list().await
+    }
+    
+    /// Revoke an API key (convenience method for API)
+    pub async fn revoke_api_key(&self, key_id: uuid::Uuid) -> DbResult<bool> {
+        let repo = api_keys::PostgresApiKeyRepository::new(self.pool.clone());
+        repo.revoke(key_id).await
+    }
+    
+    /// Active orders with the feed sequence they're current as of (convenience method for API)
+    pub async fn get_order_feed_snapshot(&self) -> DbResult<(i64, Vec<models::DbOrder>)> {
+        let repo = order_feed::PostgresOrderFeedRepository::new(self.pool.clone());
+        repo.snapshot().await
+    }
+    
+    /// Latest order feed sequence (convenience method for API)
+    pub async fn get_order_feed_head(&self) -> DbResult<i64> {
+        let repo = order_feed::PostgresOrderFeedRepository::new(self.pool.clone());
+        repo.head().await
+    }
+    
+    /// Order changes after a feed sequence (convenience method for API)
+    pub async fn get_order_feed_since(&self, seq: i64, limit: i64) -> DbResult<Vec<models::DbOrderFeedEntry>> {
+        let repo = order_feed::PostgresOrderFeedRepository::new(self.pool.clone());
+        repo.since(seq, limit).await
+    }
+    
+    /// Record the current book per token into a snapshot bucket (convenience method for API)
+    pub async fn record_orderbook_snapshot(&self, bucket: DateTime<Utc>) -> DbResult<u64> {
+        let repo = orderbook_snapshots::PostgresOrderbookSnapshotRepository::new(self.pool.clone());
+        repo.record(bucket).await
+    }
+    
+    /// Order book snapshots in [since, until) (convenience method for API)
+    pub async fn get_orderbook_history(&self, token: Option<&EthAddress>, since: DateTime<Utc>, until: DateTime<Utc>, limit: i64) -> DbResult<Vec<models::DbOrderbookSnapshot>> {
+        let repo = orderbook_snapshots::PostgresOrderbookSnapshotRepository::new(self.pool.clone());
+        repo.history(token, since, until, limit).await
+    }
+    
+    /// Lifecycle funnel of the orders created in [from, to) (convenience method for API)
+    pub async fn get_lifecycle_funnel(&self, token: Option<&EthAddress>, from: DateTime<Utc>, to: DateTime<Utc>) -> DbResult<models::DbLifecycleFunnel> {
+        let repo = funnel::PostgresFunnelRepository::new(self.pool.clone());
+        repo.lifecycle(token, from.timestamp(), to.timestamp()).await
+    }
+    
+    /// Support metadata for a trade (convenience method for API)
+    pub async fn get_trade_support(&self, trade_id: &str) -> DbResult<Option<models::DbTradeSupport>> {
+        let repo = trade_support::PostgresTradeSupportRepository::new(self.pool.clone());
+        repo.get(trade_id).await
+    }
+    
+    /// Replace a trade's support contact and tags (convenience method for API)
+    pub async fn set_trade_support(&self, trade_id: &str, buyer_contact: Option<&str>, tags: &[String], updated_by: Option<&str>) -> DbResult<models::DbTradeSupport> {
+        let repo = trade_support::PostgresTradeSupportRepository::new(self.pool.clone());
+        repo.set(trade_id, buyer_contact, tags, updated_by).await
+    }
+    
+    /// Append a support note to a trade (convenience method for API)
+    pub async fn add_trade_note(&self, trade_id: &str, note: &str, author: Option<&str>) -> DbResult<models::DbTradeNote> {
+        let repo = trade_support::PostgresTradeSupportRepository::new(self.pool.clone());
+        repo.add_note(trade_id, note, author).await
+    }
+    
+    /// A trade's support notes, oldest first (convenience method for API)
+    pub async fn get_trade_notes(&self, trade_id: &str) -> DbResult<Vec<models::DbTradeNote>> {
+        let repo = trade_support::PostgresTradeSupportRepository::new(self.pool.clone());
+        repo.notes(trade_id).await
+    }
+    
+    /// Trades with a support tag (convenience method for API)
+    pub async fn get_trades_with_support_tag(&self, tag: &str, limit: i64) -> DbResult<Vec<models::DbTradeSupport>> {
+        let repo = trade_support::PostgresTradeSupportRepository::new(self.pool.clone());
+        repo.with_tag(tag, limit).await
+    }
+    
+    /// Store a verified order intent (convenience method for API)
+    pub async fn create_order_intent(&self, intent: &order_intents::NewOrderIntent) -> DbResult<models::DbOrderIntent> {
+        let repo = order_intents::PostgresOrderIntentRepository::new(self.pool.clone());
+        repo.create(intent).await
+    }
+    
+    /// Order intent by EIP-712 hash (convenience method for API)
+    pub async fn get_order_intent(&self, intent_hash: &str) -> DbResult<Option<models::DbOrderIntent>> {
+        let repo = order_intents::PostgresOrderIntentRepository::new(self.pool.clone());
+        repo.get(intent_hash).await
+    }
+    
+    /// Live order intents, optionally for one token (convenience method for API)
+    pub async fn get_open_order_intents(&self, token: Option<&EthAddress>, now: i64, limit: i64) -> DbResult<Vec<models::DbOrderIntent>> {
+        let repo = order_intents::PostgresOrderIntentRepository::new(self.pool.clone());
+        repo.list_open(token, now, limit).await
+    }
+    
+    /// A seller's order intents (convenience method for API)
+    pub async fn get_order_intents_by_seller(&self, seller: &EthAddress, limit: i64) -> DbResult<Vec<models::DbOrderIntent>> {
+        let repo = order_intents::PostgresOrderIntentRepository::new(self.pool.clone());
+        repo.by_seller(seller, limit).await
+    }
+    
+    /// Cancel an open order intent (convenience method for API)
+    pub async fn cancel_order_intent(&self, intent_hash: &str) -> DbResult<bool> {
+        let repo = order_intents::PostgresOrderIntentRepository::new(self.pool.clone());
+        repo.cancel(intent_hash).await
+    }
+    
+    /// Record a validation or submission outcome (convenience method for API)
+    pub async fn record_settlement_outcome(&self, trade_id: &str, seller: &EthAddress, template: &str, stage: &str, outcome: &str) -> DbResult<()> {
+        let repo = anomalies::PostgresAnomalyRepository::new(self.pool.clone());
+        repo.record(trade_id, seller, template, stage, outcome).await
+    }
+    
+    /// PDF template of a trade's receipt, if an outcome was recorded (convenience method for API)
+    pub async fn get_trade_template(&self, trade_id: &str) -> DbResult<Option<String>> {
+        let repo = anomalies::PostgresAnomalyRepository::new(self.pool.clone());
+        repo.template_for_trade(trade_id).await
+    }
+    
+    /// Outcome counts per template and per seller since `since` (convenience method for API)
+    pub async fn get_failure_stats(&self, since: DateTime<Utc>) -> DbResult<(Vec<models::DbFailureStats>, Vec<models::DbFailureStats>)> {
+        let repo = anomalies::PostgresAnomalyRepository::new(self.pool.clone());
+        Ok((repo.template_stats(since).await?, repo.seller_stats(since).await?))
+    }
+    
+    /// Templates currently flagged for a mismatch spike (convenience method for API)
+    pub async fn get_flagged_templates(&self) -> DbResult<Vec<models::DbFlaggedTemplate>> {
+        let repo = anomalies::PostgresAnomalyRepository::new(self.pool.clone());
+        repo.active_flags().await
+    }
+    
+    /// Clear a template flag (convenience method for API)
+    pub async fn clear_template_flag(&self, template: &str, cleared_by: Option<&str>) -> DbResult<bool> {
+        let repo = anomalies::PostgresAnomalyRepository::new(self.pool.clone());
+        repo.clear(template, cleared_by).await
+    }
+    
+    /// Claim a trade for proof submission, or get the claim blocking it (convenience method for API)
+    pub async fn claim_proof_submission(&self, trade_id: &str, lease_secs: i64) -> DbResult<Result<(), models::DbProofSubmission>> {
+        let repo = proof_submissions::PostgresProofSubmissionRepository::new(self.pool.clone());
+        repo.claim(trade_id, lease_secs).await
+    }
+    
+    /// Record the transaction of an in-flight proof submission (convenience method for API)
+    pub async fn set_proof_submission_tx(&self, trade_id: &str, tx_hash: &str) -> DbResult<()> {
+        let repo = proof_submissions::PostgresProofSubmissionRepository::new(self.pool.clone());
+        repo.set_tx_hash(trade_id, tx_hash).await
+    }
+    
+    /// End a proof submission attempt (convenience method for API)
+    pub async fn finish_proof_submission(&self, trade_id: &str, status: &str, tx_hash: Option<&str>) -> DbResult<()> {
+        let repo = proof_submissions::PostgresProofSubmissionRepository::new(self.pool.clone());
+        repo.finish(trade_id, status, tx_hash).await
+    }
+    
+    /// Record an Axiom job transcript (convenience method for API)
+    pub async fn record_prover_transcript(&self, transcript: &prover_transcripts::NewProverTranscript) -> DbResult<i64> {
+        let repo = prover_transcripts::PostgresProverTranscriptRepository::new(self.pool.clone());
+        repo.create(transcript).await
+    }
+    
+    /// A trade's Axiom job transcripts, newest first (convenience method for API)
+    pub async fn get_prover_transcripts(&self, trade_id: &str, limit: i64) -> DbResult<Vec<models::DbProverTranscript>> {
+        let repo = prover_transcripts::PostgresProverTranscriptRepository::new(self.pool.clone());
+        repo.list_by_trade(trade_id, limit).await
+    }
+    
+    /// Record Axiom credits spent on an accepted job (convenience method for API)
+    pub async fn record_prover_usage(&self, usage: &prover_usage::NewProverUsage) -> DbResult<()> {
+        let repo = prover_usage::PostgresProverUsageRepository::new(self.pool.clone());
+        repo.record(usage).await
+    }
+    
+    /// Axiom jobs and credits since `since` (convenience method for API)
+    pub async fn get_prover_usage_summary(&self, since: DateTime<Utc>) -> DbResult<models::DbProverUsageSummary> {
+        let repo = prover_usage::PostgresProverUsageRepository::new(self.pool.clone());
+        repo.summary(since).await
+    }
+    
+    /// Set a wallet's notification channel; false if a newer signature already set it (convenience method for API)
+    pub async fn set_notification_channel(&self, channel: &notification_channels::NewNotificationChannel) -> DbResult<bool> {
+        let repo = notification_channels::PostgresNotificationChannelRepository::new(self.pool.clone());
+        repo.upsert(channel).await
+    }
+    
+    /// Remove a wallet's notification channel (convenience method for API)
+    pub async fn delete_notification_channel(&self, address: &EthAddress, channel: &str, signed_at: i64) -> DbResult<bool> {
+        let repo = notification_channels::PostgresNotificationChannelRepository::new(self.pool.clone());
+        repo.delete(address, channel, signed_at).await
+    }
+    
+    /// A wallet's notification channels (convenience method for API)
+    pub async fn get_notification_channels(&self, address: &EthAddress) -> DbResult<Vec<models::DbNotificationChannel>> {
+        let repo = notification_channels::PostgresNotificationChannelRepository::new(self.pool.clone());
+        repo.list_by_address(address).await
+    }
+    
+    /// Size and version of a trade's PDF or proof (convenience method for API)
+    pub async fn get_trade_blob_info(&self, trade_id: &str, blob: blobs::TradeBlob) -> DbResult<Option<blobs::BlobInfo>> {
+        self.repos.blobs.info(trade_id, blob).await
     }
     
-    /// Save proof for a trade (convenience method for API)
-    pub async fn save_trade_proof(&self, trade_id: &str, user_public_values: &[u8], accumulator: &[u8], proof_data: &[u8], axiom_proof_id: &str, proof_json: &str) -> DbResult<()> {
-        let repo = trades::PostgresTradeRepository::new(self.pool.clone());
-        repo.save_proof(trade_id, user_public_values, accumulator, proof_data, axiom_proof_id, proof_json).await
+    /// Stream a trade's PDF or proof in chunks (convenience method for API)
+    pub fn stream_trade_blob(&self, trade_id: &str, blob: blobs::TradeBlob, info: blobs::BlobInfo) -> impl futures::Stream<Item = DbResult<Vec<u8>>> + Send {
+        blobs::stream(self.repos.blobs.clone(), trade_id.to_string(), blob, info)
     }
 }
@@ -2,6 +2,8 @@ use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 use sqlx::FromRow;
 
+use crate::blockchain::address::EthAddress;
+
 /// Database model for Order - EXACTLY matches on-chain Order struct
 /// Plus convenience field: syncedAt
 /// NOTE: Orders never expire - they remain active until seller withdraws all funds.
@@ -11,8 +13,8 @@ pub struct DbOrder {
     // On-chain fields (EXACT match with ZkAliPayEscrow.sol Order struct)
     #[sqlx(rename = "orderId")]
     pub order_id: String,                   // bytes32 as 0x-prefixed hex string (66 chars)
-    pub seller: String,                     // address (0x-prefixed, 42 chars)
-    pub token: String,                      // address (0x-prefixed, 42 chars)
+    pub seller: EthAddress,                 // address (checksummed in JSON)
+    pub token: EthAddress,                  // address (checksummed in JSON)
     #[sqlx(rename = "totalAmount")]
     pub total_amount: String,               // uint256 as decimal string
     #[sqlx(rename = "remainingAmount")]
@@ -29,6 +31,12 @@ pub struct DbOrder {
     // Additional fields for convenience (NOT on-chain)
     #[sqlx(rename = "syncedAt")]
     pub synced_at: DateTime<Utc>,           // When record was synced to DB
+    #[sqlx(rename = "alipayIdFormat")]
+    pub alipay_id_format: String,           // "phone" or "email" (see crate::alipay)
+    #[sqlx(rename = "minFill")]
+    pub min_fill: Option<String>,           // Seller preference: smallest fill (token base units)
+    #[sqlx(rename = "lotSize")]
+    pub lot_size: Option<String>,           // Seller preference: fills are a multiple of this
 }
 
 /// Database model for Trade - EXACTLY matches on-chain Trade struct
@@ -40,7 +48,7 @@ pub struct DbTrade {
     pub trade_id: String,                   // bytes32 as 0x-prefixed hex string (66 chars)
     #[sqlx(rename = "orderId")]
     pub order_id: String,                   // bytes32 reference to order (66 chars)
-    pub buyer: String,                      // address (0x-prefixed, 42 chars)
+    pub buyer: EthAddress,                  // address (checksummed in JSON)
     #[sqlx(rename = "tokenAmount")]
     pub token_amount: String,               // uint256 as decimal string
     #[sqlx(rename = "cnyAmount")]
@@ -60,10 +68,16 @@ pub struct DbTrade {
     pub escrow_tx_hash: Option<String>,     // Transaction hash when trade created
     #[sqlx(rename = "settlementTxHash")]
     pub settlement_tx_hash: Option<String>, // Transaction hash when settled
+    #[sqlx(rename = "settlementBlock")]
+    pub settlement_block: Option<i64>,      // Block the settlement tx was mined in
+    #[serde(default)]
+    pub confirmations: i32,                 // Settlement tx confirmations, tracked until finalized
+    #[sqlx(rename = "finalizedAt")]
+    pub finalized_at: Option<DateTime<Utc>>, // When confirmations reached the configured depth
     
     // Token address (joined from orders table, not in trades table directly)
     #[sqlx(default)]
-    pub token: Option<String>,              // Token address from order (0x-prefixed, 42 chars)
+    pub token: Option<EthAddress>,          // Token address from order
     
     // PDF storage fields
     #[serde(skip_serializing)]              // Don't send binary data in JSON
@@ -77,17 +91,611 @@ pub struct DbTrade {
     // Axiom EVM proof fields
     #[serde(skip_serializing)]              // Don't send binary data in JSON by default
     #[sqlx(rename = "proof_user_public_values")]
-    pub proof_user_public_values: Option<Vec<u8>>, // 32 bytes
+    pub proof_user_public_values: Option<Vec<u8>>, // Sizes per proof_format
     #[serde(skip_serializing)]
     #[sqlx(rename = "proof_accumulator")]
-    pub proof_accumulator: Option<Vec<u8>>,  // 384 bytes
+    pub proof_accumulator: Option<Vec<u8>>,
     #[serde(skip_serializing)]
     #[sqlx(rename = "proof_data")]
-    pub proof_data: Option<Vec<u8>>,         // 1376 bytes
+    pub proof_data: Option<Vec<u8>>,
     #[sqlx(rename = "axiom_proof_id")]
     pub axiom_proof_id: Option<String>,      // Axiom API proof ID
     #[sqlx(rename = "proof_generated_at")]
     pub proof_generated_at: Option<DateTime<Utc>>, // When proof was generated
     #[sqlx(rename = "proof_json")]
     pub proof_json: Option<String>,          // Full Axiom EVM proof JSON
+    #[sqlx(rename = "proofFormat")]
+    #[serde(default)]
+    pub proof_format: Option<String>,        // ProofFormat version of the proof (None = halo2-v1)
+}
+
+/// Database model for event listener sync progress (one row per contract)
+#[derive(Debug, Clone, FromRow, Serialize, Deserialize)]
+pub struct DbSyncState {
+    pub contract_address: String,           // address (0x-prefixed, lowercase)
+    pub last_synced_block: i64,             // Last block fully processed
+    pub last_synced_at: DateTime<Utc>,      // When last_synced_block was advanced
+}
+
+/// Database model for a contract config-change event (admin history)
+#[derive(Debug, Clone, FromRow, Serialize, Deserialize)]
+pub struct DbConfigEvent {
+    pub id: i64,
+    #[sqlx(rename = "eventName")]
+    pub event_name: String,                 // Solidity event name
+    pub details: String,                    // Decoded event fields as JSON
+    #[sqlx(rename = "blockNumber")]
+    pub block_number: i64,
+    #[sqlx(rename = "txHash")]
+    pub tx_hash: String,                    // 0x-prefixed transaction hash
+    #[sqlx(rename = "logIndex")]
+    pub log_index: i64,
+    #[sqlx(rename = "syncedAt")]
+    pub synced_at: DateTime<Utc>,           // When the event was recorded
+}
+
+/// Database model for a signed settlement receipt
+/// receipt_json is the exact payload that was signed (EIP-191 personal_sign)
+#[derive(Debug, Clone, FromRow, Serialize, Deserialize)]
+pub struct DbTradeReceipt {
+    #[sqlx(rename = "tradeId")]
+    pub trade_id: String,                   // bytes32 as 0x-prefixed hex string (66 chars)
+    pub receipt_json: String,               // Canonical receipt JSON
+    pub signature: String,                  // 65-byte signature (0x-prefixed hex)
+    pub signer: String,                     // Attestation key address (0x-prefixed, 42 chars)
+    #[sqlx(rename = "issuedAt")]
+    pub issued_at: DateTime<Utc>,           // When the receipt was signed
+}
+
+/// Database model for a cached Axiom execute-mode result
+#[derive(Debug, Clone, FromRow, Serialize, Deserialize)]
+pub struct DbAxiomExecution {
+    #[sqlx(rename = "inputHash")]
+    pub input_hash: String,                 // SHA-256 of the input streams (0x-prefixed)
+    #[sqlx(rename = "programId")]
+    pub program_id: String,                 // Axiom program ID
+    #[sqlx(rename = "tradeId")]
+    pub trade_id: String,                   // Trade that first triggered the execution
+    #[sqlx(rename = "actualHash")]
+    pub actual_hash: String,                // Guest output (0x-prefixed)
+    #[sqlx(rename = "executedAt")]
+    pub executed_at: DateTime<Utc>,
+}
+
+/// Database model for a PDF validation attempt
+#[derive(Debug, Clone, FromRow, Serialize, Deserialize)]
+pub struct DbValidationAttempt {
+    pub id: i64,
+    #[sqlx(rename = "tradeId")]
+    pub trade_id: String,                   // bytes32 as 0x-prefixed hex string (66 chars)
+    pub source: String,                     // axiom, axiom_cached, local
+    #[sqlx(rename = "programId")]
+    pub program_id: Option<String>,         // Axiom program ID
+    #[sqlx(rename = "inputHash")]
+    pub input_hash: Option<String>,         // SHA-256 of the input streams (0x-prefixed)
+    #[sqlx(rename = "expectedHash")]
+    pub expected_hash: Option<String>,      // 0x-prefixed
+    #[sqlx(rename = "actualHash")]
+    pub actual_hash: Option<String>,        // 0x-prefixed, None if execution failed
+    #[sqlx(rename = "isValid")]
+    pub is_valid: Option<bool>,             // None if execution failed
+    pub error: Option<String>,              // Failure reason
+    #[sqlx(rename = "attemptedAt")]
+    pub attempted_at: DateTime<Utc>,
+}
+
+/// A PENDING trade due for a reminder (subset of DbTrade)
+#[derive(Debug, Clone, FromRow, Serialize, Deserialize)]
+pub struct DbDueReminder {
+    #[sqlx(rename = "tradeId")]
+    pub trade_id: String,                   // bytes32 as 0x-prefixed hex string (66 chars)
+    pub buyer: EthAddress,
+    #[sqlx(rename = "paymentNonce")]
+    pub payment_nonce: String,
+    #[sqlx(rename = "cnyAmount")]
+    pub cny_amount: String,                 // CNY in cents
+    #[sqlx(rename = "expiresAt")]
+    pub expires_at: i64,                    // unix timestamp
+}
+
+/// A new trade as its seller is told about it (trade joined with its order)
+#[derive(Debug, Clone, FromRow, Serialize, Deserialize)]
+pub struct DbSellerNotice {
+    #[sqlx(rename = "tradeId")]
+    pub trade_id: String,
+    #[sqlx(rename = "orderId")]
+    pub order_id: String,
+    pub seller: EthAddress,
+    pub buyer: EthAddress,
+    pub token: EthAddress,
+    #[sqlx(rename = "tokenAmount")]
+    pub token_amount: String,
+    #[sqlx(rename = "cnyAmount")]
+    pub cny_amount: String,                 // CNY in cents
+    #[sqlx(rename = "paymentNonce")]
+    pub payment_nonce: String,
+    #[sqlx(rename = "alipayId")]
+    pub alipay_id: String,                  // account the buyer pays into
+    #[sqlx(rename = "expiresAt")]
+    pub expires_at: i64,                    // unix timestamp
+}
+
+/// Relayer gas spend totals for one action
+#[derive(Debug, Clone, FromRow, Serialize, Deserialize)]
+pub struct DbActionRelayerCost {
+    pub action: String,                     // fillOrder, submitPaymentProof, cancelExpiredTrade
+    pub tx_count: i64,
+    pub reverted_count: i64,
+    pub gas_used: String,                   // decimal string
+    pub cost_wei: String,                   // decimal string
+}
+
+/// Relayer gas spend totals for one trade
+#[derive(Debug, Clone, FromRow, Serialize, Deserialize)]
+pub struct DbTradeRelayerCost {
+    pub trade_id: String,
+    pub order_id: Option<String>,           // None if neither the spend nor a synced trade knows it
+    pub tx_count: i64,
+    pub gas_used: String,                   // decimal string
+    pub cost_wei: String,                   // decimal string
+}
+
+/// Operator fee accrued for one settled trade
+#[derive(Debug, Clone, FromRow, Serialize, Deserialize)]
+pub struct DbFeeAccrual {
+    pub trade_id: String,
+    pub order_id: String,
+    pub payer_role: String,                 // seller | buyer
+    pub payer: EthAddress,
+    pub token: EthAddress,
+    pub token_fee: String,                  // token base units, decimal string
+    pub cny_fee: String,                    // CNY cents, decimal string
+    pub schedule: String,                   // schedule applied, e.g. bps:25
+    pub accrued_at: DateTime<Utc>,
+}
+
+/// Operator fee totals for one payer and token
+#[derive(Debug, Clone, FromRow, Serialize, Deserialize)]
+pub struct DbFeeTotal {
+    pub payer: EthAddress,
+    pub payer_role: String,
+    pub token: EthAddress,
+    pub trade_count: i64,
+    pub token_fee: String,                  // decimal string
+    pub cny_fee: String,                    // CNY cents, decimal string
+}
+
+/// Settlement pipeline job (validate -> prove -> verify -> submit)
+#[derive(Debug, Clone, FromRow, Serialize, Deserialize)]
+pub struct DbSettlementJob {
+    pub job_id: uuid::Uuid,
+    pub trade_id: String,
+    pub status: String,                     // running | completed | failed
+    pub step: String,                       // validate | prove | verify | submit | done
+    pub attempts: i32,
+    pub proof_id: Option<String>,
+    pub tx_hash: Option<String>,
+    pub error: Option<String>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+    pub completed_at: Option<DateTime<Utc>>,
+}
+
+/// Allowlist / denylist entry for a token
+#[derive(Debug, Clone, FromRow, Serialize, Deserialize)]
+pub struct DbTokenStatus {
+    pub token: EthAddress,
+    pub status: String,                     // allowed | denied
+    pub reason: Option<String>,
+    pub updated_by: Option<String>,         // Admin key name
+    pub updated_at: DateTime<Utc>,
+}
+
+/// Market-maker API key (the secret itself is never stored)
+#[derive(Debug, Clone, FromRow, Serialize, Deserialize)]
+pub struct DbApiKey {
+    pub key_id: uuid::Uuid,
+    #[serde(skip_serializing)]
+    pub key_hash: String,                   // hex SHA-256 of the secret
+    pub key_prefix: String,
+    pub name: String,
+    pub wallet: EthAddress,
+    pub scopes: Vec<String>,                // read | trade
+    pub rate_limit_per_minute: i32,
+    pub created_by: Option<String>,         // Admin key name
+    pub created_at: DateTime<Utc>,
+    pub last_used_at: Option<DateTime<Utc>>,
+    pub revoked_at: Option<DateTime<Utc>>,
+}
+
+/// Order change from the order feed log, with the order as of that change
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DbOrderFeedEntry {
+    pub seq: i64,
+    pub kind: String,                       // created | updated
+    pub order: DbOrder,
+}
+
+/// Aggregate book state for one token in one snapshot bucket
+#[derive(Debug, Clone, FromRow, Serialize, Deserialize)]
+pub struct DbOrderbookSnapshot {
+    pub bucket: DateTime<Utc>,              // Bucket start
+    pub token: EthAddress,
+    pub order_count: i32,
+    pub total_liquidity: String,            // decimal string
+    pub best_rate: String,                  // decimal string, lowest exchangeRate
+    pub worst_rate: String,                 // decimal string, highest exchangeRate
+    pub weighted_rate: String,              // decimal string, weighted by remainingAmount
+}
+
+/// Operator support metadata for a trade
+#[derive(Debug, Clone, FromRow, Serialize, Deserialize)]
+pub struct DbTradeSupport {
+    pub trade_id: String,
+    pub buyer_contact: Option<String>,      // e.g. Telegram handle or email
+    pub tags: Vec<String>,
+    pub updated_by: Option<String>,         // Admin key name
+    pub updated_at: DateTime<Utc>,
+}
+
+/// Operator support note on a trade
+#[derive(Debug, Clone, FromRow, Serialize, Deserialize)]
+pub struct DbTradeNote {
+    pub id: i64,
+    pub trade_id: String,
+    pub note: String,
+    pub author: Option<String>,             // Admin key name
+    pub created_at: DateTime<Utc>,
+}
+
+/// submitPaymentProof claim for a trade (at most one submission in flight)
+#[derive(Debug, Clone, FromRow, Serialize, Deserialize)]
+pub struct DbProofSubmission {
+    pub trade_id: String,
+    pub status: String,                     // submitting | submitted | failed
+    pub tx_hash: Option<String>,            // Set once the transaction is sent
+    pub attempts: i32,
+    pub started_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+/// Settled trade whose settlement tx hasn't reached the confirmation depth yet
+#[derive(Debug, Clone, FromRow, Serialize, Deserialize)]
+pub struct DbUnfinalizedSettlement {
+    pub trade_id: String,
+    pub order_id: String,
+    pub buyer: EthAddress,
+    pub settlement_tx_hash: String,
+    pub settlement_block: Option<i64>,
+    pub confirmations: i32,
+}
+
+/// PENDING trade to compare with the escrow (see blockchain::trade_drift)
+#[derive(Debug, Clone, FromRow, Serialize, Deserialize)]
+pub struct DbPendingTrade {
+    pub trade_id: String,
+    pub escrow_tx_hash: Option<String>,     // fillOrder tx (its block bounds the event search)
+    pub created_at: i64,                    // unix timestamp
+}
+
+/// Seller-signed EIP-712 order intent (see blockchain::types::OrderIntent)
+#[derive(Debug, Clone, FromRow, Serialize, Deserialize)]
+pub struct DbOrderIntent {
+    pub intent_hash: String,                // EIP-712 struct hash (66 chars)
+    pub seller: EthAddress,
+    pub token: EthAddress,
+    pub amount: String,                     // uint256 as decimal string
+    pub exchange_rate: String,              // uint256 (CNY cents per token)
+    pub alipay_hash: String,                // keccak256(abi.encode(alipayId, alipayName))
+    pub nonce: String,                      // uint256 as decimal string
+    pub deadline: i64,                      // Unix timestamp
+    pub signature: String,
+    pub status: String,                     // open | cancelled | funded
+    pub order_id: Option<String>,           // Escrow order that funded the intent
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+/// Outcome counts of one seller or template over a window
+#[derive(Debug, Clone, FromRow, Serialize, Deserialize)]
+pub struct DbFailureStats {
+    pub key: String,                        // Seller address or template id
+    pub samples: i64,
+    pub mismatches: i64,                    // PaymentDetailsMismatch / hash mismatch
+    pub proof_failures: i64,                // ProofVerificationFailed
+}
+
+/// PDF template flagged for a mismatch spike
+#[derive(Debug, Clone, FromRow, Serialize, Deserialize)]
+pub struct DbFlaggedTemplate {
+    pub id: i64,
+    pub template: String,
+    pub mismatch_rate: f64,
+    pub samples: i64,
+    pub flagged_at: DateTime<Utc>,
+    pub cleared_at: Option<DateTime<Utc>>,
+    pub cleared_by: Option<String>,         // Admin key name
+}
+
+/// Recommended payment window for trades of a token worth at least min_value_cny
+#[derive(Debug, Clone, FromRow, Serialize, Deserialize)]
+pub struct DbPaymentWindowTier {
+    pub id: i64,
+    pub token: Option<EthAddress>,          // None: any token
+    pub min_value_cny: i64,                 // CNY cents
+    pub window_secs: i64,
+    pub updated_by: Option<String>,         // Admin key name
+    pub updated_at: DateTime<Utc>,
+}
+
+/// Buyer/seller message on a trade (attachment bytes are fetched separately)
+#[derive(Debug, Clone, FromRow, Serialize, Deserialize)]
+pub struct DbTradeMessage {
+    pub id: i64,
+    pub trade_id: String,
+    pub sender: EthAddress,
+    pub role: String,                       // buyer | seller
+    pub body: String,
+    pub attachment_name: Option<String>,
+    pub attachment_type: Option<String>,    // MIME type
+    pub attachment_size: Option<i32>,       // Bytes
+    pub created_at: DateTime<Utc>,
+}
+
+/// Attachment of a trade message
+#[derive(Debug, Clone, FromRow)]
+pub struct DbMessageAttachment {
+    pub name: Option<String>,
+    pub content_type: String,
+    pub data: Vec<u8>,
+}
+
+/// Alipay transfer a buyer declared for a trade (migrations/040_trade_payments.sql)
+#[derive(Debug, Clone, FromRow, Serialize, Deserialize)]
+pub struct DbTradePayment {
+    pub id: i64,
+    pub trade_id: String,
+    pub buyer: EthAddress,
+    pub amount: i64,                        // CNY cents
+    pub paid_at: DateTime<Utc>,
+    pub amount_status: String,              // exact | short | over
+    pub matches_trade_id: Option<String>,   // Buyer's other pending trade of exactly this amount
+    pub created_at: DateTime<Utc>,
+}
+
+/// EAS attestation of a settled trade (migrations/041_trade_attestations.sql)
+#[derive(Debug, Clone, FromRow, Serialize, Deserialize)]
+pub struct DbTradeAttestation {
+    pub trade_id: String,
+    pub status: String,                     // pending | attesting | attested | failed
+    pub attempts: i32,
+    pub uid: Option<String>,                // EAS attestation UID, once attested
+    pub tx_hash: Option<String>,            // attest transaction
+    pub error: Option<String>,              // Why the last attempt failed
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+    pub attested_at: Option<DateTime<Utc>>,
+}
+
+/// Admin thresholds for stale orders (migrations/042_order_staleness.sql)
+#[derive(Debug, Clone, FromRow, Serialize, Deserialize)]
+pub struct DbStalenessSettings {
+    pub stale_after_days: i32,              // Idle days before an active order is stale
+    pub notify_every_days: i32,             // Days between nudges to the seller of a stale order
+    pub updated_by: Option<String>,         // Admin key name
+    pub updated_at: DateTime<Utc>,
+}
+
+/// Active order idle past the stale threshold
+#[derive(Debug, Clone, FromRow, Serialize, Deserialize)]
+pub struct DbStaleOrder {
+    pub order_id: String,
+    pub seller: EthAddress,
+    pub token: EthAddress,
+    pub remaining_amount: String,           // uint256 as decimal string
+    pub last_activity_at: DateTime<Utc>,    // Creation, last change (fill, withdrawal, fill limits) or seller refresh
+    pub stale_notified_at: Option<DateTime<Utc>>,
+}
+
+/// Contract event (or bulk order transaction) the listener failed to apply
+#[derive(Debug, Clone, FromRow, Serialize, Deserialize)]
+pub struct DbDeadLetter {
+    pub id: i64,
+    pub event_type: String,
+    pub block_number: i64,
+    pub tx_hash: String,
+    pub log_index: i64,
+    pub logs: String,                       // Raw logs as JSON, in order
+    pub error: String,
+    pub attempts: i32,
+    pub status: String,                     // pending | queued | reprocessed | discarded
+    pub resolved_by: Option<String>,        // Admin key name
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+/// Current liquidity of one token (active orders only)
+#[derive(Debug, Clone, FromRow, Serialize, Deserialize)]
+pub struct DbTokenLiquidity {
+    pub token: EthAddress,
+    pub order_count: i64,
+    pub total_liquidity: String,            // decimal string, token base units
+    pub best_rate: String,                  // decimal string, lowest exchangeRate
+}
+
+/// Book and recent settlement aggregates of one token (orderbook summary)
+#[derive(Debug, Clone, FromRow, Serialize, Deserialize)]
+pub struct DbTokenSummary {
+    pub token: EthAddress,
+    pub active_orders: i64,
+    pub total_liquidity: String,            // decimal string, token base units
+    pub best_rate: Option<String>,          // decimal string, lowest exchangeRate (None without active orders)
+    pub settled_trades_24h: i64,
+    pub settled_volume_24h: String,         // decimal string, token base units
+    pub settled_volume_24h_cny: String,     // decimal string, CNY cents
+}
+
+/// A match plan as handed out by /api/match-intent
+#[derive(Debug, Clone, FromRow, Serialize, Deserialize)]
+pub struct DbMatchPlan {
+    pub plan_id: uuid::Uuid,
+    pub token: EthAddress,
+    pub buyer: Option<EthAddress>,
+    pub request: String,                    // JSON
+    pub plan: String,                       // MatchPlan JSON
+    pub book_hash: String,
+    pub order_priority: String,
+    pub created_at: DateTime<Utc>,
+    pub expires_at: DateTime<Utc>,
+    pub executed_by: Option<EthAddress>,
+    pub trade_ids: Vec<String>,
+    pub executed_at: Option<DateTime<Utc>>,
+}
+
+/// Trade outcomes of one seller's orders (all time)
+#[derive(Debug, Clone, PartialEq, Eq, FromRow, Serialize, Deserialize)]
+pub struct DbSellerReputation {
+    pub seller: EthAddress,
+    pub settled_trades: i64,
+    pub expired_trades: i64,
+}
+
+/// Proofs generated over a window and how long they took after the PDF upload
+#[derive(Debug, Clone, FromRow, Serialize, Deserialize)]
+pub struct DbProofLatency {
+    pub proofs: i64,
+    pub avg_latency_secs: Option<f64>,
+}
+
+/// Seller Alipay account on the operator blocklist
+#[derive(Debug, Clone, FromRow, Serialize, Deserialize)]
+pub struct DbBlockedAlipayId {
+    pub alipay_id: String,                  // Trimmed, lowercased
+    pub reason: Option<String>,
+    pub added_by: Option<String>,           // Admin key name
+    pub added_at: DateTime<Utc>,
+}
+
+/// One change to the Alipay blocklist
+#[derive(Debug, Clone, FromRow, Serialize, Deserialize)]
+pub struct DbAlipayBlocklistChange {
+    pub id: i64,
+    pub alipay_id: String,
+    pub action: String,                     // block | unblock
+    pub reason: Option<String>,
+    pub actor: Option<String>,              // Admin key name
+    pub created_at: DateTime<Utc>,
+}
+
+/// Lifecycle counts for orders created over a period and the trades filled on them.
+/// Trade stages are cumulative: a trade counts at every stage up to the furthest one it reached.
+#[derive(Debug, Clone, FromRow, Serialize, Deserialize)]
+pub struct DbLifecycleFunnel {
+    pub orders_created: i64,
+    pub orders_matched: i64,                // Orders with at least one trade
+    pub trades_filled: i64,
+    pub pdf_uploaded: i64,
+    pub validated: i64,                     // A validation attempt passed
+    pub proven: i64,
+    pub settled: i64,
+    pub expired: i64,                       // Trades that ran out of time (left the funnel)
+}
+
+/// Rolling CNY caps of a buyer tier
+#[derive(Debug, Clone, FromRow, Serialize, Deserialize)]
+pub struct DbSpendingLimitTier {
+    pub tier: i32,
+    pub daily_limit_cny: Option<i64>,       // CNY cents over 24h, None: no cap
+    pub monthly_limit_cny: Option<i64>,     // CNY cents over 30 days, None: no cap
+    pub updated_by: Option<String>,         // Admin key name
+    pub updated_at: DateTime<Utc>,
+}
+
+/// Buyer placed above tier 0
+#[derive(Debug, Clone, FromRow, Serialize, Deserialize)]
+pub struct DbBuyerTier {
+    pub buyer: EthAddress,
+    pub tier: i32,
+    pub source: String,                     // admin | kyc
+    pub reference: Option<String>,          // KYC verification id
+    pub updated_by: Option<String>,         // Admin key name or KYC provider
+    pub updated_at: DateTime<Utc>,
+}
+
+/// CNY value of a buyer's pending and settled trades over the limit windows
+#[derive(Debug, Clone, Default, FromRow, Serialize, Deserialize)]
+pub struct DbBuyerVolume {
+    pub volume_24h_cny: i64,                // CNY cents
+    pub volume_30d_cny: i64,                // CNY cents
+}
+
+/// Latest screening verdict for an address
+#[derive(Debug, Clone, FromRow, Serialize, Deserialize)]
+pub struct DbScreeningVerdict {
+    pub address: EthAddress,
+    pub flagged: bool,
+    pub reason: Option<String>,
+    pub provider: String,
+    pub screened_at: DateTime<Utc>,
+}
+
+/// Service refused to a flagged address
+#[derive(Debug, Clone, FromRow, Serialize, Deserialize)]
+pub struct DbScreeningRefusal {
+    pub id: i64,
+    pub address: EthAddress,
+    pub role: String,                       // buyer | seller
+    pub context: String,                    // execute_fill | order_sync
+    pub reference: Option<String>,          // Order id
+    pub reason: Option<String>,
+    pub provider: String,
+    pub created_at: DateTime<Utc>,
+}
+
+/// Lifecycle event from the journal outbox
+#[derive(Debug, Clone, FromRow, Serialize, Deserialize)]
+pub struct DbJournalEvent {
+    pub id: i64,
+    pub event_type: String,                 // order_created | order_updated | trade_created | trade_settled | trade_expired
+    pub aggregate_id: String,               // Order or trade id
+    pub payload: String,                    // JSON object
+    pub occurred_at: DateTime<Utc>,
+    pub published_at: Option<DateTime<Utc>>,
+}
+
+/// Axiom request/response transcript of one execution or proof job
+#[derive(Debug, Clone, FromRow, Serialize, Deserialize)]
+pub struct DbProverTranscript {
+    pub id: i64,
+    pub trade_id: String,
+    pub kind: String,                       // execution | proof
+    pub program_id: String,
+    pub axiom_id: Option<String>,           // Axiom execution or proof id
+    pub status: String,                     // succeeded | failed
+    pub error: Option<String>,
+    pub entries: String,                    // JSON array of requests and terminal responses
+    pub started_at: DateTime<Utc>,
+    pub finished_at: DateTime<Utc>,
+}
+
+/// Axiom jobs and credits over a period (migrations/039_prover_usage.sql)
+#[derive(Debug, Clone, FromRow, Serialize, Deserialize)]
+pub struct DbProverUsageSummary {
+    pub executions: i64,
+    pub proofs: i64,
+    pub credits_used: i64,
+    pub reported_remaining: Option<i64>,    // Latest balance Axiom reported
+    pub reported_at: Option<DateTime<Utc>>,
+}
+
+/// A wallet's notification channel (migrations/037_notification_channels.sql)
+#[derive(Debug, Clone, FromRow, Serialize, Deserialize)]
+pub struct DbNotificationChannel {
+    pub address: EthAddress,
+    pub channel: String,                    // email | sms | telegram
+    pub destination: String,
+    pub events: Option<Vec<String>>,        // None = all events
+    pub signed_at: i64,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
 }
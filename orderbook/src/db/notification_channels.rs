@@ -0,0 +1,112 @@
+use async_trait::async_trait;
+use sqlx::PgPool;
+
+use super::DbResult;
+use super::models::DbNotificationChannel;
+use crate::blockchain::address::EthAddress;
+
+/// Delivery channels
+pub const CHANNEL_EMAIL: &str = "email";
+pub const CHANNEL_SMS: &str = "sms";
+pub const CHANNEL_TELEGRAM: &str = "telegram";
+
+pub const CHANNELS: [&str; 3] = [CHANNEL_EMAIL, CHANNEL_SMS, CHANNEL_TELEGRAM];
+
+/// A channel preference to store (replaces the address's previous one for the channel)
+#[derive(Debug, Clone)]
+pub struct NewNotificationChannel {
+    pub address: EthAddress,
+    pub channel: &'static str,
+    pub destination: String,
+    /// Events to deliver (None = all)
+    pub events: Option<Vec<String>>,
+    pub signed_at: i64,
+}
+
+/// Repository for per-user notification channels
+#[async_trait]
+pub trait NotificationChannelRepository: Send + Sync {
+    /// Set a channel; false if a newer signature already set it
+    async fn upsert(&self, channel: &NewNotificationChannel) -> DbResult<bool>;
+
+    /// Remove a channel; false if it wasn't set (or was set by a newer signature)
+    async fn delete(&self, address: &EthAddress, channel: &str, signed_at: i64) -> DbResult<bool>;
+
+    /// An address's channels
+    async fn list_by_address(&self, address: &EthAddress) -> DbResult<Vec<DbNotificationChannel>>;
+}
+
+pub struct PostgresNotificationChannelRepository {
+    pool: PgPool,
+}
+
+impl PostgresNotificationChannelRepository {
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+}
+
+#[async_trait]
+impl NotificationChannelRepository for PostgresNotificationChannelRepository {
+    async fn upsert(&self, channel: &NewNotificationChannel) -> DbResult<bool> {
+        let result = sqlx::query!(
+            r#"
+            INSERT INTO notification_channels ("address", "channel", "destination", "events", "signedAt")
+            VALUES ($1, $2, $3, $4, $5)
+            ON CONFLICT ("address", "channel") DO UPDATE
+            SET "destination" = EXCLUDED."destination", "events" = EXCLUDED."events",
+                "signedAt" = EXCLUDED."signedAt", "updatedAt" = NOW()
+            WHERE notification_channels."signedAt" < EXCLUDED."signedAt"
+            "#,
+            channel.address.as_str(),
+            channel.channel,
+            channel.destination,
+            channel.events.as_deref(),
+            channel.signed_at
+        )
+        .execute(&self.pool)
+        .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    async fn delete(&self, address: &EthAddress, channel: &str, signed_at: i64) -> DbResult<bool> {
+        let result = sqlx::query!(
+            r#"
+            DELETE FROM notification_channels
+            WHERE "address" = $1 AND "channel" = $2 AND "signedAt" < $3
+            "#,
+            address.as_str(),
+            channel,
+            signed_at
+        )
+        .execute(&self.pool)
+        .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    async fn list_by_address(&self, address: &EthAddress) -> DbResult<Vec<DbNotificationChannel>> {
+        let channels = sqlx::query_as!(
+            DbNotificationChannel,
+            r#"
+            SELECT
+                "address" as "address: EthAddress",
+                "channel",
+                "destination",
+                "events",
+                "signedAt" as signed_at,
+                "createdAt" as created_at,
+                "updatedAt" as updated_at
+            FROM notification_channels
+            WHERE "address" = $1
+            ORDER BY "channel"
+            "#,
+            address.as_str()
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(channels)
+    }
+}
@@ -0,0 +1,141 @@
+use async_trait::async_trait;
+use sqlx::PgPool;
+
+use super::DbResult;
+use super::models::{DbOrder, DbOrderFeedEntry};
+use crate::blockchain::address::EthAddress;
+
+/// Feed entry kinds
+pub const FEED_CREATED: &str = "created";
+pub const FEED_UPDATED: &str = "updated";
+
+/// Repository for the order feed log (written by the orders_feed trigger)
+#[async_trait]
+pub trait OrderFeedRepository: Send + Sync {
+    /// Active orders and the sequence number they are current as of
+    async fn snapshot(&self) -> DbResult<(i64, Vec<DbOrder>)>;
+
+    /// Latest sequence number
+    async fn head(&self) -> DbResult<i64>;
+
+    /// Changes after `seq`, oldest first
+    async fn since(&self, seq: i64, limit: i64) -> DbResult<Vec<DbOrderFeedEntry>>;
+}
+
+pub struct PostgresOrderFeedRepository {
+    pool: PgPool,
+}
+
+impl PostgresOrderFeedRepository {
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+}
+
+#[async_trait]
+impl OrderFeedRepository for PostgresOrderFeedRepository {
+    async fn snapshot(&self) -> DbResult<(i64, Vec<DbOrder>)> {
+        // One REPEATABLE READ transaction: the orders are exactly those as of the sequence
+        let mut tx = self.pool.begin().await?;
+        sqlx::query!("SET TRANSACTION ISOLATION LEVEL REPEATABLE READ READ ONLY")
+            .execute(&mut *tx)
+            .await?;
+
+        let seq = sqlx::query_scalar!(r#"SELECT "seq" FROM order_feed_sequence"#)
+            .fetch_one(&mut *tx)
+            .await?;
+
+        let orders = sqlx::query_as!(
+            DbOrder,
+            r#"
+            SELECT 
+                "orderId" as "order_id!",
+                seller as "seller!: EthAddress",
+                token as "token!: EthAddress",
+                "totalAmount"::TEXT as "total_amount!",
+                "remainingAmount"::TEXT as "remaining_amount!",
+                "exchangeRate"::TEXT as "exchange_rate!",
+                "alipayId" as "alipay_id!",
+                "alipayName" as "alipay_name!",
+                "createdAt" as "created_at!",
+                "syncedAt" as "synced_at!",
+                "alipayIdFormat" as "alipay_id_format!",
+                "minFill"::TEXT as min_fill,
+                "lotSize"::TEXT as lot_size
+            FROM orders
+            WHERE "remainingAmount" > 0
+            ORDER BY orders."exchangeRate" ASC, orders."createdAt" ASC
+            "#
+        )
+        .fetch_all(&mut *tx)
+        .await?;
+
+        tx.commit().await?;
+        Ok((seq, orders))
+    }
+
+    async fn head(&self) -> DbResult<i64> {
+        let seq = sqlx::query_scalar!(r#"SELECT "seq" FROM order_feed_sequence"#)
+            .fetch_one(&self.pool)
+            .await?;
+
+        Ok(seq)
+    }
+
+    async fn since(&self, seq: i64, limit: i64) -> DbResult<Vec<DbOrderFeedEntry>> {
+        let rows = sqlx::query!(
+            r#"
+            SELECT 
+                f."seq",
+                f."kind",
+                o."orderId",
+                o.seller as "seller: EthAddress",
+                o.token as "token: EthAddress",
+                o."totalAmount"::TEXT as "total_amount!",
+                f."remainingAmount"::TEXT as "remaining_amount!",
+                o."exchangeRate"::TEXT as "exchange_rate!",
+                o."alipayId",
+                o."alipayName",
+                o."createdAt",
+                o."syncedAt",
+                o."alipayIdFormat",
+                f."minFill"::TEXT as min_fill,
+                f."lotSize"::TEXT as lot_size
+            FROM order_feed f
+            JOIN orders o ON o."orderId" = f."orderId"
+            WHERE f."seq" > $1
+            ORDER BY f."seq" ASC
+            LIMIT $2
+            "#,
+            seq,
+            limit
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        let entries = rows
+            .into_iter()
+            .map(|row| DbOrderFeedEntry {
+                seq: row.seq,
+                kind: row.kind,
+                order: DbOrder {
+                    order_id: row.orderId,
+                    seller: row.seller,
+                    token: row.token,
+                    total_amount: row.total_amount,
+                    remaining_amount: row.remaining_amount,
+                    exchange_rate: row.exchange_rate,
+                    alipay_id: row.alipayId,
+                    alipay_name: row.alipayName,
+                    created_at: row.createdAt,
+                    synced_at: row.syncedAt,
+                    alipay_id_format: row.alipayIdFormat,
+                    min_fill: row.min_fill,
+                    lot_size: row.lot_size,
+                },
+            })
+            .collect();
+
+        Ok(entries)
+    }
+}
@@ -0,0 +1,225 @@
+use async_trait::async_trait;
+use rust_decimal::Decimal;
+use sqlx::PgPool;
+
+use super::{DbError, DbResult};
+use super::models::DbOrderIntent;
+use crate::blockchain::address::EthAddress;
+
+/// Intent states
+pub const INTENT_OPEN: &str = "open";
+pub const INTENT_CANCELLED: &str = "cancelled";
+pub const INTENT_FUNDED: &str = "funded";
+
+/// A verified intent to insert
+#[derive(Debug, Clone)]
+pub struct NewOrderIntent {
+    pub intent_hash: String,
+    pub seller: EthAddress,
+    pub token: EthAddress,
+    pub amount: Decimal,
+    pub exchange_rate: Decimal,
+    pub alipay_hash: String,
+    pub nonce: Decimal,
+    pub deadline: i64,
+    pub signature: String,
+}
+
+/// Repository for off-chain order intents
+#[async_trait]
+pub trait OrderIntentRepository: Send + Sync {
+    /// Store a verified intent; InvalidInput if the seller already used the nonce
+    async fn create(&self, intent: &NewOrderIntent) -> DbResult<DbOrderIntent>;
+
+    async fn get(&self, intent_hash: &str) -> DbResult<Option<DbOrderIntent>>;
+
+    /// Open intents whose deadline hasn't passed, best (lowest) rate first
+    async fn list_open(&self, token: Option<&EthAddress>, now: i64, limit: i64) -> DbResult<Vec<DbOrderIntent>>;
+
+    /// A seller's intents in any state, newest first
+    async fn by_seller(&self, seller: &EthAddress, limit: i64) -> DbResult<Vec<DbOrderIntent>>;
+
+    /// Cancel an open intent; false if it isn't open
+    async fn cancel(&self, intent_hash: &str) -> DbResult<bool>;
+
+    /// Link a newly funded escrow order to the oldest live intent with the same terms
+    /// Returns the funded intent's hash.
+    async fn mark_funded(
+        &self,
+        seller: &EthAddress,
+        token: &EthAddress,
+        amount: Decimal,
+        exchange_rate: Decimal,
+        alipay_hash: &str,
+        order_id: &str,
+        now: i64,
+    ) -> DbResult<Option<String>>;
+}
+
+pub struct PostgresOrderIntentRepository {
+    pool: PgPool,
+}
+
+impl PostgresOrderIntentRepository {
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+}
+
+#[async_trait]
+impl OrderIntentRepository for PostgresOrderIntentRepository {
+    async fn create(&self, intent: &NewOrderIntent) -> DbResult<DbOrderIntent> {
+        let created = sqlx::query_as!(
+            DbOrderIntent,
+            r#"
+            INSERT INTO order_intents (
+                "intentHash", "seller", "token", "amount", "exchangeRate",
+                "alipayHash", "nonce", "deadline", "signature"
+            )
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9)
+            RETURNING "intentHash" as intent_hash, "seller" as "seller: EthAddress", "token" as "token: EthAddress",
+                      "amount"::TEXT as "amount!", "exchangeRate"::TEXT as "exchange_rate!",
+                      "alipayHash" as alipay_hash, "nonce"::TEXT as "nonce!", "deadline", "signature",
+                      "status", "orderId" as order_id, "createdAt" as created_at, "updatedAt" as updated_at
+            "#,
+            intent.intent_hash,
+            intent.seller.as_str(),
+            intent.token.as_str(),
+            intent.amount,
+            intent.exchange_rate,
+            intent.alipay_hash,
+            intent.nonce,
+            intent.deadline,
+            intent.signature
+        )
+        .fetch_one(&self.pool)
+        .await
+        .map_err(|e| match e {
+            sqlx::Error::Database(db) if db.is_unique_violation() => DbError::InvalidInput(format!(
+                "Seller {} already posted an intent with nonce {}",
+                intent.seller, intent.nonce
+            )),
+            e => e.into(),
+        })?;
+
+        Ok(created)
+    }
+
+    async fn get(&self, intent_hash: &str) -> DbResult<Option<DbOrderIntent>> {
+        let intent = sqlx::query_as!(
+            DbOrderIntent,
+            r#"
+            SELECT "intentHash" as intent_hash, "seller" as "seller: EthAddress", "token" as "token: EthAddress",
+                   "amount"::TEXT as "amount!", "exchangeRate"::TEXT as "exchange_rate!",
+                   "alipayHash" as alipay_hash, "nonce"::TEXT as "nonce!", "deadline", "signature",
+                   "status", "orderId" as order_id, "createdAt" as created_at, "updatedAt" as updated_at
+            FROM order_intents
+            WHERE "intentHash" = $1
+            "#,
+            intent_hash
+        )
+        .fetch_optional(&self.pool)
+        .await?;
+
+        Ok(intent)
+    }
+
+    async fn list_open(&self, token: Option<&EthAddress>, now: i64, limit: i64) -> DbResult<Vec<DbOrderIntent>> {
+        let intents = sqlx::query_as!(
+            DbOrderIntent,
+            r#"
+            SELECT "intentHash" as intent_hash, "seller" as "seller: EthAddress", "token" as "token: EthAddress",
+                   "amount"::TEXT as "amount!", "exchangeRate"::TEXT as "exchange_rate!",
+                   "alipayHash" as alipay_hash, "nonce"::TEXT as "nonce!", "deadline", "signature",
+                   "status", "orderId" as order_id, "createdAt" as created_at, "updatedAt" as updated_at
+            FROM order_intents
+            WHERE "status" = 'open' AND "deadline" > $1
+            AND ($2::TEXT IS NULL OR "token" = $2)
+            ORDER BY "exchangeRate" ASC, "createdAt" ASC
+            LIMIT $3
+            "#,
+            now,
+            token.map(|t| t.as_str()),
+            limit
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(intents)
+    }
+
+    async fn by_seller(&self, seller: &EthAddress, limit: i64) -> DbResult<Vec<DbOrderIntent>> {
+        let intents = sqlx::query_as!(
+            DbOrderIntent,
+            r#"
+            SELECT "intentHash" as intent_hash, "seller" as "seller: EthAddress", "token" as "token: EthAddress",
+                   "amount"::TEXT as "amount!", "exchangeRate"::TEXT as "exchange_rate!",
+                   "alipayHash" as alipay_hash, "nonce"::TEXT as "nonce!", "deadline", "signature",
+                   "status", "orderId" as order_id, "createdAt" as created_at, "updatedAt" as updated_at
+            FROM order_intents
+            WHERE "seller" = $1
+            ORDER BY "createdAt" DESC
+            LIMIT $2
+            "#,
+            seller.as_str(),
+            limit
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(intents)
+    }
+
+    async fn cancel(&self, intent_hash: &str) -> DbResult<bool> {
+        let result = sqlx::query!(
+            r#"
+            UPDATE order_intents
+            SET "status" = 'cancelled', "updatedAt" = NOW()
+            WHERE "intentHash" = $1 AND "status" = 'open'
+            "#,
+            intent_hash
+        )
+        .execute(&self.pool)
+        .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    async fn mark_funded(
+        &self,
+        seller: &EthAddress,
+        token: &EthAddress,
+        amount: Decimal,
+        exchange_rate: Decimal,
+        alipay_hash: &str,
+        order_id: &str,
+        now: i64,
+    ) -> DbResult<Option<String>> {
+        let funded = sqlx::query_scalar!(
+            r#"
+            UPDATE order_intents
+            SET "status" = 'funded', "orderId" = $6, "updatedAt" = NOW()
+            WHERE "intentHash" = (
+                SELECT "intentHash" FROM order_intents
+                WHERE "status" = 'open' AND "seller" = $1 AND "token" = $2 AND "amount" = $3
+                AND "exchangeRate" = $4 AND "alipayHash" = $5 AND "deadline" > $7
+                ORDER BY "createdAt" ASC
+                LIMIT 1
+                FOR UPDATE SKIP LOCKED
+            )
+            RETURNING "intentHash"
+            "#,
+            seller.as_str(),
+            token.as_str(),
+            amount,
+            exchange_rate,
+            alipay_hash,
+            order_id,
+            now
+        )
+        .fetch_optional(&self.pool)
+        .await?;
+
+        Ok(funded)
+    }
+}
@@ -0,0 +1,166 @@
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use sqlx::PgPool;
+
+use super::{DbError, DbResult};
+use super::models::{DbStaleOrder, DbStalenessSettings};
+use crate::blockchain::address::EthAddress;
+
+/// Repository for stale-order tracking (migrations/042_order_staleness.sql)
+#[async_trait]
+pub trait OrderStalenessRepository: Send + Sync {
+    async fn settings(&self) -> DbResult<DbStalenessSettings>;
+
+    async fn set_settings(&self, stale_after_days: i32, notify_every_days: i32, updated_by: Option<&str>) -> DbResult<DbStalenessSettings>;
+
+    /// Record the seller's refresh of an order; false if a newer signature is already applied
+    async fn refresh(&self, order_id: &str, signed_at: i64) -> DbResult<bool>;
+
+    /// Active orders idle since before `stale_before`, longest idle first (optionally of
+    /// one token). With `notified_before`, only orders whose seller hasn't been nudged
+    /// since then. `limit` None = all.
+    async fn list_stale(
+        &self,
+        token: Option<&EthAddress>,
+        stale_before: DateTime<Utc>,
+        notified_before: Option<DateTime<Utc>>,
+        limit: Option<i64>,
+    ) -> DbResult<Vec<DbStaleOrder>>;
+
+    /// Record that the seller was nudged about the order
+    async fn mark_notified(&self, order_id: &str) -> DbResult<()>;
+}
+
+pub struct PostgresOrderStalenessRepository {
+    pool: PgPool,
+}
+
+impl PostgresOrderStalenessRepository {
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+}
+
+#[async_trait]
+impl OrderStalenessRepository for PostgresOrderStalenessRepository {
+    async fn settings(&self) -> DbResult<DbStalenessSettings> {
+        let settings = sqlx::query_as!(
+            DbStalenessSettings,
+            r#"
+            SELECT "staleAfterDays" as stale_after_days, "notifyEveryDays" as notify_every_days,
+                   "updatedBy" as updated_by, "updatedAt" as updated_at
+            FROM order_staleness_settings
+            "#
+        )
+        .fetch_one(&self.pool)
+        .await?;
+
+        Ok(settings)
+    }
+
+    async fn set_settings(&self, stale_after_days: i32, notify_every_days: i32, updated_by: Option<&str>) -> DbResult<DbStalenessSettings> {
+        let settings = sqlx::query_as!(
+            DbStalenessSettings,
+            r#"
+            UPDATE order_staleness_settings
+            SET "staleAfterDays" = $1, "notifyEveryDays" = $2, "updatedBy" = $3, "updatedAt" = NOW()
+            RETURNING "staleAfterDays" as stale_after_days, "notifyEveryDays" as notify_every_days,
+                      "updatedBy" as updated_by, "updatedAt" as updated_at
+            "#,
+            stale_after_days,
+            notify_every_days,
+            updated_by
+        )
+        .fetch_one(&self.pool)
+        .await?;
+
+        Ok(settings)
+    }
+
+    async fn refresh(&self, order_id: &str, signed_at: i64) -> DbResult<bool> {
+        let refreshed = sqlx::query_scalar!(
+            r#"
+            INSERT INTO order_activity ("orderId", "refreshedAt", "refreshSignedAt")
+            VALUES ($1, NOW(), $2)
+            ON CONFLICT ("orderId") DO UPDATE
+            SET "refreshedAt" = NOW(), "refreshSignedAt" = EXCLUDED."refreshSignedAt"
+            WHERE order_activity."refreshSignedAt" IS NULL
+               OR order_activity."refreshSignedAt" < EXCLUDED."refreshSignedAt"
+            RETURNING "orderId"
+            "#,
+            order_id,
+            signed_at
+        )
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(|e| match e {
+            sqlx::Error::Database(db) if db.is_foreign_key_violation() => DbError::OrderNotFound(order_id.to_string()),
+            e => e.into(),
+        })?;
+
+        Ok(refreshed.is_some())
+    }
+
+    async fn list_stale(
+        &self,
+        token: Option<&EthAddress>,
+        stale_before: DateTime<Utc>,
+        notified_before: Option<DateTime<Utc>>,
+        limit: Option<i64>,
+    ) -> DbResult<Vec<DbStaleOrder>> {
+        let orders = sqlx::query_as!(
+            DbStaleOrder,
+            r#"
+            SELECT idle.order_id as "order_id!",
+                   idle.seller as "seller!: EthAddress",
+                   idle.token as "token!: EthAddress",
+                   idle.remaining_amount as "remaining_amount!",
+                   idle.last_activity_at as "last_activity_at!",
+                   idle.stale_notified_at
+            FROM (
+                SELECT o."orderId" as order_id,
+                       o.seller,
+                       o.token,
+                       o."remainingAmount"::TEXT as remaining_amount,
+                       GREATEST(
+                           to_timestamp(o."createdAt"),
+                           (SELECT MAX(f."loggedAt") FROM order_feed f WHERE f."orderId" = o."orderId"),
+                           a."refreshedAt"
+                       ) as last_activity_at,
+                       a."staleNotifiedAt" as stale_notified_at
+                FROM orders o
+                LEFT JOIN order_activity a ON a."orderId" = o."orderId"
+                WHERE o."remainingAmount" > 0
+                AND ($1::TEXT IS NULL OR o.token = $1)
+            ) idle
+            WHERE idle.last_activity_at < $2
+            AND ($3::TIMESTAMPTZ IS NULL OR idle.stale_notified_at IS NULL OR idle.stale_notified_at < $3)
+            ORDER BY idle.last_activity_at
+            LIMIT $4
+            "#,
+            token.map(EthAddress::as_str),
+            stale_before,
+            notified_before,
+            limit
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(orders)
+    }
+
+    async fn mark_notified(&self, order_id: &str) -> DbResult<()> {
+        sqlx::query!(
+            r#"
+            INSERT INTO order_activity ("orderId", "staleNotifiedAt")
+            VALUES ($1, NOW())
+            ON CONFLICT ("orderId") DO UPDATE SET "staleNotifiedAt" = NOW()
+            "#,
+            order_id
+        )
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,106 @@
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use sqlx::PgPool;
+
+use super::DbResult;
+use super::models::DbOrderbookSnapshot;
+use crate::blockchain::address::EthAddress;
+
+/// Repository for time-bucketed order book snapshots
+#[async_trait]
+pub trait OrderbookSnapshotRepository: Send + Sync {
+    /// Aggregate the current book per token into `bucket` (overwriting any earlier
+    /// snapshot of the same bucket). Returns the number of tokens recorded.
+    async fn record(&self, bucket: DateTime<Utc>) -> DbResult<u64>;
+
+    /// Snapshots in [since, until), oldest first, optionally for one token
+    async fn history(
+        &self,
+        token: Option<&EthAddress>,
+        since: DateTime<Utc>,
+        until: DateTime<Utc>,
+        limit: i64,
+    ) -> DbResult<Vec<DbOrderbookSnapshot>>;
+}
+
+pub struct PostgresOrderbookSnapshotRepository {
+    pool: PgPool,
+}
+
+impl PostgresOrderbookSnapshotRepository {
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+}
+
+#[async_trait]
+impl OrderbookSnapshotRepository for PostgresOrderbookSnapshotRepository {
+    async fn record(&self, bucket: DateTime<Utc>) -> DbResult<u64> {
+        let result = sqlx::query!(
+            r#"
+            INSERT INTO orderbook_snapshots (
+                "bucket", "token", "orderCount", "totalLiquidity",
+                "bestRate", "worstRate", "weightedRate"
+            )
+            SELECT
+                $1,
+                token,
+                COUNT(*)::INTEGER,
+                SUM("remainingAmount"),
+                MIN("exchangeRate"),
+                MAX("exchangeRate"),
+                ROUND(SUM("exchangeRate" * "remainingAmount") / SUM("remainingAmount"))
+            FROM orders
+            WHERE "remainingAmount" > 0
+            GROUP BY token
+            ON CONFLICT ("bucket", "token") DO UPDATE
+            SET "orderCount" = EXCLUDED."orderCount",
+                "totalLiquidity" = EXCLUDED."totalLiquidity",
+                "bestRate" = EXCLUDED."bestRate",
+                "worstRate" = EXCLUDED."worstRate",
+                "weightedRate" = EXCLUDED."weightedRate",
+                "recordedAt" = NOW()
+            "#,
+            bucket
+        )
+        .execute(&self.pool)
+        .await?;
+
+        Ok(result.rows_affected())
+    }
+
+    async fn history(
+        &self,
+        token: Option<&EthAddress>,
+        since: DateTime<Utc>,
+        until: DateTime<Utc>,
+        limit: i64,
+    ) -> DbResult<Vec<DbOrderbookSnapshot>> {
+        let snapshots = sqlx::query_as!(
+            DbOrderbookSnapshot,
+            r#"
+            SELECT
+                "bucket",
+                "token" as "token: EthAddress",
+                "orderCount" as order_count,
+                "totalLiquidity"::TEXT as "total_liquidity!",
+                "bestRate"::TEXT as "best_rate!",
+                "worstRate"::TEXT as "worst_rate!",
+                "weightedRate"::TEXT as "weighted_rate!"
+            FROM orderbook_snapshots
+            WHERE "bucket" >= $1 AND "bucket" < $2
+            AND ($3::TEXT IS NULL OR "token" = $3)
+            ORDER BY "bucket" ASC, "token" ASC
+            LIMIT $4
+            "#,
+            since,
+            until,
+            token.map(EthAddress::as_str),
+            limit
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(snapshots)
+    }
+}
@@ -1,12 +1,15 @@
 use async_trait::async_trait;
-use sqlx::PgPool;
+use sqlx::{PgConnection, PgPool, Postgres};
 use rust_decimal::Decimal;
+use std::fmt;
 use std::str::FromStr;
 
 use super::{DbError, DbResult};
-use super::models::DbOrder;
+use crate::blockchain::address::EthAddress;
+use super::models::{DbOrder, DbSellerReputation};
+use super::token_status::TokenPolicy;
 
-/// Repository for Order operations - ONLY methods needed for event sync
+/// Repository for Order operations: writes from event sync, reads for the API
 #[async_trait]
 pub trait OrderRepository: Send + Sync {
     /// Insert new order from OrderCreatedAndLocked event
@@ -17,6 +20,94 @@ pub trait OrderRepository: Send + Sync {
     /// Positive delta: add funds back (e.g. TradeExpired)
     /// Negative delta: subtract funds (e.g. OrderPartiallyWithdrawn, TradeCreated)
     async fn adjust_remaining_amount(&self, order_id: &str, delta: &str) -> DbResult<()>;
+    
+    /// Apply the order events of one transaction (a bulk create/withdraw batch) in a
+    /// single database transaction, in log order
+    async fn apply_batch(&self, changes: &[OrderChange]) -> DbResult<()>;
+    
+    /// Active orders (remainingAmount > 0) of tokens `policy` doesn't suspend, best rate first
+    async fn get_active_orders(&self, limit: Option<i64>, policy: &TokenPolicy) -> DbResult<Vec<DbOrder>>;
+    
+    /// Active orders of one token in matching priority
+    async fn get_active_orders_by_token(&self, token: &EthAddress, limit: Option<i64>, priority: OrderPriority) -> DbResult<Vec<DbOrder>>;
+    
+    /// Single order by ID (OrderNotFound if unknown)
+    async fn get(&self, order_id: &str) -> DbResult<DbOrder>;
+    
+    /// A seller's orders, newest first
+    async fn get_by_seller(&self, seller: &EthAddress) -> DbResult<Vec<DbOrder>>;
+    
+    /// Set the seller's fill limits; false if `signed_at` isn't newer than the stored one
+    async fn set_fill_limits(&self, order_id: &str, min_fill: Option<Decimal>, lot_size: Option<Decimal>, signed_at: i64) -> DbResult<bool>;
+    
+    /// Settled and expired trade counts of `sellers` (sellers without trades are left out)
+    async fn seller_reputations(&self, sellers: &[EthAddress]) -> DbResult<Vec<DbSellerReputation>>;
+}
+
+/// An order event to apply as part of a batch
+#[derive(Debug, Clone)]
+pub enum OrderChange {
+    /// OrderCreatedAndLocked
+    Create(DbOrder),
+    /// OrderPartiallyWithdrawn (amount in token base units)
+    Withdraw { order_id: String, amount: String },
+    /// Remaining amount change of a trade: negative for TradeCreated, positive for TradeExpired
+    Adjust { order_id: String, delta: String },
+}
+
+/// How orders at the same exchange rate are prioritized when matching
+/// Price always comes first: a buyer is never matched at a worse rate while a better one
+/// has liquidity. The policy only orders the orders within one price level.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum OrderPriority {
+    /// Oldest order first
+    #[default]
+    PriceTime,
+    /// Largest remaining amount first (fewer, bigger fills)
+    PriceSize,
+    /// Split across the level in proportion to remaining amounts (allocated by matching)
+    ProRata,
+    /// Sellers whose trades settle rather than expire first, then oldest
+    Reputation,
+}
+
+impl OrderPriority {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            OrderPriority::PriceTime => "price_time",
+            OrderPriority::PriceSize => "price_size",
+            OrderPriority::ProRata => "pro_rata",
+            OrderPriority::Reputation => "reputation",
+        }
+    }
+
+    /// ORDER_PRIORITY (default price_time)
+    pub fn from_env() -> Result<Self, String> {
+        match std::env::var("ORDER_PRIORITY") {
+            Ok(value) => value.parse(),
+            Err(_) => Ok(Self::default()),
+        }
+    }
+}
+
+impl fmt::Display for OrderPriority {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for OrderPriority {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_ascii_lowercase().replace('-', "_").as_str() {
+            "price_time" => Ok(OrderPriority::PriceTime),
+            "price_size" => Ok(OrderPriority::PriceSize),
+            "pro_rata" => Ok(OrderPriority::ProRata),
+            "reputation" => Ok(OrderPriority::Reputation),
+            other => Err(format!("Unknown order priority: {} (expected price_time, price_size, pro_rata or reputation)", other)),
+        }
+    }
 }
 
 pub struct PostgresOrderRepository {
@@ -27,108 +118,233 @@ impl PostgresOrderRepository {
     pub fn new(pool: PgPool) -> Self {
         Self { pool }
     }
+}
+
+/// Insert an order (no-op if it's already synced)
+async fn insert_order<'c, E: sqlx::Executor<'c, Database = Postgres>>(executor: E, order: &DbOrder) -> DbResult<()> {
+    sqlx::query!(
+        r#"
+        INSERT INTO orders (
+            "orderId", "seller", "token", "totalAmount", "remainingAmount",
+            "exchangeRate", "alipayId", "alipayName", "createdAt", "alipayIdFormat"
+        )
+        VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10)
+        ON CONFLICT ("orderId") DO NOTHING
+        "#,
+        order.order_id,
+        order.seller.as_str(),
+        order.token.as_str(),
+        Decimal::from_str(&order.total_amount).unwrap(),
+        Decimal::from_str(&order.remaining_amount).unwrap(),
+        Decimal::from_str(&order.exchange_rate).unwrap(),
+        order.alipay_id,
+        order.alipay_name,
+        order.created_at,
+        order.alipay_id_format
+    )
+    .execute(executor)
+    .await?;
+    
+    Ok(())
+}
+
+/// Add `delta` (negative to subtract) to an order's remaining amount
+async fn add_remaining_amount<'c, E: sqlx::Executor<'c, Database = Postgres>>(executor: E, order_id: &str, delta: &str) -> DbResult<()> {
+    let delta_decimal = Decimal::from_str(delta)
+        .map_err(|e| DbError::InvalidInput(format!("Invalid delta: {}", e)))?;
+    
+    let result = sqlx::query!(
+        r#"
+        UPDATE orders 
+        SET "remainingAmount" = "remainingAmount" + $1
+        WHERE "orderId" = $2
+        "#,
+        delta_decimal,
+        order_id
+    )
+    .execute(executor)
+    .await?;
+
+    if result.rows_affected() == 0 {
+        return Err(DbError::OrderNotFound(order_id.to_string()));
+    }
+
+    Ok(())
+}
+
+/// Apply order changes in log order on `conn` (a transaction of the caller's)
+pub(super) async fn apply_changes(conn: &mut PgConnection, changes: &[OrderChange]) -> DbResult<()> {
+    for change in changes {
+        match change {
+            OrderChange::Create(order) => insert_order(&mut *conn, order).await?,
+            OrderChange::Withdraw { order_id, amount } => {
+                add_remaining_amount(&mut *conn, order_id, &format!("-{}", amount)).await?
+            }
+            OrderChange::Adjust { order_id, delta } => add_remaining_amount(&mut *conn, order_id, delta).await?,
+        }
+    }
+    Ok(())
+}
+
+#[async_trait]
+impl OrderRepository for PostgresOrderRepository {
+    async fn create(&self, order: &DbOrder) -> DbResult<()> {
+        insert_order(&self.pool, order).await
+    }
+
+    async fn adjust_remaining_amount(&self, order_id: &str, delta: &str) -> DbResult<()> {
+        add_remaining_amount(&self.pool, order_id, delta).await
+    }
+
+    async fn apply_batch(&self, changes: &[OrderChange]) -> DbResult<()> {
+        let mut tx = self.pool.begin().await?;
+        apply_changes(&mut tx, changes).await?;
+        tx.commit().await?;
+        
+        Ok(())
+    }
     
     /// Get all active orders (remainingAmount > 0) sorted by exchange rate
-    /// Used by API for matching and order list queries
-    pub async fn get_active_orders(&self, limit: Option<i64>) -> DbResult<Vec<DbOrder>> {
+    /// Used by API for matching and order list queries; orders of tokens the policy
+    /// suspends, and orders paying out to blocklisted Alipay accounts or of flagged sellers, are left out
+    /// (sorts on the qualified column: a bare "exchangeRate" would bind to the ::TEXT output
+    /// alias and sort as text, and a CAST would keep idx_orders_active_rate from serving it)
+    async fn get_active_orders(&self, limit: Option<i64>, policy: &TokenPolicy) -> DbResult<Vec<DbOrder>> {
         let limit = limit.unwrap_or(100);
+        let (allowed, denied) = policy.bind_lists();
         
-        // Use runtime query validation (no compile-time verification)
-        let rows = sqlx::query(
+        let rows = sqlx::query!(
             r#"
             SELECT 
                 "orderId",
-                seller,
-                token,
+                seller as "seller: EthAddress",
+                token as "token: EthAddress",
                 "totalAmount"::TEXT,
                 "remainingAmount"::TEXT,
                 "exchangeRate"::TEXT,
                 "alipayId",
                 "alipayName",
                 "createdAt",
-                "syncedAt"
+                "syncedAt",
+                "alipayIdFormat",
+                "minFill"::TEXT,
+                "lotSize"::TEXT
             FROM orders
             WHERE "remainingAmount" > 0
-            ORDER BY CAST("exchangeRate" AS NUMERIC) ASC, "createdAt" ASC
+            AND NOT (token = ANY($2))
+            AND (cardinality($3::TEXT[]) = 0 OR token = ANY($3))
+            AND NOT EXISTS (
+                SELECT 1 FROM alipay_blocklist b WHERE b."alipayId" = lower(btrim(orders."alipayId"))
+            )
+            AND NOT EXISTS (
+                SELECT 1 FROM address_screenings s WHERE s."address" = orders.seller AND s."flagged"
+            )
+            ORDER BY orders."exchangeRate" ASC, orders."createdAt" ASC
             LIMIT $1
-            "#
+            "#,
+            limit,
+            &denied,
+            &allowed
         )
-        .bind(limit)
         .fetch_all(&self.pool)
         .await?;
         
-        // Manually map to DbOrder structs
         let orders: Vec<DbOrder> = rows
             .into_iter()
-            .map(|row| {
-                use sqlx::Row;
-                DbOrder {
-                    order_id: row.get("orderId"),
-                    seller: row.get("seller"),
-                    token: row.get("token"),
-                    total_amount: row.get::<Option<String>, _>("totalAmount").unwrap_or_default(),
-                    remaining_amount: row.get::<Option<String>, _>("remainingAmount").unwrap_or_default(),
-                    exchange_rate: row.get::<Option<String>, _>("exchangeRate").unwrap_or_default(),
-                    alipay_id: row.get("alipayId"),
-                    alipay_name: row.get("alipayName"),
-                    created_at: row.get("createdAt"),
-                    synced_at: row.get("syncedAt"),
-                }
+            .map(|row| DbOrder {
+                order_id: row.orderId,
+                seller: row.seller,
+                token: row.token,
+                total_amount: row.totalAmount.unwrap_or_default(),
+                remaining_amount: row.remainingAmount.unwrap_or_default(),
+                exchange_rate: row.exchangeRate.unwrap_or_default(),
+                alipay_id: row.alipayId,
+                alipay_name: row.alipayName,
+                created_at: row.createdAt,
+                synced_at: row.syncedAt,
+                alipay_id_format: row.alipayIdFormat,
+                min_fill: row.minFill,
+                lot_size: row.lotSize,
             })
             .collect();
         
         Ok(orders)
     }
     
-    /// Get active orders filtered by token address (case-insensitive)
-    /// Used by API for token-specific matching
-    pub async fn get_active_orders_by_token(&self, token_address: &str, limit: Option<i64>) -> DbResult<Vec<DbOrder>> {
+    /// Get active orders filtered by token address, best rate first and each price
+    /// level in `priority` order (pro-rata levels come oldest first)
+    /// Used by API for token-specific matching (blocklisted Alipay accounts and flagged sellers are left out)
+    /// Reputation is (settled + 1) / (settled + expired + 2) over the seller's trades, so
+    /// sellers without history rank in the middle.
+    async fn get_active_orders_by_token(&self, token: &EthAddress, limit: Option<i64>, priority: OrderPriority) -> DbResult<Vec<DbOrder>> {
         let limit = limit.unwrap_or(100);
-        let token_lower = token_address.to_lowercase();
         
-        // Use runtime query validation (no compile-time verification)
-        let rows = sqlx::query(
+        let rows = sqlx::query!(
             r#"
             SELECT 
-                "orderId",
-                seller,
-                token,
-                "totalAmount"::TEXT,
-                "remainingAmount"::TEXT,
-                "exchangeRate"::TEXT,
-                "alipayId",
-                "alipayName",
-                "createdAt",
-                "syncedAt"
+                orders."orderId",
+                orders.seller as "seller: EthAddress",
+                orders.token as "token: EthAddress",
+                orders."totalAmount"::TEXT,
+                orders."remainingAmount"::TEXT,
+                orders."exchangeRate"::TEXT,
+                orders."alipayId",
+                orders."alipayName",
+                orders."createdAt",
+                orders."syncedAt",
+                orders."alipayIdFormat",
+                orders."minFill"::TEXT,
+                orders."lotSize"::TEXT
             FROM orders
-            WHERE "remainingAmount" > 0
-            AND LOWER(token) = $1
-            ORDER BY CAST("exchangeRate" AS NUMERIC) ASC, "createdAt" ASC
+            LEFT JOIN (
+                SELECT o.seller,
+                       COUNT(*) FILTER (WHERE t."status" = 1) AS settled,
+                       COUNT(*) FILTER (WHERE t."status" = 2) AS expired
+                FROM trades t
+                JOIN orders o ON o."orderId" = t."orderId"
+                WHERE $3 = 'reputation'
+                GROUP BY o.seller
+            ) reputation ON reputation.seller = orders.seller
+            WHERE orders."remainingAmount" > 0
+            AND orders.token = $1
+            AND NOT EXISTS (
+                SELECT 1 FROM alipay_blocklist b WHERE b."alipayId" = lower(btrim(orders."alipayId"))
+            )
+            AND NOT EXISTS (
+                SELECT 1 FROM address_screenings s WHERE s."address" = orders.seller AND s."flagged"
+            )
+            ORDER BY orders."exchangeRate" ASC,
+                     CASE WHEN $3 = 'price_size' THEN orders."remainingAmount" END DESC NULLS LAST,
+                     CASE WHEN $3 = 'reputation'
+                          THEN (COALESCE(reputation.settled, 0) + 1)::FLOAT8
+                               / (COALESCE(reputation.settled, 0) + COALESCE(reputation.expired, 0) + 2)
+                     END DESC NULLS LAST,
+                     orders."createdAt" ASC
             LIMIT $2
-            "#
+            "#,
+            token.as_str(),
+            limit,
+            priority.as_str()
         )
-        .bind(&token_lower)
-        .bind(limit)
         .fetch_all(&self.pool)
         .await?;
         
-        // Manually map to DbOrder structs
         let orders: Vec<DbOrder> = rows
             .into_iter()
-            .map(|row| {
-                use sqlx::Row;
-                DbOrder {
-                    order_id: row.get("orderId"),
-                    seller: row.get("seller"),
-                    token: row.get("token"),
-                    total_amount: row.get::<Option<String>, _>("totalAmount").unwrap_or_default(),
-                    remaining_amount: row.get::<Option<String>, _>("remainingAmount").unwrap_or_default(),
-                    exchange_rate: row.get::<Option<String>, _>("exchangeRate").unwrap_or_default(),
-                    alipay_id: row.get("alipayId"),
-                    alipay_name: row.get("alipayName"),
-                    created_at: row.get("createdAt"),
-                    synced_at: row.get("syncedAt"),
-                }
+            .map(|row| DbOrder {
+                order_id: row.orderId,
+                seller: row.seller,
+                token: row.token,
+                total_amount: row.totalAmount.unwrap_or_default(),
+                remaining_amount: row.remainingAmount.unwrap_or_default(),
+                exchange_rate: row.exchangeRate.unwrap_or_default(),
+                alipay_id: row.alipayId,
+                alipay_name: row.alipayName,
+                created_at: row.createdAt,
+                synced_at: row.syncedAt,
+                alipay_id_format: row.alipayIdFormat,
+                min_fill: row.minFill,
+                lot_size: row.lotSize,
             })
             .collect();
         
@@ -136,21 +352,24 @@ impl PostgresOrderRepository {
     }
     
     /// Get single order by ID
-    pub async fn get(&self, order_id: &str) -> DbResult<DbOrder> {
+    async fn get(&self, order_id: &str) -> DbResult<DbOrder> {
         let order = sqlx::query_as!(
             DbOrder,
             r#"
             SELECT 
                 "orderId" as "order_id!",
-                seller as "seller!",
-                token as "token!",
+                seller as "seller!: EthAddress",
+                token as "token!: EthAddress",
                 "totalAmount"::TEXT as "total_amount!",
                 "remainingAmount"::TEXT as "remaining_amount!",
                 "exchangeRate"::TEXT as "exchange_rate!",
                 "alipayId" as "alipay_id!",
                 "alipayName" as "alipay_name!",
                 "createdAt" as "created_at!",
-                "syncedAt" as "synced_at!"
+                "syncedAt" as "synced_at!",
+                "alipayIdFormat" as "alipay_id_format!",
+                "minFill"::TEXT as min_fill,
+                "lotSize"::TEXT as lot_size
             FROM orders
             WHERE "orderId" = $1
             "#,
@@ -164,82 +383,76 @@ impl PostgresOrderRepository {
     }
     
     /// Get orders by seller
-    pub async fn get_by_seller(&self, seller: &str) -> DbResult<Vec<DbOrder>> {
+    async fn get_by_seller(&self, seller: &EthAddress) -> DbResult<Vec<DbOrder>> {
         let orders = sqlx::query_as!(
             DbOrder,
             r#"
             SELECT 
                 "orderId" as "order_id!",
-                seller as "seller!",
-                token as "token!",
+                seller as "seller!: EthAddress",
+                token as "token!: EthAddress",
                 "totalAmount"::TEXT as "total_amount!",
                 "remainingAmount"::TEXT as "remaining_amount!",
                 "exchangeRate"::TEXT as "exchange_rate!",
                 "alipayId" as "alipay_id!",
                 "alipayName" as "alipay_name!",
                 "createdAt" as "created_at!",
-                "syncedAt" as "synced_at!"
+                "syncedAt" as "synced_at!",
+                "alipayIdFormat" as "alipay_id_format!",
+                "minFill"::TEXT as min_fill,
+                "lotSize"::TEXT as lot_size
             FROM orders
             WHERE seller = $1
             ORDER BY "createdAt" DESC
             "#,
-            seller
+            seller.as_str()
         )
         .fetch_all(&self.pool)
         .await?;
         
         Ok(orders)
     }
-}
-
-#[async_trait]
-impl OrderRepository for PostgresOrderRepository {
-    async fn create(&self, order: &DbOrder) -> DbResult<()> {
-        sqlx::query!(
+    
+    /// Set the seller's fill limits (None clears a limit). `signed_at` must be newer
+    /// than the stored one; returns false if it isn't (stale or replayed signature)
+    async fn set_fill_limits(&self, order_id: &str, min_fill: Option<Decimal>, lot_size: Option<Decimal>, signed_at: i64) -> DbResult<bool> {
+        let result = sqlx::query!(
             r#"
-            INSERT INTO orders (
-                "orderId", "seller", "token", "totalAmount", "remainingAmount",
-                "exchangeRate", "alipayId", "alipayName", "createdAt"
-            )
-            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9)
-            ON CONFLICT ("orderId") DO NOTHING
+            UPDATE orders
+            SET "minFill" = $2, "lotSize" = $3, "fillLimitsSignedAt" = $4
+            WHERE "orderId" = $1
+            AND ("fillLimitsSignedAt" IS NULL OR "fillLimitsSignedAt" < $4)
             "#,
-            order.order_id,
-            order.seller,
-            order.token,
-            Decimal::from_str(&order.total_amount).unwrap(),
-            Decimal::from_str(&order.remaining_amount).unwrap(),
-            Decimal::from_str(&order.exchange_rate).unwrap(),
-            order.alipay_id,
-            order.alipay_name,
-            order.created_at
+            order_id,
+            min_fill,
+            lot_size,
+            signed_at
         )
         .execute(&self.pool)
         .await?;
         
-        Ok(())
+        Ok(result.rows_affected() > 0)
     }
-
-    async fn adjust_remaining_amount(&self, order_id: &str, delta: &str) -> DbResult<()> {
-        let delta_decimal = Decimal::from_str(delta)
-            .map_err(|e| DbError::InvalidInput(format!("Invalid delta: {}", e)))?;
-        
-        let result = sqlx::query!(
+    
+    /// Same counts as the reputation priority of get_active_orders_by_token
+    async fn seller_reputations(&self, sellers: &[EthAddress]) -> DbResult<Vec<DbSellerReputation>> {
+        let sellers: Vec<String> = sellers.iter().map(|s| s.as_str().to_string()).collect();
+        let reputations = sqlx::query_as!(
+            DbSellerReputation,
             r#"
-            UPDATE orders 
-            SET "remainingAmount" = "remainingAmount" + $1
-            WHERE "orderId" = $2
+            SELECT o.seller as "seller!: EthAddress",
+                   COUNT(*) FILTER (WHERE t."status" = 1) AS "settled_trades!",
+                   COUNT(*) FILTER (WHERE t."status" = 2) AS "expired_trades!"
+            FROM trades t
+            JOIN orders o ON o."orderId" = t."orderId"
+            WHERE o.seller = ANY($1)
+            GROUP BY o.seller
             "#,
-            delta_decimal,
-            order_id
+            &sellers
         )
-        .execute(&self.pool)
+        .fetch_all(&self.pool)
         .await?;
-
-        if result.rows_affected() == 0 {
-            return Err(DbError::OrderNotFound(order_id.to_string()));
-        }
-
-        Ok(())
+        
+        Ok(reputations)
     }
 }
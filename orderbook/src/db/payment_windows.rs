@@ -0,0 +1,157 @@
+use async_trait::async_trait;
+use ethers::types::U256;
+use sqlx::PgPool;
+
+use super::DbResult;
+use super::models::DbPaymentWindowTier;
+use crate::blockchain::address::EthAddress;
+
+/// Payment window tiers: the window a trade should get by token and value.
+/// fillOrder can't take a per-trade window, so the policy only informs buyers.
+#[derive(Debug, Clone, Default)]
+pub struct PaymentWindowPolicy {
+    pub tiers: Vec<DbPaymentWindowTier>,
+}
+
+impl PaymentWindowPolicy {
+    pub fn from_tiers(tiers: Vec<DbPaymentWindowTier>) -> Self {
+        Self { tiers }
+    }
+
+    /// Window of the highest tier at or below `value_cny` (cents); the token's own
+    /// tiers take precedence over the any-token ones. None without a matching tier.
+    pub fn window_for(&self, token: Option<&EthAddress>, value_cny: U256) -> Option<u64> {
+        let best = |matches: &dyn Fn(&DbPaymentWindowTier) -> bool| {
+            self.tiers
+                .iter()
+                .filter(|tier| matches(tier) && U256::from(tier.min_value_cny.max(0) as u64) <= value_cny)
+                .max_by_key(|tier| tier.min_value_cny)
+                .map(|tier| tier.window_secs.max(0) as u64)
+        };
+
+        token
+            .and_then(|token| best(&|tier| tier.token.as_ref() == Some(token)))
+            .or_else(|| best(&|tier| tier.token.is_none()))
+    }
+}
+
+/// Repository for the payment window tiers
+#[async_trait]
+pub trait PaymentWindowRepository: Send + Sync {
+    /// Set the window of a tier (replacing the tier's previous window)
+    async fn set(&self, token: Option<&EthAddress>, min_value_cny: i64, window_secs: i64, updated_by: Option<&str>) -> DbResult<DbPaymentWindowTier>;
+
+    /// Remove a tier; false if it doesn't exist
+    async fn remove(&self, token: Option<&EthAddress>, min_value_cny: i64) -> DbResult<bool>;
+
+    async fn list(&self) -> DbResult<Vec<DbPaymentWindowTier>>;
+
+    async fn policy(&self) -> DbResult<PaymentWindowPolicy> {
+        Ok(PaymentWindowPolicy::from_tiers(self.list().await?))
+    }
+}
+
+pub struct PostgresPaymentWindowRepository {
+    pool: PgPool,
+}
+
+impl PostgresPaymentWindowRepository {
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+}
+
+#[async_trait]
+impl PaymentWindowRepository for PostgresPaymentWindowRepository {
+    async fn set(&self, token: Option<&EthAddress>, min_value_cny: i64, window_secs: i64, updated_by: Option<&str>) -> DbResult<DbPaymentWindowTier> {
+        let tier = sqlx::query_as!(
+            DbPaymentWindowTier,
+            r#"
+            INSERT INTO payment_window_tiers ("token", "minValueCny", "windowSecs", "updatedBy")
+            VALUES ($1, $2, $3, $4)
+            ON CONFLICT (COALESCE("token", ''), "minValueCny") DO UPDATE
+            SET "windowSecs" = EXCLUDED."windowSecs", "updatedBy" = EXCLUDED."updatedBy", "updatedAt" = NOW()
+            RETURNING "id", "token" as "token: EthAddress", "minValueCny" as min_value_cny,
+                      "windowSecs" as window_secs, "updatedBy" as updated_by, "updatedAt" as updated_at
+            "#,
+            token.map(EthAddress::as_str),
+            min_value_cny,
+            window_secs,
+            updated_by
+        )
+        .fetch_one(&self.pool)
+        .await?;
+
+        Ok(tier)
+    }
+
+    async fn remove(&self, token: Option<&EthAddress>, min_value_cny: i64) -> DbResult<bool> {
+        let result = sqlx::query!(
+            r#"
+            DELETE FROM payment_window_tiers
+            WHERE COALESCE("token", '') = COALESCE($1, '') AND "minValueCny" = $2
+            "#,
+            token.map(EthAddress::as_str),
+            min_value_cny
+        )
+        .execute(&self.pool)
+        .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    async fn list(&self) -> DbResult<Vec<DbPaymentWindowTier>> {
+        let tiers = sqlx::query_as!(
+            DbPaymentWindowTier,
+            r#"
+            SELECT "id", "token" as "token: EthAddress", "minValueCny" as min_value_cny,
+                   "windowSecs" as window_secs, "updatedBy" as updated_by, "updatedAt" as updated_at
+            FROM payment_window_tiers
+            ORDER BY "token" NULLS FIRST, "minValueCny"
+            "#
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(tiers)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Utc;
+
+    fn tier(token: Option<&str>, min_value_cny: i64, window_secs: i64) -> DbPaymentWindowTier {
+        DbPaymentWindowTier {
+            id: 0,
+            token: token.map(|t| t.parse().unwrap()),
+            min_value_cny,
+            window_secs,
+            updated_by: None,
+            updated_at: Utc::now(),
+        }
+    }
+
+    #[test]
+    fn test_payment_window_policy() {
+        let usdc = "0x036cbd53842c5426634e7929541ec2318f3dcf7e";
+        let other: EthAddress = "0x2222222222222222222222222222222222222222".parse().unwrap();
+        let policy = PaymentWindowPolicy::from_tiers(vec![
+            tier(None, 0, 900),
+            tier(None, 500_000, 1800),
+            tier(Some(usdc), 1_000_000, 3600),
+        ]);
+        let usdc: EthAddress = usdc.parse().unwrap();
+
+        assert_eq!(policy.window_for(Some(&other), U256::from(10_000)), Some(900));
+        assert_eq!(policy.window_for(Some(&other), U256::from(2_000_000)), Some(1800));
+        assert_eq!(policy.window_for(None, U256::from(500_000)), Some(1800));
+
+        // The token's own tiers win once one applies
+        assert_eq!(policy.window_for(Some(&usdc), U256::from(2_000_000)), Some(3600));
+        assert_eq!(policy.window_for(Some(&usdc), U256::from(600_000)), Some(1800));
+
+        assert_eq!(PaymentWindowPolicy::default().window_for(None, U256::from(1)), None);
+    }
+}
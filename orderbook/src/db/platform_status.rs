@@ -0,0 +1,149 @@
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use sqlx::PgPool;
+
+use super::DbResult;
+use super::models::{DbProofLatency, DbTokenLiquidity, DbTokenSummary};
+use super::token_status::TokenPolicy;
+use crate::blockchain::address::EthAddress;
+
+/// Aggregates for the public status endpoint
+#[async_trait]
+pub trait PlatformStatusRepository: Send + Sync {
+    /// Active liquidity per tradable token (orders of blocklisted Alipay accounts and flagged sellers left out)
+    async fn liquidity(&self, policy: &TokenPolicy) -> DbResult<Vec<DbTokenLiquidity>>;
+
+    /// Per tradable token: active liquidity (as `liquidity`) and the trades whose
+    /// settlement became final since `settled_since`, in one query
+    async fn summary(&self, policy: &TokenPolicy, settled_since: DateTime<Utc>) -> DbResult<Vec<DbTokenSummary>>;
+
+    /// When the latest settlement reached its confirmation depth
+    async fn last_settlement_at(&self) -> DbResult<Option<DateTime<Utc>>>;
+
+    /// Proofs generated since `since`, with the mean time from PDF upload to proof
+    async fn proof_latency(&self, since: DateTime<Utc>) -> DbResult<DbProofLatency>;
+}
+
+pub struct PostgresPlatformStatusRepository {
+    pool: PgPool,
+}
+
+impl PostgresPlatformStatusRepository {
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+}
+
+#[async_trait]
+impl PlatformStatusRepository for PostgresPlatformStatusRepository {
+    async fn liquidity(&self, policy: &TokenPolicy) -> DbResult<Vec<DbTokenLiquidity>> {
+        let (allowed, denied) = policy.bind_lists();
+        let liquidity = sqlx::query_as!(
+            DbTokenLiquidity,
+            r#"
+            SELECT token as "token: EthAddress",
+                   COUNT(*) as "order_count!",
+                   SUM("remainingAmount")::TEXT as "total_liquidity!",
+                   MIN("exchangeRate")::TEXT as "best_rate!"
+            FROM orders
+            WHERE "remainingAmount" > 0
+            AND NOT (token = ANY($1))
+            AND (cardinality($2::TEXT[]) = 0 OR token = ANY($2))
+            AND NOT EXISTS (
+                SELECT 1 FROM alipay_blocklist b WHERE b."alipayId" = lower(btrim(orders."alipayId"))
+            )
+            AND NOT EXISTS (
+                SELECT 1 FROM address_screenings s WHERE s."address" = orders.seller AND s."flagged"
+            )
+            GROUP BY token
+            ORDER BY token
+            "#,
+            &denied,
+            &allowed
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(liquidity)
+    }
+
+    async fn summary(&self, policy: &TokenPolicy, settled_since: DateTime<Utc>) -> DbResult<Vec<DbTokenSummary>> {
+        let (allowed, denied) = policy.bind_lists();
+        let summary = sqlx::query_as!(
+            DbTokenSummary,
+            r#"
+            WITH book AS (
+                SELECT token,
+                       COUNT(*) as order_count,
+                       SUM("remainingAmount") as liquidity,
+                       MIN("exchangeRate") as best_rate
+                FROM orders
+                WHERE "remainingAmount" > 0
+                AND NOT EXISTS (
+                    SELECT 1 FROM alipay_blocklist b WHERE b."alipayId" = lower(btrim(orders."alipayId"))
+                )
+                AND NOT EXISTS (
+                    SELECT 1 FROM address_screenings s WHERE s."address" = orders.seller AND s."flagged"
+                )
+                GROUP BY token
+            ),
+            settled AS (
+                SELECT o.token,
+                       COUNT(*) as trade_count,
+                       SUM(t."tokenAmount") as volume,
+                       SUM(t."cnyAmount") as volume_cny
+                FROM trades t
+                JOIN orders o ON o."orderId" = t."orderId"
+                WHERE t."status" = 1 AND t."finalizedAt" >= $3
+                GROUP BY o.token
+            )
+            SELECT COALESCE(book.token, settled.token) as "token!: EthAddress",
+                   COALESCE(book.order_count, 0) as "active_orders!",
+                   COALESCE(book.liquidity, 0)::TEXT as "total_liquidity!",
+                   book.best_rate::TEXT as best_rate,
+                   COALESCE(settled.trade_count, 0) as "settled_trades_24h!",
+                   COALESCE(settled.volume, 0)::TEXT as "settled_volume_24h!",
+                   COALESCE(settled.volume_cny, 0)::TEXT as "settled_volume_24h_cny!"
+            FROM book
+            FULL OUTER JOIN settled ON settled.token = book.token
+            WHERE NOT (COALESCE(book.token, settled.token) = ANY($1))
+            AND (cardinality($2::TEXT[]) = 0 OR COALESCE(book.token, settled.token) = ANY($2))
+            ORDER BY 1
+            "#,
+            &denied,
+            &allowed,
+            settled_since
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(summary)
+    }
+
+    async fn last_settlement_at(&self) -> DbResult<Option<DateTime<Utc>>> {
+        let last = sqlx::query_scalar!(
+            r#"SELECT MAX("finalizedAt") FROM trades WHERE "status" = 1"#
+        )
+        .fetch_one(&self.pool)
+        .await?;
+
+        Ok(last)
+    }
+
+    async fn proof_latency(&self, since: DateTime<Utc>) -> DbResult<DbProofLatency> {
+        let latency = sqlx::query_as!(
+            DbProofLatency,
+            r#"
+            SELECT COUNT(*) as "proofs!",
+                   AVG(EXTRACT(EPOCH FROM (proof_generated_at - pdf_uploaded_at)))::FLOAT8 as avg_latency_secs
+            FROM trades
+            WHERE proof_generated_at >= $1 AND pdf_uploaded_at IS NOT NULL
+            "#,
+            since
+        )
+        .fetch_one(&self.pool)
+        .await?;
+
+        Ok(latency)
+    }
+}
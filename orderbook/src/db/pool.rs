@@ -0,0 +1,122 @@
+// Connection pool configuration
+// The pool used to be fixed at 10 connections shared by API requests and the event
+// listener, so a burst of API traffic could hold every connection while the listener
+// waited to persist a block. Each pool is now sized from its own env prefix, and the
+// listener (with the tasks that follow the chain) gets a pool of its own - see
+// api-server. Pool utilization is exported per pool (metrics::DbPoolMetrics).
+//
+// Configuration (env), for <prefix> DB_POOL (API) and DB_LISTENER_POOL (event listener):
+// - <prefix>_MAX_CONNECTIONS (default 10 / 4)
+// - <prefix>_MIN_CONNECTIONS (default 2 / 1, capped at the max)
+// - <prefix>_ACQUIRE_TIMEOUT_SECS: wait for a free connection (default 30)
+// - <prefix>_IDLE_TIMEOUT_SECS: close connections idle this long (default 600)
+// - <prefix>_MAX_LIFETIME_SECS: recycle connections this old (default 1800)
+// - <prefix>_STATEMENT_CACHE: prepared statements kept per connection (default 100)
+
+use sqlx::postgres::{PgConnectOptions, PgPool, PgPoolOptions};
+use std::time::Duration;
+
+use super::DbResult;
+
+/// Env prefix of the API pool
+pub const API_POOL: &str = "DB_POOL";
+/// Env prefix of the event listener's pool
+pub const LISTENER_POOL: &str = "DB_LISTENER_POOL";
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PoolConfig {
+    pub max_connections: u32,
+    pub min_connections: u32,
+    pub acquire_timeout: Duration,
+    pub idle_timeout: Duration,
+    pub max_lifetime: Duration,
+    pub statement_cache_capacity: usize,
+}
+
+impl Default for PoolConfig {
+    /// The API pool's defaults
+    fn default() -> Self {
+        Self {
+            max_connections: 10,
+            min_connections: 2,
+            acquire_timeout: Duration::from_secs(30),
+            idle_timeout: Duration::from_secs(600),
+            max_lifetime: Duration::from_secs(1800),
+            statement_cache_capacity: 100,
+        }
+    }
+}
+
+impl PoolConfig {
+    /// The listener pool's defaults: it runs one block at a time
+    pub fn listener() -> Self {
+        Self {
+            max_connections: 4,
+            min_connections: 1,
+            ..Self::default()
+        }
+    }
+
+    /// Override `defaults` with <prefix>_* variables (defaults kept when unset or invalid)
+    pub fn from_env(prefix: &str, defaults: Self) -> Self {
+        let var = |name: &str| std::env::var(format!("{}_{}", prefix, name)).ok();
+        let secs = |name: &str, default: Duration| {
+            var(name).and_then(|v| v.parse().ok()).map(Duration::from_secs).unwrap_or(default)
+        };
+
+        let max_connections = var("MAX_CONNECTIONS")
+            .and_then(|v| v.parse().ok())
+            .filter(|max| *max > 0)
+            .unwrap_or(defaults.max_connections);
+        let min_connections = var("MIN_CONNECTIONS")
+            .and_then(|v| v.parse().ok())
+            .unwrap_or(defaults.min_connections)
+            .min(max_connections);
+        Self {
+            max_connections,
+            min_connections,
+            acquire_timeout: secs("ACQUIRE_TIMEOUT_SECS", defaults.acquire_timeout),
+            idle_timeout: secs("IDLE_TIMEOUT_SECS", defaults.idle_timeout),
+            max_lifetime: secs("MAX_LIFETIME_SECS", defaults.max_lifetime),
+            statement_cache_capacity: var("STATEMENT_CACHE")
+                .and_then(|v| v.parse().ok())
+                .unwrap_or(defaults.statement_cache_capacity),
+        }
+    }
+
+    /// Open a pool with these settings
+    pub async fn connect(&self, options: PgConnectOptions) -> DbResult<PgPool> {
+        let pool = PgPoolOptions::new()
+            .max_connections(self.max_connections)
+            .min_connections(self.min_connections)
+            .acquire_timeout(self.acquire_timeout)
+            .idle_timeout(self.idle_timeout)
+            .max_lifetime(self.max_lifetime)
+            .connect_with(options.statement_cache_capacity(self.statement_cache_capacity))
+            .await?;
+
+        Ok(pool)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_pool_config_from_env() {
+        std::env::set_var("TEST_POOL_MAX_CONNECTIONS", "3");
+        std::env::set_var("TEST_POOL_MIN_CONNECTIONS", "5");
+        std::env::set_var("TEST_POOL_ACQUIRE_TIMEOUT_SECS", "soon");
+        std::env::set_var("TEST_POOL_STATEMENT_CACHE", "0");
+
+        let config = PoolConfig::from_env("TEST_POOL", PoolConfig::listener());
+        assert_eq!(config.max_connections, 3);
+        // Capped at the max
+        assert_eq!(config.min_connections, 3);
+        assert_eq!(config.acquire_timeout, Duration::from_secs(30));
+        assert_eq!(config.statement_cache_capacity, 0);
+
+        assert_eq!(PoolConfig::from_env("UNSET_POOL", PoolConfig::listener()), PoolConfig::listener());
+    }
+}
@@ -0,0 +1,92 @@
+// Contract logs the listener has applied (migrations/044_processed_events.sql)
+// A resync hands the listener logs it has seen before. Mirroring them again is harmless,
+// but amount adjustments, seller notices, fees and event counts must happen once per log:
+// the first claim of a log's (txHash, logIndex) applies them and later ones skip them.
+// The live listener prunes claims after a retention window far longer than any resync.
+
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use sqlx::{PgConnection, PgPool};
+
+use super::orders::{apply_changes, OrderChange};
+use super::DbResult;
+
+/// A log (the first of a bulk order transaction) as claimed by its handling
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EventKey {
+    pub event_type: String,
+    pub block_number: i64,
+    pub tx_hash: String,
+    pub log_index: i64,
+}
+
+#[async_trait]
+pub trait ProcessedEventRepository: Send + Sync {
+    /// Claim a log; false if it was claimed before (its side effects are done)
+    async fn claim(&self, key: &EventKey) -> DbResult<bool>;
+
+    /// Claim a log and, the first time, apply its order changes in the same transaction
+    /// (a failed change leaves the log unclaimed)
+    async fn claim_applying(&self, key: &EventKey, changes: &[OrderChange]) -> DbResult<bool>;
+
+    /// Delete claims made before `before`, returning how many
+    async fn prune(&self, before: DateTime<Utc>) -> DbResult<u64>;
+}
+
+pub struct PostgresProcessedEventRepository {
+    pool: PgPool,
+}
+
+impl PostgresProcessedEventRepository {
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+}
+
+async fn insert_claim(conn: &mut PgConnection, key: &EventKey) -> DbResult<bool> {
+    let result = sqlx::query!(
+        r#"
+        INSERT INTO processed_events ("txHash", "logIndex", "eventType", "blockNumber")
+        VALUES ($1, $2, $3, $4)
+        ON CONFLICT ("txHash", "logIndex") DO NOTHING
+        "#,
+        key.tx_hash,
+        key.log_index,
+        key.event_type,
+        key.block_number
+    )
+    .execute(conn)
+    .await?;
+
+    Ok(result.rows_affected() == 1)
+}
+
+#[async_trait]
+impl ProcessedEventRepository for PostgresProcessedEventRepository {
+    async fn claim(&self, key: &EventKey) -> DbResult<bool> {
+        let mut conn = self.pool.acquire().await?;
+        insert_claim(&mut conn, key).await
+    }
+
+    async fn claim_applying(&self, key: &EventKey, changes: &[OrderChange]) -> DbResult<bool> {
+        let mut tx = self.pool.begin().await?;
+        if !insert_claim(&mut tx, key).await? {
+            return Ok(false);
+        }
+        apply_changes(&mut tx, changes).await?;
+        tx.commit().await?;
+
+        Ok(true)
+    }
+
+    async fn prune(&self, before: DateTime<Utc>) -> DbResult<u64> {
+        let result = sqlx::query!(
+            r#"DELETE FROM processed_events WHERE "processedAt" < $1"#,
+            before
+        )
+        .execute(&self.pool)
+        .await?;
+
+        Ok(result.rows_affected())
+    }
+}
@@ -0,0 +1,127 @@
+use async_trait::async_trait;
+use sqlx::PgPool;
+
+use super::{DbError, DbResult};
+use super::models::DbProofSubmission;
+
+/// Submission states
+pub const SUBMISSION_SUBMITTING: &str = "submitting";
+pub const SUBMISSION_SUBMITTED: &str = "submitted";
+pub const SUBMISSION_FAILED: &str = "failed";
+
+/// How long a 'submitting' claim blocks other attempts before it can be taken over
+pub const PROOF_SUBMISSION_LEASE_SECS: i64 = 10 * 60;
+
+/// Repository for per-trade proof submission claims
+#[async_trait]
+pub trait ProofSubmissionRepository: Send + Sync {
+    /// Claim the trade for a submission. Succeeds when there is no claim yet, the last
+    /// attempt failed, or a 'submitting' claim is older than `lease_secs`; otherwise
+    /// returns the claim that blocks it.
+    async fn claim(&self, trade_id: &str, lease_secs: i64) -> DbResult<Result<(), DbProofSubmission>>;
+
+    /// Record the sent transaction of the current attempt
+    async fn set_tx_hash(&self, trade_id: &str, tx_hash: &str) -> DbResult<()>;
+
+    /// End the current attempt (submitted or failed)
+    async fn finish(&self, trade_id: &str, status: &str, tx_hash: Option<&str>) -> DbResult<()>;
+
+    async fn get(&self, trade_id: &str) -> DbResult<Option<DbProofSubmission>>;
+}
+
+pub struct PostgresProofSubmissionRepository {
+    pool: PgPool,
+}
+
+impl PostgresProofSubmissionRepository {
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+}
+
+#[async_trait]
+impl ProofSubmissionRepository for PostgresProofSubmissionRepository {
+    async fn claim(&self, trade_id: &str, lease_secs: i64) -> DbResult<Result<(), DbProofSubmission>> {
+        // The conditional upsert is atomic: of two concurrent claims exactly one gets a row back
+        let claimed = sqlx::query_scalar!(
+            r#"
+            INSERT INTO proof_submissions ("tradeId", "status")
+            VALUES ($1, 'submitting')
+            ON CONFLICT ("tradeId") DO UPDATE
+            SET "status" = 'submitting', "txHash" = NULL, "attempts" = proof_submissions."attempts" + 1,
+                "startedAt" = NOW(), "updatedAt" = NOW()
+            WHERE proof_submissions."status" = 'failed'
+               OR (proof_submissions."status" = 'submitting'
+                   AND proof_submissions."startedAt" < NOW() - $2::BIGINT * INTERVAL '1 second')
+            RETURNING "tradeId"
+            "#,
+            trade_id,
+            lease_secs
+        )
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(|e| match e {
+            sqlx::Error::Database(db) if db.is_foreign_key_violation() => DbError::TradeNotFound(trade_id.to_string()),
+            e => e.into(),
+        })?;
+
+        if claimed.is_some() {
+            return Ok(Ok(()));
+        }
+        let holder = self
+            .get(trade_id)
+            .await?
+            .ok_or_else(|| DbError::InvalidInput(format!("Proof submission claim for {} vanished", trade_id)))?;
+        Ok(Err(holder))
+    }
+
+    async fn set_tx_hash(&self, trade_id: &str, tx_hash: &str) -> DbResult<()> {
+        sqlx::query!(
+            r#"
+            UPDATE proof_submissions
+            SET "txHash" = $2, "updatedAt" = NOW()
+            WHERE "tradeId" = $1 AND "status" = 'submitting'
+            "#,
+            trade_id,
+            tx_hash
+        )
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    async fn finish(&self, trade_id: &str, status: &str, tx_hash: Option<&str>) -> DbResult<()> {
+        sqlx::query!(
+            r#"
+            UPDATE proof_submissions
+            SET "status" = $2, "txHash" = COALESCE($3, "txHash"), "updatedAt" = NOW()
+            WHERE "tradeId" = $1
+            "#,
+            trade_id,
+            status,
+            tx_hash
+        )
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    async fn get(&self, trade_id: &str) -> DbResult<Option<DbProofSubmission>> {
+        let submission = sqlx::query_as!(
+            DbProofSubmission,
+            r#"
+            SELECT "tradeId" as trade_id, "status", "txHash" as tx_hash, "attempts",
+                   "startedAt" as started_at, "updatedAt" as updated_at
+            FROM proof_submissions
+            WHERE "tradeId" = $1
+            "#,
+            trade_id
+        )
+        .fetch_optional(&self.pool)
+        .await?;
+
+        Ok(submission)
+    }
+}
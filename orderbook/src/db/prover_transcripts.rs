@@ -0,0 +1,103 @@
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use sqlx::PgPool;
+
+use super::DbResult;
+use super::models::DbProverTranscript;
+
+/// Prover job kinds
+pub const TRANSCRIPT_EXECUTION: &str = "execution";
+pub const TRANSCRIPT_PROOF: &str = "proof";
+
+/// Prover job outcomes
+pub const TRANSCRIPT_SUCCEEDED: &str = "succeeded";
+pub const TRANSCRIPT_FAILED: &str = "failed";
+
+/// A finished prover job to record
+#[derive(Debug, Clone)]
+pub struct NewProverTranscript {
+    pub trade_id: String,
+    pub kind: &'static str,
+    pub program_id: String,
+    pub axiom_id: Option<String>,
+    pub status: &'static str,
+    pub error: Option<String>,
+    pub entries: String,                    // JSON array
+    pub started_at: DateTime<Utc>,
+}
+
+/// Repository for prover transcripts
+#[async_trait]
+pub trait ProverTranscriptRepository: Send + Sync {
+    /// Record a finished prover job
+    async fn create(&self, transcript: &NewProverTranscript) -> DbResult<i64>;
+
+    /// A trade's transcripts, newest first
+    async fn list_by_trade(&self, trade_id: &str, limit: i64) -> DbResult<Vec<DbProverTranscript>>;
+}
+
+pub struct PostgresProverTranscriptRepository {
+    pool: PgPool,
+}
+
+impl PostgresProverTranscriptRepository {
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+}
+
+#[async_trait]
+impl ProverTranscriptRepository for PostgresProverTranscriptRepository {
+    async fn create(&self, transcript: &NewProverTranscript) -> DbResult<i64> {
+        let id = sqlx::query_scalar!(
+            r#"
+            INSERT INTO prover_transcripts (
+                "tradeId", "kind", "programId", "axiomId", "status", "error", "entries", "startedAt"
+            )
+            VALUES ($1, $2, $3, $4, $5, $6, $7::TEXT::JSONB, $8)
+            RETURNING "id"
+            "#,
+            transcript.trade_id,
+            transcript.kind,
+            transcript.program_id,
+            transcript.axiom_id,
+            transcript.status,
+            transcript.error,
+            transcript.entries,
+            transcript.started_at
+        )
+        .fetch_one(&self.pool)
+        .await?;
+
+        Ok(id)
+    }
+
+    async fn list_by_trade(&self, trade_id: &str, limit: i64) -> DbResult<Vec<DbProverTranscript>> {
+        let transcripts = sqlx::query_as!(
+            DbProverTranscript,
+            r#"
+            SELECT
+                "id",
+                "tradeId" as trade_id,
+                "kind",
+                "programId" as program_id,
+                "axiomId" as axiom_id,
+                "status",
+                "error",
+                "entries"::TEXT as "entries!",
+                "startedAt" as started_at,
+                "finishedAt" as finished_at
+            FROM prover_transcripts
+            WHERE "tradeId" = $1
+            ORDER BY "startedAt" DESC, "id" DESC
+            LIMIT $2
+            "#,
+            trade_id,
+            limit
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(transcripts)
+    }
+}
@@ -0,0 +1,88 @@
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use sqlx::PgPool;
+
+use super::DbResult;
+use super::models::DbProverUsageSummary;
+
+/// A prover job Axiom accepted
+#[derive(Debug, Clone)]
+pub struct NewProverUsage {
+    pub trade_id: String,
+    pub kind: &'static str,                 // TRANSCRIPT_EXECUTION | TRANSCRIPT_PROOF
+    pub axiom_id: String,
+    pub credits: i64,
+    pub reported_remaining: Option<i64>,
+}
+
+/// Repository for prover credit usage
+#[async_trait]
+pub trait ProverUsageRepository: Send + Sync {
+    /// Record an accepted job
+    async fn record(&self, usage: &NewProverUsage) -> DbResult<()>;
+
+    /// Jobs and credits since `since`, with the latest balance Axiom reported in that time
+    async fn summary(&self, since: DateTime<Utc>) -> DbResult<DbProverUsageSummary>;
+}
+
+pub struct PostgresProverUsageRepository {
+    pool: PgPool,
+}
+
+impl PostgresProverUsageRepository {
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+}
+
+#[async_trait]
+impl ProverUsageRepository for PostgresProverUsageRepository {
+    async fn record(&self, usage: &NewProverUsage) -> DbResult<()> {
+        sqlx::query!(
+            r#"
+            INSERT INTO prover_usage ("tradeId", "kind", "axiomId", "credits", "reportedRemaining")
+            VALUES ($1, $2, $3, $4, $5)
+            "#,
+            usage.trade_id,
+            usage.kind,
+            usage.axiom_id,
+            usage.credits,
+            usage.reported_remaining
+        )
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    async fn summary(&self, since: DateTime<Utc>) -> DbResult<DbProverUsageSummary> {
+        let summary = sqlx::query_as!(
+            DbProverUsageSummary,
+            r#"
+            SELECT
+                COUNT(*) FILTER (WHERE "kind" = 'execution') AS "executions!",
+                COUNT(*) FILTER (WHERE "kind" = 'proof') AS "proofs!",
+                COALESCE(SUM("credits"), 0)::BIGINT AS "credits_used!",
+                (
+                    SELECT "reportedRemaining" FROM prover_usage
+                    WHERE "createdAt" >= $1 AND "reportedRemaining" IS NOT NULL
+                    ORDER BY "createdAt" DESC, "id" DESC
+                    LIMIT 1
+                ) AS reported_remaining,
+                (
+                    SELECT "createdAt" FROM prover_usage
+                    WHERE "createdAt" >= $1 AND "reportedRemaining" IS NOT NULL
+                    ORDER BY "createdAt" DESC, "id" DESC
+                    LIMIT 1
+                ) AS reported_at
+            FROM prover_usage
+            WHERE "createdAt" >= $1
+            "#,
+            since
+        )
+        .fetch_one(&self.pool)
+        .await?;
+
+        Ok(summary)
+    }
+}
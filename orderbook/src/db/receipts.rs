@@ -0,0 +1,68 @@
+use async_trait::async_trait;
+use sqlx::PgPool;
+
+use super::DbResult;
+use super::models::DbTradeReceipt;
+
+/// Repository for signed settlement receipts
+#[async_trait]
+pub trait ReceiptRepository: Send + Sync {
+    /// Store a signed receipt (first receipt wins - receipts are immutable once issued)
+    async fn create(&self, receipt: &DbTradeReceipt) -> DbResult<()>;
+
+    /// Get the receipt for a trade, if one has been issued
+    async fn get(&self, trade_id: &str) -> DbResult<Option<DbTradeReceipt>>;
+}
+
+pub struct PostgresReceiptRepository {
+    pool: PgPool,
+}
+
+impl PostgresReceiptRepository {
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+}
+
+#[async_trait]
+impl ReceiptRepository for PostgresReceiptRepository {
+    async fn create(&self, receipt: &DbTradeReceipt) -> DbResult<()> {
+        sqlx::query!(
+            r#"
+            INSERT INTO trade_receipts ("tradeId", "receipt_json", "signature", "signer", "issuedAt")
+            VALUES ($1, $2, $3, $4, $5)
+            ON CONFLICT ("tradeId") DO NOTHING
+            "#,
+            receipt.trade_id,
+            receipt.receipt_json,
+            receipt.signature,
+            receipt.signer,
+            receipt.issued_at
+        )
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    async fn get(&self, trade_id: &str) -> DbResult<Option<DbTradeReceipt>> {
+        let receipt = sqlx::query_as!(
+            DbTradeReceipt,
+            r#"
+            SELECT
+                "tradeId" as "trade_id!",
+                "receipt_json" as "receipt_json!",
+                "signature" as "signature!",
+                "signer" as "signer!",
+                "issuedAt" as "issued_at!"
+            FROM trade_receipts
+            WHERE "tradeId" = $1
+            "#,
+            trade_id
+        )
+        .fetch_optional(&self.pool)
+        .await?;
+
+        Ok(receipt)
+    }
+}
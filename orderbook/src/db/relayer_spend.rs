@@ -0,0 +1,154 @@
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use rust_decimal::Decimal;
+use sqlx::PgPool;
+use std::str::FromStr;
+
+use super::{DbError, DbResult};
+use super::models::{DbActionRelayerCost, DbTradeRelayerCost};
+
+/// Relayer actions gas is attributed to
+pub const ACTION_FILL_ORDER: &str = "fillOrder";
+pub const ACTION_SUBMIT_PROOF: &str = "submitPaymentProof";
+pub const ACTION_CANCEL_EXPIRED: &str = "cancelExpiredTrade";
+pub const ACTION_EAS_ATTEST: &str = "attest";
+
+/// A mined relayer transaction to record
+#[derive(Debug, Clone)]
+pub struct NewRelayerSpend {
+    pub tx_hash: String,
+    pub action: &'static str,
+    pub trade_id: Option<String>,
+    pub order_id: Option<String>,
+    pub relayer: String,
+    pub gas_used: String,             // decimal string
+    pub effective_gas_price: String,  // wei, decimal string
+    pub cost_wei: String,             // decimal string
+    pub reverted: bool,
+    pub block_number: Option<i64>,
+}
+
+/// Repository for relayer gas spend
+#[async_trait]
+pub trait RelayerSpendRepository: Send + Sync {
+    /// Record a relayer transaction (ignored if the tx hash is already recorded for the trade)
+    async fn create(&self, spend: &NewRelayerSpend) -> DbResult<()>;
+
+    /// Totals per action since `since`
+    async fn totals_by_action(&self, since: DateTime<Utc>) -> DbResult<Vec<DbActionRelayerCost>>;
+
+    /// Number of distinct trades with spend since `since`
+    async fn trade_count(&self, since: DateTime<Utc>) -> DbResult<i64>;
+
+    /// Totals per trade since `since`, most expensive first
+    async fn totals_by_trade(&self, since: DateTime<Utc>, limit: i64) -> DbResult<Vec<DbTradeRelayerCost>>;
+}
+
+pub struct PostgresRelayerSpendRepository {
+    pool: PgPool,
+}
+
+impl PostgresRelayerSpendRepository {
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+}
+
+fn decimal(name: &str, value: &str) -> DbResult<Decimal> {
+    Decimal::from_str(value).map_err(|e| DbError::InvalidInput(format!("Invalid {}: {}", name, e)))
+}
+
+#[async_trait]
+impl RelayerSpendRepository for PostgresRelayerSpendRepository {
+    async fn create(&self, spend: &NewRelayerSpend) -> DbResult<()> {
+        sqlx::query!(
+            r#"
+            INSERT INTO relayer_spend (
+                "txHash", "action", "tradeId", "orderId", "relayer",
+                "gasUsed", "effectiveGasPrice", "costWei", "reverted", "blockNumber"
+            )
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10)
+            ON CONFLICT ("txHash", COALESCE("tradeId", '')) DO NOTHING
+            "#,
+            spend.tx_hash,
+            spend.action,
+            spend.trade_id,
+            spend.order_id,
+            spend.relayer,
+            decimal("gas used", &spend.gas_used)?,
+            decimal("gas price", &spend.effective_gas_price)?,
+            decimal("cost", &spend.cost_wei)?,
+            spend.reverted,
+            spend.block_number
+        )
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    async fn totals_by_action(&self, since: DateTime<Utc>) -> DbResult<Vec<DbActionRelayerCost>> {
+        let totals = sqlx::query_as!(
+            DbActionRelayerCost,
+            r#"
+            SELECT
+                "action" as "action!",
+                COUNT(DISTINCT "txHash") as "tx_count!",
+                COUNT(DISTINCT "txHash") FILTER (WHERE "reverted") as "reverted_count!",
+                SUM("gasUsed")::TEXT as "gas_used!",
+                SUM("costWei")::TEXT as "cost_wei!"
+            FROM relayer_spend
+            WHERE "recordedAt" >= $1
+            GROUP BY "action"
+            ORDER BY "action"
+            "#,
+            since
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(totals)
+    }
+
+    async fn trade_count(&self, since: DateTime<Utc>) -> DbResult<i64> {
+        let count = sqlx::query_scalar!(
+            r#"
+            SELECT COUNT(DISTINCT "tradeId") as "count!"
+            FROM relayer_spend
+            WHERE "tradeId" IS NOT NULL AND "recordedAt" >= $1
+            "#,
+            since
+        )
+        .fetch_one(&self.pool)
+        .await?;
+
+        Ok(count)
+    }
+
+    async fn totals_by_trade(&self, since: DateTime<Utc>, limit: i64) -> DbResult<Vec<DbTradeRelayerCost>> {
+        // Cancels don't know the order; take it from the synced trade
+        let totals = sqlx::query_as!(
+            DbTradeRelayerCost,
+            r#"
+            SELECT
+                s."tradeId" as "trade_id!",
+                MAX(COALESCE(s."orderId", t."orderId")) as order_id,
+                COUNT(*) as "tx_count!",
+                SUM(s."gasUsed")::TEXT as "gas_used!",
+                SUM(s."costWei")::TEXT as "cost_wei!"
+            FROM relayer_spend s
+            LEFT JOIN trades t ON t."tradeId" = s."tradeId"
+            WHERE s."tradeId" IS NOT NULL AND s."recordedAt" >= $1
+            GROUP BY s."tradeId"
+            ORDER BY SUM(s."costWei") DESC
+            LIMIT $2
+            "#,
+            since,
+            limit
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(totals)
+    }
+}
@@ -0,0 +1,174 @@
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use sqlx::PgPool;
+
+use super::DbResult;
+use super::models::{DbDueReminder, DbSellerNotice};
+use crate::blockchain::address::EthAddress;
+
+/// Reminder kind for the pre-expiry payment reminder
+pub const REMINDER_EXPIRY: &str = "expiry";
+/// Notification kind for telling a seller about a new trade on their order
+pub const REMINDER_SELLER_TRADE: &str = "seller_trade";
+
+/// Repository for scheduled trade reminders
+#[async_trait]
+pub trait ReminderRepository: Send + Sync {
+    /// PENDING trades without an uploaded PDF expiring within (now, now + lead_secs]
+    /// that have not received a reminder of this kind yet
+    async fn list_due(&self, kind: &str, now: i64, lead_secs: i64, limit: i64) -> DbResult<Vec<DbDueReminder>>;
+
+    /// Record a delivered reminder
+    async fn mark_sent(&self, trade_id: &str, kind: &str) -> DbResult<()>;
+
+    /// The seller notice for a trade, unless one of this kind was already delivered
+    async fn get_seller_notice(&self, trade_id: &str, kind: &str) -> DbResult<Option<DbSellerNotice>>;
+
+    /// PENDING, unexpired trades synced before `synced_before` whose seller notice
+    /// of this kind hasn't been delivered
+    async fn list_seller_notices_due(
+        &self,
+        kind: &str,
+        now: i64,
+        synced_before: DateTime<Utc>,
+        limit: i64,
+    ) -> DbResult<Vec<DbSellerNotice>>;
+}
+
+pub struct PostgresReminderRepository {
+    pool: PgPool,
+}
+
+impl PostgresReminderRepository {
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+}
+
+#[async_trait]
+impl ReminderRepository for PostgresReminderRepository {
+    async fn list_due(&self, kind: &str, now: i64, lead_secs: i64, limit: i64) -> DbResult<Vec<DbDueReminder>> {
+        let due = sqlx::query_as!(
+            DbDueReminder,
+            r#"
+            SELECT
+                t."tradeId" as "trade_id!",
+                t."buyer" as "buyer!: EthAddress",
+                t."paymentNonce" as "payment_nonce!",
+                t."cnyAmount"::text as "cny_amount!",
+                t."expiresAt" as "expires_at!"
+            FROM trades t
+            WHERE t."status" = 0
+              AND t."pdf_file" IS NULL
+              AND t."expiresAt" > $2
+              AND t."expiresAt" <= $2 + $3
+              AND NOT EXISTS (
+                  SELECT 1 FROM trade_reminders r
+                  WHERE r."tradeId" = t."tradeId" AND r."kind" = $1
+              )
+            ORDER BY t."expiresAt" ASC
+            LIMIT $4
+            "#,
+            kind,
+            now,
+            lead_secs,
+            limit
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(due)
+    }
+
+    async fn mark_sent(&self, trade_id: &str, kind: &str) -> DbResult<()> {
+        sqlx::query!(
+            r#"
+            INSERT INTO trade_reminders ("tradeId", "kind")
+            VALUES ($1, $2)
+            ON CONFLICT ("tradeId", "kind") DO NOTHING
+            "#,
+            trade_id,
+            kind
+        )
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    async fn get_seller_notice(&self, trade_id: &str, kind: &str) -> DbResult<Option<DbSellerNotice>> {
+        let notice = sqlx::query_as!(
+            DbSellerNotice,
+            r#"
+            SELECT
+                t."tradeId" as "trade_id!",
+                t."orderId" as "order_id!",
+                o."seller" as "seller!: EthAddress",
+                t."buyer" as "buyer!: EthAddress",
+                o."token" as "token!: EthAddress",
+                t."tokenAmount"::text as "token_amount!",
+                t."cnyAmount"::text as "cny_amount!",
+                t."paymentNonce" as "payment_nonce!",
+                o."alipayId" as "alipay_id!",
+                t."expiresAt" as "expires_at!"
+            FROM trades t
+            JOIN orders o ON o."orderId" = t."orderId"
+            WHERE t."tradeId" = $1
+              AND NOT EXISTS (
+                  SELECT 1 FROM trade_reminders r
+                  WHERE r."tradeId" = t."tradeId" AND r."kind" = $2
+              )
+            "#,
+            trade_id,
+            kind
+        )
+        .fetch_optional(&self.pool)
+        .await?;
+
+        Ok(notice)
+    }
+
+    async fn list_seller_notices_due(
+        &self,
+        kind: &str,
+        now: i64,
+        synced_before: DateTime<Utc>,
+        limit: i64,
+    ) -> DbResult<Vec<DbSellerNotice>> {
+        let due = sqlx::query_as!(
+            DbSellerNotice,
+            r#"
+            SELECT
+                t."tradeId" as "trade_id!",
+                t."orderId" as "order_id!",
+                o."seller" as "seller!: EthAddress",
+                t."buyer" as "buyer!: EthAddress",
+                o."token" as "token!: EthAddress",
+                t."tokenAmount"::text as "token_amount!",
+                t."cnyAmount"::text as "cny_amount!",
+                t."paymentNonce" as "payment_nonce!",
+                o."alipayId" as "alipay_id!",
+                t."expiresAt" as "expires_at!"
+            FROM trades t
+            JOIN orders o ON o."orderId" = t."orderId"
+            WHERE t."status" = 0
+              AND t."expiresAt" > $2
+              AND t."syncedAt" <= $3
+              AND NOT EXISTS (
+                  SELECT 1 FROM trade_reminders r
+                  WHERE r."tradeId" = t."tradeId" AND r."kind" = $1
+              )
+            ORDER BY t."expiresAt" ASC
+            LIMIT $4
+            "#,
+            kind,
+            now,
+            synced_before,
+            limit
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(due)
+    }
+}
@@ -0,0 +1,163 @@
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use sqlx::PgPool;
+
+use super::DbResult;
+use super::models::{DbScreeningRefusal, DbScreeningVerdict};
+use crate::blockchain::address::EthAddress;
+
+/// Whose address was screened
+pub const ROLE_BUYER: &str = "buyer";
+pub const ROLE_SELLER: &str = "seller";
+
+/// Where service was refused
+pub const CONTEXT_EXECUTE_FILL: &str = "execute_fill";
+pub const CONTEXT_ORDER_SYNC: &str = "order_sync";
+
+/// A refusal to log
+#[derive(Debug, Clone)]
+pub struct NewScreeningRefusal<'a> {
+    pub address: &'a EthAddress,
+    pub role: &'static str,
+    pub context: &'static str,
+    pub reference: Option<&'a str>,
+    pub reason: Option<&'a str>,
+    pub provider: &'a str,
+}
+
+/// Repository for screening verdicts and refusals
+#[async_trait]
+pub trait ScreeningRepository: Send + Sync {
+    /// The address's verdict if it was screened at or after `since`
+    async fn verdict(&self, address: &EthAddress, since: DateTime<Utc>) -> DbResult<Option<DbScreeningVerdict>>;
+
+    /// Store a verdict (replacing the address's previous one)
+    async fn record_verdict(&self, address: &EthAddress, flagged: bool, reason: Option<&str>, provider: &str) -> DbResult<DbScreeningVerdict>;
+
+    /// Drop an address's verdict so the next screening asks the provider; false if there was none
+    async fn clear(&self, address: &EthAddress) -> DbResult<bool>;
+
+    /// Addresses currently flagged, latest first
+    async fn flagged(&self) -> DbResult<Vec<DbScreeningVerdict>>;
+
+    async fn record_refusal(&self, refusal: &NewScreeningRefusal<'_>) -> DbResult<DbScreeningRefusal>;
+
+    /// Latest refusals, newest first
+    async fn refusals(&self, limit: i64) -> DbResult<Vec<DbScreeningRefusal>>;
+}
+
+pub struct PostgresScreeningRepository {
+    pool: PgPool,
+}
+
+impl PostgresScreeningRepository {
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+}
+
+#[async_trait]
+impl ScreeningRepository for PostgresScreeningRepository {
+    async fn verdict(&self, address: &EthAddress, since: DateTime<Utc>) -> DbResult<Option<DbScreeningVerdict>> {
+        let verdict = sqlx::query_as!(
+            DbScreeningVerdict,
+            r#"
+            SELECT "address" as "address: EthAddress", "flagged", "reason", "provider", "screenedAt" as screened_at
+            FROM address_screenings
+            WHERE "address" = $1 AND "screenedAt" >= $2
+            "#,
+            address.as_str(),
+            since
+        )
+        .fetch_optional(&self.pool)
+        .await?;
+
+        Ok(verdict)
+    }
+
+    async fn record_verdict(&self, address: &EthAddress, flagged: bool, reason: Option<&str>, provider: &str) -> DbResult<DbScreeningVerdict> {
+        let verdict = sqlx::query_as!(
+            DbScreeningVerdict,
+            r#"
+            INSERT INTO address_screenings ("address", "flagged", "reason", "provider")
+            VALUES ($1, $2, $3, $4)
+            ON CONFLICT ("address") DO UPDATE
+            SET "flagged" = EXCLUDED."flagged", "reason" = EXCLUDED."reason",
+                "provider" = EXCLUDED."provider", "screenedAt" = NOW()
+            RETURNING "address" as "address: EthAddress", "flagged", "reason", "provider", "screenedAt" as screened_at
+            "#,
+            address.as_str(),
+            flagged,
+            reason,
+            provider
+        )
+        .fetch_one(&self.pool)
+        .await?;
+
+        Ok(verdict)
+    }
+
+    async fn clear(&self, address: &EthAddress) -> DbResult<bool> {
+        let result = sqlx::query!(r#"DELETE FROM address_screenings WHERE "address" = $1"#, address.as_str())
+            .execute(&self.pool)
+            .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    async fn flagged(&self) -> DbResult<Vec<DbScreeningVerdict>> {
+        let flagged = sqlx::query_as!(
+            DbScreeningVerdict,
+            r#"
+            SELECT "address" as "address: EthAddress", "flagged", "reason", "provider", "screenedAt" as screened_at
+            FROM address_screenings
+            WHERE "flagged"
+            ORDER BY "screenedAt" DESC
+            "#
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(flagged)
+    }
+
+    async fn record_refusal(&self, refusal: &NewScreeningRefusal<'_>) -> DbResult<DbScreeningRefusal> {
+        let refusal = sqlx::query_as!(
+            DbScreeningRefusal,
+            r#"
+            INSERT INTO screening_refusals ("address", "role", "context", "reference", "reason", "provider")
+            VALUES ($1, $2, $3, $4, $5, $6)
+            RETURNING "id", "address" as "address: EthAddress", "role", "context", "reference", "reason",
+                      "provider", "createdAt" as created_at
+            "#,
+            refusal.address.as_str(),
+            refusal.role,
+            refusal.context,
+            refusal.reference,
+            refusal.reason,
+            refusal.provider
+        )
+        .fetch_one(&self.pool)
+        .await?;
+
+        Ok(refusal)
+    }
+
+    async fn refusals(&self, limit: i64) -> DbResult<Vec<DbScreeningRefusal>> {
+        let refusals = sqlx::query_as!(
+            DbScreeningRefusal,
+            r#"
+            SELECT "id", "address" as "address: EthAddress", "role", "context", "reference", "reason",
+                   "provider", "createdAt" as created_at
+            FROM screening_refusals
+            ORDER BY "id" DESC
+            LIMIT $1
+            "#,
+            limit
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(refusals)
+    }
+}
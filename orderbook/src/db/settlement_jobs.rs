@@ -0,0 +1,261 @@
+use async_trait::async_trait;
+use sqlx::PgPool;
+use uuid::Uuid;
+
+use super::DbResult;
+use super::models::DbSettlementJob;
+
+/// Job states
+pub const JOB_RUNNING: &str = "running";
+pub const JOB_COMPLETED: &str = "completed";
+pub const JOB_FAILED: &str = "failed";
+
+/// How long a running job stays leased to its process without a renewal; once it
+/// lapses, another process may claim the job
+pub const JOB_LEASE_SECS: i64 = 120;
+
+/// Repository for settlement pipeline jobs
+#[async_trait]
+pub trait SettlementJobRepository: Send + Sync {
+    /// Start a job for a trade leased to `owner`, or return the one already running
+    /// (created = false)
+    async fn create_or_get_running(&self, trade_id: &str, owner: Uuid) -> DbResult<(DbSettlementJob, bool)>;
+
+    async fn get(&self, job_id: Uuid) -> DbResult<Option<DbSettlementJob>>;
+
+    /// Most recent job of a trade
+    async fn latest_for_trade(&self, trade_id: &str) -> DbResult<Option<DbSettlementJob>>;
+
+    /// Lease to `owner` the running jobs whose lease lapsed (their process stopped or
+    /// died); jobs another process is claiming at the same time are skipped
+    async fn claim_stale(&self, owner: Uuid) -> DbResult<Vec<DbSettlementJob>>;
+
+    /// Extend `owner`'s lease on a running job; false if the job is no longer its to run
+    async fn renew_lease(&self, job_id: Uuid, owner: Uuid) -> DbResult<bool>;
+
+    /// Persist progress to the next step
+    async fn set_step(&self, job_id: Uuid, step: &str) -> DbResult<()>;
+
+    async fn set_proof_id(&self, job_id: Uuid, proof_id: &str) -> DbResult<()>;
+
+    async fn complete(&self, job_id: Uuid, tx_hash: &str) -> DbResult<()>;
+
+    /// Mark failed, keeping the step so a resume retries it
+    async fn fail(&self, job_id: Uuid, error: &str) -> DbResult<()>;
+
+    /// Restart a failed job from its current step, leased to `owner`; false if it wasn't failed
+    async fn resume(&self, job_id: Uuid, owner: Uuid) -> DbResult<bool>;
+}
+
+pub struct PostgresSettlementJobRepository {
+    pool: PgPool,
+}
+
+impl PostgresSettlementJobRepository {
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+
+    async fn get_running(&self, trade_id: &str) -> DbResult<Option<DbSettlementJob>> {
+        let job = sqlx::query_as!(
+            DbSettlementJob,
+            r#"
+            SELECT "jobId" as job_id, "tradeId" as trade_id, status, step, attempts,
+                   "proofId" as proof_id, "txHash" as tx_hash, error,
+                   "createdAt" as created_at, "updatedAt" as updated_at, "completedAt" as completed_at
+            FROM settlement_jobs
+            WHERE "tradeId" = $1 AND status = 'running'
+            "#,
+            trade_id
+        )
+        .fetch_optional(&self.pool)
+        .await?;
+
+        Ok(job)
+    }
+}
+
+#[async_trait]
+impl SettlementJobRepository for PostgresSettlementJobRepository {
+    async fn create_or_get_running(&self, trade_id: &str, owner: Uuid) -> DbResult<(DbSettlementJob, bool)> {
+        let created = sqlx::query_as!(
+            DbSettlementJob,
+            r#"
+            INSERT INTO settlement_jobs ("jobId", "tradeId", "leaseOwner", "leaseExpiresAt")
+            VALUES ($1, $2, $3, NOW() + $4::BIGINT * INTERVAL '1 second')
+            ON CONFLICT ("tradeId") WHERE status = 'running' DO NOTHING
+            RETURNING "jobId" as job_id, "tradeId" as trade_id, status, step, attempts,
+                      "proofId" as proof_id, "txHash" as tx_hash, error,
+                      "createdAt" as created_at, "updatedAt" as updated_at, "completedAt" as completed_at
+            "#,
+            Uuid::new_v4(),
+            trade_id,
+            owner,
+            JOB_LEASE_SECS
+        )
+        .fetch_optional(&self.pool)
+        .await?;
+
+        if let Some(job) = created {
+            return Ok((job, true));
+        }
+
+        // Lost the race to a concurrent request - hand back its job
+        let running = self.get_running(trade_id).await?;
+        match running {
+            Some(job) => Ok((job, false)),
+            // It finished between the two statements; start a fresh one
+            None => self.create_or_get_running(trade_id, owner).await,
+        }
+    }
+
+    async fn get(&self, job_id: Uuid) -> DbResult<Option<DbSettlementJob>> {
+        let job = sqlx::query_as!(
+            DbSettlementJob,
+            r#"
+            SELECT "jobId" as job_id, "tradeId" as trade_id, status, step, attempts,
+                   "proofId" as proof_id, "txHash" as tx_hash, error,
+                   "createdAt" as created_at, "updatedAt" as updated_at, "completedAt" as completed_at
+            FROM settlement_jobs
+            WHERE "jobId" = $1
+            "#,
+            job_id
+        )
+        .fetch_optional(&self.pool)
+        .await?;
+
+        Ok(job)
+    }
+
+    async fn latest_for_trade(&self, trade_id: &str) -> DbResult<Option<DbSettlementJob>> {
+        let job = sqlx::query_as!(
+            DbSettlementJob,
+            r#"
+            SELECT "jobId" as job_id, "tradeId" as trade_id, status, step, attempts,
+                   "proofId" as proof_id, "txHash" as tx_hash, error,
+                   "createdAt" as created_at, "updatedAt" as updated_at, "completedAt" as completed_at
+            FROM settlement_jobs
+            WHERE "tradeId" = $1
+            ORDER BY "createdAt" DESC
+            LIMIT 1
+            "#,
+            trade_id
+        )
+        .fetch_optional(&self.pool)
+        .await?;
+
+        Ok(job)
+    }
+
+    async fn claim_stale(&self, owner: Uuid) -> DbResult<Vec<DbSettlementJob>> {
+        let mut jobs = sqlx::query_as!(
+            DbSettlementJob,
+            r#"
+            UPDATE settlement_jobs
+            SET "leaseOwner" = $1, "leaseExpiresAt" = NOW() + $2::BIGINT * INTERVAL '1 second'
+            WHERE "jobId" IN (
+                SELECT "jobId" FROM settlement_jobs
+                WHERE status = 'running' AND ("leaseExpiresAt" IS NULL OR "leaseExpiresAt" < NOW())
+                FOR UPDATE SKIP LOCKED
+            )
+            RETURNING "jobId" as job_id, "tradeId" as trade_id, status, step, attempts,
+                      "proofId" as proof_id, "txHash" as tx_hash, error,
+                      "createdAt" as created_at, "updatedAt" as updated_at, "completedAt" as completed_at
+            "#,
+            owner,
+            JOB_LEASE_SECS
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        jobs.sort_by_key(|job| job.created_at);
+        Ok(jobs)
+    }
+
+    async fn renew_lease(&self, job_id: Uuid, owner: Uuid) -> DbResult<bool> {
+        let result = sqlx::query!(
+            r#"
+            UPDATE settlement_jobs SET "leaseExpiresAt" = NOW() + $3::BIGINT * INTERVAL '1 second'
+            WHERE "jobId" = $1 AND "leaseOwner" = $2 AND status = 'running'
+            "#,
+            job_id,
+            owner,
+            JOB_LEASE_SECS
+        )
+        .execute(&self.pool)
+        .await?;
+
+        Ok(result.rows_affected() == 1)
+    }
+
+    async fn set_step(&self, job_id: Uuid, step: &str) -> DbResult<()> {
+        sqlx::query!(
+            r#"UPDATE settlement_jobs SET step = $2, "updatedAt" = NOW() WHERE "jobId" = $1"#,
+            job_id,
+            step
+        )
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    async fn set_proof_id(&self, job_id: Uuid, proof_id: &str) -> DbResult<()> {
+        sqlx::query!(
+            r#"UPDATE settlement_jobs SET "proofId" = $2, "updatedAt" = NOW() WHERE "jobId" = $1"#,
+            job_id,
+            proof_id
+        )
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    async fn complete(&self, job_id: Uuid, tx_hash: &str) -> DbResult<()> {
+        sqlx::query!(
+            r#"
+            UPDATE settlement_jobs
+            SET status = 'completed', step = 'done', "txHash" = $2, error = NULL,
+                "updatedAt" = NOW(), "completedAt" = NOW()
+            WHERE "jobId" = $1
+            "#,
+            job_id,
+            tx_hash
+        )
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    async fn fail(&self, job_id: Uuid, error: &str) -> DbResult<()> {
+        sqlx::query!(
+            r#"UPDATE settlement_jobs SET status = 'failed', error = $2, "updatedAt" = NOW() WHERE "jobId" = $1"#,
+            job_id,
+            error
+        )
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    async fn resume(&self, job_id: Uuid, owner: Uuid) -> DbResult<bool> {
+        let result = sqlx::query!(
+            r#"
+            UPDATE settlement_jobs
+            SET status = 'running', error = NULL, attempts = attempts + 1, "updatedAt" = NOW(),
+                "leaseOwner" = $2, "leaseExpiresAt" = NOW() + $3::BIGINT * INTERVAL '1 second'
+            WHERE "jobId" = $1 AND status = 'failed'
+            "#,
+            job_id,
+            owner,
+            JOB_LEASE_SECS
+        )
+        .execute(&self.pool)
+        .await?;
+
+        Ok(result.rows_affected() == 1)
+    }
+}
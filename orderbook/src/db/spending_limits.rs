@@ -0,0 +1,177 @@
+use async_trait::async_trait;
+use chrono::Utc;
+use sqlx::PgPool;
+
+use super::DbResult;
+use super::models::{DbBuyerTier, DbBuyerVolume, DbSpendingLimitTier};
+use crate::blockchain::address::EthAddress;
+
+/// Who put a buyer in their tier
+pub const SOURCE_ADMIN: &str = "admin";
+pub const SOURCE_KYC: &str = "kyc";
+
+/// Repository for spending limit tiers and buyer tier assignments
+#[async_trait]
+pub trait SpendingLimitRepository: Send + Sync {
+    /// Set the caps of a tier (replacing its previous caps)
+    async fn set_tier(&self, tier: i32, daily_limit_cny: Option<i64>, monthly_limit_cny: Option<i64>, updated_by: Option<&str>) -> DbResult<DbSpendingLimitTier>;
+
+    /// Remove a tier; false if it doesn't exist
+    async fn remove_tier(&self, tier: i32) -> DbResult<bool>;
+
+    async fn list_tiers(&self) -> DbResult<Vec<DbSpendingLimitTier>>;
+
+    /// Put a buyer in a tier (tier 0 removes the assignment)
+    async fn set_buyer_tier(&self, buyer: &EthAddress, tier: i32, source: &str, reference: Option<&str>, updated_by: Option<&str>) -> DbResult<()>;
+
+    /// A buyer's assignment; None for tier 0
+    async fn buyer_tier(&self, buyer: &EthAddress) -> DbResult<Option<DbBuyerTier>>;
+
+    async fn list_buyer_tiers(&self) -> DbResult<Vec<DbBuyerTier>>;
+
+    /// CNY value of the buyer's trades opened in the last 24 hours and 30 days
+    /// (expired trades don't count)
+    async fn buyer_volume(&self, buyer: &EthAddress) -> DbResult<DbBuyerVolume>;
+}
+
+pub struct PostgresSpendingLimitRepository {
+    pool: PgPool,
+}
+
+impl PostgresSpendingLimitRepository {
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+}
+
+#[async_trait]
+impl SpendingLimitRepository for PostgresSpendingLimitRepository {
+    async fn set_tier(&self, tier: i32, daily_limit_cny: Option<i64>, monthly_limit_cny: Option<i64>, updated_by: Option<&str>) -> DbResult<DbSpendingLimitTier> {
+        let tier = sqlx::query_as!(
+            DbSpendingLimitTier,
+            r#"
+            INSERT INTO spending_limit_tiers ("tier", "dailyLimitCny", "monthlyLimitCny", "updatedBy")
+            VALUES ($1, $2, $3, $4)
+            ON CONFLICT ("tier") DO UPDATE
+            SET "dailyLimitCny" = EXCLUDED."dailyLimitCny", "monthlyLimitCny" = EXCLUDED."monthlyLimitCny",
+                "updatedBy" = EXCLUDED."updatedBy", "updatedAt" = NOW()
+            RETURNING "tier", "dailyLimitCny" as daily_limit_cny, "monthlyLimitCny" as monthly_limit_cny,
+                      "updatedBy" as updated_by, "updatedAt" as updated_at
+            "#,
+            tier,
+            daily_limit_cny,
+            monthly_limit_cny,
+            updated_by
+        )
+        .fetch_one(&self.pool)
+        .await?;
+
+        Ok(tier)
+    }
+
+    async fn remove_tier(&self, tier: i32) -> DbResult<bool> {
+        let result = sqlx::query!(r#"DELETE FROM spending_limit_tiers WHERE "tier" = $1"#, tier)
+            .execute(&self.pool)
+            .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    async fn list_tiers(&self) -> DbResult<Vec<DbSpendingLimitTier>> {
+        let tiers = sqlx::query_as!(
+            DbSpendingLimitTier,
+            r#"
+            SELECT "tier", "dailyLimitCny" as daily_limit_cny, "monthlyLimitCny" as monthly_limit_cny,
+                   "updatedBy" as updated_by, "updatedAt" as updated_at
+            FROM spending_limit_tiers
+            ORDER BY "tier"
+            "#
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(tiers)
+    }
+
+    async fn set_buyer_tier(&self, buyer: &EthAddress, tier: i32, source: &str, reference: Option<&str>, updated_by: Option<&str>) -> DbResult<()> {
+        if tier == 0 {
+            sqlx::query!(r#"DELETE FROM buyer_tiers WHERE "buyer" = $1"#, buyer.as_str())
+                .execute(&self.pool)
+                .await?;
+            return Ok(());
+        }
+
+        sqlx::query!(
+            r#"
+            INSERT INTO buyer_tiers ("buyer", "tier", "source", "reference", "updatedBy")
+            VALUES ($1, $2, $3, $4, $5)
+            ON CONFLICT ("buyer") DO UPDATE
+            SET "tier" = EXCLUDED."tier", "source" = EXCLUDED."source", "reference" = EXCLUDED."reference",
+                "updatedBy" = EXCLUDED."updatedBy", "updatedAt" = NOW()
+            "#,
+            buyer.as_str(),
+            tier,
+            source,
+            reference,
+            updated_by
+        )
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    async fn buyer_tier(&self, buyer: &EthAddress) -> DbResult<Option<DbBuyerTier>> {
+        let tier = sqlx::query_as!(
+            DbBuyerTier,
+            r#"
+            SELECT "buyer" as "buyer: EthAddress", "tier", "source", "reference",
+                   "updatedBy" as updated_by, "updatedAt" as updated_at
+            FROM buyer_tiers
+            WHERE "buyer" = $1
+            "#,
+            buyer.as_str()
+        )
+        .fetch_optional(&self.pool)
+        .await?;
+
+        Ok(tier)
+    }
+
+    async fn list_buyer_tiers(&self) -> DbResult<Vec<DbBuyerTier>> {
+        let tiers = sqlx::query_as!(
+            DbBuyerTier,
+            r#"
+            SELECT "buyer" as "buyer: EthAddress", "tier", "source", "reference",
+                   "updatedBy" as updated_by, "updatedAt" as updated_at
+            FROM buyer_tiers
+            ORDER BY "tier" DESC, "buyer"
+            "#
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(tiers)
+    }
+
+    async fn buyer_volume(&self, buyer: &EthAddress) -> DbResult<DbBuyerVolume> {
+        let now = Utc::now().timestamp();
+        let volume = sqlx::query_as!(
+            DbBuyerVolume,
+            r#"
+            SELECT
+                COALESCE(SUM("cnyAmount") FILTER (WHERE "createdAt" >= $2), 0)::BIGINT as "volume_24h_cny!",
+                COALESCE(SUM("cnyAmount"), 0)::BIGINT as "volume_30d_cny!"
+            FROM trades
+            WHERE buyer = $1 AND "createdAt" >= $3 AND "status" <> 2
+            "#,
+            buyer.as_str(),
+            now - 24 * 3600,
+            now - 30 * 24 * 3600
+        )
+        .fetch_one(&self.pool)
+        .await?;
+
+        Ok(volume)
+    }
+}
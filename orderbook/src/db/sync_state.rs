@@ -0,0 +1,41 @@
+use async_trait::async_trait;
+use sqlx::PgPool;
+
+use super::DbResult;
+use super::models::DbSyncState;
+
+/// Read side of the event listener's sync progress (written by blockchain::events)
+#[async_trait]
+pub trait SyncStateRepository: Send + Sync {
+    /// The most recently advanced sync state; None if the listener never ran
+    async fn latest(&self) -> DbResult<Option<DbSyncState>>;
+}
+
+pub struct PostgresSyncStateRepository {
+    pool: PgPool,
+}
+
+impl PostgresSyncStateRepository {
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+}
+
+#[async_trait]
+impl SyncStateRepository for PostgresSyncStateRepository {
+    async fn latest(&self) -> DbResult<Option<DbSyncState>> {
+        let state = sqlx::query_as!(
+            DbSyncState,
+            r#"
+            SELECT contract_address, last_synced_block, last_synced_at
+            FROM event_sync_state
+            ORDER BY last_synced_at DESC
+            LIMIT 1
+            "#
+        )
+        .fetch_optional(&self.pool)
+        .await?;
+
+        Ok(state)
+    }
+}
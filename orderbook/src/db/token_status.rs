@@ -0,0 +1,153 @@
+use async_trait::async_trait;
+use sqlx::PgPool;
+use std::collections::HashSet;
+
+use super::DbResult;
+use super::models::DbTokenStatus;
+use crate::blockchain::address::EthAddress;
+
+/// Token list states
+pub const TOKEN_ALLOWED: &str = "allowed";
+pub const TOKEN_DENIED: &str = "denied";
+
+/// Which tokens can be matched: denied tokens never, and when the allowlist is
+/// non-empty only the tokens on it
+#[derive(Debug, Clone, Default)]
+pub struct TokenPolicy {
+    pub allowed: HashSet<EthAddress>,
+    pub denied: HashSet<EthAddress>,
+}
+
+impl TokenPolicy {
+    pub fn from_entries(entries: &[DbTokenStatus]) -> Self {
+        let mut policy = Self::default();
+        for entry in entries {
+            match entry.status.as_str() {
+                TOKEN_ALLOWED => policy.allowed.insert(entry.token.clone()),
+                _ => policy.denied.insert(entry.token.clone()),
+            };
+        }
+        policy
+    }
+
+    pub fn is_suspended(&self, token: &EthAddress) -> bool {
+        self.denied.contains(token) || (!self.allowed.is_empty() && !self.allowed.contains(token))
+    }
+
+    /// Lists as bound to the active-order query (stored address form)
+    pub(crate) fn bind_lists(&self) -> (Vec<String>, Vec<String>) {
+        let list = |set: &HashSet<EthAddress>| set.iter().map(|t| t.as_str().to_string()).collect();
+        (list(&self.allowed), list(&self.denied))
+    }
+}
+
+/// Repository for the token allowlist / denylist
+#[async_trait]
+pub trait TokenStatusRepository: Send + Sync {
+    /// Put a token on the allowlist or denylist (replacing any previous entry)
+    async fn set(&self, token: &EthAddress, status: &str, reason: Option<&str>, updated_by: Option<&str>) -> DbResult<DbTokenStatus>;
+
+    /// Take a token off both lists; false if it wasn't listed
+    async fn remove(&self, token: &EthAddress) -> DbResult<bool>;
+
+    async fn list(&self) -> DbResult<Vec<DbTokenStatus>>;
+
+    async fn policy(&self) -> DbResult<TokenPolicy> {
+        Ok(TokenPolicy::from_entries(&self.list().await?))
+    }
+}
+
+pub struct PostgresTokenStatusRepository {
+    pool: PgPool,
+}
+
+impl PostgresTokenStatusRepository {
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+}
+
+#[async_trait]
+impl TokenStatusRepository for PostgresTokenStatusRepository {
+    async fn set(&self, token: &EthAddress, status: &str, reason: Option<&str>, updated_by: Option<&str>) -> DbResult<DbTokenStatus> {
+        let entry = sqlx::query_as!(
+            DbTokenStatus,
+            r#"
+            INSERT INTO token_status ("token", "status", "reason", "updatedBy")
+            VALUES ($1, $2, $3, $4)
+            ON CONFLICT ("token") DO UPDATE
+            SET "status" = EXCLUDED."status", "reason" = EXCLUDED."reason",
+                "updatedBy" = EXCLUDED."updatedBy", "updatedAt" = NOW()
+            RETURNING "token" as "token: EthAddress", "status", "reason",
+                      "updatedBy" as updated_by, "updatedAt" as updated_at
+            "#,
+            token.as_str(),
+            status,
+            reason,
+            updated_by
+        )
+        .fetch_one(&self.pool)
+        .await?;
+
+        Ok(entry)
+    }
+
+    async fn remove(&self, token: &EthAddress) -> DbResult<bool> {
+        let result = sqlx::query!(r#"DELETE FROM token_status WHERE "token" = $1"#, token.as_str())
+            .execute(&self.pool)
+            .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    async fn list(&self) -> DbResult<Vec<DbTokenStatus>> {
+        let entries = sqlx::query_as!(
+            DbTokenStatus,
+            r#"
+            SELECT "token" as "token: EthAddress", "status", "reason",
+                   "updatedBy" as updated_by, "updatedAt" as updated_at
+            FROM token_status
+            ORDER BY "status", "token"
+            "#
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(entries)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Utc;
+
+    fn entry(token: &str, status: &str) -> DbTokenStatus {
+        DbTokenStatus {
+            token: token.parse().unwrap(),
+            status: status.to_string(),
+            reason: None,
+            updated_by: None,
+            updated_at: Utc::now(),
+        }
+    }
+
+    #[test]
+    fn test_token_policy() {
+        let usdc: EthAddress = "0x036cbd53842c5426634e7929541ec2318f3dcf7e".parse().unwrap();
+        let other: EthAddress = "0x2222222222222222222222222222222222222222".parse().unwrap();
+
+        // Empty lists: everything tradable
+        assert!(!TokenPolicy::default().is_suspended(&usdc));
+
+        // Denylist only suspends what's on it
+        let policy = TokenPolicy::from_entries(&[entry(other.as_str(), TOKEN_DENIED)]);
+        assert!(policy.is_suspended(&other));
+        assert!(!policy.is_suspended(&usdc));
+
+        // A non-empty allowlist suspends everything else
+        let policy = TokenPolicy::from_entries(&[entry(usdc.as_str(), TOKEN_ALLOWED)]);
+        assert!(!policy.is_suspended(&usdc));
+        assert!(policy.is_suspended(&other));
+    }
+}
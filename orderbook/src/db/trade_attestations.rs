@@ -0,0 +1,149 @@
+use async_trait::async_trait;
+use sqlx::PgPool;
+
+use super::{DbError, DbResult};
+use super::models::DbTradeAttestation;
+
+/// Attestation states
+pub const ATTESTATION_PENDING: &str = "pending";
+pub const ATTESTATION_ATTESTING: &str = "attesting";
+pub const ATTESTATION_ATTESTED: &str = "attested";
+pub const ATTESTATION_FAILED: &str = "failed";
+
+/// How long an 'attesting' claim holds before another replica can take it over
+pub const ATTESTATION_LEASE_SECS: i64 = 10 * 60;
+
+/// Repository for EAS attestations of settled trades
+#[async_trait]
+pub trait TradeAttestationRepository: Send + Sync {
+    /// Queue a settled trade (no-op if it is already queued or attested)
+    async fn enqueue(&self, trade_id: &str) -> DbResult<()>;
+
+    /// Claim the oldest queued trade whose settlement is final: a 'pending' row, or an
+    /// 'attesting' one whose claim is older than `lease_secs`
+    async fn claim_next(&self, lease_secs: i64) -> DbResult<Option<DbTradeAttestation>>;
+
+    /// Record the attestation of a claimed trade
+    async fn complete(&self, trade_id: &str, uid: &str, tx_hash: &str) -> DbResult<()>;
+
+    /// Release a claimed trade after a failed attempt ('failed' once `max_attempts` are used)
+    async fn fail(&self, trade_id: &str, error: &str, tx_hash: Option<&str>, max_attempts: i32) -> DbResult<()>;
+
+    async fn get(&self, trade_id: &str) -> DbResult<Option<DbTradeAttestation>>;
+}
+
+pub struct PostgresTradeAttestationRepository {
+    pool: PgPool,
+}
+
+impl PostgresTradeAttestationRepository {
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+}
+
+#[async_trait]
+impl TradeAttestationRepository for PostgresTradeAttestationRepository {
+    async fn enqueue(&self, trade_id: &str) -> DbResult<()> {
+        sqlx::query!(
+            r#"
+            INSERT INTO trade_attestations ("tradeId")
+            VALUES ($1)
+            ON CONFLICT ("tradeId") DO NOTHING
+            "#,
+            trade_id
+        )
+        .execute(&self.pool)
+        .await
+        .map_err(|e| match e {
+            sqlx::Error::Database(db) if db.is_foreign_key_violation() => DbError::TradeNotFound(trade_id.to_string()),
+            e => e.into(),
+        })?;
+
+        Ok(())
+    }
+
+    async fn claim_next(&self, lease_secs: i64) -> DbResult<Option<DbTradeAttestation>> {
+        // SKIP LOCKED: replicas scanning at the same time claim different trades
+        let claimed = sqlx::query_as!(
+            DbTradeAttestation,
+            r#"
+            UPDATE trade_attestations
+            SET "status" = 'attesting', "attempts" = "attempts" + 1, "error" = NULL, "updatedAt" = NOW()
+            WHERE "tradeId" = (
+                SELECT a."tradeId"
+                FROM trade_attestations a
+                JOIN trades t ON t."tradeId" = a."tradeId"
+                WHERE t."finalizedAt" IS NOT NULL
+                  AND (a."status" = 'pending'
+                       OR (a."status" = 'attesting'
+                           AND a."updatedAt" < NOW() - $1::BIGINT * INTERVAL '1 second'))
+                ORDER BY a."createdAt"
+                LIMIT 1
+                FOR UPDATE OF a SKIP LOCKED
+            )
+            RETURNING "tradeId" as trade_id, "status", "attempts", "uid", "txHash" as tx_hash, "error",
+                      "createdAt" as created_at, "updatedAt" as updated_at, "attestedAt" as attested_at
+            "#,
+            lease_secs
+        )
+        .fetch_optional(&self.pool)
+        .await?;
+
+        Ok(claimed)
+    }
+
+    async fn complete(&self, trade_id: &str, uid: &str, tx_hash: &str) -> DbResult<()> {
+        sqlx::query!(
+            r#"
+            UPDATE trade_attestations
+            SET "status" = 'attested', "uid" = $2, "txHash" = $3, "error" = NULL,
+                "updatedAt" = NOW(), "attestedAt" = NOW()
+            WHERE "tradeId" = $1
+            "#,
+            trade_id,
+            uid,
+            tx_hash
+        )
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    async fn fail(&self, trade_id: &str, error: &str, tx_hash: Option<&str>, max_attempts: i32) -> DbResult<()> {
+        sqlx::query!(
+            r#"
+            UPDATE trade_attestations
+            SET "status" = CASE WHEN "attempts" >= $4 THEN 'failed' ELSE 'pending' END,
+                "error" = $2, "txHash" = COALESCE($3, "txHash"), "updatedAt" = NOW()
+            WHERE "tradeId" = $1 AND "status" = 'attesting'
+            "#,
+            trade_id,
+            error,
+            tx_hash,
+            max_attempts
+        )
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    async fn get(&self, trade_id: &str) -> DbResult<Option<DbTradeAttestation>> {
+        let attestation = sqlx::query_as!(
+            DbTradeAttestation,
+            r#"
+            SELECT "tradeId" as trade_id, "status", "attempts", "uid", "txHash" as tx_hash, "error",
+                   "createdAt" as created_at, "updatedAt" as updated_at, "attestedAt" as attested_at
+            FROM trade_attestations
+            WHERE "tradeId" = $1
+            "#,
+            trade_id
+        )
+        .fetch_optional(&self.pool)
+        .await?;
+
+        Ok(attestation)
+    }
+}
@@ -0,0 +1,59 @@
+use async_trait::async_trait;
+use sqlx::PgPool;
+
+use super::DbResult;
+use super::models::DbPendingTrade;
+
+/// Repository for the PENDING trades the drift check compares with the escrow
+#[async_trait]
+pub trait TradeDriftRepository: Send + Sync {
+    /// PENDING trades created before `created_before` (unix seconds), oldest first
+    async fn list_pending_before(&self, created_before: i64, limit: i64) -> DbResult<Vec<DbPendingTrade>>;
+
+    /// Set a trade's status if it is still PENDING; false if it no longer was
+    async fn resolve_pending(&self, trade_id: &str, status: i32) -> DbResult<bool>;
+}
+
+pub struct PostgresTradeDriftRepository {
+    pool: PgPool,
+}
+
+impl PostgresTradeDriftRepository {
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+}
+
+#[async_trait]
+impl TradeDriftRepository for PostgresTradeDriftRepository {
+    async fn list_pending_before(&self, created_before: i64, limit: i64) -> DbResult<Vec<DbPendingTrade>> {
+        let trades = sqlx::query_as!(
+            DbPendingTrade,
+            r#"
+            SELECT "tradeId" as trade_id, "escrowTxHash" as escrow_tx_hash, "createdAt" as created_at
+            FROM trades
+            WHERE "status" = 0 AND "createdAt" < $1
+            ORDER BY "createdAt" ASC
+            LIMIT $2
+            "#,
+            created_before,
+            limit
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(trades)
+    }
+
+    async fn resolve_pending(&self, trade_id: &str, status: i32) -> DbResult<bool> {
+        let result = sqlx::query!(
+            r#"UPDATE trades SET "status" = $2 WHERE "tradeId" = $1 AND "status" = 0"#,
+            trade_id,
+            status
+        )
+        .execute(&self.pool)
+        .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+}
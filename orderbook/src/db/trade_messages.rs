@@ -0,0 +1,130 @@
+use async_trait::async_trait;
+use sqlx::PgPool;
+
+use super::{DbError, DbResult};
+use super::models::{DbMessageAttachment, DbTradeMessage};
+use crate::blockchain::address::EthAddress;
+
+/// Message roles
+pub const ROLE_BUYER: &str = "buyer";
+pub const ROLE_SELLER: &str = "seller";
+
+/// A message to store
+#[derive(Debug, Clone)]
+pub struct NewTradeMessage {
+    pub trade_id: String,
+    pub sender: EthAddress,
+    pub role: &'static str,
+    pub body: String,
+    /// (file name, MIME type, bytes)
+    pub attachment: Option<(Option<String>, &'static str, Vec<u8>)>,
+}
+
+/// Repository for buyer/seller messages on trades
+#[async_trait]
+pub trait TradeMessageRepository: Send + Sync {
+    async fn create(&self, message: &NewTradeMessage) -> DbResult<DbTradeMessage>;
+
+    /// A trade's messages after `after_id`, oldest first
+    async fn list(&self, trade_id: &str, after_id: i64, limit: i64) -> DbResult<Vec<DbTradeMessage>>;
+
+    async fn count(&self, trade_id: &str) -> DbResult<i64>;
+
+    async fn attachment(&self, trade_id: &str, message_id: i64) -> DbResult<Option<DbMessageAttachment>>;
+}
+
+pub struct PostgresTradeMessageRepository {
+    pool: PgPool,
+}
+
+impl PostgresTradeMessageRepository {
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+}
+
+#[async_trait]
+impl TradeMessageRepository for PostgresTradeMessageRepository {
+    async fn create(&self, message: &NewTradeMessage) -> DbResult<DbTradeMessage> {
+        let (name, content_type, data) = match &message.attachment {
+            Some((name, content_type, data)) => (name.as_deref(), Some(*content_type), Some(data.as_slice())),
+            None => (None, None, None),
+        };
+
+        let created = sqlx::query_as!(
+            DbTradeMessage,
+            r#"
+            INSERT INTO trade_messages ("tradeId", "sender", "role", "body", "attachment", "attachmentName", "attachmentType")
+            VALUES ($1, $2, $3, $4, $5, $6, $7)
+            RETURNING "id", "tradeId" as trade_id, "sender" as "sender: EthAddress", "role", "body",
+                      "attachmentName" as attachment_name, "attachmentType" as attachment_type,
+                      length("attachment") as attachment_size, "createdAt" as created_at
+            "#,
+            message.trade_id,
+            message.sender.as_str(),
+            message.role,
+            message.body,
+            data,
+            name,
+            content_type
+        )
+        .fetch_one(&self.pool)
+        .await
+        .map_err(|e| match e {
+            sqlx::Error::Database(db) if db.is_foreign_key_violation() => DbError::TradeNotFound(message.trade_id.clone()),
+            e => e.into(),
+        })?;
+
+        Ok(created)
+    }
+
+    async fn list(&self, trade_id: &str, after_id: i64, limit: i64) -> DbResult<Vec<DbTradeMessage>> {
+        let messages = sqlx::query_as!(
+            DbTradeMessage,
+            r#"
+            SELECT "id", "tradeId" as trade_id, "sender" as "sender: EthAddress", "role", "body",
+                   "attachmentName" as attachment_name, "attachmentType" as attachment_type,
+                   length("attachment") as attachment_size, "createdAt" as created_at
+            FROM trade_messages
+            WHERE "tradeId" = $1 AND "id" > $2
+            ORDER BY "id"
+            LIMIT $3
+            "#,
+            trade_id,
+            after_id,
+            limit
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(messages)
+    }
+
+    async fn count(&self, trade_id: &str) -> DbResult<i64> {
+        let count = sqlx::query_scalar!(
+            r#"SELECT COUNT(*) as "count!" FROM trade_messages WHERE "tradeId" = $1"#,
+            trade_id
+        )
+        .fetch_one(&self.pool)
+        .await?;
+
+        Ok(count)
+    }
+
+    async fn attachment(&self, trade_id: &str, message_id: i64) -> DbResult<Option<DbMessageAttachment>> {
+        let attachment = sqlx::query_as!(
+            DbMessageAttachment,
+            r#"
+            SELECT "attachmentName" as name, "attachmentType" as "content_type!", "attachment" as "data!"
+            FROM trade_messages
+            WHERE "tradeId" = $1 AND "id" = $2 AND "attachment" IS NOT NULL
+            "#,
+            trade_id,
+            message_id
+        )
+        .fetch_optional(&self.pool)
+        .await?;
+
+        Ok(attachment)
+    }
+}
@@ -0,0 +1,91 @@
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use sqlx::PgPool;
+
+use super::{DbError, DbResult};
+use super::models::DbTradePayment;
+use crate::blockchain::address::EthAddress;
+
+/// Declared amount compared with the trade's cnyAmount
+pub const AMOUNT_EXACT: &str = "exact";
+pub const AMOUNT_SHORT: &str = "short";
+pub const AMOUNT_OVER: &str = "over";
+
+/// A transfer the buyer declared
+#[derive(Debug, Clone)]
+pub struct NewTradePayment {
+    pub trade_id: String,
+    pub buyer: EthAddress,
+    pub amount: i64,                        // CNY cents
+    pub paid_at: DateTime<Utc>,
+    pub amount_status: &'static str,
+    pub matches_trade_id: Option<String>,
+}
+
+/// Repository for buyer-declared payments on trades
+#[async_trait]
+pub trait TradePaymentRepository: Send + Sync {
+    async fn create(&self, payment: &NewTradePayment) -> DbResult<DbTradePayment>;
+
+    /// A trade's declared payments, oldest first
+    async fn list(&self, trade_id: &str) -> DbResult<Vec<DbTradePayment>>;
+}
+
+pub struct PostgresTradePaymentRepository {
+    pool: PgPool,
+}
+
+impl PostgresTradePaymentRepository {
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+}
+
+#[async_trait]
+impl TradePaymentRepository for PostgresTradePaymentRepository {
+    async fn create(&self, payment: &NewTradePayment) -> DbResult<DbTradePayment> {
+        let created = sqlx::query_as!(
+            DbTradePayment,
+            r#"
+            INSERT INTO trade_payments ("tradeId", "buyer", "amount", "paidAt", "amountStatus", "matchesTradeId")
+            VALUES ($1, $2, $3, $4, $5, $6)
+            RETURNING "id", "tradeId" as trade_id, "buyer" as "buyer: EthAddress", "amount",
+                      "paidAt" as paid_at, "amountStatus" as amount_status,
+                      "matchesTradeId" as matches_trade_id, "createdAt" as created_at
+            "#,
+            payment.trade_id,
+            payment.buyer.as_str(),
+            payment.amount,
+            payment.paid_at,
+            payment.amount_status,
+            payment.matches_trade_id
+        )
+        .fetch_one(&self.pool)
+        .await
+        .map_err(|e| match e {
+            sqlx::Error::Database(db) if db.is_foreign_key_violation() => DbError::TradeNotFound(payment.trade_id.clone()),
+            e => e.into(),
+        })?;
+
+        Ok(created)
+    }
+
+    async fn list(&self, trade_id: &str) -> DbResult<Vec<DbTradePayment>> {
+        let payments = sqlx::query_as!(
+            DbTradePayment,
+            r#"
+            SELECT "id", "tradeId" as trade_id, "buyer" as "buyer: EthAddress", "amount",
+                   "paidAt" as paid_at, "amountStatus" as amount_status,
+                   "matchesTradeId" as matches_trade_id, "createdAt" as created_at
+            FROM trade_payments
+            WHERE "tradeId" = $1
+            ORDER BY "id"
+            "#,
+            trade_id
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(payments)
+    }
+}
@@ -0,0 +1,140 @@
+use async_trait::async_trait;
+use sqlx::PgPool;
+
+use super::{DbError, DbResult};
+use super::models::{DbTradeNote, DbTradeSupport};
+
+/// Repository for operator support metadata and notes on trades
+#[async_trait]
+pub trait TradeSupportRepository: Send + Sync {
+    async fn get(&self, trade_id: &str) -> DbResult<Option<DbTradeSupport>>;
+
+    /// Replace a trade's contact and tags
+    async fn set(&self, trade_id: &str, buyer_contact: Option<&str>, tags: &[String], updated_by: Option<&str>) -> DbResult<DbTradeSupport>;
+
+    async fn add_note(&self, trade_id: &str, note: &str, author: Option<&str>) -> DbResult<DbTradeNote>;
+
+    /// A trade's notes, oldest first
+    async fn notes(&self, trade_id: &str) -> DbResult<Vec<DbTradeNote>>;
+
+    /// Trades carrying `tag`, most recently updated first
+    async fn with_tag(&self, tag: &str, limit: i64) -> DbResult<Vec<DbTradeSupport>>;
+}
+
+pub struct PostgresTradeSupportRepository {
+    pool: PgPool,
+}
+
+impl PostgresTradeSupportRepository {
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+}
+
+/// Writes for a trade that was never synced fail the foreign key
+fn trade_not_found(trade_id: &str) -> impl FnOnce(sqlx::Error) -> DbError + '_ {
+    move |e| match e {
+        sqlx::Error::Database(db) if db.is_foreign_key_violation() => DbError::TradeNotFound(trade_id.to_string()),
+        e => e.into(),
+    }
+}
+
+#[async_trait]
+impl TradeSupportRepository for PostgresTradeSupportRepository {
+    async fn get(&self, trade_id: &str) -> DbResult<Option<DbTradeSupport>> {
+        let support = sqlx::query_as!(
+            DbTradeSupport,
+            r#"
+            SELECT "tradeId" as trade_id, "buyerContact" as buyer_contact, "tags",
+                   "updatedBy" as updated_by, "updatedAt" as updated_at
+            FROM trade_support
+            WHERE "tradeId" = $1
+            "#,
+            trade_id
+        )
+        .fetch_optional(&self.pool)
+        .await?;
+
+        Ok(support)
+    }
+
+    async fn set(&self, trade_id: &str, buyer_contact: Option<&str>, tags: &[String], updated_by: Option<&str>) -> DbResult<DbTradeSupport> {
+        let support = sqlx::query_as!(
+            DbTradeSupport,
+            r#"
+            INSERT INTO trade_support ("tradeId", "buyerContact", "tags", "updatedBy")
+            VALUES ($1, $2, $3, $4)
+            ON CONFLICT ("tradeId") DO UPDATE
+            SET "buyerContact" = EXCLUDED."buyerContact", "tags" = EXCLUDED."tags",
+                "updatedBy" = EXCLUDED."updatedBy", "updatedAt" = NOW()
+            RETURNING "tradeId" as trade_id, "buyerContact" as buyer_contact, "tags",
+                      "updatedBy" as updated_by, "updatedAt" as updated_at
+            "#,
+            trade_id,
+            buyer_contact,
+            tags,
+            updated_by
+        )
+        .fetch_one(&self.pool)
+        .await
+        .map_err(trade_not_found(trade_id))?;
+
+        Ok(support)
+    }
+
+    async fn add_note(&self, trade_id: &str, note: &str, author: Option<&str>) -> DbResult<DbTradeNote> {
+        let note = sqlx::query_as!(
+            DbTradeNote,
+            r#"
+            INSERT INTO trade_notes ("tradeId", "note", "author")
+            VALUES ($1, $2, $3)
+            RETURNING "id", "tradeId" as trade_id, "note", "author", "createdAt" as created_at
+            "#,
+            trade_id,
+            note,
+            author
+        )
+        .fetch_one(&self.pool)
+        .await
+        .map_err(trade_not_found(trade_id))?;
+
+        Ok(note)
+    }
+
+    async fn notes(&self, trade_id: &str) -> DbResult<Vec<DbTradeNote>> {
+        let notes = sqlx::query_as!(
+            DbTradeNote,
+            r#"
+            SELECT "id", "tradeId" as trade_id, "note", "author", "createdAt" as created_at
+            FROM trade_notes
+            WHERE "tradeId" = $1
+            ORDER BY "createdAt" ASC, "id" ASC
+            "#,
+            trade_id
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(notes)
+    }
+
+    async fn with_tag(&self, tag: &str, limit: i64) -> DbResult<Vec<DbTradeSupport>> {
+        let trades = sqlx::query_as!(
+            DbTradeSupport,
+            r#"
+            SELECT "tradeId" as trade_id, "buyerContact" as buyer_contact, "tags",
+                   "updatedBy" as updated_by, "updatedAt" as updated_at
+            FROM trade_support
+            WHERE "tags" @> ARRAY[$1::TEXT]
+            ORDER BY "updatedAt" DESC
+            LIMIT $2
+            "#,
+            tag,
+            limit
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(trades)
+    }
+}
@@ -5,6 +5,7 @@ use std::str::FromStr;
 use chrono::{DateTime, Utc};
 
 use super::{DbError, DbResult};
+use crate::blockchain::address::EthAddress;
 use super::models::DbTrade;
 
 /// Repository for Trade operations - ONLY methods needed for event sync
@@ -16,6 +17,12 @@ pub trait TradeRepository: Send + Sync {
     /// Get trade by ID
     async fn get(&self, trade_id: &str) -> DbResult<DbTrade>;
     
+    /// Get trade by payment nonce (nonces are unique)
+    async fn get_by_nonce(&self, payment_nonce: &str) -> DbResult<Option<DbTrade>>;
+    
+    /// Get all trades for a buyer, newest first
+    async fn get_by_buyer(&self, buyer: &EthAddress) -> DbResult<Vec<DbTrade>>;
+    
     /// Update trade status from TradeSettled or TradeExpired events
     async fn update_status(&self, trade_id: &str, new_status: i32) -> DbResult<()>;
     
@@ -25,11 +32,13 @@ pub trait TradeRepository: Send + Sync {
     /// Update settlement transaction hash from TradeSettled event
     async fn update_settlement_tx(&self, trade_id: &str, settlement_tx_hash: &str) -> DbResult<()>;
     
-    /// Save PDF file for a trade
+    /// Save PDF file for a PENDING trade; a proof of the previous PDF is archived and cleared
+    /// (a settled trade keeps its PDF and proof, InvalidInput; TradeNotFound if there is none)
     async fn save_pdf(&self, trade_id: &str, pdf_data: &[u8], filename: &str) -> DbResult<DateTime<Utc>>;
     
-    /// Save Axiom EVM proof data
-    async fn save_proof(&self, trade_id: &str, user_public_values: &[u8], accumulator: &[u8], proof_data: &[u8], axiom_proof_id: &str, proof_json: &str) -> DbResult<()>;
+    /// Save Axiom EVM proof data generated from the PDF uploaded at `pdf_uploaded_at`;
+    /// false (nothing saved) if another PDF has been uploaded since
+    async fn save_proof(&self, trade_id: &str, pdf_uploaded_at: Option<DateTime<Utc>>, user_public_values: &[u8], accumulator: &[u8], proof_data: &[u8], axiom_proof_id: &str, proof_json: &str, proof_format: &str) -> DbResult<bool>;
 }
 
 pub struct PostgresTradeRepository {
@@ -57,7 +66,7 @@ impl TradeRepository for PostgresTradeRepository {
             "#,
             trade.trade_id,
             trade.order_id,
-            trade.buyer,
+            trade.buyer.as_str(),
             Decimal::from_str(&trade.token_amount).unwrap(),
             Decimal::from_str(&trade.cny_amount).unwrap(),
             trade.payment_nonce,
@@ -77,12 +86,12 @@ impl TradeRepository for PostgresTradeRepository {
         let row = sqlx::query!(
             r#"
             SELECT 
-                "tradeId", "orderId", "buyer", "tokenAmount"::text, "cnyAmount"::text,
+                "tradeId", "orderId", "buyer" as "buyer: EthAddress", "tokenAmount"::text, "cnyAmount"::text,
                 "paymentNonce", "createdAt", "expiresAt", "status",
-                "escrowTxHash", "settlementTxHash", "syncedAt",
+                "escrowTxHash", "settlementTxHash", "settlementBlock", "confirmations", "finalizedAt", "syncedAt",
                 pdf_file, pdf_filename, pdf_uploaded_at,
                 proof_user_public_values, proof_accumulator, proof_data,
-                axiom_proof_id, proof_generated_at, proof_json
+                axiom_proof_id, proof_generated_at, proof_json, "proofFormat"
             FROM trades
             WHERE "tradeId" = $1
             "#,
@@ -104,6 +113,9 @@ impl TradeRepository for PostgresTradeRepository {
             status: row.status,
             escrow_tx_hash: row.escrowTxHash,
             settlement_tx_hash: row.settlementTxHash,
+            settlement_block: row.settlementBlock,
+            confirmations: row.confirmations,
+            finalized_at: row.finalizedAt,
             synced_at: row.syncedAt,
             token: None, // Not available in single trade query (would need JOIN)
             pdf_file: row.pdf_file,
@@ -115,9 +127,78 @@ impl TradeRepository for PostgresTradeRepository {
             axiom_proof_id: row.axiom_proof_id,
             proof_generated_at: row.proof_generated_at,
             proof_json: row.proof_json,
+            proof_format: row.proofFormat,
         })
     }
 
+    async fn get_by_nonce(&self, payment_nonce: &str) -> DbResult<Option<DbTrade>> {
+        let trade_id = sqlx::query_scalar!(
+            r#"SELECT "tradeId" FROM trades WHERE "paymentNonce" = $1"#,
+            payment_nonce
+        )
+        .fetch_optional(&self.pool)
+        .await?;
+
+        match trade_id {
+            Some(trade_id) => Ok(Some(self.get(&trade_id).await?)),
+            None => Ok(None),
+        }
+    }
+
+    async fn get_by_buyer(&self, buyer: &EthAddress) -> DbResult<Vec<DbTrade>> {
+        let rows = sqlx::query!(
+            r#"
+            SELECT 
+                t."tradeId", t."orderId", t."buyer" as "buyer: EthAddress", t."tokenAmount"::text, t."cnyAmount"::text,
+                t."paymentNonce", t."createdAt", t."expiresAt", t."status",
+                t."escrowTxHash", t."settlementTxHash", t."settlementBlock", t."confirmations", t."finalizedAt", t."syncedAt",
+                t.pdf_file, t.pdf_filename, t.pdf_uploaded_at,
+                t.proof_user_public_values, t.proof_accumulator, t.proof_data,
+                t.axiom_proof_id, t.proof_generated_at, t.proof_json, t."proofFormat",
+                o.token as "token: EthAddress"
+            FROM trades t
+            INNER JOIN orders o ON t."orderId" = o."orderId"
+            WHERE t.buyer = $1
+            ORDER BY t."createdAt" DESC
+            "#,
+            buyer.as_str()
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(rows
+            .into_iter()
+            .map(|row| DbTrade {
+                trade_id: row.tradeId,
+                order_id: row.orderId,
+                buyer: row.buyer,
+                token_amount: row.tokenAmount.unwrap_or_default(),
+                cny_amount: row.cnyAmount.unwrap_or_default(),
+                payment_nonce: row.paymentNonce,
+                created_at: row.createdAt,
+                expires_at: row.expiresAt,
+                status: row.status,
+                escrow_tx_hash: row.escrowTxHash,
+                settlement_tx_hash: row.settlementTxHash,
+                settlement_block: row.settlementBlock,
+                confirmations: row.confirmations,
+                finalized_at: row.finalizedAt,
+                synced_at: row.syncedAt,
+                token: Some(row.token),
+                pdf_file: row.pdf_file,
+                pdf_filename: row.pdf_filename,
+                pdf_uploaded_at: row.pdf_uploaded_at,
+                proof_user_public_values: row.proof_user_public_values,
+                proof_accumulator: row.proof_accumulator,
+                proof_data: row.proof_data,
+                axiom_proof_id: row.axiom_proof_id,
+                proof_generated_at: row.proof_generated_at,
+                proof_json: row.proof_json,
+                proof_format: row.proofFormat,
+            })
+            .collect())
+    }
+
     async fn update_status(&self, trade_id: &str, new_status: i32) -> DbResult<()> {
         let result = sqlx::query!(
             r#"UPDATE trades SET "status" = $1 WHERE "tradeId" = $2"#,
@@ -159,29 +240,61 @@ impl TradeRepository for PostgresTradeRepository {
     
     async fn save_pdf(&self, trade_id: &str, pdf_data: &[u8], filename: &str) -> DbResult<DateTime<Utc>> {
         let uploaded_at = Utc::now();
+        let mut tx = self.pool.begin().await?;
+        
+        // A proof proves the old PDF - keep it in the archive, off the trade
+        sqlx::query!(
+            r#"
+            INSERT INTO trade_proof_archive (
+                "tradeId", "userPublicValues", "accumulator", "proofData",
+                "axiomProofId", "proofJson", "generatedAt", "pdfUploadedAt", "proofFormat"
+            )
+            SELECT "tradeId", proof_user_public_values, proof_accumulator, proof_data,
+                   axiom_proof_id, proof_json, proof_generated_at, pdf_uploaded_at, "proofFormat"
+            FROM trades
+            WHERE "tradeId" = $1 AND status = 0
+              AND (proof_user_public_values IS NOT NULL OR proof_json IS NOT NULL)
+            "#,
+            trade_id
+        )
+        .execute(&mut *tx)
+        .await?;
         
         let result = sqlx::query!(
             r#"
             UPDATE trades 
-            SET pdf_file = $1, pdf_filename = $2, pdf_uploaded_at = $3
-            WHERE "tradeId" = $4
+            SET pdf_file = $1, pdf_filename = $2, pdf_uploaded_at = $3,
+                proof_user_public_values = NULL, proof_accumulator = NULL, proof_data = NULL,
+                axiom_proof_id = NULL, proof_generated_at = NULL, proof_json = NULL, "proofFormat" = NULL
+            WHERE "tradeId" = $4 AND status = 0
             "#,
             pdf_data,
             filename,
             uploaded_at,
             trade_id
         )
-        .execute(&self.pool)
+        .execute(&mut *tx)
         .await?;
 
         if result.rows_affected() == 0 {
-            return Err(DbError::TradeNotFound(trade_id.to_string()));
+            let exists = sqlx::query_scalar!(
+                r#"SELECT EXISTS(SELECT 1 FROM trades WHERE "tradeId" = $1) AS "exists!""#,
+                trade_id
+            )
+            .fetch_one(&mut *tx)
+            .await?;
+            return Err(if exists {
+                DbError::InvalidInput(format!("Trade {} is not pending", trade_id))
+            } else {
+                DbError::TradeNotFound(trade_id.to_string())
+            });
         }
 
+        tx.commit().await?;
         Ok(uploaded_at)
     }
     
-    async fn save_proof(&self, trade_id: &str, user_public_values: &[u8], accumulator: &[u8], proof_data: &[u8], axiom_proof_id: &str, proof_json: &str) -> DbResult<()> {
+    async fn save_proof(&self, trade_id: &str, pdf_uploaded_at: Option<DateTime<Utc>>, user_public_values: &[u8], accumulator: &[u8], proof_data: &[u8], axiom_proof_id: &str, proof_json: &str, proof_format: &str) -> DbResult<bool> {
         let generated_at = Utc::now();
         
         let result = sqlx::query!(
@@ -192,8 +305,9 @@ impl TradeRepository for PostgresTradeRepository {
                 proof_data = $3,
                 axiom_proof_id = $4,
                 proof_generated_at = $5,
-                proof_json = $6
-            WHERE "tradeId" = $7
+                proof_json = $6,
+                "proofFormat" = $9
+            WHERE "tradeId" = $7 AND pdf_uploaded_at IS NOT DISTINCT FROM $8
             "#,
             user_public_values,
             accumulator,
@@ -201,15 +315,19 @@ impl TradeRepository for PostgresTradeRepository {
             axiom_proof_id,
             generated_at,
             proof_json,
-            trade_id
+            trade_id,
+            pdf_uploaded_at,
+            proof_format
         )
         .execute(&self.pool)
         .await?;
 
         if result.rows_affected() == 0 {
-            return Err(DbError::TradeNotFound(trade_id.to_string()));
+            // Tell a missing trade from a replaced PDF
+            self.get(trade_id).await?;
+            return Ok(false);
         }
 
-        Ok(())
+        Ok(true)
     }
 }
@@ -0,0 +1,101 @@
+use async_trait::async_trait;
+use sqlx::PgPool;
+
+use super::DbResult;
+use super::models::DbValidationAttempt;
+
+/// Verdict from a fresh Axiom execute-mode run
+pub const SOURCE_AXIOM: &str = "axiom";
+/// Verdict from a stored Axiom execution of identical inputs
+pub const SOURCE_AXIOM_CACHED: &str = "axiom_cached";
+/// Verdict from a local OpenVM execution
+pub const SOURCE_LOCAL: &str = "local";
+/// Verdict from the sandbox's mock prover
+pub const SOURCE_SANDBOX: &str = "sandbox";
+
+/// Fields of a validation attempt to record
+#[derive(Debug, Clone, Default)]
+pub struct NewValidationAttempt<'a> {
+    pub trade_id: &'a str,
+    pub source: &'a str,
+    pub program_id: Option<&'a str>,
+    pub input_hash: Option<&'a str>,
+    pub expected_hash: Option<&'a str>,
+    pub actual_hash: Option<&'a str>,
+    pub is_valid: Option<bool>,
+    pub error: Option<&'a str>,
+}
+
+/// Repository for per-trade validation history
+#[async_trait]
+pub trait ValidationAttemptRepository: Send + Sync {
+    /// Record a validation attempt
+    async fn create(&self, attempt: &NewValidationAttempt<'_>) -> DbResult<()>;
+
+    /// All attempts for a trade, newest first
+    async fn list_for_trade(&self, trade_id: &str) -> DbResult<Vec<DbValidationAttempt>>;
+}
+
+pub struct PostgresValidationAttemptRepository {
+    pool: PgPool,
+}
+
+impl PostgresValidationAttemptRepository {
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+}
+
+#[async_trait]
+impl ValidationAttemptRepository for PostgresValidationAttemptRepository {
+    async fn create(&self, attempt: &NewValidationAttempt<'_>) -> DbResult<()> {
+        sqlx::query!(
+            r#"
+            INSERT INTO validation_attempts (
+                "tradeId", "source", "programId", "inputHash",
+                "expectedHash", "actualHash", "isValid", "error"
+            )
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8)
+            "#,
+            attempt.trade_id,
+            attempt.source,
+            attempt.program_id,
+            attempt.input_hash,
+            attempt.expected_hash,
+            attempt.actual_hash,
+            attempt.is_valid,
+            attempt.error
+        )
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    async fn list_for_trade(&self, trade_id: &str) -> DbResult<Vec<DbValidationAttempt>> {
+        let attempts = sqlx::query_as!(
+            DbValidationAttempt,
+            r#"
+            SELECT
+                "id" as "id!",
+                "tradeId" as "trade_id!",
+                "source" as "source!",
+                "programId" as program_id,
+                "inputHash" as input_hash,
+                "expectedHash" as expected_hash,
+                "actualHash" as actual_hash,
+                "isValid" as is_valid,
+                "error" as error,
+                "attemptedAt" as "attempted_at!"
+            FROM validation_attempts
+            WHERE "tradeId" = $1
+            ORDER BY "attemptedAt" DESC, "id" DESC
+            "#,
+            trade_id
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(attempts)
+    }
+}
@@ -0,0 +1,32 @@
+use async_trait::async_trait;
+use sqlx::PgPool;
+
+use super::DbResult;
+
+/// Repository for webhook delivery IDs (migrations/043_webhook_deliveries.sql)
+#[async_trait]
+pub trait WebhookDeliveryRepository: Send + Sync {
+    /// Next delivery ID, greater than every ID handed out before
+    async fn next_id(&self) -> DbResult<u64>;
+}
+
+pub struct PostgresWebhookDeliveryRepository {
+    pool: PgPool,
+}
+
+impl PostgresWebhookDeliveryRepository {
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+}
+
+#[async_trait]
+impl WebhookDeliveryRepository for PostgresWebhookDeliveryRepository {
+    async fn next_id(&self) -> DbResult<u64> {
+        let id = sqlx::query_scalar!(r#"SELECT nextval('webhook_delivery_ids') as "id!""#)
+            .fetch_one(&self.pool)
+            .await?;
+
+        Ok(id as u64)
+    }
+}
@@ -0,0 +1,226 @@
+// Protocol fee accounting
+// The escrow contract doesn't collect fees yet; until it does, the operator fee for
+// each settled trade is computed off-chain and recorded as an accrual against the
+// paying side, so statements can be issued (and later reconciled on-chain).
+
+use ethers::types::U256;
+use serde::Serialize;
+use std::fmt;
+use thiserror::Error;
+
+use crate::db::{
+    DbError,
+    fees::{FeeAccrualRepository, NewFeeAccrual, PostgresFeeAccrualRepository},
+    orders::{OrderRepository, PostgresOrderRepository},
+    trades::{TradeRepository, PostgresTradeRepository},
+};
+
+/// Basis points denominator (10_000 bps = 100%)
+pub const BPS_DENOMINATOR: u64 = 10_000;
+
+/// Refuse schedules above this (10%) - almost certainly a misconfiguration
+pub const MAX_FEE_BPS: u32 = 1_000;
+
+#[derive(Error, Debug)]
+pub enum FeeError {
+    #[error("Database error: {0}")]
+    Database(#[from] DbError),
+    #[error("Trade {0} is not settled")]
+    NotSettled(String),
+    #[error("Invalid fee config: {0}")]
+    Config(String),
+    #[error("Invalid amount {0:?}")]
+    InvalidAmount(String),
+}
+
+/// How the operator fee is computed
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FeeSchedule {
+    /// Share of the trade in basis points
+    Bps(u32),
+    /// Fixed fee per trade in CNY cents (capped at the trade value)
+    FlatCny(u64),
+}
+
+impl fmt::Display for FeeSchedule {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            FeeSchedule::Bps(bps) => write!(f, "bps:{}", bps),
+            FeeSchedule::FlatCny(cents) => write!(f, "flat_cny:{}", cents),
+        }
+    }
+}
+
+/// Side of the trade the fee is accrued against
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum FeePayer {
+    Seller,
+    Buyer,
+}
+
+impl FeePayer {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            FeePayer::Seller => "seller",
+            FeePayer::Buyer => "buyer",
+        }
+    }
+}
+
+/// Fee for one trade, in both denominations
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FeeQuote {
+    pub token_fee: U256,      // token base units
+    pub cny_fee: U256,        // CNY cents
+}
+
+/// Computes and accrues operator fees for settled trades
+#[derive(Debug, Clone)]
+pub struct FeeEngine {
+    pub schedule: FeeSchedule,
+    pub payer: FeePayer,
+}
+
+impl FeeEngine {
+    pub fn new(schedule: FeeSchedule, payer: FeePayer) -> Result<Self, FeeError> {
+        if let FeeSchedule::Bps(bps) = schedule {
+            if bps > MAX_FEE_BPS {
+                return Err(FeeError::Config(format!("{} bps exceeds the {} bps maximum", bps, MAX_FEE_BPS)));
+            }
+        }
+        Ok(Self { schedule, payer })
+    }
+
+    /// Load from FEE_SCHEDULE ("bps:<n>" or "flat_cny:<cents>") and FEE_PAYER (seller|buyer, default seller)
+    /// Returns None when FEE_SCHEDULE is unset (fee accounting disabled)
+    pub fn from_env() -> Result<Option<Self>, FeeError> {
+        let schedule = match std::env::var("FEE_SCHEDULE") {
+            Ok(schedule) => Self::parse_schedule(&schedule)?,
+            Err(_) => return Ok(None),
+        };
+
+        let payer = match std::env::var("FEE_PAYER").as_deref() {
+            Ok("seller") | Err(_) => FeePayer::Seller,
+            Ok("buyer") => FeePayer::Buyer,
+            Ok(other) => return Err(FeeError::Config(format!("FEE_PAYER must be seller or buyer, got {:?}", other))),
+        };
+
+        Self::new(schedule, payer).map(Some)
+    }
+
+    fn parse_schedule(value: &str) -> Result<FeeSchedule, FeeError> {
+        let invalid = || FeeError::Config(format!("FEE_SCHEDULE must be bps:<n> or flat_cny:<cents>, got {:?}", value));
+        let (kind, amount) = value.trim().split_once(':').ok_or_else(invalid)?;
+
+        match kind {
+            "bps" => amount.parse().map(FeeSchedule::Bps).map_err(|_| invalid()),
+            "flat_cny" => amount.parse().map(FeeSchedule::FlatCny).map_err(|_| invalid()),
+            _ => Err(invalid()),
+        }
+    }
+
+    /// Fee for a trade of `token_amount` base units worth `cny_amount` cents (rounded down)
+    pub fn quote(&self, token_amount: U256, cny_amount: U256) -> FeeQuote {
+        match self.schedule {
+            FeeSchedule::Bps(bps) => {
+                let bps = U256::from(bps);
+                let denominator = U256::from(BPS_DENOMINATOR);
+                FeeQuote {
+                    token_fee: token_amount.saturating_mul(bps) / denominator,
+                    cny_fee: cny_amount.saturating_mul(bps) / denominator,
+                }
+            }
+            FeeSchedule::FlatCny(cents) => {
+                // Express the flat fee in tokens at the trade's own price
+                let cny_fee = U256::from(cents).min(cny_amount);
+                let token_fee = if cny_amount.is_zero() {
+                    U256::zero()
+                } else {
+                    token_amount.saturating_mul(cny_fee) / cny_amount
+                };
+                FeeQuote { token_fee, cny_fee }
+            }
+        }
+    }
+}
+
+fn parse_amount(value: &str) -> Result<U256, FeeError> {
+    U256::from_dec_str(value).map_err(|_| FeeError::InvalidAmount(value.to_string()))
+}
+
+/// Record the fee accrual for a settled trade (idempotent: re-processing a
+/// TradeSettled event keeps the first accrual)
+pub async fn accrue_fee(
+    pool: &sqlx::PgPool,
+    engine: &FeeEngine,
+    trade_id: &str,
+) -> Result<FeeQuote, FeeError> {
+    let trade = PostgresTradeRepository::new(pool.clone()).get(trade_id).await?;
+    if trade.status != 1 {
+        return Err(FeeError::NotSettled(trade_id.to_string()));
+    }
+
+    let order = PostgresOrderRepository::new(pool.clone()).get(&trade.order_id).await?;
+
+    let quote = engine.quote(parse_amount(&trade.token_amount)?, parse_amount(&trade.cny_amount)?);
+    let payer = match engine.payer {
+        FeePayer::Seller => order.seller,
+        FeePayer::Buyer => trade.buyer,
+    };
+
+    PostgresFeeAccrualRepository::new(pool.clone())
+        .create(&NewFeeAccrual {
+            trade_id: trade.trade_id,
+            order_id: trade.order_id,
+            payer_role: engine.payer.as_str(),
+            payer,
+            token: order.token,
+            token_fee: quote.token_fee.to_string(),
+            cny_fee: quote.cny_fee.to_string(),
+            schedule: engine.schedule.to_string(),
+        })
+        .await?;
+
+    Ok(quote)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_fee_quotes() {
+        // 100 USDC (6 decimals) worth 735.00 CNY
+        let token_amount = U256::from(100_000_000u64);
+        let cny_amount = U256::from(73_500u64);
+
+        // 25 bps = 0.25 USDC / 1.83 CNY (rounded down)
+        let engine = FeeEngine::new(FeeSchedule::Bps(25), FeePayer::Seller).unwrap();
+        let quote = engine.quote(token_amount, cny_amount);
+        assert_eq!(quote.token_fee, U256::from(250_000u64));
+        assert_eq!(quote.cny_fee, U256::from(183u64));
+
+        // Flat 7.35 CNY = 1% of this trade = 1 USDC
+        let engine = FeeEngine::new(FeeSchedule::FlatCny(735), FeePayer::Buyer).unwrap();
+        let quote = engine.quote(token_amount, cny_amount);
+        assert_eq!(quote.token_fee, U256::from(1_000_000u64));
+        assert_eq!(quote.cny_fee, U256::from(735u64));
+
+        // Flat fee never exceeds the trade itself
+        let quote = engine.quote(U256::from(1_000u64), U256::from(500u64));
+        assert_eq!(quote, FeeQuote { token_fee: U256::from(1_000u64), cny_fee: U256::from(500u64) });
+
+        assert!(FeeEngine::new(FeeSchedule::Bps(MAX_FEE_BPS + 1), FeePayer::Seller).is_err());
+    }
+
+    #[test]
+    fn test_parse_schedule() {
+        assert_eq!(FeeEngine::parse_schedule("bps:25").unwrap(), FeeSchedule::Bps(25));
+        assert_eq!(FeeEngine::parse_schedule("flat_cny:200").unwrap(), FeeSchedule::FlatCny(200));
+        assert_eq!(FeeSchedule::FlatCny(200).to_string(), "flat_cny:200");
+        assert!(FeeEngine::parse_schedule("25").is_err());
+        assert!(FeeEngine::parse_schedule("percent:1").is_err());
+        assert!(FeeEngine::parse_schedule("bps:-1").is_err());
+    }
+}
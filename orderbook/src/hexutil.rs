@@ -0,0 +1,148 @@
+// Hex encoding with optional 0x prefixes
+// Every decoder in the crate follows one rule: surrounding whitespace is ignored, at
+// most one 0x/0X prefix is stripped, and the rest must be an even number of hex
+// digits of either case. (trim_start_matches("0x") used to accept "0x0x..." and some
+// decoders rejected 0X or padding others let through.) Encoders emit 0x + lowercase.
+
+use serde::{de, Deserialize, Deserializer, Serializer};
+use thiserror::Error;
+
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+pub enum HexError {
+    #[error("Invalid hex: {0}")]
+    Invalid(String),
+
+    #[error("Invalid hex length: expected {expected} bytes, got {actual}")]
+    Length { expected: usize, actual: usize },
+}
+
+/// The hex digits of `s`: trimmed, with one 0x/0X prefix removed if present
+pub fn strip_0x(s: &str) -> &str {
+    let trimmed = s.trim();
+    trimmed
+        .strip_prefix("0x")
+        .or_else(|| trimmed.strip_prefix("0X"))
+        .unwrap_or(trimmed)
+}
+
+/// Decode hex of any length (prefix optional)
+pub fn decode(s: &str) -> Result<Vec<u8>, HexError> {
+    hex::decode(strip_0x(s)).map_err(|e| HexError::Invalid(e.to_string()))
+}
+
+/// Decode hex of exactly N bytes (prefix optional)
+pub fn decode_fixed<const N: usize>(s: &str) -> Result<[u8; N], HexError> {
+    let bytes = decode(s)?;
+    let actual = bytes.len();
+    <[u8; N]>::try_from(bytes).map_err(|_| HexError::Length { expected: N, actual })
+}
+
+/// 0x + lowercase hex
+pub fn encode_0x(bytes: impl AsRef<[u8]>) -> String {
+    format!("0x{}", hex::encode(bytes))
+}
+
+/// `#[serde(with = "hexutil::serde_bytes")]` for `Vec<u8>` fields: 0x-prefixed out,
+/// prefix optional in
+pub mod serde_bytes {
+    use super::*;
+
+    pub fn serialize<S: Serializer>(bytes: &[u8], serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&encode_0x(bytes))
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Vec<u8>, D::Error> {
+        let s = String::deserialize(deserializer)?;
+        decode(&s).map_err(de::Error::custom)
+    }
+}
+
+/// `#[serde(with = "hexutil::serde_fixed")]` for `[u8; N]` fields
+pub mod serde_fixed {
+    use super::*;
+
+    pub fn serialize<S: Serializer, const N: usize>(bytes: &[u8; N], serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&encode_0x(bytes))
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>, const N: usize>(deserializer: D) -> Result<[u8; N], D::Error> {
+        let s = String::deserialize(deserializer)?;
+        decode_fixed(&s).map_err(de::Error::custom)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use proptest::prelude::*;
+    use serde::{Deserialize, Serialize};
+
+    #[derive(Debug, PartialEq, Serialize, Deserialize)]
+    struct Sample {
+        #[serde(with = "serde_bytes")]
+        data: Vec<u8>,
+        #[serde(with = "serde_fixed")]
+        hash: [u8; 4],
+    }
+
+    #[test]
+    fn test_prefix_handling() {
+        assert_eq!(decode("0xabCD").unwrap(), vec![0xab, 0xcd]);
+        assert_eq!(decode("0XABcd").unwrap(), vec![0xab, 0xcd]);
+        assert_eq!(decode(" abcd\n").unwrap(), vec![0xab, 0xcd]);
+        assert_eq!(decode("0x").unwrap(), Vec::<u8>::new());
+
+        // One prefix only, and nothing but hex after it
+        assert!(decode("0x0xabcd").is_err());
+        assert!(decode("0xabc").is_err());
+        assert!(decode("0x ab").is_err());
+        assert!(decode("xabcd").is_err());
+
+        assert_eq!(decode_fixed::<2>("0xabcd").unwrap(), [0xab, 0xcd]);
+        assert_eq!(decode_fixed::<32>("0xabcd"), Err(HexError::Length { expected: 32, actual: 2 }));
+        assert_eq!(encode_0x([0xab, 0xcd]), "0xabcd");
+    }
+
+    #[test]
+    fn test_serde_helpers() {
+        let sample: Sample = serde_json::from_str(r#"{"data":"ABCD","hash":"0x01020304"}"#).unwrap();
+        assert_eq!(sample, Sample { data: vec![0xab, 0xcd], hash: [1, 2, 3, 4] });
+        assert_eq!(serde_json::to_string(&sample).unwrap(), r#"{"data":"0xabcd","hash":"0x01020304"}"#);
+        assert!(serde_json::from_str::<Sample>(r#"{"data":"0x","hash":"0x010203"}"#).is_err());
+    }
+
+    proptest! {
+        #[test]
+        fn prop_encode_decode_round_trips(bytes in proptest::collection::vec(any::<u8>(), 0..64)) {
+            let encoded = encode_0x(&bytes);
+            prop_assert_eq!(decode(&encoded).unwrap(), bytes.clone());
+            prop_assert_eq!(decode(&encoded.to_uppercase()).unwrap(), bytes.clone());
+            prop_assert_eq!(decode(&encoded[2..]).unwrap(), bytes);
+        }
+
+        #[test]
+        fn prop_arbitrary_input_never_panics(s in "\\PC{0,80}") {
+            if let Ok(bytes) = decode(&s) {
+                // Whatever decodes is exactly the digits after one optional prefix
+                prop_assert_eq!(encode_0x(&bytes)[2..].to_string(), strip_0x(&s).to_lowercase());
+            }
+            let _ = decode_fixed::<32>(&s);
+        }
+
+        #[test]
+        fn prop_malformed_hex_is_rejected(digits in "[0-9a-f]{0,20}", junk in "[g-zG-Z._-]", at in 0usize..20) {
+            let at = at.min(digits.len());
+            let malformed = format!("0x{}{}{}", &digits[..at], junk, &digits[at..]);
+            prop_assert!(decode(&malformed).is_err());
+            prop_assert!(decode(&format!("0x0x{}", digits)).is_err());
+        }
+
+        #[test]
+        fn prop_decode_fixed_checks_length(bytes in proptest::collection::vec(any::<u8>(), 0..40)) {
+            match decode_fixed::<32>(&encode_0x(&bytes)) {
+                Ok(fixed) => prop_assert_eq!(fixed.to_vec(), bytes),
+                Err(e) => prop_assert_eq!(e, HexError::Length { expected: 32, actual: bytes.len() }),
+            }
+        }
+    }
+}
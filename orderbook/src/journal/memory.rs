@@ -0,0 +1,46 @@
+// In-process event sink (single instance deployments, embedders and tests)
+
+use async_trait::async_trait;
+use tokio::sync::broadcast;
+
+use super::{EventSink, JournalError, JournalEvent};
+
+/// Events buffered per subscriber before a slow one starts missing them
+const CHANNEL_CAPACITY: usize = 1024;
+
+/// Broadcasts events to in-process subscribers
+/// Nothing is persisted: events published while no one is subscribed are dropped.
+pub struct MemorySink {
+    sender: broadcast::Sender<JournalEvent>,
+}
+
+impl Default for MemorySink {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl MemorySink {
+    pub fn new() -> Self {
+        let (sender, _) = broadcast::channel(CHANNEL_CAPACITY);
+        Self { sender }
+    }
+
+    /// Receive every event published from now on
+    pub fn subscribe(&self) -> broadcast::Receiver<JournalEvent> {
+        self.sender.subscribe()
+    }
+}
+
+#[async_trait]
+impl EventSink for MemorySink {
+    async fn publish(&self, event: &JournalEvent) -> Result<(), JournalError> {
+        // An error only means no one is subscribed
+        let _ = self.sender.send(event.clone());
+        Ok(())
+    }
+
+    fn backend(&self) -> &'static str {
+        "memory"
+    }
+}
@@ -0,0 +1,103 @@
+// Lifecycle event journal for downstream consumers
+// Order and trade lifecycle changes are recorded in event_journal by triggers, in the
+// same transaction as the change itself, and the publisher forwards them to an
+// EventSink: NATS JetStream when JOURNAL_NATS_URL is set (nats feature), otherwise an
+// in-process broadcast channel that embedders can subscribe to. Analytics and risk
+// systems consume the stream instead of reading our schema.
+//
+// Delivery is at-least-once: an event is stamped published only after the sink
+// accepted it, so a sink outage delays the stream without losing events. Every
+// message carries the journal id; consumers dedupe on it (JetStream does within its
+// duplicate window via Nats-Msg-Id). Another broker (e.g. Kafka) plugs in as an EventSink.
+//
+// Configuration (env):
+// - JOURNAL_NATS_URL: NATS server (requires the nats feature)
+// - JOURNAL_SUBJECT_PREFIX: subject prefix, events go to <prefix>.<event_type>
+//   (default zkalipay.events)
+// - JOURNAL_RETENTION_HOURS: published events are deleted after this long (default 168)
+
+pub mod memory;
+#[cfg(feature = "nats")]
+pub mod nats;
+pub mod publisher;
+
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+use thiserror::Error;
+
+use crate::db::models::DbJournalEvent;
+
+pub use memory::MemorySink;
+pub use publisher::JournalPublisher;
+
+/// Default subject prefix (events go to <prefix>.<event_type>)
+pub const DEFAULT_SUBJECT_PREFIX: &str = "zkalipay.events";
+
+#[derive(Error, Debug)]
+pub enum JournalError {
+    #[error("Event sink error: {0}")]
+    Backend(String),
+    #[error("Invalid journal config: {0}")]
+    Config(String),
+    #[error("Undecodable journal payload: {0}")]
+    Payload(#[from] serde_json::Error),
+}
+
+/// A lifecycle event as published to consumers
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct JournalEvent {
+    /// Journal id, increasing; consumers dedupe on it
+    pub id: i64,
+    pub event_type: String,
+    /// Order or trade id
+    pub aggregate_id: String,
+    pub occurred_at: DateTime<Utc>,
+    pub payload: serde_json::Value,
+}
+
+impl TryFrom<DbJournalEvent> for JournalEvent {
+    type Error = JournalError;
+
+    fn try_from(event: DbJournalEvent) -> Result<Self, Self::Error> {
+        Ok(Self {
+            id: event.id,
+            event_type: event.event_type,
+            aggregate_id: event.aggregate_id,
+            occurred_at: event.occurred_at,
+            payload: serde_json::from_str(&event.payload)?,
+        })
+    }
+}
+
+/// Destination of journal events (message broker or in-process channel)
+#[async_trait]
+pub trait EventSink: Send + Sync {
+    /// Returns once the sink durably accepted the event
+    async fn publish(&self, event: &JournalEvent) -> Result<(), JournalError>;
+
+    /// Backend name for logs
+    fn backend(&self) -> &'static str;
+}
+
+/// NATS when JOURNAL_NATS_URL is set (requires the nats feature), in-process otherwise
+pub async fn sink_from_env() -> Result<Arc<dyn EventSink>, JournalError> {
+    match std::env::var("JOURNAL_NATS_URL") {
+        Ok(url) => {
+            let prefix = std::env::var("JOURNAL_SUBJECT_PREFIX").unwrap_or_else(|_| DEFAULT_SUBJECT_PREFIX.to_string());
+            nats_sink(&url, prefix).await
+        }
+        Err(_) => Ok(Arc::new(MemorySink::new())),
+    }
+}
+
+#[cfg(feature = "nats")]
+async fn nats_sink(url: &str, prefix: String) -> Result<Arc<dyn EventSink>, JournalError> {
+    Ok(Arc::new(nats::NatsSink::connect(url, prefix).await?))
+}
+
+#[cfg(not(feature = "nats"))]
+async fn nats_sink(_url: &str, _prefix: String) -> Result<Arc<dyn EventSink>, JournalError> {
+    Err(JournalError::Config("JOURNAL_NATS_URL requires building with the nats feature".to_string()))
+}
@@ -0,0 +1,48 @@
+// NATS JetStream event sink (JOURNAL_NATS_URL, nats feature)
+// The stream covering <prefix>.> is provisioned by operations; publishing waits for the
+// JetStream ack, so an event is only stamped published once the stream stored it.
+
+use async_nats::jetstream;
+use async_trait::async_trait;
+
+use super::{EventSink, JournalError, JournalEvent};
+
+pub struct NatsSink {
+    jetstream: jetstream::Context,
+    prefix: String,
+}
+
+impl NatsSink {
+    pub async fn connect(url: &str, prefix: String) -> Result<Self, JournalError> {
+        let client = async_nats::connect(url)
+            .await
+            .map_err(|e| JournalError::Config(e.to_string()))?;
+        Ok(Self { jetstream: jetstream::new(client), prefix })
+    }
+
+    fn subject(&self, event: &JournalEvent) -> String {
+        format!("{}.{}", self.prefix, event.event_type)
+    }
+}
+
+#[async_trait]
+impl EventSink for NatsSink {
+    async fn publish(&self, event: &JournalEvent) -> Result<(), JournalError> {
+        let payload = serde_json::to_vec(event)?;
+        // JetStream drops a re-published event within its duplicate window
+        let mut headers = async_nats::HeaderMap::new();
+        headers.insert("Nats-Msg-Id", event.id.to_string().as_str());
+
+        self.jetstream
+            .publish_with_headers(self.subject(event), headers, payload.into())
+            .await
+            .map_err(|e| JournalError::Backend(e.to_string()))?
+            .await
+            .map_err(|e| JournalError::Backend(e.to_string()))?;
+        Ok(())
+    }
+
+    fn backend(&self) -> &'static str {
+        "nats"
+    }
+}
@@ -0,0 +1,112 @@
+// Forwards unpublished journal events to the sink
+// One publisher runs at a time (advisory lock), so events leave in journal order.
+// A batch stops at the first event the sink refuses; it is retried on the next poll.
+
+use chrono::{Duration as ChronoDuration, Utc};
+use std::sync::Arc;
+use tokio::task::JoinHandle;
+use tokio::time::{interval, Duration, Instant, MissedTickBehavior};
+
+use super::{EventSink, JournalEvent};
+use crate::db::event_journal::{EventJournalRepository, PostgresEventJournalRepository};
+use crate::db::leader::{LeaderLock, DEFAULT_LEADER_RETRY, LOCK_EVENT_JOURNAL};
+use crate::db::DbResult;
+
+/// How often unpublished events are polled
+pub const DEFAULT_POLL_INTERVAL: Duration = Duration::from_secs(1);
+/// How long published events are kept
+pub const DEFAULT_RETENTION_HOURS: i64 = 7 * 24;
+/// Events forwarded per poll
+const BATCH_SIZE: i64 = 500;
+/// How often published events past retention are deleted
+const PRUNE_INTERVAL: Duration = Duration::from_secs(60 * 60);
+
+pub struct JournalPublisher {
+    pool: sqlx::PgPool,
+    repo: PostgresEventJournalRepository,
+    sink: Arc<dyn EventSink>,
+    retention: ChronoDuration,
+}
+
+impl JournalPublisher {
+    pub fn new(pool: sqlx::PgPool, sink: Arc<dyn EventSink>, retention_hours: i64) -> Self {
+        Self {
+            repo: PostgresEventJournalRepository::new(pool.clone()),
+            pool,
+            sink,
+            retention: ChronoDuration::hours(retention_hours.max(1)),
+        }
+    }
+
+    /// Retention from JOURNAL_RETENTION_HOURS
+    pub fn from_env(pool: sqlx::PgPool, sink: Arc<dyn EventSink>) -> Self {
+        let retention_hours = std::env::var("JOURNAL_RETENTION_HOURS")
+            .ok()
+            .and_then(|v| v.parse().ok())
+            .unwrap_or(DEFAULT_RETENTION_HOURS);
+        Self::new(pool, sink, retention_hours)
+    }
+
+    /// Spawn the publish loop as a background task
+    pub fn spawn(self) -> JoinHandle<()> {
+        tokio::spawn(async move { self.run().await })
+    }
+
+    async fn run(self) {
+        let mut lock = LeaderLock::acquire(&self.pool, "event journal", LOCK_EVENT_JOURNAL, DEFAULT_LEADER_RETRY).await;
+        let mut ticker = interval(DEFAULT_POLL_INTERVAL);
+        ticker.set_missed_tick_behavior(MissedTickBehavior::Delay);
+        let mut last_prune: Option<Instant> = None;
+
+        loop {
+            ticker.tick().await;
+            if !lock.is_held().await {
+                tracing::warn!("📰 Lost event journal leadership - re-electing");
+                lock = LeaderLock::acquire(&self.pool, "event journal", LOCK_EVENT_JOURNAL, DEFAULT_LEADER_RETRY).await;
+            }
+
+            if let Err(e) = self.publish_pending().await {
+                tracing::warn!("📰 Event journal publish failed: {}", e);
+            }
+
+            if last_prune.is_none_or(|at| at.elapsed() >= PRUNE_INTERVAL) {
+                last_prune = Some(Instant::now());
+                match self.repo.prune(Utc::now() - self.retention).await {
+                    Ok(0) => {}
+                    Ok(count) => tracing::info!("📰 Pruned {} published journal events", count),
+                    Err(e) => tracing::warn!("📰 Event journal prune failed: {}", e),
+                }
+            }
+        }
+    }
+
+    /// Forward one batch of unpublished events
+    /// Returns the number of events the sink accepted.
+    pub async fn publish_pending(&self) -> DbResult<u64> {
+        let events = self.repo.unpublished(BATCH_SIZE).await?;
+        let mut published = Vec::with_capacity(events.len());
+
+        for event in events {
+            let id = event.id;
+            let event = match JournalEvent::try_from(event) {
+                Ok(event) => event,
+                Err(e) => {
+                    // Written by our own triggers, so this is a bug; don't block the stream on it
+                    tracing::error!("📰 Skipping journal event #{}: {}", id, e);
+                    published.push(id);
+                    continue;
+                }
+            };
+            if let Err(e) = self.sink.publish(&event).await {
+                tracing::warn!("📰 {} refused journal event #{}, retrying later: {}", self.sink.backend(), id, e);
+                break;
+            }
+            published.push(id);
+        }
+
+        if published.is_empty() {
+            return Ok(0);
+        }
+        self.repo.mark_published(&published).await
+    }
+}
@@ -0,0 +1,107 @@
+// Latency budget of a request, phase by phase
+// execute-fill is the slowest call buyers wait on, and its total duration alone doesn't
+// say whether the time went to checking the plan, to the node estimating gas, to sending
+// the transaction or to waiting for it to be mined. The handler times each phase in a
+// tracing span, exports a histogram per phase (zkalipay_execute_fill_phase_seconds) and,
+// with ?debug=1, returns the breakdown with the response.
+
+use serde::Serialize;
+use std::future::Future;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+use tracing::Instrument;
+
+/// Checking the match plan: screening, contract config, order pricing, limits, liquidity
+pub const PHASE_VALIDATION: &str = "validation";
+/// eth_estimateGas of the fillOrder call
+pub const PHASE_GAS_ESTIMATION: &str = "gas_estimation";
+/// Signing and sending the transaction (the sandbox's simulated fill)
+pub const PHASE_SEND: &str = "send";
+/// Waiting for the receipt
+pub const PHASE_CONFIRMATION: &str = "confirmation";
+
+/// Time spent per phase of one request (a phase run once per fill adds up)
+#[derive(Debug)]
+pub struct PhaseTimer {
+    started: Instant,
+    phases: Mutex<Vec<(&'static str, Duration)>>,
+}
+
+impl Default for PhaseTimer {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl PhaseTimer {
+    pub fn new() -> Self {
+        Self { started: Instant::now(), phases: Mutex::new(Vec::new()) }
+    }
+
+    /// Run `fut` in a span of `phase` and add its duration to the phase
+    pub async fn time<F: Future>(&self, phase: &'static str, fut: F) -> F::Output {
+        let started = Instant::now();
+        let output = fut.instrument(tracing::info_span!("phase", phase)).await;
+        self.record(phase, started.elapsed());
+        output
+    }
+
+    pub fn record(&self, phase: &'static str, elapsed: Duration) {
+        let mut phases = self.phases.lock().unwrap();
+        match phases.iter_mut().find(|(name, _)| *name == phase) {
+            Some((_, total)) => *total += elapsed,
+            None => phases.push((phase, elapsed)),
+        }
+    }
+
+    /// Phases in the order they first ran, with the time since the timer started
+    pub fn breakdown(&self) -> LatencyBreakdown {
+        LatencyBreakdown {
+            total_ms: self.started.elapsed().as_millis() as u64,
+            phases: self
+                .phases
+                .lock()
+                .unwrap()
+                .iter()
+                .map(|(phase, elapsed)| PhaseLatency { phase, ms: elapsed.as_millis() as u64, elapsed: *elapsed })
+                .collect(),
+        }
+    }
+}
+
+/// Where a request's time went
+#[derive(Debug, Clone, Serialize)]
+pub struct LatencyBreakdown {
+    /// Whole request, including what no phase covers
+    pub total_ms: u64,
+    pub phases: Vec<PhaseLatency>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct PhaseLatency {
+    pub phase: &'static str,
+    pub ms: u64,
+    #[serde(skip)]
+    pub elapsed: Duration,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_repeated_phases_add_up_in_first_run_order() {
+        let timer = PhaseTimer::new();
+        timer.record(PHASE_VALIDATION, Duration::from_millis(30));
+        assert_eq!(timer.time(PHASE_SEND, async { 7 }).await, 7);
+        timer.record(PHASE_VALIDATION, Duration::from_millis(20));
+
+        let breakdown = timer.breakdown();
+        let phases: Vec<_> = breakdown.phases.iter().map(|p| p.phase).collect();
+        assert_eq!(phases, vec![PHASE_VALIDATION, PHASE_SEND]);
+        assert_eq!(breakdown.phases[0].ms, 50);
+
+        let json = serde_json::to_value(&breakdown).unwrap();
+        assert_eq!(json["phases"][0], serde_json::json!({ "phase": "validation", "ms": 50 }));
+    }
+}
@@ -1,7 +1,27 @@
 pub mod db;
 pub mod api;
 pub mod blockchain;
+pub mod alipay;
+pub mod anomalies;
 pub mod axiom_prover;
+pub mod cache;
+pub mod fees;
+pub mod hexutil;
+pub mod journal;
+pub mod latency;
+pub mod metrics;
+pub mod notifications;
+pub mod orderbook_history;
+pub mod pdf_text;
+pub mod receipts;
+pub mod text_utils;
+pub mod proof_format;
+pub mod proof_inputs;
+pub mod proof_programs;
+pub mod sandbox;
+pub mod screening;
+pub mod spending_limits;
+pub mod startup;
 
 pub use db::{Database, DbError, DbResult};
 pub use api::{AppState, create_router, MatchPlan, Fill, match_buy_intent};
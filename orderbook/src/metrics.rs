@@ -0,0 +1,522 @@
+// Process-wide metrics rendered in the Prometheus text exposition format
+// Kept dependency-free: plain atomics behind an Arc shared by the API and background tasks
+
+use std::fmt::Write;
+use std::collections::BTreeMap;
+use std::sync::atomic::{AtomicBool, AtomicI64, AtomicU64, Ordering};
+use std::sync::Mutex;
+
+use serde::{Deserialize, Serialize};
+use sqlx::PgPool;
+
+use crate::latency::LatencyBreakdown;
+
+/// All metrics exported at GET /metrics
+#[derive(Debug, Default)]
+pub struct Metrics {
+    pub listener: ListenerMetrics,
+    pub api_keys: ApiKeyMetrics,
+    pub orderbook_cache: OrderbookCacheMetrics,
+    pub contract_reads: ContractReadMetrics,
+    pub db_pools: DbPoolMetrics,
+    pub trade_drift: TradeDriftMetrics,
+    pub execute_fill: ExecuteFillMetrics,
+}
+
+impl Metrics {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Render all metrics in Prometheus text format
+    pub fn render(&self) -> String {
+        let mut out = String::new();
+        self.listener.render(&mut out);
+        self.api_keys.render(&mut out);
+        self.orderbook_cache.render(&mut out);
+        self.contract_reads.render(&mut out);
+        self.db_pools.render(&mut out);
+        self.trade_drift.render(&mut out);
+        self.execute_fill.render(&mut out);
+        out
+    }
+}
+
+/// Event listener progress and supervision metrics
+#[derive(Debug, Default)]
+pub struct ListenerMetrics {
+    /// Set once a supervisor has been started (false when blockchain integration is disabled)
+    enabled: AtomicBool,
+    /// Whether a listener task is currently alive
+    running: AtomicBool,
+    /// Whether this instance holds the listener leader lock (standbys don't listen)
+    leader: AtomicBool,
+    /// Latest chain head observed by the listener
+    head_block: AtomicU64,
+    /// Last block fully processed and persisted
+    synced_block: AtomicU64,
+    /// Unix timestamp of the last successful poll (0 = never)
+    last_poll_at: AtomicI64,
+    /// Number of times the supervisor restarted the listener
+    restarts: AtomicU64,
+    last_restart_reason: Mutex<Option<String>>,
+    /// Handled, replayed and failed events per event type
+    events: Mutex<BTreeMap<String, EventCounts>>,
+}
+
+/// Events of one type handled since process start
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct EventCounts {
+    pub handled: u64,
+    pub failed: u64,
+    /// Seen again in a resync (side effects skipped, not counted as handled)
+    #[serde(default)]
+    pub replayed: u64,
+}
+
+/// Snapshot of listener state for /health
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ListenerHealth {
+    pub running: bool,
+    pub leader: bool,
+    pub head_block: u64,
+    pub synced_block: u64,
+    pub lag_blocks: u64,
+    pub last_poll_at: Option<i64>,
+    pub restarts: u64,
+    pub last_restart_reason: Option<String>,
+}
+
+impl ListenerMetrics {
+    pub fn set_enabled(&self) {
+        self.enabled.store(true, Ordering::Relaxed);
+    }
+
+    pub fn set_running(&self, running: bool) {
+        self.running.store(running, Ordering::Relaxed);
+    }
+
+    pub fn set_leader(&self, leader: bool) {
+        self.leader.store(leader, Ordering::Relaxed);
+    }
+
+    /// Record a successful poll of the chain head
+    pub fn record_poll(&self, head_block: u64) {
+        self.head_block.store(head_block, Ordering::Relaxed);
+        self.last_poll_at.store(chrono::Utc::now().timestamp(), Ordering::Relaxed);
+    }
+
+    /// Record the last block that has been processed and persisted
+    pub fn record_synced(&self, synced_block: u64) {
+        self.synced_block.store(synced_block, Ordering::Relaxed);
+    }
+
+    pub fn record_restart(&self, reason: &str) {
+        self.restarts.fetch_add(1, Ordering::Relaxed);
+        *self.last_restart_reason.lock().unwrap() = Some(reason.to_string());
+    }
+
+    /// Record the outcome of handling one event
+    pub fn record_event(&self, event_type: &str, ok: bool) {
+        let mut events = self.events.lock().unwrap();
+        let counts = events.entry(event_type.to_string()).or_default();
+        if ok {
+            counts.handled += 1;
+        } else {
+            counts.failed += 1;
+        }
+    }
+
+    /// Record an event seen again, whose side effects had already been applied
+    pub fn record_replay(&self, event_type: &str) {
+        self.events.lock().unwrap().entry(event_type.to_string()).or_default().replayed += 1;
+    }
+
+    pub fn event_counts(&self, event_type: &str) -> EventCounts {
+        self.events.lock().unwrap().get(event_type).cloned().unwrap_or_default()
+    }
+
+    /// Blocks between the observed head and the last synced block
+    pub fn lag_blocks(&self) -> u64 {
+        self.head_block
+            .load(Ordering::Relaxed)
+            .saturating_sub(self.synced_block.load(Ordering::Relaxed))
+    }
+
+    /// Unix timestamp of the last successful poll, if any
+    pub fn last_poll_at(&self) -> Option<i64> {
+        match self.last_poll_at.load(Ordering::Relaxed) {
+            0 => None,
+            ts => Some(ts),
+        }
+    }
+
+    /// Health snapshot, None if no listener was ever started
+    pub fn health(&self) -> Option<ListenerHealth> {
+        if !self.enabled.load(Ordering::Relaxed) {
+            return None;
+        }
+
+        Some(ListenerHealth {
+            running: self.running.load(Ordering::Relaxed),
+            leader: self.leader.load(Ordering::Relaxed),
+            head_block: self.head_block.load(Ordering::Relaxed),
+            synced_block: self.synced_block.load(Ordering::Relaxed),
+            lag_blocks: self.lag_blocks(),
+            last_poll_at: self.last_poll_at(),
+            restarts: self.restarts.load(Ordering::Relaxed),
+            last_restart_reason: self.last_restart_reason.lock().unwrap().clone(),
+        })
+    }
+
+    fn render(&self, out: &mut String) {
+        gauge(out, "zkalipay_listener_running", "Whether the event listener task is alive",
+            self.running.load(Ordering::Relaxed) as u64);
+        gauge(out, "zkalipay_listener_leader", "Whether this instance holds the event listener leader lock",
+            self.leader.load(Ordering::Relaxed) as u64);
+        gauge(out, "zkalipay_listener_head_block", "Latest chain head observed by the event listener",
+            self.head_block.load(Ordering::Relaxed));
+        gauge(out, "zkalipay_listener_synced_block", "Last block processed by the event listener",
+            self.synced_block.load(Ordering::Relaxed));
+        gauge(out, "zkalipay_listener_lag_blocks", "Blocks between chain head and last synced block",
+            self.lag_blocks());
+        gauge(out, "zkalipay_listener_last_poll_timestamp_seconds", "Unix time of the last successful head poll",
+            self.last_poll_at.load(Ordering::Relaxed).max(0) as u64);
+        counter(out, "zkalipay_listener_restarts_total", "Event listener restarts performed by the supervisor",
+            self.restarts.load(Ordering::Relaxed));
+
+        let events = self.events.lock().unwrap();
+        per_event(out, "zkalipay_listener_events_total", "Contract events handled per event type", &events, |c| c.handled);
+        per_event(out, "zkalipay_listener_event_failures_total", "Contract events whose handling failed (dead-lettered) per event type", &events, |c| c.failed);
+        per_event(out, "zkalipay_listener_event_replays_total", "Contract events seen again in a resync, side effects skipped, per event type", &events, |c| c.replayed);
+    }
+}
+
+/// Per-key request counts for market-maker API keys (see api::api_keys)
+#[derive(Debug, Default)]
+pub struct ApiKeyMetrics {
+    /// Requests presenting an unknown or revoked key
+    auth_failures: AtomicU64,
+    usage: Mutex<BTreeMap<String, ApiKeyUsage>>, // key name -> counts
+}
+
+/// Requests of one API key since process start
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ApiKeyUsage {
+    pub requests: u64,
+    pub rate_limited: u64,
+    pub forbidden: u64,
+}
+
+impl ApiKeyMetrics {
+    pub fn record_auth_failure(&self) {
+        self.auth_failures.fetch_add(1, Ordering::Relaxed);
+    }
+
+    pub fn record_request(&self, key: &str) {
+        self.usage.lock().unwrap().entry(key.to_string()).or_default().requests += 1;
+    }
+
+    pub fn record_rate_limited(&self, key: &str) {
+        self.usage.lock().unwrap().entry(key.to_string()).or_default().rate_limited += 1;
+    }
+
+    pub fn record_forbidden(&self, key: &str) {
+        self.usage.lock().unwrap().entry(key.to_string()).or_default().forbidden += 1;
+    }
+
+    pub fn usage(&self, key: &str) -> ApiKeyUsage {
+        self.usage.lock().unwrap().get(key).cloned().unwrap_or_default()
+    }
+
+    fn render(&self, out: &mut String) {
+        counter(out, "zkalipay_api_key_auth_failures_total", "Requests with an unknown or revoked API key",
+            self.auth_failures.load(Ordering::Relaxed));
+
+        let usage = self.usage.lock().unwrap();
+        per_key(out, "zkalipay_api_key_requests_total", "Requests admitted per API key", &usage, |u| u.requests);
+        per_key(out, "zkalipay_api_key_rate_limited_total", "Requests rejected by an API key's rate limit", &usage, |u| u.rate_limited);
+        per_key(out, "zkalipay_api_key_forbidden_total", "Requests outside an API key's scopes", &usage, |u| u.forbidden);
+    }
+}
+
+/// Match requests served from the in-memory order book (see cache::orderbook)
+#[derive(Debug, Default)]
+pub struct OrderbookCacheMetrics {
+    hits: AtomicU64,
+    misses: AtomicU64,
+}
+
+impl OrderbookCacheMetrics {
+    pub fn record_hit(&self) {
+        self.hits.fetch_add(1, Ordering::Relaxed);
+    }
+
+    pub fn record_miss(&self) {
+        self.misses.fetch_add(1, Ordering::Relaxed);
+    }
+
+    fn render(&self, out: &mut String) {
+        counter(out, "zkalipay_orderbook_cache_hits_total", "Matches run against the in-memory order book",
+            self.hits.load(Ordering::Relaxed));
+        counter(out, "zkalipay_orderbook_cache_misses_total", "Matches that loaded the order book from Postgres",
+            self.misses.load(Ordering::Relaxed));
+    }
+}
+
+/// Contract reads answered from the client's read cache (see blockchain::read_cache)
+#[derive(Debug, Default)]
+pub struct ContractReadMetrics {
+    /// (hits, misses) per read
+    reads: Mutex<BTreeMap<&'static str, (u64, u64)>>,
+}
+
+impl ContractReadMetrics {
+    pub fn record(&self, read: &'static str, hit: bool) {
+        let mut reads = self.reads.lock().unwrap();
+        let (hits, misses) = reads.entry(read).or_default();
+        if hit {
+            *hits += 1;
+        } else {
+            *misses += 1;
+        }
+    }
+
+    fn render(&self, out: &mut String) {
+        let reads = self.reads.lock().unwrap();
+        per_read(out, "zkalipay_contract_read_cache_hits_total", "Contract reads served from the read cache",
+            &reads, |(hits, _)| *hits);
+        per_read(out, "zkalipay_contract_read_cache_misses_total", "Contract reads that called the contract",
+            &reads, |(_, misses)| *misses);
+    }
+}
+
+/// Utilization of the Postgres connection pools (see db::pool), read at render time
+#[derive(Debug, Default)]
+pub struct DbPoolMetrics {
+    pools: Mutex<Vec<(&'static str, PgPool)>>,
+}
+
+impl DbPoolMetrics {
+    /// Export a pool under `name` (the "pool" label)
+    pub fn register(&self, name: &'static str, pool: PgPool) {
+        self.pools.lock().unwrap().push((name, pool));
+    }
+
+    fn render(&self, out: &mut String) {
+        let pools = self.pools.lock().unwrap();
+        let gauges: [(&str, &str, fn(&PgPool) -> u64); 4] = [
+            ("zkalipay_db_pool_max_connections", "Connections a pool may open", |p| p.options().get_max_connections() as u64),
+            ("zkalipay_db_pool_connections", "Connections a pool has open", |p| p.size() as u64),
+            ("zkalipay_db_pool_idle_connections", "Open connections waiting in a pool", |p| p.num_idle() as u64),
+            ("zkalipay_db_pool_in_use_connections", "Connections checked out of a pool", |p| {
+                (p.size() as u64).saturating_sub(p.num_idle() as u64)
+            }),
+        ];
+        for (name, help, value) in gauges {
+            let _ = writeln!(out, "# HELP {} {}", name, help);
+            let _ = writeln!(out, "# TYPE {} gauge", name);
+            for (pool, handle) in pools.iter() {
+                let _ = writeln!(out, "{}{{pool=\"{}\"}} {}", name, pool, value(handle));
+            }
+        }
+    }
+}
+
+/// PENDING trades found resolved on-chain (see blockchain::trade_drift)
+#[derive(Debug, Default)]
+pub struct TradeDriftMetrics {
+    /// Drifted trades per on-chain status
+    drifted: Mutex<BTreeMap<&'static str, u64>>,
+    /// Trades compared with the escrow
+    checked: AtomicU64,
+}
+
+impl TradeDriftMetrics {
+    pub fn record_checked(&self, trades: u64) {
+        self.checked.fetch_add(trades, Ordering::Relaxed);
+    }
+
+    pub fn record_drift(&self, status: &'static str) {
+        *self.drifted.lock().unwrap().entry(status).or_default() += 1;
+    }
+
+    pub fn drifted(&self, status: &str) -> u64 {
+        self.drifted.lock().unwrap().get(status).copied().unwrap_or_default()
+    }
+
+    fn render(&self, out: &mut String) {
+        let _ = writeln!(out, "# HELP zkalipay_trade_drift_checked_total PENDING trades compared with the escrow");
+        let _ = writeln!(out, "# TYPE zkalipay_trade_drift_checked_total counter");
+        let _ = writeln!(out, "zkalipay_trade_drift_checked_total {}", self.checked.load(Ordering::Relaxed));
+        let _ = writeln!(out, "# HELP zkalipay_trade_drift_total PENDING trades the escrow had already settled or expired");
+        let _ = writeln!(out, "# TYPE zkalipay_trade_drift_total counter");
+        for (status, count) in self.drifted.lock().unwrap().iter() {
+            let _ = writeln!(out, "zkalipay_trade_drift_total{{status=\"{}\"}} {}", status, count);
+        }
+    }
+}
+
+/// Upper bounds (seconds) of the execute-fill phase histogram buckets
+const PHASE_BUCKETS: [f64; 10] = [0.05, 0.1, 0.25, 0.5, 1.0, 2.5, 5.0, 10.0, 30.0, 60.0];
+
+/// Duration of each execute-fill phase (see latency)
+#[derive(Debug, Default)]
+pub struct ExecuteFillMetrics {
+    phases: Mutex<BTreeMap<&'static str, PhaseHistogram>>,
+}
+
+/// Observations of one phase: count per bucket (not cumulative), sum and count
+#[derive(Debug, Clone, Default)]
+struct PhaseHistogram {
+    buckets: [u64; PHASE_BUCKETS.len()],
+    sum_secs: f64,
+    count: u64,
+}
+
+impl ExecuteFillMetrics {
+    /// Record the phases of one execute-fill request
+    pub fn observe(&self, breakdown: &LatencyBreakdown) {
+        let mut phases = self.phases.lock().unwrap();
+        for phase in &breakdown.phases {
+            let secs = phase.elapsed.as_secs_f64();
+            let histogram = phases.entry(phase.phase).or_default();
+            if let Some(bucket) = PHASE_BUCKETS.iter().position(|bound| secs <= *bound) {
+                histogram.buckets[bucket] += 1;
+            }
+            histogram.sum_secs += secs;
+            histogram.count += 1;
+        }
+    }
+
+    /// Requests that went through `phase`
+    pub fn count(&self, phase: &str) -> u64 {
+        self.phases.lock().unwrap().get(phase).map(|h| h.count).unwrap_or_default()
+    }
+
+    fn render(&self, out: &mut String) {
+        let name = "zkalipay_execute_fill_phase_seconds";
+        let _ = writeln!(out, "# HELP {} Time execute-fill requests spent per phase", name);
+        let _ = writeln!(out, "# TYPE {} histogram", name);
+        for (phase, histogram) in self.phases.lock().unwrap().iter() {
+            let mut cumulative = 0;
+            for (bound, count) in PHASE_BUCKETS.iter().zip(histogram.buckets) {
+                cumulative += count;
+                let _ = writeln!(out, "{}_bucket{{phase=\"{}\",le=\"{}\"}} {}", name, phase, bound, cumulative);
+            }
+            let _ = writeln!(out, "{}_bucket{{phase=\"{}\",le=\"+Inf\"}} {}", name, phase, histogram.count);
+            let _ = writeln!(out, "{}_sum{{phase=\"{}\"}} {}", name, phase, histogram.sum_secs);
+            let _ = writeln!(out, "{}_count{{phase=\"{}\"}} {}", name, phase, histogram.count);
+        }
+    }
+}
+
+/// Write a counter labeled by contract read
+fn per_read(out: &mut String, name: &str, help: &str, reads: &BTreeMap<&'static str, (u64, u64)>, value: fn(&(u64, u64)) -> u64) {
+    let _ = writeln!(out, "# HELP {} {}", name, help);
+    let _ = writeln!(out, "# TYPE {} counter", name);
+    for (read, counts) in reads {
+        let _ = writeln!(out, "{}{{read=\"{}\"}} {}", name, read, value(counts));
+    }
+}
+
+/// Write a counter labeled by API key name
+fn per_key(out: &mut String, name: &str, help: &str, usage: &BTreeMap<String, ApiKeyUsage>, value: fn(&ApiKeyUsage) -> u64) {
+    let _ = writeln!(out, "# HELP {} {}", name, help);
+    let _ = writeln!(out, "# TYPE {} counter", name);
+    for (key, counts) in usage {
+        let _ = writeln!(out, "{}{{key=\"{}\"}} {}", name, key, value(counts));
+    }
+}
+
+/// Write a counter labeled by event type
+fn per_event(out: &mut String, name: &str, help: &str, events: &BTreeMap<String, EventCounts>, value: fn(&EventCounts) -> u64) {
+    let _ = writeln!(out, "# HELP {} {}", name, help);
+    let _ = writeln!(out, "# TYPE {} counter", name);
+    for (event, counts) in events {
+        let _ = writeln!(out, "{}{{event=\"{}\"}} {}", name, event, value(counts));
+    }
+}
+
+/// Write a single unlabeled gauge
+pub(crate) fn gauge(out: &mut String, name: &str, help: &str, value: u64) {
+    let _ = writeln!(out, "# HELP {} {}", name, help);
+    let _ = writeln!(out, "# TYPE {} gauge", name);
+    let _ = writeln!(out, "{} {}", name, value);
+}
+
+/// Write a single unlabeled counter
+pub(crate) fn counter(out: &mut String, name: &str, help: &str, value: u64) {
+    let _ = writeln!(out, "# HELP {} {}", name, help);
+    let _ = writeln!(out, "# TYPE {} counter", name);
+    let _ = writeln!(out, "{} {}", name, value);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::latency::{PhaseTimer, PHASE_CONFIRMATION, PHASE_VALIDATION};
+    use std::time::Duration;
+
+    #[test]
+    fn test_listener_lag_and_render() {
+        let metrics = Metrics::new();
+        assert!(metrics.listener.health().is_none());
+
+        metrics.listener.set_enabled();
+        metrics.listener.record_poll(1_000);
+        metrics.listener.record_synced(990);
+        metrics.listener.record_restart("lag exceeded");
+
+        let health = metrics.listener.health().unwrap();
+        assert_eq!(health.lag_blocks, 10);
+        assert_eq!(health.restarts, 1);
+        assert_eq!(health.last_restart_reason.as_deref(), Some("lag exceeded"));
+
+        let text = metrics.render();
+        assert!(text.contains("# TYPE zkalipay_listener_lag_blocks gauge"));
+        assert!(text.contains("zkalipay_listener_lag_blocks 10\n"));
+        assert!(text.contains("zkalipay_listener_restarts_total 1\n"));
+
+        metrics.listener.record_event("TradeSettled", true);
+        metrics.listener.record_event("TradeSettled", false);
+        metrics.listener.record_replay("TradeSettled");
+        assert_eq!(metrics.listener.event_counts("TradeSettled"), EventCounts { handled: 1, failed: 1, replayed: 1 });
+        let text = metrics.render();
+        assert!(text.contains("zkalipay_listener_event_failures_total{event=\"TradeSettled\"} 1\n"));
+        assert!(text.contains("zkalipay_listener_event_replays_total{event=\"TradeSettled\"} 1\n"));
+    }
+
+    #[test]
+    fn test_api_key_usage_render() {
+        let metrics = Metrics::new();
+        metrics.api_keys.record_request("mm-1");
+        metrics.api_keys.record_request("mm-1");
+        metrics.api_keys.record_rate_limited("mm-1");
+
+        assert_eq!(metrics.api_keys.usage("mm-1"), ApiKeyUsage { requests: 2, rate_limited: 1, forbidden: 0 });
+        let text = metrics.render();
+        assert!(text.contains("zkalipay_api_key_requests_total{key=\"mm-1\"} 2\n"));
+        assert!(text.contains("zkalipay_api_key_rate_limited_total{key=\"mm-1\"} 1\n"));
+    }
+
+    #[test]
+    fn test_execute_fill_phase_histogram() {
+        let metrics = Metrics::new();
+        let timer = PhaseTimer::new();
+        timer.record(PHASE_VALIDATION, Duration::from_millis(80));
+        timer.record(PHASE_CONFIRMATION, Duration::from_secs(3));
+        metrics.execute_fill.observe(&timer.breakdown());
+        timer.record(PHASE_VALIDATION, Duration::from_millis(100));
+        metrics.execute_fill.observe(&timer.breakdown());
+
+        assert_eq!(metrics.execute_fill.count(PHASE_VALIDATION), 2);
+        let text = metrics.render();
+        assert!(text.contains("# TYPE zkalipay_execute_fill_phase_seconds histogram"));
+        assert!(text.contains("zkalipay_execute_fill_phase_seconds_bucket{phase=\"validation\",le=\"0.1\"} 1\n"));
+        assert!(text.contains("zkalipay_execute_fill_phase_seconds_bucket{phase=\"validation\",le=\"0.25\"} 2\n"));
+        assert!(text.contains("zkalipay_execute_fill_phase_seconds_bucket{phase=\"confirmation\",le=\"2.5\"} 0\n"));
+        assert!(text.contains("zkalipay_execute_fill_phase_seconds_bucket{phase=\"confirmation\",le=\"+Inf\"} 2\n"));
+        assert!(text.contains("zkalipay_execute_fill_phase_seconds_count{phase=\"confirmation\"} 2\n"));
+    }
+}
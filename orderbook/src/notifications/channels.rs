@@ -0,0 +1,116 @@
+// Per-user notification channels
+// The ChannelNotifier wraps the operator webhook: every event still goes to the
+// webhook, and once it's accepted the event's recipient (buyer or seller) is also
+// sent a rendered message on each channel they registered for it. A failed channel
+// is only logged - retrying the event would repeat the webhook and the other channels.
+
+use async_trait::async_trait;
+use std::sync::Arc;
+
+use super::adapters::ChannelAdapter;
+use super::templates::render;
+use super::{NotificationError, NotificationEvent, Notifier, WebhookNotifier};
+use crate::blockchain::address::EthAddress;
+use crate::db::notification_channels::{
+    NotificationChannelRepository, PostgresNotificationChannelRepository, CHANNEL_EMAIL, CHANNEL_SMS, CHANNEL_TELEGRAM,
+};
+
+/// Check a destination's shape for its channel
+pub fn validate_destination(channel: &str, destination: &str) -> Result<(), String> {
+    let valid = match channel {
+        CHANNEL_EMAIL => {
+            let mut parts = destination.split('@');
+            matches!(
+                (parts.next(), parts.next(), parts.next()),
+                (Some(local), Some(domain), None) if !local.is_empty() && domain.contains('.')
+            ) && !destination.chars().any(char::is_whitespace)
+        }
+        // E.164
+        CHANNEL_SMS => destination
+            .strip_prefix('+')
+            .is_some_and(|digits| (8..=15).contains(&digits.len()) && digits.bytes().all(|b| b.is_ascii_digit())),
+        // Numeric chat id (negative for groups)
+        CHANNEL_TELEGRAM => {
+            let digits = destination.strip_prefix('-').unwrap_or(destination);
+            !digits.is_empty() && digits.len() <= 20 && digits.bytes().all(|b| b.is_ascii_digit())
+        }
+        _ => return Err(format!("Unknown channel {}", channel)),
+    };
+    if valid {
+        Ok(())
+    } else {
+        Err(format!("Invalid {} destination {:?}", channel, destination))
+    }
+}
+
+pub struct ChannelNotifier {
+    repo: PostgresNotificationChannelRepository,
+    adapters: Vec<Arc<dyn ChannelAdapter>>,
+    webhook: Option<WebhookNotifier>,
+}
+
+impl ChannelNotifier {
+    pub fn new(db_pool: sqlx::PgPool, adapters: Vec<Arc<dyn ChannelAdapter>>, webhook: Option<WebhookNotifier>) -> Self {
+        Self {
+            repo: PostgresNotificationChannelRepository::new(db_pool),
+            adapters,
+            webhook,
+        }
+    }
+
+    /// Send the event on the recipient's channels that want it
+    async fn notify_channels(&self, recipient: &EthAddress, event: &NotificationEvent) {
+        let channels = match self.repo.list_by_address(recipient).await {
+            Ok(channels) => channels,
+            Err(e) => {
+                tracing::warn!("📨 Failed to load notification channels of {}: {}", recipient, e);
+                return;
+            }
+        };
+
+        let message = render(event);
+        for channel in channels {
+            if channel.events.as_ref().is_some_and(|events| !events.iter().any(|e| e == event.name())) {
+                continue;
+            }
+            let Some(adapter) = self.adapters.iter().find(|a| a.channel() == channel.channel) else {
+                continue;
+            };
+            match adapter.send(&channel.destination, &message).await {
+                Ok(()) => tracing::debug!("📨 Sent {} to {} via {}", event.name(), recipient, channel.channel),
+                Err(e) => tracing::warn!("📨 Failed to send {} to {} via {}: {}", event.name(), recipient, channel.channel, e),
+            }
+        }
+    }
+}
+
+#[async_trait]
+impl Notifier for ChannelNotifier {
+    async fn notify(&self, event: &NotificationEvent) -> Result<(), NotificationError> {
+        if let Some(webhook) = &self.webhook {
+            webhook.notify(event).await?;
+        }
+        if let Some(recipient) = event.recipient() {
+            self.notify_channels(recipient, event).await;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_validate_destination() {
+        assert!(validate_destination(CHANNEL_EMAIL, "seller@example.com").is_ok());
+        assert!(validate_destination(CHANNEL_SMS, "+8613945908941").is_ok());
+        assert!(validate_destination(CHANNEL_TELEGRAM, "-1001234567890").is_ok());
+
+        assert!(validate_destination(CHANNEL_EMAIL, "seller@localhost").is_err());
+        assert!(validate_destination(CHANNEL_EMAIL, "a b@example.com").is_err());
+        assert!(validate_destination(CHANNEL_SMS, "13945908941").is_err());
+        assert!(validate_destination(CHANNEL_TELEGRAM, "@seller").is_err());
+        assert!(validate_destination("pager", "123").is_err());
+    }
+}
@@ -0,0 +1,278 @@
+// Outbound notifications
+// Events are delivered as signed JSON POSTs (see webhooks) to the operator-configured
+// webhook, which can fan them out further, and to any extra webhook subscribers.
+// Wallets can also register their own email, SMS or Telegram destinations (see
+// channels), which get a templated message for their events.
+
+pub mod adapters;
+pub mod channels;
+pub mod reminders;
+pub mod sellers;
+pub mod stale_orders;
+pub mod templates;
+pub mod webhooks;
+
+use async_trait::async_trait;
+use chrono::Utc;
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+use std::time::Duration;
+use thiserror::Error;
+
+use crate::blockchain::address::EthAddress;
+use crate::db::webhook_deliveries::{PostgresWebhookDeliveryRepository, WebhookDeliveryRepository};
+use channels::ChannelNotifier;
+use webhooks::{WebhookPayload, WEBHOOK_DELIVERY_HEADER, WEBHOOK_SIGNATURE_HEADER, WEBHOOK_TIMESTAMP_HEADER};
+
+#[derive(Error, Debug)]
+pub enum NotificationError {
+    #[error("Webhook request failed: {0}")]
+    Request(String),
+    #[error("Webhook returned status {0}")]
+    Status(u16),
+    #[error("Notification configuration error: {0}")]
+    Config(String),
+}
+
+/// Event names (the "event" field) users can subscribe their channels to
+pub const NOTIFICATION_EVENTS: [&str; 4] = ["trade_expiry_reminder", "seller_trade_created", "trade_settled", "order_stale"];
+
+/// Notification payload (the "event" field names the type)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "event", rename_all = "snake_case")]
+pub enum NotificationEvent {
+    /// Buyer has not uploaded a payment PDF and the trade expires soon
+    TradeExpiryReminder {
+        trade_id: String,
+        buyer: EthAddress,
+        payment_nonce: String,
+        cny_amount: String,
+        expires_at: i64,
+        seconds_remaining: i64,
+    },
+    /// A buyer locked part of the seller's order: expect an Alipay transfer of
+    /// cny_amount (in cents) carrying payment_nonce into alipay_id before expires_at
+    SellerTradeCreated {
+        trade_id: String,
+        order_id: String,
+        seller: EthAddress,
+        buyer: EthAddress,
+        token: EthAddress,
+        token_amount: String,
+        cny_amount: String,
+        payment_nonce: String,
+        alipay_id: String,
+        expires_at: i64,
+    },
+    /// Settlement tx reached the configured confirmation depth: the trade is final
+    TradeSettled {
+        trade_id: String,
+        order_id: String,
+        buyer: EthAddress,
+        settlement_tx_hash: String,
+        settlement_block: u64,
+        confirmations: i32,
+    },
+    /// The seller's order has had no fills or seller activity for idle_days: withdraw
+    /// it, or refresh it to keep it in matching
+    OrderStale {
+        order_id: String,
+        seller: EthAddress,
+        token: EthAddress,
+        remaining_amount: String,
+        last_activity_at: i64,
+        idle_days: i64,
+    },
+    /// Mismatch rate of a receipt template spiked (likely an Alipay layout change)
+    TemplateFlagged {
+        template: String,
+        mismatch_rate: f64,
+        samples: i64,
+        window_secs: i64,
+    },
+}
+
+impl NotificationEvent {
+    /// The "event" field
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::TradeExpiryReminder { .. } => "trade_expiry_reminder",
+            Self::SellerTradeCreated { .. } => "seller_trade_created",
+            Self::TradeSettled { .. } => "trade_settled",
+            Self::OrderStale { .. } => "order_stale",
+            Self::TemplateFlagged { .. } => "template_flagged",
+        }
+    }
+
+    /// The wallet the event is about (None for operator events)
+    pub fn recipient(&self) -> Option<&EthAddress> {
+        match self {
+            Self::TradeExpiryReminder { buyer, .. } | Self::TradeSettled { buyer, .. } => Some(buyer),
+            Self::SellerTradeCreated { seller, .. } | Self::OrderStale { seller, .. } => Some(seller),
+            Self::TemplateFlagged { .. } => None,
+        }
+    }
+}
+
+/// Delivery channel for notification events
+#[async_trait]
+pub trait Notifier: Send + Sync {
+    async fn notify(&self, event: &NotificationEvent) -> Result<(), NotificationError>;
+}
+
+/// One webhook endpoint: the operator's (NOTIFICATION_WEBHOOK_URL) or a NOTIFICATION_WEBHOOKS entry
+#[derive(Debug, Clone)]
+pub struct WebhookSubscriber {
+    pub name: String,
+    pub url: String,
+    /// Bearer token sent along (operator webhook only)
+    pub token: Option<String>,
+    /// HMAC key the deliveries are signed with (None = unsigned)
+    pub secret: Option<String>,
+}
+
+/// POSTs events as signed JSON payloads (see webhooks) to each webhook subscriber
+/// Only a failure of the first subscriber is returned, so the event is retried; later
+/// subscribers' failures are logged, as a retry would deliver it to all of them again.
+pub struct WebhookNotifier {
+    subscribers: Vec<WebhookSubscriber>,
+    deliveries: PostgresWebhookDeliveryRepository,
+    client: reqwest::Client,
+}
+
+impl WebhookNotifier {
+    pub fn new(db_pool: sqlx::PgPool, subscribers: Vec<WebhookSubscriber>) -> Self {
+        let client = reqwest::Client::builder()
+            .timeout(Duration::from_secs(10))
+            .build()
+            .unwrap_or_default();
+
+        Self {
+            subscribers,
+            deliveries: PostgresWebhookDeliveryRepository::new(db_pool),
+            client,
+        }
+    }
+
+    /// Configure from the environment (None if no webhook is set):
+    /// - NOTIFICATION_WEBHOOK_URL / _TOKEN / _SECRET: the operator webhook
+    /// - NOTIFICATION_WEBHOOKS: comma-separated "name=url" subscribers, each signing
+    ///   with NOTIFICATION_WEBHOOK_SECRET_<NAME> (required)
+    pub fn from_env(db_pool: sqlx::PgPool) -> Result<Option<Self>, NotificationError> {
+        let mut subscribers = Vec::new();
+        if let Ok(url) = std::env::var("NOTIFICATION_WEBHOOK_URL") {
+            subscribers.push(WebhookSubscriber {
+                name: "operator".to_string(),
+                url,
+                token: std::env::var("NOTIFICATION_WEBHOOK_TOKEN").ok(),
+                secret: std::env::var("NOTIFICATION_WEBHOOK_SECRET").ok(),
+            });
+        }
+        for entry in std::env::var("NOTIFICATION_WEBHOOKS").unwrap_or_default().split(',') {
+            if entry.trim().is_empty() {
+                continue;
+            }
+            let (name, url) = entry
+                .trim()
+                .split_once('=')
+                .filter(|(name, url)| !name.is_empty() && !url.is_empty())
+                .ok_or_else(|| {
+                    NotificationError::Config(format!("Invalid NOTIFICATION_WEBHOOKS entry {:?}: expected name=url", entry))
+                })?;
+            let secret_var = format!("NOTIFICATION_WEBHOOK_SECRET_{}", name.to_ascii_uppercase().replace('-', "_"));
+            let secret = std::env::var(&secret_var)
+                .map_err(|_| NotificationError::Config(format!("Webhook subscriber {} needs {}", name, secret_var)))?;
+            subscribers.push(WebhookSubscriber {
+                name: name.to_string(),
+                url: url.to_string(),
+                token: None,
+                secret: Some(secret),
+            });
+        }
+
+        Ok((!subscribers.is_empty()).then(|| Self::new(db_pool, subscribers)))
+    }
+
+    async fn deliver(&self, subscriber: &WebhookSubscriber, event: &NotificationEvent) -> Result<(), NotificationError> {
+        let delivery_id = self
+            .deliveries
+            .next_id()
+            .await
+            .map_err(|e| NotificationError::Request(format!("No delivery ID: {}", e)))?;
+        let timestamp = Utc::now().timestamp();
+        let body = serde_json::to_vec(&WebhookPayload::new(event.clone(), delivery_id, timestamp))
+            .map_err(|e| NotificationError::Request(e.to_string()))?;
+
+        let mut request = self
+            .client
+            .post(&subscriber.url)
+            .header(reqwest::header::CONTENT_TYPE, "application/json")
+            .header(WEBHOOK_DELIVERY_HEADER, delivery_id.to_string())
+            .header(WEBHOOK_TIMESTAMP_HEADER, timestamp.to_string());
+        if let Some(secret) = &subscriber.secret {
+            request = request.header(WEBHOOK_SIGNATURE_HEADER, webhooks::sign(secret.as_bytes(), delivery_id, timestamp, &body));
+        }
+        if let Some(token) = &subscriber.token {
+            request = request.bearer_auth(token);
+        }
+
+        let response = request
+            .body(body)
+            .send()
+            .await
+            .map_err(|e| NotificationError::Request(e.to_string()))?;
+
+        if !response.status().is_success() {
+            return Err(NotificationError::Status(response.status().as_u16()));
+        }
+
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl Notifier for WebhookNotifier {
+    async fn notify(&self, event: &NotificationEvent) -> Result<(), NotificationError> {
+        for (i, subscriber) in self.subscribers.iter().enumerate() {
+            match self.deliver(subscriber, event).await {
+                Ok(()) => {}
+                Err(e) if i == 0 => return Err(e),
+                Err(e) => tracing::warn!("📨 Failed to deliver {} to webhook {}: {}", event.name(), subscriber.name, e),
+            }
+        }
+        Ok(())
+    }
+}
+
+/// The notifier for the configured delivery: the webhooks (NOTIFICATION_WEBHOOK_URL,
+/// NOTIFICATION_WEBHOOKS) and/or user channels (see adapters); None if neither is configured
+pub fn notifier_from_env(db_pool: sqlx::PgPool) -> Result<Option<Arc<dyn Notifier>>, NotificationError> {
+    let webhook = WebhookNotifier::from_env(db_pool.clone())?;
+    let adapters = adapters::adapters_from_env()?;
+    Ok(match (webhook, adapters.is_empty()) {
+        (None, true) => None,
+        (Some(webhook), true) => Some(Arc::new(webhook)),
+        (webhook, false) => Some(Arc::new(ChannelNotifier::new(db_pool, adapters, webhook))),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_event_names_match_serialized_tag() {
+        let event = NotificationEvent::TradeSettled {
+            trade_id: "0xab".to_string(),
+            order_id: "0xcd".to_string(),
+            buyer: "0x2222222222222222222222222222222222222222".parse().unwrap(),
+            settlement_tx_hash: "0xef".to_string(),
+            settlement_block: 10,
+            confirmations: 3,
+        };
+        let json = serde_json::to_value(&event).unwrap();
+        assert_eq!(json["event"], event.name());
+        assert!(NOTIFICATION_EVENTS.contains(&event.name()));
+        assert_eq!(event.recipient().map(|a| a.as_str()), Some("0x2222222222222222222222222222222222222222"));
+    }
+}
@@ -0,0 +1,102 @@
+// Pre-expiry payment reminders
+// Buyers who lock a trade and then forget to pay lose the trade when it expires
+// on-chain. The scheduler scans the trades table for PENDING trades without an
+// uploaded PDF that expire within the lead time and notifies each one once. The same
+// scan retries seller notices of new trades that couldn't be delivered (see sellers)
+// and nudges sellers of stale orders (see stale_orders).
+
+use chrono::Utc;
+use std::sync::Arc;
+use tokio::task::JoinHandle;
+use tokio::time::{interval, Duration};
+
+use super::sellers::SellerNotifier;
+use super::stale_orders::StaleOrderNotifier;
+use super::{NotificationEvent, Notifier};
+use crate::db::reminders::{PostgresReminderRepository, ReminderRepository, REMINDER_EXPIRY};
+use crate::db::DbResult;
+
+/// Default lead time before expiresAt
+pub const DEFAULT_REMINDER_LEAD_SECS: u64 = 10 * 60;
+
+/// How often upcoming expirations are scanned
+const SCAN_INTERVAL: Duration = Duration::from_secs(60);
+
+/// Maximum reminders dispatched per scan
+const SCAN_BATCH: i64 = 100;
+
+pub struct ReminderScheduler {
+    repo: PostgresReminderRepository,
+    notifier: Arc<dyn Notifier>,
+    sellers: SellerNotifier,
+    stale_orders: StaleOrderNotifier,
+    lead_secs: u64,
+}
+
+impl ReminderScheduler {
+    pub fn new(db_pool: sqlx::PgPool, notifier: Arc<dyn Notifier>, lead_secs: u64) -> Self {
+        Self {
+            repo: PostgresReminderRepository::new(db_pool.clone()),
+            sellers: SellerNotifier::new(db_pool.clone(), notifier.clone()),
+            stale_orders: StaleOrderNotifier::new(db_pool, notifier.clone()),
+            notifier,
+            lead_secs,
+        }
+    }
+
+    /// Spawn the scan loop as a background task
+    pub fn spawn(self) -> JoinHandle<()> {
+        tokio::spawn(async move { self.run().await })
+    }
+
+    async fn run(self) {
+        let mut ticker = interval(SCAN_INTERVAL);
+        loop {
+            ticker.tick().await;
+            if let Err(e) = self.dispatch_due().await {
+                tracing::warn!("⏰ Reminder scan failed: {}", e);
+            }
+            if let Err(e) = self.sellers.dispatch_missed().await {
+                tracing::warn!("📨 Seller notice retry failed: {}", e);
+            }
+            if let Err(e) = self.stale_orders.dispatch_due().await {
+                tracing::warn!("🕸️ Stale order scan failed: {}", e);
+            }
+        }
+    }
+
+    /// Send reminders for every trade currently inside the lead window
+    /// Returns the number of reminders delivered.
+    pub async fn dispatch_due(&self) -> DbResult<usize> {
+        let now = Utc::now().timestamp();
+        let due = self
+            .repo
+            .list_due(REMINDER_EXPIRY, now, self.lead_secs as i64, SCAN_BATCH)
+            .await?;
+
+        let mut sent = 0;
+        for trade in due {
+            let trade_id = trade.trade_id.clone();
+            let event = NotificationEvent::TradeExpiryReminder {
+                seconds_remaining: (trade.expires_at - now).max(0),
+                trade_id: trade.trade_id,
+                buyer: trade.buyer,
+                payment_nonce: trade.payment_nonce,
+                cny_amount: trade.cny_amount,
+                expires_at: trade.expires_at,
+            };
+
+            // Only delivered reminders are recorded - failures retry on the next scan
+            match self.notifier.notify(&event).await {
+                Ok(()) => {
+                    self.repo.mark_sent(&trade_id, REMINDER_EXPIRY).await?;
+                    tracing::info!("⏰ Sent expiry reminder for trade {}", trade_id);
+                    sent += 1;
+                }
+                Err(e) => tracing::warn!("⏰ Failed to send expiry reminder for trade {}: {}", trade_id, e),
+            }
+        }
+
+        Ok(sent)
+    }
+}
@@ -0,0 +1,92 @@
+// Seller notifications for new trades
+// Sellers used to learn about a trade only when the Alipay transfer arrived. When the
+// listener syncs a TradeCreated event the seller is told the expected CNY amount and
+// payment nonce, so they can anticipate the transfer and check it against the trade.
+// Each trade's notice is recorded in trade_reminders once delivered; notices that
+// failed are retried by the reminder scan while the trade still awaits payment.
+
+use chrono::Utc;
+use std::sync::Arc;
+
+use super::{NotificationEvent, Notifier};
+use crate::db::models::DbSellerNotice;
+use crate::db::reminders::{PostgresReminderRepository, ReminderRepository, REMINDER_SELLER_TRADE};
+use crate::db::DbResult;
+
+/// Trades younger than this are left to the listener's own delivery
+const RETRY_GRACE_SECS: i64 = 120;
+
+/// Maximum notices retried per scan
+const RETRY_BATCH: i64 = 100;
+
+pub struct SellerNotifier {
+    repo: PostgresReminderRepository,
+    notifier: Arc<dyn Notifier>,
+}
+
+impl SellerNotifier {
+    pub fn new(db_pool: sqlx::PgPool, notifier: Arc<dyn Notifier>) -> Self {
+        Self {
+            repo: PostgresReminderRepository::new(db_pool),
+            notifier,
+        }
+    }
+
+    /// Tell the seller about a newly synced trade (no-op if already told)
+    /// Returns whether a notice was delivered.
+    pub async fn notify_trade(&self, trade_id: &str) -> DbResult<bool> {
+        match self.repo.get_seller_notice(trade_id, REMINDER_SELLER_TRADE).await? {
+            Some(notice) => self.deliver(notice).await,
+            None => Ok(false),
+        }
+    }
+
+    /// Retry undelivered notices of trades still awaiting payment
+    /// Returns the number of notices delivered.
+    pub async fn dispatch_missed(&self) -> DbResult<usize> {
+        let now = Utc::now();
+        let synced_before = now - chrono::Duration::seconds(RETRY_GRACE_SECS);
+        let due = self
+            .repo
+            .list_seller_notices_due(REMINDER_SELLER_TRADE, now.timestamp(), synced_before, RETRY_BATCH)
+            .await?;
+
+        let mut sent = 0;
+        for notice in due {
+            if self.deliver(notice).await? {
+                sent += 1;
+            }
+        }
+        Ok(sent)
+    }
+
+    async fn deliver(&self, notice: DbSellerNotice) -> DbResult<bool> {
+        let trade_id = notice.trade_id.clone();
+        let seller = notice.seller.clone();
+        let event = NotificationEvent::SellerTradeCreated {
+            trade_id: notice.trade_id,
+            order_id: notice.order_id,
+            seller: notice.seller,
+            buyer: notice.buyer,
+            token: notice.token,
+            token_amount: notice.token_amount,
+            cny_amount: notice.cny_amount,
+            payment_nonce: notice.payment_nonce,
+            alipay_id: notice.alipay_id,
+            expires_at: notice.expires_at,
+        };
+
+        // Only delivered notices are recorded - failures retry on the next scan
+        match self.notifier.notify(&event).await {
+            Ok(()) => {
+                self.repo.mark_sent(&trade_id, REMINDER_SELLER_TRADE).await?;
+                tracing::info!("📨 Notified seller {} of trade {}", seller, trade_id);
+                Ok(true)
+            }
+            Err(e) => {
+                tracing::warn!("📨 Failed to notify seller {} of trade {}: {}", seller, trade_id, e);
+                Ok(false)
+            }
+        }
+    }
+}
@@ -0,0 +1,68 @@
+// Stale-order nudges
+// Orders never expire on-chain, so liquidity a seller stopped watching stays matchable.
+// An active order with no fills, withdrawals or seller refreshes for the admin-set
+// "stale after" days gets its seller a nudge to withdraw or refresh it, repeated at most
+// every "notify every" days while it stays idle. Runs as part of the reminder scan.
+
+use chrono::{Duration, Utc};
+use std::sync::Arc;
+
+use super::{NotificationEvent, Notifier};
+use crate::db::order_staleness::{OrderStalenessRepository, PostgresOrderStalenessRepository};
+use crate::db::DbResult;
+
+/// Maximum nudges dispatched per scan
+const SCAN_BATCH: i64 = 100;
+
+pub struct StaleOrderNotifier {
+    repo: PostgresOrderStalenessRepository,
+    notifier: Arc<dyn Notifier>,
+}
+
+impl StaleOrderNotifier {
+    pub fn new(db_pool: sqlx::PgPool, notifier: Arc<dyn Notifier>) -> Self {
+        Self {
+            repo: PostgresOrderStalenessRepository::new(db_pool),
+            notifier,
+        }
+    }
+
+    /// Nudge the sellers of stale orders not nudged within the notify interval
+    /// Returns the number of nudges delivered.
+    pub async fn dispatch_due(&self) -> DbResult<usize> {
+        let settings = self.repo.settings().await?;
+        let now = Utc::now();
+        let stale_before = now - Duration::days(settings.stale_after_days as i64);
+        let notified_before = now - Duration::days(settings.notify_every_days as i64);
+        let due = self
+            .repo
+            .list_stale(None, stale_before, Some(notified_before), Some(SCAN_BATCH))
+            .await?;
+
+        let mut sent = 0;
+        for order in due {
+            let order_id = order.order_id.clone();
+            let seller = order.seller.clone();
+            let event = NotificationEvent::OrderStale {
+                idle_days: (now - order.last_activity_at).num_days(),
+                last_activity_at: order.last_activity_at.timestamp(),
+                order_id: order.order_id,
+                seller: order.seller,
+                token: order.token,
+                remaining_amount: order.remaining_amount,
+            };
+
+            // Only delivered nudges are recorded - failures retry on the next scan
+            match self.notifier.notify(&event).await {
+                Ok(()) => {
+                    self.repo.mark_notified(&order_id).await?;
+                    tracing::info!("🕸️ Nudged seller {} about stale order {}", seller, order_id);
+                    sent += 1;
+                }
+                Err(e) => tracing::warn!("🕸️ Failed to nudge seller {} about stale order {}: {}", seller, order_id, e),
+            }
+        }
+
+        Ok(sent)
+    }
+}
@@ -0,0 +1,123 @@
+// Message templates for user channels
+// The webhook receives events as JSON; email, SMS and Telegram get a short subject
+// and a plain-text body rendered here (SMS and Telegram send both as one text).
+
+use chrono::{TimeZone, Utc};
+
+use super::NotificationEvent;
+use crate::text_utils::format_cents;
+
+/// A rendered notification
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct NotificationMessage {
+    pub subject: String,
+    pub body: String,
+}
+
+impl NotificationMessage {
+    /// Subject and body as one text (SMS, chat)
+    pub fn text(&self) -> String {
+        format!("{}\n\n{}", self.subject, self.body)
+    }
+}
+
+/// "¥7.35" from an amount in cents (the raw value if it isn't a number)
+fn yuan(cents: &str) -> String {
+    match cents.parse::<u64>() {
+        Ok(cents) => format!("¥{}", format_cents(cents)),
+        Err(_) => format!("{} cents", cents),
+    }
+}
+
+fn utc_time(timestamp: i64) -> String {
+    match Utc.timestamp_opt(timestamp, 0).single() {
+        Some(time) => time.format("%Y-%m-%d %H:%M UTC").to_string(),
+        None => timestamp.to_string(),
+    }
+}
+
+pub fn render(event: &NotificationEvent) -> NotificationMessage {
+    match event {
+        NotificationEvent::TradeExpiryReminder { trade_id, payment_nonce, cny_amount, expires_at, seconds_remaining, .. } => {
+            NotificationMessage {
+                subject: format!("Your zkAlipay trade expires in {} min", seconds_remaining / 60),
+                body: format!(
+                    "Pay {} by Alipay with payment note {} and upload the receipt before {}, \
+                     or the trade expires and the tokens go back to the seller.\nTrade: {}",
+                    yuan(cny_amount),
+                    payment_nonce,
+                    utc_time(*expires_at),
+                    trade_id
+                ),
+            }
+        }
+        NotificationEvent::SellerTradeCreated { trade_id, cny_amount, payment_nonce, alipay_id, expires_at, .. } => {
+            NotificationMessage {
+                subject: format!("Incoming Alipay payment of {}", yuan(cny_amount)),
+                body: format!(
+                    "A buyer filled your zkAlipay order. Expect {} into Alipay account {} with \
+                     payment note {} before {}.\nTrade: {}",
+                    yuan(cny_amount),
+                    alipay_id,
+                    payment_nonce,
+                    utc_time(*expires_at),
+                    trade_id
+                ),
+            }
+        }
+        NotificationEvent::TradeSettled { trade_id, settlement_tx_hash, confirmations, .. } => NotificationMessage {
+            subject: "Your zkAlipay trade is settled".to_string(),
+            body: format!(
+                "Trade {} is final: settlement transaction {} has {} confirmations.",
+                trade_id, settlement_tx_hash, confirmations
+            ),
+        },
+        NotificationEvent::OrderStale { order_id, remaining_amount, last_activity_at, idle_days, .. } => NotificationMessage {
+            subject: format!("Your zkAlipay order has been idle for {} days", idle_days),
+            body: format!(
+                "Order {} still holds {} token units but has had no fills or updates since {}. \
+                 Withdraw what's left, or refresh the order if you still watch its Alipay account - \
+                 buyers can choose to skip stale orders.",
+                order_id,
+                remaining_amount,
+                utc_time(*last_activity_at)
+            ),
+        },
+        NotificationEvent::TemplateFlagged { template, mismatch_rate, samples, window_secs } => NotificationMessage {
+            subject: format!("Receipt template {} flagged", template),
+            body: format!(
+                "{:.0}% of {} settlements with template {} failed in the last {} min.",
+                mismatch_rate * 100.0,
+                samples,
+                template,
+                window_secs / 60
+            ),
+        },
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_seller_trade_message() {
+        let event = NotificationEvent::SellerTradeCreated {
+            trade_id: "0xab".to_string(),
+            order_id: "0xcd".to_string(),
+            seller: "0x1111111111111111111111111111111111111111".parse().unwrap(),
+            buyer: "0x2222222222222222222222222222222222222222".parse().unwrap(),
+            token: "0x3333333333333333333333333333333333333333".parse().unwrap(),
+            token_amount: "1000000".to_string(),
+            cny_amount: "73500".to_string(),
+            payment_nonce: "12345678".to_string(),
+            alipay_id: "13945908941".to_string(),
+            expires_at: 1_700_000_900,
+        };
+
+        let message = render(&event);
+        assert_eq!(message.subject, "Incoming Alipay payment of ¥735.00");
+        assert!(message.body.contains("payment note 12345678 before 2023-11-14 22:28 UTC"));
+        assert!(message.text().starts_with("Incoming Alipay payment of ¥735.00\n\nA buyer"));
+    }
+}
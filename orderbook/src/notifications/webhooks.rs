@@ -0,0 +1,230 @@
+// Signed webhook payloads
+// Webhook POSTs used to be authenticated by a bearer token at most, so a leaked token
+// or a captured request could be replayed at will. Each delivery now carries a
+// monotonically increasing delivery ID (shared sequence, see
+// migrations/043_webhook_deliveries.sql) and a timestamp, in the body and in headers,
+// and the subscriber's HMAC-SHA256 over "<delivery id>.<timestamp>.<body>":
+//
+//   X-ZkAlipay-Delivery: 1042
+//   X-ZkAlipay-Timestamp: 1718000000
+//   X-ZkAlipay-Signature: sha256=<hex>
+//
+// The body is a WebhookPayload: the event's fields (the "event" field names it) plus
+// schema_version, delivery_id and timestamp. Consumers authenticate a callback with
+// verify_webhook, which checks the signature, the timestamp tolerance and that the
+// delivery ID is newer than the last one they accepted, and parses the payload
+// against this crate's event schema.
+
+use hmac::{Hmac, Mac};
+use serde::{Deserialize, Serialize};
+use sha2::Sha256;
+use thiserror::Error;
+
+use super::NotificationEvent;
+
+pub const WEBHOOK_DELIVERY_HEADER: &str = "x-zkalipay-delivery";
+pub const WEBHOOK_TIMESTAMP_HEADER: &str = "x-zkalipay-timestamp";
+pub const WEBHOOK_SIGNATURE_HEADER: &str = "x-zkalipay-signature";
+
+/// Version of the payload layout (bumped on incompatible changes)
+pub const WEBHOOK_SCHEMA_VERSION: u32 = 1;
+
+/// How far a delivery's timestamp may be from the consumer's clock
+pub const WEBHOOK_TOLERANCE_SECS: i64 = 300;
+
+const SIGNATURE_PREFIX: &str = "sha256=";
+
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+pub enum WebhookVerifyError {
+    #[error("Missing or malformed {0} header")]
+    MalformedHeader(&'static str),
+    #[error("Signature doesn't match the payload")]
+    BadSignature,
+    #[error("Delivery timestamp {timestamp} is more than {}s from now ({now})", WEBHOOK_TOLERANCE_SECS)]
+    Expired { timestamp: i64, now: i64 },
+    #[error("Delivery {delivery_id} is not newer than the last accepted delivery {last_delivery_id}")]
+    Replayed { delivery_id: u64, last_delivery_id: u64 },
+    #[error("Invalid payload: {0}")]
+    InvalidPayload(String),
+    #[error("Unsupported payload schema version {0}")]
+    UnsupportedSchema(u32),
+    #[error("Payload delivery ID or timestamp doesn't match the headers")]
+    HeaderMismatch,
+}
+
+/// Body of a webhook delivery
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WebhookPayload {
+    pub schema_version: u32,
+    pub delivery_id: u64,
+    pub timestamp: i64,
+    #[serde(flatten)]
+    pub event: NotificationEvent,
+}
+
+impl WebhookPayload {
+    pub fn new(event: NotificationEvent, delivery_id: u64, timestamp: i64) -> Self {
+        Self { schema_version: WEBHOOK_SCHEMA_VERSION, delivery_id, timestamp, event }
+    }
+}
+
+/// The authentication headers of a delivery, as received
+#[derive(Debug, Clone, Copy)]
+pub struct WebhookHeaders<'a> {
+    pub delivery_id: &'a str,
+    pub timestamp: &'a str,
+    pub signature: &'a str,
+}
+
+impl<'a> WebhookHeaders<'a> {
+    /// Read the headers from a request received with axum / http 1.x
+    pub fn from_header_map(headers: &'a axum::http::HeaderMap) -> Result<Self, WebhookVerifyError> {
+        let header = |name: &'static str| {
+            headers
+                .get(name)
+                .and_then(|value| value.to_str().ok())
+                .ok_or(WebhookVerifyError::MalformedHeader(name))
+        };
+        Ok(Self {
+            delivery_id: header(WEBHOOK_DELIVERY_HEADER)?,
+            timestamp: header(WEBHOOK_TIMESTAMP_HEADER)?,
+            signature: header(WEBHOOK_SIGNATURE_HEADER)?,
+        })
+    }
+}
+
+fn mac(secret: &[u8], delivery_id: u64, timestamp: i64, body: &[u8]) -> Hmac<Sha256> {
+    let mut mac = Hmac::<Sha256>::new_from_slice(secret).expect("HMAC takes keys of any length");
+    mac.update(format!("{}.{}.", delivery_id, timestamp).as_bytes());
+    mac.update(body);
+    mac
+}
+
+/// X-ZkAlipay-Signature value of a delivery
+pub fn sign(secret: &[u8], delivery_id: u64, timestamp: i64, body: &[u8]) -> String {
+    format!("{}{}", SIGNATURE_PREFIX, hex::encode(mac(secret, delivery_id, timestamp, body).finalize().into_bytes()))
+}
+
+/// Authenticate a webhook delivery and parse its payload
+/// `now` is the consumer's unix time; `last_delivery_id` the newest delivery it has
+/// accepted from this sender (None for the first), which it should update on success.
+pub fn verify_webhook(
+    secret: &[u8],
+    headers: &WebhookHeaders,
+    body: &[u8],
+    now: i64,
+    last_delivery_id: Option<u64>,
+) -> Result<WebhookPayload, WebhookVerifyError> {
+    let delivery_id: u64 = headers
+        .delivery_id
+        .trim()
+        .parse()
+        .map_err(|_| WebhookVerifyError::MalformedHeader(WEBHOOK_DELIVERY_HEADER))?;
+    let timestamp: i64 = headers
+        .timestamp
+        .trim()
+        .parse()
+        .map_err(|_| WebhookVerifyError::MalformedHeader(WEBHOOK_TIMESTAMP_HEADER))?;
+    let signature = headers
+        .signature
+        .trim()
+        .strip_prefix(SIGNATURE_PREFIX)
+        .and_then(|signature| hex::decode(signature).ok())
+        .ok_or(WebhookVerifyError::MalformedHeader(WEBHOOK_SIGNATURE_HEADER))?;
+
+    // Constant-time comparison
+    mac(secret, delivery_id, timestamp, body)
+        .verify_slice(&signature)
+        .map_err(|_| WebhookVerifyError::BadSignature)?;
+
+    if (now - timestamp).abs() > WEBHOOK_TOLERANCE_SECS {
+        return Err(WebhookVerifyError::Expired { timestamp, now });
+    }
+    if let Some(last_delivery_id) = last_delivery_id.filter(|last| delivery_id <= *last) {
+        return Err(WebhookVerifyError::Replayed { delivery_id, last_delivery_id });
+    }
+
+    let payload: WebhookPayload =
+        serde_json::from_slice(body).map_err(|e| WebhookVerifyError::InvalidPayload(e.to_string()))?;
+    if payload.schema_version != WEBHOOK_SCHEMA_VERSION {
+        return Err(WebhookVerifyError::UnsupportedSchema(payload.schema_version));
+    }
+    if payload.delivery_id != delivery_id || payload.timestamp != timestamp {
+        return Err(WebhookVerifyError::HeaderMismatch);
+    }
+    Ok(payload)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SECRET: &[u8] = b"whsec_test";
+
+    fn delivery(delivery_id: u64, timestamp: i64) -> (Vec<u8>, String) {
+        let event = NotificationEvent::TradeSettled {
+            trade_id: "0xab".to_string(),
+            order_id: "0xcd".to_string(),
+            buyer: "0x2222222222222222222222222222222222222222".parse().unwrap(),
+            settlement_tx_hash: "0xef".to_string(),
+            settlement_block: 10,
+            confirmations: 3,
+        };
+        let body = serde_json::to_vec(&WebhookPayload::new(event, delivery_id, timestamp)).unwrap();
+        let signature = sign(SECRET, delivery_id, timestamp, &body);
+        (body, signature)
+    }
+
+    #[test]
+    fn test_verify_webhook_accepts_signed_deliveries_once() {
+        let (body, signature) = delivery(7, 1_000);
+        let headers = WebhookHeaders { delivery_id: "7", timestamp: "1000", signature: &signature };
+
+        let payload = verify_webhook(SECRET, &headers, &body, 1_010, Some(6)).unwrap();
+        assert_eq!(payload.event.name(), "trade_settled");
+        assert_eq!(payload.delivery_id, 7);
+        // Event fields stay at the top level, next to the delivery metadata
+        let json: serde_json::Value = serde_json::from_slice(&body).unwrap();
+        assert_eq!((json["event"].as_str(), json["trade_id"].as_str()), (Some("trade_settled"), Some("0xab")));
+
+        assert_eq!(
+            verify_webhook(SECRET, &headers, &body, 1_010, Some(7)).unwrap_err(),
+            WebhookVerifyError::Replayed { delivery_id: 7, last_delivery_id: 7 }
+        );
+        assert!(matches!(
+            verify_webhook(SECRET, &headers, &body, 1_000 + WEBHOOK_TOLERANCE_SECS + 1, None),
+            Err(WebhookVerifyError::Expired { .. })
+        ));
+        assert_eq!(verify_webhook(b"other", &headers, &body, 1_010, None).unwrap_err(), WebhookVerifyError::BadSignature);
+
+        let mut tampered = body.clone();
+        tampered.extend_from_slice(b" ");
+        assert_eq!(verify_webhook(SECRET, &headers, &tampered, 1_010, None).unwrap_err(), WebhookVerifyError::BadSignature);
+
+        // Headers can't be moved to another delivery's body
+        let moved = WebhookHeaders { delivery_id: "8", timestamp: "1000", signature: &sign(SECRET, 8, 1_000, &body) };
+        assert_eq!(verify_webhook(SECRET, &moved, &body, 1_010, None).unwrap_err(), WebhookVerifyError::HeaderMismatch);
+
+        let unsigned = WebhookHeaders { signature: "deadbeef", ..headers };
+        assert_eq!(
+            verify_webhook(SECRET, &unsigned, &body, 1_010, None).unwrap_err(),
+            WebhookVerifyError::MalformedHeader(WEBHOOK_SIGNATURE_HEADER)
+        );
+    }
+
+    #[test]
+    fn test_verify_webhook_checks_payload_schema() {
+        let body = br#"{"schema_version":1,"delivery_id":1,"timestamp":1000,"event":"order_cancelled"}"#;
+        let signature = sign(SECRET, 1, 1_000, body);
+        let headers = WebhookHeaders { delivery_id: "1", timestamp: "1000", signature: &signature };
+        assert!(matches!(
+            verify_webhook(SECRET, &headers, body, 1_000, None),
+            Err(WebhookVerifyError::InvalidPayload(_))
+        ));
+
+        let body = br#"{"schema_version":2,"delivery_id":1,"timestamp":1000,"event":"template_flagged","template":"t","mismatch_rate":0.5,"samples":4,"window_secs":60}"#;
+        let signature = sign(SECRET, 1, 1_000, body);
+        let headers = WebhookHeaders { delivery_id: "1", timestamp: "1000", signature: &signature };
+        assert_eq!(verify_webhook(SECRET, &headers, body, 1_000, None).unwrap_err(), WebhookVerifyError::UnsupportedSchema(2));
+    }
+}
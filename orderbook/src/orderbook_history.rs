@@ -0,0 +1,81 @@
+// Order book history snapshots
+// The orders table only holds the current book, so spreads and liquidity can't be
+// charted after the fact. The scheduler aggregates the book per token once per
+// interval into orderbook_snapshots, keyed by the interval-aligned bucket start;
+// /api/analytics/orderbook-history reads them back.
+//
+// Configuration (env):
+// - ORDERBOOK_SNAPSHOT_INTERVAL_SECS: bucket width (default 300, 0 disables snapshots)
+
+use chrono::{DateTime, TimeZone, Utc};
+use tokio::task::JoinHandle;
+use tokio::time::{interval, Duration, MissedTickBehavior};
+
+use crate::db::orderbook_snapshots::{OrderbookSnapshotRepository, PostgresOrderbookSnapshotRepository};
+use crate::db::DbResult;
+
+/// Default bucket width
+pub const DEFAULT_SNAPSHOT_INTERVAL_SECS: u64 = 5 * 60;
+
+pub struct OrderbookSnapshotScheduler {
+    repo: PostgresOrderbookSnapshotRepository,
+    interval_secs: u64,
+}
+
+impl OrderbookSnapshotScheduler {
+    pub fn new(db_pool: sqlx::PgPool, interval_secs: u64) -> Self {
+        Self {
+            repo: PostgresOrderbookSnapshotRepository::new(db_pool),
+            interval_secs: interval_secs.max(1),
+        }
+    }
+
+    /// Spawn the snapshot loop as a background task
+    pub fn spawn(self) -> JoinHandle<()> {
+        tokio::spawn(async move { self.run().await })
+    }
+
+    async fn run(self) {
+        let mut ticker = interval(Duration::from_secs(self.interval_secs));
+        ticker.set_missed_tick_behavior(MissedTickBehavior::Delay);
+        loop {
+            ticker.tick().await;
+            if let Err(e) = self.snapshot_now().await {
+                tracing::warn!("📸 Order book snapshot failed: {}", e);
+            }
+        }
+    }
+
+    /// Record the current book into the bucket containing now
+    /// Returns the number of tokens recorded.
+    pub async fn snapshot_now(&self) -> DbResult<u64> {
+        let bucket = bucket_start(Utc::now(), self.interval_secs);
+        let tokens = self.repo.record(bucket).await?;
+        tracing::debug!("📸 Order book snapshot {} ({} tokens)", bucket.to_rfc3339(), tokens);
+        Ok(tokens)
+    }
+}
+
+/// Start of the `interval_secs`-wide bucket containing `at` (buckets are aligned to the epoch)
+pub fn bucket_start(at: DateTime<Utc>, interval_secs: u64) -> DateTime<Utc> {
+    let interval = interval_secs.max(1) as i64;
+    let secs = at.timestamp();
+    Utc.timestamp_opt(secs - secs.rem_euclid(interval), 0).single().unwrap_or(at)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_bucket_start() {
+        let at = Utc.with_ymd_and_hms(2025, 3, 1, 12, 7, 42).unwrap();
+        assert_eq!(bucket_start(at, 300), Utc.with_ymd_and_hms(2025, 3, 1, 12, 5, 0).unwrap());
+        assert_eq!(bucket_start(at, 3600), Utc.with_ymd_and_hms(2025, 3, 1, 12, 0, 0).unwrap());
+
+        // Already aligned, and a zero interval doesn't divide by zero
+        let aligned = Utc.with_ymd_and_hms(2025, 3, 1, 12, 5, 0).unwrap();
+        assert_eq!(bucket_start(aligned, 300), aligned);
+        assert_eq!(bucket_start(at, 0), at);
+    }
+}
@@ -0,0 +1,52 @@
+// Local PDF text extraction for matching uploaded receipts to trades
+// This is a best-effort convenience (the guest program remains the source of truth):
+// it only reads the text layer to find the payment nonce, which the receipt prints on
+// a line of its own (see proof_inputs::ProofInputs::lines).
+
+use thiserror::Error;
+
+use crate::blockchain::types::validate_payment_nonce;
+
+#[derive(Error, Debug)]
+pub enum PdfTextError {
+    #[error("Failed to extract PDF text: {0}")]
+    Extraction(String),
+}
+
+/// Extract the text layer of a PDF
+/// CPU-bound and can be slow for large files - call from a blocking task
+pub fn extract_text(pdf_bytes: &[u8]) -> Result<String, PdfTextError> {
+    // The parser panics on some malformed inputs; treat that as an extraction failure
+    std::panic::catch_unwind(|| pdf_extract::extract_text_from_mem(pdf_bytes))
+        .map_err(|_| PdfTextError::Extraction("parser panicked".to_string()))?
+        .map_err(|e| PdfTextError::Extraction(e.to_string()))
+}
+
+/// Candidate payment nonces: lines consisting of exactly one 8-digit nonce, in order of appearance
+pub fn find_payment_nonces(text: &str) -> Vec<String> {
+    let mut nonces: Vec<String> = Vec::new();
+    for line in text.lines().map(str::trim) {
+        if validate_payment_nonce(line).is_ok() && !nonces.iter().any(|n| n == line) {
+            nonces.push(line.to_string());
+        }
+    }
+    nonces
+}
+
+/// Extract the text layer and return candidate payment nonces
+pub fn extract_payment_nonces(pdf_bytes: &[u8]) -> Result<Vec<String>, PdfTextError> {
+    extract_text(pdf_bytes).map(|text| find_payment_nonces(&text))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_find_payment_nonces() {
+        let text = "支付宝电子回单\n账户名：张三\n小写：735.00\n  12345678  \n流水号 2024011522001400001234567890\n20240115\n12345678\n";
+        assert_eq!(find_payment_nonces(text), vec!["12345678", "20240115"]);
+        assert!(find_payment_nonces("备注：1234567").is_empty());
+        assert!(extract_text(b"%PDF-1.4 truncated").is_err());
+    }
+}
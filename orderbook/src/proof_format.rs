@@ -0,0 +1,198 @@
+// Proof formats accepted by the zk verifier
+// submitPaymentProof takes the proof as user public values, a Halo2 accumulator and
+// the proof bytes, whose sizes are fixed by the verifier contract the escrow points at.
+// A ProofFormat describes those sizes under a version name. The format in force is
+// picked by the escrow's current zkVerifier address (fetched with the contract config);
+// each stored proof records the format it was generated in, so after a verifier
+// upgrade a pending trade's older proof is still checked against its own sizes and the
+// buyer is asked to generate it again, instead of the submission failing a size check.
+//
+// Configuration (env):
+// - PROOF_FORMAT: default format, used when no format is pinned to the verifier
+//   (default halo2-v1)
+// - PROOF_FORMATS: comma-separated "version=<upv>/<accumulator>/<proof>[@0x<verifier>]"
+//   entries, added to the built-in halo2-v1 (32/384/1376)
+
+use ethers::types::Address;
+use thiserror::Error;
+
+/// Format of proofs from the original OpenVM Halo2 verifier
+pub const PROOF_FORMAT_HALO2_V1: &str = "halo2-v1";
+
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+pub enum ProofFormatError {
+    #[error("Invalid proof format entry {0:?}: expected version=<upv>/<accumulator>/<proof>[@0x<verifier>]")]
+    InvalidEntry(String),
+    #[error("Unknown proof format {0}")]
+    Unknown(String),
+    #[error("Invalid {component} size for proof format {version}: expected {expected}, got {actual}")]
+    Size { version: String, component: &'static str, expected: usize, actual: usize },
+}
+
+/// Component sizes of one verifier's proofs
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ProofFormat {
+    pub version: String,
+    pub user_public_values_len: usize,
+    pub accumulator_len: usize,
+    pub proof_data_len: usize,
+    /// Verifier contract this format is pinned to (None = not pinned)
+    pub verifier: Option<Address>,
+}
+
+impl ProofFormat {
+    pub fn halo2_v1() -> Self {
+        Self {
+            version: PROOF_FORMAT_HALO2_V1.to_string(),
+            user_public_values_len: 32,
+            accumulator_len: 384,
+            proof_data_len: 1376,
+            verifier: None,
+        }
+    }
+
+    /// Parse one "version=<upv>/<accumulator>/<proof>[@0x<verifier>]" entry
+    pub fn parse(entry: &str) -> Result<Self, ProofFormatError> {
+        let invalid = || ProofFormatError::InvalidEntry(entry.to_string());
+
+        let (version, rest) = entry.trim().split_once('=').ok_or_else(invalid)?;
+        let (sizes, verifier) = match rest.split_once('@') {
+            Some((sizes, verifier)) => (sizes, Some(verifier.parse::<Address>().map_err(|_| invalid())?)),
+            None => (rest, None),
+        };
+        let sizes: Vec<usize> = sizes
+            .split('/')
+            .map(|size| size.trim().parse().map_err(|_| invalid()))
+            .collect::<Result<_, _>>()?;
+        let [user_public_values_len, accumulator_len, proof_data_len] = sizes[..] else {
+            return Err(invalid());
+        };
+        // submitPaymentProof takes the public values as bytes32
+        if version.is_empty() || user_public_values_len != 32 {
+            return Err(invalid());
+        }
+
+        Ok(Self {
+            version: version.to_string(),
+            user_public_values_len,
+            accumulator_len,
+            proof_data_len,
+            verifier,
+        })
+    }
+
+    /// Check proof components against this format's sizes
+    pub fn validate(&self, user_public_values: &[u8], accumulator: &[u8], proof_data: &[u8]) -> Result<(), ProofFormatError> {
+        let components = [
+            ("user_public_values", self.user_public_values_len, user_public_values.len()),
+            ("accumulator", self.accumulator_len, accumulator.len()),
+            ("proof_data", self.proof_data_len, proof_data.len()),
+        ];
+        for (component, expected, actual) in components {
+            if expected != actual {
+                return Err(ProofFormatError::Size { version: self.version.clone(), component, expected, actual });
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Known proof formats, looked up by verifier address or version
+#[derive(Debug, Clone)]
+pub struct ProofFormatRegistry {
+    default: ProofFormat,
+    formats: Vec<ProofFormat>,
+}
+
+impl ProofFormatRegistry {
+    /// `default` must be one of `formats` or is added to them
+    pub fn new(default: ProofFormat, mut formats: Vec<ProofFormat>) -> Self {
+        if !formats.iter().any(|f| f.version == default.version) {
+            formats.push(default.clone());
+        }
+        Self { default, formats }
+    }
+
+    /// Build the registry from PROOF_FORMAT and PROOF_FORMATS
+    pub fn from_env() -> Result<Self, ProofFormatError> {
+        let mut formats = vec![ProofFormat::halo2_v1()];
+        if let Ok(entries) = std::env::var("PROOF_FORMATS") {
+            for entry in entries.split(',').filter(|entry| !entry.trim().is_empty()) {
+                let format = ProofFormat::parse(entry)?;
+                formats.retain(|f| f.version != format.version);
+                formats.push(format);
+            }
+        }
+
+        let default_version = std::env::var("PROOF_FORMAT").unwrap_or_else(|_| PROOF_FORMAT_HALO2_V1.to_string());
+        let default = formats
+            .iter()
+            .find(|f| f.version == default_version)
+            .cloned()
+            .ok_or(ProofFormatError::Unknown(default_version))?;
+        Ok(Self::new(default, formats))
+    }
+
+    /// Format of proofs `verifier` accepts (default format if none is pinned to it)
+    pub fn for_verifier(&self, verifier: &Address) -> &ProofFormat {
+        self.formats
+            .iter()
+            .find(|f| f.verifier.as_ref() == Some(verifier))
+            .unwrap_or(&self.default)
+    }
+
+    pub fn get(&self, version: &str) -> Option<&ProofFormat> {
+        self.formats.iter().find(|f| f.version == version)
+    }
+
+    /// Format a stored proof was generated in (proofs stored before formats were
+    /// recorded are halo2-v1)
+    pub fn of_stored(&self, version: Option<&str>) -> Result<&ProofFormat, ProofFormatError> {
+        let version = version.unwrap_or(PROOF_FORMAT_HALO2_V1);
+        self.get(version).ok_or_else(|| ProofFormatError::Unknown(version.to_string()))
+    }
+
+    pub fn default_format(&self) -> &ProofFormat {
+        &self.default
+    }
+
+    pub fn formats(&self) -> impl Iterator<Item = &ProofFormat> {
+        self.formats.iter()
+    }
+}
+
+impl Default for ProofFormatRegistry {
+    fn default() -> Self {
+        Self::new(ProofFormat::halo2_v1(), Vec::new())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_registry_selects_format_by_verifier() {
+        let verifier: Address = "0x4444444444444444444444444444444444444444".parse().unwrap();
+        let upgraded = ProofFormat::parse(&format!("halo2-v2=32/416/1472@{:#x}", verifier)).unwrap();
+        assert_eq!((upgraded.accumulator_len, upgraded.proof_data_len), (416, 1472));
+        let registry = ProofFormatRegistry::new(ProofFormat::halo2_v1(), vec![upgraded]);
+
+        assert_eq!(registry.for_verifier(&verifier).version, "halo2-v2");
+        assert_eq!(registry.for_verifier(&Address::zero()).version, PROOF_FORMAT_HALO2_V1);
+        assert_eq!(registry.of_stored(None).unwrap().version, PROOF_FORMAT_HALO2_V1);
+        assert!(registry.of_stored(Some("plonk-v9")).is_err());
+
+        // A pending trade's halo2-v1 proof still validates against its own sizes
+        let v1 = registry.of_stored(Some(PROOF_FORMAT_HALO2_V1)).unwrap();
+        assert!(v1.validate(&[0; 32], &[0; 384], &[0; 1376]).is_ok());
+        assert_eq!(
+            registry.for_verifier(&verifier).validate(&[0; 32], &[0; 384], &[0; 1376]),
+            Err(ProofFormatError::Size { version: "halo2-v2".to_string(), component: "accumulator", expected: 416, actual: 384 })
+        );
+
+        assert!(ProofFormat::parse("halo2-v3=32/416").is_err());
+        assert!(ProofFormat::parse("halo2-v3=64/416/1472").is_err());
+        assert!(ProofFormat::parse("halo2-v3=32/416/1472@0x1234").is_err());
+    }
+}
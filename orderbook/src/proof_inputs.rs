@@ -0,0 +1,397 @@
+// Inputs for the zkPDF guest program
+// Builds the receipt lines the guest checks, the expected output hash, and the OpenVM
+// input streams sent to Axiom. Validation (execute mode) and proof generation must agree
+// on these byte-for-byte, so both paths go through this module.
+
+use openvm::serde::to_vec as openvm_serialize;
+use rust_decimal::prelude::ToPrimitive;
+use rust_decimal::{Decimal, RoundingStrategy};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::fmt;
+use std::str::FromStr;
+use thiserror::Error;
+
+use crate::alipay::{mask_alipay_id, AlipayIdFormat};
+use crate::db::models::{DbOrder, DbTrade};
+use crate::text_utils::{format_cents, labeled_line};
+
+/// Receipt line numbers checked by the guest (name, account, amount, nonce)
+pub const ALIPAY_LINE_NUMBERS: [u32; 4] = [20, 21, 29, 32];
+
+/// Receipt page the lines are read from
+pub const RECEIPT_PAGE: u8 = 0;
+
+#[derive(Error, Debug)]
+pub enum ProofInputError {
+    #[error("Failed to compute expected hash: {0}")]
+    HashComputation(String),
+
+    #[error("Invalid output format: {0}")]
+    InvalidOutput(String),
+}
+
+/// Layout of the OpenVM input streams read by the guest program
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum StreamFormat {
+    /// PDF, page, line count, lines, hash length, hash bytes (44 streams for 4 lines)
+    #[default]
+    V1,
+    /// V1 preceded by a format-version word and the unpadded PDF length (46 streams),
+    /// so the guest can strip the word-alignment padding from the PDF
+    V2,
+}
+
+impl StreamFormat {
+    pub fn version(&self) -> u32 {
+        match self {
+            StreamFormat::V1 => 1,
+            StreamFormat::V2 => 2,
+        }
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            StreamFormat::V1 => "v1",
+            StreamFormat::V2 => "v2",
+        }
+    }
+
+    /// Number of streams produced for `line_count` receipt lines
+    pub fn stream_count(&self, line_count: usize) -> usize {
+        // PDF + page + line count + 2 per line + hash length + 32 hash bytes
+        let v1 = 3 + 2 * line_count + 1 + 32;
+        match self {
+            StreamFormat::V1 => v1,
+            StreamFormat::V2 => v1 + 2,
+        }
+    }
+}
+
+impl fmt::Display for StreamFormat {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for StreamFormat {
+    type Err = ProofInputError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_ascii_lowercase().as_str() {
+            "v1" | "1" => Ok(StreamFormat::V1),
+            "v2" | "2" => Ok(StreamFormat::V2),
+            other => Err(ProofInputError::InvalidOutput(format!("Unknown stream format: {}", other))),
+        }
+    }
+}
+
+/// A trade's CNY amount (decimal string of cents, as synced) in whole cents
+/// Fractions round half away from zero. Negative, NaN, infinite and out-of-range values
+/// are refused: parsed as f64 they used to saturate to 0 or u64::MAX cents.
+pub fn parse_cny_cents(amount: &str) -> Result<u64, ProofInputError> {
+    let invalid = || ProofInputError::HashComputation(format!("Invalid CNY amount: {:?}", amount));
+    let cents = Decimal::from_str(amount.trim()).map_err(|_| invalid())?;
+    if cents.is_sign_negative() && !cents.is_zero() {
+        return Err(invalid());
+    }
+    cents
+        .round_dp_with_strategy(0, RoundingStrategy::MidpointAwayFromZero)
+        .to_u64()
+        .ok_or_else(invalid)
+}
+
+/// Trade details a receipt is proven against
+#[derive(Debug, Clone)]
+pub struct ProofInputs<'a> {
+    pub alipay_name: &'a str,
+    pub alipay_id: &'a str,
+    pub alipay_id_format: AlipayIdFormat,
+    pub cny_amount_cents: u64,
+    pub payment_nonce: &'a str,
+    pub public_key_der_hash: [u8; 32],
+}
+
+/// The proof's payment details are ones the trade had at an earlier validation
+pub const MISMATCH_STALE_DETAILS: &str = "stale_trade_details";
+/// The proof's payment details are none the trade ever had (another trade's, or a test nonce)
+pub const MISMATCH_UNKNOWN_DETAILS: &str = "unknown_payment_details";
+
+/// Stored proof public values that don't hash the trade's current payment details
+#[derive(Debug, Clone, Serialize)]
+pub struct PublicValuesMismatch {
+    /// Public values of the stored proof, 0x-prefixed
+    pub proof_public_values: String,
+    /// Hash submitPaymentProof will compute from the on-chain trade and order, 0x-prefixed
+    pub expected_public_values: String,
+    /// Receipt lines the expected hash covers, as "line: text"
+    pub expected_lines: Vec<String>,
+    pub cny_amount_cents: u64,
+    pub payment_nonce: String,
+    pub public_key_der_hash: String,
+    /// MISMATCH_STALE_DETAILS or MISMATCH_UNKNOWN_DETAILS, when known
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub cause: Option<&'static str>,
+}
+
+impl fmt::Display for PublicValuesMismatch {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "proof public values {} != expected {} (amount {} cents, nonce {})",
+            self.proof_public_values, self.expected_public_values, self.cny_amount_cents, self.payment_nonce
+        )?;
+        if let Some(cause) = self.cause {
+            write!(f, ", {}", cause)?;
+        }
+        Ok(())
+    }
+}
+
+impl<'a> ProofInputs<'a> {
+    /// Inputs from the synced trade and order, as the contract reads them in submitPaymentProof
+    pub fn for_trade(order: &'a DbOrder, trade: &'a DbTrade, public_key_der_hash: [u8; 32]) -> Result<Self, ProofInputError> {
+        let alipay_id_format = order
+            .alipay_id_format
+            .parse::<AlipayIdFormat>()
+            .map_err(|e| ProofInputError::HashComputation(e.to_string()))?;
+        let cny_amount_cents = parse_cny_cents(&trade.cny_amount)?;
+
+        Ok(Self {
+            alipay_name: &order.alipay_name,
+            alipay_id: &order.alipay_id,
+            alipay_id_format,
+            cny_amount_cents,
+            payment_nonce: &trade.payment_nonce,
+            public_key_der_hash,
+        })
+    }
+}
+
+impl ProofInputs<'_> {
+    /// Compare a proof's public values against the expected hash; None if they match
+    pub fn public_values_mismatch(&self, public_values: &[u8; 32]) -> Result<Option<PublicValuesMismatch>, ProofInputError> {
+        let expected = self.expected_hash()?;
+        if *public_values == expected {
+            return Ok(None);
+        }
+
+        Ok(Some(PublicValuesMismatch {
+            proof_public_values: format!("0x{}", hex::encode(public_values)),
+            expected_public_values: format!("0x{}", hex::encode(expected)),
+            expected_lines: self.lines()?.into_iter().map(|(num, text)| format!("{}: {}", num, text)).collect(),
+            cny_amount_cents: self.cny_amount_cents,
+            payment_nonce: self.payment_nonce.to_string(),
+            public_key_der_hash: format!("0x{}", hex::encode(self.public_key_der_hash)),
+            cause: None,
+        }))
+    }
+
+    /// Receipt lines as (line number, text) pairs, exactly as the guest compares them
+    pub fn lines(&self) -> Result<Vec<(u32, String)>, ProofInputError> {
+        let masked_alipay_id = mask_alipay_id(self.alipay_id, self.alipay_id_format)
+            .map_err(|e| ProofInputError::HashComputation(e.to_string()))?;
+
+        let line_texts = [
+            labeled_line("账户名", self.alipay_name),
+            labeled_line("账号", &masked_alipay_id),
+            labeled_line("小写", &format_cents(self.cny_amount_cents)),
+            self.payment_nonce.to_string(), // Just the nonce, no prefix
+        ];
+
+        // Each value must stay on its own receipt line
+        if line_texts.iter().any(|line| line.contains('\n')) {
+            return Err(ProofInputError::InvalidOutput(
+                "Line text must not contain newlines".to_string(),
+            ));
+        }
+
+        Ok(ALIPAY_LINE_NUMBERS
+            .into_iter()
+            .zip(line_texts.iter().map(|line| line.trim().to_string()))
+            .collect())
+    }
+
+    /// Expected guest output: SHA256(0x01 || publicKeyDerHash || linesHash)
+    ///
+    /// linesHash is SHA256(line_num_0 || line_text_0 || line_num_1 || ...), with line
+    /// numbers as little-endian u32. The hash is the same for every stream format.
+    pub fn expected_hash(&self) -> Result<[u8; 32], ProofInputError> {
+        let mut lines_data = Vec::new();
+        for (num, text) in self.lines()? {
+            lines_data.extend_from_slice(&num.to_le_bytes());
+            lines_data.extend_from_slice(text.as_bytes());
+        }
+        let lines_hash = Sha256::digest(&lines_data);
+
+        let mut final_data = Vec::with_capacity(65);
+        final_data.push(0x01); // result = true
+        final_data.extend_from_slice(&self.public_key_der_hash);
+        final_data.extend_from_slice(&lines_hash);
+
+        Ok(Sha256::digest(&final_data).into())
+    }
+
+    /// Hex-encoded OpenVM input streams (0x01-prefixed) in the given layout
+    pub fn streams(&self, pdf_bytes: &[u8], format: StreamFormat) -> Result<Vec<String>, ProofInputError> {
+        let lines = self.lines()?;
+        let mut streams = Vec::with_capacity(format.stream_count(lines.len()));
+
+        if format == StreamFormat::V2 {
+            add_value_stream(&mut streams, &format.version())?;
+            add_value_stream(&mut streams, &(pdf_bytes.len() as u32))?;
+        }
+
+        // PDF bytes (raw, padded to 4-byte boundary)
+        let padding = (4 - (pdf_bytes.len() % 4)) % 4;
+        let mut pdf_padded = pdf_bytes.to_vec();
+        pdf_padded.extend(vec![0u8; padding]);
+        streams.push(format!("0x01{}", hex::encode(&pdf_padded)));
+
+        add_value_stream(&mut streams, &RECEIPT_PAGE)?;
+        add_value_stream(&mut streams, &(lines.len() as u32))?;
+
+        for (num, text) in &lines {
+            add_value_stream(&mut streams, num)?;
+            add_value_stream(&mut streams, text)?;
+        }
+
+        // Hash length, then one stream per hash byte
+        add_value_stream(&mut streams, &32u32)?;
+        for byte in self.public_key_der_hash {
+            add_value_stream(&mut streams, &byte)?;
+        }
+
+        tracing::info!("✅ Generated {} OpenVM input streams ({})", streams.len(), format);
+
+        Ok(streams)
+    }
+}
+
+/// Cache key for a set of input streams: SHA-256 over each stream prefixed with its
+/// length (u32 LE), so different splits of the same bytes never collide
+pub fn input_streams_hash(streams: &[String]) -> [u8; 32] {
+    let mut hasher = Sha256::new();
+    for stream in streams {
+        hasher.update((stream.len() as u32).to_le_bytes());
+        hasher.update(stream.as_bytes());
+    }
+    hasher.finalize().into()
+}
+
+/// Append a value as one stream (OpenVM word serialization, little-endian bytes)
+fn add_value_stream<T: Serialize>(streams: &mut Vec<String>, value: &T) -> Result<(), ProofInputError> {
+    let words = openvm_serialize(value)
+        .map_err(|e| ProofInputError::InvalidOutput(format!("OpenVM serialization failed: {}", e)))?;
+
+    let bytes: Vec<u8> = words.into_iter().flat_map(|w| w.to_le_bytes()).collect();
+    streams.push(format!("0x01{}", hex::encode(&bytes)));
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use proptest::prelude::*;
+
+    fn sample() -> ProofInputs<'static> {
+        ProofInputs {
+            alipay_name: "张三",
+            alipay_id: "13945908941",
+            alipay_id_format: AlipayIdFormat::Phone,
+            cny_amount_cents: 106000,
+            payment_nonce: "12345678",
+            public_key_der_hash: [0xab; 32],
+        }
+    }
+
+    #[test]
+    fn test_expected_hash_golden() {
+        let lines = sample().lines().unwrap();
+        assert_eq!(
+            lines,
+            vec![
+                (20, "账户名：张三".to_string()),
+                (21, "账号：139******41".to_string()),
+                (29, "小写：1060.00".to_string()),
+                (32, "12345678".to_string()),
+            ]
+        );
+        assert_eq!(
+            hex::encode(sample().expected_hash().unwrap()),
+            "5b26dabbdadfc2b12d69e7d6ec6b6286084833b92400e8a65e2f9d1b181e0f30"
+        );
+    }
+
+    #[test]
+    fn test_public_values_mismatch() {
+        let expected = sample().expected_hash().unwrap();
+        assert!(sample().public_values_mismatch(&expected).unwrap().is_none());
+
+        // A proof made with another nonce
+        let stale = ProofInputs { payment_nonce: "87654321", ..sample() }.expected_hash().unwrap();
+        let report = sample().public_values_mismatch(&stale).unwrap().unwrap();
+        assert_eq!(report.proof_public_values, format!("0x{}", hex::encode(stale)));
+        assert_eq!(report.expected_public_values, format!("0x{}", hex::encode(expected)));
+        assert_eq!(report.expected_lines[3], "32: 12345678");
+        assert_eq!(report.cny_amount_cents, 106000);
+    }
+
+    #[test]
+    fn test_v1_streams_golden() {
+        let streams = sample().streams(b"%PDF-", StreamFormat::V1).unwrap();
+        assert_eq!(streams.len(), 44);
+        assert_eq!(streams.len(), StreamFormat::V1.stream_count(4));
+        assert_eq!(streams[0], "0x01255044462d000000");
+        assert_eq!(streams[1], "0x0100000000"); // page
+        assert_eq!(streams[2], "0x0104000000"); // line count
+        assert_eq!(streams[3], "0x0114000000"); // line 20
+        assert_eq!(streams[9], "0x0120000000"); // line 32
+        assert_eq!(streams[10], "0x01080000003132333435363738");
+        assert_eq!(streams[11], "0x0120000000"); // hash length
+        assert!(streams[12..].iter().all(|s| s == "0x01ab000000"));
+    }
+
+    #[test]
+    fn test_v2_streams_golden() {
+        let v1 = sample().streams(b"%PDF-", StreamFormat::V1).unwrap();
+        let v2 = sample().streams(b"%PDF-", StreamFormat::V2).unwrap();
+        assert_eq!(v2.len(), 46);
+        assert_eq!(v2.len(), StreamFormat::V2.stream_count(4));
+        assert_eq!(v2[0], "0x0102000000"); // format version
+        assert_eq!(v2[1], "0x0105000000"); // unpadded PDF length
+        assert_eq!(v2[2..], v1[..]);
+    }
+
+    #[test]
+    fn test_parse_cny_cents() {
+        assert_eq!(parse_cny_cents("106000").unwrap(), 106000);
+        assert_eq!(parse_cny_cents(" 73500.5 ").unwrap(), 73501);
+        assert_eq!(parse_cny_cents("0").unwrap(), 0);
+        for bad in ["", "NaN", "inf", "-1", "1e5", "0x10", "18446744073709551616", "12 34"] {
+            assert!(parse_cny_cents(bad).is_err(), "{:?} parsed", bad);
+        }
+    }
+
+    proptest! {
+        #[test]
+        fn prop_cny_cents_round_trip(cents in any::<u64>()) {
+            prop_assert_eq!(parse_cny_cents(&cents.to_string()).unwrap(), cents);
+        }
+
+        #[test]
+        fn prop_cny_cents_are_never_clamped(s in "[-+ ]?[0-9]{0,25}(\\.[0-9]{0,30})?[eE]?[0-9]?") {
+            // Whatever parses is the amount rounded to a cent, not a saturated cast
+            if let Ok(cents) = parse_cny_cents(&s) {
+                let exact = Decimal::from_str(s.trim()).unwrap();
+                prop_assert!((Decimal::from(cents) - exact).abs() <= Decimal::new(5, 1));
+            }
+        }
+
+        #[test]
+        fn prop_cny_cents_arbitrary_input_never_panics(s in "\\PC{0,40}") {
+            let _ = parse_cny_cents(&s);
+        }
+    }
+}
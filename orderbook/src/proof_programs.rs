@@ -0,0 +1,166 @@
+// Registry of zkPDF guest programs known to the orderbook
+// Each Axiom program is pinned to an appExeCommit and reads a specific input stream
+// layout. The contract verifies proofs against its current appExeCommit, so the program
+// (and stream format) used for a trade is picked by matching that commit. Keeping the
+// previous program registered lets trades validated before an upgrade still be proven
+// while the contract points at the old commit, and new trades switch over as soon as
+// the commit is updated on-chain.
+//
+// Configuration (env):
+// - AXIOM_PROGRAM_ID / PROOF_STREAM_FORMAT: default program, used when no commit matches
+// - PROOF_PROGRAMS: comma-separated "program_id=0x<appExeCommit>:<v1|v2>" entries
+
+use thiserror::Error;
+
+use crate::hexutil;
+use crate::proof_inputs::StreamFormat;
+
+/// Default Axiom program when AXIOM_PROGRAM_ID is unset
+pub const DEFAULT_AXIOM_PROGRAM_ID: &str = "prg_01k8vn94vy3hwve3np6dxgkgz8";
+
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+pub enum ProofProgramError {
+    #[error("Invalid proof program entry {0:?}: expected program_id=0x<appExeCommit>:<v1|v2>")]
+    InvalidEntry(String),
+    #[error("Invalid appExeCommit for program {0}")]
+    InvalidCommit(String),
+    #[error("Unknown stream format for program {0}: {1}")]
+    InvalidFormat(String, String),
+}
+
+/// A guest program deployed on Axiom
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ProofProgram {
+    pub program_id: String,
+    /// Commitment the program's proofs verify against (None = not pinned)
+    pub app_exe_commit: Option<[u8; 32]>,
+    pub stream_format: StreamFormat,
+}
+
+impl ProofProgram {
+    /// Parse one "program_id=0x<appExeCommit>:<format>" entry
+    pub fn parse(entry: &str) -> Result<Self, ProofProgramError> {
+        let invalid = || ProofProgramError::InvalidEntry(entry.to_string());
+
+        let (program_id, rest) = entry.trim().split_once('=').ok_or_else(invalid)?;
+        let (commit, format) = rest.split_once(':').ok_or_else(invalid)?;
+        if program_id.is_empty() {
+            return Err(invalid());
+        }
+
+        let commit_bytes = hexutil::decode_fixed::<32>(commit)
+            .map_err(|_| ProofProgramError::InvalidCommit(program_id.to_string()))?;
+        let stream_format = format
+            .parse()
+            .map_err(|_| ProofProgramError::InvalidFormat(program_id.to_string(), format.to_string()))?;
+
+        Ok(Self {
+            program_id: program_id.to_string(),
+            app_exe_commit: Some(commit_bytes),
+            stream_format,
+        })
+    }
+}
+
+/// Known guest programs, looked up by the contract's current appExeCommit
+#[derive(Debug, Clone)]
+pub struct ProofProgramRegistry {
+    default: ProofProgram,
+    pinned: Vec<ProofProgram>,
+}
+
+impl ProofProgramRegistry {
+    pub fn new(default: ProofProgram, pinned: Vec<ProofProgram>) -> Self {
+        Self { default, pinned }
+    }
+
+    /// Build the registry from AXIOM_PROGRAM_ID, PROOF_STREAM_FORMAT and PROOF_PROGRAMS
+    pub fn from_env() -> Result<Self, ProofProgramError> {
+        let program_id = std::env::var("AXIOM_PROGRAM_ID")
+            .unwrap_or_else(|_| DEFAULT_AXIOM_PROGRAM_ID.to_string());
+        let stream_format = match std::env::var("PROOF_STREAM_FORMAT") {
+            Ok(format) => format
+                .parse()
+                .map_err(|_| ProofProgramError::InvalidFormat(program_id.clone(), format))?,
+            Err(_) => StreamFormat::default(),
+        };
+
+        let pinned = match std::env::var("PROOF_PROGRAMS") {
+            Ok(entries) => Self::parse_entries(&entries)?,
+            Err(_) => Vec::new(),
+        };
+
+        Ok(Self::new(
+            ProofProgram {
+                program_id,
+                app_exe_commit: None,
+                stream_format,
+            },
+            pinned,
+        ))
+    }
+
+    fn parse_entries(entries: &str) -> Result<Vec<ProofProgram>, ProofProgramError> {
+        entries
+            .split(',')
+            .filter(|entry| !entry.trim().is_empty())
+            .map(ProofProgram::parse)
+            .collect()
+    }
+
+    /// Program whose proofs verify against `app_exe_commit` (default program if none is pinned to it)
+    pub fn for_commit(&self, app_exe_commit: &[u8; 32]) -> &ProofProgram {
+        self.pinned
+            .iter()
+            .find(|p| p.app_exe_commit.as_ref() == Some(app_exe_commit))
+            .unwrap_or(&self.default)
+    }
+
+    pub fn default_program(&self) -> &ProofProgram {
+        &self.default
+    }
+
+    pub fn programs(&self) -> impl Iterator<Item = &ProofProgram> {
+        self.pinned.iter().chain(std::iter::once(&self.default))
+    }
+}
+
+impl Default for ProofProgramRegistry {
+    fn default() -> Self {
+        Self::new(
+            ProofProgram {
+                program_id: DEFAULT_AXIOM_PROGRAM_ID.to_string(),
+                app_exe_commit: None,
+                stream_format: StreamFormat::default(),
+            },
+            Vec::new(),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_registry_selects_program_by_commit() {
+        let old_commit = format!("0x{}", "11".repeat(32));
+        let new_commit = format!("0x{}", "22".repeat(32));
+        let pinned = ProofProgramRegistry::parse_entries(&format!(
+            "prg_old={}:v1, prg_new={}:v2",
+            old_commit, new_commit
+        ))
+        .unwrap();
+        let registry = ProofProgramRegistry::new(ProofProgramRegistry::default().default.clone(), pinned);
+
+        assert_eq!(registry.for_commit(&[0x11; 32]).program_id, "prg_old");
+        assert_eq!(registry.for_commit(&[0x11; 32]).stream_format, StreamFormat::V1);
+        assert_eq!(registry.for_commit(&[0x22; 32]).program_id, "prg_new");
+        assert_eq!(registry.for_commit(&[0x22; 32]).stream_format, StreamFormat::V2);
+        assert_eq!(registry.for_commit(&[0x33; 32]).program_id, DEFAULT_AXIOM_PROGRAM_ID);
+
+        assert!(ProofProgram::parse("prg_x=0x1234:v1").is_err());
+        assert!(ProofProgram::parse(&format!("prg_x={}:v9", old_commit)).is_err());
+        assert!(ProofProgram::parse("prg_x").is_err());
+    }
+}
@@ -0,0 +1,228 @@
+// Trade receipts: signed settlement attestations
+// A receipt is a canonical JSON document describing a settled trade, signed with
+// EIP-191 personal_sign so anyone can recover the attestation key address.
+
+use chrono::Utc;
+use ethers::signers::{LocalWallet, Signer};
+use ethers::types::{Address, Signature};
+use serde::{Deserialize, Serialize};
+use std::str::FromStr;
+use thiserror::Error;
+
+use crate::db::{
+    DbError,
+    models::DbTradeReceipt,
+    orders::{OrderRepository, PostgresOrderRepository},
+    receipts::{ReceiptRepository, PostgresReceiptRepository},
+    trades::{TradeRepository, PostgresTradeRepository},
+};
+
+/// Receipt schema version (bump when fields change)
+pub const RECEIPT_VERSION: u32 = 1;
+
+#[derive(Error, Debug)]
+pub enum ReceiptError {
+    #[error("Database error: {0}")]
+    Database(#[from] DbError),
+    #[error("Trade {0} is not settled")]
+    NotSettled(String),
+    #[error("Signing error: {0}")]
+    Signing(String),
+    #[error("Invalid signature: {0}")]
+    InvalidSignature(String),
+}
+
+/// Settlement attestation payload (field order is the canonical JSON order)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TradeReceipt {
+    pub version: u32,
+    pub chain_id: u64,
+    pub escrow_contract: String,
+    pub trade_id: String,
+    pub order_id: String,
+    pub buyer: String,
+    pub seller: String,
+    pub token: String,
+    pub token_amount: String,       // token base units
+    pub cny_amount: String,         // CNY cents
+    pub payment_nonce: String,
+    pub escrow_tx_hash: Option<String>,
+    pub settlement_tx_hash: Option<String>,
+    pub axiom_proof_id: Option<String>,
+    pub issued_at: i64,             // unix timestamp
+}
+
+/// Receipt as returned by the API: payload plus detached signature
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SignedReceipt {
+    pub receipt: TradeReceipt,
+    pub receipt_json: String,
+    pub signature: String,
+    pub signer: String,
+}
+
+impl TryFrom<DbTradeReceipt> for SignedReceipt {
+    type Error = serde_json::Error;
+
+    fn try_from(row: DbTradeReceipt) -> Result<Self, Self::Error> {
+        Ok(Self {
+            receipt: serde_json::from_str(&row.receipt_json)?,
+            receipt_json: row.receipt_json,
+            signature: row.signature,
+            signer: row.signer,
+        })
+    }
+}
+
+/// Signs settlement receipts with the relayer key or a dedicated attestation key
+pub struct ReceiptSigner {
+    wallet: LocalWallet,
+    chain_id: u64,
+    escrow_address: Address,
+}
+
+impl ReceiptSigner {
+    pub fn new(private_key: &str, chain_id: u64, escrow_address: Address) -> Result<Self, ReceiptError> {
+        let wallet: LocalWallet = private_key
+            .parse()
+            .map_err(|e| ReceiptError::Signing(format!("Invalid attestation key: {}", e)))?;
+
+        Ok(Self {
+            wallet: wallet.with_chain_id(chain_id),
+            chain_id,
+            escrow_address,
+        })
+    }
+
+    /// Address of the attestation key
+    pub fn address(&self) -> Address {
+        self.wallet.address()
+    }
+
+    /// Serialize and sign a receipt, producing the row to persist
+    pub async fn sign(&self, receipt: &TradeReceipt) -> Result<DbTradeReceipt, ReceiptError> {
+        let receipt_json = serde_json::to_string(receipt)
+            .map_err(|e| ReceiptError::Signing(format!("Failed to serialize receipt: {}", e)))?;
+
+        let signature = self
+            .wallet
+            .sign_message(receipt_json.as_bytes())
+            .await
+            .map_err(|e| ReceiptError::Signing(e.to_string()))?;
+
+        Ok(DbTradeReceipt {
+            trade_id: receipt.trade_id.clone(),
+            receipt_json,
+            signature: format!("0x{}", hex::encode(signature.to_vec())),
+            signer: format!("{:#x}", self.address()),
+            issued_at: Utc::now(),
+        })
+    }
+}
+
+/// Recover the signer address of a receipt (EIP-191 over the exact receipt JSON)
+pub fn recover_receipt_signer(receipt_json: &str, signature: &str) -> Result<Address, ReceiptError> {
+    let signature = Signature::from_str(signature)
+        .map_err(|e| ReceiptError::InvalidSignature(e.to_string()))?;
+
+    signature
+        .recover(receipt_json)
+        .map_err(|e| ReceiptError::InvalidSignature(e.to_string()))
+}
+
+/// Issue (or return the already issued) receipt for a settled trade
+pub async fn issue_receipt(
+    pool: &sqlx::PgPool,
+    signer: &ReceiptSigner,
+    trade_id: &str,
+) -> Result<DbTradeReceipt, ReceiptError> {
+    let receipt_repo = PostgresReceiptRepository::new(pool.clone());
+
+    if let Some(existing) = receipt_repo.get(trade_id).await? {
+        return Ok(existing);
+    }
+
+    let trade = PostgresTradeRepository::new(pool.clone()).get(trade_id).await?;
+    if trade.status != 1 {
+        return Err(ReceiptError::NotSettled(trade_id.to_string()));
+    }
+
+    let order = PostgresOrderRepository::new(pool.clone()).get(&trade.order_id).await?;
+
+    let receipt = TradeReceipt {
+        version: RECEIPT_VERSION,
+        chain_id: signer.chain_id,
+        escrow_contract: format!("{:#x}", signer.escrow_address),
+        trade_id: trade.trade_id,
+        order_id: trade.order_id,
+        // Lowercase, as in every receipt signed so far
+        buyer: trade.buyer.to_string(),
+        seller: order.seller.to_string(),
+        token: order.token.to_string(),
+        token_amount: trade.token_amount,
+        cny_amount: trade.cny_amount,
+        payment_nonce: trade.payment_nonce,
+        escrow_tx_hash: trade.escrow_tx_hash,
+        settlement_tx_hash: trade.settlement_tx_hash,
+        axiom_proof_id: trade.axiom_proof_id,
+        issued_at: Utc::now().timestamp(),
+    };
+
+    let signed = signer.sign(&receipt).await?;
+    receipt_repo.create(&signed).await?;
+
+    // Re-read so concurrent issuers converge on the single stored receipt
+    Ok(receipt_repo.get(trade_id).await?.unwrap_or(signed))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Well-known anvil test key #0
+    const TEST_KEY: &str = "ac0974bec39a17e36ba4a6b4d238ff944bacb478cbed5efcae784d7bf4f2ff80";
+
+    fn test_receipt() -> TradeReceipt {
+        TradeReceipt {
+            version: RECEIPT_VERSION,
+            chain_id: 84532,
+            escrow_contract: "0xe03c7b74a7c4338e397c65d8b60b18faf56e3546".to_string(),
+            trade_id: format!("0x{}", "11".repeat(32)),
+            order_id: format!("0x{}", "22".repeat(32)),
+            buyer: "0x70997970c51812dc3a010c7d01b50e0d17dc79c8".to_string(),
+            seller: "0x3c44cdddb6a900fa2b585dd299e03d12fa4293bc".to_string(),
+            token: "0xd4b280ffb336e2061cb39347bd599cb88ff1617a".to_string(),
+            token_amount: "100000000".to_string(),
+            cny_amount: "73500".to_string(),
+            payment_nonce: "12345678".to_string(),
+            escrow_tx_hash: Some(format!("0x{}", "aa".repeat(32))),
+            settlement_tx_hash: Some(format!("0x{}", "bb".repeat(32))),
+            axiom_proof_id: Some("prf_test".to_string()),
+            issued_at: 1_700_000_000,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_sign_and_recover_receipt() {
+        let escrow: Address = "0xe03C7b74A7c4338E397c65d8B60b18FAF56E3546".parse().unwrap();
+        let signer = ReceiptSigner::new(TEST_KEY, 84532, escrow).unwrap();
+
+        let signed = signer.sign(&test_receipt()).await.unwrap();
+        let recovered = recover_receipt_signer(&signed.receipt_json, &signed.signature).unwrap();
+
+        assert_eq!(recovered, signer.address());
+        assert_eq!(signed.signer, format!("{:#x}", signer.address()));
+    }
+
+    #[tokio::test]
+    async fn test_tampered_receipt_recovers_different_signer() {
+        let escrow: Address = "0xe03C7b74A7c4338E397c65d8B60b18FAF56E3546".parse().unwrap();
+        let signer = ReceiptSigner::new(TEST_KEY, 84532, escrow).unwrap();
+
+        let signed = signer.sign(&test_receipt()).await.unwrap();
+        let tampered = signed.receipt_json.replace("73500", "99999");
+        let recovered = recover_receipt_signer(&tampered, &signed.signature).unwrap();
+
+        assert_ne!(recovered, signer.address());
+    }
+}
@@ -0,0 +1,326 @@
+// Sandbox mode
+// SANDBOX_MODE=true runs the API without a chain or Axiom, for integrators building
+// against the full buy flow without testnet funds or proving credits:
+// - the escrow is faked in the database: sandbox orders are created through
+//   POST /api/sandbox/orders, fills create trades and reduce remaining amounts directly,
+//   and a submitted proof settles its trade immediately (what the event listener would
+//   have synced after the real transactions)
+// - validation and proof generation use a mock prover that answers instantly with the
+//   expected payment hash, so any uploaded receipt passes
+// - the contract config is fixed (SANDBOX_PAYMENT_WINDOW_SECS, SANDBOX_MIN_TRADE_VALUE_CNY,
+//   SANDBOX_MAX_TRADE_VALUE_CNY) and every sandbox token has SANDBOX_TOKEN_DECIMALS decimals
+// - every response carries an X-Sandbox header, JSON objects get "sandbox": true and
+//   /api/status raises its banner
+// Use a database of its own: sandbox trades and orders are indistinguishable from synced ones.
+
+use axum::{
+    body::{to_bytes, Body},
+    extract::{Request, State},
+    http::{header, HeaderValue},
+    middleware::Next,
+    response::Response,
+};
+use chrono::Utc;
+use ethers::types::{Address, H256, U256};
+
+use crate::alipay::AlipayIdFormat;
+use crate::api::state::AppState;
+use crate::axiom_prover::GeneratedProof;
+use crate::blockchain::address::EthAddress;
+use crate::blockchain::config_cache::ContractConfig;
+use crate::blockchain::ids::{OrderId, TradeId};
+use crate::blockchain::types::PAYMENT_NONCE_LEN;
+use crate::db::confirmations::{ConfirmationRepository, PostgresConfirmationRepository};
+use crate::db::models::{DbOrder, DbTrade};
+use crate::db::orders::{OrderRepository, PostgresOrderRepository};
+use crate::db::trades::{PostgresTradeRepository, TradeRepository};
+use crate::db::{Database, DbError};
+use crate::hexutil;
+use crate::proof_format::ProofFormat;
+
+/// Header marking sandbox responses
+pub const SANDBOX_HEADER: &str = "x-sandbox";
+
+/// Largest JSON body the response marker rewrites (bigger ones only get the header)
+const MAX_MARKED_BODY: usize = 4 * 1024 * 1024;
+
+#[derive(Debug, thiserror::Error)]
+pub enum SandboxError {
+    #[error("Order {order_id} has {remaining} remaining, can't fill {amount}")]
+    InsufficientRemaining { order_id: String, remaining: String, amount: String },
+
+    #[error("Trade {0} is not pending")]
+    TradeNotPending(String),
+
+    #[error("Trade {0} has expired")]
+    TradeExpired(String),
+
+    #[error(transparent)]
+    Database(#[from] DbError),
+}
+
+/// A new sandbox order
+#[derive(Debug, Clone)]
+pub struct SandboxOrder {
+    pub seller: EthAddress,
+    pub token: EthAddress,
+    pub total_amount: U256,
+    pub exchange_rate: U256,
+    pub alipay_id: String,
+    pub alipay_name: String,
+}
+
+/// In-memory stand-in for the escrow contract and the prover
+#[derive(Debug, Clone)]
+pub struct SandboxChain {
+    pub payment_window_secs: u64,
+    pub min_trade_value_cny: U256,
+    pub max_trade_value_cny: U256,
+    pub token_decimals: u8,
+}
+
+impl Default for SandboxChain {
+    fn default() -> Self {
+        Self {
+            payment_window_secs: 900,
+            min_trade_value_cny: U256::from(1_000),
+            max_trade_value_cny: U256::from(5_000_000),
+            token_decimals: 6,
+        }
+    }
+}
+
+impl SandboxChain {
+    /// Sandbox settings if SANDBOX_MODE is on
+    pub fn from_env() -> Option<Self> {
+        let enabled = std::env::var("SANDBOX_MODE")
+            .map(|v| matches!(v.trim().to_ascii_lowercase().as_str(), "1" | "true" | "yes"))
+            .unwrap_or(false);
+        if !enabled {
+            return None;
+        }
+
+        let defaults = Self::default();
+        let var = |name: &str| std::env::var(name).ok();
+        Some(Self {
+            payment_window_secs: var("SANDBOX_PAYMENT_WINDOW_SECS").and_then(|v| v.parse().ok()).unwrap_or(defaults.payment_window_secs),
+            min_trade_value_cny: var("SANDBOX_MIN_TRADE_VALUE_CNY").and_then(|v| U256::from_dec_str(&v).ok()).unwrap_or(defaults.min_trade_value_cny),
+            max_trade_value_cny: var("SANDBOX_MAX_TRADE_VALUE_CNY").and_then(|v| U256::from_dec_str(&v).ok()).unwrap_or(defaults.max_trade_value_cny),
+            token_decimals: var("SANDBOX_TOKEN_DECIMALS").and_then(|v| v.parse().ok()).unwrap_or(defaults.token_decimals),
+        })
+    }
+
+    /// The fixed contract configuration (never paused, no verifier or signing key)
+    pub fn config(&self) -> ContractConfig {
+        ContractConfig {
+            min_trade_value_cny: self.min_trade_value_cny,
+            max_trade_value_cny: self.max_trade_value_cny,
+            payment_window: U256::from(self.payment_window_secs),
+            paused: false,
+            zk_verifier: Address::zero(),
+            public_key_der_hash: [0u8; 32],
+            app_exe_commit: [0u8; 32],
+            app_vm_commit: [0u8; 32],
+            fetched_at: Utc::now(),
+        }
+    }
+
+    /// Record a new order as if OrderCreatedAndLocked had been synced
+    pub async fn create_order(&self, db: &Database, order: SandboxOrder) -> Result<DbOrder, SandboxError> {
+        let now = Utc::now();
+        let db_order = DbOrder {
+            order_id: OrderId::from(random_bytes32()).to_string(),
+            seller: order.seller,
+            token: order.token,
+            total_amount: order.total_amount.to_string(),
+            remaining_amount: order.total_amount.to_string(),
+            exchange_rate: order.exchange_rate.to_string(),
+            alipay_id_format: AlipayIdFormat::detect(&order.alipay_id).to_string(),
+            alipay_id: order.alipay_id,
+            alipay_name: order.alipay_name,
+            created_at: now.timestamp(),
+            synced_at: now,
+            min_fill: None,
+            lot_size: None,
+        };
+
+        PostgresOrderRepository::new(db.pool().clone()).create(&db_order).await?;
+        tracing::info!("🧪 Sandbox order {} created for {}", db_order.order_id, db_order.seller);
+        Ok(db_order)
+    }
+
+    /// fillOrder: create the trade and take its amount off the order.
+    /// Returns the fake transaction hash and the trade.
+    pub async fn fill_order(
+        &self,
+        db: &Database,
+        order_id: &OrderId,
+        amount: U256,
+        buyer: &EthAddress,
+        cny_amount: U256,
+    ) -> Result<(H256, DbTrade), SandboxError> {
+        let order = db.get_order(&order_id.to_string()).await?;
+        let remaining = U256::from_dec_str(&order.remaining_amount).unwrap_or_default();
+        if amount.is_zero() || amount > remaining {
+            return Err(SandboxError::InsufficientRemaining {
+                order_id: order.order_id,
+                remaining: remaining.to_string(),
+                amount: amount.to_string(),
+            });
+        }
+
+        let trade_id = random_bytes32();
+        let tx_hash = H256(random_bytes32());
+        let now = Utc::now();
+        let trade = DbTrade {
+            trade_id: TradeId::from(trade_id).to_string(),
+            order_id: order.order_id.clone(),
+            buyer: buyer.clone(),
+            token_amount: amount.to_string(),
+            cny_amount: cny_amount.to_string(),
+            payment_nonce: payment_nonce(&trade_id),
+            created_at: now.timestamp(),
+            expires_at: now.timestamp() + self.payment_window_secs as i64,
+            status: 0, // PENDING
+            synced_at: now,
+            escrow_tx_hash: Some(format!("{:#x}", tx_hash)),
+            settlement_tx_hash: None,
+            settlement_block: None,
+            confirmations: 0,
+            finalized_at: None,
+            token: Some(order.token.clone()),
+            pdf_file: None,
+            pdf_filename: None,
+            pdf_uploaded_at: None,
+            proof_user_public_values: None,
+            proof_accumulator: None,
+            proof_data: None,
+            axiom_proof_id: None,
+            proof_generated_at: None,
+            proof_json: None,
+            proof_format: None,
+        };
+
+        PostgresTradeRepository::new(db.pool().clone()).create(&trade).await?;
+        PostgresOrderRepository::new(db.pool().clone())
+            .adjust_remaining_amount(&order.order_id, &format!("-{}", amount))
+            .await?;
+
+        tracing::info!("🧪 Sandbox trade {} filled {} from order {}", trade.trade_id, amount, order.order_id);
+        Ok((tx_hash, trade))
+    }
+
+    /// submitPaymentProof: settle a pending trade at once, already final
+    pub async fn settle(&self, db: &Database, trade_id: &str) -> Result<H256, SandboxError> {
+        let trade = db.get_trade(trade_id).await?;
+        if trade.status != 0 {
+            return Err(SandboxError::TradeNotPending(trade.trade_id.clone()));
+        }
+        if trade.expires_at < Utc::now().timestamp() {
+            return Err(SandboxError::TradeExpired(trade.trade_id.clone()));
+        }
+
+        let tx_hash = H256(random_bytes32());
+        let trades = PostgresTradeRepository::new(db.pool().clone());
+        trades.update_status(&trade.trade_id, 1).await?;
+        trades.update_settlement_tx(&trade.trade_id, &format!("{:#x}", tx_hash)).await?;
+        PostgresConfirmationRepository::new(db.pool().clone())
+            .finalize(&trade.trade_id, 0, 1)
+            .await?;
+
+        tracing::info!("🧪 Sandbox trade {} settled", trade.trade_id);
+        Ok(tx_hash)
+    }
+}
+
+/// Mock prover output: the receipt "proves" the payment the trade expects, with
+/// zeroed accumulator and proof bytes of the format's sizes
+pub fn mock_proof(trade_id: &str, expected_hash: [u8; 32], format: &ProofFormat) -> GeneratedProof {
+    let hex_id = hexutil::strip_0x(trade_id);
+    let proof_id = format!("sandbox-{}", &hex_id[..hex_id.len().min(16)]);
+    GeneratedProof {
+        full_json: serde_json::json!({
+            "sandbox": true,
+            "user_public_values": hex::encode(expected_hash),
+        }),
+        proof_id,
+        user_public_values: expected_hash.to_vec(),
+        accumulator: vec![0u8; format.accumulator_len],
+        proof_data: vec![0u8; format.proof_data_len],
+        app_exe_commit: vec![0u8; 32],
+        app_vm_commit: vec![0u8; 32],
+        proof_format: format.version.clone(),
+    }
+}
+
+/// The contract's _generate8DigitNonce: trade ID mod 10^8, zero-padded
+pub fn payment_nonce(trade_id: &[u8; 32]) -> String {
+    let nonce = U256::from_big_endian(trade_id) % U256::exp10(PAYMENT_NONCE_LEN);
+    format!("{:0width$}", nonce.as_u64(), width = PAYMENT_NONCE_LEN)
+}
+
+fn random_bytes32() -> [u8; 32] {
+    ethers::core::rand::random::<[u8; 32]>()
+}
+
+/// Middleware: mark every response as coming from the sandbox
+pub async fn mark_sandbox_responses(State(state): State<AppState>, request: Request, next: Next) -> Response {
+    let response = next.run(request).await;
+    if state.sandbox.is_none() {
+        return response;
+    }
+
+    let (mut parts, body) = response.into_parts();
+    parts.headers.insert(SANDBOX_HEADER, HeaderValue::from_static("true"));
+
+    let is_json = parts
+        .headers
+        .get(header::CONTENT_TYPE)
+        .and_then(|v| v.to_str().ok())
+        .is_some_and(|v| v.starts_with("application/json"));
+    if !is_json {
+        return Response::from_parts(parts, body);
+    }
+
+    let Ok(bytes) = to_bytes(body, MAX_MARKED_BODY).await else {
+        return Response::from_parts(parts, Body::empty());
+    };
+    let body = match serde_json::from_slice::<serde_json::Value>(&bytes) {
+        Ok(serde_json::Value::Object(mut object)) => {
+            object.insert("sandbox".to_string(), serde_json::Value::Bool(true));
+            parts.headers.remove(header::CONTENT_LENGTH);
+            Body::from(serde_json::Value::Object(object).to_string())
+        }
+        _ => Body::from(bytes),
+    };
+    Response::from_parts(parts, body)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_payment_nonce() {
+        let mut trade_id = [0u8; 32];
+        trade_id[31] = 0x2a;
+        assert_eq!(payment_nonce(&trade_id), "00000042");
+
+        // 10^8 + 7 wraps to 7
+        let id = U256::exp10(8) + U256::from(7);
+        let mut bytes = [0u8; 32];
+        id.to_big_endian(&mut bytes);
+        assert_eq!(payment_nonce(&bytes), "00000007");
+        assert!(crate::blockchain::types::validate_payment_nonce(&payment_nonce(&random_bytes32())).is_ok());
+    }
+
+    #[test]
+    fn test_mock_proof_sizes() {
+        let format = ProofFormat::halo2_v1();
+        let proof = mock_proof("0xabcdef0123456789abcdef", [7u8; 32], &format);
+        assert_eq!(proof.user_public_values, vec![7u8; 32]);
+        assert!(format.validate(&proof.user_public_values, &proof.accumulator, &proof.proof_data).is_ok());
+        assert_eq!(proof.proof_format, format.version);
+        assert_eq!(proof.proof_id, "sandbox-abcdef0123456789");
+    }
+}
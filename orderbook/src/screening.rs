@@ -0,0 +1,239 @@
+// Address screening (sanctions / abuse)
+// Buyer addresses are screened in execute-fill, seller addresses when their orders are
+// synced and again when they are filled. An AddressScreener gives the verdict; it is
+// cached in address_screenings for SCREENING_CACHE_SECS, so the provider is asked at
+// most once per address per period. A flagged buyer is refused with an auditable
+// reason (screening_refusals, /api/admin/screening); a flagged seller's orders are
+// still mirrored from the chain but stay out of matching until a later screening
+// clears the address. Provider failures don't refuse service: they are logged and
+// the address is screened again on the next request.
+//
+// Configuration (env):
+// - SCREENING_URL: provider endpoint, POSTed {"address", "role"} and answering
+//   {"flagged", "reason"} (screening is off without it)
+// - SCREENING_TOKEN: bearer token for the provider (optional)
+// - SCREENING_CACHE_SECS: how long a verdict is reused (default 86400)
+
+use async_trait::async_trait;
+use chrono::{Duration as ChronoDuration, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::PgPool;
+use std::sync::Arc;
+use std::time::Duration;
+use thiserror::Error;
+
+use crate::blockchain::address::EthAddress;
+use crate::db::models::{DbScreeningRefusal, DbScreeningVerdict};
+use crate::db::screening::{NewScreeningRefusal, PostgresScreeningRepository, ScreeningRepository};
+use crate::db::DbResult;
+
+/// How long a verdict is reused by default
+pub const DEFAULT_SCREENING_CACHE_SECS: i64 = 86_400;
+
+#[derive(Error, Debug)]
+pub enum ScreeningError {
+    #[error("Screening request failed: {0}")]
+    Request(String),
+    #[error("Screening provider returned status {0}")]
+    Status(u16),
+}
+
+/// A provider's answer for one address
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ScreeningVerdict {
+    pub flagged: bool,
+    #[serde(default)]
+    pub reason: Option<String>,
+}
+
+/// Sanctions / abuse screening provider
+#[async_trait]
+pub trait AddressScreener: Send + Sync {
+    /// Name recorded with verdicts and refusals
+    fn name(&self) -> &str;
+
+    /// Whether addresses are screened at all (false skips the verdict cache too)
+    fn enabled(&self) -> bool {
+        true
+    }
+
+    async fn screen(&self, address: &EthAddress, role: &str) -> Result<ScreeningVerdict, ScreeningError>;
+}
+
+/// Default screener: every address is clear
+pub struct NoScreening;
+
+#[async_trait]
+impl AddressScreener for NoScreening {
+    fn name(&self) -> &str {
+        "none"
+    }
+
+    fn enabled(&self) -> bool {
+        false
+    }
+
+    async fn screen(&self, _address: &EthAddress, _role: &str) -> Result<ScreeningVerdict, ScreeningError> {
+        Ok(ScreeningVerdict::default())
+    }
+}
+
+#[derive(Serialize)]
+struct ScreeningRequest<'a> {
+    address: &'a EthAddress,
+    role: &'a str,
+}
+
+/// POSTs {"address", "role"} to a provider endpoint (optional bearer token)
+pub struct HttpScreener {
+    url: String,
+    token: Option<String>,
+    client: reqwest::Client,
+}
+
+impl HttpScreener {
+    pub fn new(url: String, token: Option<String>) -> Self {
+        let client = reqwest::Client::builder()
+            .timeout(Duration::from_secs(10))
+            .build()
+            .unwrap_or_default();
+
+        Self { url, token, client }
+    }
+
+    /// Configure from SCREENING_URL / SCREENING_TOKEN (None if no URL)
+    pub fn from_env() -> Option<Self> {
+        let url = std::env::var("SCREENING_URL").ok()?;
+        let token = std::env::var("SCREENING_TOKEN").ok();
+        Some(Self::new(url, token))
+    }
+}
+
+#[async_trait]
+impl AddressScreener for HttpScreener {
+    fn name(&self) -> &str {
+        "http"
+    }
+
+    async fn screen(&self, address: &EthAddress, role: &str) -> Result<ScreeningVerdict, ScreeningError> {
+        let mut request = self.client.post(&self.url).json(&ScreeningRequest { address, role });
+        if let Some(token) = &self.token {
+            request = request.bearer_auth(token);
+        }
+
+        let response = request
+            .send()
+            .await
+            .map_err(|e| ScreeningError::Request(e.to_string()))?;
+        if !response.status().is_success() {
+            return Err(ScreeningError::Status(response.status().as_u16()));
+        }
+
+        response
+            .json()
+            .await
+            .map_err(|e| ScreeningError::Request(e.to_string()))
+    }
+}
+
+/// A screener with its verdict cache
+#[derive(Clone)]
+pub struct Screening {
+    screener: Arc<dyn AddressScreener>,
+    cache_ttl: ChronoDuration,
+}
+
+impl Default for Screening {
+    fn default() -> Self {
+        Self::new(Arc::new(NoScreening), DEFAULT_SCREENING_CACHE_SECS)
+    }
+}
+
+impl Screening {
+    pub fn new(screener: Arc<dyn AddressScreener>, cache_secs: i64) -> Self {
+        Self { screener, cache_ttl: ChronoDuration::seconds(cache_secs.max(0)) }
+    }
+
+    /// HTTP provider from SCREENING_URL, otherwise no screening
+    pub fn from_env() -> Self {
+        let cache_secs = std::env::var("SCREENING_CACHE_SECS")
+            .ok()
+            .and_then(|v| v.parse().ok())
+            .unwrap_or(DEFAULT_SCREENING_CACHE_SECS);
+        match HttpScreener::from_env() {
+            Some(screener) => Self::new(Arc::new(screener), cache_secs),
+            None => Self::default(),
+        }
+    }
+
+    pub fn provider(&self) -> &str {
+        self.screener.name()
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        self.screener.enabled()
+    }
+
+    /// The address's verdict, cached or fresh from the provider
+    /// None when screening is off or the provider failed.
+    pub async fn verdict(&self, pool: &PgPool, address: &EthAddress, role: &str) -> DbResult<Option<DbScreeningVerdict>> {
+        if !self.is_enabled() {
+            return Ok(None);
+        }
+        let repo = PostgresScreeningRepository::new(pool.clone());
+        if let Some(cached) = repo.verdict(address, Utc::now() - self.cache_ttl).await? {
+            return Ok(Some(cached));
+        }
+
+        match self.screener.screen(address, role).await {
+            Ok(verdict) => {
+                let verdict = repo
+                    .record_verdict(address, verdict.flagged, verdict.reason.as_deref(), self.provider())
+                    .await?;
+                if verdict.flagged {
+                    tracing::warn!("🚫 {} {} flagged by screening: {}", role, address, verdict.reason.as_deref().unwrap_or("no reason given"));
+                }
+                Ok(Some(verdict))
+            }
+            Err(e) => {
+                tracing::warn!("Screening {} {} failed, not refusing: {}", role, address, e);
+                Ok(None)
+            }
+        }
+    }
+
+    /// Screen an address; a flagged one is refused and the refusal logged
+    /// `reference` is the order the request concerns, if any.
+    pub async fn check(
+        &self,
+        pool: &PgPool,
+        address: &EthAddress,
+        role: &'static str,
+        context: &'static str,
+        reference: Option<&str>,
+    ) -> DbResult<Result<(), DbScreeningRefusal>> {
+        let Some(verdict) = self.verdict(pool, address, role).await?.filter(|v| v.flagged) else {
+            return Ok(Ok(()));
+        };
+
+        let refusal = PostgresScreeningRepository::new(pool.clone())
+            .record_refusal(&NewScreeningRefusal {
+                address,
+                role,
+                context,
+                reference,
+                reason: verdict.reason.as_deref(),
+                provider: &verdict.provider,
+            })
+            .await?;
+        tracing::warn!(
+            "🚫 Refused {} {} ({}{}), refusal #{}",
+            role,
+            address,
+            context,
+            reference.map(|r| format!(" {}", r)).unwrap_or_default(),
+            refusal.id
+        );
+        Ok(Err(refusal))
+    }
+}
@@ -0,0 +1,236 @@
+// Buyer spending limits
+// Every buyer is in a tier: 0 unless an admin or the KYC provider put them higher
+// (buyer_tiers). A tier caps the CNY value of the trades a buyer opened over the last
+// 24 hours and the last 30 days; pending and settled trades count, expired ones don't.
+// A buyer gets the caps of the highest configured tier at or below their own, so with
+// no tiers configured nobody is limited. execute-fill refuses a plan that would take
+// the buyer over a cap (spending_limit_exceeded), match refuses a buyer with no room left.
+//
+// KYC: an external provider plugs in as a KycProvider (AppState::with_kyc_provider).
+// When a buyer hits a cap it is asked for the tier it verified them for; a higher
+// tier is stored with source "kyc" and the check is repeated.
+
+use async_trait::async_trait;
+use serde::Serialize;
+use std::fmt;
+use thiserror::Error;
+
+use crate::blockchain::address::EthAddress;
+use crate::db::models::{DbBuyerVolume, DbSpendingLimitTier};
+use crate::db::spending_limits::SOURCE_KYC;
+use crate::db::{Database, DbResult};
+use crate::text_utils::format_cents;
+
+/// Limit windows
+pub const WINDOW_24H: &str = "24h";
+pub const WINDOW_30D: &str = "30d";
+
+#[derive(Error, Debug)]
+pub enum KycError {
+    #[error("KYC provider request failed: {0}")]
+    Request(String),
+}
+
+/// What a KYC provider verified a buyer for
+#[derive(Debug, Clone)]
+pub struct KycVerification {
+    pub tier: i32,
+    /// The provider's id of the verification, kept with the buyer's tier
+    pub reference: Option<String>,
+}
+
+/// External identity verification that can raise a buyer's tier
+#[async_trait]
+pub trait KycProvider: Send + Sync {
+    /// Name recorded as who set the tier
+    fn name(&self) -> &str;
+
+    /// Tier the buyer is verified for (None: not verified)
+    async fn verified_tier(&self, buyer: &EthAddress) -> Result<Option<KycVerification>, KycError>;
+}
+
+/// A buyer's tier, its caps and what the buyer already traded
+#[derive(Debug, Clone, Serialize)]
+pub struct BuyerLimits {
+    pub tier: i32,
+    /// CNY cents over 24h (None: no cap)
+    pub daily_limit_cny: Option<i64>,
+    /// CNY cents over 30 days (None: no cap)
+    pub monthly_limit_cny: Option<i64>,
+    pub used_24h_cny: i64,
+    pub used_30d_cny: i64,
+}
+
+impl BuyerLimits {
+    /// CNY cents the buyer may still trade (None: no cap)
+    pub fn remaining_cny(&self) -> Option<i64> {
+        let daily = self.daily_limit_cny.map(|limit| (limit - self.used_24h_cny).max(0));
+        let monthly = self.monthly_limit_cny.map(|limit| (limit - self.used_30d_cny).max(0));
+        match (daily, monthly) {
+            (Some(d), Some(m)) => Some(d.min(m)),
+            (d, m) => d.or(m),
+        }
+    }
+
+    /// The first cap `requested_cny` more would go over
+    pub fn check(&self, requested_cny: i64) -> Result<(), LimitExceeded> {
+        let windows = [
+            (WINDOW_24H, self.daily_limit_cny, self.used_24h_cny),
+            (WINDOW_30D, self.monthly_limit_cny, self.used_30d_cny),
+        ];
+        for (window, limit, used) in windows {
+            let Some(limit_cny) = limit else { continue };
+            if used.saturating_add(requested_cny) > limit_cny {
+                return Err(LimitExceeded { tier: self.tier, window, limit_cny, used_cny: used, requested_cny });
+            }
+        }
+        Ok(())
+    }
+}
+
+/// A fill the buyer's tier doesn't allow
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct LimitExceeded {
+    pub tier: i32,
+    /// 24h or 30d
+    pub window: &'static str,
+    pub limit_cny: i64,
+    pub used_cny: i64,
+    pub requested_cny: i64,
+}
+
+impl fmt::Display for LimitExceeded {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "tier {} allows {} CNY per {}, {} CNY already traded and {} CNY requested",
+            self.tier,
+            format_cents(self.limit_cny.max(0) as u64),
+            self.window,
+            format_cents(self.used_cny.max(0) as u64),
+            format_cents(self.requested_cny.max(0) as u64)
+        )
+    }
+}
+
+/// Configured tiers: a buyer gets the highest one at or below their own
+#[derive(Debug, Clone, Default)]
+pub struct SpendingLimitPolicy {
+    pub tiers: Vec<DbSpendingLimitTier>,
+}
+
+impl SpendingLimitPolicy {
+    pub fn from_tiers(tiers: Vec<DbSpendingLimitTier>) -> Self {
+        Self { tiers }
+    }
+
+    /// Caps of `tier`; None when no tier at or below it is configured
+    pub fn tier_for(&self, tier: i32) -> Option<&DbSpendingLimitTier> {
+        self.tiers.iter().filter(|t| t.tier <= tier).max_by_key(|t| t.tier)
+    }
+
+    pub fn limits(&self, tier: i32, volume: &DbBuyerVolume) -> Option<BuyerLimits> {
+        self.tier_for(tier).map(|caps| BuyerLimits {
+            tier,
+            daily_limit_cny: caps.daily_limit_cny,
+            monthly_limit_cny: caps.monthly_limit_cny,
+            used_24h_cny: volume.volume_24h_cny,
+            used_30d_cny: volume.volume_30d_cny,
+        })
+    }
+}
+
+/// The buyer's limits (None: not limited)
+pub async fn buyer_limits(db: &Database, buyer: &EthAddress) -> DbResult<Option<BuyerLimits>> {
+    let policy = SpendingLimitPolicy::from_tiers(db.list_spending_limit_tiers().await?);
+    if policy.tiers.is_empty() {
+        return Ok(None);
+    }
+    let tier = db.get_buyer_tier(buyer).await?.map(|t| t.tier).unwrap_or(0);
+    if policy.tier_for(tier).is_none() {
+        return Ok(None);
+    }
+    Ok(policy.limits(tier, &db.get_buyer_volume(buyer).await?))
+}
+
+/// Check that the buyer may trade `requested_cny` more. On a cap the KYC provider, if
+/// any, is asked whether the buyer is verified for a higher tier.
+/// Returns the limits the fill was checked against (None: not limited).
+pub async fn check_buyer(
+    db: &Database,
+    kyc: Option<&dyn KycProvider>,
+    buyer: &EthAddress,
+    requested_cny: i64,
+) -> DbResult<Result<Option<BuyerLimits>, LimitExceeded>> {
+    let Some(limits) = buyer_limits(db, buyer).await? else {
+        return Ok(Ok(None));
+    };
+    let exceeded = match limits.check(requested_cny) {
+        Ok(()) => return Ok(Ok(Some(limits))),
+        Err(exceeded) => exceeded,
+    };
+
+    let Some(kyc) = kyc else {
+        return Ok(Err(exceeded));
+    };
+    let verification = match kyc.verified_tier(buyer).await {
+        Ok(Some(verification)) if verification.tier > limits.tier => verification,
+        Ok(_) => return Ok(Err(exceeded)),
+        Err(e) => {
+            tracing::warn!("KYC lookup for {} failed: {}", buyer, e);
+            return Ok(Err(exceeded));
+        }
+    };
+    db.set_buyer_tier(buyer, verification.tier, SOURCE_KYC, verification.reference.as_deref(), Some(kyc.name()))
+        .await?;
+    tracing::info!("Buyer {} raised to tier {} by {}", buyer, verification.tier, kyc.name());
+
+    let Some(limits) = buyer_limits(db, buyer).await? else {
+        return Ok(Ok(None));
+    };
+    Ok(limits.check(requested_cny).map(|()| Some(limits)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Utc;
+
+    fn tier(tier: i32, daily: Option<i64>, monthly: Option<i64>) -> DbSpendingLimitTier {
+        DbSpendingLimitTier {
+            tier,
+            daily_limit_cny: daily,
+            monthly_limit_cny: monthly,
+            updated_by: None,
+            updated_at: Utc::now(),
+        }
+    }
+
+    #[test]
+    fn test_spending_limit_policy() {
+        let policy = SpendingLimitPolicy::from_tiers(vec![
+            tier(0, Some(100_000), Some(1_000_000)),
+            tier(2, Some(1_000_000), None),
+        ]);
+        let volume = DbBuyerVolume { volume_24h_cny: 60_000, volume_30d_cny: 990_000 };
+
+        // Tier 1 isn't configured: tier 0's caps apply
+        let limits = policy.limits(1, &volume).unwrap();
+        assert_eq!(limits.daily_limit_cny, Some(100_000));
+        assert_eq!(limits.remaining_cny(), Some(10_000));
+        assert!(limits.check(10_000).is_ok());
+        assert_eq!(
+            limits.check(20_000),
+            Err(LimitExceeded { tier: 1, window: WINDOW_30D, limit_cny: 1_000_000, used_cny: 990_000, requested_cny: 20_000 })
+        );
+        assert_eq!(limits.check(50_000).unwrap_err().window, WINDOW_24H);
+
+        // No monthly cap from tier 2 up
+        let limits = policy.limits(3, &volume).unwrap();
+        assert_eq!(limits.remaining_cny(), Some(940_000));
+        assert!(limits.check(900_000).is_ok());
+
+        assert!(SpendingLimitPolicy::default().limits(0, &volume).is_none());
+        assert!(SpendingLimitPolicy::from_tiers(vec![tier(1, Some(1), None)]).limits(0, &volume).is_none());
+    }
+}
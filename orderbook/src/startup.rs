@@ -0,0 +1,338 @@
+// Startup sanity checks
+// Problems that would otherwise only surface on the first request that hits them -
+// half-applied or edited migrations, an escrow ABI that no longer matches the deployed
+// contract, a guest program commit nobody registered, missing prover credentials - are
+// checked once before the server binds. Fatal findings refuse to start; degraded ones
+// start the server and are reported by /health. STARTUP_STRICT=true refuses to start on
+// degraded findings as well.
+//
+// Configuration (env):
+// - STARTUP_STRICT: treat degraded checks as fatal (default false)
+// - ESCROW_ABI_SHA256: expected sha256 of abi/ZkAliPayEscrow.json (unset = not pinned)
+
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::HashMap;
+
+use crate::api::AppState;
+use crate::blockchain::client::EthereumClient;
+use crate::blockchain::ZKALIPAYESCROW_ABI;
+use crate::db::{AppliedMigration, MIGRATOR};
+use crate::hexutil;
+
+/// Escrow ABI the contract bindings were generated from
+const ESCROW_ABI_JSON: &[u8] = include_bytes!("../abi/ZkAliPayEscrow.json");
+
+/// Settings that only matter once blockchain integration is enabled, and what fails without them
+const BLOCKCHAIN_ENV: &[(&str, &str)] = &[
+    ("AXIOM_API_KEY", "proof generation"),
+    ("AXIOM_CONFIG_ID", "proof generation"),
+];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum CheckStatus {
+    Ok,
+    /// The server can run, but a feature will fail until this is fixed
+    Degraded,
+    /// Running would serve wrong results or fail every request
+    Fatal,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StartupCheck {
+    pub name: String,
+    pub status: CheckStatus,
+    pub detail: String,
+}
+
+impl StartupCheck {
+    fn new(name: &'static str, status: CheckStatus, detail: impl Into<String>) -> Self {
+        Self { name: name.to_string(), status, detail: detail.into() }
+    }
+}
+
+/// Outcome of all startup checks
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct StartupReport {
+    pub checks: Vec<StartupCheck>,
+}
+
+impl StartupReport {
+    /// Worst status across all checks
+    pub fn status(&self) -> CheckStatus {
+        self.checks.iter().map(|c| c.status).max().unwrap_or(CheckStatus::Ok)
+    }
+
+    /// Checks that did not pass
+    pub fn issues(&self) -> Vec<StartupCheck> {
+        self.checks.iter().filter(|c| c.status != CheckStatus::Ok).cloned().collect()
+    }
+
+    /// Whether the server should refuse to start
+    pub fn refuses_start(&self, strict: bool) -> bool {
+        match self.status() {
+            CheckStatus::Fatal => true,
+            CheckStatus::Degraded => strict,
+            CheckStatus::Ok => false,
+        }
+    }
+
+    pub fn log(&self) {
+        for check in &self.checks {
+            match check.status {
+                CheckStatus::Ok => tracing::info!("✅ Startup check {}: {}", check.name, check.detail),
+                CheckStatus::Degraded => tracing::warn!("⚠️  Startup check {} degraded: {}", check.name, check.detail),
+                CheckStatus::Fatal => tracing::error!("❌ Startup check {} failed: {}", check.name, check.detail),
+            }
+        }
+    }
+}
+
+/// STARTUP_STRICT: refuse to start on degraded checks too
+pub fn strict_from_env() -> bool {
+    std::env::var("STARTUP_STRICT")
+        .map(|v| matches!(v.trim().to_ascii_lowercase().as_str(), "1" | "true" | "yes"))
+        .unwrap_or(false)
+}
+
+/// Run every startup check against the configured state
+pub async fn run_startup_checks(state: &AppState) -> StartupReport {
+    let mut checks = vec![
+        check_migrations(state).await,
+        check_abi_hash(std::env::var("ESCROW_ABI_SHA256").ok().as_deref()),
+    ];
+
+    let blockchain_configured = std::env::var("ESCROW_CONTRACT_ADDRESS").is_ok();
+    match &state.blockchain_client {
+        Some(client) => {
+            checks.push(check_escrow_code(client).await);
+            checks.push(check_proof_program(state, client).await);
+        }
+        None if state.sandbox.is_some() => {}
+        None if blockchain_configured => checks.push(StartupCheck::new(
+            "blockchain",
+            CheckStatus::Degraded,
+            "ESCROW_CONTRACT_ADDRESS is set but the Ethereum client failed to initialize; running without blockchain integration",
+        )),
+        None => {}
+    }
+    checks.push(check_env(state.blockchain_client.is_some(), |key| std::env::var(key).ok()));
+
+    StartupReport { checks }
+}
+
+async fn check_migrations(state: &AppState) -> StartupCheck {
+    match state.db.applied_migrations().await {
+        Ok(applied) => compare_migrations(&expected_migrations(), &applied),
+        Err(e) => StartupCheck::new("migrations", CheckStatus::Fatal, format!("cannot read migration history: {}", e)),
+    }
+}
+
+/// (version, checksum) of the up migrations embedded in the binary
+fn expected_migrations() -> Vec<(i64, Vec<u8>)> {
+    MIGRATOR
+        .iter()
+        .filter(|m| !m.migration_type.is_down_migration())
+        .map(|m| (m.version, m.checksum.to_vec()))
+        .collect()
+}
+
+/// Compare embedded migrations with the database's history. Missing, failed or edited
+/// migrations are fatal; versions the binary doesn't know about mean a newer release
+/// migrated this database, which usually still works but is worth flagging.
+fn compare_migrations(expected: &[(i64, Vec<u8>)], applied: &[AppliedMigration]) -> StartupCheck {
+    let applied_by_version: HashMap<i64, &AppliedMigration> = applied.iter().map(|m| (m.version, m)).collect();
+
+    let mut pending = Vec::new();
+    let mut failed = Vec::new();
+    let mut modified = Vec::new();
+    for (version, checksum) in expected {
+        match applied_by_version.get(version) {
+            None => pending.push(*version),
+            Some(m) if !m.success => failed.push(*version),
+            Some(m) if m.checksum != *checksum => modified.push(*version),
+            Some(_) => {}
+        }
+    }
+    let unknown: Vec<i64> = applied
+        .iter()
+        .map(|m| m.version)
+        .filter(|v| !expected.iter().any(|(version, _)| version == v))
+        .collect();
+
+    let mut problems = Vec::new();
+    for (label, versions) in [("pending", &pending), ("failed", &failed), ("modified after apply", &modified)] {
+        if !versions.is_empty() {
+            problems.push(format!("{} {:?}", label, versions));
+        }
+    }
+    if !problems.is_empty() {
+        return StartupCheck::new("migrations", CheckStatus::Fatal, problems.join("; "));
+    }
+    if !unknown.is_empty() {
+        return StartupCheck::new(
+            "migrations",
+            CheckStatus::Degraded,
+            format!("database has migrations this build doesn't know: {:?}", unknown),
+        );
+    }
+    StartupCheck::new("migrations", CheckStatus::Ok, format!("{} applied", expected.len()))
+}
+
+fn check_abi_hash(pinned: Option<&str>) -> StartupCheck {
+    let hash = hex::encode(Sha256::digest(ESCROW_ABI_JSON));
+    match pinned.map(|p| hexutil::strip_0x(p).to_ascii_lowercase()) {
+        Some(expected) if expected != hash => StartupCheck::new(
+            "escrow_abi",
+            CheckStatus::Fatal,
+            format!("abi/ZkAliPayEscrow.json sha256 {} does not match ESCROW_ABI_SHA256 {}", hash, expected),
+        ),
+        Some(_) => StartupCheck::new("escrow_abi", CheckStatus::Ok, format!("sha256 {} (pinned)", hash)),
+        None => StartupCheck::new("escrow_abi", CheckStatus::Ok, format!("sha256 {}", hash)),
+    }
+}
+
+/// The deployed escrow must exist and dispatch every function the bindings call
+async fn check_escrow_code(client: &EthereumClient) -> StartupCheck {
+    let code = match client.escrow_code().await {
+        Ok(code) => code,
+        Err(e) => {
+            return StartupCheck::new("escrow_contract", CheckStatus::Degraded, format!("cannot fetch escrow bytecode: {}", e))
+        }
+    };
+    if code.is_empty() {
+        return StartupCheck::new(
+            "escrow_contract",
+            CheckStatus::Fatal,
+            format!("no contract deployed at the escrow address on chain {}", client.chain_id()),
+        );
+    }
+
+    let missing = missing_selectors(&code);
+    if missing.is_empty() {
+        StartupCheck::new("escrow_contract", CheckStatus::Ok, "deployed bytecode matches the escrow ABI")
+    } else {
+        StartupCheck::new(
+            "escrow_contract",
+            CheckStatus::Degraded,
+            format!("deployed bytecode has no dispatcher entry for {} (stale ABI?)", missing.join(", ")),
+        )
+    }
+}
+
+/// ABI functions whose selector doesn't appear in `code`. Solidity dispatchers push each
+/// selector with the shortest PUSH that fits, so leading zero bytes are dropped.
+fn missing_selectors(code: &[u8]) -> Vec<String> {
+    let mut missing: Vec<String> = ZKALIPAYESCROW_ABI
+        .functions()
+        .filter(|function| {
+            let selector = function.short_signature();
+            let significant: Vec<u8> = selector.iter().copied().skip_while(|b| *b == 0).collect();
+            if significant.is_empty() {
+                return false;
+            }
+            let mut push = vec![0x5f + significant.len() as u8];
+            push.extend_from_slice(&significant);
+            !code.windows(push.len()).any(|window| window == push.as_slice())
+        })
+        .map(|function| function.name.clone())
+        .collect();
+    missing.sort();
+    missing.dedup();
+    missing
+}
+
+/// When programs are pinned, the contract's appExeCommit should be one of them; otherwise
+/// proofs are generated with the default program and may not verify
+async fn check_proof_program(state: &AppState, client: &EthereumClient) -> StartupCheck {
+    let config = match state.contract_config.get(client).await {
+        Ok(config) => config,
+        Err(e) => {
+            return StartupCheck::new("proof_program", CheckStatus::Degraded, format!("cannot read contract config: {}", e))
+        }
+    };
+
+    let commit = config.app_exe_commit;
+    let program = state.proof_programs.for_commit(&commit);
+    let pinned = state.proof_programs.programs().any(|p| p.app_exe_commit.is_some());
+    if pinned && program.app_exe_commit.is_none() {
+        return StartupCheck::new(
+            "proof_program",
+            CheckStatus::Degraded,
+            format!(
+                "on-chain appExeCommit 0x{} matches no PROOF_PROGRAMS entry; falling back to {}",
+                hex::encode(commit),
+                program.program_id
+            ),
+        );
+    }
+    StartupCheck::new(
+        "proof_program",
+        CheckStatus::Ok,
+        format!("appExeCommit 0x{} -> {}", hex::encode(commit), program.program_id),
+    )
+}
+
+fn check_env(blockchain_enabled: bool, lookup: impl Fn(&str) -> Option<String>) -> StartupCheck {
+    if !blockchain_enabled {
+        return StartupCheck::new("environment", CheckStatus::Ok, "blockchain integration disabled");
+    }
+
+    let missing: Vec<String> = BLOCKCHAIN_ENV
+        .iter()
+        .filter(|(key, _)| lookup(key).is_none_or(|value| value.trim().is_empty()))
+        .map(|(key, feature)| format!("{} ({})", key, feature))
+        .collect();
+    if missing.is_empty() {
+        StartupCheck::new("environment", CheckStatus::Ok, "complete")
+    } else {
+        StartupCheck::new("environment", CheckStatus::Degraded, format!("missing {}", missing.join(", ")))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn applied(version: i64, success: bool, checksum: &[u8]) -> AppliedMigration {
+        AppliedMigration { version, success, checksum: checksum.to_vec() }
+    }
+
+    #[test]
+    fn test_compare_migrations() {
+        let expected = vec![(1, vec![1u8]), (2, vec![2u8])];
+
+        let check = compare_migrations(&expected, &[applied(1, true, &[1]), applied(2, true, &[2])]);
+        assert_eq!(check.status, CheckStatus::Ok);
+
+        // Partially applied: one pending, one dirty
+        let check = compare_migrations(&expected, &[applied(1, false, &[1])]);
+        assert_eq!(check.status, CheckStatus::Fatal);
+        assert_eq!(check.detail, "pending [2]; failed [1]");
+
+        let check = compare_migrations(&expected, &[applied(1, true, &[1]), applied(2, true, &[9])]);
+        assert_eq!(check.detail, "modified after apply [2]");
+
+        // A newer release migrated the database
+        let check = compare_migrations(&expected, &[applied(1, true, &[1]), applied(2, true, &[2]), applied(3, true, &[3])]);
+        assert_eq!(check.status, CheckStatus::Degraded);
+    }
+
+    #[test]
+    fn test_abi_hash_and_env() {
+        let hash = hex::encode(Sha256::digest(ESCROW_ABI_JSON));
+        assert_eq!(check_abi_hash(None).status, CheckStatus::Ok);
+        assert_eq!(check_abi_hash(Some(&format!("0x{}", hash.to_uppercase()))).status, CheckStatus::Ok);
+        assert_eq!(check_abi_hash(Some(&"00".repeat(32))).status, CheckStatus::Fatal);
+
+        assert_eq!(check_env(false, |_| None).status, CheckStatus::Ok);
+        let check = check_env(true, |key| (key == "AXIOM_API_KEY").then(|| "key".to_string()));
+        assert_eq!(check.status, CheckStatus::Degraded);
+        assert_eq!(check.detail, "missing AXIOM_CONFIG_ID (proof generation)");
+
+        let report = StartupReport { checks: vec![check] };
+        assert!(!report.refuses_start(false));
+        assert!(report.refuses_start(true));
+    }
+}
@@ -0,0 +1,116 @@
+// Unicode-safe text helpers for building proof input lines
+// Alipay receipts mix ASCII with Chinese labels and names, so everything here works on
+// grapheme clusters rather than byte offsets - slicing a &str at a byte index inside a
+// multi-byte character panics, and counting bytes miscounts "characters".
+
+use unicode_segmentation::UnicodeSegmentation;
+
+/// Full-width colon used in Alipay receipt labels (e.g. "账号：")
+pub const FULLWIDTH_COLON: char = '：';
+
+/// Number of user-perceived characters (grapheme clusters)
+pub fn grapheme_len(s: &str) -> usize {
+    s.graphemes(true).count()
+}
+
+/// First `n` graphemes of `s` (all of `s` if shorter)
+pub fn take_graphemes(s: &str, n: usize) -> &str {
+    match s.grapheme_indices(true).nth(n) {
+        Some((idx, _)) => &s[..idx],
+        None => s,
+    }
+}
+
+/// Last `n` graphemes of `s` (all of `s` if shorter)
+pub fn last_graphemes(s: &str, n: usize) -> &str {
+    if n == 0 {
+        return "";
+    }
+    match s.grapheme_indices(true).rev().nth(n - 1) {
+        Some((idx, _)) => &s[idx..],
+        None => s,
+    }
+}
+
+/// Keep the first `keep_start` and last `keep_end` graphemes and replace the middle
+/// with `mask` repeated `mask_len` times
+///
+/// Returns None if `s` has no more than `keep_start + keep_end` graphemes (nothing to mask).
+pub fn mask_middle(s: &str, keep_start: usize, keep_end: usize, mask: char, mask_len: usize) -> Option<String> {
+    if grapheme_len(s) <= keep_start + keep_end {
+        return None;
+    }
+
+    let mut out = String::with_capacity(s.len());
+    out.push_str(take_graphemes(s, keep_start));
+    out.extend(std::iter::repeat_n(mask, mask_len));
+    out.push_str(last_graphemes(s, keep_end));
+    Some(out)
+}
+
+/// Whether `s` consists of exactly `len` ASCII digits
+pub fn is_ascii_digits(s: &str, len: usize) -> bool {
+    s.len() == len && s.bytes().all(|b| b.is_ascii_digit())
+}
+
+/// Format an amount in cents with two decimals (106000 → "1060.00")
+pub fn format_cents(cents: u64) -> String {
+    format!("{}.{:02}", cents / 100, cents % 100)
+}
+
+/// Build a receipt line "<label>：<value>" (label without the colon)
+pub fn labeled_line(label: &str, value: &str) -> String {
+    format!("{}{}{}", label, FULLWIDTH_COLON, value)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use proptest::prelude::*;
+
+    #[test]
+    fn test_multibyte_helpers() {
+        assert_eq!(grapheme_len("张三丰"), 3);
+        assert_eq!(take_graphemes("张三丰", 2), "张三");
+        assert_eq!(last_graphemes("张三丰", 1), "丰");
+        assert_eq!(last_graphemes("张三丰", 5), "张三丰");
+        assert_eq!(mask_middle("张三丰", 1, 1, '*', 1).as_deref(), Some("张*丰"));
+        assert_eq!(mask_middle("13945908941", 3, 2, '*', 6).as_deref(), Some("139******41"));
+        assert_eq!(mask_middle("ab", 1, 1, '*', 3), None);
+        assert_eq!(format_cents(106000), "1060.00");
+        assert_eq!(format_cents(5), "0.05");
+        assert_eq!(labeled_line("账号", "139******41"), "账号：139******41");
+    }
+
+    proptest! {
+        #[test]
+        fn prop_take_and_last_are_prefix_and_suffix(s in "\\PC{0,24}", n in 0usize..30) {
+            let head = take_graphemes(&s, n);
+            let tail = last_graphemes(&s, n);
+            prop_assert!(s.starts_with(head));
+            prop_assert!(s.ends_with(tail));
+            prop_assert_eq!(grapheme_len(head), n.min(grapheme_len(&s)));
+            prop_assert_eq!(grapheme_len(tail), n.min(grapheme_len(&s)));
+        }
+
+        #[test]
+        fn prop_mask_middle_keeps_ends(s in "[\\p{Han}a-z0-9@.]{0,20}", keep_start in 0usize..5, keep_end in 0usize..5) {
+            match mask_middle(&s, keep_start, keep_end, '*', 3) {
+                Some(masked) => {
+                    prop_assert!(masked.starts_with(take_graphemes(&s, keep_start)));
+                    prop_assert!(masked.ends_with(last_graphemes(&s, keep_end)));
+                    prop_assert_eq!(grapheme_len(&masked), keep_start + 3 + keep_end);
+                }
+                None => prop_assert!(grapheme_len(&s) <= keep_start + keep_end),
+            }
+        }
+
+        #[test]
+        fn prop_format_cents_round_trips(cents in any::<u64>()) {
+            let formatted = format_cents(cents);
+            let (yuan, frac) = formatted.split_once('.').unwrap();
+            prop_assert_eq!(frac.len(), 2);
+            prop_assert_eq!(yuan.parse::<u64>().unwrap() * 100 + frac.parse::<u64>().unwrap(), cents);
+        }
+    }
+}
@@ -34,3 +34,1851 @@ async fn test_health_check() {
     let result = db.health_check().await;
     assert!(result.is_ok(), "Health check failed");
 }
+
+#[tokio::test]
+async fn test_concurrent_migrations_apply_once() {
+    setup_migrated_pool().await;
+
+    // Replicas starting together queue on the migration lock; none re-applies anything
+    let replicas: Vec<Database> = futures::future::try_join_all((0..3).map(|_| Database::new(&test_database_url())))
+        .await
+        .unwrap();
+    let applied = futures::future::try_join_all(replicas.iter().map(|db| db.migrate())).await.unwrap();
+    assert_eq!(applied, vec![0, 0, 0]);
+}
+
+// ============================================================================
+// Schema Invariant Tests (migrations/008_financial_invariants.sql)
+// ============================================================================
+
+use chrono::Utc;
+use zkalipay_orderbook::DbError;
+
+async fn setup_migrated_pool() -> PgPool {
+    let db = Database::new(&test_database_url()).await.unwrap();
+    db.migrate().await.unwrap();
+    setup_test_pool().await
+}
+
+fn random_bytes32() -> String {
+    format!("0x{}", hex::encode(rand::random::<[u8; 32]>()))
+}
+
+fn random_nonce() -> String {
+    format!("{:08}", rand::random::<u32>() % 100_000_000)
+}
+
+fn test_order() -> DbOrder {
+    DbOrder {
+        order_id: random_bytes32(),
+        seller: "0x1111111111111111111111111111111111111111".parse().unwrap(),
+        token: "0x2222222222222222222222222222222222222222".parse().unwrap(),
+        total_amount: "1000000".to_string(),
+        remaining_amount: "1000000".to_string(),
+        exchange_rate: "735".to_string(),
+        alipay_id: "13945908941".to_string(),
+        alipay_name: "张三".to_string(),
+        created_at: 1_700_000_000,
+        synced_at: Utc::now(),
+        alipay_id_format: "phone".to_string(),
+        min_fill: None,
+        lot_size: None,
+    }
+}
+
+fn test_trade(order_id: &str) -> DbTrade {
+    DbTrade {
+        trade_id: random_bytes32(),
+        order_id: order_id.to_string(),
+        buyer: "0x3333333333333333333333333333333333333333".parse().unwrap(),
+        token_amount: "1000".to_string(),
+        cny_amount: "735".to_string(),
+        payment_nonce: random_nonce(),
+        created_at: 1_700_000_000,
+        expires_at: 1_700_000_900,
+        status: 0,
+        synced_at: Utc::now(),
+        escrow_tx_hash: None,
+        settlement_tx_hash: None,
+        settlement_block: None,
+        confirmations: 0,
+        finalized_at: None,
+        token: None,
+        pdf_file: None,
+        pdf_filename: None,
+        pdf_uploaded_at: None,
+        proof_user_public_values: None,
+        proof_accumulator: None,
+        proof_data: None,
+        axiom_proof_id: None,
+        proof_generated_at: None,
+        proof_json: None,
+        proof_format: None,
+    }
+}
+
+fn assert_constraint(result: Result<(), DbError>, expected: &str) {
+    match result {
+        Err(DbError::ConstraintViolation { constraint, .. }) => assert_eq!(constraint, expected),
+        other => panic!("expected violation of {}, got {:?}", expected, other),
+    }
+}
+
+#[tokio::test]
+async fn test_remaining_amount_bounds_are_enforced() {
+    let pool = setup_migrated_pool().await;
+    let orders = PostgresOrderRepository::new(pool);
+    let order = test_order();
+    orders.create(&order).await.unwrap();
+
+    assert_constraint(
+        orders.adjust_remaining_amount(&order.order_id, "-1000001").await,
+        "orders_remainingAmount_nonnegative",
+    );
+    assert_constraint(
+        orders.adjust_remaining_amount(&order.order_id, "1").await,
+        "orders_remainingAmount_lte_totalAmount",
+    );
+    assert!(orders.adjust_remaining_amount(&order.order_id, "-1000000").await.is_ok());
+}
+
+#[tokio::test]
+async fn test_trade_invariants_are_enforced() {
+    let pool = setup_migrated_pool().await;
+    let orders = PostgresOrderRepository::new(pool.clone());
+    let trades = PostgresTradeRepository::new(pool);
+    let order = test_order();
+    orders.create(&order).await.unwrap();
+
+    let mut trade = test_trade(&order.order_id);
+    trade.expires_at = trade.created_at;
+    assert_constraint(trades.create(&trade).await, "trades_expiresAt_after_createdAt");
+
+    // Also caught by trades_paymentNonce_format (004) - whichever Postgres checks first
+    let mut trade = test_trade(&order.order_id);
+    trade.payment_nonce = String::new();
+    assert!(matches!(
+        trades.create(&trade).await,
+        Err(DbError::ConstraintViolation { constraint, .. }) if constraint.starts_with("trades_paymentNonce_")
+    ));
+
+    let mut trade = test_trade(&order.order_id);
+    trade.status = 3;
+    assert_constraint(trades.create(&trade).await, "trades_status_valid");
+
+    // SETTLED is terminal
+    let trade = test_trade(&order.order_id);
+    trades.create(&trade).await.unwrap();
+    trades.update_status(&trade.trade_id, 1).await.unwrap();
+    assert_constraint(trades.update_status(&trade.trade_id, 2).await, "trades_status_transition");
+}
+
+// ============================================================================
+// Relayer Spend Tests (migrations/010_relayer_spend.sql)
+// ============================================================================
+
+use zkalipay_orderbook::db::relayer_spend::{
+    NewRelayerSpend, PostgresRelayerSpendRepository, RelayerSpendRepository, ACTION_CANCEL_EXPIRED, ACTION_FILL_ORDER,
+};
+
+fn test_spend(action: &'static str, trade_id: &str, order_id: Option<&str>, gas_used: u64, reverted: bool) -> NewRelayerSpend {
+    NewRelayerSpend {
+        tx_hash: random_bytes32(),
+        action,
+        trade_id: Some(trade_id.to_string()),
+        order_id: order_id.map(String::from),
+        relayer: "0x4444444444444444444444444444444444444444".to_string(),
+        gas_used: gas_used.to_string(),
+        effective_gas_price: "1000000000".to_string(),
+        cost_wei: (gas_used as u128 * 1_000_000_000).to_string(),
+        reverted,
+        block_number: Some(1),
+    }
+}
+
+#[tokio::test]
+async fn test_relayer_spend_is_attributed_per_trade() {
+    let pool = setup_migrated_pool().await;
+    let order = test_order();
+    PostgresOrderRepository::new(pool.clone()).create(&order).await.unwrap();
+    let trade = test_trade(&order.order_id);
+    PostgresTradeRepository::new(pool.clone()).create(&trade).await.unwrap();
+
+    let repo = PostgresRelayerSpendRepository::new(pool);
+    let since = Utc::now() - chrono::Duration::minutes(1);
+
+    let fill = test_spend(ACTION_FILL_ORDER, &trade.trade_id, Some(&order.order_id), 150_000, false);
+    repo.create(&fill).await.unwrap();
+    repo.create(&fill).await.unwrap(); // same tx hash - ignored
+    repo.create(&test_spend(ACTION_CANCEL_EXPIRED, &trade.trade_id, None, 50_000, true)).await.unwrap();
+
+    let totals = repo.totals_by_trade(since, 500).await.unwrap();
+    let ours = totals.iter().find(|t| t.trade_id == trade.trade_id).unwrap();
+    assert_eq!(ours.tx_count, 2);
+    assert_eq!(ours.gas_used, "200000");
+    assert_eq!(ours.cost_wei, "200000000000000");
+    assert_eq!(ours.order_id.as_deref(), Some(order.order_id.as_str()));
+
+    let by_action = repo.totals_by_action(since).await.unwrap();
+    let cancels = by_action.iter().find(|a| a.action == ACTION_CANCEL_EXPIRED).unwrap();
+    assert!(cancels.reverted_count >= 1);
+    assert!(repo.trade_count(since).await.unwrap() >= 1);
+}
+
+// ============================================================================
+// Fee Accrual Tests (migrations/011_fee_accruals.sql)
+// ============================================================================
+
+use zkalipay_orderbook::fees::{accrue_fee, FeeEngine, FeePayer, FeeSchedule};
+
+#[tokio::test]
+async fn test_fee_accrues_once_per_settled_trade() {
+    let pool = setup_migrated_pool().await;
+    let mut order = test_order();
+    order.seller = ethers::types::Address::random().into();
+    PostgresOrderRepository::new(pool.clone()).create(&order).await.unwrap();
+    let trades = PostgresTradeRepository::new(pool.clone());
+    let trade = test_trade(&order.order_id);
+    trades.create(&trade).await.unwrap();
+
+    let engine = FeeEngine::new(FeeSchedule::Bps(100), FeePayer::Seller).unwrap();
+    assert!(accrue_fee(&pool, &engine, &trade.trade_id).await.is_err()); // still pending
+
+    trades.update_status(&trade.trade_id, 1).await.unwrap();
+    accrue_fee(&pool, &engine, &trade.trade_id).await.unwrap();
+
+    // Replayed TradeSettled under a different schedule keeps the first accrual
+    let flat = FeeEngine::new(FeeSchedule::FlatCny(100), FeePayer::Seller).unwrap();
+    accrue_fee(&pool, &flat, &trade.trade_id).await.unwrap();
+
+    let db = Database::new(&test_database_url()).await.unwrap();
+    let (from, to) = (Utc::now() - chrono::Duration::minutes(1), Utc::now() + chrono::Duration::minutes(1));
+    let accruals = db.get_fee_accruals(&order.seller, from, to).await.unwrap();
+    assert_eq!(accruals.len(), 1);
+    assert_eq!(accruals[0].token_fee, "10");
+    assert_eq!(accruals[0].cny_fee, "7");
+    assert_eq!(accruals[0].schedule, "bps:100");
+
+    let totals = db.get_fee_totals(from, to, 1000).await.unwrap();
+    let ours = totals.iter().find(|t| t.payer == order.seller).unwrap();
+    assert_eq!((ours.trade_count, ours.payer_role.as_str()), (1, "seller"));
+}
+
+// ============================================================================
+// Settlement Job Tests (migrations/012_settlement_jobs.sql)
+// ============================================================================
+
+use zkalipay_orderbook::db::settlement_jobs::{PostgresSettlementJobRepository, SettlementJobRepository};
+
+#[tokio::test]
+async fn test_settlement_job_runs_once_and_resumes_at_failed_step() {
+    let pool = setup_migrated_pool().await;
+    let order = test_order();
+    PostgresOrderRepository::new(pool.clone()).create(&order).await.unwrap();
+    let trade = test_trade(&order.order_id);
+    PostgresTradeRepository::new(pool.clone()).create(&trade).await.unwrap();
+
+    let jobs = PostgresSettlementJobRepository::new(pool.clone());
+    let (owner, other) = (uuid::Uuid::new_v4(), uuid::Uuid::new_v4());
+    let (job, created) = jobs.create_or_get_running(&trade.trade_id, owner).await.unwrap();
+    assert!(created);
+    assert_eq!((job.status.as_str(), job.step.as_str()), ("running", "validate"));
+
+    // A second request while running gets the same job
+    let (again, created) = jobs.create_or_get_running(&trade.trade_id, other).await.unwrap();
+    assert!(!created);
+    assert_eq!(again.job_id, job.job_id);
+
+    // Leased to the process that started it: nobody else claims it until the lease lapses
+    assert!(!jobs.claim_stale(other).await.unwrap().iter().any(|j| j.job_id == job.job_id));
+    assert!(jobs.renew_lease(job.job_id, owner).await.unwrap());
+    assert!(!jobs.renew_lease(job.job_id, other).await.unwrap());
+    sqlx::query(r#"UPDATE settlement_jobs SET "leaseExpiresAt" = NOW() - INTERVAL '1 second' WHERE "jobId" = $1"#)
+        .bind(job.job_id)
+        .execute(&pool)
+        .await
+        .unwrap();
+    assert!(jobs.claim_stale(other).await.unwrap().iter().any(|j| j.job_id == job.job_id));
+    assert!(!jobs.claim_stale(owner).await.unwrap().iter().any(|j| j.job_id == job.job_id));
+    assert!(!jobs.renew_lease(job.job_id, owner).await.unwrap());
+
+    jobs.set_step(job.job_id, "prove").await.unwrap();
+    jobs.fail(job.job_id, "Axiom unavailable").await.unwrap();
+    assert!(jobs.resume(job.job_id, owner).await.unwrap());
+    assert!(!jobs.resume(job.job_id, other).await.unwrap()); // already running
+    assert!(jobs.renew_lease(job.job_id, owner).await.unwrap());
+
+    let resumed = jobs.get(job.job_id).await.unwrap().unwrap();
+    assert_eq!((resumed.status.as_str(), resumed.step.as_str(), resumed.attempts), ("running", "prove", 2));
+    assert!(resumed.error.is_none());
+
+    jobs.complete(job.job_id, &random_bytes32()).await.unwrap();
+    let latest = jobs.latest_for_trade(&trade.trade_id).await.unwrap().unwrap();
+    assert_eq!((latest.status.as_str(), latest.step.as_str()), ("completed", "done"));
+    assert!(!jobs.renew_lease(job.job_id, owner).await.unwrap());
+}
+
+#[tokio::test]
+async fn test_leader_lock_is_exclusive_until_released() {
+    use zkalipay_orderbook::db::leader::LeaderLock;
+
+    let pool = setup_migrated_pool().await;
+    // Random key so parallel test runs don't contend with each other or a running service
+    let key = rand::random::<u32>() as i64;
+
+    let mut leader = LeaderLock::try_acquire(&pool, "test", key).await.unwrap().expect("first instance leads");
+    assert!(leader.is_held().await);
+    assert!(LeaderLock::try_acquire(&pool, "test", key).await.unwrap().is_none());
+
+    leader.release().await;
+    let mut standby = LeaderLock::try_acquire(&pool, "test", key).await.unwrap().expect("standby takes over");
+    assert!(standby.is_held().await);
+}
+
+// ============================================================================
+// Token Status Tests (migrations/015_token_status.sql)
+// ============================================================================
+
+use zkalipay_orderbook::db::token_status::{TokenPolicy, TOKEN_DENIED};
+
+#[tokio::test]
+async fn test_denylisted_token_leaves_active_listing() {
+    let pool = setup_migrated_pool().await;
+    let db = Database::new(&test_database_url()).await.unwrap();
+    let mut order = test_order();
+    order.token = ethers::types::Address::random().into();
+    order.exchange_rate = "1".to_string(); // sorts first
+    PostgresOrderRepository::new(pool.clone()).create(&order).await.unwrap();
+
+    let listed = |orders: Vec<DbOrder>| orders.iter().any(|o| o.order_id == order.order_id);
+    assert!(listed(db.get_active_orders(Some(100), &db.get_token_policy().await.unwrap()).await.unwrap()));
+
+    db.set_token_status(&order.token, TOKEN_DENIED, Some("delisted"), None).await.unwrap();
+    let policy = db.get_token_policy().await.unwrap();
+    assert!(policy.is_suspended(&order.token));
+    assert!(!listed(db.get_active_orders(Some(100), &policy).await.unwrap()));
+    assert!(listed(db.get_active_orders(Some(100), &TokenPolicy::default()).await.unwrap()));
+
+    assert!(db.remove_token_status(&order.token).await.unwrap());
+    assert!(!db.remove_token_status(&order.token).await.unwrap());
+}
+
+// ============================================================================
+// Order Feed Tests (migrations/018_order_feed.sql)
+// ============================================================================
+
+use zkalipay_orderbook::db::order_feed::{OrderFeedRepository, PostgresOrderFeedRepository, FEED_CREATED, FEED_UPDATED};
+
+#[tokio::test]
+async fn test_order_changes_are_logged_in_sequence() {
+    let pool = setup_migrated_pool().await;
+    let feed = PostgresOrderFeedRepository::new(pool.clone());
+    let orders = PostgresOrderRepository::new(pool.clone());
+    let start = feed.head().await.unwrap();
+
+    let order = test_order();
+    orders.create(&order).await.unwrap();
+    orders.adjust_remaining_amount(&order.order_id, "-400000").await.unwrap();
+
+    let ours: Vec<_> = feed
+        .since(start, 10_000)
+        .await
+        .unwrap()
+        .into_iter()
+        .filter(|e| e.order.order_id == order.order_id)
+        .collect();
+    assert_eq!(ours.len(), 2);
+    assert_eq!((ours[0].kind.as_str(), ours[1].kind.as_str()), (FEED_CREATED, FEED_UPDATED));
+    assert!(ours[0].seq < ours[1].seq);
+    assert_eq!(ours[1].order.remaining_amount, "600000");
+
+    let (seq, snapshot) = feed.snapshot().await.unwrap();
+    assert!(seq >= ours[1].seq);
+    assert!(snapshot.iter().any(|o| o.order_id == order.order_id && o.remaining_amount == "600000"));
+}
+
+// ============================================================================
+// Blob Streaming Tests
+// ============================================================================
+
+use futures::TryStreamExt;
+use zkalipay_orderbook::db::blobs::{TradeBlob, BLOB_CHUNK_SIZE};
+
+#[tokio::test]
+async fn test_pdf_streams_back_in_chunks() {
+    let pool = setup_migrated_pool().await;
+    let order = test_order();
+    PostgresOrderRepository::new(pool.clone()).create(&order).await.unwrap();
+    let trade = test_trade(&order.order_id);
+    let trades = PostgresTradeRepository::new(pool.clone());
+    trades.create(&trade).await.unwrap();
+
+    let db = Database::new(&test_database_url()).await.unwrap();
+    assert!(db.get_trade_blob_info(&trade.trade_id, TradeBlob::Pdf).await.unwrap().is_none());
+
+    let pdf: Vec<u8> = (0..BLOB_CHUNK_SIZE * 2 + 17).map(|i| (i % 251) as u8).collect();
+    trades.save_pdf(&trade.trade_id, &pdf, "payment.pdf").await.unwrap();
+
+    let info = db.get_trade_blob_info(&trade.trade_id, TradeBlob::Pdf).await.unwrap().unwrap();
+    assert_eq!(info.len, pdf.len() as i64);
+    let chunks: Vec<Vec<u8>> = db.stream_trade_blob(&trade.trade_id, TradeBlob::Pdf, info).try_collect().await.unwrap();
+    assert_eq!(chunks.len(), 3);
+    assert_eq!(chunks.concat(), pdf);
+}
+
+// ============================================================================
+// Order Book Snapshot Tests (migrations/019_orderbook_snapshots.sql)
+// ============================================================================
+
+use zkalipay_orderbook::db::orderbook_snapshots::{OrderbookSnapshotRepository, PostgresOrderbookSnapshotRepository};
+
+#[tokio::test]
+async fn test_orderbook_snapshot_aggregates_per_token() {
+    let pool = setup_migrated_pool().await;
+    let orders = PostgresOrderRepository::new(pool.clone());
+    let snapshots = PostgresOrderbookSnapshotRepository::new(pool.clone());
+    let token: zkalipay_orderbook::blockchain::address::EthAddress = ethers::types::Address::random().into();
+
+    for (rate, amount) in [("700", "1000000"), ("800", "3000000")] {
+        let mut order = test_order();
+        order.token = token.clone();
+        order.exchange_rate = rate.to_string();
+        order.total_amount = amount.to_string();
+        order.remaining_amount = amount.to_string();
+        orders.create(&order).await.unwrap();
+    }
+
+    // A bucket of its own, far from any the scheduler writes
+    let at = Utc::now() - chrono::Duration::days(3650) + chrono::Duration::seconds(rand::random::<u32>() as i64);
+    let bucket = zkalipay_orderbook::orderbook_history::bucket_start(at, 1);
+    snapshots.record(bucket).await.unwrap();
+    // Re-recording the same bucket overwrites rather than duplicating
+    snapshots.record(bucket).await.unwrap();
+
+    let until = bucket + chrono::Duration::seconds(1);
+    let history = snapshots.history(Some(&token), bucket, until, 10).await.unwrap();
+    assert_eq!(history.len(), 1);
+    let snapshot = &history[0];
+    assert_eq!(snapshot.order_count, 2);
+    assert_eq!(snapshot.total_liquidity, "4000000");
+    assert_eq!((snapshot.best_rate.as_str(), snapshot.worst_rate.as_str()), ("700", "800"));
+    assert_eq!(snapshot.weighted_rate, "775");
+}
+
+// ============================================================================
+// Trade Support Tests (migrations/020_trade_support.sql)
+// ============================================================================
+
+use zkalipay_orderbook::db::trade_support::{PostgresTradeSupportRepository, TradeSupportRepository};
+
+#[tokio::test]
+async fn test_trade_support_notes_and_tags() {
+    let pool = setup_migrated_pool().await;
+    let order = test_order();
+    PostgresOrderRepository::new(pool.clone()).create(&order).await.unwrap();
+    let trade = test_trade(&order.order_id);
+    PostgresTradeRepository::new(pool.clone()).create(&trade).await.unwrap();
+    let support = PostgresTradeSupportRepository::new(pool.clone());
+
+    assert!(support.get(&trade.trade_id).await.unwrap().is_none());
+    let tag = format!("test-{}", random_nonce());
+    support.set(&trade.trade_id, Some("@buyer"), &[tag.clone()], Some("ops")).await.unwrap();
+    let updated = support.set(&trade.trade_id, None, &[tag.clone(), "vip".to_string()], Some("ops")).await.unwrap();
+    assert_eq!(updated.buyer_contact, None);
+    assert_eq!(updated.tags.len(), 2);
+
+    let tagged = support.with_tag(&tag, 10).await.unwrap();
+    assert_eq!(tagged.len(), 1);
+    assert_eq!(tagged[0].trade_id, trade.trade_id);
+
+    support.add_note(&trade.trade_id, "buyer says bank transfer delayed", Some("ops")).await.unwrap();
+    support.add_note(&trade.trade_id, "transfer arrived", None).await.unwrap();
+    let notes = support.notes(&trade.trade_id).await.unwrap();
+    assert_eq!(notes.iter().map(|n| n.note.as_str()).collect::<Vec<_>>(), ["buyer says bank transfer delayed", "transfer arrived"]);
+
+    // Unknown trades are refused rather than getting orphaned metadata
+    let unknown = random_bytes32();
+    assert!(matches!(support.add_note(&unknown, "note", None).await, Err(DbError::TradeNotFound(_))));
+}
+
+// ============================================================================
+// Proof Submission Claim Tests (migrations/021_proof_submissions.sql)
+// ============================================================================
+
+use zkalipay_orderbook::db::proof_submissions::{
+    ProofSubmissionRepository, PostgresProofSubmissionRepository, SUBMISSION_FAILED, SUBMISSION_SUBMITTED,
+};
+
+#[tokio::test]
+async fn test_one_proof_submission_in_flight_per_trade() {
+    let pool = setup_migrated_pool().await;
+    let order = test_order();
+    PostgresOrderRepository::new(pool.clone()).create(&order).await.unwrap();
+    let trade = test_trade(&order.order_id);
+    PostgresTradeRepository::new(pool.clone()).create(&trade).await.unwrap();
+    let submissions = PostgresProofSubmissionRepository::new(pool.clone());
+    let tx_hash = random_bytes32();
+
+    // Concurrent claims: exactly one wins
+    let (a, b) = tokio::join!(submissions.claim(&trade.trade_id, 600), submissions.claim(&trade.trade_id, 600));
+    assert_eq!([a.unwrap().is_ok(), b.unwrap().is_ok()].iter().filter(|won| **won).count(), 1);
+
+    submissions.set_tx_hash(&trade.trade_id, &tx_hash).await.unwrap();
+    let holder = submissions.claim(&trade.trade_id, 600).await.unwrap().unwrap_err();
+    assert_eq!(holder.tx_hash.as_deref(), Some(tx_hash.as_str()));
+
+    // A failed attempt can be retried, a stale claim taken over
+    submissions.finish(&trade.trade_id, SUBMISSION_FAILED, None).await.unwrap();
+    assert!(submissions.claim(&trade.trade_id, 600).await.unwrap().is_ok());
+    assert!(submissions.claim(&trade.trade_id, 0).await.unwrap().is_ok());
+    assert_eq!(submissions.get(&trade.trade_id).await.unwrap().unwrap().attempts, 3);
+
+    // A confirmed submission is never claimed again
+    submissions.finish(&trade.trade_id, SUBMISSION_SUBMITTED, Some(&tx_hash)).await.unwrap();
+    assert!(submissions.claim(&trade.trade_id, 0).await.unwrap().is_err());
+}
+
+// ============================================================================
+// Settlement Confirmation Tests (migrations/022_settlement_confirmations.sql)
+// ============================================================================
+
+use zkalipay_orderbook::db::confirmations::{ConfirmationRepository, PostgresConfirmationRepository};
+
+#[tokio::test]
+async fn test_settlement_finalized_once() {
+    let pool = setup_migrated_pool().await;
+    let order = test_order();
+    PostgresOrderRepository::new(pool.clone()).create(&order).await.unwrap();
+    let trade = test_trade(&order.order_id);
+    let trades = PostgresTradeRepository::new(pool.clone());
+    trades.create(&trade).await.unwrap();
+    trades.update_settlement_tx(&trade.trade_id, &random_bytes32()).await.unwrap();
+    trades.update_status(&trade.trade_id, 1).await.unwrap();
+    let confirmations = PostgresConfirmationRepository::new(pool.clone());
+
+    confirmations.update(&trade.trade_id, Some(100), 3).await.unwrap();
+    let pending = confirmations.list_unfinalized(1000).await.unwrap();
+    let settlement = pending.iter().find(|s| s.trade_id == trade.trade_id).unwrap();
+    assert_eq!((settlement.settlement_block, settlement.confirmations), (Some(100), 3));
+
+    // Only the first finalize wins; final trades leave the list and stop updating
+    assert!(confirmations.finalize(&trade.trade_id, 100, 12).await.unwrap());
+    assert!(!confirmations.finalize(&trade.trade_id, 100, 13).await.unwrap());
+    let pending = confirmations.list_unfinalized(1000).await.unwrap();
+    assert!(pending.iter().all(|s| s.trade_id != trade.trade_id));
+
+    confirmations.update(&trade.trade_id, None, 0).await.unwrap();
+    let stored = trades.get(&trade.trade_id).await.unwrap();
+    assert_eq!((stored.settlement_block, stored.confirmations), (Some(100), 12));
+    assert!(stored.finalized_at.is_some());
+}
+
+// ============================================================================
+// Order Intent Tests (migrations/023_order_intents.sql)
+// ============================================================================
+
+use rust_decimal::Decimal;
+use zkalipay_orderbook::db::order_intents::{NewOrderIntent, OrderIntentRepository, PostgresOrderIntentRepository};
+
+#[tokio::test]
+async fn test_order_intent_lifecycle() {
+    let pool = setup_migrated_pool().await;
+    let order = test_order();
+    PostgresOrderRepository::new(pool.clone()).create(&order).await.unwrap();
+    let intents = PostgresOrderIntentRepository::new(pool.clone());
+    let now = Utc::now().timestamp();
+    let alipay_hash = random_bytes32();
+    // Seller nonces are unique, and the test database is shared between runs
+    let base_nonce = rand::random::<u32>() as u64;
+
+    let new_intent = |nonce: u64| NewOrderIntent {
+        intent_hash: random_bytes32(),
+        seller: order.seller.clone(),
+        token: order.token.clone(),
+        amount: order.total_amount.parse().unwrap(),
+        exchange_rate: order.exchange_rate.parse().unwrap(),
+        alipay_hash: alipay_hash.clone(),
+        nonce: Decimal::from(base_nonce + nonce),
+        deadline: now + 3600,
+        signature: format!("0x{}", "11".repeat(65)),
+    };
+
+    let first = intents.create(&new_intent(1)).await.unwrap();
+    assert_eq!(first.status, "open");
+    // A nonce can't be reused by the same seller
+    assert!(matches!(intents.create(&new_intent(1)).await, Err(DbError::InvalidInput(_))));
+
+    let second = intents.create(&new_intent(2)).await.unwrap();
+    assert!(intents.cancel(&second.intent_hash).await.unwrap());
+    assert!(!intents.cancel(&second.intent_hash).await.unwrap());
+
+    let open = intents.list_open(Some(&order.token), now, 1000).await.unwrap();
+    assert!(open.iter().any(|i| i.intent_hash == first.intent_hash));
+    assert!(open.iter().all(|i| i.intent_hash != second.intent_hash));
+
+    // Funding links the order to the open intent with the same terms, once
+    let amount: Decimal = order.total_amount.parse().unwrap();
+    let rate: Decimal = order.exchange_rate.parse().unwrap();
+    let funded = intents
+        .mark_funded(&order.seller, &order.token, amount, rate, &alipay_hash, &order.order_id, now)
+        .await
+        .unwrap();
+    assert_eq!(funded, Some(first.intent_hash.clone()));
+    let funded = intents
+        .mark_funded(&order.seller, &order.token, amount, rate, &alipay_hash, &order.order_id, now)
+        .await
+        .unwrap();
+    assert_eq!(funded, None);
+
+    let funded = intents.get(&first.intent_hash).await.unwrap().unwrap();
+    assert_eq!((funded.status.as_str(), funded.order_id.as_deref()), ("funded", Some(order.order_id.as_str())));
+    assert!(!intents.cancel(&first.intent_hash).await.unwrap());
+}
+
+// ============================================================================
+// Order Batch Tests (bulk create/withdraw applied atomically)
+// ============================================================================
+
+use zkalipay_orderbook::db::orders::OrderChange;
+
+#[tokio::test]
+async fn test_order_batch_is_atomic() {
+    let pool = setup_migrated_pool().await;
+    let orders = PostgresOrderRepository::new(pool.clone());
+    let old = test_order();
+    orders.create(&old).await.unwrap();
+
+    // A reprice: withdraw the old order, create its replacement
+    let new = test_order();
+    orders
+        .apply_batch(&[
+            OrderChange::Withdraw { order_id: old.order_id.clone(), amount: old.total_amount.clone() },
+            OrderChange::Create(new.clone()),
+        ])
+        .await
+        .unwrap();
+    let db = Database::new(&test_database_url()).await.unwrap();
+    assert_eq!(db.get_order(&old.order_id).await.unwrap().remaining_amount, "0");
+    assert_eq!(db.get_order(&new.order_id).await.unwrap().remaining_amount, new.total_amount);
+
+    // One failing change rolls the whole batch back
+    let orphan = test_order();
+    let result = orders
+        .apply_batch(&[
+            OrderChange::Create(orphan.clone()),
+            OrderChange::Withdraw { order_id: random_bytes32(), amount: "1".to_string() },
+        ])
+        .await;
+    assert!(matches!(result, Err(DbError::OrderNotFound(_))));
+    assert!(db.get_order(&orphan.order_id).await.is_err());
+}
+
+// ============================================================================
+// Settlement Anomaly Tests (per-template failure stats and flags)
+// ============================================================================
+
+use zkalipay_orderbook::db::anomalies::{AnomalyRepository, PostgresAnomalyRepository, OUTCOME_MISMATCH, OUTCOME_OK, STAGE_VALIDATION};
+
+#[tokio::test]
+async fn test_template_flag_lifecycle() {
+    let pool = setup_migrated_pool().await;
+    let anomalies = PostgresAnomalyRepository::new(pool);
+    let template = format!("tpl_{}", &random_bytes32()[2..14]);
+    let trade_id = random_bytes32();
+    let seller = test_order().seller;
+
+    anomalies.record(&trade_id, &seller, &template, STAGE_VALIDATION, OUTCOME_MISMATCH).await.unwrap();
+    anomalies.record(&random_bytes32(), &seller, &template, STAGE_VALIDATION, OUTCOME_OK).await.unwrap();
+    assert_eq!(anomalies.template_for_trade(&trade_id).await.unwrap(), Some(template.clone()));
+
+    let since = chrono::Utc::now() - chrono::Duration::hours(1);
+    let stats = anomalies.template_stats(since).await.unwrap();
+    let stat = stats.iter().find(|s| s.key == template).unwrap();
+    assert_eq!((stat.samples, stat.mismatches, stat.proof_failures), (2, 1, 0));
+
+    // Flagged once while active
+    assert!(anomalies.flag(&template, 0.5, 2, since).await.unwrap().is_some());
+    assert!(anomalies.flag(&template, 0.5, 2, since).await.unwrap().is_none());
+    assert!(anomalies.active_flags().await.unwrap().iter().any(|f| f.template == template));
+
+    // A clear acknowledges the window's failures; later windows can flag again
+    assert!(anomalies.clear(&template, Some("ops")).await.unwrap());
+    assert!(!anomalies.clear(&template, Some("ops")).await.unwrap());
+    assert!(anomalies.flag(&template, 0.5, 2, since).await.unwrap().is_none());
+    let later = chrono::Utc::now() + chrono::Duration::seconds(1);
+    assert!(anomalies.flag(&template, 0.5, 2, later).await.unwrap().is_some());
+}
+
+// ============================================================================
+// Payment Window Tier Tests
+// ============================================================================
+
+use zkalipay_orderbook::db::payment_windows::{PaymentWindowRepository, PostgresPaymentWindowRepository};
+
+#[tokio::test]
+async fn test_payment_window_tier_upsert() {
+    let pool = setup_migrated_pool().await;
+    let tiers = PostgresPaymentWindowRepository::new(pool);
+    let token = test_order().token;
+    let min_value_cny = rand::random::<u32>() as i64;
+
+    // Any-token and token tiers at the same value are distinct; setting one again replaces it
+    tiers.set(None, min_value_cny, 1800, Some("ops")).await.unwrap();
+    tiers.set(Some(&token), min_value_cny, 2400, None).await.unwrap();
+    let updated = tiers.set(None, min_value_cny, 3600, Some("ops")).await.unwrap();
+    assert_eq!((updated.token, updated.window_secs), (None, 3600));
+
+    let listed = tiers.list().await.unwrap();
+    assert_eq!(listed.iter().filter(|t| t.min_value_cny == min_value_cny).count(), 2);
+
+    assert!(tiers.remove(None, min_value_cny).await.unwrap());
+    assert!(!tiers.remove(None, min_value_cny).await.unwrap());
+    assert!(tiers.remove(Some(&token), min_value_cny).await.unwrap());
+}
+
+// ============================================================================
+// Trade Message Tests (migrations/026_trade_messages.sql)
+// ============================================================================
+
+use zkalipay_orderbook::db::trade_messages::{
+    NewTradeMessage, PostgresTradeMessageRepository, TradeMessageRepository, ROLE_BUYER, ROLE_SELLER,
+};
+
+#[tokio::test]
+async fn test_trade_messages_with_attachment() {
+    let pool = setup_migrated_pool().await;
+    let order = test_order();
+    PostgresOrderRepository::new(pool.clone()).create(&order).await.unwrap();
+    let trade = test_trade(&order.order_id);
+    PostgresTradeRepository::new(pool.clone()).create(&trade).await.unwrap();
+    let messages = PostgresTradeMessageRepository::new(pool);
+
+    let message = |sender: &zkalipay_orderbook::blockchain::address::EthAddress, role, body: &str, attachment| NewTradeMessage {
+        trade_id: trade.trade_id.clone(),
+        sender: sender.clone(),
+        role,
+        body: body.to_string(),
+        attachment,
+    };
+    let first = messages.create(&message(&trade.buyer, ROLE_BUYER, "typed the nonce wrong, paid 735.00", None)).await.unwrap();
+    let screenshot = b"%PDF-1.4 receipt".to_vec();
+    let second = messages
+        .create(&message(&order.seller, ROLE_SELLER, "", Some((Some("receipt.pdf".to_string()), "application/pdf", screenshot.clone()))))
+        .await
+        .unwrap();
+    assert_eq!(second.attachment_size, Some(screenshot.len() as i32));
+
+    let listed = messages.list(&trade.trade_id, 0, 100).await.unwrap();
+    assert_eq!(listed.iter().map(|m| m.id).collect::<Vec<_>>(), [first.id, second.id]);
+    assert_eq!(messages.list(&trade.trade_id, first.id, 100).await.unwrap().len(), 1);
+    assert_eq!(messages.count(&trade.trade_id).await.unwrap(), 2);
+
+    let attachment = messages.attachment(&trade.trade_id, second.id).await.unwrap().unwrap();
+    assert_eq!((attachment.content_type.as_str(), attachment.data), ("application/pdf", screenshot));
+    assert!(messages.attachment(&trade.trade_id, first.id).await.unwrap().is_none());
+
+    // Empty messages and unknown trades are refused
+    assert!(messages.create(&message(&trade.buyer, ROLE_BUYER, "", None)).await.is_err());
+    let mut orphan = message(&trade.buyer, ROLE_BUYER, "hello", None);
+    orphan.trade_id = random_bytes32();
+    assert!(matches!(messages.create(&orphan).await, Err(DbError::TradeNotFound(_))));
+}
+
+// ============================================================================
+// Dead Letter Tests (migrations/027_dead_letter_events.sql)
+// ============================================================================
+
+use zkalipay_orderbook::db::dead_letters::{
+    DeadLetterRepository, NewDeadLetter, PostgresDeadLetterRepository, DEAD_LETTER_PENDING, DEAD_LETTER_REPROCESSED,
+};
+
+#[tokio::test]
+async fn test_dead_letter_replay_lifecycle() {
+    let pool = setup_migrated_pool().await;
+    let dead_letters = PostgresDeadLetterRepository::new(pool);
+    let failed = NewDeadLetter {
+        event_type: "TradeSettled".to_string(),
+        block_number: 1234,
+        tx_hash: random_bytes32(),
+        log_index: 3,
+        logs: "[]".to_string(),
+        error: "Database error: connection reset".to_string(),
+    };
+
+    // Failing again on the same log is one entry with more attempts
+    let first = dead_letters.record(&failed).await.unwrap();
+    let again = dead_letters.record(&NewDeadLetter { error: "Database error: timeout".to_string(), ..failed.clone() }).await.unwrap();
+    assert_eq!((again.id, again.attempts, again.error.as_str()), (first.id, 2, "Database error: timeout"));
+
+    // Only pending entries can be queued; a failed replay makes it pending again
+    assert!(dead_letters.queue(first.id, Some("ops")).await.unwrap());
+    assert!(!dead_letters.queue(first.id, Some("ops")).await.unwrap());
+    assert!(dead_letters.queued(1000).await.unwrap().iter().any(|d| d.id == first.id));
+    dead_letters.finish_replay(first.id, Some("still failing")).await.unwrap();
+    let entry = dead_letters.get(first.id).await.unwrap().unwrap();
+    assert_eq!((entry.status.as_str(), entry.attempts), (DEAD_LETTER_PENDING, 3));
+
+    assert!(dead_letters.queue(first.id, Some("ops")).await.unwrap());
+    dead_letters.finish_replay(first.id, None).await.unwrap();
+    let entry = dead_letters.get(first.id).await.unwrap().unwrap();
+    assert_eq!((entry.status.as_str(), entry.error.as_str()), (DEAD_LETTER_REPROCESSED, "still failing"));
+    assert!(!dead_letters.discard(first.id, None).await.unwrap());
+}
+
+// ============================================================================
+// Order Priority Tests
+// ============================================================================
+
+use zkalipay_orderbook::db::orders::OrderPriority;
+
+#[tokio::test]
+async fn test_order_priority_within_price_level() {
+    let pool = setup_migrated_pool().await;
+    let db = Database::new(&test_database_url()).await.unwrap();
+    let orders = PostgresOrderRepository::new(pool.clone());
+    let trades = PostgresTradeRepository::new(pool.clone());
+    let token: zkalipay_orderbook::blockchain::address::EthAddress = ethers::types::Address::random().into();
+
+    // Same rate: an older small order from a seller whose trades expire, a newer large
+    // one from a seller whose trades settle
+    let mut unreliable = test_order();
+    unreliable.seller = ethers::types::Address::random().into();
+    unreliable.token = token.clone();
+    let mut reliable = test_order();
+    reliable.seller = ethers::types::Address::random().into();
+    reliable.token = token.clone();
+    reliable.created_at = unreliable.created_at + 60;
+    reliable.total_amount = "5000000".to_string();
+    reliable.remaining_amount = "5000000".to_string();
+    orders.create(&unreliable).await.unwrap();
+    orders.create(&reliable).await.unwrap();
+    for (order, status) in [(&unreliable, 2), (&unreliable, 2), (&reliable, 1)] {
+        let trade = test_trade(&order.order_id);
+        trades.create(&trade).await.unwrap();
+        trades.update_status(&trade.trade_id, status).await.unwrap();
+    }
+
+    let first = |priority| {
+        let db = &db;
+        let token = token.clone();
+        async move { db.get_active_orders_by_token(&token, Some(10), priority).await.unwrap()[0].order_id.clone() }
+    };
+    assert_eq!(first(OrderPriority::PriceTime).await, unreliable.order_id);
+    assert_eq!(first(OrderPriority::ProRata).await, unreliable.order_id);
+    assert_eq!(first(OrderPriority::PriceSize).await, reliable.order_id);
+    assert_eq!(first(OrderPriority::Reputation).await, reliable.order_id);
+}
+
+// ============================================================================
+// Alipay Blocklist Tests (migrations/028_alipay_blocklist.sql)
+// ============================================================================
+
+#[tokio::test]
+async fn test_alipay_blocklist_excludes_orders() {
+    let pool = setup_migrated_pool().await;
+    let db = Database::new(&test_database_url()).await.unwrap();
+    let orders = PostgresOrderRepository::new(pool.clone());
+    let token: zkalipay_orderbook::blockchain::address::EthAddress = ethers::types::Address::random().into();
+
+    let mut flagged = test_order();
+    flagged.token = token.clone();
+    flagged.alipay_id = format!("Fraud{}@Example.com", &random_bytes32()[2..10]);
+    flagged.alipay_id_format = "email".to_string();
+    let mut clean = test_order();
+    clean.token = token.clone();
+    orders.create(&flagged).await.unwrap();
+    orders.create(&clean).await.unwrap();
+
+    // Listed in another case and with stray whitespace: still the same account
+    let listed = db
+        .block_alipay_id(&format!("  {} ", flagged.alipay_id.to_uppercase()), Some("chargeback"), Some("ops"))
+        .await
+        .unwrap();
+    assert_eq!(listed.alipay_id, flagged.alipay_id.to_lowercase());
+    assert!(db.is_alipay_id_blocked(&flagged.alipay_id).await.unwrap());
+    assert!(!db.is_alipay_id_blocked(&clean.alipay_id).await.unwrap());
+
+    let active = db.get_active_orders_by_token(&token, Some(10), Default::default()).await.unwrap();
+    assert_eq!(active.iter().map(|o| o.order_id.clone()).collect::<Vec<_>>(), vec![clean.order_id.clone()]);
+
+    // Off the list again; both changes are audited
+    assert!(db.unblock_alipay_id(&flagged.alipay_id, Some("resolved"), Some("ops")).await.unwrap());
+    assert!(!db.unblock_alipay_id(&flagged.alipay_id, None, Some("ops")).await.unwrap());
+    assert_eq!(db.get_active_orders_by_token(&token, Some(10), Default::default()).await.unwrap().len(), 2);
+
+    let audit: Vec<_> = db
+        .get_alipay_blocklist_audit(1000)
+        .await
+        .unwrap()
+        .into_iter()
+        .filter(|change| change.alipay_id == listed.alipay_id)
+        .collect();
+    assert_eq!(audit.iter().map(|c| c.action.as_str()).collect::<Vec<_>>(), vec!["unblock", "block"]);
+    assert_eq!(audit[1].reason.as_deref(), Some("chargeback"));
+}
+
+// ============================================================================
+// Proof Invalidation Tests (migrations/029_trade_proof_archive.sql)
+// ============================================================================
+
+#[tokio::test]
+async fn test_pdf_reupload_clears_proof() {
+    let pool = setup_migrated_pool().await;
+    let order = test_order();
+    PostgresOrderRepository::new(pool.clone()).create(&order).await.unwrap();
+    let trade = test_trade(&order.order_id);
+    let trades = PostgresTradeRepository::new(pool.clone());
+    trades.create(&trade).await.unwrap();
+
+    trades.save_pdf(&trade.trade_id, b"%PDF-first", "first.pdf").await.unwrap();
+    let first_upload = trades.get(&trade.trade_id).await.unwrap().pdf_uploaded_at;
+    assert!(first_upload.is_some());
+    assert!(trades
+        .save_proof(&trade.trade_id, first_upload, &[1u8; 32], &[2u8; 384], &[3u8; 1376], "proof-1", "{}", "halo2-v1")
+        .await
+        .unwrap());
+    let proven = trades.get(&trade.trade_id).await.unwrap();
+    assert!(proven.proof_user_public_values.is_some());
+    assert_eq!(proven.proof_format.as_deref(), Some("halo2-v1"));
+
+    // A new PDF takes the proof of the old one off the trade
+    trades.save_pdf(&trade.trade_id, b"%PDF-second", "second.pdf").await.unwrap();
+    let reuploaded = trades.get(&trade.trade_id).await.unwrap();
+    assert!(reuploaded.proof_user_public_values.is_none());
+    assert!(reuploaded.proof_data.is_none());
+    assert!(reuploaded.axiom_proof_id.is_none());
+    assert!(reuploaded.proof_generated_at.is_none());
+    assert!(reuploaded.proof_format.is_none());
+
+    // A generation that read the first PDF can't store its proof any more
+    assert!(!trades
+        .save_proof(&trade.trade_id, first_upload, &[1u8; 32], &[2u8; 384], &[3u8; 1376], "proof-2", "{}", "halo2-v1")
+        .await
+        .unwrap());
+    assert!(trades
+        .save_proof(&trade.trade_id, reuploaded.pdf_uploaded_at, &[4u8; 32], &[5u8; 384], &[6u8; 1376], "proof-3", "{}", "halo2-v1")
+        .await
+        .unwrap());
+    assert_eq!(trades.get(&trade.trade_id).await.unwrap().axiom_proof_id.as_deref(), Some("proof-3"));
+
+    // Once settled, the PDF and its proof stay
+    trades.update_status(&trade.trade_id, 1).await.unwrap();
+    assert!(matches!(trades.save_pdf(&trade.trade_id, b"%PDF-third", "third.pdf").await, Err(DbError::InvalidInput(_))));
+    assert!(matches!(trades.save_pdf(&random_bytes32(), b"%PDF-third", "third.pdf").await, Err(DbError::TradeNotFound(_))));
+    let settled = trades.get(&trade.trade_id).await.unwrap();
+    assert_eq!(settled.pdf_filename.as_deref(), Some("second.pdf"));
+    assert_eq!(settled.axiom_proof_id.as_deref(), Some("proof-3"));
+}
+
+// ============================================================================
+// Lifecycle Funnel Tests
+// ============================================================================
+
+#[tokio::test]
+async fn test_lifecycle_funnel_counts_cohort_stages() {
+    let pool = setup_migrated_pool().await;
+    let db = Database::new(&test_database_url()).await.unwrap();
+    let orders = PostgresOrderRepository::new(pool.clone());
+    let trades = PostgresTradeRepository::new(pool.clone());
+    let token: zkalipay_orderbook::blockchain::address::EthAddress = ethers::types::Address::random().into();
+
+    let mut matched = test_order();
+    matched.token = token.clone();
+    let mut unmatched = test_order();
+    unmatched.token = token.clone();
+    let mut earlier = test_order();
+    earlier.token = token.clone();
+    earlier.created_at -= 86_400;
+    for order in [&matched, &unmatched, &earlier] {
+        orders.create(order).await.unwrap();
+    }
+
+    let proven = test_trade(&matched.order_id);
+    let uploaded = test_trade(&matched.order_id);
+    let abandoned = test_trade(&matched.order_id);
+    let outside = test_trade(&earlier.order_id);
+    for trade in [&proven, &uploaded, &abandoned, &outside] {
+        trades.create(trade).await.unwrap();
+    }
+    for trade in [&proven, &uploaded, &outside] {
+        trades.save_pdf(&trade.trade_id, b"%PDF-receipt", "receipt.pdf").await.unwrap();
+    }
+    let uploaded_at = trades.get(&proven.trade_id).await.unwrap().pdf_uploaded_at;
+    assert!(trades
+        .save_proof(&proven.trade_id, uploaded_at, &[1u8; 32], &[2u8; 384], &[3u8; 1376], "proof-1", "{}", "halo2-v1")
+        .await
+        .unwrap());
+
+    // The earlier order and its trade fall outside the period
+    let from = chrono::DateTime::from_timestamp(matched.created_at - 60, 0).unwrap();
+    let to = chrono::DateTime::from_timestamp(matched.created_at + 60, 0).unwrap();
+    let funnel = db.get_lifecycle_funnel(Some(&token), from, to).await.unwrap();
+    assert_eq!(funnel.orders_created, 2);
+    assert_eq!(funnel.orders_matched, 1);
+    assert_eq!(funnel.trades_filled, 3);
+    assert_eq!(funnel.pdf_uploaded, 2);
+    // No validation attempt is recorded, but a proof implies one passed
+    assert_eq!(funnel.validated, 1);
+    assert_eq!(funnel.proven, 1);
+    assert_eq!(funnel.settled, 0);
+}
+
+// ============================================================================
+// Spending Limit Tests (migrations/030_buyer_spending_limits.sql)
+// ============================================================================
+
+#[tokio::test]
+async fn test_buyer_volume_counts_recent_live_trades() {
+    let pool = setup_migrated_pool().await;
+    let db = Database::new(&test_database_url()).await.unwrap();
+    let order = test_order();
+    PostgresOrderRepository::new(pool.clone()).create(&order).await.unwrap();
+    let trades = PostgresTradeRepository::new(pool.clone());
+    let buyer: zkalipay_orderbook::blockchain::address::EthAddress = ethers::types::Address::random().into();
+    let now = Utc::now().timestamp();
+
+    // Today, last week, last quarter, and an expired one today
+    for (age_secs, cny_amount, status) in [(60, "10000", 0), (7 * 86_400, "20000", 1), (90 * 86_400, "40000", 1), (60, "80000", 2)] {
+        let mut trade = test_trade(&order.order_id);
+        trade.buyer = buyer.clone();
+        trade.cny_amount = cny_amount.to_string();
+        trade.created_at = now - age_secs;
+        trade.expires_at = trade.created_at + 900;
+        trade.status = status;
+        if status == 1 {
+            trade.settlement_tx_hash = Some(random_bytes32());
+        }
+        trades.create(&trade).await.unwrap();
+    }
+
+    let volume = db.get_buyer_volume(&buyer).await.unwrap();
+    assert_eq!((volume.volume_24h_cny, volume.volume_30d_cny), (10_000, 30_000));
+
+    // Tier 0 is the default; tier 0 again drops the assignment
+    assert!(db.get_buyer_tier(&buyer).await.unwrap().is_none());
+    db.set_buyer_tier(&buyer, 2, "kyc", Some("case-1"), Some("provider")).await.unwrap();
+    let tier = db.get_buyer_tier(&buyer).await.unwrap().unwrap();
+    assert_eq!((tier.tier, tier.source.as_str(), tier.reference.as_deref()), (2, "kyc", Some("case-1")));
+    db.set_buyer_tier(&buyer, 0, "admin", None, Some("ops")).await.unwrap();
+    assert!(db.get_buyer_tier(&buyer).await.unwrap().is_none());
+}
+
+// ============================================================================
+// Address Screening Tests (migrations/031_address_screening.sql)
+// ============================================================================
+
+struct FlagScreener {
+    flagged: zkalipay_orderbook::blockchain::address::EthAddress,
+    calls: std::sync::atomic::AtomicUsize,
+}
+
+#[async_trait::async_trait]
+impl zkalipay_orderbook::screening::AddressScreener for FlagScreener {
+    fn name(&self) -> &str {
+        "test"
+    }
+
+    async fn screen(
+        &self,
+        address: &zkalipay_orderbook::blockchain::address::EthAddress,
+        _role: &str,
+    ) -> Result<zkalipay_orderbook::screening::ScreeningVerdict, zkalipay_orderbook::screening::ScreeningError> {
+        self.calls.fetch_add(1, std::sync::atomic::Ordering::SeqCst);
+        Ok(zkalipay_orderbook::screening::ScreeningVerdict {
+            flagged: *address == self.flagged,
+            reason: Some("sanctions list match".to_string()),
+        })
+    }
+}
+
+#[tokio::test]
+async fn test_flagged_seller_is_refused_and_unlisted() {
+    use zkalipay_orderbook::db::screening::{CONTEXT_ORDER_SYNC, ROLE_SELLER};
+    use zkalipay_orderbook::screening::Screening;
+
+    let pool = setup_migrated_pool().await;
+    let db = Database::new(&test_database_url()).await.unwrap();
+    let orders = PostgresOrderRepository::new(pool.clone());
+    let token: zkalipay_orderbook::blockchain::address::EthAddress = ethers::types::Address::random().into();
+
+    let mut flagged = test_order();
+    flagged.token = token.clone();
+    flagged.seller = ethers::types::Address::random().into();
+    let mut clean = test_order();
+    clean.token = token.clone();
+    clean.seller = ethers::types::Address::random().into();
+    orders.create(&flagged).await.unwrap();
+    orders.create(&clean).await.unwrap();
+
+    let screener = std::sync::Arc::new(FlagScreener { flagged: flagged.seller.clone(), calls: Default::default() });
+    let screening = Screening::new(screener.clone(), 3600);
+
+    let refusal = screening
+        .check(&pool, &flagged.seller, ROLE_SELLER, CONTEXT_ORDER_SYNC, Some(&flagged.order_id))
+        .await
+        .unwrap()
+        .unwrap_err();
+    assert_eq!(refusal.reason.as_deref(), Some("sanctions list match"));
+    assert_eq!(refusal.reference.as_deref(), Some(flagged.order_id.as_str()));
+    assert!(screening.check(&pool, &clean.seller, ROLE_SELLER, CONTEXT_ORDER_SYNC, None).await.unwrap().is_ok());
+
+    // Verdicts are cached: a second screening doesn't ask the provider
+    assert!(screening.check(&pool, &flagged.seller, ROLE_SELLER, CONTEXT_ORDER_SYNC, None).await.unwrap().is_err());
+    assert_eq!(screener.calls.load(std::sync::atomic::Ordering::SeqCst), 2);
+    assert!(db.get_screening_refusals(1000).await.unwrap().iter().any(|r| r.id == refusal.id));
+
+    let active = db.get_active_orders_by_token(&token, Some(10), Default::default()).await.unwrap();
+    assert_eq!(active.iter().map(|o| o.order_id.clone()).collect::<Vec<_>>(), vec![clean.order_id.clone()]);
+
+    // Cleared: listed again until the next screening
+    assert!(db.clear_screening_verdict(&flagged.seller).await.unwrap());
+    assert_eq!(db.get_active_orders_by_token(&token, Some(10), Default::default()).await.unwrap().len(), 2);
+}
+
+// ============================================================================
+// Event Journal Tests (migrations/032_event_journal.sql)
+// ============================================================================
+
+#[tokio::test]
+async fn test_journal_publishes_lifecycle_events() {
+    use std::sync::Arc;
+    use zkalipay_orderbook::db::event_journal::{EventJournalRepository, PostgresEventJournalRepository};
+    use zkalipay_orderbook::journal::{JournalPublisher, MemorySink};
+
+    let pool = setup_migrated_pool().await;
+    let order = test_order();
+    PostgresOrderRepository::new(pool.clone()).create(&order).await.unwrap();
+    let trades = PostgresTradeRepository::new(pool.clone());
+    let trade = test_trade(&order.order_id);
+    trades.create(&trade).await.unwrap();
+    trades.update_status(&trade.trade_id, 1).await.unwrap();
+
+    let sink = Arc::new(MemorySink::new());
+    let mut received = sink.subscribe();
+    let publisher = JournalPublisher::new(pool.clone(), sink.clone(), 24);
+
+    // Drain the whole backlog (other tests journal events too), batch by batch
+    let mut ours = Vec::new();
+    while publisher.publish_pending().await.unwrap() > 0 {
+        while let Ok(event) = received.try_recv() {
+            if event.aggregate_id == order.order_id || event.aggregate_id == trade.trade_id {
+                ours.push(event);
+            }
+        }
+    }
+
+    let types: Vec<&str> = ours.iter().map(|e| e.event_type.as_str()).collect();
+    assert_eq!(types, vec!["order_created", "trade_created", "trade_settled"]);
+    assert!(ours.windows(2).all(|w| w[0].id < w[1].id));
+    assert_eq!(ours[0].payload["seller"], order.seller.as_str());
+    assert_eq!(ours[2].payload["cny_amount"], trade.cny_amount.as_str());
+    assert_eq!(ours[2].payload["status"], 1);
+
+    // Published events aren't handed out again
+    let repo = PostgresEventJournalRepository::new(pool.clone());
+    let pending = repo.unpublished(10_000).await.unwrap();
+    assert!(pending.iter().all(|e| e.aggregate_id != order.order_id && e.aggregate_id != trade.trade_id));
+}
+
+// ============================================================================
+// Order Book Notify Tests (migrations/033_orderbook_notify.sql)
+// ============================================================================
+
+#[tokio::test]
+async fn test_order_changes_notify_orderbook_cache() {
+    use zkalipay_orderbook::cache::orderbook::ORDERBOOK_CHANNEL;
+
+    let pool = setup_migrated_pool().await;
+    let mut listener = sqlx::postgres::PgListener::connect_with(&pool).await.unwrap();
+    listener.listen(ORDERBOOK_CHANNEL).await.unwrap();
+
+    let mut order = test_order();
+    order.token = ethers::types::Address::random().into();
+    PostgresOrderRepository::new(pool.clone()).create(&order).await.unwrap();
+
+    // Other tests write orders too; wait for ours
+    let deadline = tokio::time::Instant::now() + std::time::Duration::from_secs(5);
+    loop {
+        let notification = tokio::time::timeout_at(deadline, listener.recv()).await.unwrap().unwrap();
+        if notification.payload() == order.token.as_str() {
+            break;
+        }
+    }
+}
+
+// ============================================================================
+// Relayer Spend Batch Tests (migrations/035_relayer_spend_batches.sql)
+// ============================================================================
+
+#[tokio::test]
+async fn test_batched_spend_is_shared_between_trades() {
+    use zkalipay_orderbook::db::relayer_spend::ACTION_SUBMIT_PROOF;
+
+    let pool = setup_migrated_pool().await;
+    let order = test_order();
+    PostgresOrderRepository::new(pool.clone()).create(&order).await.unwrap();
+    let (first, second) = (test_trade(&order.order_id), test_trade(&order.order_id));
+
+    let repo = PostgresRelayerSpendRepository::new(pool);
+    let since = Utc::now() - chrono::Duration::minutes(1);
+
+    // One aggregate3 transaction settling both trades, each with half the gas
+    let share = test_spend(ACTION_SUBMIT_PROOF, &first.trade_id, Some(&order.order_id), 300_000, false);
+    let other = NewRelayerSpend { trade_id: Some(second.trade_id.clone()), ..share.clone() };
+    repo.create(&share).await.unwrap();
+    repo.create(&other).await.unwrap();
+    repo.create(&share).await.unwrap(); // same tx and trade - ignored
+
+    let totals = repo.totals_by_trade(since, 500).await.unwrap();
+    for trade_id in [&first.trade_id, &second.trade_id] {
+        let ours = totals.iter().find(|t| &t.trade_id == trade_id).unwrap();
+        assert_eq!((ours.tx_count, ours.gas_used.as_str()), (1, "300000"));
+    }
+}
+
+// ============================================================================
+// Prover Transcript Tests (migrations/036_prover_transcripts.sql)
+// ============================================================================
+
+#[tokio::test]
+async fn test_prover_transcripts_listed_newest_first() {
+    use zkalipay_orderbook::db::prover_transcripts::{
+        NewProverTranscript, PostgresProverTranscriptRepository, ProverTranscriptRepository, TRANSCRIPT_EXECUTION,
+        TRANSCRIPT_FAILED, TRANSCRIPT_PROOF, TRANSCRIPT_SUCCEEDED,
+    };
+
+    let pool = setup_migrated_pool().await;
+    let repo = PostgresProverTranscriptRepository::new(pool);
+    let trade_id = random_bytes32();
+
+    let execution = NewProverTranscript {
+        trade_id: trade_id.clone(),
+        kind: TRANSCRIPT_EXECUTION,
+        program_id: "prg_test".to_string(),
+        axiom_id: Some("exec_1".to_string()),
+        status: TRANSCRIPT_SUCCEEDED,
+        error: None,
+        entries: r#"[{"method":"POST","path":"/v1/executions","status":200}]"#.to_string(),
+        started_at: Utc::now() - chrono::Duration::minutes(5),
+    };
+    repo.create(&execution).await.unwrap();
+    let proof = NewProverTranscript {
+        kind: TRANSCRIPT_PROOF,
+        axiom_id: None,
+        status: TRANSCRIPT_FAILED,
+        error: Some("Failed to submit proof request (401 Unauthorized)".to_string()),
+        entries: r#"[{"method":"POST","path":"/v1/proofs","status":401}]"#.to_string(),
+        started_at: Utc::now(),
+        ..execution.clone()
+    };
+    repo.create(&proof).await.unwrap();
+
+    let transcripts = repo.list_by_trade(&trade_id, 10).await.unwrap();
+    assert_eq!(transcripts.len(), 2);
+    assert_eq!((transcripts[0].kind.as_str(), transcripts[0].status.as_str()), (TRANSCRIPT_PROOF, TRANSCRIPT_FAILED));
+    assert_eq!(transcripts[1].axiom_id.as_deref(), Some("exec_1"));
+    let entries: serde_json::Value = serde_json::from_str(&transcripts[1].entries).unwrap();
+    assert_eq!(entries[0]["path"], "/v1/executions");
+}
+
+// ============================================================================
+// Seller Notice Tests (migrations/009_trade_reminders.sql)
+// ============================================================================
+
+#[tokio::test]
+async fn test_seller_notice_is_delivered_once() {
+    use zkalipay_orderbook::db::reminders::{PostgresReminderRepository, ReminderRepository, REMINDER_SELLER_TRADE};
+
+    let pool = setup_migrated_pool().await;
+    let order = test_order();
+    PostgresOrderRepository::new(pool.clone()).create(&order).await.unwrap();
+    let trade = DbTrade { expires_at: Utc::now().timestamp() + 900, ..test_trade(&order.order_id) };
+    PostgresTradeRepository::new(pool.clone()).create(&trade).await.unwrap();
+
+    let repo = PostgresReminderRepository::new(pool);
+    let notice = repo.get_seller_notice(&trade.trade_id, REMINDER_SELLER_TRADE).await.unwrap().unwrap();
+    assert_eq!((notice.seller.clone(), notice.alipay_id.as_str()), (order.seller.clone(), "13945908941"));
+    assert_eq!((notice.cny_amount.as_str(), notice.payment_nonce.as_str()), ("735", trade.payment_nonce.as_str()));
+
+    // Retried only once the listener had its chance
+    let now = Utc::now().timestamp();
+    let early = Utc::now() - chrono::Duration::minutes(2);
+    let due = repo.list_seller_notices_due(REMINDER_SELLER_TRADE, now, early, 1000).await.unwrap();
+    assert!(due.iter().all(|n| n.trade_id != trade.trade_id));
+    let later = Utc::now() + chrono::Duration::seconds(1);
+    let due = repo.list_seller_notices_due(REMINDER_SELLER_TRADE, now, later, 1000).await.unwrap();
+    assert!(due.iter().any(|n| n.trade_id == trade.trade_id));
+
+    repo.mark_sent(&trade.trade_id, REMINDER_SELLER_TRADE).await.unwrap();
+    assert!(repo.get_seller_notice(&trade.trade_id, REMINDER_SELLER_TRADE).await.unwrap().is_none());
+    let due = repo.list_seller_notices_due(REMINDER_SELLER_TRADE, now, later, 1000).await.unwrap();
+    assert!(due.iter().all(|n| n.trade_id != trade.trade_id));
+}
+
+// ============================================================================
+// Notification Channel Tests (migrations/037_notification_channels.sql)
+// ============================================================================
+
+#[tokio::test]
+async fn test_notification_channels_keep_newest_signature() {
+    use zkalipay_orderbook::db::notification_channels::{
+        NewNotificationChannel, NotificationChannelRepository, PostgresNotificationChannelRepository, CHANNEL_EMAIL,
+        CHANNEL_TELEGRAM,
+    };
+
+    let pool = setup_migrated_pool().await;
+    let repo = PostgresNotificationChannelRepository::new(pool);
+    let address: zkalipay_orderbook::blockchain::address::EthAddress =
+        format!("0x{}", hex::encode(rand::random::<[u8; 20]>())).parse().unwrap();
+
+    let email = NewNotificationChannel {
+        address: address.clone(),
+        channel: CHANNEL_EMAIL,
+        destination: "seller@example.com".to_string(),
+        events: Some(vec!["seller_trade_created".to_string()]),
+        signed_at: 1_700_000_100,
+    };
+    assert!(repo.upsert(&email).await.unwrap());
+    let telegram = NewNotificationChannel { channel: CHANNEL_TELEGRAM, destination: "12345".to_string(), events: None, ..email.clone() };
+    assert!(repo.upsert(&telegram).await.unwrap());
+
+    // An older signature doesn't overwrite or remove a newer one
+    let stale = NewNotificationChannel { destination: "old@example.com".to_string(), signed_at: 1_700_000_000, ..email.clone() };
+    assert!(!repo.upsert(&stale).await.unwrap());
+    assert!(!repo.delete(&address, CHANNEL_EMAIL, 1_700_000_050).await.unwrap());
+
+    let channels = repo.list_by_address(&address).await.unwrap();
+    assert_eq!(channels.len(), 2);
+    assert_eq!((channels[0].channel.as_str(), channels[0].destination.as_str()), (CHANNEL_EMAIL, "seller@example.com"));
+    assert_eq!(channels[1].events, None);
+
+    assert!(repo.delete(&address, CHANNEL_EMAIL, 1_700_000_200).await.unwrap());
+    assert_eq!(repo.list_by_address(&address).await.unwrap().len(), 1);
+}
+
+// ============================================================================
+// Prover Usage Tests (migrations/039_prover_usage.sql)
+// ============================================================================
+
+#[tokio::test]
+async fn test_prover_usage_summary_counts_credits_since() {
+    use zkalipay_orderbook::db::prover_transcripts::{TRANSCRIPT_EXECUTION, TRANSCRIPT_PROOF};
+    use zkalipay_orderbook::db::prover_usage::{NewProverUsage, PostgresProverUsageRepository, ProverUsageRepository};
+
+    let pool = setup_migrated_pool().await;
+    let since: chrono::DateTime<Utc> = sqlx::query_scalar("SELECT NOW()").fetch_one(&pool).await.unwrap();
+    let repo = PostgresProverUsageRepository::new(pool);
+    let trade_id = random_bytes32();
+
+    let execution = NewProverUsage {
+        trade_id: trade_id.clone(),
+        kind: TRANSCRIPT_EXECUTION,
+        axiom_id: "exec_1".to_string(),
+        credits: 1,
+        reported_remaining: Some(500),
+    };
+    repo.record(&execution).await.unwrap();
+    let proof = NewProverUsage { kind: TRANSCRIPT_PROOF, axiom_id: "proof_1".to_string(), credits: 10, reported_remaining: None, ..execution.clone() };
+    repo.record(&proof).await.unwrap();
+
+    let summary = repo.summary(since).await.unwrap();
+    assert_eq!((summary.executions, summary.proofs, summary.credits_used), (1, 1, 11));
+    // The latest reported balance is kept even when later responses didn't carry one
+    assert_eq!(summary.reported_remaining, Some(500));
+    assert!(summary.reported_at.is_some());
+
+    let empty = repo.summary(Utc::now() + chrono::Duration::hours(1)).await.unwrap();
+    assert_eq!((empty.executions, empty.credits_used, empty.reported_remaining), (0, 0, None));
+}
+
+// ============================================================================
+// Trade Payment Tests (migrations/040_trade_payments.sql)
+// ============================================================================
+
+#[tokio::test]
+async fn test_trade_payments_listed_oldest_first() {
+    use zkalipay_orderbook::db::trade_payments::{
+        NewTradePayment, PostgresTradePaymentRepository, TradePaymentRepository, AMOUNT_EXACT, AMOUNT_SHORT,
+    };
+
+    let pool = setup_migrated_pool().await;
+    let order = test_order();
+    PostgresOrderRepository::new(pool.clone()).create(&order).await.unwrap();
+    let trade = test_trade(&order.order_id);
+    PostgresTradeRepository::new(pool.clone()).create(&trade).await.unwrap();
+    let other = test_trade(&order.order_id);
+    PostgresTradeRepository::new(pool.clone()).create(&other).await.unwrap();
+    let payments = PostgresTradePaymentRepository::new(pool);
+
+    let short = NewTradePayment {
+        trade_id: trade.trade_id.clone(),
+        buyer: trade.buyer.clone(),
+        amount: 500,
+        paid_at: Utc::now() - chrono::Duration::minutes(3),
+        amount_status: AMOUNT_SHORT,
+        matches_trade_id: Some(other.trade_id.clone()),
+    };
+    payments.create(&short).await.unwrap();
+    let exact = NewTradePayment { amount: 735, amount_status: AMOUNT_EXACT, matches_trade_id: None, ..short.clone() };
+    payments.create(&exact).await.unwrap();
+
+    let listed = payments.list(&trade.trade_id).await.unwrap();
+    assert_eq!(listed.len(), 2);
+    assert_eq!((listed[0].amount, listed[0].amount_status.as_str()), (500, AMOUNT_SHORT));
+    assert_eq!(listed[0].matches_trade_id.as_deref(), Some(other.trade_id.as_str()));
+    assert_eq!(listed[1].buyer, trade.buyer);
+    assert!(payments.list(&other.trade_id).await.unwrap().is_empty());
+
+    // Declarations need a synced trade
+    let unknown = NewTradePayment { trade_id: random_bytes32(), ..exact };
+    assert!(matches!(payments.create(&unknown).await, Err(DbError::TradeNotFound(_))));
+}
+
+// ============================================================================
+// Trade Attestation Tests (migrations/041_trade_attestations.sql)
+// ============================================================================
+
+#[tokio::test]
+async fn test_trade_attestation_claimed_once_final() {
+    use zkalipay_orderbook::db::trade_attestations::{
+        PostgresTradeAttestationRepository, TradeAttestationRepository, ATTESTATION_ATTESTED, ATTESTATION_FAILED,
+        ATTESTATION_PENDING,
+    };
+
+    let pool = setup_migrated_pool().await;
+    let order = test_order();
+    PostgresOrderRepository::new(pool.clone()).create(&order).await.unwrap();
+    let trade = test_trade(&order.order_id);
+    let trades = PostgresTradeRepository::new(pool.clone());
+    trades.create(&trade).await.unwrap();
+    trades.update_status(&trade.trade_id, 1).await.unwrap();
+    let attestations = PostgresTradeAttestationRepository::new(pool.clone());
+
+    // Claims everything open; returns whether `trade_id` was among it
+    let claim_all = |trade_id: String| {
+        let attestations = PostgresTradeAttestationRepository::new(pool.clone());
+        async move {
+            let mut claimed = false;
+            while let Some(claim) = attestations.claim_next(600).await.unwrap() {
+                if claim.trade_id == trade_id {
+                    claimed = true;
+                } else {
+                    attestations.fail(&claim.trade_id, "claimed by another test", None, 1).await.unwrap();
+                }
+            }
+            claimed
+        }
+    };
+
+    attestations.enqueue(&trade.trade_id).await.unwrap();
+    attestations.enqueue(&trade.trade_id).await.unwrap();
+    let queued = attestations.get(&trade.trade_id).await.unwrap().unwrap();
+    assert_eq!((queued.status.as_str(), queued.attempts), (ATTESTATION_PENDING, 0));
+
+    // Not claimable until the settlement is final
+    assert!(!claim_all(trade.trade_id.clone()).await);
+    PostgresConfirmationRepository::new(pool.clone()).finalize(&trade.trade_id, 100, 12).await.unwrap();
+    assert!(claim_all(trade.trade_id.clone()).await);
+
+    // A failed attempt goes back to pending until the attempts run out
+    attestations.fail(&trade.trade_id, "gas estimation failed", None, 2).await.unwrap();
+    assert_eq!(attestations.get(&trade.trade_id).await.unwrap().unwrap().status, ATTESTATION_PENDING);
+    assert!(claim_all(trade.trade_id.clone()).await);
+    let tx_hash = random_bytes32();
+    attestations.fail(&trade.trade_id, "reverted", Some(&tx_hash), 2).await.unwrap();
+    let failed = attestations.get(&trade.trade_id).await.unwrap().unwrap();
+    assert_eq!((failed.status.as_str(), failed.attempts), (ATTESTATION_FAILED, 2));
+    assert_eq!(failed.tx_hash.as_deref(), Some(tx_hash.as_str()));
+    assert!(!claim_all(trade.trade_id.clone()).await);
+
+    let other = test_trade(&order.order_id);
+    trades.create(&other).await.unwrap();
+    attestations.enqueue(&other.trade_id).await.unwrap();
+    PostgresConfirmationRepository::new(pool.clone()).finalize(&other.trade_id, 100, 12).await.unwrap();
+    assert!(claim_all(other.trade_id.clone()).await);
+    let uid = random_bytes32();
+    attestations.complete(&other.trade_id, &uid, &tx_hash).await.unwrap();
+    let attested = attestations.get(&other.trade_id).await.unwrap().unwrap();
+    assert_eq!((attested.status.as_str(), attested.uid.as_deref()), (ATTESTATION_ATTESTED, Some(uid.as_str())));
+    assert!(attested.attested_at.is_some());
+
+    // Only synced trades can be queued
+    assert!(matches!(attestations.enqueue(&random_bytes32()).await, Err(DbError::TradeNotFound(_))));
+}
+
+// ============================================================================
+// Order Staleness Tests (migrations/042_order_staleness.sql)
+// ============================================================================
+
+#[tokio::test]
+async fn test_order_staleness_tracks_activity_and_nudges() {
+    use zkalipay_orderbook::db::order_staleness::{OrderStalenessRepository, PostgresOrderStalenessRepository};
+
+    let pool = setup_migrated_pool().await;
+    let order = test_order();
+    PostgresOrderRepository::new(pool.clone()).create(&order).await.unwrap();
+    let staleness = PostgresOrderStalenessRepository::new(pool.clone());
+    let is_stale = |stale_before, notified_before| {
+        let staleness = PostgresOrderStalenessRepository::new(pool.clone());
+        let (order_id, token) = (order.order_id.clone(), order.token.clone());
+        async move {
+            staleness
+                .list_stale(Some(&token), stale_before, notified_before, None)
+                .await
+                .unwrap()
+                .iter()
+                .any(|stale| stale.order_id == order_id)
+        }
+    };
+
+    // Syncing the order counts as activity (logged in order_feed)
+    let now = Utc::now();
+    assert!(!is_stale(now - chrono::Duration::days(1), None).await);
+    assert!(is_stale(now + chrono::Duration::minutes(1), None).await);
+
+    // A nudged order isn't listed again until the notify interval passes
+    staleness.mark_notified(&order.order_id).await.unwrap();
+    assert!(!is_stale(now + chrono::Duration::minutes(1), Some(now - chrono::Duration::days(1))).await);
+    assert!(is_stale(now + chrono::Duration::minutes(1), Some(now + chrono::Duration::minutes(1))).await);
+
+    // Refreshes only apply with a newer signature
+    assert!(staleness.refresh(&order.order_id, 100).await.unwrap());
+    assert!(!staleness.refresh(&order.order_id, 100).await.unwrap());
+    assert!(staleness.refresh(&order.order_id, 101).await.unwrap());
+    assert!(matches!(staleness.refresh(&random_bytes32(), 100).await, Err(DbError::OrderNotFound(_))));
+
+    let settings = staleness.set_settings(30, 3, Some("ops")).await.unwrap();
+    assert_eq!((settings.stale_after_days, settings.notify_every_days), (30, 3));
+    assert_eq!(staleness.settings().await.unwrap().updated_by.as_deref(), Some("ops"));
+    staleness.set_settings(14, 7, None).await.unwrap();
+}
+
+// ============================================================================
+// Event Replay Golden Tests (blockchain/fixtures.rs)
+// ============================================================================
+
+use zkalipay_orderbook::blockchain::events::{EventListener, Handled};
+use zkalipay_orderbook::blockchain::fixtures;
+
+#[tokio::test]
+async fn test_event_fixtures_replay_to_golden_rows() {
+    let pool = setup_migrated_pool().await;
+
+    // The fixtures have fixed IDs: clear what a previous run left
+    sqlx::query(r#"DELETE FROM orders WHERE "orderId" = $1"#).bind(fixtures::ORDER_ID).execute(&pool).await.unwrap();
+    sqlx::query(r#"DELETE FROM trades WHERE "paymentNonce" = ANY($1)"#)
+        .bind(vec![fixtures::TRADE_NONCE, fixtures::EXPIRED_TRADE_NONCE])
+        .execute(&pool)
+        .await
+        .unwrap();
+    let config_txs: Vec<&str> = fixtures::CONFIG_CHANGES.iter().map(|fixture| fixture.tx_hash).collect();
+    sqlx::query(r#"DELETE FROM contract_config_events WHERE "txHash" = ANY($1)"#)
+        .bind(&config_txs)
+        .execute(&pool)
+        .await
+        .unwrap();
+    let fixture_txs: Vec<&str> = fixtures::LIFECYCLE.iter().chain(&fixtures::CONFIG_CHANGES).map(|fixture| fixture.tx_hash).collect();
+    sqlx::query(r#"DELETE FROM processed_events WHERE "txHash" = ANY($1)"#)
+        .bind(&fixture_txs)
+        .execute(&pool)
+        .await
+        .unwrap();
+
+    // Never reaches the RPC: the start block is given and block times are known
+    let mut listener = EventListener::new("http://127.0.0.1:1", fixtures::CONTRACT.parse().unwrap(), pool.clone(), Some(100))
+        .await
+        .unwrap();
+    for (block, timestamp) in fixtures::BLOCK_TIMES {
+        listener = listener.with_block_time(block, timestamp);
+    }
+    for fixture in fixtures::LIFECYCLE.iter().chain(&fixtures::CONFIG_CHANGES) {
+        let handled = listener
+            .handle_logs(fixture.event_type, vec![fixture.log()])
+            .await
+            .unwrap_or_else(|e| panic!("{} failed: {}", fixture.signature, e));
+        assert_eq!(handled, Handled::Applied, "{}", fixture.signature);
+    }
+
+    // A resync sees every log again: the mirror is rewritten, amounts aren't adjusted twice
+    for fixture in fixtures::LIFECYCLE.iter().chain(&fixtures::CONFIG_CHANGES) {
+        let handled = listener.handle_logs(fixture.event_type, vec![fixture.log()]).await.unwrap();
+        assert_eq!(handled, Handled::Replayed, "{}", fixture.signature);
+    }
+
+    let order = PostgresOrderRepository::new(pool.clone()).get(fixtures::ORDER_ID).await.unwrap();
+    assert_eq!(order.seller.as_str(), fixtures::SELLER);
+    assert_eq!(order.token.as_str(), fixtures::TOKEN);
+    assert_eq!((order.total_amount.as_str(), order.remaining_amount.as_str()), ("1000000000", "700000000"));
+    assert_eq!(order.exchange_rate, "735");
+    assert_eq!((order.alipay_id.as_str(), order.alipay_name.as_str()), (fixtures::ALIPAY_ID, fixtures::ALIPAY_NAME));
+    assert_eq!(order.created_at, 1_718_000_000);
+
+    let trades = PostgresTradeRepository::new(pool.clone());
+    let settled = trades.get(fixtures::TRADE_ID).await.unwrap();
+    assert_eq!(settled.order_id, fixtures::ORDER_ID);
+    assert_eq!(settled.buyer.as_str(), fixtures::BUYER);
+    assert_eq!((settled.token_amount.as_str(), settled.cny_amount.as_str()), ("100000000", "73500"));
+    assert_eq!(settled.payment_nonce, fixtures::TRADE_NONCE);
+    assert_eq!((settled.created_at, settled.expires_at), (1_718_000_012, 1_718_000_912));
+    assert_eq!(settled.status, 1);
+    assert_eq!(settled.escrow_tx_hash.as_deref(), Some(fixtures::TRADE_CREATED.tx_hash));
+    assert_eq!(settled.settlement_tx_hash.as_deref(), Some(fixtures::TRADE_SETTLED.tx_hash));
+    assert_eq!((settled.settlement_block, settled.confirmations), (Some(103), 1));
+
+    let expired = trades.get(fixtures::EXPIRED_TRADE_ID).await.unwrap();
+    assert_eq!((expired.status, expired.token_amount.as_str()), (2, "50000000"));
+    assert_eq!(expired.created_at, 1_718_000_048);
+
+    let config_events: Vec<(String, String, i64)> = sqlx::query_as(
+        r#"SELECT "eventName", "details", "blockNumber" FROM contract_config_events WHERE "txHash" = ANY($1) ORDER BY "blockNumber""#,
+    )
+    .bind(&config_txs)
+    .fetch_all(&pool)
+    .await
+    .unwrap();
+    let names: Vec<&str> = config_events.iter().map(|(name, _, _)| name.as_str()).collect();
+    assert_eq!(names, ["ConfigUpdated", "ZkVerifierUpdated", "ZkPDFConfigUpdated", "Paused", "Unpaused"]);
+    let details: serde_json::Value = serde_json::from_str(&config_events[0].1).unwrap();
+    assert_eq!(details, serde_json::json!({ "max_trade_value_cny": "7200000", "payment_window": "900" }));
+    let details: serde_json::Value = serde_json::from_str(&config_events[1].1).unwrap();
+    assert_eq!(details["new_verifier"], fixtures::NEW_VERIFIER);
+    assert_eq!(config_events[4].2, 186);
+}
+
+// ============================================================================
+// Processed Event Tests (processed_events.rs)
+// ============================================================================
+
+use zkalipay_orderbook::db::processed_events::{EventKey, PostgresProcessedEventRepository, ProcessedEventRepository};
+
+#[tokio::test]
+async fn test_processed_event_applies_order_changes_once() {
+    let pool = setup_migrated_pool().await;
+    let orders = PostgresOrderRepository::new(pool.clone());
+    let processed = PostgresProcessedEventRepository::new(pool.clone());
+    let order = test_order();
+    orders.create(&order).await.unwrap();
+
+    let key = |log_index| EventKey {
+        event_type: "TradeCreated".to_string(),
+        block_number: 1,
+        tx_hash: random_bytes32(),
+        log_index,
+    };
+    let fill = [OrderChange::Adjust { order_id: order.order_id.clone(), delta: "-400".to_string() }];
+    let trade_created = key(0);
+    assert!(processed.claim_applying(&trade_created, &fill).await.unwrap());
+    assert!(!processed.claim_applying(&trade_created, &fill).await.unwrap());
+    assert_eq!(orders.get(&order.order_id).await.unwrap().remaining_amount, "999600");
+
+    // A change that fails leaves the log unclaimed, so a retry still applies it
+    let unknown = [OrderChange::Adjust { order_id: random_bytes32(), delta: "-1".to_string() }];
+    let failing = key(3);
+    assert!(matches!(processed.claim_applying(&failing, &unknown).await, Err(DbError::OrderNotFound(_))));
+    assert!(processed.claim(&failing).await.unwrap());
+    assert!(!processed.claim(&failing).await.unwrap());
+
+    // Pruning only forgets claims older than the cutoff
+    processed.prune(Utc::now() - chrono::Duration::days(1)).await.unwrap();
+    assert!(!processed.claim(&failing).await.unwrap());
+}
+
+// ============================================================================
+// Orderbook Summary Tests (platform_status.rs)
+// ============================================================================
+
+use zkalipay_orderbook::db::platform_status::{PlatformStatusRepository, PostgresPlatformStatusRepository};
+
+#[tokio::test]
+async fn test_orderbook_summary_counts_book_and_settled_volume() {
+    let pool = setup_migrated_pool().await;
+    let orders = PostgresOrderRepository::new(pool.clone());
+    let trades = PostgresTradeRepository::new(pool.clone());
+    let confirmations = PostgresConfirmationRepository::new(pool.clone());
+    let token: zkalipay_orderbook::blockchain::address::EthAddress = ethers::types::Address::random().into();
+
+    let mut order_ids = Vec::new();
+    for (rate, remaining) in [("700", "1000000"), ("800", "3000000"), ("650", "0")] {
+        let mut order = test_order();
+        order.token = token.clone();
+        order.exchange_rate = rate.to_string();
+        order.remaining_amount = remaining.to_string();
+        orders.create(&order).await.unwrap();
+        order_ids.push(order.order_id);
+    }
+
+    // Only final settlements count (the exhausted order's trade too); pending ones don't
+    for (order_id, settle) in [(&order_ids[0], true), (&order_ids[2], true), (&order_ids[1], false)] {
+        let trade = test_trade(order_id);
+        trades.create(&trade).await.unwrap();
+        if settle {
+            trades.update_status(&trade.trade_id, 1).await.unwrap();
+            confirmations.finalize(&trade.trade_id, 100, 12).await.unwrap();
+        }
+    }
+
+    let repo = PostgresPlatformStatusRepository::new(pool.clone());
+    let summary = repo
+        .summary(&TokenPolicy::default(), Utc::now() - chrono::Duration::hours(24))
+        .await
+        .unwrap();
+    let entry = summary.iter().find(|s| s.token == token).unwrap();
+    assert_eq!((entry.active_orders, entry.total_liquidity.as_str()), (2, "4000000"));
+    assert_eq!(entry.best_rate.as_deref(), Some("700"));
+    assert_eq!(entry.settled_trades_24h, 2);
+    assert_eq!((entry.settled_volume_24h.as_str(), entry.settled_volume_24h_cny.as_str()), ("2000", "1470"));
+
+    // Settlements before the window are left out
+    let summary = repo.summary(&TokenPolicy::default(), Utc::now() + chrono::Duration::minutes(1)).await.unwrap();
+    let entry = summary.iter().find(|s| s.token == token).unwrap();
+    assert_eq!((entry.settled_trades_24h, entry.settled_volume_24h.as_str()), (0, "0"));
+}
+
+// ============================================================================
+// Match Plan Tests (match_plans.rs)
+// ============================================================================
+
+use zkalipay_orderbook::db::match_plans::{MatchPlanRepository, NewMatchPlan, PostgresMatchPlanRepository};
+
+#[tokio::test]
+async fn test_match_plans_record_trades_and_expired_quotes_are_pruned() {
+    let pool = setup_migrated_pool().await;
+    let plans = PostgresMatchPlanRepository::new(pool.clone());
+    let buyer: zkalipay_orderbook::blockchain::address::EthAddress = ethers::types::Address::random().into();
+    let plan = |expires_at| NewMatchPlan {
+        token: ethers::types::Address::random().into(),
+        buyer: None,
+        request: r#"{"desired_amount":"100"}"#.to_string(),
+        plan: r#"{"fills":[]}"#.to_string(),
+        book_hash: format!("0x{}", "ab".repeat(32)),
+        order_priority: "price_time".to_string(),
+        expires_at,
+    };
+
+    let expired = plans.create(&plan(Utc::now() - chrono::Duration::hours(1))).await.unwrap();
+    let executed = plans.create(&plan(Utc::now() - chrono::Duration::hours(1))).await.unwrap();
+    let live = plans.create(&plan(Utc::now() + chrono::Duration::minutes(5))).await.unwrap();
+
+    // A plan executed in two attempts keeps the trades of both
+    plans.record_execution(executed, &buyer, &[random_bytes32()]).await.unwrap();
+    plans.record_execution(executed, &buyer, &[random_bytes32()]).await.unwrap();
+    let stored = plans.get(executed).await.unwrap().unwrap();
+    assert_eq!(stored.trade_ids.len(), 2);
+    assert_eq!(stored.executed_by, Some(buyer));
+    assert_eq!(stored.book_hash, format!("0x{}", "ab".repeat(32)));
+
+    // Only quotes that expired unexecuted go
+    assert!(plans.prune_expired(Utc::now()).await.unwrap() >= 1);
+    assert!(plans.get(expired).await.unwrap().is_none());
+    assert!(plans.get(executed).await.unwrap().is_some());
+    assert!(plans.get(live).await.unwrap().is_some());
+}
+
+// ============================================================================
+// Inspection Page Tests (inspection.rs - built with QueryBuilder, so unchecked at compile time)
+// ============================================================================
+
+use std::cmp::Ordering;
+use zkalipay_orderbook::db::inspection::{
+    InspectionRepository, OrderPage, OrderSort, OrderState, PageCursor, PostgresInspectionRepository, TradePage, TradeSort,
+};
+
+/// Assert (sort value, ID) keys strictly increase (decrease when descending)
+fn assert_keyset_order(keys: &[(String, String)], descending: bool) {
+    let expected = if descending { Ordering::Less } else { Ordering::Greater };
+    for pair in keys.windows(2) {
+        // Sort values are non-negative integers
+        let (previous, next) = (&pair[0], &pair[1]);
+        let by_value = next.0.len().cmp(&previous.0.len()).then_with(|| next.0.cmp(&previous.0));
+        assert_eq!(by_value.then_with(|| next.1.cmp(&previous.1)), expected, "{:?} after {:?}", next, previous);
+    }
+}
+
+#[tokio::test]
+async fn test_inspection_pages_every_sort_and_filter() {
+    let pool = setup_migrated_pool().await;
+    let orders = PostgresOrderRepository::new(pool.clone());
+    let trades = PostgresTradeRepository::new(pool.clone());
+    let inspection = PostgresInspectionRepository::new(pool.clone());
+
+    // Rows of every status, sharing sort values so IDs break the ties
+    let order = test_order();
+    let mut filled = test_order();
+    filled.remaining_amount = "0".to_string();
+    for order in [&order, &test_order(), &filled] {
+        orders.create(order).await.unwrap();
+    }
+    let proven = test_trade(&order.order_id);
+    trades.create(&proven).await.unwrap();
+    for status in [0, 1, 1, 2, 2] {
+        let trade = test_trade(&order.order_id);
+        trades.create(&trade).await.unwrap();
+        if status != 0 {
+            trades.update_status(&trade.trade_id, status).await.unwrap();
+        }
+    }
+    let uploaded_at = trades.save_pdf(&proven.trade_id, b"%PDF-1", "receipt.pdf").await.unwrap();
+    assert!(trades
+        .save_proof(&proven.trade_id, Some(uploaded_at), &[1u8; 32], &[2u8; 384], &[3u8; 1376], "proof-1", r#"{"proof":"0x01"}"#, "halo2-v1")
+        .await
+        .unwrap());
+
+    // Every combination runs, filters, and continues past its cursor in keyset order
+    for sort in [OrderSort::CreatedAt, OrderSort::RemainingAmount] {
+        for status in [None, Some(OrderState::Active), Some(OrderState::Filled)] {
+            for descending in [false, true] {
+                let mut page = OrderPage { status, sort, descending, after: None, limit: 3 };
+                let mut rows = inspection.page_orders(&page).await.unwrap();
+                let last = rows.last().expect("page of seeded orders is empty").clone();
+                page.after = Some(PageCursor::new(&sort.value(&last), &last.order_id));
+                rows.extend(inspection.page_orders(&page).await.unwrap());
+
+                for row in &rows {
+                    match status {
+                        Some(OrderState::Active) => assert_ne!(row.remaining_amount, "0"),
+                        Some(OrderState::Filled) => assert_eq!(row.remaining_amount, "0"),
+                        None => {}
+                    }
+                }
+                let keys: Vec<_> = rows.iter().map(|o| (sort.value(o), o.order_id.clone())).collect();
+                assert_keyset_order(&keys, descending);
+            }
+        }
+    }
+
+    for sort in [TradeSort::CreatedAt, TradeSort::ExpiresAt, TradeSort::CnyAmount] {
+        for status in [None, Some(0), Some(1), Some(2)] {
+            for descending in [false, true] {
+                for with_proof_json in [false, true] {
+                    let mut page = TradePage { status, sort, descending, after: None, limit: 3, with_proof_json };
+                    let mut rows = inspection.page_trades(&page).await.unwrap();
+                    let last = rows.last().expect("page of seeded trades is empty").clone();
+                    page.after = Some(PageCursor::new(&sort.value(&last), &last.trade_id));
+                    rows.extend(inspection.page_trades(&page).await.unwrap());
+
+                    for row in &rows {
+                        assert!(status.is_none() || status == Some(row.status));
+                        assert!(row.pdf_file.is_none() && row.proof_data.is_none());
+                        assert!(with_proof_json || row.proof_json.is_none());
+                    }
+                    let keys: Vec<_> = rows.iter().map(|t| (sort.value(t), t.trade_id.clone())).collect();
+                    assert_keyset_order(&keys, descending);
+                }
+            }
+        }
+    }
+
+    // Paging through pending trades reaches the proven one, with its proof JSON and token
+    let mut page = TradePage { status: Some(0), limit: 50, with_proof_json: true, ..Default::default() };
+    let found = loop {
+        let rows = inspection.page_trades(&page).await.unwrap();
+        if let Some(trade) = rows.iter().find(|t| t.trade_id == proven.trade_id) {
+            break trade.clone();
+        }
+        let last = rows.last().expect("proven trade not paged");
+        page.after = Some(PageCursor::new(&page.sort.value(last), &last.trade_id));
+    };
+    assert_eq!(found.proof_json.as_deref(), Some(r#"{"proof":"0x01"}"#));
+    assert_eq!(found.token, Some(order.token));
+    assert_eq!(found.pdf_filename.as_deref(), Some("receipt.pdf"));
+}